@@ -207,6 +207,28 @@ impl<E: FieldElement> Assertion<E> {
         }
     }
 
+    /// Returns the value which this assertion requires to be at the specified `step` of its
+    /// column in an execution trace of the specified length, or None if this assertion does not
+    /// cover the specified step.
+    pub fn get_value_at(&self, step: usize, trace_length: usize) -> Option<E> {
+        if step < self.first_step || step >= trace_length {
+            return None;
+        }
+        if self.is_single() {
+            return (step == self.first_step).then_some(self.values[0]);
+        }
+
+        let offset = step - self.first_step;
+        if offset % self.stride != 0 {
+            return None;
+        }
+        if self.is_periodic() {
+            Some(self.values[0])
+        } else {
+            self.values.get(offset / self.stride).copied()
+        }
+    }
+
     /// Panics if the assertion cannot be placed against an execution trace of the specified width.
     pub fn validate_trace_width(&self, trace_width: usize) -> Result<(), AssertionError> {
         if self.column >= trace_width {
@@ -176,6 +176,32 @@ fn sequence_assertion_num_values_not_power_of_two() {
         Assertion::sequence(3, 2, 4, vec![BaseElement::ONE, BaseElement::ZERO, BaseElement::ONE]);
 }
 
+// ASSERTED VALUES
+// ================================================================================================
+
+#[test]
+fn assertion_get_value_at() {
+    let value = rand_value::<BaseElement>();
+    let a = Assertion::single(3, 2, value);
+    assert_eq!(Some(value), a.get_value_at(2, 16));
+    assert_eq!(None, a.get_value_at(3, 16));
+    assert_eq!(None, a.get_value_at(2, 2));
+
+    let a = Assertion::periodic(3, 1, 4, value);
+    assert_eq!(Some(value), a.get_value_at(1, 16));
+    assert_eq!(Some(value), a.get_value_at(13, 16));
+    assert_eq!(None, a.get_value_at(0, 16));
+    assert_eq!(None, a.get_value_at(3, 16));
+    assert_eq!(None, a.get_value_at(17, 16));
+
+    let values = rand_vector::<BaseElement>(4);
+    let a = Assertion::sequence(3, 1, 4, values.clone());
+    assert_eq!(Some(values[0]), a.get_value_at(1, 16));
+    assert_eq!(Some(values[3]), a.get_value_at(13, 16));
+    assert_eq!(None, a.get_value_at(2, 16));
+    assert_eq!(None, a.get_value_at(17, 32));
+}
+
 // OVERLAPPING ASSERTIONS
 // ================================================================================================
 
@@ -3,12 +3,15 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use alloc::vec::Vec;
-use core::cmp;
+use alloc::{string::String, vec::Vec};
+use core::{cmp, ops::Range};
 
-use math::StarkField;
+use math::{ExtensionOf, FieldElement, StarkField};
 
-use crate::{air::TransitionConstraintDegree, ProofOptions, TraceInfo};
+use crate::{
+    air::{SelectorGroup, TransitionConstraintDegree},
+    MainTraceTablesError, ProofOptions, TraceInfo,
+};
 
 // AIR CONTEXT
 // ================================================================================================
@@ -26,6 +29,10 @@ pub struct AirContext<B: StarkField> {
     pub(super) trace_domain_generator: B,
     pub(super) lde_domain_generator: B,
     pub(super) num_transition_exemptions: usize,
+    pub(super) num_preprocessed_columns: usize,
+    pub(super) main_trace_table_widths: Vec<usize>,
+    pub(super) main_trace_table_names: Vec<String>,
+    pub(super) selector_groups: Vec<SelectorGroup>,
 }
 
 impl<B: StarkField> AirContext<B> {
@@ -157,6 +164,7 @@ impl<B: StarkField> AirContext<B> {
 
         let trace_length = trace_info.length();
         let lde_domain_size = trace_length * options.blowup_factor();
+        let main_trace_width = trace_info.main_trace_width();
 
         AirContext {
             options,
@@ -170,6 +178,10 @@ impl<B: StarkField> AirContext<B> {
             trace_domain_generator: B::get_root_of_unity(trace_length.ilog2()),
             lde_domain_generator: B::get_root_of_unity(lde_domain_size.ilog2()),
             num_transition_exemptions: 1,
+            num_preprocessed_columns: 0,
+            main_trace_table_widths: vec![main_trace_width],
+            main_trace_table_names: Vec::new(),
+            selector_groups: Vec::new(),
         }
     }
 
@@ -181,6 +193,11 @@ impl<B: StarkField> AirContext<B> {
         &self.trace_info
     }
 
+    /// Returns the proof options which were used to instantiate this context.
+    pub fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
     /// Returns length of the execution trace for an instance of a computation.
     ///
     /// This is guaranteed to be a power of two greater than or equal to 8.
@@ -209,6 +226,18 @@ impl<B: StarkField> AirContext<B> {
         self.trace_info.length() * self.options.blowup_factor()
     }
 
+    /// Returns the maximum number of positions in the LDE domain which can be excluded from
+    /// querying (see [Air::is_query_position_excluded()](crate::Air::is_query_position_excluded)).
+    ///
+    /// When `k` out of `n` positions are excluded, queries are drawn from the remaining `n - k`
+    /// positions, and thus, each query is more likely to miss an invalid evaluation by a factor
+    /// of at most `n / (n - k)`. Limiting `k` to `n / (2 * num_queries)` ensures that, across all
+    /// queries, exclusions reduce the security of a proof by at most one bit compared to the
+    /// security level estimates, which do not take exclusions into account.
+    pub fn max_excluded_query_positions(&self) -> usize {
+        self.lde_domain_size() / (2 * self.options.num_queries())
+    }
+
     /// Returns the number of transition constraints for a computation, excluding the Lagrange
     /// kernel transition constraints, which are managed separately.
     ///
@@ -259,6 +288,109 @@ impl<B: StarkField> AirContext<B> {
         self.num_transition_exemptions
     }
 
+    /// Returns the number of preprocessed columns in the main segment of an execution trace.
+    ///
+    /// Preprocessed columns are the first columns of the main trace segment. Their values are the
+    /// same for all executions of a computation (for a given trace length), and thus, their
+    /// low-degree extension and the commitment to it can be computed once and then reused for
+    /// every proof. The commitment to the preprocessed columns is known to the verifier in
+    /// advance, and therefore, it is not included in a proof.
+    ///
+    /// This is 0 by default.
+    pub fn num_preprocessed_columns(&self) -> usize {
+        self.num_preprocessed_columns
+    }
+
+    /// Returns the widths of the tables into which the main segment of an execution trace is
+    /// partitioned.
+    ///
+    /// Tables are consecutive groups of columns of the main trace segment. Each table is committed
+    /// to separately, and all commitments are sent to the verifier before any randomness for the
+    /// auxiliary trace segment is drawn; the public coin is reseeded once with the commitments
+    /// merged together (see
+    /// [Commitments::merge_main_trace_table_roots()](crate::proof::Commitments::merge_main_trace_table_roots)).
+    /// Thus, several related traces of the same length can be proven in a single proof: the
+    /// auxiliary segment (e.g., a bus) can enforce consistency between the tables using randomness
+    /// shared by all of them, while the DEEP composition and FRI phases are performed once for all
+    /// tables.
+    ///
+    /// All tables have the same number of rows, which is the length of the execution trace;
+    /// tables of different lengths are not supported.
+    ///
+    /// By default, the main trace segment consists of a single table.
+    pub fn main_trace_table_widths(&self) -> &[usize] {
+        &self.main_trace_table_widths
+    }
+
+    /// Returns the number of tables into which the main segment of an execution trace is
+    /// partitioned (see [main_trace_table_widths()](Self::main_trace_table_widths) for details).
+    pub fn num_main_trace_tables(&self) -> usize {
+        self.main_trace_table_widths.len()
+    }
+
+    /// Returns the range of main trace segment columns which form the table at the specified
+    /// index.
+    ///
+    /// # Panics
+    /// Panics if `table_idx` is not smaller than the number of main trace tables.
+    pub fn main_trace_table_columns(&self, table_idx: usize) -> Range<usize> {
+        assert!(
+            table_idx < self.num_main_trace_tables(),
+            "table index must be smaller than {}, but was {table_idx}",
+            self.num_main_trace_tables()
+        );
+        let start = self.main_trace_table_widths[..table_idx].iter().sum::<usize>();
+        start..start + self.main_trace_table_widths[table_idx]
+    }
+
+    /// Returns the index of the main trace table with the specified name, or None if there is no
+    /// such table.
+    ///
+    /// Tables have names only if the main trace segment was partitioned via
+    /// [set_main_trace_column_groups()](Self::set_main_trace_column_groups).
+    pub fn main_trace_table_index(&self, name: &str) -> Option<usize> {
+        self.main_trace_table_names.iter().position(|table_name| table_name == name)
+    }
+
+    /// Returns the groups of transition constraints which are gated by selector columns.
+    ///
+    /// See [add_selector_group()](Self::add_selector_group) for details.
+    pub fn selector_groups(&self) -> &[SelectorGroup] {
+        &self.selector_groups
+    }
+
+    /// Returns the index of the selector group with the specified name, or None if there is no
+    /// such group.
+    pub fn selector_group_index(&self, name: &str) -> Option<usize> {
+        self.selector_groups.iter().position(|group| group.name() == name)
+    }
+
+    /// Multiplies evaluations of main transition constraints gated by selector columns by the
+    /// values of these columns in the `current` row of the main trace segment.
+    ///
+    /// This is invoked by the prover and the verifier after main transition constraints are
+    /// evaluated via [Air::evaluate_transition()](crate::Air::evaluate_transition).
+    pub fn apply_main_selectors<E: FieldElement>(&self, current: &[E], evaluations: &mut [E]) {
+        for group in self.selector_groups.iter() {
+            group.apply_main(current, evaluations);
+        }
+    }
+
+    /// Multiplies evaluations of auxiliary transition constraints gated by selector columns by
+    /// the values of these columns in the `current` row of the main trace segment.
+    ///
+    /// This is invoked by the prover and the verifier after auxiliary transition constraints are
+    /// evaluated via [Air::evaluate_aux_transition()](crate::Air::evaluate_aux_transition).
+    pub fn apply_aux_selectors<F, E>(&self, current: &[F], evaluations: &mut [E])
+    where
+        F: FieldElement,
+        E: FieldElement + ExtensionOf<F>,
+    {
+        for group in self.selector_groups.iter() {
+            group.apply_aux(current, evaluations);
+        }
+    }
+
     /// Returns the number of columns needed to store the constraint composition polynomial.
     ///
     /// This is the maximum of:
@@ -360,4 +492,158 @@ impl<B: StarkField> AirContext<B> {
         self.num_transition_exemptions = n;
         self
     }
+
+    /// Sets the number of preprocessed columns for this context.
+    ///
+    /// The preprocessed columns are the first `n` columns of the main trace segment (see
+    /// [num_preprocessed_columns()](Self::num_preprocessed_columns) for details).
+    ///
+    /// # Panics
+    /// Panics if `n` is not smaller than the width of the main trace segment (i.e., the main
+    /// trace segment must contain at least one column which is not preprocessed).
+    pub fn set_num_preprocessed_columns(mut self, n: usize) -> Self {
+        assert_eq!(
+            self.num_main_trace_tables(),
+            1,
+            "preprocessed columns cannot be combined with multiple main trace tables"
+        );
+        assert!(
+            n < self.trace_info.main_trace_width(),
+            "number of preprocessed columns must be smaller than {}, but was {}",
+            self.trace_info.main_trace_width(),
+            n
+        );
+
+        self.num_preprocessed_columns = n;
+        self
+    }
+
+    /// Partitions the main trace segment into tables with the specified widths.
+    ///
+    /// The first `table_widths[0]` columns of the main trace segment form the first table, the
+    /// next `table_widths[1]` columns form the second table, and so on (see
+    /// [main_trace_table_widths()](Self::main_trace_table_widths) for details). Since tables
+    /// are groups of columns of the same execution trace, all tables have the same length.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * `table_widths` is empty or contains more than 255 tables.
+    /// * Any of the tables has zero width.
+    /// * The sum of table widths is not equal to the width of the main trace segment.
+    /// * This context has preprocessed columns.
+    pub fn set_main_trace_tables(
+        mut self,
+        table_widths: Vec<usize>,
+    ) -> Result<Self, MainTraceTablesError> {
+        if table_widths.is_empty() {
+            return Err(MainTraceTablesError::NoTables);
+        }
+        if table_widths.len() > u8::MAX as usize {
+            return Err(MainTraceTablesError::TooManyTables(table_widths.len()));
+        }
+        if let Some(index) = table_widths.iter().position(|&width| width == 0) {
+            return Err(MainTraceTablesError::EmptyTable(index));
+        }
+        let main_trace_width = self.trace_info.main_trace_width();
+        let total_width = table_widths.iter().sum::<usize>();
+        if total_width != main_trace_width {
+            return Err(MainTraceTablesError::WidthMismatch(main_trace_width, total_width));
+        }
+        if self.num_preprocessed_columns != 0 {
+            return Err(MainTraceTablesError::PreprocessedColumns);
+        }
+
+        self.main_trace_table_widths = table_widths;
+        self.main_trace_table_names = Vec::new();
+        Ok(self)
+    }
+
+    /// Partitions the main trace segment into named groups of columns, each of which is committed
+    /// to as a separate main trace table.
+    ///
+    /// Groups are specified as (name, width) pairs in the order of their columns; for example,
+    /// `[("cpu", 12), ("memory", 4)]` places the first 12 columns into the "cpu" group and the
+    /// remaining 4 columns into the "memory" group. Since each group has its own commitment, the
+    /// prover can later open a single group (see `TraceLde::query_main_trace_table()` in the
+    /// prover crate) without revealing the values of other columns.
+    ///
+    /// # Errors
+    /// Returns an error if any two groups have the same name, or for any of the reasons listed
+    /// for [set_main_trace_tables()](Self::set_main_trace_tables).
+    pub fn set_main_trace_column_groups(
+        self,
+        groups: &[(&str, usize)],
+    ) -> Result<Self, MainTraceTablesError> {
+        for (i, (name, _)) in groups.iter().enumerate() {
+            if groups[..i].iter().any(|(other, _)| other == name) {
+                return Err(MainTraceTablesError::DuplicateGroupName((*name).into()));
+            }
+        }
+
+        let mut context =
+            self.set_main_trace_tables(groups.iter().map(|&(_, width)| width).collect())?;
+        context.main_trace_table_names = groups.iter().map(|&(name, _)| name.into()).collect();
+        Ok(context)
+    }
+
+    /// Adds a group of transition constraints which are active only in rows in which the
+    /// selector column of the group is set to ONE.
+    ///
+    /// Evaluations of the constraints of the group are multiplied by the value of the selector
+    /// column in the current row by the prover and the verifier, and the degrees of these
+    /// constraints are increased by one; thus, an AIR should evaluate the constraints of the group
+    /// as if they applied to every row, and should specify their degrees without accounting for
+    /// the selector. A constraint may belong to several groups, in which case it is multiplied by
+    /// the selector column of each of these groups.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * A group with the same name has already been added to this context.
+    /// * The selector column is not a column of the main trace segment.
+    /// * The constraints of the group are out of bounds for the main or the auxiliary transition
+    ///   constraints of this context.
+    /// * The blowup factor of this context is too small for the increased constraint degrees.
+    pub fn add_selector_group(mut self, group: SelectorGroup) -> Self {
+        assert!(
+            self.selector_group_index(group.name()).is_none(),
+            "selector group name {} is not unique",
+            group.name()
+        );
+        let main_trace_width = self.trace_info.main_trace_width();
+        assert!(
+            group.selector_column() < main_trace_width,
+            "selector column must be smaller than {main_trace_width}, but was {}",
+            group.selector_column()
+        );
+        assert!(
+            group.main_constraints().end <= self.num_main_transition_constraints(),
+            "main constraints of selector group {} are out of bounds",
+            group.name()
+        );
+        assert!(
+            group.aux_constraints().end <= self.num_aux_transition_constraints(),
+            "auxiliary constraints of selector group {} are out of bounds",
+            group.name()
+        );
+
+        for idx in group.main_constraints() {
+            let degree = &mut self.main_transition_constraint_degrees[idx];
+            *degree = degree.with_selector();
+            self.ce_blowup_factor = cmp::max(self.ce_blowup_factor, degree.min_blowup_factor());
+        }
+        for idx in group.aux_constraints() {
+            let degree = &mut self.aux_transition_constraint_degrees[idx];
+            *degree = degree.with_selector();
+            self.ce_blowup_factor = cmp::max(self.ce_blowup_factor, degree.min_blowup_factor());
+        }
+        assert!(
+            self.options.blowup_factor() >= self.ce_blowup_factor,
+            "blowup factor too small; expected at least {}, but was {}",
+            self.ce_blowup_factor,
+            self.options.blowup_factor()
+        );
+
+        self.selector_groups.push(group);
+        self
+    }
 }
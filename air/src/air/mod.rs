@@ -22,11 +22,17 @@ pub use context::AirContext;
 mod assertions;
 pub use assertions::Assertion;
 
+mod outputs;
+pub use outputs::AirWithOutputs;
+
 mod boundary;
 pub use boundary::{BoundaryConstraint, BoundaryConstraintGroup, BoundaryConstraints};
 
 mod transition;
-pub use transition::{EvaluationFrame, TransitionConstraintDegree, TransitionConstraints};
+pub use transition::{
+    EvaluationFrame, EvaluationFrameBatch, SelectorGroup, TransitionConstraintDegree,
+    TransitionConstraints,
+};
 
 mod lagrange;
 pub use lagrange::{
@@ -42,6 +48,9 @@ pub use coefficients::{
 
 mod divisor;
 pub use divisor::ConstraintDivisor;
+
+mod periodic;
+pub use periodic::evaluate_periodic_columns_at;
 use utils::{Deserializable, Serializable};
 
 #[cfg(test)]
@@ -318,6 +327,46 @@ pub trait Air: Send + Sync {
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Evaluates transition constraints over a batch of consecutive evaluation frames.
+    ///
+    /// The values in `periodic_values` and `result` are laid out in the same way as the values in
+    /// the `frames` batch: the value of the j-th periodic column for the i-th frame is located at
+    /// position `j * frames.num_frames() + i`, and the evaluation of the j-th transition
+    /// constraint for the i-th frame should be written into position `j * frames.num_frames() + i`
+    /// of the `result`. Slots of the `result` are initialized to zeros.
+    ///
+    /// The default implementation evaluates constraints one frame at a time via
+    /// [Air::evaluate_transition()]. AIRs which can evaluate constraints over many frames more
+    /// efficiently (e.g., using SIMD instructions or a hardware accelerator) should override this
+    /// method. The prover uses this method to evaluate constraints against the main segment of the
+    /// execution trace over the constraint evaluation domain.
+    fn evaluate_transition_batch<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frames: &EvaluationFrameBatch<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let num_frames = frames.num_frames();
+        let num_periodic_values = periodic_values.len() / num_frames;
+        let num_constraints = result.len() / num_frames;
+
+        let mut frame = EvaluationFrame::new(frames.num_columns());
+        let mut frame_periodic_values = vec![E::ZERO; num_periodic_values];
+        let mut frame_result = vec![E::ZERO; num_constraints];
+        for i in 0..num_frames {
+            frames.read_frame_into(i, &mut frame);
+            for (j, value) in frame_periodic_values.iter_mut().enumerate() {
+                *value = periodic_values[j * num_frames + i];
+            }
+
+            frame_result.fill(E::ZERO);
+            self.evaluate_transition(&frame, &frame_periodic_values, &mut frame_result);
+            for (j, &value) in frame_result.iter().enumerate() {
+                result[j * num_frames + i] = value;
+            }
+        }
+    }
+
     /// Returns a vector of field elements required for construction of the auxiliary trace segment
     /// (except the Lagrange kernel column, if any).
     ///
@@ -401,6 +450,51 @@ pub trait Air: Send + Sync {
             .collect()
     }
 
+    /// Returns values of all periodic columns at the specified point `x`.
+    ///
+    /// This is used by the verifier to evaluate periodic columns at the out-of-domain point. The
+    /// columns are evaluated from the polynomials returned by
+    /// [get_periodic_column_polys()](Air::get_periodic_column_polys) as described in
+    /// [evaluate_periodic_columns_at()]; computations which can evaluate their periodic columns
+    /// more efficiently (e.g., in closed form) can override this method.
+    fn evaluate_periodic_columns<E>(&self, x: E) -> Vec<E>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        evaluate_periodic_columns_at(&self.get_periodic_column_polys(), self.trace_length(), x)
+    }
+
+    /// Returns true if the specified position in the LDE domain must not be queried.
+    ///
+    /// Both the prover and the verifier skip excluded positions when drawing query positions
+    /// from the public coin, and keep drawing until the number of queries specified by the proof
+    /// options is reached (see
+    /// [draw_query_positions_with_exclusions()](fri::positions::draw_query_positions_with_exclusions)).
+    /// This makes it possible to keep evaluations at some positions from ever being opened; e.g.,
+    /// evaluations which could reveal the randomized rows of the trace in zero-knowledge mode.
+    ///
+    /// Excluding positions reduces the size of the domain from which queries are drawn; thus,
+    /// computations which override this method must also override
+    /// [num_excluded_query_positions()](Air::num_excluded_query_positions).
+    ///
+    /// The default implementation does not exclude any positions.
+    fn is_query_position_excluded(&self, _position: usize) -> bool {
+        false
+    }
+
+    /// Returns an upper bound on the number of positions in the LDE domain excluded by
+    /// [is_query_position_excluded()](Air::is_query_position_excluded).
+    ///
+    /// Both the prover and the verifier reject computations for which this number is greater than
+    /// [AirContext::max_excluded_query_positions()]; this keeps the security level estimates
+    /// valid to within one bit. In addition, in debug builds, the prover checks that this number
+    /// is not smaller than the number of positions actually excluded.
+    ///
+    /// The default implementation returns 0.
+    fn num_excluded_query_positions(&self) -> usize {
+        0
+    }
+
     /// Groups transition constraints together by their degree.
     ///
     /// This function also assigns composition coefficients to each constraint. These coefficients
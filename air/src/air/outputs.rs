@@ -0,0 +1,35 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use super::Air;
+
+// AIR WITH PUBLIC OUTPUTS
+// ================================================================================================
+
+/// Describes public outputs of a computation described by an [Air].
+///
+/// Public outputs are values in specific cells of the main trace segment which are bound by
+/// assertions returned from [Air::get_assertions()]. Since a valid proof attests to all assertions
+/// being satisfied, the values of these cells are known to the verifier once the proof is
+/// verified; thus, the verifier can return them to the caller in a typed form, so that the caller
+/// does not need to extract the result of the computation from the public inputs separately.
+pub trait AirWithOutputs: Air {
+    /// A type defining shape of public outputs of the computation.
+    type PublicOutputs;
+
+    /// Returns the cells of the main trace segment which contain public outputs as
+    /// (column, step) tuples.
+    ///
+    /// Every returned cell must be covered by one of the assertions returned from
+    /// [Air::get_assertions()].
+    fn public_output_cells(&self) -> Vec<(usize, usize)>;
+
+    /// Builds public outputs from the values of the cells returned by
+    /// [AirWithOutputs::public_output_cells()]; the values are provided in the same order as the
+    /// cells.
+    fn build_public_outputs(&self, values: Vec<Self::BaseField>) -> Self::PublicOutputs;
+}
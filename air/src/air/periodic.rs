@@ -0,0 +1,70 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use math::{polynom, FieldElement};
+
+// PERIODIC COLUMN EVALUATION
+// ================================================================================================
+
+/// Evaluates periodic columns described by the specified polynomials at point `x`.
+///
+/// Each polynomial is expected to be interpolated from the values of a periodic column over its
+/// cycle (see [Air::get_periodic_column_polys()](crate::Air::get_periodic_column_polys)), and
+/// `trace_length` is the length of the execution trace. The value of a column with cycle length
+/// $k$ at $x$ is $p(x^{n / k})$, where $p$ is the polynomial of the column and $n$ is the trace
+/// length.
+///
+/// Since both $n$ and $k$ are powers of two, $x^{n / k}$ is computed by squaring $x$
+/// $\log_2(n / k)$ times. The powers are shared by all columns: the columns are processed from the
+/// longest cycle to the shortest one, and each power is derived from the previous one by
+/// additional squarings. Thus, at most $\log_2(n / k_{min})$ squarings are performed in total,
+/// followed by a Horner evaluation of each polynomial.
+///
+/// # Panics
+/// Panics if `trace_length` or the length of any polynomial is not a power of two, or if any
+/// polynomial is longer than `trace_length`.
+pub fn evaluate_periodic_columns_at<B, E>(
+    column_polys: &[Vec<B>],
+    trace_length: usize,
+    x: E,
+) -> Vec<E>
+where
+    B: FieldElement,
+    E: FieldElement + From<B>,
+{
+    assert!(trace_length.is_power_of_two(), "trace length must be a power of two");
+
+    // compute x^(n / k) for each distinct cycle length k, starting from the longest cycle
+    let mut cycle_powers = BTreeMap::new();
+    for poly in column_polys.iter() {
+        let cycle_length = poly.len();
+        assert!(
+            cycle_length.is_power_of_two(),
+            "number of values in a periodic column must be a power of two, but was {cycle_length}"
+        );
+        assert!(
+            cycle_length <= trace_length,
+            "number of values in a periodic column cannot exceed trace length {trace_length}, but was {cycle_length}"
+        );
+        cycle_powers.insert(cycle_length, E::ZERO);
+    }
+
+    let mut power = x;
+    let mut power_cycle_length = trace_length;
+    for (&cycle_length, value) in cycle_powers.iter_mut().rev() {
+        while power_cycle_length > cycle_length {
+            power = power.square();
+            power_cycle_length >>= 1;
+        }
+        *value = power;
+    }
+
+    column_polys
+        .iter()
+        .map(|poly| polynom::eval(poly, cycle_powers[&poly.len()]))
+        .collect()
+}
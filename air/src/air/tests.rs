@@ -7,12 +7,13 @@ use alloc::{collections::BTreeMap, vec::Vec};
 
 use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
 use math::{fields::f64::BaseElement, get_power_series, polynom, FieldElement, StarkField};
+use rand_utils::rand_vector;
 
 use super::{
-    Air, AirContext, Assertion, EvaluationFrame, ProofOptions, TraceInfo,
+    Air, AirContext, Assertion, EvaluationFrame, EvaluationFrameBatch, ProofOptions, TraceInfo,
     TransitionConstraintDegree,
 };
-use crate::FieldExtension;
+use crate::{FieldExtension, MainTraceTablesError};
 
 // PERIODIC COLUMNS
 // ================================================================================================
@@ -52,6 +53,36 @@ fn get_periodic_column_polys_num_values_not_power_of_two() {
     assert_eq!(0, column_polys.len());
 }
 
+#[test]
+fn evaluate_periodic_columns() {
+    let col1 = vec![BaseElement::ONE, BaseElement::ZERO];
+    let col2 = vec![BaseElement::new(3), BaseElement::ZERO, BaseElement::ONE, BaseElement::new(7)];
+    let col3 = (0..16).map(BaseElement::new).collect::<Vec<_>>();
+    let air = MockAir::with_periodic_columns(vec![col1, col2.clone(), col3, col2], 16);
+    let column_polys = air.get_periodic_column_polys();
+
+    // values at points of the trace domain are the values of the columns at the respective steps
+    let g = air.trace_domain_generator();
+    for step in [0, 1, 5, 13] {
+        let x = g.exp(step as u64);
+        let expected = air
+            .get_periodic_column_values()
+            .iter()
+            .map(|column| column[step % column.len()])
+            .collect::<Vec<_>>();
+        assert_eq!(expected, air.evaluate_periodic_columns(x));
+    }
+
+    // values at any other point are evaluations of the column polynomials at x^(n / k)
+    let x = BaseElement::new(123456789);
+    let expected = column_polys
+        .iter()
+        .map(|poly| polynom::eval(poly, x.exp((16 / poly.len()) as u64)))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, air.evaluate_periodic_columns(x));
+    assert_eq!(expected, super::evaluate_periodic_columns_at(&column_polys, 16, x));
+}
+
 // TRANSITION CONSTRAINTS
 // ================================================================================================
 
@@ -188,6 +219,55 @@ fn get_boundary_constraints() {
     assert_eq!(expected_cc[&5], constraint.cc().clone());
 }
 
+// TRANSITION CONSTRAINTS
+// ================================================================================================
+
+#[test]
+fn evaluate_transition_batch() {
+    let air = MockAir::with_periodic_columns(vec![vec![BaseElement::ONE; 4]], 16);
+    let num_frames = 8;
+
+    let frames = (0..num_frames)
+        .map(|_| EvaluationFrame::from_rows(rand_vector(4), rand_vector(4)))
+        .collect::<Vec<_>>();
+    let mut batch = EvaluationFrameBatch::new(4, num_frames);
+    for (i, frame) in frames.iter().enumerate() {
+        batch.set_frame(i, frame);
+    }
+    let periodic_values: Vec<BaseElement> = rand_vector(num_frames);
+
+    let mut batch_result = vec![BaseElement::ZERO; num_frames];
+    air.evaluate_transition_batch(&batch, &periodic_values, &mut batch_result);
+
+    // batch evaluations must be the same as evaluations over individual frames
+    for (i, frame) in frames.iter().enumerate() {
+        let mut result = [BaseElement::ZERO];
+        air.evaluate_transition(frame, &[periodic_values[i]], &mut result);
+        assert_eq!(result[0], batch_result[i]);
+    }
+    assert!(batch_result.iter().any(|&value| value != BaseElement::ZERO));
+}
+
+// MAIN TRACE TABLES
+// ================================================================================================
+
+#[test]
+fn set_main_trace_tables() {
+    let context = build_context::<BaseElement>(16, 4, 1);
+    let tables = context.clone().set_main_trace_tables(vec![3, 1]).unwrap();
+    assert_eq!(&[3, 1], tables.main_trace_table_widths());
+    assert_eq!(3..4, tables.main_trace_table_columns(1));
+
+    let result = context.clone().set_main_trace_tables(vec![]);
+    assert_eq!(Some(MainTraceTablesError::NoTables), result.err());
+    let result = context.clone().set_main_trace_tables(vec![4, 0]);
+    assert_eq!(Some(MainTraceTablesError::EmptyTable(1)), result.err());
+    let result = context.clone().set_main_trace_tables(vec![2, 1]);
+    assert_eq!(Some(MainTraceTablesError::WidthMismatch(4, 3)), result.err());
+    let result = context.set_main_trace_column_groups(&[("cpu", 2), ("cpu", 2)]);
+    assert_eq!(Some(MainTraceTablesError::DuplicateGroupName("cpu".into())), result.err());
+}
+
 // MOCK AIR
 // ================================================================================================
 
@@ -252,10 +332,13 @@ impl Air for MockAir {
 
     fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
         &self,
-        _frame: &EvaluationFrame<E>,
-        _periodic_values: &[E],
-        _result: &mut [E],
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
     ) {
+        let current = frame.current();
+        let periodic_sum = periodic_values.iter().fold(E::ZERO, |acc, &value| acc + value);
+        result[0] = frame.next()[0] - current[0] * current[1] - periodic_sum;
     }
 }
 
@@ -299,6 +299,10 @@ impl Deserializable for TraceInfo {
             return Err(DeserializationError::InvalidValue(
                 "a non-empty trace segment must require at least one random element".to_string(),
             ));
+        } else if aux_segment_width == 0 && num_aux_segment_rands != 0 {
+            return Err(DeserializationError::InvalidValue(
+                "an empty trace segment cannot require any random elements".to_string(),
+            ));
         } else if num_aux_segment_rands > TraceInfo::MAX_RAND_SEGMENT_ELEMENTS {
             return Err(DeserializationError::InvalidValue(format!(
                 "number of random elements required by a segment cannot exceed {}, but was {}",
@@ -315,6 +319,12 @@ impl Deserializable for TraceInfo {
                 TraceInfo::MIN_TRACE_LENGTH.ilog2(),
                 trace_length
             )));
+        } else if trace_length as u32 >= usize::BITS {
+            return Err(DeserializationError::InvalidValue(format!(
+                "trace length cannot be greater than or equal to 2^{}, but was 2^{}",
+                usize::BITS,
+                trace_length
+            )));
         }
         let trace_length = 2_usize.pow(trace_length as u32);
 
@@ -342,6 +352,7 @@ impl Deserializable for TraceInfo {
 #[cfg(test)]
 mod tests {
     use math::{fields::f64::BaseElement, FieldElement};
+    use utils::{Deserializable, Serializable};
 
     use super::{ToElements, TraceInfo};
 
@@ -391,4 +402,20 @@ mod tests {
 
         assert_eq!(expected, info.to_elements());
     }
+
+    #[test]
+    fn trace_info_read_invalid() {
+        let info = TraceInfo::new(4, 64);
+        assert_eq!(info, TraceInfo::read_from_bytes(&info.to_bytes()).unwrap());
+
+        // random elements for an empty auxiliary segment
+        let mut bytes = info.to_bytes();
+        bytes[2] = 1;
+        assert!(TraceInfo::read_from_bytes(&bytes).is_err());
+
+        // trace length which does not fit into usize
+        let mut bytes = info.to_bytes();
+        bytes[3] = usize::BITS as u8;
+        assert!(TraceInfo::read_from_bytes(&bytes).is_err());
+    }
 }
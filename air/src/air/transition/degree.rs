@@ -67,6 +67,15 @@ impl TransitionConstraintDegree {
         TransitionConstraintDegree { base: base_degree, cycles }
     }
 
+    /// Returns the degree of this constraint after it is multiplied by a trace column (e.g., a
+    /// selector column).
+    pub(crate) fn with_selector(&self) -> Self {
+        TransitionConstraintDegree {
+            base: self.base + 1,
+            cycles: self.cycles.clone(),
+        }
+    }
+
     /// Computes a degree to which this degree description expands in the context of execution
     /// trace of the specified length.
     ///
@@ -79,3 +79,86 @@ impl<E: FieldElement> EvaluationFrame<E> {
         &mut self.next
     }
 }
+
+// EVALUATION FRAME BATCH
+// ================================================================================================
+
+/// A block of consecutive evaluation frames stored in a structure-of-arrays layout.
+///
+/// For each column of the execution trace, the batch stores the values of this column in the
+/// current rows of all frames in a single contiguous slice, and the values of this column in the
+/// next rows of all frames in another contiguous slice. This layout allows evaluating transition
+/// constraints over many frames at once (e.g., using SIMD instructions or a hardware accelerator)
+/// via [Air::evaluate_transition_batch()](crate::Air::evaluate_transition_batch).
+#[derive(Debug, Clone)]
+pub struct EvaluationFrameBatch<E: FieldElement> {
+    num_frames: usize,
+    current: Vec<E>,
+    next: Vec<E>,
+}
+
+impl<E: FieldElement> EvaluationFrameBatch<E> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new batch of `num_frames` evaluation frames instantiated with the specified
+    /// number of columns.
+    ///
+    /// # Panics
+    /// Panics if `num_columns` or `num_frames` is zero.
+    pub fn new(num_columns: usize, num_frames: usize) -> Self {
+        assert!(num_columns > 0, "number of columns must be greater than zero");
+        assert!(num_frames > 0, "number of frames must be greater than zero");
+        EvaluationFrameBatch {
+            num_frames,
+            current: vec![E::ZERO; num_columns * num_frames],
+            next: vec![E::ZERO; num_columns * num_frames],
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of frames in this batch.
+    pub fn num_frames(&self) -> usize {
+        self.num_frames
+    }
+
+    /// Returns the number of columns in each frame of this batch.
+    pub fn num_columns(&self) -> usize {
+        self.current.len() / self.num_frames
+    }
+
+    /// Returns values of the specified column in the current rows of all frames in this batch.
+    #[inline(always)]
+    pub fn current(&self, col_idx: usize) -> &[E] {
+        &self.current[col_idx * self.num_frames..(col_idx + 1) * self.num_frames]
+    }
+
+    /// Returns values of the specified column in the next rows of all frames in this batch.
+    #[inline(always)]
+    pub fn next(&self, col_idx: usize) -> &[E] {
+        &self.next[col_idx * self.num_frames..(col_idx + 1) * self.num_frames]
+    }
+
+    /// Copies the frame at the specified position in this batch into the provided `frame`.
+    pub fn read_frame_into(&self, frame_idx: usize, frame: &mut EvaluationFrame<E>) {
+        for col_idx in 0..self.num_columns() {
+            let position = col_idx * self.num_frames + frame_idx;
+            frame.current_mut()[col_idx] = self.current[position];
+            frame.next_mut()[col_idx] = self.next[position];
+        }
+    }
+
+    // DATA MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Copies the provided `frame` into the specified position in this batch.
+    pub fn set_frame(&mut self, frame_idx: usize, frame: &EvaluationFrame<E>) {
+        for (col_idx, (&current, &next)) in frame.current().iter().zip(frame.next()).enumerate() {
+            let position = col_idx * self.num_frames + frame_idx;
+            self.current[position] = current;
+            self.next[position] = next;
+        }
+    }
+}
@@ -8,11 +8,14 @@ use alloc::vec::Vec;
 use super::{AirContext, ConstraintDivisor, ExtensionOf, FieldElement};
 
 mod frame;
-pub use frame::EvaluationFrame;
+pub use frame::{EvaluationFrame, EvaluationFrameBatch};
 
 mod degree;
 pub use degree::TransitionConstraintDegree;
 
+mod selector;
+pub use selector::SelectorGroup;
+
 // CONSTANTS
 // ================================================================================================
 
@@ -0,0 +1,114 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::string::String;
+use core::ops::Range;
+
+use math::{ExtensionOf, FieldElement};
+
+// SELECTOR GROUP
+// ================================================================================================
+
+/// A group of transition constraints which are active only when a selector column is set to ONE.
+///
+/// A selector group is described by a name, an index of a column in the main trace segment
+/// (the selector column), and ranges of main and auxiliary transition constraints gated by this
+/// column. When a selector group is added to an [AirContext](crate::AirContext), the framework
+/// multiplies evaluations of the gated constraints by the value of the selector column in the
+/// current row of the evaluation frame, and increases the degrees of these constraints by one.
+/// Thus, constraints of a group should be written as if they applied to every row, and their
+/// degrees should be specified without accounting for the selector.
+///
+/// The selector column itself is not constrained to be binary; this should be enforced by the
+/// AIR, if needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorGroup {
+    name: String,
+    selector_column: usize,
+    main_constraints: Range<usize>,
+    aux_constraints: Range<usize>,
+}
+
+impl SelectorGroup {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new selector group with the specified name gated by the main trace column at
+    /// index `selector_column`.
+    ///
+    /// The group initially contains no constraints; constraints are added to it via
+    /// [with_main_constraints()](Self::with_main_constraints) and
+    /// [with_aux_constraints()](Self::with_aux_constraints).
+    pub fn new(name: &str, selector_column: usize) -> Self {
+        Self {
+            name: name.into(),
+            selector_column,
+            main_constraints: 0..0,
+            aux_constraints: 0..0,
+        }
+    }
+
+    /// Gates the main transition constraints with the specified indexes by the selector column of
+    /// this group.
+    pub fn with_main_constraints(mut self, constraints: Range<usize>) -> Self {
+        self.main_constraints = constraints;
+        self
+    }
+
+    /// Gates the auxiliary transition constraints with the specified indexes by the selector
+    /// column of this group.
+    pub fn with_aux_constraints(mut self, constraints: Range<usize>) -> Self {
+        self.aux_constraints = constraints;
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the name of this group.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the index of the selector column of this group in the main trace segment.
+    pub fn selector_column(&self) -> usize {
+        self.selector_column
+    }
+
+    /// Returns the indexes of the main transition constraints gated by this group.
+    pub fn main_constraints(&self) -> Range<usize> {
+        self.main_constraints.clone()
+    }
+
+    /// Returns the indexes of the auxiliary transition constraints gated by this group.
+    pub fn aux_constraints(&self) -> Range<usize> {
+        self.aux_constraints.clone()
+    }
+
+    // SELECTOR APPLICATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Multiplies evaluations of the main transition constraints of this group by the value of
+    /// the selector column in the `current` row of the main trace segment.
+    pub fn apply_main<E: FieldElement>(&self, current: &[E], evaluations: &mut [E]) {
+        let selector = current[self.selector_column];
+        for evaluation in evaluations[self.main_constraints()].iter_mut() {
+            *evaluation *= selector;
+        }
+    }
+
+    /// Multiplies evaluations of the auxiliary transition constraints of this group by the value
+    /// of the selector column in the `current` row of the main trace segment.
+    pub fn apply_aux<F, E>(&self, current: &[F], evaluations: &mut [E])
+    where
+        F: FieldElement,
+        E: FieldElement + ExtensionOf<F>,
+    {
+        let selector = current[self.selector_column];
+        for evaluation in evaluations[self.aux_constraints()].iter_mut() {
+            *evaluation = evaluation.mul_base(selector);
+        }
+    }
+}
@@ -3,6 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use alloc::string::String;
 use core::fmt;
 
 // ASSERTION ERROR
@@ -42,3 +43,49 @@ impl fmt::Display for AssertionError {
         }
     }
 }
+
+// MAIN TRACE TABLES ERROR
+// ================================================================================================
+/// Represents an error returned when the main trace segment cannot be partitioned into the
+/// specified tables.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MainTraceTablesError {
+    /// This error occurs when no tables are specified.
+    NoTables,
+    /// This error occurs when more than 255 tables are specified.
+    TooManyTables(usize),
+    /// This error occurs when the table at the specified index has zero width.
+    EmptyTable(usize),
+    /// This error occurs when the sum of table widths is not equal to the width of the main trace
+    /// segment.
+    WidthMismatch(usize, usize),
+    /// This error occurs when the main trace segment has preprocessed columns.
+    PreprocessedColumns,
+    /// This error occurs when two column groups have the same name.
+    DuplicateGroupName(String),
+}
+
+impl fmt::Display for MainTraceTablesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoTables => {
+                write!(f, "at least one main trace table must be specified")
+            },
+            Self::TooManyTables(actual) => {
+                write!(f, "expected at most {} main trace tables, but was {actual}", u8::MAX)
+            },
+            Self::EmptyTable(index) => {
+                write!(f, "main trace table {index} has zero width")
+            },
+            Self::WidthMismatch(expected, actual) => {
+                write!(f, "expected sum of table widths to be {expected}, but was {actual}")
+            },
+            Self::PreprocessedColumns => {
+                write!(f, "multiple main trace tables cannot be combined with preprocessed columns")
+            },
+            Self::DuplicateGroupName(name) => {
+                write!(f, "column group name {name} is not unique")
+            },
+        }
+    }
+}
@@ -35,18 +35,21 @@ extern crate alloc;
 pub mod proof;
 
 mod errors;
-pub use errors::AssertionError;
+pub use errors::{AssertionError, MainTraceTablesError};
 
 mod options;
 pub use options::{FieldExtension, ProofOptions};
 
+mod transcript;
+pub use transcript::{Transcript, TranscriptEntry, TranscriptEntryKind};
+
 mod air;
 pub use air::{
-    Air, AirContext, Assertion, AuxRandElements, BoundaryConstraint, BoundaryConstraintGroup,
-    BoundaryConstraints, ConstraintCompositionCoefficients, ConstraintDivisor,
-    DeepCompositionCoefficients, EvaluationFrame, GkrVerifier,
-    LagrangeConstraintsCompositionCoefficients, LagrangeKernelBoundaryConstraint,
-    LagrangeKernelConstraints, LagrangeKernelEvaluationFrame, LagrangeKernelRandElements,
-    LagrangeKernelTransitionConstraints, TraceInfo, TransitionConstraintDegree,
-    TransitionConstraints,
+    evaluate_periodic_columns_at, Air, AirContext, AirWithOutputs, Assertion, AuxRandElements,
+    BoundaryConstraint, BoundaryConstraintGroup, BoundaryConstraints,
+    ConstraintCompositionCoefficients, ConstraintDivisor, DeepCompositionCoefficients,
+    EvaluationFrame, EvaluationFrameBatch, GkrVerifier, LagrangeConstraintsCompositionCoefficients,
+    LagrangeKernelBoundaryConstraint, LagrangeKernelConstraints, LagrangeKernelEvaluationFrame,
+    LagrangeKernelRandElements, LagrangeKernelTransitionConstraints, SelectorGroup, TraceInfo,
+    TransitionConstraintDegree, TransitionConstraints,
 };
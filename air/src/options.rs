@@ -3,11 +3,14 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use alloc::vec::Vec;
+use alloc::{string::ToString, vec::Vec};
 
+use crypto::{hashers::Blake3_256, Digest, HashFunctionId, Hasher, RandomCoin};
 use fri::FriOptions;
 use math::{StarkField, ToElements};
-use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+use utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
 
 // CONSTANTS
 // ================================================================================================
@@ -23,8 +26,28 @@ const MAX_GRINDING_FACTOR: u32 = 32;
 
 const FRI_MIN_FOLDING_FACTOR: usize = 2;
 const FRI_MAX_FOLDING_FACTOR: usize = 16;
+const FRI_MAX_FIRST_LAYER_FOLDING_FACTOR: usize = 32;
 const FRI_MAX_REMAINDER_DEGREE: usize = 255;
 
+/// Flag set in the serialized field extension byte when hash function identifiers follow the
+/// rest of the serialized options.
+const HASH_FUNCTIONS_FLAG: u8 = 0x80;
+
+/// Flag set in the serialized field extension byte when a FRI folding schedule follows the rest of
+/// the serialized options.
+const FRI_FOLDING_SCHEDULE_FLAG: u8 = 0x40;
+
+/// Flag set in the serialized field extension byte when a digest truncation width follows the rest
+/// of the serialized options.
+const DIGEST_TRUNCATION_FLAG: u8 = 0x20;
+
+/// Flag set in the serialized field extension byte when a parameters digest follows the rest of the
+/// serialized options.
+const PARAMETERS_DIGEST_FLAG: u8 = 0x10;
+
+const MIN_DIGEST_TRUNCATION: usize = 16;
+const MAX_DIGEST_TRUNCATION: usize = 32;
+
 // TYPES AND INTERFACES
 // ================================================================================================
 
@@ -74,6 +97,27 @@ pub enum FieldExtension {
 /// is the hash function used in the protocol. The soundness of a STARK proof is limited by the
 /// collision resistance of the hash function used by the protocol. For example, if a hash function
 /// with 128-bit collision resistance is used, soundness of a STARK proof cannot exceed 128 bits.
+///
+/// Hash functions can optionally be recorded in [ProofOptions] via
+/// [ProofOptions::with_hash_functions()]. This is useful when different hash functions are used
+/// for commitments and for the public coin: the recorded hash functions are checked by both the
+/// prover and the verifier, and are included in the proof context.
+///
+/// Similarly, a per-layer FRI folding schedule can be recorded via
+/// [ProofOptions::with_fri_folding_schedule()]; in this case, the FRI layers are folded by the
+/// factors in the schedule rather than by the same `fri_folding_factor`, and the schedule is
+/// included in the proof context.
+///
+/// Finally, when commitments are computed with a hasher which truncates digests (e.g., via
+/// `Truncated` hasher from the `winter-crypto` crate), the truncation width can be recorded via
+/// [ProofOptions::with_digest_truncation()]; the verifier then rejects proofs for which the
+/// digests of its commitment hash function have a different size.
+///
+/// To detect mismatches between the protocol configurations of the prover and the verifier (e.g.,
+/// different round constants of a hash function, or different FRI folding schedules), a digest of
+/// the full configuration can be recorded via [ProofOptions::with_parameters_digest()]; the
+/// verifier then rejects proofs whose recorded digest differs from the digest of its own
+/// configuration.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ProofOptions {
     num_queries: u8,
@@ -82,6 +126,10 @@ pub struct ProofOptions {
     field_extension: FieldExtension,
     fri_folding_factor: u8,
     fri_remainder_max_degree: u8,
+    hash_functions: Option<(HashFunctionId, HashFunctionId)>,
+    fri_folding_schedule: Vec<u8>,
+    digest_truncation: Option<u8>,
+    parameters_digest: Option<[u8; 32]>,
 }
 
 // PROOF OPTIONS IMPLEMENTATION
@@ -107,7 +155,7 @@ impl ProofOptions {
     /// - `blowup_factor` is smaller than 2, greater than 128, or is not a power of two.
     /// - `grinding_factor` is greater than 32.
     /// - `fri_folding_factor` is not 2, 4, 8, or 16.
-    /// - `fri_remainder_max_degree` is greater than 255 or is not a power of two minus 1.
+    /// - `fri_remainder_max_degree` is greater than 255.
     #[rustfmt::skip]
     pub const fn new(
         num_queries: usize,
@@ -131,10 +179,6 @@ impl ProofOptions {
         assert!(fri_folding_factor >= FRI_MIN_FOLDING_FACTOR, "FRI folding factor cannot be smaller than 2");
         assert!(fri_folding_factor <= FRI_MAX_FOLDING_FACTOR, "FRI folding factor cannot be greater than 16");
 
-        assert!(
-            (fri_remainder_max_degree + 1).is_power_of_two(),
-            "FRI polynomial remainder degree must be one less than a power of two"
-        );
         assert!(
             fri_remainder_max_degree <= FRI_MAX_REMAINDER_DEGREE,
             "FRI polynomial remainder degree cannot be greater than 255"
@@ -147,7 +191,123 @@ impl ProofOptions {
             field_extension,
             fri_folding_factor: fri_folding_factor as u8,
             fri_remainder_max_degree: fri_remainder_max_degree as u8,
+            hash_functions: None,
+            fri_folding_schedule: Vec::new(),
+            digest_truncation: None,
+            parameters_digest: None,
+        }
+    }
+
+    /// Returns proof options with the hash functions used for commitments and for the public coin
+    /// set to the specified values.
+    ///
+    /// When hash functions are set, the prover and the verifier check that they match the hash
+    /// functions they are instantiated with, and the hash functions become a part of the proof
+    /// context.
+    pub const fn with_hash_functions(
+        mut self,
+        commitment_hash_fn: HashFunctionId,
+        random_coin_hash_fn: HashFunctionId,
+    ) -> ProofOptions {
+        self.hash_functions = Some((commitment_hash_fn, random_coin_hash_fn));
+        self
+    }
+
+    /// Returns proof options with the per-layer FRI folding schedule set to the specified folding
+    /// factors.
+    ///
+    /// The i-th FRI layer is folded by the i-th factor in the schedule; layers beyond the end of
+    /// the schedule are folded by `fri_folding_factor`. A schedule which minimizes proof size for
+    /// a given domain can be computed via `FriOptions::optimal_folding_schedule()`.
+    ///
+    /// The first FRI layer can be folded by a factor of up to 32, while all other layers can be
+    /// folded by a factor of up to 16.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - The first folding factor is not 2, 4, 8, 16, or 32.
+    /// - Any other folding factor is not 2, 4, 8, or 16.
+    /// - The schedule contains more than 255 folding factors.
+    pub fn with_fri_folding_schedule(mut self, fri_folding_schedule: &[usize]) -> ProofOptions {
+        assert!(
+            fri_folding_schedule.len() <= u8::MAX as usize,
+            "FRI folding schedule cannot contain more than 255 folding factors"
+        );
+        for (depth, &folding_factor) in fri_folding_schedule.iter().enumerate() {
+            assert!(
+                is_supported_fri_folding_factor_at(depth, folding_factor),
+                "FRI folding factor {folding_factor} is not supported at layer {depth}"
+            );
         }
+        self.fri_folding_schedule = fri_folding_schedule
+            .iter()
+            .map(|&folding_factor| folding_factor as u8)
+            .collect();
+        self
+    }
+
+    /// Returns proof options with the number of bytes in commitment digests set to the specified
+    /// value.
+    ///
+    /// This should be used together with a commitment hash function which truncates its digests
+    /// (e.g., to 20 or 24 bytes) in order to reduce the size of Merkle authentication paths in
+    /// the proof. When the truncation width is set, the prover and the verifier check that it
+    /// matches the size of the digests of their commitment hash function, and the width becomes
+    /// a part of the proof context.
+    ///
+    /// # Panics
+    /// Panics if `num_digest_bytes` is smaller than 16 or greater than 32.
+    pub const fn with_digest_truncation(mut self, num_digest_bytes: usize) -> ProofOptions {
+        assert!(
+            num_digest_bytes >= MIN_DIGEST_TRUNCATION,
+            "digest truncation width cannot be smaller than 16 bytes"
+        );
+        assert!(
+            num_digest_bytes <= MAX_DIGEST_TRUNCATION,
+            "digest truncation width cannot be greater than 32 bytes"
+        );
+        self.digest_truncation = Some(num_digest_bytes as u8);
+        self
+    }
+
+    /// Returns proof options with the digest of the protocol configuration defined by these
+    /// options, the base field `B`, the commitment hash function `H`, and the public coin `R`
+    /// recorded in them (see [parameters_digest()](Self::parameters_digest)).
+    ///
+    /// When the digest is recorded, the prover and the verifier check that it matches the digest
+    /// of the configuration they are instantiated with, and the digest becomes a part of the
+    /// proof context. Options set after the digest is recorded are not reflected in the digest;
+    /// thus, this should be called after all other options have been set.
+    pub fn with_parameters_digest<B: StarkField, H: Hasher, R: RandomCoin>(
+        mut self,
+    ) -> ProofOptions {
+        self.parameters_digest = Some(self.parameters_digest::<B, H, R>());
+        self
+    }
+
+    /// Returns proof options with the maximum degree of the FRI remainder polynomial set to the
+    /// specified value.
+    ///
+    /// When the DEEP composition polynomial fits into the FRI remainder (i.e., for traces of up to
+    /// `degree + 1` steps), no FRI layers are built: the prover sends the coefficients of the DEEP
+    /// composition polynomial directly, and the verifier checks its degree by the number of
+    /// coefficients and evaluates it at the query positions. For such traces, FRI layer
+    /// commitments and queries are omitted from the proof, which makes proofs of small
+    /// computations much smaller. For longer traces, FRI is executed as usual, but stops once the
+    /// degree of the folded polynomial drops to `degree` or below (see
+    /// [skips_fri()](Self::skips_fri)); thus, these options do not guarantee that FRI is skipped.
+    /// The largest supported degree is 255, and thus, FRI can be skipped only for traces of up to
+    /// 256 steps.
+    ///
+    /// # Panics
+    /// Panics if `degree` is greater than 255.
+    pub const fn with_max_fri_remainder_degree(mut self, degree: usize) -> ProofOptions {
+        assert!(
+            degree <= FRI_MAX_REMAINDER_DEGREE,
+            "FRI polynomial remainder degree cannot be greater than 255"
+        );
+        self.fri_remainder_max_degree = degree as u8;
+        self
     }
 
     // PUBLIC ACCESSORS
@@ -179,6 +339,11 @@ impl ProofOptions {
     /// they try to change a commitment. Thus, higher grinding factor makes it more difficult to
     /// forge a STARK proof. However, setting grinding factor too high (e.g. higher than 20) will
     /// adversely affect prover time.
+    ///
+    /// The grinding step is placed in the transcript after the commitment to the FRI remainder
+    /// (i.e., after all FRI commitments) and immediately before query positions are drawn. Thus,
+    /// the work has to be redone whenever any of the commitments of the proof changes, and the
+    /// grinding factor contributes its full number of bits to the soundness of the query phase.
     pub const fn grinding_factor(&self) -> u32 {
         self.grinding_factor as u32
     }
@@ -192,6 +357,37 @@ impl ProofOptions {
         self.field_extension
     }
 
+    /// Returns identifiers of the hash functions used for commitments and for the public coin, if
+    /// they were specified via [ProofOptions::with_hash_functions()].
+    pub const fn hash_functions(&self) -> Option<(HashFunctionId, HashFunctionId)> {
+        self.hash_functions
+    }
+
+    /// Returns the number of bytes in commitment digests, if it was specified via
+    /// [ProofOptions::with_digest_truncation()].
+    pub const fn digest_truncation(&self) -> Option<usize> {
+        match self.digest_truncation {
+            Some(num_digest_bytes) => Some(num_digest_bytes as usize),
+            None => None,
+        }
+    }
+
+    /// Returns the digest of the protocol configuration recorded via
+    /// [ProofOptions::with_parameters_digest()], if any.
+    pub const fn recorded_parameters_digest(&self) -> Option<[u8; 32]> {
+        self.parameters_digest
+    }
+
+    /// Returns the per-layer FRI folding schedule specified via
+    /// [ProofOptions::with_fri_folding_schedule()]; the schedule is empty if all FRI layers are
+    /// folded by the same folding factor.
+    pub fn fri_folding_schedule(&self) -> Vec<usize> {
+        self.fri_folding_schedule
+            .iter()
+            .map(|&folding_factor| folding_factor as usize)
+            .collect()
+    }
+
     /// Returns the offset by which the low-degree extension domain is shifted in relation to the
     /// trace domain.
     ///
@@ -200,27 +396,117 @@ impl ProofOptions {
         B::GENERATOR
     }
 
+    /// Returns true if proofs generated with these options for traces of the specified length
+    /// contain no FRI layers (i.e., the DEEP composition polynomial is sent directly as the FRI
+    /// remainder).
+    pub fn skips_fri(&self, trace_length: usize) -> bool {
+        let lde_domain_size = trace_length * self.blowup_factor();
+        self.to_fri_options().num_fri_layers(lde_domain_size) == 0
+    }
+
+    /// Returns a digest of the protocol configuration defined by these options, the base field
+    /// `B`, the commitment hash function `H`, and the public coin `R`.
+    ///
+    /// The digest is a BLAKE3 hash of the modulus, the generator, and the two-adicity of `B`, of
+    /// the parameters of `H` (see `Hasher::write_parameters()`), which include round constants
+    /// and MDS matrices of algebraic hash functions, of the identifier of the hash function used
+    /// by `R`, and of all other proof options (including the field extension, the FRI folding
+    /// schedule, and the recorded hash functions). A parameters digest recorded in these options
+    /// does not contribute to the digest.
+    pub fn parameters_digest<B: StarkField, H: Hasher, R: RandomCoin>(&self) -> [u8; 32] {
+        let mut bytes = Vec::new();
+        let modulus_bytes = B::get_modulus_le_bytes();
+        bytes.write_u8(modulus_bytes.len() as u8);
+        bytes.write_bytes(&modulus_bytes);
+        bytes.write(B::GENERATOR);
+        bytes.write_u32(B::TWO_ADICITY);
+
+        H::write_parameters(&mut bytes);
+        bytes.write(R::HASH_FUNCTION_ID);
+
+        let mut options = self.clone();
+        options.parameters_digest = None;
+        options.write_into(&mut bytes);
+
+        Blake3_256::<B>::hash(&bytes).as_bytes()
+    }
+
     /// Returns options for FRI protocol instantiated with parameters from this proof options.
     pub fn to_fri_options(&self) -> FriOptions {
         let folding_factor = self.fri_folding_factor as usize;
         let remainder_max_degree = self.fri_remainder_max_degree as usize;
         FriOptions::new(self.blowup_factor(), folding_factor, remainder_max_degree)
+            .with_folding_schedule(&self.fri_folding_schedule())
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the field extension byte with a flag set for each optional field present in these
+    /// options.
+    ///
+    /// This byte is written into the serialized options and is bound to the proof via the options
+    /// elements, so that the optional fields which follow it can always be told apart.
+    fn extension_byte(&self) -> u8 {
+        let mut extension_byte = self.field_extension as u8;
+        if self.hash_functions.is_some() {
+            extension_byte |= HASH_FUNCTIONS_FLAG;
+        }
+        if !self.fri_folding_schedule.is_empty() {
+            extension_byte |= FRI_FOLDING_SCHEDULE_FLAG;
+        }
+        if self.digest_truncation.is_some() {
+            extension_byte |= DIGEST_TRUNCATION_FLAG;
+        }
+        if self.parameters_digest.is_some() {
+            extension_byte |= PARAMETERS_DIGEST_FLAG;
+        }
+        extension_byte
     }
 }
 
 impl<E: StarkField> ToElements<E> for ProofOptions {
     fn to_elements(&self) -> Vec<E> {
-        // encode field extension and FRI parameters into a single field element
-        let mut buf = self.field_extension as u32;
+        // encode field extension, presence flags of the optional fields, and FRI parameters into
+        // a single field element; the flags make the encoding of the optional fields which follow
+        // unambiguous
+        let mut buf = self.extension_byte() as u32;
         buf = (buf << 8) | self.fri_folding_factor as u32;
         buf = (buf << 8) | self.fri_remainder_max_degree as u32;
 
-        vec![
+        let mut result = vec![
             E::from(buf),
             E::from(self.grinding_factor),
             E::from(self.blowup_factor),
             E::from(self.num_queries),
-        ]
+        ];
+
+        // encode hash functions into a single field element
+        if let Some((commitment_hash_fn, random_coin_hash_fn)) = self.hash_functions {
+            let buf = ((commitment_hash_fn as u32) << 8) | random_coin_hash_fn as u32;
+            result.push(E::from(buf));
+        }
+
+        // encode the FRI folding schedule as its length followed by the folding factors
+        if !self.fri_folding_schedule.is_empty() {
+            result.push(E::from(self.fri_folding_schedule.len() as u32));
+            result.extend(self.fri_folding_schedule.iter().map(|&factor| E::from(factor)));
+        }
+
+        if let Some(num_digest_bytes) = self.digest_truncation {
+            result.push(E::from(num_digest_bytes));
+        }
+
+        // encode the parameters digest into 4-byte chunks
+        if let Some(parameters_digest) = self.parameters_digest {
+            result.extend(
+                parameters_digest
+                    .chunks(4)
+                    .map(|chunk| E::from(u32::from_le_bytes(chunk.try_into().unwrap()))),
+            );
+        }
+
+        result
     }
 }
 
@@ -230,9 +516,23 @@ impl Serializable for ProofOptions {
         target.write_u8(self.num_queries);
         target.write_u8(self.blowup_factor);
         target.write_u8(self.grinding_factor);
-        target.write(self.field_extension);
+        target.write_u8(self.extension_byte());
         target.write_u8(self.fri_folding_factor);
         target.write_u8(self.fri_remainder_max_degree);
+        if let Some((commitment_hash_fn, random_coin_hash_fn)) = self.hash_functions {
+            target.write(commitment_hash_fn);
+            target.write(random_coin_hash_fn);
+        }
+        if !self.fri_folding_schedule.is_empty() {
+            target.write_u8(self.fri_folding_schedule.len() as u8);
+            target.write_bytes(&self.fri_folding_schedule);
+        }
+        if let Some(num_digest_bytes) = self.digest_truncation {
+            target.write_u8(num_digest_bytes);
+        }
+        if let Some(parameters_digest) = self.parameters_digest {
+            target.write_bytes(&parameters_digest);
+        }
     }
 }
 
@@ -242,14 +542,110 @@ impl Deserializable for ProofOptions {
     /// # Errors
     /// Returns an error of a valid proof options could not be read from the specified `source`.
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
-        Ok(ProofOptions::new(
-            source.read_u8()? as usize,
-            source.read_u8()? as usize,
-            source.read_u8()? as u32,
-            FieldExtension::read_from(source)?,
-            source.read_u8()? as usize,
-            source.read_u8()? as usize,
-        ))
+        let num_queries = source.read_u8()? as usize;
+        let blowup_factor = source.read_u8()? as usize;
+        let grinding_factor = source.read_u8()? as u32;
+        let extension_byte = source.read_u8()?;
+        let field_extension = FieldExtension::read_from(&mut SliceReader::new(&[extension_byte
+            & !(HASH_FUNCTIONS_FLAG
+                | FRI_FOLDING_SCHEDULE_FLAG
+                | DIGEST_TRUNCATION_FLAG
+                | PARAMETERS_DIGEST_FLAG)]))?;
+
+        let fri_folding_factor = source.read_u8()? as usize;
+        let fri_remainder_max_degree = source.read_u8()? as usize;
+
+        // validate the parameters here, as the constructor panics on invalid parameters; the
+        // number of queries and the FRI remainder degree always fit into the limits
+        if num_queries == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "number of queries must be greater than 0".to_string(),
+            ));
+        }
+        if !blowup_factor.is_power_of_two()
+            || !(MIN_BLOWUP_FACTOR..=MAX_BLOWUP_FACTOR).contains(&blowup_factor)
+        {
+            return Err(DeserializationError::InvalidValue(format!(
+                "blowup factor must be a power of 2 between {MIN_BLOWUP_FACTOR} and \
+                {MAX_BLOWUP_FACTOR}, but was {blowup_factor}"
+            )));
+        }
+        if grinding_factor > MAX_GRINDING_FACTOR {
+            return Err(DeserializationError::InvalidValue(format!(
+                "grinding factor cannot be greater than {MAX_GRINDING_FACTOR}, but was \
+                {grinding_factor}"
+            )));
+        }
+        if !fri_folding_factor.is_power_of_two()
+            || !(FRI_MIN_FOLDING_FACTOR..=FRI_MAX_FOLDING_FACTOR).contains(&fri_folding_factor)
+        {
+            return Err(DeserializationError::InvalidValue(format!(
+                "FRI folding factor must be a power of 2 between {FRI_MIN_FOLDING_FACTOR} and \
+                {FRI_MAX_FOLDING_FACTOR}, but was {fri_folding_factor}"
+            )));
+        }
+
+        let mut options = ProofOptions::new(
+            num_queries,
+            blowup_factor,
+            grinding_factor,
+            field_extension,
+            fri_folding_factor,
+            fri_remainder_max_degree,
+        );
+
+        if extension_byte & HASH_FUNCTIONS_FLAG != 0 {
+            let commitment_hash_fn = HashFunctionId::read_from(source)?;
+            let random_coin_hash_fn = HashFunctionId::read_from(source)?;
+            options = options.with_hash_functions(commitment_hash_fn, random_coin_hash_fn);
+        }
+
+        if extension_byte & FRI_FOLDING_SCHEDULE_FLAG != 0 {
+            let schedule_len = source.read_u8()? as usize;
+            let schedule = source.read_vec(schedule_len)?;
+            if schedule
+                .iter()
+                .enumerate()
+                .any(|(depth, &factor)| !is_supported_fri_folding_factor_at(depth, factor as usize))
+            {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "FRI folding schedule {schedule:?} contains an unsupported folding factor"
+                )));
+            }
+            let schedule = schedule.into_iter().map(|factor| factor as usize).collect::<Vec<_>>();
+            options = options.with_fri_folding_schedule(&schedule);
+        }
+
+        if extension_byte & DIGEST_TRUNCATION_FLAG != 0 {
+            let num_digest_bytes = source.read_u8()? as usize;
+            if !(MIN_DIGEST_TRUNCATION..=MAX_DIGEST_TRUNCATION).contains(&num_digest_bytes) {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "digest truncation width must be between {MIN_DIGEST_TRUNCATION} and \
+                    {MAX_DIGEST_TRUNCATION} bytes, but was {num_digest_bytes}"
+                )));
+            }
+            options = options.with_digest_truncation(num_digest_bytes);
+        }
+
+        if extension_byte & PARAMETERS_DIGEST_FLAG != 0 {
+            options.parameters_digest = Some(source.read_array()?);
+        }
+
+        Ok(options)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ProofOptions {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        utils::serde_compat::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ProofOptions {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        utils::serde_compat::deserialize(deserializer)
     }
 }
 
@@ -301,12 +697,31 @@ impl Deserializable for FieldExtension {
     }
 }
 
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns true if the FRI layer at the specified depth can be folded by the specified factor.
+fn is_supported_fri_folding_factor_at(depth: usize, folding_factor: usize) -> bool {
+    let max_folding_factor = if depth == 0 {
+        FRI_MAX_FIRST_LAYER_FOLDING_FACTOR
+    } else {
+        FRI_MAX_FOLDING_FACTOR
+    };
+    folding_factor.is_power_of_two()
+        && (FRI_MIN_FOLDING_FACTOR..=max_folding_factor).contains(&folding_factor)
+}
+
 // TESTS
 // ================================================================================================
 
 #[cfg(test)]
 mod tests {
+    use crypto::{
+        hashers::{Blake3_256, Sha3_256},
+        DefaultRandomCoin, HashFunctionId,
+    };
     use math::fields::f64::BaseElement;
+    use utils::{Deserializable, Serializable};
 
     use super::{FieldExtension, ProofOptions, ToElements};
 
@@ -342,4 +757,217 @@ mod tests {
         );
         assert_eq!(expected, options.to_elements());
     }
+
+    #[test]
+    fn proof_options_to_elements_with_optional_fields() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 8, 127);
+
+        // without the presence flags, both option sets below would be encoded as [.., 1, 16]
+        let options_with_hash_fns = options
+            .clone()
+            .with_hash_functions(HashFunctionId::Custom, HashFunctionId::Blake3_256)
+            .with_digest_truncation(16);
+        let options_with_schedule = options.clone().with_fri_folding_schedule(&[16]);
+
+        let elements_with_hash_fns = ToElements::<BaseElement>::to_elements(&options_with_hash_fns);
+        let elements_with_schedule = ToElements::<BaseElement>::to_elements(&options_with_schedule);
+        assert_eq!(elements_with_hash_fns[1..], elements_with_schedule[1..]);
+        assert_ne!(elements_with_hash_fns, elements_with_schedule);
+
+        // the flags are encoded in the first element next to the field extension
+        let ext_fri =
+            |extension_byte: u8| BaseElement::from(u32::from_le_bytes([127, 8, extension_byte, 0]));
+        assert_eq!(ext_fri(FieldExtension::None as u8 | 0x80 | 0x20), elements_with_hash_fns[0]);
+        assert_eq!(ext_fri(FieldExtension::None as u8 | 0x40), elements_with_schedule[0]);
+    }
+
+    #[test]
+    fn proof_options_with_hash_functions() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127);
+        let options_with_hash_fns = options
+            .clone()
+            .with_hash_functions(HashFunctionId::Blake3_256, HashFunctionId::Rp64_256);
+        assert_eq!(
+            Some((HashFunctionId::Blake3_256, HashFunctionId::Rp64_256)),
+            options_with_hash_fns.hash_functions()
+        );
+
+        // options without hash functions are serialized in the same way as before
+        let bytes = options.to_bytes();
+        assert_eq!(6, bytes.len());
+        assert_eq!(options, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        let bytes = options_with_hash_fns.to_bytes();
+        assert_eq!(8, bytes.len());
+        assert_eq!(options_with_hash_fns, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // hash functions are bound to the proof via the options elements
+        let elements = ToElements::<BaseElement>::to_elements(&options_with_hash_fns);
+        assert_eq!(ToElements::<BaseElement>::to_elements(&options).len() + 1, elements.len());
+        assert_eq!(BaseElement::from((1u32 << 8) | 5), elements[4]);
+    }
+
+    #[test]
+    fn proof_options_with_digest_truncation() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127)
+            .with_fri_folding_schedule(&[16, 4]);
+        assert_eq!(None, options.digest_truncation());
+
+        let truncated_options = options.clone().with_digest_truncation(20);
+        assert_eq!(Some(20), truncated_options.digest_truncation());
+
+        // the truncation width follows the rest of the serialized options
+        let bytes = truncated_options.to_bytes();
+        assert_eq!(options.to_bytes().len() + 1, bytes.len());
+        assert_eq!(20, bytes[bytes.len() - 1]);
+        assert_eq!(truncated_options, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // invalid truncation widths are rejected on deserialization
+        let mut invalid_bytes = bytes.clone();
+        *invalid_bytes.last_mut().unwrap() = 8;
+        assert!(ProofOptions::read_from_bytes(&invalid_bytes).is_err());
+
+        // the truncation width is bound to the proof via the options elements
+        let elements = ToElements::<BaseElement>::to_elements(&truncated_options);
+        assert_eq!(ToElements::<BaseElement>::to_elements(&options).len() + 1, elements.len());
+        assert_eq!(BaseElement::from(20u32), elements[elements.len() - 1]);
+    }
+
+    #[test]
+    fn proof_options_with_parameters_digest() {
+        type Coin = DefaultRandomCoin<Blake3_256<BaseElement>>;
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127);
+        assert_eq!(None, options.recorded_parameters_digest());
+
+        let digest = options.parameters_digest::<BaseElement, Blake3_256<BaseElement>, Coin>();
+        let options_with_digest = options
+            .clone()
+            .with_parameters_digest::<BaseElement, Blake3_256<BaseElement>, Coin>();
+        assert_eq!(Some(digest), options_with_digest.recorded_parameters_digest());
+
+        // the recorded digest does not contribute to the digest
+        assert_eq!(
+            digest,
+            options_with_digest.parameters_digest::<BaseElement, Blake3_256<BaseElement>, Coin>()
+        );
+
+        // the digest changes with the hash functions and with the other options
+        assert_ne!(digest, options.parameters_digest::<BaseElement, Sha3_256<BaseElement>, Coin>());
+        assert_ne!(
+            digest,
+            options
+                .clone()
+                .with_fri_folding_schedule(&[4])
+                .parameters_digest::<BaseElement, Blake3_256<BaseElement>, Coin>()
+        );
+
+        // the digest follows the rest of the serialized options
+        let bytes = options_with_digest.to_bytes();
+        assert_eq!(options.to_bytes().len() + 32, bytes.len());
+        assert_eq!(digest, bytes[bytes.len() - 32..]);
+        assert_eq!(options_with_digest, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // the digest is bound to the proof via the options elements
+        let elements = ToElements::<BaseElement>::to_elements(&options_with_digest);
+        assert_eq!(ToElements::<BaseElement>::to_elements(&options).len() + 8, elements.len());
+    }
+
+    #[test]
+    fn proof_options_with_max_fri_remainder_degree() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 31);
+        assert!(options.skips_fri(4));
+        assert!(!options.skips_fri(64));
+
+        let options_without_fri = options.clone().with_max_fri_remainder_degree(255);
+        assert!(options_without_fri.skips_fri(64));
+        assert!(options_without_fri.skips_fri(256));
+        assert!(!options_without_fri.skips_fri(512));
+        assert_eq!(1, options_without_fri.to_fri_options().num_fri_layers(512 * 8));
+
+        // the remainder degree is bound to the proof via the options elements
+        assert_ne!(
+            ToElements::<BaseElement>::to_elements(&options),
+            ToElements::<BaseElement>::to_elements(&options_without_fri)
+        );
+
+        let options = options.with_max_fri_remainder_degree(63);
+        assert!(options.skips_fri(64));
+        assert!(!options.skips_fri(128));
+    }
+
+    #[test]
+    #[should_panic(expected = "FRI polynomial remainder degree cannot be greater than 255")]
+    fn proof_options_with_max_fri_remainder_degree_too_large() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 31);
+        let _ = options.with_max_fri_remainder_degree(256);
+    }
+
+    #[test]
+    fn proof_options_with_fri_folding_schedule() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127);
+        assert!(options.fri_folding_schedule().is_empty());
+
+        let options_with_schedule = options.clone().with_fri_folding_schedule(&[16, 4]);
+        assert_eq!(vec![16, 4], options_with_schedule.fri_folding_schedule());
+        let fri_options = options_with_schedule.to_fri_options();
+        assert_eq!(16, fri_options.folding_factor_at(0));
+        assert_eq!(4, fri_options.folding_factor_at(1));
+        assert_eq!(8, fri_options.folding_factor_at(2));
+
+        // the schedule is serialized after the rest of the options
+        let bytes = options_with_schedule.to_bytes();
+        assert_eq!(9, bytes.len());
+        assert_eq!(options_with_schedule, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        let options_with_all = options_with_schedule
+            .clone()
+            .with_hash_functions(HashFunctionId::Blake3_256, HashFunctionId::Rp64_256);
+        let bytes = options_with_all.to_bytes();
+        assert_eq!(options_with_all, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // the schedule is bound to the proof via the options elements
+        let elements = ToElements::<BaseElement>::to_elements(&options_with_schedule);
+        assert_eq!(ToElements::<BaseElement>::to_elements(&options).len() + 3, elements.len());
+        assert_eq!(
+            vec![BaseElement::from(2u32), BaseElement::from(16u32), BaseElement::from(4u32)],
+            elements[4..]
+        );
+    }
+
+    #[test]
+    fn proof_options_with_large_first_fri_fold() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127)
+            .with_fri_folding_schedule(&[32, 4]);
+        assert_eq!(32, options.to_fri_options().folding_factor_at(0));
+        let bytes = options.to_bytes();
+        assert_eq!(options, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // only the first layer can be folded by 32
+        let mut bytes = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127)
+            .with_fri_folding_schedule(&[4, 16])
+            .to_bytes();
+        *bytes.last_mut().unwrap() = 32;
+        assert!(ProofOptions::read_from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "FRI folding factor 32 is not supported at layer 1")]
+    fn proof_options_with_large_second_fri_fold() {
+        ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127)
+            .with_fri_folding_schedule(&[4, 32]);
+    }
+
+    #[test]
+    fn proof_options_read_invalid() {
+        let options = ProofOptions::new(28, 8, 16, FieldExtension::None, 4, 31);
+        let bytes = options.to_bytes();
+        assert_eq!(options, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // invalid parameters are reported as errors rather than causing a panic
+        for (idx, value) in [(0, 0), (1, 6), (1, 1), (2, 33), (4, 3), (4, 32)] {
+            let mut bytes = bytes.clone();
+            bytes[idx] = value;
+            assert!(ProofOptions::read_from_bytes(&bytes).is_err());
+        }
+    }
 }
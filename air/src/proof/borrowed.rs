@@ -0,0 +1,129 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use crypto::Hasher;
+use fri::FriProofRef;
+use utils::{ByteReader, DeserializationError, SliceReader};
+
+use super::{Commitments, Context, LazyProof, OodFrame, Proof, ProofExtension, QueriesRef};
+use crate::{ProofOptions, TraceInfo};
+
+// BORROWED PROOF
+// ================================================================================================
+
+/// A borrowed view of a [Proof].
+///
+/// The view references trace, constraint, and FRI query values and Merkle authentication paths
+/// (as well as the GKR proof) in a buffer holding a serialized proof, and thus, it can be read
+/// from the buffer via [read_from_slice()](ProofRef::read_from_slice) without copying these
+/// bytes. This allows verifying a proof straight from the buffer in which it was received. The
+/// remaining components of the proof (i.e., the context, the commitments, the out-of-domain
+/// frame, and the extensions) are small, and are copied into the view.
+///
+/// A view of an owned proof can be obtained via [Proof::as_borrowed()].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProofRef<'a> {
+    /// Basic metadata about the execution of the computation described by this proof.
+    pub context: Context,
+    /// Number of unique queries made by the verifier.
+    pub num_unique_queries: u8,
+    /// Number of tables into which the main trace segment is partitioned.
+    pub num_main_trace_tables: u8,
+    /// Commitments made by the prover during the commit phase of the protocol.
+    pub commitments: Commitments,
+    /// Decommitments of extended execution trace values at positions queried by the verifier;
+    /// see [Proof::trace_queries] for details.
+    pub trace_queries: Vec<QueriesRef<'a>>,
+    /// Decommitments of constraint composition polynomial evaluations at positions queried by
+    /// the verifier.
+    pub constraint_queries: QueriesRef<'a>,
+    /// Trace and constraint polynomial evaluations at an out-of-domain point.
+    pub ood_frame: OodFrame,
+    /// Low-degree proof for a DEEP composition polynomial.
+    pub fri_proof: FriProofRef<'a>,
+    /// Proof-of-work nonce for query seed grinding.
+    pub pow_nonce: u64,
+    /// Optionally, an auxiliary (non-STARK) proof that was generated during auxiliary trace
+    /// generation.
+    pub gkr_proof: Option<&'a [u8]>,
+    /// Application-defined extensions of the proof.
+    pub extensions: Vec<ProofExtension>,
+}
+
+impl<'a> ProofRef<'a> {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns STARK protocol parameters used to generate this proof.
+    pub fn options(&self) -> &ProofOptions {
+        self.context.options()
+    }
+
+    /// Returns trace info for the computation described by this proof.
+    pub fn trace_info(&self) -> &TraceInfo {
+        self.context.trace_info()
+    }
+
+    /// Returns the size of the LDE domain for the computation described by this proof.
+    pub fn lde_domain_size(&self) -> usize {
+        self.context.lde_domain_size()
+    }
+
+    /// Returns security level of this proof (in bits).
+    ///
+    /// See [Proof::security_level()] for details.
+    pub fn security_level<H: Hasher>(&self, conjectured: bool) -> u32 {
+        self.context.security_level::<H>(conjectured)
+    }
+
+    /// Returns an owned copy of this proof.
+    pub fn to_owned_proof(&self) -> Proof {
+        Proof {
+            context: self.context.clone(),
+            num_unique_queries: self.num_unique_queries,
+            num_main_trace_tables: self.num_main_trace_tables,
+            commitments: self.commitments.clone(),
+            trace_queries: self.trace_queries.iter().map(QueriesRef::to_owned_queries).collect(),
+            constraint_queries: self.constraint_queries.to_owned_queries(),
+            ood_frame: self.ood_frame.clone(),
+            fri_proof: self.fri_proof.to_owned_proof(),
+            pow_nonce: self.pow_nonce,
+            gkr_proof: self.gkr_proof.map(<[u8]>::to_vec),
+            extensions: self.extensions.clone(),
+        }
+    }
+
+    // DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a view of a STARK proof serialized via [Proof::to_bytes()] in the specified
+    /// `source`.
+    ///
+    /// Proofs serialized using any of the supported versions of the serialization format can be
+    /// read; see [Proof] for details.
+    ///
+    /// # Errors
+    /// Returns an error if a valid STARK proof could not be read from the specified `source`, or
+    /// if not all bytes of the `source` have been consumed.
+    pub fn read_from_slice(source: &'a [u8]) -> Result<Self, DeserializationError> {
+        let mut reader = SliceReader::new(source);
+        let proof = Self::read_from(&mut reader)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(proof)
+    }
+
+    /// Reads a view of a STARK proof from the specified `source`; the view borrows the bytes of
+    /// the source.
+    ///
+    /// # Errors
+    /// Returns an error if a valid STARK proof could not be read from the specified `source`.
+    pub fn read_from(source: &mut SliceReader<'a>) -> Result<Self, DeserializationError> {
+        LazyProof::read_prefix(source)?.read_sections_from(source)
+    }
+}
@@ -44,6 +44,19 @@ impl Commitments {
     // PUBLIC METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Merges the commitments to the tables of the main trace segment into a single digest.
+    ///
+    /// This digest is used to reseed the public coin after the main trace segment is committed
+    /// to. For a single table, the digest is the commitment to this table; otherwise, the
+    /// commitments are merged sequentially as `merge(...merge(merge(r_0, r_1), r_2)..., r_n)`.
+    ///
+    /// # Panics
+    /// Panics if `table_roots` is empty.
+    pub fn merge_main_trace_table_roots<H: Hasher>(table_roots: &[H::Digest]) -> H::Digest {
+        let (&first, rest) = table_roots.split_first().expect("no main trace table roots");
+        rest.iter().fold(first, |acc, &root| H::merge(&[acc, root]))
+    }
+
     /// Adds the specified commitment to the list of commitments.
     pub fn add<H: Hasher>(&mut self, commitment: &H::Digest) {
         commitment.write_into(&mut self.0);
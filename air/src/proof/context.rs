@@ -5,9 +5,11 @@
 
 use alloc::{string::ToString, vec::Vec};
 
+use crypto::Hasher;
 use math::{StarkField, ToElements};
 use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
+use super::{get_conjectured_security, get_proven_security, VerificationCost};
 use crate::{ProofOptions, TraceInfo};
 
 // PROOF CONTEXT
@@ -84,6 +86,45 @@ impl Context {
     pub fn options(&self) -> &ProofOptions {
         &self.options
     }
+
+    /// Returns an estimate of the work performed by the verifier to verify a proof generated in
+    /// this context, for a computation with the specified number of constraint composition
+    /// columns.
+    ///
+    /// See [VerificationCost::estimate()] for details.
+    pub fn estimate_verification_cost(
+        &self,
+        num_constraint_composition_columns: usize,
+    ) -> VerificationCost {
+        VerificationCost::estimate(self, num_constraint_composition_columns)
+    }
+
+    /// Returns security level (in bits) of a proof generated in this context.
+    ///
+    /// See [Proof::security_level()](super::Proof::security_level) for details.
+    pub fn security_level<H: Hasher>(&self, conjectured: bool) -> u32 {
+        self.security_level_for(conjectured, H::COLLISION_RESISTANCE)
+    }
+
+    /// Returns security level (in bits) of a proof generated in this context with a hash
+    /// function of the specified collision resistance.
+    pub(super) fn security_level_for(&self, conjectured: bool, collision_resistance: u32) -> u32 {
+        if conjectured {
+            get_conjectured_security(
+                &self.options,
+                self.num_modulus_bits(),
+                self.trace_info.length(),
+                collision_resistance,
+            )
+        } else {
+            get_proven_security(
+                &self.options,
+                self.num_modulus_bits(),
+                self.trace_info.length(),
+                collision_resistance,
+            )
+        }
+    }
 }
 
 impl<E: StarkField> ToElements<E> for Context {
@@ -92,10 +133,11 @@ impl<E: StarkField> ToElements<E> for Context {
     /// The elements are laid out as follows:
     /// - trace info [2 or more elements].
     /// - field modulus bytes [2 field elements].
-    /// - field extension and FRI parameters [1 element].
+    /// - field extension, presence flags of optional proof options, and FRI parameters [1 element].
     /// - grinding factor [1 element].
     /// - blowup factor [1 element].
     /// - number of queries [1 element].
+    /// - optional proof options which are present [variable number of elements].
     fn to_elements(&self) -> Vec<E> {
         // convert trace layout
         let mut result = self.trace_info.to_elements();
@@ -152,6 +194,44 @@ impl Deserializable for Context {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Context {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        utils::serde_compat::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Context {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        utils::serde_compat::deserialize(deserializer)
+    }
+}
+
+impl Context {
+    /// Writes this context into the `target` omitting the field modulus, which can be inferred
+    /// from the base field of the computation when the context is read back.
+    pub(super) fn write_compressed<W: ByteWriter>(&self, target: &mut W) {
+        self.trace_info.write_into(target);
+        self.options.write_into(target);
+    }
+
+    /// Reads a context previously written via [write_compressed()](Self::write_compressed) from
+    /// the `source` for a computation in the base field `B`.
+    pub(super) fn read_compressed<B: StarkField, R: ByteReader>(
+        source: &mut R,
+    ) -> Result<Self, DeserializationError> {
+        let trace_info = TraceInfo::read_from(source)?;
+        let options = ProofOptions::read_from(source)?;
+
+        Ok(Context {
+            trace_info,
+            field_modulus_bytes: B::get_modulus_le_bytes(),
+            options,
+        })
+    }
+}
+
 // TESTS
 // ================================================================================================
 
@@ -0,0 +1,124 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::Context;
+
+// VERIFICATION COST
+// ================================================================================================
+
+/// An estimate of the work performed by the verifier to verify a proof.
+///
+/// The estimate is computed from the proof [Context] alone, before the proof is generated, and
+/// thus, it can be used to choose proof parameters for environments in which verification cost
+/// matters (e.g., to estimate gas costs of on-chain verification, or the size of a circuit which
+/// verifies a proof recursively).
+///
+/// The estimate is an upper bound in the sense that it assumes that none of the query positions
+/// drawn by the verifier are duplicates, and that Merkle authentication paths are checked
+/// individually (batch Merkle proofs allow the verifier to skip some of the hashing for paths
+/// which share nodes). It does not include the work of evaluating the constraints of a specific
+/// AIR at the out-of-domain point, and of drawing random coefficients for these constraints, as
+/// this work depends on the AIR rather than on the proof parameters; this work is performed only
+/// once per proof and, for most computations, it is dominated by the work of checking queries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerificationCost {
+    /// Number of invocations of the hash function, including hashing of queried values into
+    /// Merkle tree leaves, merging of nodes along Merkle authentication paths, and updates of the
+    /// public coin.
+    pub num_hash_invocations: usize,
+    /// Number of multiplications in the base field; a multiplication in an extension field of
+    /// degree `k` is counted as `k^2` base field multiplications.
+    pub num_field_multiplications: usize,
+    /// Number of Merkle authentication paths checked against commitments.
+    pub num_merkle_path_checks: usize,
+}
+
+impl VerificationCost {
+    /// Returns an estimate of the work performed by the verifier to verify a proof generated in
+    /// the specified context, for a computation whose constraint composition polynomial is split
+    /// into `num_constraint_composition_columns` columns.
+    ///
+    /// The number of constraint composition columns is not a part of the proof context; it can be
+    /// obtained via `AirContext::num_constraint_composition_columns()`.
+    pub fn estimate(context: &Context, num_constraint_composition_columns: usize) -> Self {
+        let trace_info = context.trace_info();
+        let options = context.options();
+        let fri_options = options.to_fri_options();
+
+        let num_queries = options.num_queries();
+        let lde_domain_size = context.lde_domain_size();
+        let lde_domain_depth = lde_domain_size.ilog2() as usize;
+        let trace_depth = trace_info.length().ilog2() as usize;
+        let extension_degree = options.field_extension().degree() as usize;
+        let ext_mul = extension_degree * extension_degree;
+
+        let num_trace_segments = trace_info.num_segments();
+        let num_trace_columns = trace_info.main_trace_width() + trace_info.aux_segment_width();
+        let folding_factors = fri_options.folding_factors(lde_domain_size);
+        let num_fri_layers = folding_factors.len();
+        let remainder_size = fri_options.remainder_max_degree() + 1;
+
+        // --- Merkle path checks -----------------------------------------------------------------
+        // each query is checked against every trace commitment, the constraint commitment, and
+        // the commitment to every FRI layer
+        let num_merkle_path_checks = num_queries * (num_trace_segments + 1 + num_fri_layers);
+
+        // --- hash invocations -------------------------------------------------------------------
+        // queried rows of the trace and of the constraint evaluations are hashed into leaves, and
+        // the leaves are merged with their siblings up to the root of the LDE domain tree
+        let mut num_hash_invocations =
+            num_queries * (num_trace_segments + 1) * (1 + lde_domain_depth);
+        // the tree of a FRI layer has a leaf for each coset of the folded domain
+        let mut domain_depth = lde_domain_depth;
+        for &folding_factor in folding_factors.iter() {
+            domain_depth -= folding_factor.ilog2() as usize;
+            num_hash_invocations += num_queries * (1 + domain_depth);
+        }
+        // the public coin is reseeded with trace commitments, the constraint commitment, the two
+        // parts of the out-of-domain frame, FRI layer commitments, and the FRI remainder
+        // commitment; then, it is used to draw the out-of-domain point, DEEP composition
+        // coefficients, FRI folding challenges, and query positions, and to check the
+        // proof-of-work; the remainder itself is hashed once
+        let num_deep_coefficients = num_trace_columns + num_constraint_composition_columns;
+        num_hash_invocations += num_trace_segments + 1 + 2 + (num_fri_layers + 1);
+        num_hash_invocations += 1 + num_deep_coefficients + (num_fri_layers + 1) + num_queries + 1;
+        num_hash_invocations += 1;
+
+        // --- field multiplications --------------------------------------------------------------
+        // out-of-domain consistency check: constraint composition columns are combined using
+        // powers of z, each of which requires an exponentiation by a multiple of trace length
+        let mut num_ext_muls = num_constraint_composition_columns * (1 + 2 * trace_depth);
+        let mut num_base_muls = 0;
+
+        // DEEP composition: for each query, the domain point is computed via an exponentiation,
+        // each trace column contributes two terms and each constraint composition column
+        // contributes one term to the composition; the three quotients are computed via batch
+        // inversion which costs 3 multiplications per element
+        num_base_muls += num_queries * 2 * lde_domain_depth;
+        num_ext_muls +=
+            num_queries * (2 * num_trace_columns + num_constraint_composition_columns + 3 * 3);
+
+        // FRI: at each layer, for each query, the folded domain point is computed via an
+        // exponentiation, and the coset of `folding_factor` values is interpolated into a
+        // polynomial which is then evaluated at the folding challenge
+        let mut domain_depth = lde_domain_depth;
+        for &folding_factor in folding_factors.iter() {
+            num_base_muls += num_queries * (2 * domain_depth + folding_factor);
+            num_ext_muls += num_queries * (folding_factor * folding_factor + folding_factor);
+            domain_depth -= folding_factor.ilog2() as usize;
+        }
+
+        // FRI remainder: for each query, the remainder polynomial is evaluated at the domain point
+        // of the last layer using Horner's method
+        num_base_muls += num_queries * 2 * domain_depth;
+        num_ext_muls += num_queries * remainder_size;
+
+        VerificationCost {
+            num_hash_invocations,
+            num_field_multiplications: num_base_muls + num_ext_muls * ext_mul,
+            num_merkle_path_checks,
+        }
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use crypto::{Digest, Hasher};
+use fri::FriProof;
+use utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+
+use super::{Proof, Queries};
+
+// PROOF SECTION
+// ================================================================================================
+
+/// A section of a STARK proof which can be detached from the proof.
+///
+/// Together, these sections make up the bulk of a proof; see [Proof::detach()] for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum ProofSection {
+    /// Decommitments of the execution trace for all trace segments.
+    TraceQueries = 1,
+    /// Decommitments of the constraint composition polynomial evaluations.
+    ConstraintQueries = 2,
+    /// The low-degree proof for the DEEP composition polynomial (i.e., queries against all FRI
+    /// layers and the FRI remainder).
+    FriProof = 3,
+}
+
+impl Serializable for ProofSection {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for ProofSection {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            1 => Ok(Self::TraceQueries),
+            2 => Ok(Self::ConstraintQueries),
+            3 => Ok(Self::FriProof),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as a proof section"
+            ))),
+        }
+    }
+}
+
+// DETACHED SECTION
+// ================================================================================================
+
+/// A reference to a section detached from a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetachedSection {
+    /// The detached section.
+    pub section: ProofSection,
+    /// Hash of the serialized section.
+    pub digest: [u8; 32],
+}
+
+impl Serializable for DetachedSection {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.section.write_into(target);
+        target.write_bytes(&self.digest);
+    }
+}
+
+impl Deserializable for DetachedSection {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let section = ProofSection::read_from(source)?;
+        let digest = source.read_array()?;
+        Ok(Self { section, digest })
+    }
+}
+
+// DETACHED PROOF
+// ================================================================================================
+
+/// A STARK proof with some of its sections stored separately from it.
+///
+/// A detached proof contains all parts of a [Proof] except for the detached sections; each
+/// detached section is replaced by a hash of its serialized bytes. This makes it possible to keep
+/// the bulk of a proof (e.g., queries against FRI layers) in a separate storage (e.g., a data
+/// availability layer), and to keep only a small commitment to this data alongside the rest of
+/// the proof.
+///
+/// A detached proof is produced via [Proof::detach()]. To verify it, the detached sections must
+/// be supplied again via [DetachedProof::attach()], which checks them against their hashes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetachedProof {
+    proof: Proof,
+    detached: Vec<DetachedSection>,
+}
+
+impl DetachedProof {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns references to the sections detached from this proof, in the order of their
+    /// appearance in the proof.
+    pub fn detached_sections(&self) -> &[DetachedSection] {
+        &self.detached
+    }
+
+    /// Returns the proof with detached sections left empty.
+    ///
+    /// The returned proof cannot be verified, but all its parameters (e.g., the proof context)
+    /// are available.
+    pub fn partial_proof(&self) -> &Proof {
+        &self.proof
+    }
+
+    // ATTACHING
+    // --------------------------------------------------------------------------------------------
+
+    /// Restores the full proof by attaching the detached sections to it.
+    ///
+    /// For each detached section, `fetch` is invoked with a reference to the section, and must
+    /// return the serialized bytes of the section (as returned by [Proof::detach()]), or None if
+    /// the section is not available. The bytes are checked against the hash of the section using
+    /// the hash function `H`, which must be the same as the one used to detach the section.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * Any of the sections could not be fetched.
+    /// * The fetched bytes of any section do not hash to the hash of this section.
+    /// * The fetched bytes of any section could not be deserialized into this section.
+    pub fn attach<H, F>(self, mut fetch: F) -> Result<Proof, DeserializationError>
+    where
+        H: Hasher,
+        F: FnMut(&DetachedSection) -> Option<Vec<u8>>,
+    {
+        let mut proof = self.proof;
+        for detached in self.detached.iter() {
+            let bytes = fetch(detached).ok_or_else(|| {
+                DeserializationError::InvalidValue(format!(
+                    "detached section {:?} is not available",
+                    detached.section
+                ))
+            })?;
+            if H::hash(&bytes).as_bytes() != detached.digest {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "detached section {:?} does not match its hash",
+                    detached.section
+                )));
+            }
+
+            match detached.section {
+                ProofSection::TraceQueries => {
+                    let trace_queries = read_section::<Vec<Queries>>(&bytes)?;
+                    if trace_queries.len() != proof.trace_queries.len() {
+                        return Err(DeserializationError::InvalidValue(format!(
+                            "expected {} trace queries in the detached section, but found {}",
+                            proof.trace_queries.len(),
+                            trace_queries.len()
+                        )));
+                    }
+                    proof.trace_queries = trace_queries;
+                },
+                ProofSection::ConstraintQueries => {
+                    proof.constraint_queries = read_section(&bytes)?;
+                },
+                ProofSection::FriProof => {
+                    proof.fri_proof = read_section(&bytes)?;
+                },
+            }
+        }
+
+        Ok(proof)
+    }
+}
+
+impl Proof {
+    /// Detaches the specified sections from this proof.
+    ///
+    /// Returns a [DetachedProof] in which each of the specified sections is replaced by a hash
+    /// of its serialized bytes computed using the hash function `H`, together with the detached
+    /// sections and their serialized bytes. The sections are detached in the order of their
+    /// appearance in the proof, and duplicate sections are ignored.
+    ///
+    /// The hashes only bind the detached proof to the detached data; the soundness of the proof
+    /// does not depend on them, as the restored proof is verified in full.
+    pub fn detach<H: Hasher>(
+        mut self,
+        sections: &[ProofSection],
+    ) -> (DetachedProof, Vec<(DetachedSection, Vec<u8>)>) {
+        let mut sections = sections.to_vec();
+        sections.sort_unstable();
+        sections.dedup();
+
+        let mut detached = Vec::with_capacity(sections.len());
+        for section in sections {
+            let bytes = match section {
+                ProofSection::TraceQueries => {
+                    let empty = self.trace_queries.iter().map(|_| Queries::new_empty()).collect();
+                    core::mem::replace(&mut self.trace_queries, empty).to_bytes()
+                },
+                ProofSection::ConstraintQueries => {
+                    core::mem::replace(&mut self.constraint_queries, Queries::new_empty())
+                        .to_bytes()
+                },
+                ProofSection::FriProof => {
+                    core::mem::replace(&mut self.fri_proof, FriProof::new_dummy()).to_bytes()
+                },
+            };
+            let digest = H::hash(&bytes).as_bytes();
+            detached.push((DetachedSection { section, digest }, bytes));
+        }
+
+        let proof = DetachedProof {
+            proof: self,
+            detached: detached.iter().map(|(section, _)| *section).collect(),
+        };
+        (proof, detached)
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for DetachedProof {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.proof.write_into(target);
+        self.detached.write_into(target);
+    }
+}
+
+impl Deserializable for DetachedProof {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let proof = source.read_field("proof", Proof::read_from)?;
+        let detached = source.read_field("detached_sections", Vec::<DetachedSection>::read_from)?;
+        for (i, section) in detached.iter().enumerate().skip(1) {
+            if section.section <= detached[i - 1].section {
+                return Err(DeserializationError::InvalidValue(
+                    "detached sections must be unique and sorted".into(),
+                ));
+            }
+        }
+        Ok(Self { proof, detached })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads a section from the specified bytes, making sure that all bytes are consumed.
+fn read_section<T: Deserializable>(bytes: &[u8]) -> Result<T, DeserializationError> {
+    let mut reader = SliceReader::new(bytes);
+    let section = T::read_from(&mut reader)?;
+    if reader.has_more_bytes() {
+        return Err(DeserializationError::UnconsumedBytes);
+    }
+    Ok(section)
+}
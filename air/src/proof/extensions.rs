@@ -0,0 +1,73 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+// PROOF EXTENSION
+// ================================================================================================
+/// An application-defined section of a STARK proof.
+///
+/// Extensions allow applications to attach additional data to a proof. For example, an
+/// application may open the committed execution trace at positions or columns which are not
+/// required by the STARK protocol itself, and use these openings to answer application-level
+/// queries about the trace.
+///
+/// An extension consists of an application-defined identifier and an opaque sequence of bytes.
+/// The Winterfell verifier does not interpret the contents of extensions; checking them is the
+/// responsibility of the application. Since each extension is serialized as a length-prefixed
+/// sequence of bytes, readers can skip extensions they do not understand, and new kinds of
+/// extensions can be introduced without changing the serialization format of the proof.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProofExtension {
+    id: u16,
+    data: Vec<u8>,
+}
+
+impl ProofExtension {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new proof extension with the specified identifier and contents.
+    pub fn new(id: u16, data: Vec<u8>) -> Self {
+        Self { id, data }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the application-defined identifier of this extension.
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    /// Returns the contents of this extension.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Consumes this extension and returns its contents.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for ProofExtension {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u16(self.id);
+        self.data.write_into(target);
+    }
+}
+
+impl Deserializable for ProofExtension {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let id = source.read_u16()?;
+        let data = Vec::<u8>::read_from(source)?;
+        Ok(Self { id, data })
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use crypto::Hasher;
+use fri::FriProofRef;
+use utils::{ByteReader, Deserializable, DeserializationError, SliceReader};
+
+use super::{
+    num_trace_queries, read_extensions, read_header, read_num_main_trace_tables, Commitments,
+    Context, OodFrame, Proof, ProofRef, QueriesRef, GKR_PROOF_FLAG,
+};
+use crate::{ProofOptions, TraceInfo};
+
+// LAZY PROOF
+// ================================================================================================
+
+/// A STARK proof whose query sections are parsed on access.
+///
+/// When a lazy proof is read from a buffer holding a serialized proof via
+/// [read_from_slice()](LazyProof::read_from_slice), only the context, the number of unique
+/// queries, the number of main trace tables, and the commitments are parsed; the remaining
+/// sections of the proof (i.e., trace and constraint queries, the out-of-domain frame, the FRI
+/// proof, the proof-of-work nonce, the GKR proof, and the extensions) are kept as raw bytes, and
+/// are parsed into a [ProofRef] only when [read_sections()](LazyProof::read_sections) is
+/// called.
+///
+/// This allows a service which receives proofs to cheaply reject proofs generated with
+/// unacceptable options, or proofs with unexpected numbers of commitments, before spending any
+/// effort on parsing the bulk of the proof.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LazyProof<'a> {
+    /// Basic metadata about the execution of the computation described by this proof.
+    pub context: Context,
+    /// Number of unique queries made by the verifier.
+    pub num_unique_queries: u8,
+    /// Number of tables into which the main trace segment is partitioned.
+    pub num_main_trace_tables: u8,
+    /// Commitments made by the prover during the commit phase of the protocol.
+    pub commitments: Commitments,
+    version: u8,
+    feature_flags: u8,
+    sections: &'a [u8],
+}
+
+impl<'a> LazyProof<'a> {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns STARK protocol parameters used to generate this proof.
+    pub fn options(&self) -> &ProofOptions {
+        self.context.options()
+    }
+
+    /// Returns trace info for the computation described by this proof.
+    pub fn trace_info(&self) -> &TraceInfo {
+        self.context.trace_info()
+    }
+
+    /// Returns the size of the LDE domain for the computation described by this proof.
+    pub fn lde_domain_size(&self) -> usize {
+        self.context.lde_domain_size()
+    }
+
+    /// Returns security level of this proof (in bits).
+    ///
+    /// See [Proof::security_level()] for details.
+    pub fn security_level<H: Hasher>(&self, conjectured: bool) -> u32 {
+        self.context.security_level::<H>(conjectured)
+    }
+
+    /// Returns the number of bytes in the sections of this proof which have not been parsed yet.
+    pub fn num_section_bytes(&self) -> usize {
+        self.sections.len()
+    }
+
+    // COMMITMENT PARSING
+    // --------------------------------------------------------------------------------------------
+
+    /// Parses the commitments of this proof into trace commitments, the constraint commitment,
+    /// and FRI layer commitments (in this order) using the hash function `H`.
+    ///
+    /// The expected number of each kind of commitments is derived from the context of the proof
+    /// (i.e., the number of trace segments, the number of main trace tables, and the FRI options).
+    ///
+    /// # Errors
+    /// Returns an error if the commitments could not be parsed into the expected number of
+    /// commitments.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_commitments<H: Hasher>(
+        &self,
+    ) -> Result<(Vec<H::Digest>, H::Digest, Vec<H::Digest>), DeserializationError> {
+        let num_trace_segments =
+            self.trace_info().num_segments() + self.num_main_trace_tables as usize - 1;
+        let num_fri_layers = self.options().to_fri_options().num_fri_layers(self.lde_domain_size());
+        self.commitments.clone().parse::<H>(num_trace_segments, num_fri_layers)
+    }
+
+    // SECTION PARSING
+    // --------------------------------------------------------------------------------------------
+
+    /// Parses the remaining sections of this proof, and returns a borrowed view of the full proof.
+    ///
+    /// The sections are parsed every time this method is called.
+    ///
+    /// # Errors
+    /// Returns an error if the sections of the proof could not be parsed, or if not all bytes of
+    /// the sections have been consumed.
+    pub fn read_sections(&self) -> Result<ProofRef<'a>, DeserializationError> {
+        let mut reader = SliceReader::new(self.sections);
+        let proof = self.read_sections_from(&mut reader)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(proof)
+    }
+
+    /// Parses the remaining sections of this proof, and returns an owned copy of the full proof.
+    ///
+    /// # Errors
+    /// Returns an error if the sections of the proof could not be parsed, or if not all bytes of
+    /// the sections have been consumed.
+    pub fn to_owned_proof(&self) -> Result<Proof, DeserializationError> {
+        self.read_sections().map(|proof| proof.to_owned_proof())
+    }
+
+    // DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Reads a lazy proof from a STARK proof serialized via [Proof::to_bytes()] in the specified
+    /// `source`.
+    ///
+    /// Proofs serialized using any of the supported versions of the serialization format can be
+    /// read; see [Proof] for details. All bytes following the commitments are assumed to belong
+    /// to the proof.
+    ///
+    /// # Errors
+    /// Returns an error if the context, the number of unique queries, the number of main trace
+    /// tables, or the commitments could not be read from the specified `source`.
+    pub fn read_from_slice(source: &'a [u8]) -> Result<Self, DeserializationError> {
+        let mut reader = SliceReader::new(source);
+        let mut proof = Self::read_prefix(&mut reader)?;
+        let num_prefix_bytes = reader.position().expect("slice reader has a position");
+        proof.sections = &source[num_prefix_bytes..];
+        Ok(proof)
+    }
+
+    /// Reads the eagerly parsed parts of a proof from the specified `source`; the sections of the
+    /// returned proof are left empty.
+    pub(super) fn read_prefix(source: &mut SliceReader<'a>) -> Result<Self, DeserializationError> {
+        let (version, feature_flags) = read_header(source)?;
+
+        let context = source.read_field("context", Context::read_from)?;
+        let num_unique_queries = source.read_field("num_unique_queries", SliceReader::read_u8)?;
+        let num_main_trace_tables = source.read_field("num_main_trace_tables", |source| {
+            read_num_main_trace_tables(source, feature_flags)
+        })?;
+        let commitments = source.read_field("commitments", Commitments::read_from)?;
+
+        Ok(LazyProof {
+            context,
+            num_unique_queries,
+            num_main_trace_tables,
+            commitments,
+            version,
+            feature_flags,
+            sections: &[],
+        })
+    }
+
+    /// Reads the sections of a proof following the parts parsed into this lazy proof from the
+    /// specified `source`.
+    pub(super) fn read_sections_from(
+        &self,
+        source: &mut SliceReader<'a>,
+    ) -> Result<ProofRef<'a>, DeserializationError> {
+        let feature_flags = self.feature_flags;
+        let num_trace_queries =
+            num_trace_queries(self.trace_info(), feature_flags, self.num_main_trace_tables);
+        let mut trace_queries = Vec::with_capacity(num_trace_queries);
+        for i in 0..num_trace_queries {
+            trace_queries.push(source.read_field("trace_queries", |source| {
+                source.read_indexed(i, QueriesRef::read_from)
+            })?);
+        }
+
+        let constraint_queries = source.read_field("constraint_queries", QueriesRef::read_from)?;
+        let ood_frame = source.read_field("ood_frame", OodFrame::read_from)?;
+        let fri_proof = source.read_field("fri_proof", FriProofRef::read_from)?;
+        let pow_nonce = source.read_field("pow_nonce", SliceReader::read_u64)?;
+
+        // in the legacy format, the GKR proof is always serialized as an optional value
+        let gkr_proof = source.read_field("gkr_proof", |source| {
+            let has_gkr_proof = if self.version == 0 {
+                source.read_bool()?
+            } else {
+                feature_flags & GKR_PROOF_FLAG != 0
+            };
+            if has_gkr_proof {
+                let num_gkr_proof_bytes = source.read_usize()?;
+                source.read_borrowed_slice(num_gkr_proof_bytes).map(Some)
+            } else {
+                Ok(None)
+            }
+        })?;
+        let extensions =
+            source.read_field("extensions", |source| read_extensions(source, feature_flags))?;
+
+        Ok(ProofRef {
+            context: self.context.clone(),
+            num_unique_queries: self.num_unique_queries,
+            num_main_trace_tables: self.num_main_trace_tables,
+            commitments: self.commitments.clone(),
+            trace_queries,
+            constraint_queries,
+            ood_frame,
+            fri_proof,
+            pow_nonce,
+            gkr_proof,
+            extensions,
+        })
+    }
+}
@@ -8,12 +8,14 @@
 use alloc::vec::Vec;
 use core::cmp;
 
-use crypto::Hasher;
+use crypto::{HashFunctionId, Hasher, MerkleNodeDictionary};
 use fri::FriProof;
-use math::FieldElement;
-use utils::{ByteReader, Deserializable, DeserializationError, Serializable, SliceReader};
+use math::{FieldElement, StarkField};
+use utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
 
-use crate::{ProofOptions, TraceInfo};
+use crate::{FieldExtension, ProofOptions, TraceInfo};
 
 mod context;
 pub use context::Context;
@@ -22,7 +24,7 @@ mod commitments;
 pub use commitments::Commitments;
 
 mod queries;
-pub use queries::Queries;
+pub use queries::{Queries, QueriesRef};
 
 mod ood_frame;
 pub use ood_frame::{OodFrame, TraceOodFrame};
@@ -30,6 +32,24 @@ pub use ood_frame::{OodFrame, TraceOodFrame};
 mod table;
 pub use table::Table;
 
+mod extensions;
+pub use extensions::ProofExtension;
+
+mod cost;
+pub use cost::VerificationCost;
+
+mod borrowed;
+pub use borrowed::ProofRef;
+
+mod lazy;
+pub use lazy::LazyProof;
+
+mod schema;
+pub use schema::{proof_schema, PROOF_SCHEMA_VERSION};
+
+mod detached;
+pub use detached::{DetachedProof, DetachedSection, ProofSection};
+
 #[cfg(test)]
 mod tests;
 
@@ -39,6 +59,50 @@ mod tests;
 const GRINDING_CONTRIBUTION_FLOOR: u32 = 80;
 const MAX_PROXIMITY_PARAMETER: u64 = 1000;
 
+/// Version of the proof serialization format produced by [Proof::to_bytes()].
+///
+/// Version 0 refers to the legacy format in which proofs were serialized without a header.
+pub const PROOF_VERSION: u8 = 1;
+
+/// Marker byte which starts the header of a versioned proof. Proofs serialized in the legacy
+/// format start with the width of the main trace segment, which can never be zero, and thus,
+/// the legacy format can always be distinguished from the versioned ones.
+const VERSIONED_PROOF_MARKER: u8 = 0;
+
+/// Feature flag indicating that the proof contains a GKR proof.
+const GKR_PROOF_FLAG: u8 = 0b0000_0001;
+
+/// Feature flag indicating that the proof contains queries against the preprocessed columns of
+/// the main trace segment.
+const PREPROCESSED_TRACE_FLAG: u8 = 0b0000_0010;
+
+/// Feature flag indicating that the proof contains application-defined extensions.
+const EXTENSIONS_FLAG: u8 = 0b0000_0100;
+
+/// Feature flag indicating that the main trace segment is partitioned into multiple tables.
+const MAIN_TRACE_TABLES_FLAG: u8 = 0b0000_1000;
+
+/// Feature flags which are supported by the current version of the deserializer.
+const SUPPORTED_FEATURE_FLAGS: u8 =
+    GKR_PROOF_FLAG | PREPROCESSED_TRACE_FLAG | EXTENSIONS_FLAG | MAIN_TRACE_TABLES_FLAG;
+
+/// Magic bytes which start a proof produced by [Proof::to_bytes_compressed()].
+#[cfg(feature = "compression")]
+const COMPRESSED_PROOF_MAGIC: [u8; 4] = *b"WFPZ";
+
+/// Number of bytes in the header of a compressed proof: magic bytes, codec identifier, and the
+/// length of the uncompressed proof.
+#[cfg(feature = "compression")]
+const COMPRESSED_PROOF_HEADER_LEN: usize = 13;
+
+/// Identifier of the DEFLATE codec (RFC 1951) in the header of a compressed proof.
+#[cfg(feature = "compression")]
+const DEFLATE_CODEC: u8 = 1;
+
+/// Compression level used by [Proof::to_bytes_compressed()].
+#[cfg(feature = "compression")]
+const DEFLATE_LEVEL: u8 = 6;
+
 // PROOF
 // ================================================================================================
 /// A proof generated by Winterfell prover.
@@ -49,10 +113,21 @@ const MAX_PROXIMITY_PARAMETER: u64 = 1000;
 ///
 /// Optionally, it may contain a GKR proof. The GKR proof object gives the possibility to prove some
 /// auxiliary trace constraints using GKR, as described in [Improving logarithmic derivative lookups
-/// using GKR](https://eprint.iacr.org/2023/1284.pdf).
+/// using GKR](https://eprint.iacr.org/2023/1284.pdf). A proof may also contain a list of
+/// application-defined [extensions](ProofExtension), which are not interpreted by the verifier.
 ///
 /// A proof can be serialized into a sequence of bytes using [to_bytes()](Proof::to_bytes) function,
 /// and deserialized from a sequence of bytes using [from_bytes()](Proof::from_bytes) function.
+/// A proof can also be read from a sequence of bytes without copying query values and Merkle
+/// authentication paths into a borrowed view ([ProofRef]).
+/// Serialized proofs are self-describing: they start with a header which contains the version
+/// of the serialization format and a set of feature flags describing which optional sections are
+/// present in the proof. The deserializer accepts proofs serialized using any of the previous
+/// versions of the format (including the legacy format without a header).
+///
+/// For cases when proof size matters (e.g., for proofs verified on-chain), a proof can also be
+/// serialized into a more compact form using [compress()](Proof::compress) function, and read back
+/// using [decompress()](Proof::decompress) function.
 ///
 /// To estimate soundness of a proof (in bits), [security_level()](Proof::security_level) function
 /// can be used.
@@ -63,10 +138,17 @@ pub struct Proof {
     /// Number of unique queries made by the verifier. This will be different from the
     /// context.options.num_queries if the same position in the domain was queried more than once.
     pub num_unique_queries: u8,
+    /// Number of tables into which the main trace segment is partitioned; this is 1 unless
+    /// several traces are proven jointly (see
+    /// [AirContext::main_trace_table_widths()](crate::AirContext::main_trace_table_widths)).
+    pub num_main_trace_tables: u8,
     /// Commitments made by the prover during the commit phase of the protocol.
     pub commitments: Commitments,
     /// Decommitments of extended execution trace values (for all trace segments) at position
-    ///  queried by the verifier.
+    ///  queried by the verifier. Decommitments of each main trace table are stored first (one
+    ///  after another), followed by the decommitments of the auxiliary trace segment. If the main
+    ///  trace segment contains preprocessed columns, the decommitments of these columns are
+    ///  stored last.
     pub trace_queries: Vec<Queries>,
     /// Decommitments of constraint composition polynomial evaluations at positions queried by
     /// the verifier.
@@ -79,6 +161,9 @@ pub struct Proof {
     pub pow_nonce: u64,
     /// Optionally, an auxiliary (non-STARK) proof that was generated during auxiliary trace generation.
     pub gkr_proof: Option<Vec<u8>>,
+    /// Application-defined extensions of the proof (e.g., openings of the committed execution
+    /// trace at additional positions).
+    pub extensions: Vec<ProofExtension>,
 }
 
 impl Proof {
@@ -97,6 +182,128 @@ impl Proof {
         self.context.lde_domain_size()
     }
 
+    /// Returns the length of the execution trace of the computation described by this proof.
+    pub fn trace_length(&self) -> usize {
+        self.trace_info().length()
+    }
+
+    /// Returns the total number of columns in the execution trace of the computation described
+    /// by this proof, including the columns of the auxiliary trace segment.
+    pub fn trace_width(&self) -> usize {
+        self.trace_info().width()
+    }
+
+    /// Returns the modulus of the base field of this proof encoded in little-endian byte order.
+    pub fn field_modulus_bytes(&self) -> &[u8] {
+        self.context.field_modulus_bytes()
+    }
+
+    /// Returns the number of bits in the modulus of the base field of this proof.
+    pub fn num_modulus_bits(&self) -> u32 {
+        self.context.num_modulus_bits()
+    }
+
+    /// Returns the field extension used to generate this proof.
+    pub fn field_extension(&self) -> FieldExtension {
+        self.options().field_extension()
+    }
+
+    /// Returns the degree of the field extension used to generate this proof; for proofs
+    /// generated without a field extension, this is 1.
+    pub fn extension_degree(&self) -> u32 {
+        self.field_extension().degree()
+    }
+
+    /// Returns identifiers of the hash functions used for commitments and for the public coin
+    /// as recorded in this proof, or None if the hash functions were not recorded.
+    ///
+    /// See [ProofOptions::with_hash_functions()] for details.
+    pub fn hash_functions(&self) -> Option<(HashFunctionId, HashFunctionId)> {
+        self.options().hash_functions()
+    }
+
+    /// Returns the number of queries specified by the options of this proof.
+    ///
+    /// The number of unique queries actually included in the proof may be smaller; it is stored
+    /// in [Proof::num_unique_queries].
+    pub fn num_queries(&self) -> usize {
+        self.options().num_queries()
+    }
+
+    /// Returns the number of FRI layers in this proof, not counting the FRI remainder.
+    pub fn num_fri_layers(&self) -> usize {
+        self.fri_proof.num_layers()
+    }
+
+    /// Returns the number of bits of proof-of-work used to generate this proof.
+    pub fn grinding_factor(&self) -> u32 {
+        self.options().grinding_factor()
+    }
+
+    /// Returns the commitments to the tables of the main trace segment contained in this proof,
+    /// in the order of the tables.
+    ///
+    /// These commitments can be used to check openings of a single main trace table (e.g., a
+    /// group of columns opened for an auxiliary protocol) against this proof.
+    ///
+    /// # Errors
+    /// Returns an error if the commitments of this proof could not be parsed.
+    pub fn main_trace_table_commitments<H: Hasher>(
+        &self,
+    ) -> Result<Vec<H::Digest>, DeserializationError> {
+        let num_main_trace_tables = self.num_main_trace_tables as usize;
+        let num_trace_segments = self.trace_info().num_segments() + num_main_trace_tables - 1;
+        let (mut trace_commitments, ..) =
+            self.commitments.clone().parse::<H>(num_trace_segments, self.num_fri_layers())?;
+        trace_commitments.truncate(num_main_trace_tables);
+        Ok(trace_commitments)
+    }
+
+    /// Returns a borrowed view of this proof.
+    ///
+    /// Query values and Merkle authentication paths of the view reference the ones contained in
+    /// this proof; see [ProofRef] for details.
+    pub fn as_borrowed(&self) -> ProofRef<'_> {
+        ProofRef {
+            context: self.context.clone(),
+            num_unique_queries: self.num_unique_queries,
+            num_main_trace_tables: self.num_main_trace_tables,
+            commitments: self.commitments.clone(),
+            trace_queries: self.trace_queries.iter().map(Queries::as_borrowed).collect(),
+            constraint_queries: self.constraint_queries.as_borrowed(),
+            ood_frame: self.ood_frame.clone(),
+            fri_proof: self.fri_proof.as_borrowed(),
+            pow_nonce: self.pow_nonce,
+            gkr_proof: self.gkr_proof.as_deref(),
+            extensions: self.extensions.clone(),
+        }
+    }
+
+    /// Returns the first extension of this proof with the specified identifier, if any.
+    pub fn get_extension(&self, id: u16) -> Option<&ProofExtension> {
+        self.extensions.iter().find(|extension| extension.id() == id)
+    }
+
+    /// Returns feature flags describing which optional sections are present in this proof.
+    fn feature_flags(&self) -> u8 {
+        let mut feature_flags = 0;
+        if self.gkr_proof.is_some() {
+            feature_flags |= GKR_PROOF_FLAG;
+        }
+        let num_trace_segments =
+            self.trace_info().num_segments() + self.num_main_trace_tables as usize - 1;
+        if self.trace_queries.len() > num_trace_segments {
+            feature_flags |= PREPROCESSED_TRACE_FLAG;
+        }
+        if !self.extensions.is_empty() {
+            feature_flags |= EXTENSIONS_FLAG;
+        }
+        if self.num_main_trace_tables > 1 {
+            feature_flags |= MAIN_TRACE_TABLES_FLAG;
+        }
+        feature_flags
+    }
+
     // SECURITY LEVEL
     // --------------------------------------------------------------------------------------------
     /// Returns security level of this proof (in bits).
@@ -106,21 +313,27 @@ impl Proof {
     /// 2x - 3x higher than the number of queries needed for conjectured security at the same
     /// security level.
     pub fn security_level<H: Hasher>(&self, conjectured: bool) -> u32 {
-        if conjectured {
-            get_conjectured_security(
-                self.context.options(),
-                self.context.num_modulus_bits(),
-                self.trace_info().length(),
-                H::COLLISION_RESISTANCE,
-            )
-        } else {
-            get_proven_security(
-                self.context.options(),
-                self.context.num_modulus_bits(),
-                self.trace_info().length(),
-                H::COLLISION_RESISTANCE,
-            )
+        self.context.security_level::<H>(conjectured)
+    }
+
+    /// Returns security level of this proof (in bits) estimated for the commitment hash function
+    /// recorded in this proof.
+    ///
+    /// This works like [Proof::security_level()], but does not require the hash function to be
+    /// known at compile time. Returns None if the hash functions were not recorded in this proof
+    /// (see [ProofOptions::with_hash_functions()]), or if the commitment hash function is not
+    /// defined in the `winter-crypto` crate.
+    ///
+    /// If the proof records a digest truncation width (see
+    /// [ProofOptions::with_digest_truncation()]), the collision resistance of the commitment hash
+    /// function is limited to 4 bits per digest byte.
+    pub fn estimated_security_level(&self, conjectured: bool) -> Option<u32> {
+        let (commitment_hash_fn, _) = self.hash_functions()?;
+        let mut collision_resistance = commitment_hash_fn.collision_resistance()?;
+        if let Some(num_digest_bytes) = self.options().digest_truncation() {
+            collision_resistance = collision_resistance.min(num_digest_bytes as u32 * 4);
         }
+        Some(self.context.security_level_for(conjectured, collision_resistance))
     }
 
     // SERIALIZATION / DESERIALIZATION
@@ -139,6 +352,221 @@ impl Proof {
         Deserializable::read_from_bytes(source)
     }
 
+    /// Returns this proof serialized via [to_bytes()](Proof::to_bytes) and encoded as a lowercase
+    /// hex string.
+    ///
+    /// See [utils::encoding] for details about the encoding.
+    #[cfg(feature = "encoding")]
+    pub fn to_hex(&self) -> alloc::string::String {
+        utils::encoding::to_hex(self)
+    }
+
+    /// Returns a STARK proof decoded from the specified hex string.
+    ///
+    /// The decoded bytes must be exactly the bytes which the proof serializes into; thus, proofs
+    /// serialized using the legacy format are rejected.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a canonical hex encoding of a valid STARK proof.
+    #[cfg(feature = "encoding")]
+    pub fn from_hex(source: &str) -> Result<Self, DeserializationError> {
+        utils::encoding::from_hex(source)
+    }
+
+    /// Returns this proof serialized via [to_bytes()](Proof::to_bytes) and encoded as a padded
+    /// Base64 string.
+    ///
+    /// See [utils::encoding] for details about the encoding.
+    #[cfg(feature = "encoding")]
+    pub fn to_base64(&self) -> alloc::string::String {
+        utils::encoding::to_base64(self)
+    }
+
+    /// Returns a STARK proof decoded from the specified Base64 string.
+    ///
+    /// The decoded bytes must be exactly the bytes which the proof serializes into; thus, proofs
+    /// serialized using the legacy format are rejected.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a canonical Base64 encoding of a valid STARK proof.
+    #[cfg(feature = "encoding")]
+    pub fn from_base64(source: &str) -> Result<Self, DeserializationError> {
+        utils::encoding::from_base64(source)
+    }
+
+    /// Serializes this proof into a compact vector of bytes.
+    ///
+    /// As compared to [to_bytes()](Proof::to_bytes), the compressed form:
+    /// * Does not include the modulus of the base field; it is inferred from the base field
+    ///   specified during decompression.
+    /// * Includes every Merkle tree node only once: nodes shared across trace, constraint, and FRI
+    ///   queries are replaced with references to their first occurrence.
+    /// * Encodes lengths of query values using variable-length integers.
+    ///
+    /// A compressed proof can be read back using [decompress()](Proof::decompress) function.
+    ///
+    /// # Errors
+    /// Returns an error if Merkle authentication paths in this proof could not be parsed into
+    /// digests of the hash function `H`.
+    pub fn compress<H: Hasher>(&self) -> Result<Vec<u8>, DeserializationError> {
+        let mut target = Vec::new();
+        target.write_u8(PROOF_VERSION);
+        target.write_u8(self.feature_flags());
+
+        let mut nodes = MerkleNodeDictionary::<H>::new();
+        self.context.write_compressed(&mut target);
+        target.write_u8(self.num_unique_queries);
+        if self.num_main_trace_tables > 1 {
+            target.write_u8(self.num_main_trace_tables);
+        }
+        self.commitments.write_into(&mut target);
+        for queries in self.trace_queries.iter() {
+            queries.write_compressed(&mut target, &mut nodes)?;
+        }
+        self.constraint_queries.write_compressed(&mut target, &mut nodes)?;
+        self.ood_frame.write_into(&mut target);
+        self.fri_proof.write_compressed(&mut target, &mut nodes)?;
+        self.pow_nonce.write_into(&mut target);
+        if let Some(gkr_proof) = &self.gkr_proof {
+            gkr_proof.write_into(&mut target);
+        }
+        if !self.extensions.is_empty() {
+            self.extensions.write_into(&mut target);
+        }
+
+        Ok(target)
+    }
+
+    /// Returns a STARK proof read from the specified compressed `source`.
+    ///
+    /// The `source` is expected to be produced by [compress()](Proof::compress) function invoked
+    /// with the same hash function `H`, for a computation over the base field `B`.
+    ///
+    /// # Errors
+    /// Returns an error of a valid compressed STARK proof could not be read from the specified
+    /// `source`.
+    pub fn decompress<H: Hasher, B: StarkField>(
+        source: &[u8],
+    ) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(source);
+        let version = source.read_u8()?;
+        if version == 0 || version > PROOF_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "proof version {version} is not supported; the latest supported version is {PROOF_VERSION}"
+            )));
+        }
+        let feature_flags = read_feature_flags(&mut source)?;
+
+        let mut nodes = MerkleNodeDictionary::<H>::new();
+        let context = Context::read_compressed::<B, _>(&mut source)?;
+        let num_unique_queries = source.read_u8()?;
+        let num_main_trace_tables = read_num_main_trace_tables(&mut source, feature_flags)?;
+        let commitments = Commitments::read_from(&mut source)?;
+        let num_trace_queries =
+            num_trace_queries(context.trace_info(), feature_flags, num_main_trace_tables);
+        let mut trace_queries = Vec::with_capacity(num_trace_queries);
+        for _ in 0..num_trace_queries {
+            trace_queries.push(Queries::read_compressed(&mut source, &mut nodes)?);
+        }
+        let constraint_queries = Queries::read_compressed(&mut source, &mut nodes)?;
+        let ood_frame = OodFrame::read_from(&mut source)?;
+        let fri_proof = FriProof::read_compressed(&mut source, &mut nodes)?;
+        let pow_nonce = source.read_u64()?;
+        let gkr_proof = if feature_flags & GKR_PROOF_FLAG != 0 {
+            Some(Vec::<u8>::read_from(&mut source)?)
+        } else {
+            None
+        };
+        let extensions = read_extensions(&mut source, feature_flags)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+
+        Ok(Proof {
+            context,
+            num_unique_queries,
+            num_main_trace_tables,
+            commitments,
+            trace_queries,
+            constraint_queries,
+            ood_frame,
+            fri_proof,
+            pow_nonce,
+            gkr_proof,
+            extensions,
+        })
+    }
+
+    /// Serializes this proof into a vector of bytes, and compresses the result with a
+    /// general-purpose compression codec.
+    ///
+    /// The output starts with a header consisting of 4 magic bytes (`WFPZ`), an identifier of the
+    /// codec, and the length of the uncompressed proof (as a `u64`); these are followed by the
+    /// output of [to_bytes()](Proof::to_bytes) compressed using DEFLATE (RFC 1951). Unlike
+    /// [compress()](Proof::compress), this does not require knowing the hash function and the
+    /// base field of the proof.
+    ///
+    /// A compressed proof can be read back using
+    /// [from_bytes_compressed()](Proof::from_bytes_compressed) function.
+    #[cfg(feature = "compression")]
+    pub fn to_bytes_compressed(&self) -> Vec<u8> {
+        let bytes = self.to_bytes();
+        let mut target = Vec::new();
+        target.write_bytes(&COMPRESSED_PROOF_MAGIC);
+        target.write_u8(DEFLATE_CODEC);
+        target.write_u64(bytes.len() as u64);
+        target.write_bytes(&miniz_oxide::deflate::compress_to_vec(&bytes, DEFLATE_LEVEL));
+        target
+    }
+
+    /// Returns a STARK proof read from the specified `source` produced by
+    /// [to_bytes_compressed()](Proof::to_bytes_compressed) function.
+    ///
+    /// The size of the decompressed proof is limited by the length recorded in the header, and
+    /// thus, decompression never allocates more memory than the header declares.
+    ///
+    /// # Errors
+    /// Returns an error if the `source` does not start with a valid header, if the `source` could
+    /// not be decompressed into exactly the number of bytes declared in the header, or if a
+    /// valid STARK proof could not be read from the decompressed bytes.
+    #[cfg(feature = "compression")]
+    pub fn from_bytes_compressed(source: &[u8]) -> Result<Self, DeserializationError> {
+        let mut header = SliceReader::new(source);
+        if header.read_array::<4>()? != COMPRESSED_PROOF_MAGIC {
+            return Err(DeserializationError::InvalidValue(
+                "source is not a compressed proof".into(),
+            ));
+        }
+        let codec = header.read_u8()?;
+        if codec != DEFLATE_CODEC {
+            return Err(DeserializationError::InvalidValue(format!(
+                "proof compression codec {codec} is not supported"
+            )));
+        }
+        let num_bytes = usize::try_from(header.read_u64()?).map_err(|_| {
+            DeserializationError::InvalidValue("compressed proof is too large".into())
+        })?;
+        let compressed = &source[COMPRESSED_PROOF_HEADER_LEN..];
+
+        let bytes = miniz_oxide::inflate::decompress_to_vec_with_limit(compressed, num_bytes)
+            .map_err(|err| {
+                DeserializationError::InvalidValue(format!("failed to decompress proof: {err}"))
+            })?;
+        if bytes.len() != num_bytes {
+            return Err(DeserializationError::InvalidValue(format!(
+                "decompressed proof has {} bytes, but {num_bytes} bytes were expected",
+                bytes.len()
+            )));
+        }
+
+        let mut reader = SliceReader::new(&bytes);
+        let proof = Self::read_from(&mut reader)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(proof)
+    }
+
     /// Creates a dummy `Proof` for use in tests.
     pub fn new_dummy() -> Self {
         use crypto::{hashers::Blake3_192 as DummyHasher, BatchMerkleProof};
@@ -152,6 +580,7 @@ impl Proof {
                 ProofOptions::new(1, 2, 2, FieldExtension::None, 8, 1),
             ),
             num_unique_queries: 0,
+            num_main_trace_tables: 1,
             commitments: Commitments::default(),
             trace_queries: Vec::new(),
             constraint_queries: Queries::new::<_, DummyField>(
@@ -166,6 +595,7 @@ impl Proof {
             fri_proof: FriProof::new_dummy(),
             pow_nonce: 0,
             gkr_proof: None,
+            extensions: Vec::new(),
         }
     }
 }
@@ -175,47 +605,185 @@ impl Proof {
 
 impl Serializable for Proof {
     fn write_into<W: utils::ByteWriter>(&self, target: &mut W) {
+        target.write_u8(VERSIONED_PROOF_MARKER);
+        target.write_u8(PROOF_VERSION);
+        target.write_u8(self.feature_flags());
+
         self.context.write_into(target);
         target.write_u8(self.num_unique_queries);
+        if self.num_main_trace_tables > 1 {
+            target.write_u8(self.num_main_trace_tables);
+        }
         self.commitments.write_into(target);
         target.write_many(&self.trace_queries);
         self.constraint_queries.write_into(target);
         self.ood_frame.write_into(target);
         self.fri_proof.write_into(target);
         self.pow_nonce.write_into(target);
-        self.gkr_proof.write_into(target);
+        if let Some(gkr_proof) = &self.gkr_proof {
+            gkr_proof.write_into(target);
+        }
+        if !self.extensions.is_empty() {
+            self.extensions.write_into(target);
+        }
     }
 }
 
 impl Deserializable for Proof {
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
-        let context = Context::read_from(source)?;
-        let num_unique_queries = source.read_u8()?;
-        let commitments = Commitments::read_from(source)?;
-        let num_trace_segments = context.trace_info().num_segments();
-        let mut trace_queries = Vec::with_capacity(num_trace_segments);
-        for _ in 0..num_trace_segments {
-            trace_queries.push(Queries::read_from(source)?);
+        let (version, feature_flags) = read_header(source)?;
+
+        let context = source.read_field("context", Context::read_from)?;
+        let num_unique_queries = source.read_field("num_unique_queries", R::read_u8)?;
+        let num_main_trace_tables = source.read_field("num_main_trace_tables", |source| {
+            read_num_main_trace_tables(source, feature_flags)
+        })?;
+        let commitments = source.read_field("commitments", Commitments::read_from)?;
+        let num_trace_queries =
+            num_trace_queries(context.trace_info(), feature_flags, num_main_trace_tables);
+        let mut trace_queries = Vec::with_capacity(num_trace_queries);
+        for i in 0..num_trace_queries {
+            trace_queries.push(source.read_field("trace_queries", |source| {
+                source.read_indexed(i, Queries::read_from)
+            })?);
         }
 
+        let constraint_queries = source.read_field("constraint_queries", Queries::read_from)?;
+        let ood_frame = source.read_field("ood_frame", OodFrame::read_from)?;
+        let fri_proof = source.read_field("fri_proof", FriProof::read_from)?;
+        let pow_nonce = source.read_field("pow_nonce", R::read_u64)?;
+
+        // in the legacy format, the GKR proof is always serialized as an optional value
+        let gkr_proof = source.read_field("gkr_proof", |source| {
+            if version == 0 {
+                Option::<Vec<u8>>::read_from(source)
+            } else if feature_flags & GKR_PROOF_FLAG != 0 {
+                Vec::<u8>::read_from(source).map(Some)
+            } else {
+                Ok(None)
+            }
+        })?;
+        let extensions =
+            source.read_field("extensions", |source| read_extensions(source, feature_flags))?;
+
         let proof = Proof {
             context,
             num_unique_queries,
+            num_main_trace_tables,
             commitments,
             trace_queries,
-            constraint_queries: Queries::read_from(source)?,
-            ood_frame: OodFrame::read_from(source)?,
-            fri_proof: FriProof::read_from(source)?,
-            pow_nonce: source.read_u64()?,
-            gkr_proof: Option::<Vec<u8>>::read_from(source)?,
+            constraint_queries,
+            ood_frame,
+            fri_proof,
+            pow_nonce,
+            gkr_proof,
+            extensions,
         };
         Ok(proof)
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Proof {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        utils::serde_compat::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Proof {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        utils::serde_compat::deserialize(deserializer)
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Reads the header of a proof from the specified `source`, and returns the version of the
+/// serialization format and the feature flags of the proof.
+fn read_header<R: ByteReader>(source: &mut R) -> Result<(u8, u8), DeserializationError> {
+    // proofs serialized in the legacy format do not have a header; in such proofs, the first
+    // byte is the width of the main trace segment which is never zero.
+    if source.peek_u8()? != VERSIONED_PROOF_MARKER {
+        return Ok((0, 0));
+    }
+    source.read_u8()?;
+    let version = source.read_u8()?;
+    if version == 0 || version > PROOF_VERSION {
+        return Err(DeserializationError::InvalidValue(format!(
+            "proof version {version} is not supported; the latest supported version is {PROOF_VERSION}"
+        )));
+    }
+    Ok((version, read_feature_flags(source)?))
+}
+
+/// Reads proof feature flags from the specified `source` and makes sure that all of them are
+/// supported by this version of the deserializer.
+fn read_feature_flags<R: ByteReader>(source: &mut R) -> Result<u8, DeserializationError> {
+    let feature_flags = source.read_u8()?;
+    if feature_flags & !SUPPORTED_FEATURE_FLAGS != 0 {
+        return Err(DeserializationError::InvalidValue(format!(
+            "proof contains unsupported feature flags {feature_flags:#010b}"
+        )));
+    }
+    Ok(feature_flags)
+}
+
+/// Reads proof extensions from the specified `source` if the feature flags indicate that the
+/// proof contains them.
+fn read_extensions<R: ByteReader>(
+    source: &mut R,
+    feature_flags: u8,
+) -> Result<Vec<ProofExtension>, DeserializationError> {
+    if feature_flags & EXTENSIONS_FLAG == 0 {
+        return Ok(Vec::new());
+    }
+    let extensions = Vec::<ProofExtension>::read_from(source)?;
+    if extensions.is_empty() {
+        return Err(DeserializationError::InvalidValue(
+            "proof extensions are flagged as present, but the list of extensions is empty".into(),
+        ));
+    }
+    Ok(extensions)
+}
+
+/// Reads the number of main trace tables from the specified `source` if the feature flags
+/// indicate that the main trace segment is partitioned into multiple tables.
+fn read_num_main_trace_tables<R: ByteReader>(
+    source: &mut R,
+    feature_flags: u8,
+) -> Result<u8, DeserializationError> {
+    if feature_flags & MAIN_TRACE_TABLES_FLAG == 0 {
+        return Ok(1);
+    }
+    let num_main_trace_tables = source.read_u8()?;
+    if num_main_trace_tables < 2 {
+        return Err(DeserializationError::InvalidValue(format!(
+            "number of main trace tables must be at least 2, but was {num_main_trace_tables}"
+        )));
+    }
+    Ok(num_main_trace_tables)
+}
+
+/// Returns the number of trace query sets in a proof with the specified feature flags.
+///
+/// A proof contains a set of trace queries for every main trace table and for the auxiliary
+/// trace segment, and an additional set of queries against the preprocessed columns of the main
+/// trace segment if these are present.
+fn num_trace_queries(
+    trace_info: &TraceInfo,
+    feature_flags: u8,
+    num_main_trace_tables: u8,
+) -> usize {
+    let num_trace_segments = trace_info.num_segments() + num_main_trace_tables as usize - 1;
+    if feature_flags & PREPROCESSED_TRACE_FLAG != 0 {
+        num_trace_segments + 1
+    } else {
+        num_trace_segments
+    }
+}
+
 /// Computes conjectured security level for the specified proof parameters.
 fn get_conjectured_security(
     options: &ProofOptions,
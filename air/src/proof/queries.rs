@@ -5,7 +5,7 @@
 
 use alloc::vec::Vec;
 
-use crypto::{BatchMerkleProof, ElementHasher, Hasher};
+use crypto::{BatchMerkleProof, ElementHasher, Hasher, MerkleNodeDictionary};
 use math::FieldElement;
 use utils::{
     ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
@@ -78,6 +78,14 @@ impl Queries {
         Queries { paths, values }
     }
 
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a borrowed view of these queries.
+    pub fn as_borrowed(&self) -> QueriesRef<'_> {
+        QueriesRef { paths: &self.paths, values: &self.values }
+    }
+
     // PARSER
     // --------------------------------------------------------------------------------------------
     /// Convert internally stored bytes into a set of query values and the corresponding Merkle
@@ -94,6 +102,131 @@ impl Queries {
         num_queries: usize,
         values_per_query: usize,
     ) -> Result<(BatchMerkleProof<H>, Table<E>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        self.as_borrowed().parse(domain_size, num_queries, values_per_query)
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Queries {
+    /// Returns queries which contain no values and no authentication paths; these are used in
+    /// place of the queries detached from a proof.
+    pub(crate) fn new_empty() -> Self {
+        Self { paths: Vec::new(), values: Vec::new() }
+    }
+}
+
+impl Serializable for Queries {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        // write value bytes
+        target.write_u32(self.values.len() as u32);
+        target.write_bytes(&self.values);
+
+        // write path bytes
+        target.write_u32(self.paths.len() as u32);
+        target.write_bytes(&self.paths);
+    }
+
+    /// Returns an estimate of how many bytes are needed to represent self.
+    fn get_size_hint(&self) -> usize {
+        self.paths.len() + self.values.len() + 8
+    }
+}
+
+impl Deserializable for Queries {
+    /// Reads a query struct from the specified `source` and returns the result
+    ///
+    /// # Errors
+    /// Returns an error of a valid query struct could not be read from the specified source.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        // read values
+        let values = source.read_field("values", |source| {
+            let num_value_bytes = source.read_u32()?;
+            source.read_vec(num_value_bytes as usize)
+        })?;
+
+        // read paths
+        let paths = source.read_field("paths", |source| {
+            let num_paths_bytes = source.read_u32()?;
+            source.read_vec(num_paths_bytes as usize)
+        })?;
+
+        Ok(Queries { paths, values })
+    }
+}
+
+impl Queries {
+    /// Writes these queries into the `target` replacing Merkle tree nodes already present in the
+    /// `nodes` dictionary with references to them.
+    pub(super) fn write_compressed<H: Hasher, W: ByteWriter>(
+        &self,
+        target: &mut W,
+        nodes: &mut MerkleNodeDictionary<H>,
+    ) -> Result<(), DeserializationError> {
+        target.write_usize(self.values.len());
+        target.write_bytes(&self.values);
+        nodes.write_nodes(&self.paths, target)
+    }
+
+    /// Reads queries previously written via [write_compressed()](Self::write_compressed) from the
+    /// `source`.
+    pub(super) fn read_compressed<H: Hasher, R: ByteReader>(
+        source: &mut R,
+        nodes: &mut MerkleNodeDictionary<H>,
+    ) -> Result<Self, DeserializationError> {
+        let num_value_bytes = source.read_usize()?;
+        let values = source.read_vec(num_value_bytes)?;
+        let paths = nodes.read_nodes(source)?;
+
+        Ok(Queries { paths, values })
+    }
+}
+
+// BORROWED QUERIES
+// ================================================================================================
+
+/// A borrowed view of [Queries].
+///
+/// The view references query values and Merkle paths in a buffer holding a serialized proof (or
+/// in owned [Queries] via [Queries::as_borrowed()]), and thus, it can be read from a buffer via
+/// [read_from()](QueriesRef::read_from) without copying any of these bytes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct QueriesRef<'a> {
+    paths: &'a [u8],
+    values: &'a [u8],
+}
+
+impl<'a> QueriesRef<'a> {
+    /// Returns an owned copy of these queries.
+    pub fn to_owned_queries(&self) -> Queries {
+        Queries {
+            paths: self.paths.to_vec(),
+            values: self.values.to_vec(),
+        }
+    }
+
+    // PARSER
+    // --------------------------------------------------------------------------------------------
+    /// Convert internally stored bytes into a set of query values and the corresponding Merkle
+    /// authentication paths.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is not a power of two.
+    /// * `num_queries` is zero.
+    /// * `values_per_query` is zero.
+    pub fn parse<H, E>(
+        &self,
+        domain_size: usize,
+        num_queries: usize,
+        values_per_query: usize,
+    ) -> Result<(BatchMerkleProof<H>, Table<E>), DeserializationError>
     where
         E: FieldElement,
         H: ElementHasher<BaseField = E::BaseField>,
@@ -115,11 +248,11 @@ impl Queries {
 
         // read bytes corresponding to each query, convert them into field elements,
         // and also hash them to build leaf nodes of the batch Merkle proof
-        let query_values = Table::<E>::from_bytes(&self.values, num_queries, values_per_query)?;
+        let query_values = Table::<E>::from_bytes(self.values, num_queries, values_per_query)?;
         let hashed_queries = query_values.rows().map(|row| H::hash_elements(row)).collect();
 
         // build batch Merkle proof
-        let mut reader = SliceReader::new(&self.paths);
+        let mut reader = SliceReader::new(self.paths);
         let tree_depth = domain_size.ilog2() as u8;
         let merkle_proof = BatchMerkleProof::deserialize(&mut reader, hashed_queries, tree_depth)?;
         if reader.has_more_bytes() {
@@ -128,43 +261,28 @@ impl Queries {
 
         Ok((merkle_proof, query_values))
     }
-}
-
-// SERIALIZATION
-// ================================================================================================
-
-impl Serializable for Queries {
-    /// Serializes `self` and writes the resulting bytes into the `target`.
-    fn write_into<W: ByteWriter>(&self, target: &mut W) {
-        // write value bytes
-        target.write_u32(self.values.len() as u32);
-        target.write_bytes(&self.values);
 
-        // write path bytes
-        target.write_u32(self.paths.len() as u32);
-        target.write_bytes(&self.paths);
-    }
-
-    /// Returns an estimate of how many bytes are needed to represent self.
-    fn get_size_hint(&self) -> usize {
-        self.paths.len() + self.values.len() + 8
-    }
-}
+    // DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
 
-impl Deserializable for Queries {
-    /// Reads a query struct from the specified `source` and returns the result
+    /// Reads a view of queries serialized via [Queries::to_bytes()](Serializable::to_bytes) from
+    /// the specified `source`; the view borrows the bytes of the source.
     ///
     /// # Errors
     /// Returns an error of a valid query struct could not be read from the specified source.
-    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+    pub fn read_from(source: &mut SliceReader<'a>) -> Result<Self, DeserializationError> {
         // read values
-        let num_value_bytes = source.read_u32()?;
-        let values = source.read_vec(num_value_bytes as usize)?;
+        let values = source.read_field("values", |source| {
+            let num_value_bytes = source.read_u32()?;
+            source.read_borrowed_slice(num_value_bytes as usize)
+        })?;
 
         // read paths
-        let num_paths_bytes = source.read_u32()?;
-        let paths = source.read_vec(num_paths_bytes as usize)?;
+        let paths = source.read_field("paths", |source| {
+            let num_paths_bytes = source.read_u32()?;
+            source.read_borrowed_slice(num_paths_bytes as usize)
+        })?;
 
-        Ok(Queries { paths, values })
+        Ok(QueriesRef { paths, values })
     }
 }
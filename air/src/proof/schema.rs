@@ -0,0 +1,425 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crypto::Hasher;
+use math::StarkField;
+use utils::Serializable;
+
+use super::{
+    EXTENSIONS_FLAG, GKR_PROOF_FLAG, MAIN_TRACE_TABLES_FLAG, PREPROCESSED_TRACE_FLAG,
+    PROOF_VERSION, VERSIONED_PROOF_MARKER,
+};
+use crate::{ProofOptions, TraceInfo};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Version of the schema document produced by [proof_schema()].
+///
+/// The version is incremented whenever the structure of the document changes; changes to the
+/// proof serialization format itself are reflected by the `proof_version` entry of the document.
+pub const PROOF_SCHEMA_VERSION: u32 = 1;
+
+// PROOF SCHEMA
+// ================================================================================================
+
+/// Returns a machine-readable description of the layout of proofs serialized via
+/// [Proof::to_bytes()](super::Proof::to_bytes) for a computation with the specified trace info
+/// and proof options, using hash function `H` over base field `B`.
+///
+/// The description is a JSON document which lists every part of a serialized proof in the order
+/// in which the parts appear in the serialized form. Each part is described by:
+/// * `name` - name of the part.
+/// * `type` - either a primitive type (`u8`, `u16`, `u32`, `u64` - all little-endian, `vint64` -
+///   a variable-length integer, or `bytes`), or `group` for parts consisting of other parts.
+/// * `size` - number of bytes in the part, or `null` if the size depends on the proof (e.g., on
+///   the number of unique queries).
+/// * `offset` - offset of the part from the start of the proof, or `null` if it depends on the
+///   proof.
+/// * `value` - for parts with a fixed value, the value.
+/// * `condition` - for parts which are present only in some proofs, the condition under which the
+///   part is present, expressed in terms of the `feature_flags` part.
+/// * `content` - for `bytes` parts, a description of what the bytes encode (e.g., field elements
+///   or digests); for repeated `group` parts, the part which holds the number of repetitions.
+/// * `fields` - for `group` parts, the parts of the group.
+///
+/// Sizes which depend on the AIR of the computation rather than on the proof options (e.g., the
+/// number of constraint composition columns) are described in terms of named quantities. The
+/// additional trace queries included in proofs with multiple main trace tables or with a
+/// preprocessed trace are not described (beyond the corresponding feature flags), nor are proofs
+/// serialized using the legacy format.
+pub fn proof_schema<H: Hasher, B: StarkField>(
+    trace_info: &TraceInfo,
+    options: &ProofOptions,
+) -> String {
+    let extension_degree = options.field_extension().degree() as usize;
+    let base_element_bytes = B::ELEMENT_BYTES;
+    let element_bytes = base_element_bytes * extension_degree;
+    let digest_bytes = H::Digest::default().serialized_size();
+    let modulus_bytes = B::get_modulus_le_bytes().len();
+
+    let lde_domain_size = trace_info.length() * options.blowup_factor();
+    let folding_factors = options.to_fri_options().folding_factors(lde_domain_size);
+    let remainder_domain_size = lde_domain_size / folding_factors.iter().product::<usize>();
+    let num_remainder_coefficients = remainder_domain_size / options.blowup_factor();
+    let num_commitments = trace_info.num_segments() + 1 + folding_factors.len() + 1;
+
+    let mut fields = vec![
+        Entry::new("marker", "u8", Some(1)).value(VERSIONED_PROOF_MARKER as u64),
+        Entry::new("version", "u8", Some(1)).value(PROOF_VERSION as u64),
+        Entry::new("feature_flags", "u8", Some(1)).content(&format!(
+            "{{\"gkr_proof\":{GKR_PROOF_FLAG},\"preprocessed_trace\":{PREPROCESSED_TRACE_FLAG},\
+             \"extensions\":{EXTENSIONS_FLAG},\"main_trace_tables\":{MAIN_TRACE_TABLES_FLAG}}}"
+        )),
+        context_entry(trace_info, options, modulus_bytes),
+        Entry::new("num_unique_queries", "u8", Some(1)),
+        Entry::new("num_main_trace_tables", "u8", Some(1))
+            .condition(&format!("feature_flags & {MAIN_TRACE_TABLES_FLAG} != 0")),
+        length_prefixed(
+            "commitments",
+            "u16",
+            Some(num_commitments * digest_bytes),
+            &digests_content(num_commitments, digest_bytes),
+        ),
+    ];
+
+    // trace queries; the main segment contains base field elements, while the auxiliary segment
+    // contains extension field elements
+    let mut segment_widths = vec![(trace_info.main_trace_width(), base_element_bytes)];
+    if trace_info.is_multi_segment() {
+        segment_widths.push((trace_info.aux_segment_width(), element_bytes));
+    }
+    let trace_queries = segment_widths
+        .into_iter()
+        .map(|(width, element_bytes)| {
+            queries_entry("segment", &row_elements_content(width, element_bytes), digest_bytes)
+        })
+        .collect();
+    fields.push(Entry::group("trace_queries", trace_queries));
+
+    fields.push(queries_entry(
+        "constraint_queries",
+        &row_elements_content_named("num_constraint_composition_columns", element_bytes),
+        digest_bytes,
+    ));
+    fields.push(Entry::group(
+        "ood_frame",
+        vec![
+            length_prefixed("trace_states", "u16", None, &elements_content(element_bytes)),
+            length_prefixed(
+                "lagrange_kernel_trace_states",
+                "u16",
+                None,
+                &elements_content(element_bytes),
+            ),
+            length_prefixed("evaluations", "u16", None, &elements_content(element_bytes)),
+        ],
+    ));
+
+    // FRI layers and remainder
+    let mut fri_fields =
+        vec![Entry::new("num_layers", "u8", Some(1)).value(folding_factors.len() as u64)];
+    let mut domain_size = lde_domain_size;
+    for (i, &folding_factor) in folding_factors.iter().enumerate() {
+        let content = format!(
+            "{{\"kind\":\"field_elements\",\"element_size\":{element_bytes},\
+             \"elements_per_query\":{folding_factor},\"domain_size\":{domain_size}}}"
+        );
+        fri_fields.push(queries_entry(&format!("layer_{i}"), &content, digest_bytes));
+        domain_size /= folding_factor;
+    }
+    fri_fields.push(length_prefixed(
+        "remainder",
+        "u16",
+        Some(num_remainder_coefficients * element_bytes),
+        &format!(
+            "{{\"kind\":\"polynomial_coefficients\",\"element_size\":{element_bytes},\
+             \"count\":{num_remainder_coefficients}}}"
+        ),
+    ));
+    fri_fields.push(Entry::new("num_partitions", "u8", Some(1)));
+    fields.push(Entry::group("fri_proof", fri_fields));
+
+    fields.push(Entry::new("pow_nonce", "u64", Some(8)));
+    fields.push(
+        length_prefixed("gkr_proof", "vint64", None, "{\"kind\":\"opaque\"}")
+            .condition(&format!("feature_flags & {GKR_PROOF_FLAG} != 0")),
+    );
+    fields.push(
+        Entry::group(
+            "extensions",
+            vec![
+                Entry::new("num_extensions", "vint64", None),
+                Entry::group(
+                    "extension",
+                    vec![
+                        Entry::new("id", "u16", Some(2)),
+                        length_prefixed("data", "vint64", None, "{\"kind\":\"opaque\"}"),
+                    ],
+                )
+                .content("{\"repeated\":\"num_extensions\"}"),
+            ],
+        )
+        .condition(&format!("feature_flags & {EXTENSIONS_FLAG} != 0")),
+    );
+
+    let mut result = String::new();
+    write!(
+        result,
+        "{{\"schema_version\":{PROOF_SCHEMA_VERSION},\"proof_version\":{PROOF_VERSION},\
+         \"endianness\":\"little\",\"base_field_element_size\":{base_element_bytes},\
+         \"extension_degree\":{extension_degree},\"digest_size\":{digest_bytes},\
+         \"lde_domain_size\":{lde_domain_size},\"num_queries\":{},\"fri_folding_factors\":{:?},\
+         \"fields\":",
+        options.num_queries(),
+        folding_factors,
+    )
+    .expect("writing into a string cannot fail");
+    let mut offset = Some(0);
+    write_entries(&mut result, &fields, &mut offset);
+    result.push('}');
+    result
+}
+
+// SCHEMA ENTRIES
+// ================================================================================================
+
+/// Describes a single part of a serialized proof.
+struct Entry {
+    name: String,
+    ty: &'static str,
+    size: Option<usize>,
+    value: Option<u64>,
+    condition: Option<String>,
+    content: Option<String>,
+    fields: Vec<Entry>,
+}
+
+impl Entry {
+    fn new(name: &str, ty: &'static str, size: Option<usize>) -> Self {
+        Self {
+            name: name.into(),
+            ty,
+            size,
+            value: None,
+            condition: None,
+            content: None,
+            fields: Vec::new(),
+        }
+    }
+
+    fn group(name: &str, fields: Vec<Entry>) -> Self {
+        Self { fields, ..Self::new(name, "group", None) }
+    }
+
+    fn value(mut self, value: u64) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    fn condition(mut self, condition: &str) -> Self {
+        self.condition = Some(condition.into());
+        self
+    }
+
+    /// Sets the content description of this entry; `content` must be a JSON value.
+    fn content(mut self, content: &str) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+}
+
+/// Returns an entry for a sequence of bytes preceded by its length.
+fn length_prefixed(name: &str, prefix: &'static str, len: Option<usize>, content: &str) -> Entry {
+    let prefix_size = match prefix {
+        "u16" => Some(2),
+        "u32" => Some(4),
+        _ => None,
+    };
+    let length = match len {
+        Some(len) => Entry::new("length", prefix, prefix_size).value(len as u64),
+        None => Entry::new("length", prefix, prefix_size),
+    };
+    Entry::group(name, vec![length, Entry::new("data", "bytes", len).content(content)])
+}
+
+/// Returns an entry for query values and the batch Merkle proof authenticating them.
+fn queries_entry(name: &str, values_content: &str, digest_bytes: usize) -> Entry {
+    let paths_content = format!(
+        "{{\"kind\":\"batch_merkle_proof_nodes\",\"digest_size\":{digest_bytes},\
+         \"layout\":\"u8 number of paths, then for each path: u8 number of nodes followed by the \
+         nodes\"}}"
+    );
+    Entry::group(
+        name,
+        vec![
+            length_prefixed("values", "u32", None, values_content),
+            length_prefixed("paths", "u32", None, &paths_content),
+        ],
+    )
+}
+
+fn digests_content(count: usize, digest_bytes: usize) -> String {
+    format!("{{\"kind\":\"digests\",\"digest_size\":{digest_bytes},\"count\":{count}}}")
+}
+
+fn elements_content(element_bytes: usize) -> String {
+    format!("{{\"kind\":\"field_elements\",\"element_size\":{element_bytes}}}")
+}
+
+fn row_elements_content(width: usize, element_bytes: usize) -> String {
+    format!(
+        "{{\"kind\":\"field_elements\",\"element_size\":{element_bytes},\
+         \"elements_per_query\":{width}}}"
+    )
+}
+
+fn row_elements_content_named(width: &str, element_bytes: usize) -> String {
+    format!(
+        "{{\"kind\":\"field_elements\",\"element_size\":{element_bytes},\
+         \"elements_per_query\":\"{width}\"}}"
+    )
+}
+
+fn context_entry(trace_info: &TraceInfo, options: &ProofOptions, modulus_bytes: usize) -> Entry {
+    let meta_len = trace_info.meta().len();
+    let trace_info_entry = Entry::group(
+        "trace_info",
+        vec![
+            Entry::new("main_segment_width", "u8", Some(1))
+                .value(trace_info.main_trace_width() as u64),
+            Entry::new("aux_segment_width", "u8", Some(1))
+                .value(trace_info.aux_segment_width() as u64),
+            Entry::new("num_aux_segment_rands", "u8", Some(1)),
+            Entry::new("log2_trace_length", "u8", Some(1))
+                .value(trace_info.length().ilog2() as u64),
+            length_prefixed("meta", "u16", Some(meta_len), "{\"kind\":\"opaque\"}"),
+        ],
+    );
+
+    let mut options_fields = vec![
+        Entry::new("num_queries", "u8", Some(1)).value(options.num_queries() as u64),
+        Entry::new("blowup_factor", "u8", Some(1)).value(options.blowup_factor() as u64),
+        Entry::new("grinding_factor", "u8", Some(1)).value(options.grinding_factor() as u64),
+        Entry::new("field_extension_and_flags", "u8", Some(1)),
+        Entry::new("fri_folding_factor", "u8", Some(1)),
+        Entry::new("fri_remainder_max_degree", "u8", Some(1)),
+    ];
+    if let Some((commitment_hash_fn, random_coin_hash_fn)) = options.hash_functions() {
+        options_fields.push(
+            Entry::new("commitment_hash_function", "u8", Some(1)).value(commitment_hash_fn as u64),
+        );
+        options_fields.push(
+            Entry::new("random_coin_hash_function", "u8", Some(1))
+                .value(random_coin_hash_fn as u64),
+        );
+    }
+    let schedule = options.fri_folding_schedule();
+    if !schedule.is_empty() {
+        options_fields.push(Entry::group(
+            "fri_folding_schedule",
+            vec![
+                Entry::new("length", "u8", Some(1)).value(schedule.len() as u64),
+                Entry::new("data", "bytes", Some(schedule.len())),
+            ],
+        ));
+    }
+    if let Some(num_digest_bytes) = options.digest_truncation() {
+        options_fields
+            .push(Entry::new("digest_truncation", "u8", Some(1)).value(num_digest_bytes as u64));
+    }
+    if options.recorded_parameters_digest().is_some() {
+        options_fields.push(Entry::new("parameters_digest", "bytes", Some(32)));
+    }
+
+    Entry::group(
+        "context",
+        vec![
+            trace_info_entry,
+            Entry::group(
+                "field_modulus",
+                vec![
+                    Entry::new("length", "u8", Some(1)).value(modulus_bytes as u64),
+                    Entry::new("data", "bytes", Some(modulus_bytes))
+                        .content("{\"kind\":\"integer\",\"endianness\":\"little\"}"),
+                ],
+            ),
+            Entry::group("options", options_fields),
+        ],
+    )
+}
+
+// JSON SERIALIZATION
+// ================================================================================================
+
+/// Writes the specified entries as a JSON array, and advances the `offset` past the entries; the
+/// offset becomes unknown once an entry of unknown size has been written.
+fn write_entries(target: &mut String, entries: &[Entry], offset: &mut Option<usize>) {
+    target.push('[');
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            target.push(',');
+        }
+        write_entry(target, entry, offset);
+    }
+    target.push(']');
+}
+
+fn write_entry(target: &mut String, entry: &Entry, offset: &mut Option<usize>) {
+    // the offsets of a conditional entry, and of all entries following it, depend on whether the
+    // entry is present
+    let start = if entry.condition.is_some() { None } else { *offset };
+    let size = entry_size(entry);
+    *offset = start.zip(size).map(|(start, size)| start + size);
+
+    write!(
+        target,
+        "{{\"name\":\"{}\",\"type\":\"{}\",\"size\":{},\"offset\":{}",
+        entry.name,
+        entry.ty,
+        json_option(size),
+        json_option(start)
+    )
+    .expect("writing into a string cannot fail");
+    if let Some(value) = entry.value {
+        write!(target, ",\"value\":{value}").expect("writing into a string cannot fail");
+    }
+    if let Some(condition) = &entry.condition {
+        write!(target, ",\"condition\":\"{condition}\"")
+            .expect("writing into a string cannot fail");
+    }
+    if let Some(content) = &entry.content {
+        write!(target, ",\"content\":{content}").expect("writing into a string cannot fail");
+    }
+    if !entry.fields.is_empty() {
+        target.push_str(",\"fields\":");
+        let mut field_offset = start;
+        write_entries(target, &entry.fields, &mut field_offset);
+    }
+    target.push('}');
+}
+
+/// Returns the size of the specified entry, or `None` if the size depends on the proof.
+fn entry_size(entry: &Entry) -> Option<usize> {
+    if entry.fields.is_empty() {
+        return entry.size;
+    }
+    entry.fields.iter().try_fold(0, |size, field| {
+        if field.condition.is_some() {
+            return None;
+        }
+        Some(size + entry_size(field)?)
+    })
+}
+
+fn json_option(value: Option<usize>) -> String {
+    match value {
+        Some(value) => format!("{value}"),
+        None => "null".into(),
+    }
+}
@@ -57,12 +57,16 @@ impl<E: FieldElement> Table<E> {
             "number of columns cannot exceed {MAX_COLS}, but was {num_cols}"
         );
 
+        // read the elements directly into the spare capacity of the vector to avoid initializing
+        // the (potentially large) buffer twice
         let mut reader = SliceReader::new(bytes);
         let num_elements = num_rows * num_cols;
-        Ok(Self {
-            data: reader.read_many(num_elements)?,
-            row_width: num_cols,
-        })
+        let mut data = Vec::with_capacity(num_elements);
+        reader.read_many_into(&mut data.spare_capacity_mut()[..num_elements])?;
+        // SAFETY: all `num_elements` elements have been initialized by the read above
+        unsafe { data.set_len(num_elements) };
+
+        Ok(Self { data, row_width: num_cols })
     }
 
     // PUBLIC ACCESSORS
@@ -85,27 +89,51 @@ impl<E: FieldElement> Table<E> {
     }
 
     /// Returns an iterator over rows of this table.
-    pub fn rows(&self) -> RowIterator<E> {
+    pub fn rows(&self) -> RowIterator<'_, E> {
         RowIterator::new(self)
     }
 
     // TABLE PROCESSING
     // --------------------------------------------------------------------------------------------
 
+    /// Returns a table containing the rows of this table at the specified indexes, in the order in
+    /// which the indexes are specified.
+    ///
+    /// # Panics
+    /// Panics if any of the indexes is greater than or equal to the number of rows in this table.
+    pub fn select_rows(&self, row_indexes: &[usize]) -> Table<E> {
+        let mut data = Vec::with_capacity(row_indexes.len() * self.row_width);
+        for &row_idx in row_indexes {
+            data.extend_from_slice(self.get_row(row_idx));
+        }
+        Table { data, row_width: self.row_width }
+    }
+
     /// Combines multiple tables together into a single table by stacking tables column-wise (e.g.
     /// the number of rows remains the same but the number of columns changes).
     ///
-    /// Currently, this method does not support inputs containing more than one table.
-    ///
     /// # Panics
-    /// Panics if the list of tables is empty.
+    /// Panics if the list of tables is empty or if the tables have different numbers of rows.
     pub fn merge(mut tables: Vec<Table<E>>) -> Table<E> {
         assert!(!tables.is_empty(), "cannot merge an empty set of tables");
         if tables.len() == 1 {
-            tables.remove(0)
-        } else {
-            unimplemented!("merging of multiple tables is not yet implemented")
+            return tables.remove(0);
+        }
+
+        let num_rows = tables[0].num_rows();
+        for table in tables.iter() {
+            assert_eq!(num_rows, table.num_rows(), "all tables must have the same number of rows");
         }
+
+        let row_width = tables.iter().map(|table| table.row_width).sum();
+        let mut data = Vec::with_capacity(num_rows * row_width);
+        for row_idx in 0..num_rows {
+            for table in tables.iter() {
+                data.extend_from_slice(table.get_row(row_idx));
+            }
+        }
+
+        Self { data, row_width }
     }
 }
 
@@ -3,9 +3,397 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::Proof;
+use alloc::vec::Vec;
+
+use crypto::{hashers::Blake3_192, HashFunctionId};
+use math::{fields::f64::BaseElement, StarkField};
+use utils::{ByteWriter, Deserializable, DeserializationError, Serializable};
+
+use super::{
+    Context, DetachedProof, DetachedSection, LazyProof, Proof, ProofExtension, ProofRef,
+    ProofSection, VerificationCost, PROOF_VERSION,
+};
+use crate::{FieldExtension, ProofOptions, TraceInfo};
 
 #[test]
 pub fn starkproof_new_dummy_doesnt_panic() {
     let _ = Proof::new_dummy();
 }
+
+#[test]
+fn proof_serialization_round_trip() {
+    let proof = build_proof(None);
+    let bytes = proof.to_bytes();
+    assert_eq!(&bytes[..3], &[0, PROOF_VERSION, 0]);
+    assert_eq!(proof, Proof::from_bytes(&bytes).unwrap());
+
+    let proof = build_proof(Some(vec![1, 2, 3]));
+    let bytes = proof.to_bytes();
+    assert_eq!(&bytes[..3], &[0, PROOF_VERSION, 1]);
+    assert_eq!(proof, Proof::from_bytes(&bytes).unwrap());
+}
+
+#[test]
+fn proof_serialization_with_preprocessed_trace_queries() {
+    let mut proof = build_proof(None);
+    proof.trace_queries.push(proof.constraint_queries.clone());
+    let bytes = proof.to_bytes();
+    assert_eq!(&bytes[..3], &[0, PROOF_VERSION, 0b10]);
+    assert_eq!(proof, Proof::from_bytes(&bytes).unwrap());
+
+    let compressed = proof.compress::<Blake3_192<BaseElement>>().unwrap();
+    let decompressed =
+        Proof::decompress::<Blake3_192<BaseElement>, BaseElement>(&compressed).unwrap();
+    assert_eq!(proof, decompressed);
+}
+
+#[test]
+fn proof_serialization_with_extensions() {
+    let mut proof = build_proof(Some(vec![1, 2, 3]));
+    proof.extensions.push(ProofExtension::new(7, vec![4, 5, 6]));
+    proof.extensions.push(ProofExtension::new(3, Vec::new()));
+    let bytes = proof.to_bytes();
+    assert_eq!(&bytes[..3], &[0, PROOF_VERSION, 0b101]);
+    assert_eq!(proof, Proof::from_bytes(&bytes).unwrap());
+    assert_eq!(Some(&[4u8, 5, 6][..]), proof.get_extension(7).map(|e| e.data()));
+    assert!(proof.get_extension(4).is_none());
+
+    let compressed = proof.compress::<Blake3_192<BaseElement>>().unwrap();
+    let decompressed =
+        Proof::decompress::<Blake3_192<BaseElement>, BaseElement>(&compressed).unwrap();
+    assert_eq!(proof, decompressed);
+}
+
+#[test]
+fn proof_serialization_with_main_trace_tables() {
+    let mut proof = build_proof(None);
+    proof.num_main_trace_tables = 2;
+    proof.trace_queries.push(proof.constraint_queries.clone());
+    let bytes = proof.to_bytes();
+    assert_eq!(&bytes[..3], &[0, PROOF_VERSION, 0b1000]);
+    assert_eq!(proof, Proof::from_bytes(&bytes).unwrap());
+
+    let compressed = proof.compress::<Blake3_192<BaseElement>>().unwrap();
+    let decompressed =
+        Proof::decompress::<Blake3_192<BaseElement>, BaseElement>(&compressed).unwrap();
+    assert_eq!(proof, decompressed);
+}
+
+#[test]
+fn proof_deserialization_legacy_format() {
+    for gkr_proof in [None, Some(vec![1, 2, 3])] {
+        let proof = build_proof(gkr_proof);
+        let bytes = write_legacy_proof(&proof);
+        assert_eq!(proof, Proof::from_bytes(&bytes).unwrap());
+    }
+}
+
+#[test]
+fn proof_deserialization_unsupported_header() {
+    let mut bytes = build_proof(None).to_bytes();
+
+    // future versions of the format are rejected
+    bytes[1] = PROOF_VERSION + 1;
+    assert!(matches!(Proof::from_bytes(&bytes), Err(DeserializationError::InvalidValue(_))));
+
+    // unknown feature flags are rejected
+    bytes[1] = PROOF_VERSION;
+    bytes[2] = 0b1000_0000;
+    assert!(matches!(Proof::from_bytes(&bytes), Err(DeserializationError::InvalidValue(_))));
+}
+
+#[test]
+fn proof_deserialization_error_context() {
+    let bytes = build_proof(None).to_bytes();
+    let truncated = &bytes[..bytes.len() - 1];
+
+    // the error identifies the field which could not be read and where it starts
+    let err = Proof::from_bytes(truncated).unwrap_err();
+    assert_eq!(&DeserializationError::UnexpectedEOF, err.root_cause());
+    assert_eq!(Some("pow_nonce"), err.path());
+    assert_eq!(Some(bytes.len() - 8), err.offset());
+    assert_eq!(err, ProofRef::read_from_slice(truncated).unwrap_err());
+
+    // errors in nested values are reported with the full path
+    let err = Proof::from_bytes(&bytes[..12]).unwrap_err();
+    assert!(err.path().unwrap().starts_with("context"), "{err}");
+}
+
+#[test]
+fn proof_compression_round_trip() {
+    for gkr_proof in [None, Some(vec![1, 2, 3])] {
+        let proof = build_proof(gkr_proof);
+        let compressed = proof.compress::<Blake3_192<BaseElement>>().unwrap();
+        assert!(compressed.len() < proof.to_bytes().len());
+
+        let decompressed =
+            Proof::decompress::<Blake3_192<BaseElement>, BaseElement>(&compressed).unwrap();
+        assert_eq!(proof, decompressed);
+    }
+}
+
+#[test]
+fn proof_borrowed_deserialization() {
+    let mut proof_with_extensions = build_proof(Some(vec![1, 2, 3]));
+    proof_with_extensions.extensions.push(ProofExtension::new(7, vec![4, 5, 6]));
+
+    for proof in [build_proof(None), build_proof(Some(vec![1, 2, 3])), proof_with_extensions] {
+        let bytes = proof.to_bytes();
+        let proof_ref = ProofRef::read_from_slice(&bytes).unwrap();
+        assert_eq!(proof.as_borrowed(), proof_ref);
+        assert_eq!(proof, proof_ref.to_owned_proof());
+    }
+
+    // proofs serialized in the legacy format can be read as well
+    for gkr_proof in [None, Some(vec![1, 2, 3])] {
+        let proof = build_proof(gkr_proof);
+        let bytes = write_legacy_proof(&proof);
+        assert_eq!(proof.as_borrowed(), ProofRef::read_from_slice(&bytes).unwrap());
+    }
+
+    // trailing bytes are rejected
+    let mut bytes = build_proof(None).to_bytes();
+    bytes.push(0);
+    assert_eq!(Err(DeserializationError::UnconsumedBytes), ProofRef::read_from_slice(&bytes));
+}
+
+#[test]
+fn proof_lazy_deserialization() {
+    let mut proof_with_extensions = build_proof(Some(vec![1, 2, 3]));
+    proof_with_extensions.extensions.push(ProofExtension::new(7, vec![4, 5, 6]));
+
+    for proof in [build_proof(None), proof_with_extensions] {
+        let bytes = proof.to_bytes();
+        let lazy_proof = LazyProof::read_from_slice(&bytes).unwrap();
+        assert_eq!(&proof.context, &lazy_proof.context);
+        assert_eq!(proof.num_unique_queries, lazy_proof.num_unique_queries);
+        assert_eq!(&proof.commitments, &lazy_proof.commitments);
+        assert_eq!(proof.as_borrowed(), lazy_proof.read_sections().unwrap());
+        assert_eq!(proof, lazy_proof.to_owned_proof().unwrap());
+    }
+
+    // proofs serialized in the legacy format can be read as well
+    let proof = build_proof(Some(vec![1, 2, 3]));
+    let bytes = write_legacy_proof(&proof);
+    let lazy_proof = LazyProof::read_from_slice(&bytes).unwrap();
+    assert_eq!(proof.as_borrowed(), lazy_proof.read_sections().unwrap());
+
+    // malformed sections are detected only when the sections are read
+    let bytes = build_proof(None).to_bytes();
+    let lazy_proof = LazyProof::read_from_slice(&bytes[..bytes.len() - 1]).unwrap();
+    assert!(lazy_proof.read_sections().is_err());
+
+    let mut bytes = build_proof(None).to_bytes();
+    bytes.push(0);
+    let lazy_proof = LazyProof::read_from_slice(&bytes).unwrap();
+    assert_eq!(Err(DeserializationError::UnconsumedBytes), lazy_proof.read_sections());
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn proof_general_purpose_compression_round_trip() {
+    for gkr_proof in [None, Some(vec![1, 2, 3])] {
+        let proof = build_proof(gkr_proof);
+        let compressed = proof.to_bytes_compressed();
+        assert_eq!(b"WFPZ", &compressed[..4]);
+        assert_eq!(proof, Proof::from_bytes_compressed(&compressed).unwrap());
+    }
+
+    let compressed = build_proof(None).to_bytes_compressed();
+
+    // unknown magic bytes and codecs are rejected
+    let mut bytes = compressed.clone();
+    bytes[0] = b'X';
+    assert!(matches!(
+        Proof::from_bytes_compressed(&bytes),
+        Err(DeserializationError::InvalidValue(_))
+    ));
+    let mut bytes = compressed.clone();
+    bytes[4] = 2;
+    assert!(matches!(
+        Proof::from_bytes_compressed(&bytes),
+        Err(DeserializationError::InvalidValue(_))
+    ));
+
+    // the uncompressed length must match the header
+    for delta in [-1i64, 1] {
+        let mut bytes = compressed.clone();
+        let num_bytes = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
+        bytes[5..13].copy_from_slice(&((num_bytes as i64 + delta) as u64).to_le_bytes());
+        assert!(Proof::from_bytes_compressed(&bytes).is_err());
+    }
+
+    // truncated inputs are rejected
+    assert!(Proof::from_bytes_compressed(&compressed[..compressed.len() - 1]).is_err());
+    assert_eq!(
+        Err(DeserializationError::UnexpectedEOF),
+        Proof::from_bytes_compressed(&compressed[..8])
+    );
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn proof_text_encoding_round_trip() {
+    let proof = build_proof(Some(vec![1, 2, 3]));
+    assert_eq!(proof, Proof::from_hex(&proof.to_hex()).unwrap());
+    assert_eq!(proof, Proof::from_base64(&proof.to_base64()).unwrap());
+
+    // proofs in the legacy format do not have a canonical text encoding
+    let legacy_hex = utils::encoding::encode_hex(&write_legacy_proof(&proof));
+    assert!(matches!(
+        Proof::from_hex(&legacy_hex),
+        Err(DeserializationError::InvalidValue(_))
+    ));
+}
+
+#[test]
+fn proof_schema_offsets() {
+    let proof = build_proof(None);
+    let schema = super::proof_schema::<Blake3_192<BaseElement>, BaseElement>(
+        proof.trace_info(),
+        proof.options(),
+    );
+    assert!(schema.starts_with(&format!("{{\"schema_version\":{}", super::PROOF_SCHEMA_VERSION)));
+    assert_eq!(schema.matches('{').count(), schema.matches('}').count());
+
+    // offsets are known up to the first part whose size depends on the proof
+    let offset = 3 + proof.context.to_bytes().len();
+    assert!(schema.contains(&format!(
+        "{{\"name\":\"num_unique_queries\",\"type\":\"u8\",\"size\":1,\"offset\":{offset}}}"
+    )));
+    assert!(schema.contains("{\"name\":\"pow_nonce\",\"type\":\"u64\",\"size\":8,\"offset\":null}"));
+}
+
+#[test]
+fn verification_cost_estimate() {
+    let build_context = |num_queries, field_extension, aux_width| {
+        let trace_info = if aux_width == 0 {
+            TraceInfo::new(4, 1024)
+        } else {
+            TraceInfo::new_multi_segment(4, aux_width, 1, 1024, Vec::new())
+        };
+        let options = ProofOptions::new(num_queries, 8, 0, field_extension, 4, 31);
+        Context::new::<BaseElement>(trace_info, options)
+    };
+
+    // 1024 * 8 LDE domain folded by 4 down to at most 32 * 8 values requires 3 FRI layers
+    let context = build_context(32, FieldExtension::None, 0);
+    let cost = context.estimate_verification_cost(2);
+    assert_eq!(cost, VerificationCost::estimate(&context, 2));
+    assert_eq!(cost.num_merkle_path_checks, 32 * (1 + 1 + 3));
+
+    // the cost grows with the number of queries, the number of trace segments, and the degree
+    // of the field extension
+    let more_queries = build_context(64, FieldExtension::None, 0).estimate_verification_cost(2);
+    assert!(more_queries.num_hash_invocations > cost.num_hash_invocations);
+    assert!(more_queries.num_field_multiplications > cost.num_field_multiplications);
+    assert!(more_queries.num_merkle_path_checks > cost.num_merkle_path_checks);
+
+    let with_aux = build_context(32, FieldExtension::None, 2).estimate_verification_cost(2);
+    assert_eq!(with_aux.num_merkle_path_checks, 32 * (2 + 1 + 3));
+    assert!(with_aux.num_hash_invocations > cost.num_hash_invocations);
+
+    let extended = build_context(32, FieldExtension::Quadratic, 0).estimate_verification_cost(2);
+    assert_eq!(extended.num_hash_invocations, cost.num_hash_invocations);
+    assert!(extended.num_field_multiplications > cost.num_field_multiplications);
+}
+
+#[test]
+fn proof_detach_and_attach() {
+    let proof = build_proof(Some(vec![1, 2, 3]));
+    let sections = [ProofSection::FriProof, ProofSection::TraceQueries, ProofSection::FriProof];
+    let (detached, parts) = proof.clone().detach::<Blake3_192<BaseElement>>(&sections);
+
+    // sections are detached in the order of their appearance and without duplicates
+    let expected = [ProofSection::TraceQueries, ProofSection::FriProof];
+    let detached_sections = detached.detached_sections();
+    assert_eq!(
+        expected.to_vec(),
+        detached_sections.iter().map(|s| s.section).collect::<Vec<_>>()
+    );
+    assert_eq!(proof.context, detached.partial_proof().context);
+
+    // a detached proof survives serialization and can be restored from the detached parts
+    let detached = DetachedProof::read_from_bytes(&detached.to_bytes()).unwrap();
+    let fetch = |section: &DetachedSection| {
+        parts.iter().find(|(s, _)| s == section).map(|(_, bytes)| bytes.clone())
+    };
+    assert_eq!(proof, detached.clone().attach::<Blake3_192<BaseElement>, _>(fetch).unwrap());
+
+    // missing or tampered parts are rejected
+    assert!(detached.clone().attach::<Blake3_192<BaseElement>, _>(|_| None).is_err());
+    let tampered = |section: &DetachedSection| {
+        fetch(section).map(|mut bytes| {
+            bytes[0] ^= 1;
+            bytes
+        })
+    };
+    assert!(detached.attach::<Blake3_192<BaseElement>, _>(tampered).is_err());
+}
+
+#[test]
+fn proof_introspection() {
+    let mut proof = build_proof(None);
+    proof.context = Context::new::<BaseElement>(
+        TraceInfo::new_multi_segment(4, 2, 1, 1024, Vec::new()),
+        ProofOptions::new(32, 8, 16, FieldExtension::Quadratic, 4, 31),
+    );
+    assert_eq!(1024, proof.trace_length());
+    assert_eq!(6, proof.trace_width());
+    assert_eq!(64, proof.num_modulus_bits());
+    assert_eq!(&BaseElement::get_modulus_le_bytes()[..], proof.field_modulus_bytes());
+    assert_eq!(FieldExtension::Quadratic, proof.field_extension());
+    assert_eq!(2, proof.extension_degree());
+    assert_eq!(32, proof.num_queries());
+    assert_eq!(0, proof.num_fri_layers());
+    assert_eq!(16, proof.grinding_factor());
+
+    // security level can be estimated only when the hash functions are recorded in the proof
+    assert_eq!(None, proof.hash_functions());
+    assert_eq!(None, proof.estimated_security_level(true));
+
+    let hash_fns = (HashFunctionId::Blake3_192, HashFunctionId::Blake3_256);
+    let options = proof.options().clone().with_hash_functions(hash_fns.0, hash_fns.1);
+    proof.context = Context::new::<BaseElement>(proof.trace_info().clone(), options);
+    assert_eq!(Some(hash_fns), proof.hash_functions());
+    for conjectured in [true, false] {
+        assert_eq!(
+            Some(proof.security_level::<Blake3_192<BaseElement>>(conjectured)),
+            proof.estimated_security_level(conjectured)
+        );
+    }
+
+    let options = proof
+        .options()
+        .clone()
+        .with_hash_functions(HashFunctionId::Custom, HashFunctionId::Custom);
+    proof.context = Context::new::<BaseElement>(proof.trace_info().clone(), options);
+    assert_eq!(None, proof.estimated_security_level(true));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a dummy proof which can be deserialized (i.e., one with trace queries for the main
+/// trace segment).
+fn build_proof(gkr_proof: Option<Vec<u8>>) -> Proof {
+    let mut proof = Proof::new_dummy();
+    proof.trace_queries.push(proof.constraint_queries.clone());
+    proof.gkr_proof = gkr_proof;
+    proof
+}
+
+/// Serializes the proof using the legacy format without a header.
+fn write_legacy_proof(proof: &Proof) -> Vec<u8> {
+    let mut target = Vec::new();
+    proof.context.write_into(&mut target);
+    target.write_u8(proof.num_unique_queries);
+    proof.commitments.write_into(&mut target);
+    target.write_many(&proof.trace_queries);
+    proof.constraint_queries.write_into(&mut target);
+    proof.ood_frame.write_into(&mut target);
+    proof.fri_proof.write_into(&mut target);
+    proof.pow_nonce.write_into(&mut target);
+    proof.gkr_proof.write_into(&mut target);
+    target
+}
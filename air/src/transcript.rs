@@ -0,0 +1,196 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use utils::{ByteWriter, Serializable};
+
+// TRANSCRIPT
+// ================================================================================================
+
+/// A log of all messages exchanged between the prover and the verifier during an execution of
+/// the protocol.
+///
+/// The transcript contains every commitment made by the prover and every challenge drawn from the
+/// public coin, in the order in which they were made or drawn. Each entry is labeled and contains
+/// serialized values of the corresponding message.
+///
+/// Both sides of the protocol can record a transcript: the prover records the messages it sends
+/// and the challenges it draws during proof generation, while the verifier records the messages
+/// it reads from a proof and the challenges it re-derives from them. The labels and the order of
+/// entries are the same on both sides; thus, for a valid proof the two transcripts are identical,
+/// and for an invalid proof [first_divergence()](Transcript::first_divergence) points to the first
+/// message on which the prover and the verifier disagree (or at which the verifier stopped).
+///
+/// A transcript can be dumped in a human-readable form via its [Display](fmt::Display)
+/// implementation (one entry per line). This is primarily intended for debugging custom AIRs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transcript {
+    entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new empty transcript.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns all entries recorded in this transcript in the order in which they were recorded.
+    pub fn entries(&self) -> &[TranscriptEntry] {
+        &self.entries
+    }
+
+    /// Returns the number of entries recorded in this transcript.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no entries have been recorded in this transcript.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the index of the first entry at which this transcript differs from the `other`
+    /// transcript, or None if the transcripts are identical.
+    ///
+    /// If one of the transcripts is a prefix of the other one, the returned index is the length
+    /// of the shorter transcript.
+    pub fn first_divergence(&self, other: &Self) -> Option<usize> {
+        let num_common_entries = self.entries.len().min(other.entries.len());
+        (0..num_common_entries)
+            .find(|&i| self.entries[i] != other.entries[i])
+            .or_else(|| (self.entries.len() != other.entries.len()).then_some(num_common_entries))
+    }
+
+    // RECORDING METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Records a commitment made by the prover.
+    pub fn record_commitment<T: Serializable>(&mut self, label: &'static str, values: &[T]) {
+        self.record(TranscriptEntryKind::Commitment, label, values);
+    }
+
+    /// Records a challenge drawn from the public coin.
+    pub fn record_challenge<T: Serializable>(&mut self, label: &'static str, values: &[T]) {
+        self.record(TranscriptEntryKind::Challenge, label, values);
+    }
+
+    fn record<T: Serializable>(
+        &mut self,
+        kind: TranscriptEntryKind,
+        label: &'static str,
+        values: &[T],
+    ) {
+        let mut data = Vec::new();
+        data.write_many(values);
+        self.entries.push(TranscriptEntry { kind, label, data });
+    }
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, entry) in self.entries.iter().enumerate() {
+            writeln!(f, "{i:>4} {entry}")?;
+        }
+        Ok(())
+    }
+}
+
+// TRANSCRIPT ENTRY
+// ================================================================================================
+
+/// Defines the type of a message recorded in a [Transcript].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptEntryKind {
+    /// A value sent by the prover to the verifier (e.g., a Merkle root or a proof-of-work nonce).
+    Commitment,
+    /// A value drawn from the public coin (e.g., random coefficients or query positions).
+    Challenge,
+}
+
+/// A single labeled message recorded in a [Transcript].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptEntry {
+    kind: TranscriptEntryKind,
+    label: &'static str,
+    data: Vec<u8>,
+}
+
+impl TranscriptEntry {
+    /// Returns the type of the message recorded in this entry.
+    pub fn kind(&self) -> TranscriptEntryKind {
+        self.kind
+    }
+
+    /// Returns the label of the message recorded in this entry.
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    /// Returns the serialized values of the message recorded in this entry.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl fmt::Display for TranscriptEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            TranscriptEntryKind::Commitment => "commit",
+            TranscriptEntryKind::Challenge => "draw",
+        };
+        write!(f, "{kind:<6} {}: 0x", self.label)?;
+        for byte in self.data.iter() {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Transcript;
+
+    #[test]
+    fn transcript_first_divergence() {
+        let mut prover = Transcript::new();
+        prover.record_commitment("trace_root", &[1u64]);
+        prover.record_challenge("ood_point", &[2u64]);
+        prover.record_challenge("query_positions", &[3u64, 4]);
+
+        let mut verifier = Transcript::new();
+        assert_eq!(Some(0), prover.first_divergence(&verifier));
+
+        // a prefix diverges at its length
+        verifier.record_commitment("trace_root", &[1u64]);
+        verifier.record_challenge("ood_point", &[2u64]);
+        assert_eq!(Some(2), prover.first_divergence(&verifier));
+        assert_eq!(Some(2), verifier.first_divergence(&prover));
+
+        // identical transcripts do not diverge
+        verifier.record_challenge("query_positions", &[3u64, 4]);
+        assert_eq!(None, prover.first_divergence(&verifier));
+
+        // entries with different values or kinds diverge
+        let mut other = Transcript::new();
+        other.record_commitment("trace_root", &[1u64]);
+        other.record_challenge("ood_point", &[5u64]);
+        assert_eq!(Some(1), prover.first_divergence(&other));
+
+        let mut other = Transcript::new();
+        other.record_challenge("trace_root", &[1u64]);
+        assert_eq!(Some(0), prover.first_divergence(&other));
+    }
+}
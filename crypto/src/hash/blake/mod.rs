@@ -8,7 +8,7 @@ use core::{fmt::Debug, marker::PhantomData};
 use math::{FieldElement, StarkField};
 use utils::ByteWriter;
 
-use super::{ByteDigest, ElementHasher, Hasher};
+use super::{ByteDigest, ElementHasher, HashFunctionId, Hasher};
 
 #[cfg(test)]
 mod tests;
@@ -26,6 +26,8 @@ impl<B: StarkField> Hasher for Blake3_256<B> {
 
     const COLLISION_RESISTANCE: u32 = 128;
 
+    const ID: HashFunctionId = HashFunctionId::Blake3_256;
+
     fn hash(bytes: &[u8]) -> Self::Digest {
         ByteDigest(*blake3::hash(bytes).as_bytes())
     }
@@ -74,6 +76,8 @@ impl<B: StarkField> Hasher for Blake3_192<B> {
 
     const COLLISION_RESISTANCE: u32 = 96;
 
+    const ID: HashFunctionId = HashFunctionId::Blake3_192;
+
     fn hash(bytes: &[u8]) -> Self::Digest {
         let result = blake3::hash(bytes);
         ByteDigest(result.as_bytes()[..24].try_into().unwrap())
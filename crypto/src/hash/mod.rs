@@ -6,7 +6,7 @@
 use core::{fmt::Debug, slice};
 
 use math::{FieldElement, StarkField};
-use utils::{ByteReader, Deserializable, DeserializationError, Serializable};
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 mod blake;
 pub use blake::{Blake3_192, Blake3_256};
@@ -16,9 +16,18 @@ pub use sha::Sha3_256;
 
 mod mds;
 
+mod monolith;
+pub use monolith::Monolith_256;
+
+mod poseidon2;
+pub use poseidon2::Poseidon2_256;
+
 mod rescue;
 pub use rescue::{Rp62_248, Rp64_256, RpJive64_256};
 
+mod truncated;
+pub use truncated::Truncated;
+
 // HASHER TRAITS
 // ================================================================================================
 
@@ -35,6 +44,11 @@ pub trait Hasher {
     /// Collision resistance of the hash function measured in bits.
     const COLLISION_RESISTANCE: u32;
 
+    /// Identifier of the hash function; this is used to record which hash functions were used
+    /// to generate a proof. Hash functions not defined in this crate are identified as
+    /// [HashFunctionId::Custom] by default.
+    const ID: HashFunctionId = HashFunctionId::Custom;
+
     /// Returns a hash of the provided sequence of bytes.
     fn hash(bytes: &[u8]) -> Self::Digest;
 
@@ -44,6 +58,19 @@ pub trait Hasher {
 
     /// Returns hash(`seed` || `value`). This method is intended for use in PRNG and PoW contexts.
     fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest;
+
+    /// Writes the parameters which define this hash function into the specified `target`.
+    ///
+    /// This is intended for computing fingerprints of protocol configurations which differ
+    /// whenever the hash functions of two configurations differ. The default implementation
+    /// writes the identifier and the collision resistance of the hash function, and the size of
+    /// its digests; hash functions defined by constants (e.g., round constants and MDS matrices of
+    /// algebraic hash functions) should also write these constants.
+    fn write_parameters<W: ByteWriter>(target: &mut W) {
+        target.write(Self::ID);
+        target.write_u32(Self::COLLISION_RESISTANCE);
+        target.write_usize(Self::Digest::default().serialized_size());
+    }
 }
 
 /// Defines a cryptographic hash function for hashing field elements.
@@ -74,6 +101,121 @@ pub trait Digest:
     /// upper limit on the possible digest size. For digests which are smaller than 32 bytes, the
     /// unused bytes should be set to 0.
     fn as_bytes(&self) -> [u8; 32];
+
+    /// Returns this digest encoded as a lowercase hex string.
+    ///
+    /// See [utils::encoding] for details about the encoding.
+    #[cfg(feature = "encoding")]
+    fn to_hex(&self) -> alloc::string::String {
+        utils::encoding::to_hex(self)
+    }
+
+    /// Returns a digest decoded from the specified hex string.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a canonical hex encoding of a valid digest.
+    #[cfg(feature = "encoding")]
+    fn from_hex(source: &str) -> Result<Self, DeserializationError> {
+        utils::encoding::from_hex(source)
+    }
+
+    /// Returns this digest encoded as a padded Base64 string.
+    ///
+    /// See [utils::encoding] for details about the encoding.
+    #[cfg(feature = "encoding")]
+    fn to_base64(&self) -> alloc::string::String {
+        utils::encoding::to_base64(self)
+    }
+
+    /// Returns a digest decoded from the specified Base64 string.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a canonical Base64 encoding of a valid digest.
+    #[cfg(feature = "encoding")]
+    fn from_base64(source: &str) -> Result<Self, DeserializationError> {
+        utils::encoding::from_base64(source)
+    }
+}
+
+// HASH FUNCTION IDENTIFIER
+// ================================================================================================
+
+/// Identifies a hash function implemented in this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HashFunctionId {
+    /// A hash function defined outside of this crate.
+    Custom = 0,
+    /// BLAKE3 hash function with 256-bit output.
+    Blake3_256 = 1,
+    /// BLAKE3 hash function with 192-bit output.
+    Blake3_192 = 2,
+    /// SHA3 hash function with 256-bit output.
+    Sha3_256 = 3,
+    /// Rescue Prime hash function over a 62-bit field with 248-bit output.
+    Rp62_248 = 4,
+    /// Rescue Prime hash function over a 64-bit field with 256-bit output.
+    Rp64_256 = 5,
+    /// Rescue Prime hash function over a 64-bit field with 256-bit output in Jive mode.
+    RpJive64_256 = 6,
+    /// Poseidon2 hash function over a 64-bit field with 256-bit output.
+    Poseidon2_256 = 7,
+    /// Monolith hash function over a 64-bit field with 256-bit output.
+    #[allow(non_camel_case_types)]
+    Monolith_256 = 8,
+}
+
+impl HashFunctionId {
+    /// Returns collision resistance (in bits) of the identified hash function, or None for a
+    /// hash function defined outside of this crate.
+    ///
+    /// The returned values are the same as [Hasher::COLLISION_RESISTANCE] of the corresponding
+    /// hashers.
+    pub const fn collision_resistance(&self) -> Option<u32> {
+        match self {
+            Self::Custom => None,
+            Self::Blake3_256
+            | Self::Sha3_256
+            | Self::Rp64_256
+            | Self::RpJive64_256
+            | Self::Poseidon2_256
+            | Self::Monolith_256 => Some(128),
+            Self::Blake3_192 => Some(96),
+            Self::Rp62_248 => Some(124),
+        }
+    }
+}
+
+impl Serializable for HashFunctionId {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+
+    /// Returns an estimate of how many bytes are needed to represent self.
+    fn get_size_hint(&self) -> usize {
+        1
+    }
+}
+
+impl Deserializable for HashFunctionId {
+    /// Reads a hash function identifier from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(Self::Custom),
+            1 => Ok(Self::Blake3_256),
+            2 => Ok(Self::Blake3_192),
+            3 => Ok(Self::Sha3_256),
+            4 => Ok(Self::Rp62_248),
+            5 => Ok(Self::Rp64_256),
+            6 => Ok(Self::RpJive64_256),
+            7 => Ok(Self::Poseidon2_256),
+            8 => Ok(Self::Monolith_256),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as HashFunctionId enum"
+            ))),
+        }
+    }
 }
 
 // BYTE DIGEST
@@ -117,7 +259,7 @@ impl<const N: usize> Default for ByteDigest<N> {
 }
 
 impl<const N: usize> Serializable for ByteDigest<N> {
-    fn write_into<W: utils::ByteWriter>(&self, target: &mut W) {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
         target.write_bytes(&self.0);
     }
 }
@@ -128,6 +270,20 @@ impl<const N: usize> Deserializable for ByteDigest<N> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for ByteDigest<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        utils::serde_compat::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for ByteDigest<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        utils::serde_compat::deserialize(deserializer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{ByteDigest, Digest};
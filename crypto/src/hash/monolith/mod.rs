@@ -0,0 +1,426 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::ops::Range;
+
+use math::{fields::f64::BaseElement, FieldElement, StarkField};
+use utils::ByteWriter;
+
+use super::{mds::mds_f64_12x12::mds_multiply, Digest, ElementHasher, HashFunctionId, Hasher};
+
+mod digest;
+pub use digest::ElementDigest;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Sponge state is set to 12 field elements or 96 bytes; 8 elements are reserved for rate and
+/// the remaining 4 elements are reserved for capacity.
+const STATE_WIDTH: usize = 12;
+
+/// The rate portion of the state is located in elements 0 through 7.
+const RATE_RANGE: Range<usize> = 0..8;
+const RATE_WIDTH: usize = RATE_RANGE.end - RATE_RANGE.start;
+
+const INPUT1_RANGE: Range<usize> = 0..4;
+const INPUT2_RANGE: Range<usize> = 4..8;
+
+/// The capacity portion of the state is located in elements 8, 9, 10, and 11.
+const CAPACITY_RANGE: Range<usize> = 8..12;
+
+/// The output of the hash function is a digest which consists of 4 field elements or 32 bytes.
+///
+/// The digest is returned from state elements 0, 1, 2, and 3 (the first four elements of the
+/// rate portion).
+const DIGEST_RANGE: Range<usize> = 0..4;
+const DIGEST_SIZE: usize = DIGEST_RANGE.end - DIGEST_RANGE.start;
+
+/// The number of rounds is set to 6 as specified for a 12-element state over the 64-bit field in
+/// <https://eprint.iacr.org/2023/1025.pdf>.
+const NUM_ROUNDS: usize = 6;
+
+/// The Bars layer is applied to the first 4 elements of the state.
+const NUM_BARS: usize = 4;
+
+// HASHER IMPLEMENTATION
+// ================================================================================================
+
+/// Implementation of [Hasher] trait for Monolith hash function with 256-bit output.
+///
+/// The permutation is implemented according to the Monolith
+/// [specifications](https://eprint.iacr.org/2023/1025.pdf) for the 64-bit prime field with modulus
+/// 2^64 - 2^32 + 1 (sometimes called Goldilocks field). Each round consists of the following
+/// layers:
+/// * Bars: each of the first 4 elements of the state is decomposed into 8 bytes, an 8-bit S-Box
+///   is applied to each byte, and the bytes are recomposed into an element.
+/// * Bricks: the square of each element of the state is added to the next element of the state.
+/// * Concrete: the state is multiplied by an MDS matrix; this is the same circulant matrix as the
+///   one used by [Rp64_256](crate::hashers::Rp64_256).
+/// * Round constants are added to the state in all rounds except for the last one.
+///
+/// Additionally, the Concrete layer is applied once before the first round. The round constants
+/// are generated by SHAKE128 as described in the specifications.
+///
+/// The sponge construction on top of the permutation is the padding-free, overwrite-mode sponge
+/// and the truncated-permutation compression used by Plonky2 and Plonky3:
+/// * We use the first 8 elements of the state for rate and the remaining 4 elements for
+///   capacity. The output of the hash function comes from the first four elements of the state.
+/// * When hashing a sequence of elements, each chunk of 8 elements overwrites the rate portion
+///   of the state (a partial chunk at the end overwrites only the first elements of the rate),
+///   and the permutation is applied after each chunk. No padding is applied; thus, sequences
+///   which differ only by trailing Fp(0) elements within the last chunk hash to the same digest,
+///   and the function should only be used to hash sequences of fixed length (e.g., rows of an
+///   execution trace).
+/// * [merge()](Monolith_256::merge) copies the two digests into the rate portion of an all-zero
+///   state, applies the permutation, and returns the first four elements of the state.
+///
+/// The parameters used to instantiate the function are:
+/// * Field: 64-bit prime field with modulus 2^64 - 2^32 + 1.
+/// * State width: 12 field elements.
+/// * Capacity size: 4 field elements.
+/// * Number of rounds: 6.
+///
+/// The above parameters target 128-bit security level. The digest consists of four field elements
+/// and it can be serialized into 32 bytes (256 bits).
+///
+/// ## Compatibility
+/// The permutation matches the [reference implementation](https://github.com/HorizenLabs/monolith)
+/// of Monolith for a 12-element state over the 64-bit field, and
+/// [hash_elements()](Monolith_256::hash_elements) and [merge()](Monolith_256::merge) produce the
+/// same digests as `hash_no_pad()` and `two_to_one()` of the reference `MonolithHash` for
+/// Plonky2. Thus, Merkle trees built with this hash function can be checked against commitments
+/// made by other systems using Monolith.
+///
+/// Unlike the S-Boxes of Rescue Prime and Poseidon2, the Bars layer does not consist of field
+/// operations; thus, the permutation is fast in native execution, but is more expensive to
+/// arithmetize than arithmetization-oriented hash functions over the same field.
+///
+/// ## Hash output consistency
+/// Functions [hash_elements()](Monolith_256::hash_elements), [merge()](Monolith_256::merge),
+/// and [merge_with_int()](Monolith_256::merge_with_int) are internally consistent. That is,
+/// computing a hash for the same set of elements using these functions will always produce the
+/// same result. However, [hash()](Monolith_256::hash) function is not consistent with functions
+/// mentioned above, for the same reasons as described for [Rp64_256](crate::hashers::Rp64_256).
+#[allow(non_camel_case_types)]
+pub struct Monolith_256();
+
+impl Hasher for Monolith_256 {
+    type Digest = ElementDigest;
+
+    const COLLISION_RESISTANCE: u32 = 128;
+
+    const ID: HashFunctionId = HashFunctionId::Monolith_256;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        // compute the number of elements required to represent the string; we will be processing
+        // the string in 7-byte chunks, thus the number of elements will be equal to the number
+        // of such chunks (including a potential partial chunk at the end).
+        let num_elements = if bytes.len() % 7 == 0 {
+            bytes.len() / 7
+        } else {
+            bytes.len() / 7 + 1
+        };
+
+        // break the string into 7-byte chunks, convert each chunk into a field element, and
+        // absorb the element into the rate portion of the state. we use 7-byte chunks because
+        // every 7-byte chunk is guaranteed to map to some field element.
+        let mut state = [BaseElement::ZERO; STATE_WIDTH];
+        let mut i = 0;
+        let mut buf = [0_u8; 8];
+        for (chunk_idx, chunk) in bytes.chunks(7).enumerate() {
+            if chunk_idx < num_elements - 1 {
+                buf[..7].copy_from_slice(chunk);
+            } else {
+                // if we are dealing with the last chunk, it may be smaller than 7 bytes long, so
+                // we need to handle it slightly differently. we also append a byte with value 1
+                // to the end of the string; this pads the string in such a way that adding
+                // trailing zeros results in different hash
+                let chunk_len = chunk.len();
+                buf = [0_u8; 8];
+                buf[..chunk_len].copy_from_slice(chunk);
+                buf[chunk_len] = 1;
+            }
+
+            // convert the bytes into a field element and write it into the rate portion of the
+            // state; if the rate is filled up, apply the Monolith permutation and start
+            // absorbing again from zero index.
+            state[RATE_RANGE.start + i] = BaseElement::new(u64::from_le_bytes(buf));
+            i += 1;
+            if i % RATE_WIDTH == 0 {
+                Self::apply_permutation(&mut state);
+                i = 0;
+            }
+        }
+
+        // if we absorbed some elements but didn't apply a permutation to them (would happen when
+        // the number of elements is not a multiple of RATE_WIDTH), apply the Monolith
+        // permutation.
+        if i > 0 {
+            Self::apply_permutation(&mut state);
+        }
+
+        // return the first 4 elements of the state as hash result
+        ElementDigest::new(state[DIGEST_RANGE].try_into().unwrap())
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        // initialize the state by copying the digest elements into the rate portion of the state
+        // (8 total elements), and leave the capacity portion set to zeros.
+        let mut state = [BaseElement::ZERO; STATE_WIDTH];
+        state[RATE_RANGE].copy_from_slice(Self::Digest::digests_as_elements(values));
+
+        // apply the Monolith permutation and return the first four elements of the state
+        Self::apply_permutation(&mut state);
+        ElementDigest::new(state[DIGEST_RANGE].try_into().unwrap())
+    }
+
+    fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+        // initialize the state as follows:
+        // - seed is copied into the first 4 elements of the rate portion of the state.
+        // - if the value fits into a single field element, copy it into the fifth rate element.
+        // - if the value doesn't fit into a single field element, split it into two field
+        //   elements, and copy them into rate elements 5 and 6.
+        let mut state = [BaseElement::ZERO; STATE_WIDTH];
+        state[INPUT1_RANGE].copy_from_slice(seed.as_elements());
+        state[INPUT2_RANGE.start] = BaseElement::new(value);
+        if value >= BaseElement::MODULUS {
+            state[INPUT2_RANGE.start + 1] = BaseElement::new(value / BaseElement::MODULUS);
+        }
+
+        // apply the Monolith permutation and return the first four elements of the state
+        Self::apply_permutation(&mut state);
+        ElementDigest::new(state[DIGEST_RANGE].try_into().unwrap())
+    }
+
+    fn write_parameters<W: ByteWriter>(target: &mut W) {
+        target.write(Self::ID);
+        target.write_u32(Self::COLLISION_RESISTANCE);
+        target.write_u8(NUM_ROUNDS as u8);
+        target.write_many(MDS_ROW);
+        target.write_many(ARK.iter().flatten());
+    }
+}
+
+impl ElementHasher for Monolith_256 {
+    type BaseField = BaseElement;
+
+    fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
+        // convert the elements into a list of base field elements
+        let elements = E::slice_as_base_elements(elements);
+
+        // write elements into the rate portion of the state one by one until it is filled up;
+        // then apply the Monolith permutation and start absorbing again; repeat until all
+        // elements have been absorbed
+        let mut state = [BaseElement::ZERO; STATE_WIDTH];
+        let mut i = 0;
+        for &element in elements.iter() {
+            state[RATE_RANGE.start + i] = element;
+            i += 1;
+            if i % RATE_WIDTH == 0 {
+                Self::apply_permutation(&mut state);
+                i = 0;
+            }
+        }
+
+        // if we absorbed some elements but didn't apply a permutation to them (would happen when
+        // the number of elements is not a multiple of RATE_WIDTH), apply the Monolith
+        // permutation. the elements of the previous chunk which were not overwritten remain in
+        // the rate portion of the state, as in the sponge of the reference implementations.
+        if i > 0 {
+            Self::apply_permutation(&mut state);
+        }
+
+        // return the first 4 elements of the state as hash result
+        ElementDigest::new(state[DIGEST_RANGE].try_into().unwrap())
+    }
+}
+
+// HASH FUNCTION IMPLEMENTATION
+// ================================================================================================
+
+impl Monolith_256 {
+    // CONSTANTS
+    // --------------------------------------------------------------------------------------------
+
+    /// The number of rounds is set to 6 to target 128-bit security level.
+    pub const NUM_ROUNDS: usize = NUM_ROUNDS;
+
+    /// Sponge state is set to 12 field elements or 96 bytes; 8 elements are reserved for rate and
+    /// the remaining 4 elements are reserved for capacity.
+    pub const STATE_WIDTH: usize = STATE_WIDTH;
+
+    /// The rate portion of the state is located in elements 0 through 7 (inclusive).
+    pub const RATE_RANGE: Range<usize> = RATE_RANGE;
+
+    /// The capacity portion of the state is located in elements 8, 9, 10, and 11.
+    pub const CAPACITY_RANGE: Range<usize> = CAPACITY_RANGE;
+
+    /// The output of the hash function can be read from state elements 0, 1, 2, and 3.
+    pub const DIGEST_RANGE: Range<usize> = DIGEST_RANGE;
+
+    /// Round constants added to the hasher state at the end of each round but the last one.
+    pub const ARK: [[BaseElement; STATE_WIDTH]; NUM_ROUNDS - 1] = ARK;
+
+    // MONOLITH PERMUTATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Applies Monolith permutation to the provided state.
+    pub fn apply_permutation(state: &mut [BaseElement; STATE_WIDTH]) {
+        Self::apply_concrete(state);
+        for round in 0..NUM_ROUNDS {
+            Self::apply_round(state, round);
+        }
+    }
+
+    /// Monolith round function.
+    #[inline(always)]
+    pub fn apply_round(state: &mut [BaseElement; STATE_WIDTH], round: usize) {
+        Self::apply_bars(state);
+        Self::apply_bricks(state);
+        Self::apply_concrete(state);
+        if round < NUM_ROUNDS - 1 {
+            state.iter_mut().zip(ARK[round]).for_each(|(s, k)| *s += k);
+        }
+    }
+
+    // HELPER FUNCTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Applies the 8-bit S-Box to each byte of the first [NUM_BARS] elements of the state.
+    #[inline(always)]
+    fn apply_bars(state: &mut [BaseElement; STATE_WIDTH]) {
+        for s in state[..NUM_BARS].iter_mut() {
+            *s = BaseElement::new(bar(s.as_int()));
+        }
+    }
+
+    /// Adds the square of each element of the state to the next element of the state.
+    #[inline(always)]
+    fn apply_bricks(state: &mut [BaseElement; STATE_WIDTH]) {
+        for i in (1..STATE_WIDTH).rev() {
+            state[i] += state[i - 1].square();
+        }
+    }
+
+    #[inline(always)]
+    fn apply_concrete(state: &mut [BaseElement; STATE_WIDTH]) {
+        mds_multiply(state)
+    }
+}
+
+/// Applies the 8-bit Monolith S-Box S(y) = (y ^ ((!y <<< 1) & (y <<< 2) & (y <<< 3))) <<< 1 to
+/// each byte of the specified value.
+///
+/// For values smaller than the field modulus, the result is also smaller than the field modulus.
+#[inline(always)]
+fn bar(value: u64) -> u64 {
+    let rotl1 = |x: u64| ((x & 0x8080808080808080) >> 7) | ((x & 0x7f7f7f7f7f7f7f7f) << 1);
+    let rotl2 = |x: u64| ((x & 0xc0c0c0c0c0c0c0c0) >> 6) | ((x & 0x3f3f3f3f3f3f3f3f) << 2);
+    let rotl3 = |x: u64| ((x & 0xe0e0e0e0e0e0e0e0) >> 5) | ((x & 0x1f1f1f1f1f1f1f1f) << 3);
+    rotl1(value ^ (rotl1(!value) & rotl2(value) & rotl3(value)))
+}
+
+// MDS
+// ================================================================================================
+
+/// First row of the circulant MDS matrix used in the Concrete layer; the multiplication itself
+/// is performed in frequency domain.
+const MDS_ROW: [BaseElement; STATE_WIDTH] = [
+    BaseElement::new(7),
+    BaseElement::new(23),
+    BaseElement::new(8),
+    BaseElement::new(26),
+    BaseElement::new(13),
+    BaseElement::new(10),
+    BaseElement::new(9),
+    BaseElement::new(7),
+    BaseElement::new(6),
+    BaseElement::new(22),
+    BaseElement::new(21),
+    BaseElement::new(8),
+];
+
+// ROUND CONSTANTS
+// ================================================================================================
+
+/// Monolith round constants; generated using SHAKE128 seeded with the string "Monolith" followed
+/// by the state width, the number of rounds, the little-endian bytes of the field modulus, and
+/// the sizes of the S-Boxes in the Bars layer, as described in
+/// <https://eprint.iacr.org/2023/1025.pdf>.
+const ARK: [[BaseElement; STATE_WIDTH]; NUM_ROUNDS - 1] = [
+    [
+        BaseElement::new(13596126580325903823),
+        BaseElement::new(5676126986831820406),
+        BaseElement::new(11349149288412960427),
+        BaseElement::new(3368797843020733411),
+        BaseElement::new(16240671731749717664),
+        BaseElement::new(9273190757374900239),
+        BaseElement::new(14446552112110239438),
+        BaseElement::new(4033077683985131644),
+        BaseElement::new(4291229347329361293),
+        BaseElement::new(13231607645683636062),
+        BaseElement::new(1383651072186713277),
+        BaseElement::new(8898815177417587567),
+    ],
+    [
+        BaseElement::new(2383619671172821638),
+        BaseElement::new(6065528368924797662),
+        BaseElement::new(16737578966352303081),
+        BaseElement::new(2661700069680749654),
+        BaseElement::new(7414030722730336790),
+        BaseElement::new(18124970299993404776),
+        BaseElement::new(9169923000283400738),
+        BaseElement::new(15832813151034110977),
+        BaseElement::new(16245117847613094506),
+        BaseElement::new(11056181639108379773),
+        BaseElement::new(10546400734398052938),
+        BaseElement::new(8443860941261719174),
+    ],
+    [
+        BaseElement::new(15799082741422909885),
+        BaseElement::new(13421235861052008152),
+        BaseElement::new(15448208253823605561),
+        BaseElement::new(2540286744040770964),
+        BaseElement::new(2895626806801935918),
+        BaseElement::new(8644593510196221619),
+        BaseElement::new(17722491003064835823),
+        BaseElement::new(5166255496419771636),
+        BaseElement::new(1015740739405252346),
+        BaseElement::new(4400043467547597488),
+        BaseElement::new(5176473243271652644),
+        BaseElement::new(4517904634837939508),
+    ],
+    [
+        BaseElement::new(18341030605319882173),
+        BaseElement::new(13366339881666916534),
+        BaseElement::new(6291492342503367536),
+        BaseElement::new(10004214885638819819),
+        BaseElement::new(4748655089269860551),
+        BaseElement::new(1520762444865670308),
+        BaseElement::new(8393589389936386108),
+        BaseElement::new(11025183333304586284),
+        BaseElement::new(5993305003203422738),
+        BaseElement::new(458912836931247573),
+        BaseElement::new(5947003897778655410),
+        BaseElement::new(17184667486285295106),
+    ],
+    [
+        BaseElement::new(15710528677110011358),
+        BaseElement::new(8929476121507374707),
+        BaseElement::new(2351989866172789037),
+        BaseElement::new(11264145846854799752),
+        BaseElement::new(14924075362538455764),
+        BaseElement::new(10107004551857451916),
+        BaseElement::new(18325221206052792232),
+        BaseElement::new(16751515052585522105),
+        BaseElement::new(15305034267720085905),
+        BaseElement::new(15639149412312342017),
+        BaseElement::new(14624541102106656564),
+        BaseElement::new(3542311898554959098),
+    ],
+];
@@ -0,0 +1,213 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use proptest::prelude::*;
+use rand_utils::{rand_array, rand_value};
+
+use super::{
+    super::Rp64_256, bar, BaseElement, ElementDigest, ElementHasher, FieldElement, Hasher,
+    Monolith_256, StarkField, MDS_ROW, NUM_BARS, STATE_WIDTH,
+};
+
+/// Applies the 8-bit Monolith S-Box to a single byte.
+fn sbox_naive(y: u8) -> u8 {
+    (y ^ ((!y).rotate_left(1) & y.rotate_left(2) & y.rotate_left(3))).rotate_left(1)
+}
+
+#[test]
+fn sbox_is_permutation() {
+    let mut outputs = (0..=255u8).map(sbox_naive).collect::<Vec<_>>();
+    outputs.sort_unstable();
+    assert_eq!((0..=255u8).collect::<Vec<_>>(), outputs);
+}
+
+#[test]
+fn mds_row_matches_rescue_mds() {
+    assert_eq!(MDS_ROW, Rp64_256::MDS[0]);
+}
+
+#[test]
+fn apply_permutation() {
+    let state: [BaseElement; STATE_WIDTH] = rand_array();
+
+    // the round function agrees with a naive implementation of the Monolith layers
+    for round in 0..Monolith_256::NUM_ROUNDS {
+        let mut expected = state;
+        for s in expected[..NUM_BARS].iter_mut() {
+            let bytes = s.as_int().to_le_bytes().map(sbox_naive);
+            *s = BaseElement::new(u64::from_le_bytes(bytes));
+        }
+        let squares = expected.map(|s| s.square());
+        for i in 1..STATE_WIDTH {
+            expected[i] += squares[i - 1];
+        }
+        apply_mds_naive(&mut expected);
+        if round < Monolith_256::NUM_ROUNDS - 1 {
+            expected.iter_mut().zip(Monolith_256::ARK[round]).for_each(|(s, k)| *s += k);
+        }
+
+        let mut actual = state;
+        Monolith_256::apply_round(&mut actual, round);
+        assert_eq!(expected, actual);
+    }
+
+    let mut actual = state;
+    Monolith_256::apply_permutation(&mut actual);
+    assert_ne!(state, actual);
+}
+
+#[test]
+fn hash_elements_vs_merge() {
+    let elements: [BaseElement; 8] = rand_array();
+
+    let digests: [ElementDigest; 2] = [
+        ElementDigest::new(elements[..4].try_into().unwrap()),
+        ElementDigest::new(elements[4..].try_into().unwrap()),
+    ];
+
+    let m_result = Monolith_256::merge(&digests);
+    let h_result = Monolith_256::hash_elements(&elements);
+    assert_eq!(m_result, h_result);
+}
+
+#[test]
+fn hash_elements_vs_merge_with_int() {
+    let seed = ElementDigest::new(rand_array());
+
+    // ----- value fits into a field element ------------------------------------------------------
+    let val: BaseElement = rand_value();
+    let m_result = Monolith_256::merge_with_int(seed, val.as_int());
+
+    let mut elements = seed.as_elements().to_vec();
+    elements.push(val);
+    let h_result = Monolith_256::hash_elements(&elements);
+
+    assert_eq!(m_result, h_result);
+
+    // ----- value does not fit into a field element ----------------------------------------------
+    let val = BaseElement::MODULUS + 2;
+    let m_result = Monolith_256::merge_with_int(seed, val);
+
+    let mut elements = seed.as_elements().to_vec();
+    elements.push(BaseElement::new(val));
+    elements.push(BaseElement::new(1));
+    let h_result = Monolith_256::hash_elements(&elements);
+
+    assert_eq!(m_result, h_result);
+}
+
+#[test]
+fn hash_padding() {
+    // adding a zero bytes at the end of a byte string should result in a different hash
+    let r1 = Monolith_256::hash(&[1_u8, 2, 3]);
+    let r2 = Monolith_256::hash(&[1_u8, 2, 3, 0]);
+    assert_ne!(r1, r2);
+
+    // same as above but with input splitting over two elements
+    let r1 = Monolith_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7]);
+    let r2 = Monolith_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0]);
+    assert_ne!(r1, r2);
+}
+
+#[test]
+fn hash_elements_no_padding() {
+    // the sponge does not pad its input; thus, trailing zeros within the last chunk of the input
+    // do not affect the digest
+    let e1: [BaseElement; 2] = rand_array();
+    let e2 = [e1[0], e1[1], BaseElement::ZERO];
+
+    let r1 = Monolith_256::hash_elements(&e1);
+    let r2 = Monolith_256::hash_elements(&e2);
+    assert_eq!(r1, r2);
+}
+
+#[test]
+fn permutation_known_answer() {
+    // the expected output is taken from the known-answer test of the reference implementation of
+    // Monolith for the 64-bit field and a 12-element state
+    let mut state: [BaseElement; STATE_WIDTH] =
+        core::array::from_fn(|i| BaseElement::new(i as u64));
+    Monolith_256::apply_permutation(&mut state);
+
+    let expected = [
+        BaseElement::new(0x516dd661e959f541),
+        BaseElement::new(0x082c137169707901),
+        BaseElement::new(0x53dff3fd9f0a5beb),
+        BaseElement::new(0x0b2ebaa261590650),
+        BaseElement::new(0x89aadb57e2969cb6),
+        BaseElement::new(0x5d3d6905970259bd),
+        BaseElement::new(0x6e5ac1a4c0cfa0fe),
+        BaseElement::new(0xd674b7736abfc5ce),
+        BaseElement::new(0x0d8697e1cd9a235f),
+        BaseElement::new(0x85fc4017c247136e),
+        BaseElement::new(0x572bafd76e511424),
+        BaseElement::new(0xbec1638e28eae57f),
+    ];
+    assert_eq!(expected, state);
+}
+
+#[test]
+fn hash_elements_known_answer() {
+    // the expected outputs were computed with `MonolithHash::hash_no_pad()` of the reference
+    // implementation of Monolith for Plonky2
+    let digest = Monolith_256::hash_elements(&[BaseElement::new(1), BaseElement::new(2)]);
+    let expected = [
+        BaseElement::new(18116668868493036719),
+        BaseElement::new(16629900505643491719),
+        BaseElement::new(5372639224105255745),
+        BaseElement::new(13509631924811825476),
+    ];
+    assert_eq!(&expected, digest.as_elements());
+
+    let elements: [BaseElement; 10] = core::array::from_fn(|i| BaseElement::new(i as u64));
+    let digest = Monolith_256::hash_elements(&elements);
+    let expected = [
+        BaseElement::new(5578432682130415841),
+        BaseElement::new(17984826319860468265),
+        BaseElement::new(2977584103897394617),
+        BaseElement::new(5110399557104769054),
+    ];
+    assert_eq!(&expected, digest.as_elements());
+}
+
+#[test]
+fn merge_known_answer() {
+    // the expected output was computed with `MonolithHash::two_to_one()` of the reference
+    // implementation of Monolith for Plonky2
+    let digests = [
+        ElementDigest::new(core::array::from_fn(|i| BaseElement::new(i as u64))),
+        ElementDigest::new(core::array::from_fn(|i| BaseElement::new(i as u64 + 4))),
+    ];
+    let digest = Monolith_256::merge(&digests);
+    let expected = [
+        BaseElement::new(8597293992452543654),
+        BaseElement::new(13251886779405042379),
+        BaseElement::new(6979248705951146223),
+        BaseElement::new(15358842403937303290),
+    ];
+    assert_eq!(&expected, digest.as_elements());
+}
+
+fn apply_mds_naive(state: &mut [BaseElement; STATE_WIDTH]) {
+    let mut result = [BaseElement::ZERO; STATE_WIDTH];
+    result.iter_mut().zip(Rp64_256::MDS).for_each(|(r, mds_row)| {
+        state.iter().zip(mds_row).for_each(|(&s, m)| {
+            *r += m * s;
+        });
+    });
+    *state = result;
+}
+
+proptest! {
+    #[test]
+    fn bar_proptest(a in 0..BaseElement::MODULUS) {
+        // bars map canonical field elements to canonical field elements
+        let result = bar(a);
+        prop_assert!(result < BaseElement::MODULUS);
+        prop_assert_eq!(u64::from_le_bytes(a.to_le_bytes().map(sbox_naive)), result);
+    }
+}
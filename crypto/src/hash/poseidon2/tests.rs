@@ -0,0 +1,232 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use proptest::prelude::*;
+use rand_utils::{rand_array, rand_value};
+
+use super::{
+    BaseElement, ElementDigest, ElementHasher, FieldElement, Hasher, Poseidon2_256, StarkField,
+    ALPHA, INTERNAL_MATRIX_DIAG, STATE_WIDTH,
+};
+
+/// The 4x4 matrix used to build the external linear layer.
+const M4: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+#[test]
+fn test_sbox() {
+    let e: BaseElement = rand_value();
+    assert_eq!(e.exp(ALPHA), e.exp7());
+}
+
+#[test]
+fn internal_matrix_is_invertible() {
+    // the determinant of the matrix of ones plus a diagonal matrix D is
+    // det(D) * (1 + sum of the inverses of the diagonal elements)
+    let det = INTERNAL_MATRIX_DIAG.iter().fold(BaseElement::ONE, |acc, &d| acc * d);
+    let inv_sum = INTERNAL_MATRIX_DIAG.iter().fold(BaseElement::ONE, |acc, &d| acc + d.inv());
+    assert_ne!(BaseElement::ZERO, det * inv_sum);
+}
+
+#[test]
+fn apply_permutation() {
+    let state: [BaseElement; STATE_WIDTH] = rand_array();
+
+    // the permutation agrees with a naive implementation of the Poseidon2 rounds
+    let mut expected = state;
+    apply_external_linear_layer_naive(&mut expected);
+    for ark in Poseidon2_256::ARK_EXTERNAL_INITIAL.iter() {
+        expected.iter_mut().zip(ark).for_each(|(s, &k)| *s = (*s + k).exp(ALPHA));
+        apply_external_linear_layer_naive(&mut expected);
+    }
+    for &ark in Poseidon2_256::ARK_INTERNAL.iter() {
+        expected[0] = (expected[0] + ark).exp(ALPHA);
+        apply_internal_linear_layer_naive(&mut expected);
+    }
+    for ark in Poseidon2_256::ARK_EXTERNAL_TERMINAL.iter() {
+        expected.iter_mut().zip(ark).for_each(|(s, &k)| *s = (*s + k).exp(ALPHA));
+        apply_external_linear_layer_naive(&mut expected);
+    }
+
+    let mut actual = state;
+    Poseidon2_256::apply_permutation(&mut actual);
+    assert_eq!(expected, actual);
+    assert_ne!(state, actual);
+}
+
+#[test]
+fn hash_elements_vs_merge() {
+    let elements: [BaseElement; 8] = rand_array();
+
+    let digests: [ElementDigest; 2] = [
+        ElementDigest::new(elements[..4].try_into().unwrap()),
+        ElementDigest::new(elements[4..].try_into().unwrap()),
+    ];
+
+    let m_result = Poseidon2_256::merge(&digests);
+    let h_result = Poseidon2_256::hash_elements(&elements);
+    assert_eq!(m_result, h_result);
+}
+
+#[test]
+fn hash_elements_vs_merge_with_int() {
+    let seed = ElementDigest::new(rand_array());
+
+    // ----- value fits into a field element ------------------------------------------------------
+    let val: BaseElement = rand_value();
+    let m_result = Poseidon2_256::merge_with_int(seed, val.as_int());
+
+    let mut elements = seed.as_elements().to_vec();
+    elements.push(val);
+    let h_result = Poseidon2_256::hash_elements(&elements);
+
+    assert_eq!(m_result, h_result);
+
+    // ----- value does not fit into a field element ----------------------------------------------
+    let val = BaseElement::MODULUS + 2;
+    let m_result = Poseidon2_256::merge_with_int(seed, val);
+
+    let mut elements = seed.as_elements().to_vec();
+    elements.push(BaseElement::new(val));
+    elements.push(BaseElement::new(1));
+    let h_result = Poseidon2_256::hash_elements(&elements);
+
+    assert_eq!(m_result, h_result);
+}
+
+#[test]
+fn hash_padding() {
+    // adding a zero bytes at the end of a byte string should result in a different hash
+    let r1 = Poseidon2_256::hash(&[1_u8, 2, 3]);
+    let r2 = Poseidon2_256::hash(&[1_u8, 2, 3, 0]);
+    assert_ne!(r1, r2);
+
+    // same as above but with input splitting over two elements
+    let r1 = Poseidon2_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7]);
+    let r2 = Poseidon2_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0]);
+    assert_ne!(r1, r2);
+}
+
+#[test]
+fn hash_elements_no_padding() {
+    // the sponge does not pad its input; thus, trailing zeros within the last chunk of the input
+    // do not affect the digest
+    let e1: [BaseElement; 2] = rand_array();
+    let e2 = [e1[0], e1[1], BaseElement::ZERO];
+
+    let r1 = Poseidon2_256::hash_elements(&e1);
+    let r2 = Poseidon2_256::hash_elements(&e2);
+    assert_eq!(r1, r2);
+}
+
+#[test]
+fn permutation_known_answer() {
+    // the expected output is taken from the known-answer test of the reference implementation of
+    // Poseidon2 for the 64-bit field and a 12-element state
+    let mut state: [BaseElement; STATE_WIDTH] =
+        core::array::from_fn(|i| BaseElement::new(i as u64));
+    Poseidon2_256::apply_permutation(&mut state);
+
+    let expected = [
+        BaseElement::new(0x01eaef96bdf1c0c1),
+        BaseElement::new(0x1f0d2cc525b2540c),
+        BaseElement::new(0x6282c1dfe1e0358d),
+        BaseElement::new(0xe780d721f698e1e6),
+        BaseElement::new(0x280c0b6f753d833b),
+        BaseElement::new(0x1b942dd5023156ab),
+        BaseElement::new(0x43f0df3fcccb8398),
+        BaseElement::new(0xe8e8190585489025),
+        BaseElement::new(0x56bdbf72f77ada22),
+        BaseElement::new(0x7911c32bf9dcd705),
+        BaseElement::new(0xec467926508fbe67),
+        BaseElement::new(0x6a50450ddf85a6ed),
+    ];
+    assert_eq!(expected, state);
+}
+
+#[test]
+fn hash_elements_known_answer() {
+    // the expected outputs were computed with the sponge of Plonky3 (`PaddingFreeSponge` with
+    // width 12, rate 8, and output size 4) instantiated with the permutation of the reference
+    // implementation of Poseidon2 for the 64-bit field and a 12-element state
+    let digest = Poseidon2_256::hash_elements(&[BaseElement::new(1), BaseElement::new(2)]);
+    let expected = [
+        BaseElement::new(9164423616874635022),
+        BaseElement::new(5941761159324491805),
+        BaseElement::new(1501772137076846051),
+        BaseElement::new(15752539538529161801),
+    ];
+    assert_eq!(&expected, digest.as_elements());
+
+    let elements: [BaseElement; 10] = core::array::from_fn(|i| BaseElement::new(i as u64));
+    let digest = Poseidon2_256::hash_elements(&elements);
+    let expected = [
+        BaseElement::new(14293879368063999610),
+        BaseElement::new(17745505180142164931),
+        BaseElement::new(11101679328823870214),
+        BaseElement::new(11710387920646700933),
+    ];
+    assert_eq!(&expected, digest.as_elements());
+}
+
+#[test]
+fn merge_known_answer() {
+    // the expected output was computed with the compression function of Plonky3
+    // (`TruncatedPermutation` with 2 inputs of 4 elements and width 12) instantiated with the
+    // permutation of the reference implementation of Poseidon2 for the 64-bit field and a
+    // 12-element state
+    let digests = [
+        ElementDigest::new(core::array::from_fn(|i| BaseElement::new(i as u64))),
+        ElementDigest::new(core::array::from_fn(|i| BaseElement::new(i as u64 + 4))),
+    ];
+    let digest = Poseidon2_256::merge(&digests);
+    let expected = [
+        BaseElement::new(18243748776347319819),
+        BaseElement::new(10674975148410631354),
+        BaseElement::new(14220205444945226431),
+        BaseElement::new(7294857961130422363),
+    ];
+    assert_eq!(&expected, digest.as_elements());
+}
+
+fn apply_external_linear_layer_naive(state: &mut [BaseElement; STATE_WIDTH]) {
+    // the external matrix is circ(2 * M4, M4, M4)
+    let mut result = [BaseElement::ZERO; STATE_WIDTH];
+    for (i, r) in result.iter_mut().enumerate() {
+        for (j, &s) in state.iter().enumerate() {
+            let factor = if i / 4 == j / 4 { 2 } else { 1 };
+            *r += BaseElement::new(factor * M4[i % 4][j % 4]) * s;
+        }
+    }
+    *state = result;
+}
+
+fn apply_internal_linear_layer_naive(state: &mut [BaseElement; STATE_WIDTH]) {
+    // the internal matrix has ones everywhere except for the diagonal
+    let mut result = [BaseElement::ZERO; STATE_WIDTH];
+    for (i, r) in result.iter_mut().enumerate() {
+        for (j, &s) in state.iter().enumerate() {
+            let m = if i == j {
+                INTERNAL_MATRIX_DIAG[i] + BaseElement::ONE
+            } else {
+                BaseElement::ONE
+            };
+            *r += m * s;
+        }
+    }
+    *state = result;
+}
+
+proptest! {
+    #[test]
+    fn external_linear_layer_proptest(a in any::<[u64; STATE_WIDTH]>()) {
+        let mut v1 = a.map(BaseElement::new);
+        let mut v2 = v1;
+
+        apply_external_linear_layer_naive(&mut v1);
+        Poseidon2_256::apply_external_linear_layer(&mut v2);
+
+        prop_assert_eq!(v1, v2);
+    }
+}
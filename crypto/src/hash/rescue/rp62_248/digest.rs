@@ -80,6 +80,20 @@ impl Deserializable for ElementDigest {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for ElementDigest {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        utils::serde_compat::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ElementDigest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        utils::serde_compat::deserialize(deserializer)
+    }
+}
+
 // TESTS
 // ================================================================================================
 
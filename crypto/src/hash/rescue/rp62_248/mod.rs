@@ -4,8 +4,9 @@
 // LICENSE file in the root directory of this source tree.
 
 use math::{fields::f62::BaseElement, FieldElement, StarkField};
+use utils::ByteWriter;
 
-use super::{exp_acc, Digest, ElementHasher, Hasher};
+use super::{exp_acc, Digest, ElementHasher, HashFunctionId, Hasher};
 
 mod digest;
 pub use digest::ElementDigest;
@@ -94,6 +95,8 @@ impl Hasher for Rp62_248 {
 
     const COLLISION_RESISTANCE: u32 = 124;
 
+    const ID: HashFunctionId = HashFunctionId::Rp62_248;
+
     fn hash(bytes: &[u8]) -> Self::Digest {
         // compute the number of elements required to represent the string; we will be processing
         // the string in 7-byte chunks, thus the number of elements will be equal to the number
@@ -187,6 +190,15 @@ impl Hasher for Rp62_248 {
         apply_permutation(&mut state);
         ElementDigest::new(state[..DIGEST_SIZE].try_into().unwrap())
     }
+
+    fn write_parameters<W: ByteWriter>(target: &mut W) {
+        target.write(Self::ID);
+        target.write_u32(Self::COLLISION_RESISTANCE);
+        target.write_u8(NUM_ROUNDS as u8);
+        target.write_many(MDS.iter().flatten());
+        target.write_many(ARK1.iter().flatten());
+        target.write_many(ARK2.iter().flatten());
+    }
 }
 
 impl ElementHasher for Rp62_248 {
@@ -228,6 +240,27 @@ impl ElementHasher for Rp62_248 {
     }
 }
 
+// HASH FUNCTION IMPLEMENTATION
+// ================================================================================================
+
+impl Rp62_248 {
+    // RESCUE PERMUTATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Applies Rescue-XLIX permutation to the provided state.
+    pub fn apply_permutation(state: &mut [BaseElement; STATE_WIDTH]) {
+        apply_permutation(state)
+    }
+
+    /// Applies the inverse of Rescue-XLIX permutation to the provided state.
+    ///
+    /// That is, applying this function to a state obtained via
+    /// [apply_permutation()](Self::apply_permutation) returns the original state.
+    pub fn apply_inverse_permutation(state: &mut [BaseElement; STATE_WIDTH]) {
+        apply_inverse_permutation(state)
+    }
+}
+
 // RESCUE PERMUTATION
 // ================================================================================================
 
@@ -241,6 +274,14 @@ fn apply_permutation(state: &mut [BaseElement; STATE_WIDTH]) {
     }
 }
 
+/// Applies the inverse of Rescue-XLIX permutation to the provided state by undoing the rounds in
+/// reverse order.
+fn apply_inverse_permutation(state: &mut [BaseElement; STATE_WIDTH]) {
+    for i in (0..NUM_ROUNDS).rev() {
+        apply_inverse_round(state, i);
+    }
+}
+
 /// Rescue-XLIX round function.
 #[inline(always)]
 fn apply_round(state: &mut [BaseElement; STATE_WIDTH], round: usize) {
@@ -255,6 +296,20 @@ fn apply_round(state: &mut [BaseElement; STATE_WIDTH], round: usize) {
     add_constants(state, &ARK2[round]);
 }
 
+/// Inverse of Rescue-XLIX round function.
+#[inline(always)]
+fn apply_inverse_round(state: &mut [BaseElement; STATE_WIDTH], round: usize) {
+    // undo second half of Rescue round
+    sub_constants(state, &ARK2[round]);
+    apply_inv_mds(state);
+    apply_sbox(state);
+
+    // undo first half of Rescue round
+    sub_constants(state, &ARK1[round]);
+    apply_inv_mds(state);
+    apply_inv_sbox(state);
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -269,11 +324,27 @@ fn apply_mds(state: &mut [BaseElement; STATE_WIDTH]) {
     *state = result
 }
 
+#[inline(always)]
+fn apply_inv_mds(state: &mut [BaseElement; STATE_WIDTH]) {
+    let mut result = [BaseElement::ZERO; STATE_WIDTH];
+    result.iter_mut().zip(INV_MDS).for_each(|(r, inv_mds_row)| {
+        state.iter().zip(inv_mds_row).for_each(|(&s, m)| {
+            *r += m * s;
+        });
+    });
+    *state = result
+}
+
 #[inline(always)]
 fn add_constants(state: &mut [BaseElement; STATE_WIDTH], ark: &[BaseElement; STATE_WIDTH]) {
     state.iter_mut().zip(ark).for_each(|(s, &k)| *s += k);
 }
 
+#[inline(always)]
+fn sub_constants(state: &mut [BaseElement; STATE_WIDTH], ark: &[BaseElement; STATE_WIDTH]) {
+    state.iter_mut().zip(ark).for_each(|(s, &k)| *s -= k);
+}
+
 #[inline(always)]
 fn apply_sbox(state: &mut [BaseElement; STATE_WIDTH]) {
     state.iter_mut().for_each(|v| *v = v.cube())
@@ -488,6 +559,178 @@ const MDS: [[BaseElement; STATE_WIDTH]; STATE_WIDTH] = [
     ],
 ];
 
+/// Rescue Inverse MDS matrix
+const INV_MDS: [[BaseElement; STATE_WIDTH]; STATE_WIDTH] = [
+    [
+        BaseElement::new(2543214393608729353),
+        BaseElement::new(3762795846116643991),
+        BaseElement::new(4438849810642161459),
+        BaseElement::new(4298755612766639084),
+        BaseElement::new(4455010435134145486),
+        BaseElement::new(2362995963082075917),
+        BaseElement::new(3356376909291513628),
+        BaseElement::new(1630917871591059121),
+        BaseElement::new(3316515321334287269),
+        BaseElement::new(859593051542731802),
+        BaseElement::new(2975654946305759059),
+        BaseElement::new(2892319802840624528),
+    ],
+    [
+        BaseElement::new(368283692631198345),
+        BaseElement::new(3821882031952991751),
+        BaseElement::new(3476552368135263448),
+        BaseElement::new(3958068798704093740),
+        BaseElement::new(1731451906094024936),
+        BaseElement::new(2847153232177801147),
+        BaseElement::new(2360599011628745871),
+        BaseElement::new(2987743978482994531),
+        BaseElement::new(2271224717401259993),
+        BaseElement::new(3539409508046580597),
+        BaseElement::new(477345364064277180),
+        BaseElement::new(4441660359405092821),
+    ],
+    [
+        BaseElement::new(1568827540912063778),
+        BaseElement::new(4332207950420739467),
+        BaseElement::new(2481345451872945893),
+        BaseElement::new(4528287854509273618),
+        BaseElement::new(3920212211782097284),
+        BaseElement::new(3362607288243330639),
+        BaseElement::new(207288330759570062),
+        BaseElement::new(3733211422055142166),
+        BaseElement::new(1813990736143733218),
+        BaseElement::new(1016194247100592142),
+        BaseElement::new(1587761984087005855),
+        BaseElement::new(3729439950837830238),
+    ],
+    [
+        BaseElement::new(2179848979073563646),
+        BaseElement::new(3120561316127378957),
+        BaseElement::new(3039050561496358752),
+        BaseElement::new(3576726000456613630),
+        BaseElement::new(3049129121130371719),
+        BaseElement::new(2412055299550347430),
+        BaseElement::new(3391029755605512982),
+        BaseElement::new(2076718207625898861),
+        BaseElement::new(723330253485555993),
+        BaseElement::new(4000022283803792044),
+        BaseElement::new(3825069463936394724),
+        BaseElement::new(887833726432535622),
+    ],
+    [
+        BaseElement::new(2645560821312685574),
+        BaseElement::new(3260907069637758879),
+        BaseElement::new(4040435960770752447),
+        BaseElement::new(3462761707136273209),
+        BaseElement::new(80171325581462721),
+        BaseElement::new(3690681005689464516),
+        BaseElement::new(4406904204047112988),
+        BaseElement::new(2941786071004520786),
+        BaseElement::new(4465566812605546108),
+        BaseElement::new(1967903017320603492),
+        BaseElement::new(3827361284742779322),
+        BaseElement::new(2102960684407410655),
+    ],
+    [
+        BaseElement::new(4418033832635420588),
+        BaseElement::new(1291509756869726286),
+        BaseElement::new(3393254948165828982),
+        BaseElement::new(3316225222140730771),
+        BaseElement::new(4596469248279018466),
+        BaseElement::new(2454404485473448867),
+        BaseElement::new(1637164548295097982),
+        BaseElement::new(903064565693201532),
+        BaseElement::new(3078633957790980167),
+        BaseElement::new(1063516675463167514),
+        BaseElement::new(3412634315134424247),
+        BaseElement::new(2716463412783278958),
+    ],
+    [
+        BaseElement::new(4201664907110604111),
+        BaseElement::new(3529633094566816640),
+        BaseElement::new(243490173087670280),
+        BaseElement::new(374789816579644645),
+        BaseElement::new(3962883653017491306),
+        BaseElement::new(4299307123555753314),
+        BaseElement::new(878656984409763268),
+        BaseElement::new(4598779593735204381),
+        BaseElement::new(1940529055632632894),
+        BaseElement::new(3078755472919790983),
+        BaseElement::new(3869756673046568445),
+        BaseElement::new(1303128421062384093),
+    ],
+    [
+        BaseElement::new(1295953197752500080),
+        BaseElement::new(1288773479846847920),
+        BaseElement::new(4238674408135814741),
+        BaseElement::new(1889423190079563019),
+        BaseElement::new(58438490826028),
+        BaseElement::new(1511069147316731888),
+        BaseElement::new(2595105675107098734),
+        BaseElement::new(2404032603520979119),
+        BaseElement::new(3123416769302320770),
+        BaseElement::new(823368413649637234),
+        BaseElement::new(1208862803941411730),
+        BaseElement::new(2679386850516500423),
+    ],
+    [
+        BaseElement::new(413708217679758237),
+        BaseElement::new(197977288508044591),
+        BaseElement::new(2279669398710741663),
+        BaseElement::new(1827297735317476774),
+        BaseElement::new(3897472199280225832),
+        BaseElement::new(383481040474515038),
+        BaseElement::new(4285717414671885350),
+        BaseElement::new(1973784002452403107),
+        BaseElement::new(913435258711991714),
+        BaseElement::new(2609606920111745223),
+        BaseElement::new(2118128668281370612),
+        BaseElement::new(2157846833460073545),
+    ],
+    [
+        BaseElement::new(426245806230104999),
+        BaseElement::new(488965953463523718),
+        BaseElement::new(3113611670630349332),
+        BaseElement::new(3041900213294428044),
+        BaseElement::new(583656723991564315),
+        BaseElement::new(2103714546455644827),
+        BaseElement::new(1960107369203103768),
+        BaseElement::new(3559289207403463951),
+        BaseElement::new(2477370050738085501),
+        BaseElement::new(1824777902859277717),
+        BaseElement::new(81964335816202060),
+        BaseElement::new(3396521197574483454),
+    ],
+    [
+        BaseElement::new(619521384948335066),
+        BaseElement::new(2319306944618760804),
+        BaseElement::new(4377674142265065366),
+        BaseElement::new(2126458600008345713),
+        BaseElement::new(1690948185577240853),
+        BaseElement::new(3139952138857830261),
+        BaseElement::new(4350475476230085736),
+        BaseElement::new(2327157655730232015),
+        BaseElement::new(744916420647648974),
+        BaseElement::new(2791122571205405764),
+        BaseElement::new(2169008220204157453),
+        BaseElement::new(1013208232899170018),
+    ],
+    [
+        BaseElement::new(4163288835884674935),
+        BaseElement::new(1408052361744926933),
+        BaseElement::new(3617341455374615327),
+        BaseElement::new(695710763989007220),
+        BaseElement::new(3430294246476878354),
+        BaseElement::new(4417692377407060358),
+        BaseElement::new(1909496136494135061),
+        BaseElement::new(3829184888236287376),
+        BaseElement::new(3141823874307132285),
+        BaseElement::new(2829082284041162764),
+        BaseElement::new(2257617713504303731),
+        BaseElement::new(581790031264140016),
+    ],
+];
+
 // ROUND CONSTANTS
 // ================================================================================================
 
@@ -8,9 +8,30 @@ use rand_utils::{rand_array, rand_value};
 
 use super::{
     BaseElement, ElementDigest, ElementHasher, FieldElement, Hasher, Rp62_248, ALPHA, INV_ALPHA,
-    STATE_WIDTH,
+    INV_MDS, MDS, STATE_WIDTH,
 };
 
+#[allow(clippy::needless_range_loop)]
+#[test]
+fn mds_inv_test() {
+    for i in 0..STATE_WIDTH {
+        for j in 0..STATE_WIDTH {
+            let result = {
+                let mut result = BaseElement::new(0);
+                for k in 0..STATE_WIDTH {
+                    result += MDS[i][k] * INV_MDS[k][j]
+                }
+                result
+            };
+            if i == j {
+                assert_eq!(result, BaseElement::new(1));
+            } else {
+                assert_eq!(result, BaseElement::new(0));
+            }
+        }
+    }
+}
+
 #[test]
 fn test_alphas() {
     let e: BaseElement = rand_value();
@@ -69,6 +90,23 @@ fn apply_permutation() {
     assert_eq!(expected, state);
 }
 
+#[test]
+fn apply_inverse_permutation() {
+    let state: [BaseElement; STATE_WIDTH] = rand_array();
+
+    // the inverse permutation undoes the permutation
+    let mut result = state;
+    Rp62_248::apply_permutation(&mut result);
+    assert_ne!(state, result);
+    Rp62_248::apply_inverse_permutation(&mut result);
+    assert_eq!(state, result);
+
+    // and the permutation undoes the inverse permutation
+    Rp62_248::apply_inverse_permutation(&mut result);
+    Rp62_248::apply_permutation(&mut result);
+    assert_eq!(state, result);
+}
+
 #[test]
 fn hash_elements_vs_merge() {
     let elements: [BaseElement; 8] = rand_array();
@@ -104,6 +104,34 @@ fn apply_permutation() {
     assert_eq!(expected, state);
 }
 
+#[test]
+fn apply_inverse_permutation() {
+    let state: [BaseElement; STATE_WIDTH] = rand_array();
+
+    // the inverse permutation undoes the permutation
+    let mut result = state;
+    Rp64_256::apply_permutation(&mut result);
+    assert_ne!(state, result);
+    Rp64_256::apply_inverse_permutation(&mut result);
+    assert_eq!(state, result);
+
+    // and the permutation undoes the inverse permutation
+    Rp64_256::apply_inverse_permutation(&mut result);
+    Rp64_256::apply_permutation(&mut result);
+    assert_eq!(state, result);
+}
+
+#[test]
+fn apply_inverse_round() {
+    let state: [BaseElement; STATE_WIDTH] = rand_array();
+    for round in 0..Rp64_256::NUM_ROUNDS {
+        let mut result = state;
+        Rp64_256::apply_round(&mut result, round);
+        Rp64_256::apply_inverse_round(&mut result, round);
+        assert_eq!(state, result);
+    }
+}
+
 #[test]
 fn hash_elements_vs_merge() {
     let elements: [BaseElement; 8] = rand_array();
@@ -6,8 +6,11 @@
 use core::ops::Range;
 
 use math::{fields::f64::BaseElement, FieldElement, StarkField};
+use utils::ByteWriter;
 
-use super::{super::mds::mds_f64_8x8::mds_multiply, exp_acc, Digest, ElementHasher, Hasher};
+use super::{
+    super::mds::mds_f64_8x8::mds_multiply, exp_acc, Digest, ElementHasher, HashFunctionId, Hasher,
+};
 
 mod digest;
 pub use digest::ElementDigest;
@@ -116,6 +119,8 @@ impl Hasher for RpJive64_256 {
 
     const COLLISION_RESISTANCE: u32 = 128;
 
+    const ID: HashFunctionId = HashFunctionId::RpJive64_256;
+
     fn hash(bytes: &[u8]) -> Self::Digest {
         // compute the number of elements required to represent the string; we will be processing
         // the string in 7-byte chunks, thus the number of elements will be equal to the number
@@ -221,6 +226,15 @@ impl Hasher for RpJive64_256 {
 
         Self::apply_jive_summation(&initial_state, &state)
     }
+
+    fn write_parameters<W: ByteWriter>(target: &mut W) {
+        target.write(Self::ID);
+        target.write_u32(Self::COLLISION_RESISTANCE);
+        target.write_u8(NUM_ROUNDS as u8);
+        target.write_many(MDS.iter().flatten());
+        target.write_many(ARK1.iter().flatten());
+        target.write_many(ARK2.iter().flatten());
+    }
 }
 
 impl ElementHasher for RpJive64_256 {
@@ -330,6 +344,30 @@ impl RpJive64_256 {
         Self::add_constants(state, &ARK2[round]);
     }
 
+    /// Applies the inverse of Rescue-XLIX permutation to the provided state.
+    ///
+    /// That is, applying this function to a state obtained via
+    /// [apply_permutation()](Self::apply_permutation) returns the original state.
+    pub fn apply_inverse_permutation(state: &mut [BaseElement; STATE_WIDTH]) {
+        for i in (0..NUM_ROUNDS).rev() {
+            Self::apply_inverse_round(state, i);
+        }
+    }
+
+    /// Inverse of Rescue-XLIX round function.
+    #[inline(always)]
+    pub fn apply_inverse_round(state: &mut [BaseElement; STATE_WIDTH], round: usize) {
+        // undo second half of Rescue round
+        Self::sub_constants(state, &ARK2[round]);
+        Self::apply_inv_mds(state);
+        Self::apply_sbox(state);
+
+        // undo first half of Rescue round
+        Self::sub_constants(state, &ARK1[round]);
+        Self::apply_inv_mds(state);
+        Self::apply_inv_sbox(state);
+    }
+
     #[inline(always)]
     pub fn apply_jive_summation(
         initial_state: &[BaseElement; STATE_WIDTH],
@@ -354,11 +392,27 @@ impl RpJive64_256 {
         mds_multiply(state)
     }
 
+    #[inline(always)]
+    fn apply_inv_mds(state: &mut [BaseElement; STATE_WIDTH]) {
+        let mut result = [BaseElement::ZERO; STATE_WIDTH];
+        result.iter_mut().zip(INV_MDS).for_each(|(r, inv_mds_row)| {
+            state.iter().zip(inv_mds_row).for_each(|(&s, m)| {
+                *r += m * s;
+            });
+        });
+        *state = result
+    }
+
     #[inline(always)]
     fn add_constants(state: &mut [BaseElement; STATE_WIDTH], ark: &[BaseElement; STATE_WIDTH]) {
         state.iter_mut().zip(ark).for_each(|(s, &k)| *s += k);
     }
 
+    #[inline(always)]
+    fn sub_constants(state: &mut [BaseElement; STATE_WIDTH], ark: &[BaseElement; STATE_WIDTH]) {
+        state.iter_mut().zip(ark).for_each(|(s, &k)| *s -= k);
+    }
+
     #[inline(always)]
     fn apply_sbox(state: &mut [BaseElement; STATE_WIDTH]) {
         state[0] = state[0].exp7();
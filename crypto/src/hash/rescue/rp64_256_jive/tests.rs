@@ -96,6 +96,34 @@ fn apply_permutation() {
     assert_eq!(expected, state);
 }
 
+#[test]
+fn apply_inverse_permutation() {
+    let state: [BaseElement; STATE_WIDTH] = rand_array();
+
+    // the inverse permutation undoes the permutation
+    let mut result = state;
+    RpJive64_256::apply_permutation(&mut result);
+    assert_ne!(state, result);
+    RpJive64_256::apply_inverse_permutation(&mut result);
+    assert_eq!(state, result);
+
+    // and the permutation undoes the inverse permutation
+    RpJive64_256::apply_inverse_permutation(&mut result);
+    RpJive64_256::apply_permutation(&mut result);
+    assert_eq!(state, result);
+}
+
+#[test]
+fn apply_inverse_round() {
+    let state: [BaseElement; STATE_WIDTH] = rand_array();
+    for round in 0..RpJive64_256::NUM_ROUNDS {
+        let mut result = state;
+        RpJive64_256::apply_round(&mut result, round);
+        RpJive64_256::apply_inverse_round(&mut result, round);
+        assert_eq!(state, result);
+    }
+}
+
 #[test]
 fn hash_elements_vs_merge() {
     let elements: [BaseElement; 8] = rand_array();
@@ -9,7 +9,7 @@ use math::{FieldElement, StarkField};
 use sha3::Digest;
 use utils::ByteWriter;
 
-use super::{ByteDigest, ElementHasher, Hasher};
+use super::{ByteDigest, ElementHasher, HashFunctionId, Hasher};
 
 // SHA3 WITH 256-BIT OUTPUT
 // ================================================================================================
@@ -23,6 +23,8 @@ impl<B: StarkField> Hasher for Sha3_256<B> {
 
     const COLLISION_RESISTANCE: u32 = 128;
 
+    const ID: HashFunctionId = HashFunctionId::Sha3_256;
+
     fn hash(bytes: &[u8]) -> Self::Digest {
         ByteDigest(sha3::Sha3_256::digest(bytes).into())
     }
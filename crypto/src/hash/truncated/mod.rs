@@ -0,0 +1,93 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::{fmt::Debug, marker::PhantomData};
+
+use math::FieldElement;
+use utils::ByteWriter;
+
+use super::{ByteDigest, Digest, ElementHasher, HashFunctionId, Hasher};
+
+#[cfg(test)]
+mod tests;
+
+// TRUNCATED HASHER
+// ================================================================================================
+
+/// Implementation of the [Hasher](super::Hasher) trait which truncates digests of the hash
+/// function `H` to their first `N` bytes.
+///
+/// Commitments built with a truncated hasher (e.g., Merkle trees over the trace and constraint
+/// evaluations, and over FRI layers) consist of `N`-byte nodes; thus, authentication paths in a
+/// proof shrink proportionally to the truncation. The collision resistance of the hasher is
+/// reduced accordingly: it is the smaller of the collision resistance of `H` and `4 * N` bits.
+///
+/// Digests of inner nodes are computed by hashing the bytes of the two truncated child digests
+/// with `H`, and the seed of [merge_with_int()](Hasher::merge_with_int) is hashed together with the
+/// little-endian bytes of the integer; thus, for hash functions which merge digests by hashing
+/// their concatenation (e.g., BLAKE3 and SHA3), `Truncated<H, 32>` computes the same digests as
+/// `H`.
+///
+/// The identifier of the hasher is the identifier of `H`; the truncation width is recorded in
+/// the proof context separately (see `ProofOptions::with_digest_truncation()`).
+///
+/// `N` must be between 1 and 32, since digests are truncated from their 32-byte representation
+/// (see [Digest::as_bytes()]); using any other width fails at compile time.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Truncated<H: Hasher, const N: usize>(PhantomData<H>);
+
+impl<H: Hasher, const N: usize> Truncated<H, N> {
+    /// Fails compilation of any use of a truncated hasher with an unsupported truncation width.
+    const VALID_WIDTH: () = assert!(N > 0 && N <= 32, "truncation width must be between 1 and 32");
+
+    /// Returns the first `N` bytes of the specified digest.
+    fn truncate(digest: H::Digest) -> ByteDigest<N> {
+        let () = Self::VALID_WIDTH;
+        ByteDigest(digest.as_bytes()[..N].try_into().unwrap())
+    }
+}
+
+impl<H: Hasher, const N: usize> Hasher for Truncated<H, N> {
+    type Digest = ByteDigest<N>;
+
+    const COLLISION_RESISTANCE: u32 = {
+        let truncated_resistance = N as u32 * 4;
+        if truncated_resistance < H::COLLISION_RESISTANCE {
+            truncated_resistance
+        } else {
+            H::COLLISION_RESISTANCE
+        }
+    };
+
+    const ID: HashFunctionId = H::ID;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        Self::truncate(H::hash(bytes))
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        Self::truncate(H::hash(ByteDigest::digests_as_bytes(values)))
+    }
+
+    fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+        let mut data = [0; 40];
+        data[..N].copy_from_slice(&seed.0);
+        data[N..N + 8].copy_from_slice(&value.to_le_bytes());
+        Self::truncate(H::hash(&data[..N + 8]))
+    }
+
+    fn write_parameters<W: ByteWriter>(target: &mut W) {
+        H::write_parameters(target);
+        target.write_usize(N);
+    }
+}
+
+impl<H: ElementHasher, const N: usize> ElementHasher for Truncated<H, N> {
+    type BaseField = H::BaseField;
+
+    fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
+        Self::truncate(H::hash_elements(elements))
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use math::fields::f62::BaseElement;
+use rand_utils::rand_array;
+
+use super::{
+    super::{Blake3_256, Rp64_256},
+    Digest, ElementHasher, Hasher, Truncated,
+};
+
+type Blake3 = Blake3_256<BaseElement>;
+
+#[test]
+fn truncated_digests() {
+    let elements: [BaseElement; 8] = rand_array();
+    let full = Blake3::hash_elements(&elements);
+    let truncated = Truncated::<Blake3, 20>::hash_elements(&elements);
+    assert_eq!(full.as_bytes()[..20], truncated.0);
+
+    let bytes = [1_u8, 2, 3, 4, 5];
+    let full = Blake3::hash(&bytes);
+    let truncated = Truncated::<Blake3, 24>::hash(&bytes);
+    assert_eq!(full.as_bytes()[..24], truncated.0);
+}
+
+#[test]
+fn truncated_merge() {
+    // without truncation, the digests are the same as the digests of the underlying hasher
+    let values = [Blake3::hash(&[1, 2, 3]), Blake3::hash(&[4, 5, 6])];
+    assert_eq!(
+        values,
+        [
+            Truncated::<Blake3, 32>::hash(&[1, 2, 3]),
+            Truncated::<Blake3, 32>::hash(&[4, 5, 6])
+        ]
+    );
+    assert_eq!(Blake3::merge(&values), Truncated::<Blake3, 32>::merge(&values));
+    assert_eq!(
+        Blake3::merge_with_int(values[0], 42),
+        Truncated::<Blake3, 32>::merge_with_int(values[0], 42)
+    );
+
+    // with truncation, inner nodes are hashes of the truncated children
+    type Hasher20 = Truncated<Blake3, 20>;
+    let values = [Hasher20::hash(&[1, 2, 3]), Hasher20::hash(&[4, 5, 6])];
+    let mut children = [0_u8; 40];
+    children[..20].copy_from_slice(&values[0].0);
+    children[20..].copy_from_slice(&values[1].0);
+    assert_eq!(Blake3::hash(&children).as_bytes()[..20], Hasher20::merge(&values).0);
+    assert_ne!(Hasher20::merge_with_int(values[0], 1), Hasher20::merge_with_int(values[0], 2));
+}
+
+#[test]
+fn truncated_collision_resistance() {
+    assert_eq!(128, Truncated::<Blake3, 32>::COLLISION_RESISTANCE);
+    assert_eq!(96, Truncated::<Blake3, 24>::COLLISION_RESISTANCE);
+    assert_eq!(80, Truncated::<Blake3, 20>::COLLISION_RESISTANCE);
+    assert_eq!(Rp64_256::COLLISION_RESISTANCE, Truncated::<Rp64_256, 32>::COLLISION_RESISTANCE);
+    assert_eq!(Blake3::ID, Truncated::<Blake3, 20>::ID);
+}
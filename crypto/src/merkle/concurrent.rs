@@ -24,9 +24,8 @@ pub const MIN_CONCURRENT_LEAVES: usize = 1024;
 pub fn build_merkle_nodes<H: Hasher>(leaves: &[H::Digest]) -> Vec<H::Digest> {
     let n = leaves.len() / 2;
 
-    // create un-initialized array to hold all intermediate nodes
-    let mut nodes = unsafe { utils::uninit_vector::<H::Digest>(2 * n) };
-    nodes[0] = H::Digest::default();
+    // create array to hold all intermediate nodes
+    let mut nodes = vec![H::Digest::default(); 2 * n];
 
     // re-interpret leaves as an array of two leaves fused together and use it to
     // build first row of internal nodes (parents of leaves)
@@ -0,0 +1,203 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::{
+    collections::{btree_map::Entry, BTreeMap},
+    string::ToString,
+    vec::Vec,
+};
+
+use utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+
+use crate::{Digest, Hasher};
+
+// MERKLE NODE DICTIONARY
+// ================================================================================================
+
+/// A dictionary of Merkle tree nodes shared across multiple batch Merkle proofs.
+///
+/// A batch Merkle proof removes duplicate nodes only within a single proof. When many batch proofs
+/// are serialized together (e.g., all proofs contained in a STARK proof), this dictionary can be
+/// used to remove nodes which repeat across proofs as well: the first occurrence of every node is
+/// written out in full, while every subsequent occurrence is replaced with a reference to the
+/// first one.
+///
+/// The same sequence of proofs must be passed through the dictionary when writing and reading
+/// nodes as the references are resolved against the nodes which were seen so far.
+#[derive(Debug, Clone)]
+pub struct MerkleNodeDictionary<H: Hasher> {
+    nodes: Vec<H::Digest>,
+    node_index: BTreeMap<[u8; 32], usize>,
+}
+
+impl<H: Hasher> MerkleNodeDictionary<H> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new empty dictionary.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            node_index: BTreeMap::new(),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of distinct nodes seen by this dictionary.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns true if this dictionary has not seen any nodes yet.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // SERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Writes the nodes of a batch Merkle proof into the `target`, replacing all nodes which have
+    /// been seen by this dictionary before with references to them.
+    ///
+    /// The `node_bytes` are expected to be in the format produced by
+    /// [BatchMerkleProof::serialize_nodes()](crate::BatchMerkleProof::serialize_nodes).
+    ///
+    /// # Errors
+    /// Returns an error if `node_bytes` could not be parsed into a set of Merkle tree nodes.
+    pub fn write_nodes<W: ByteWriter>(
+        &mut self,
+        node_bytes: &[u8],
+        target: &mut W,
+    ) -> Result<(), DeserializationError> {
+        // parse the layout of node vectors and the nodes themselves
+        let mut reader = SliceReader::new(node_bytes);
+        let num_node_vectors = reader.read_u8()?;
+        let mut vector_lengths = Vec::with_capacity(num_node_vectors as usize);
+        let mut nodes = Vec::new();
+        for _ in 0..num_node_vectors {
+            let num_digests = reader.read_u8()?;
+            vector_lengths.push(num_digests);
+            nodes.append(&mut reader.read_many::<H::Digest>(num_digests as usize)?);
+        }
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+
+        // split the nodes into the new ones and the references to the previously seen ones
+        let mut references = Vec::new();
+        let mut new_nodes = Vec::new();
+        for (position, node) in nodes.into_iter().enumerate() {
+            match self.node_index.get(&node.as_bytes()) {
+                Some(&index) => references.push((position, index)),
+                None => {
+                    self.insert(node);
+                    new_nodes.push(node);
+                },
+            }
+        }
+
+        // write the layout followed by the references and the new nodes
+        target.write_u8(num_node_vectors);
+        target.write_bytes(&vector_lengths);
+        target.write_usize(references.len());
+        for (position, index) in references {
+            target.write_usize(position);
+            target.write_usize(index);
+        }
+        target.write_many(&new_nodes);
+
+        Ok(())
+    }
+
+    /// Reads the nodes of a batch Merkle proof previously written via
+    /// [write_nodes()](Self::write_nodes) from the `source`, and returns them in the format
+    /// produced by [BatchMerkleProof::serialize_nodes()](crate::BatchMerkleProof::serialize_nodes).
+    ///
+    /// # Errors
+    /// Returns an error if the nodes could not be read from the `source` or if any of the read
+    /// references does not point to a previously seen node.
+    pub fn read_nodes<R: ByteReader>(
+        &mut self,
+        source: &mut R,
+    ) -> Result<Vec<u8>, DeserializationError> {
+        let num_node_vectors = source.read_u8()?;
+        let vector_lengths = source.read_vec(num_node_vectors as usize)?;
+        let num_nodes = vector_lengths.iter().map(|&len| len as usize).sum::<usize>();
+
+        // read the references and make sure they point to the positions in increasing order
+        let num_references = source.read_usize()?;
+        if num_references > num_nodes {
+            return Err(DeserializationError::InvalidValue(format!(
+                "number of node references ({num_references}) exceeds the number of nodes ({num_nodes})"
+            )));
+        }
+        let mut references = Vec::with_capacity(num_references);
+        for _ in 0..num_references {
+            let position = source.read_usize()?;
+            let index = source.read_usize()?;
+            if position >= num_nodes || references.last().is_some_and(|&(p, _)| p >= position) {
+                return Err(DeserializationError::InvalidValue(
+                    "node references must point to increasing node positions".to_string(),
+                ));
+            }
+            references.push((position, index));
+        }
+
+        // rebuild the nodes in the order in which they were written
+        let mut references = references.into_iter().peekable();
+        let mut nodes = Vec::with_capacity(num_nodes);
+        for position in 0..num_nodes {
+            match references.next_if(|&(p, _)| p == position) {
+                Some((_, index)) => {
+                    let node = *self.nodes.get(index).ok_or_else(|| {
+                        DeserializationError::InvalidValue(format!(
+                            "node reference {index} does not point to a previously seen node"
+                        ))
+                    })?;
+                    nodes.push(node);
+                },
+                None => {
+                    let node = H::Digest::read_from(source)?;
+                    self.insert(node);
+                    nodes.push(node);
+                },
+            }
+        }
+
+        // serialize the nodes in the batch Merkle proof format
+        let mut result = Vec::new();
+        result.push(num_node_vectors);
+        let mut nodes = nodes.into_iter();
+        for len in vector_lengths {
+            result.push(len);
+            for node in nodes.by_ref().take(len as usize) {
+                node.write_into(&mut result);
+            }
+        }
+
+        Ok(result)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the specified node to this dictionary unless the node is already in the dictionary.
+    fn insert(&mut self, node: H::Digest) {
+        if let Entry::Vacant(entry) = self.node_index.entry(node.as_bytes()) {
+            entry.insert(self.nodes.len());
+            self.nodes.push(node);
+        }
+    }
+}
+
+impl<H: Hasher> Default for MerkleNodeDictionary<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
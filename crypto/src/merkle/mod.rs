@@ -14,6 +14,9 @@ use crate::{errors::MerkleTreeError, hash::Hasher};
 mod proofs;
 pub use proofs::BatchMerkleProof;
 
+mod dictionary;
+pub use dictionary::MerkleNodeDictionary;
+
 #[cfg(feature = "concurrent")]
 pub mod concurrent;
 
@@ -335,9 +338,8 @@ impl<H: Hasher> MerkleTree<H> {
 pub fn build_merkle_nodes<H: Hasher>(leaves: &[H::Digest]) -> Vec<H::Digest> {
     let n = leaves.len() / 2;
 
-    // create un-initialized array to hold all intermediate nodes
-    let mut nodes = unsafe { utils::uninit_vector::<H::Digest>(2 * n) };
-    nodes[0] = H::Digest::default();
+    // create array to hold all intermediate nodes
+    let mut nodes = vec![H::Digest::default(); 2 * n];
 
     // re-interpret leaves as an array of two leaves fused together
     let two_leaves = unsafe { slice::from_raw_parts(leaves.as_ptr() as *const [H::Digest; 2], n) };
@@ -5,7 +5,7 @@
 
 use alloc::{collections::BTreeMap, string::ToString, vec::Vec};
 
-use utils::{ByteReader, DeserializationError, Serializable};
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 use crate::{errors::MerkleTreeError, Hasher};
 
@@ -466,6 +466,52 @@ impl<H: Hasher> BatchMerkleProof<H> {
     }
 }
 
+// SERIALIZATION / DESERIALIZATION
+// ================================================================================================
+
+impl<H: Hasher> Serializable for BatchMerkleProof<H> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    ///
+    /// # Panics
+    /// Panics if the proof contains more than 255 leaves or Merkle paths, or if the Merkle paths
+    /// consist of more than 255 nodes.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        assert!(self.leaves.len() <= MAX_PATHS, "too many leaves");
+        target.write_u8(self.depth);
+        target.write_u8(self.leaves.len() as u8);
+        target.write_many(&self.leaves);
+        target.write_bytes(&self.serialize_nodes());
+    }
+}
+
+impl<H: Hasher> Deserializable for BatchMerkleProof<H> {
+    /// Reads a batch Merkle proof from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid batch Merkle proof could not be read from the specified
+    /// `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let depth = source.read_u8()?;
+        let num_leaves = source.read_u8()? as usize;
+        let leaves = source.read_many(num_leaves)?;
+        Self::deserialize(source, leaves, depth)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<H: Hasher> serde::Serialize for BatchMerkleProof<H> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        utils::serde_compat::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, H: Hasher> serde::Deserialize<'de> for BatchMerkleProof<H> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        utils::serde_compat::deserialize(deserializer)
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -5,6 +5,7 @@
 
 use math::fields::f128::BaseElement;
 use proptest::prelude::*;
+use utils::{ByteReader, SliceReader};
 
 use super::*;
 
@@ -236,6 +237,45 @@ fn verify_into_paths() {
     assert_eq!(proof6, result[2]);
 }
 
+#[test]
+fn prove_batch_packs_siblings() {
+    let leaves = Digest256::bytes_as_digests(&LEAVES8).to_vec();
+    let tree = MerkleTree::<Blake3_256>::new(leaves).unwrap();
+
+    // opening a sibling together with a leaf does not require any extra nodes; instead, the
+    // sibling node included in the single-leaf proof is not serialized
+    let single = tree.prove_batch(&[4]).unwrap().serialize_nodes();
+    let siblings = tree.prove_batch(&[4, 5]).unwrap().serialize_nodes();
+    assert_eq!(single.len() - 32, siblings.len());
+
+    // leaves which are adjacent but are not siblings share nodes in the upper part of the tree
+    let neighbors = tree.prove_batch(&[5, 6]).unwrap().serialize_nodes();
+    let far_apart = tree.prove_batch(&[1, 6]).unwrap().serialize_nodes();
+    assert!(neighbors.len() < far_apart.len());
+}
+
+#[test]
+fn node_dictionary_round_trip() {
+    let leaves = Digest256::bytes_as_digests(&LEAVES8).to_vec();
+    let tree = MerkleTree::<Blake3_256>::new(leaves).unwrap();
+
+    // the two proofs share the nodes in the upper half of the tree
+    let proof1 = tree.prove_batch(&[1, 2]).unwrap().serialize_nodes();
+    let proof2 = tree.prove_batch(&[0, 3]).unwrap().serialize_nodes();
+
+    let mut dictionary = MerkleNodeDictionary::<Blake3_256>::new();
+    let mut compressed = Vec::new();
+    dictionary.write_nodes(&proof1, &mut compressed).unwrap();
+    dictionary.write_nodes(&proof2, &mut compressed).unwrap();
+    assert!(compressed.len() < proof1.len() + proof2.len());
+
+    let mut dictionary = MerkleNodeDictionary::<Blake3_256>::new();
+    let mut reader = SliceReader::new(&compressed);
+    assert_eq!(proof1, dictionary.read_nodes(&mut reader).unwrap());
+    assert_eq!(proof2, dictionary.read_nodes(&mut reader).unwrap());
+    assert!(!reader.has_more_bytes());
+}
+
 proptest! {
     #[test]
     fn prove_n_verify(tree in random_blake3_merkle_tree(128),
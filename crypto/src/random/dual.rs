@@ -0,0 +1,131 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use math::{FieldElement, StarkField};
+
+use crate::{
+    errors::RandomCoinError, DefaultRandomCoin, Digest, ElementHasher, HashFunctionId, RandomCoin,
+};
+
+// DUAL HASH RANDOM COIN IMPLEMENTATION
+// ================================================================================================
+
+/// Pseudo-random element generator for finite fields which accepts digests of one hash function
+/// but draws elements using another hash function.
+///
+/// This coin allows using different hash functions for commitments and for the Fiat-Shamir
+/// transform. For example, Merkle trees can be built using a hash function which is fast in
+/// hardware (e.g., BLAKE3), while the public coin can use an arithmetization-friendly hash
+/// function (e.g., RPO), or vice versa.
+///
+/// The `H` type parameter specifies the hash function used for commitments; digests of this hash
+/// function are used to reseed the coin. The `C` type parameter specifies the hash function used
+/// to draw elements. Internally, the coin is a [DefaultRandomCoin] instantiated with `C`; to
+/// reseed it with a digest of `H`, the digest is first hashed with `C`.
+///
+/// # Examples
+/// ```
+/// # use winter_crypto::{RandomCoin, DualHashRandomCoin, Hasher, hashers::{Blake3_256, Sha3_256}};
+/// # use math::fields::f128::BaseElement;
+/// type Coin = DualHashRandomCoin<Blake3_256<BaseElement>, Sha3_256<BaseElement>>;
+///
+/// // initial elements for seeding the random coin
+/// let seed = &[BaseElement::new(1), BaseElement::new(2), BaseElement::new(3), BaseElement::new(4)];
+///
+/// let mut coin1 = Coin::new(seed);
+/// let mut coin2 = Coin::new(seed);
+///
+/// // the coin is reseeded with BLAKE3 digests
+/// coin1.reseed(Blake3_256::<BaseElement>::hash(&[1, 2, 3, 4]));
+/// coin2.reseed(Blake3_256::<BaseElement>::hash(&[1, 2, 3, 4]));
+/// let e1 = coin1.draw::<BaseElement>().unwrap();
+/// let e2 = coin2.draw::<BaseElement>().unwrap();
+/// assert_eq!(e1, e2);
+///
+/// coin2.reseed(Blake3_256::<BaseElement>::hash(&[2, 3, 4, 5]));
+/// let e1 = coin1.draw::<BaseElement>().unwrap();
+/// let e2 = coin2.draw::<BaseElement>().unwrap();
+/// assert_ne!(e1, e2);
+/// ```
+pub struct DualHashRandomCoin<H: ElementHasher, C: ElementHasher> {
+    coin: DefaultRandomCoin<C>,
+    _commitment_hasher: PhantomData<fn() -> H>,
+}
+
+impl<B, H, C> RandomCoin for DualHashRandomCoin<H, C>
+where
+    B: StarkField,
+    H: ElementHasher<BaseField = B>,
+    C: ElementHasher<BaseField = B>,
+{
+    type BaseField = B;
+    type Hasher = H;
+
+    const HASH_FUNCTION_ID: HashFunctionId = C::ID;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new random coin instantiated with the provided `seed`.
+    fn new(seed: &[Self::BaseField]) -> Self {
+        Self {
+            coin: DefaultRandomCoin::new(seed),
+            _commitment_hasher: PhantomData,
+        }
+    }
+
+    // RESEEDING
+    // --------------------------------------------------------------------------------------------
+
+    /// Reseeds the coin with the specified data by setting the new seed to
+    /// C(`seed` || C(`data`)).
+    fn reseed(&mut self, data: H::Digest) {
+        self.coin.reseed(C::hash(&data.as_bytes()));
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes C(`seed` || `value`) and returns the number of leading zeros in the resulting
+    /// value if it is interpreted as an integer in big-endian byte order.
+    fn check_leading_zeros(&self, value: u64) -> u32 {
+        self.coin.check_leading_zeros(value)
+    }
+
+    // DRAW METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the next pseudo-random field element.
+    ///
+    /// # Errors
+    /// Returns an error if a valid field element could not be generated after 1000 calls to the
+    /// PRNG.
+    fn draw<E: FieldElement<BaseField = Self::BaseField>>(&mut self) -> Result<E, RandomCoinError> {
+        self.coin.draw()
+    }
+
+    /// Returns a vector of integers selected from the range [0, domain_size) after reseeding
+    /// the PRNG with the specified `nonce`.
+    ///
+    /// # Errors
+    /// Returns an error if the specified number of integers could not be generated after 1000
+    /// calls to the PRNG.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `domain_size` is not a power of two.
+    /// - `num_values` is greater than or equal to `domain_size`.
+    fn draw_integers(
+        &mut self,
+        num_values: usize,
+        domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        self.coin.draw_integers(num_values, domain_size, nonce)
+    }
+}
@@ -7,11 +7,14 @@ use alloc::vec::Vec;
 
 use math::{FieldElement, StarkField};
 
-use crate::{errors::RandomCoinError, ElementHasher, Hasher};
+use crate::{errors::RandomCoinError, ElementHasher, HashFunctionId, Hasher};
 
 mod default;
 pub use default::DefaultRandomCoin;
 
+mod dual;
+pub use dual::DualHashRandomCoin;
+
 // RANDOM COIN TRAIT
 // ================================================================================================
 
@@ -22,13 +25,22 @@ pub use default::DefaultRandomCoin;
 ///
 /// Internally we use a cryptographic hash function (which is specified via the `Hasher` associated
 /// type), to draw elements from the field.
+///
+/// The coin is reseeded with digests of the `Hasher` (e.g., commitments made by the prover). A coin
+/// may use a different hash function internally to draw elements; in such a case, the coin should
+/// report the function via [RandomCoin::HASH_FUNCTION_ID].
 pub trait RandomCoin: Sync {
     /// Base field for random elements which can be generated by this random coin.
     type BaseField: StarkField;
 
-    /// Hash function which is used by the random coin to generate random field elements.
+    /// Hash function whose digests are used to reseed the random coin; unless specified otherwise
+    /// by the implementation, this hash function is also used to generate random field elements.
     type Hasher: ElementHasher<BaseField = Self::BaseField>;
 
+    /// Identifier of the hash function which is used by the random coin to generate random field
+    /// elements.
+    const HASH_FUNCTION_ID: HashFunctionId = <Self::Hasher as Hasher>::ID;
+
     // REQUIRED METHODS
     // --------------------------------------------------------------------------------------------
 
@@ -0,0 +1,74 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Compares hash functions by generating and verifying complete proofs for the same computation.
+//!
+//! The computation is the `fib_small` example (a Fibonacci sequence over the 64-bit field), which
+//! supports all hash functions implemented in the `winter-crypto` crate. Prover and verifier
+//! times are measured by criterion; proof sizes are printed to stdout.
+
+use std::time::Duration;
+
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BatchSize, BenchmarkGroup, BenchmarkId,
+    Criterion,
+};
+use examples::{fibonacci::fib_small::FibExample, Example};
+use winterfell::{
+    crypto::{hashers, ElementHasher},
+    math::fields::f64::BaseElement,
+    FieldExtension, ProofOptions,
+};
+
+const SIZES: [usize; 2] = [16_384, 65_536];
+
+fn hash_functions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash_functions");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(20));
+
+    let options = ProofOptions::new(28, 8, 0, FieldExtension::Quadratic, 4, 255);
+
+    for &size in SIZES.iter() {
+        bench_hash_fn::<hashers::Blake3_192<BaseElement>>(&mut group, "blake3_192", size, &options);
+        bench_hash_fn::<hashers::Blake3_256<BaseElement>>(&mut group, "blake3_256", size, &options);
+        bench_hash_fn::<hashers::Sha3_256<BaseElement>>(&mut group, "sha3_256", size, &options);
+        bench_hash_fn::<hashers::Rp64_256>(&mut group, "rp64_256", size, &options);
+        bench_hash_fn::<hashers::RpJive64_256>(&mut group, "rp_jive64_256", size, &options);
+    }
+    group.finish();
+}
+
+/// Benchmarks proof generation and verification for a sequence of the specified length using
+/// hash function `H`, and prints the size of the resulting proof.
+fn bench_hash_fn<H>(
+    group: &mut BenchmarkGroup<WallTime>,
+    name: &str,
+    size: usize,
+    options: &ProofOptions,
+) where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    let fib = FibExample::<H>::new(size, options.clone());
+    let proof = fib.prove();
+    println!(
+        "{name}: proof size for sequence length {size} is {} bytes",
+        proof.to_bytes().len()
+    );
+
+    group.bench_function(BenchmarkId::new(format!("prove/{name}"), size), |bench| {
+        bench.iter(|| fib.prove());
+    });
+    group.bench_function(BenchmarkId::new(format!("verify/{name}"), size), |bench| {
+        bench.iter_batched(
+            || proof.clone(),
+            |proof| fib.verify(proof).unwrap(),
+            BatchSize::SmallInput,
+        );
+    });
+}
+
+criterion_group!(hash_functions_group, hash_functions);
+criterion_main!(hash_functions_group);
@@ -0,0 +1,41 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use std::time::Duration;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use examples::{fibonacci, Example};
+use winterfell::{
+    crypto::hashers::Blake3_256,
+    math::fields::f128::BaseElement,
+    proof::{Proof, ProofRef},
+    FieldExtension, ProofOptions,
+};
+
+const SIZES: [usize; 2] = [16_384, 262_144];
+
+fn proof_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("proof_parsing");
+    group.measurement_time(Duration::from_secs(10));
+
+    let options = ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 255);
+
+    for &size in SIZES.iter() {
+        let fib =
+            fibonacci::fib2::FibExample::<Blake3_256<BaseElement>>::new(size, options.clone());
+        let proof_bytes = fib.prove().to_bytes();
+
+        group.bench_function(BenchmarkId::new("owned", size), |bench| {
+            bench.iter(|| Proof::from_bytes(black_box(&proof_bytes)).unwrap());
+        });
+        group.bench_function(BenchmarkId::new("borrowed", size), |bench| {
+            bench.iter(|| ProofRef::read_from_slice(black_box(&proof_bytes)).unwrap());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(proof_parsing_group, proof_parsing);
+criterion_main!(proof_parsing_group);
@@ -0,0 +1,327 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Python bindings for generating and verifying proofs of the Winterfell examples.
+//!
+//! The bindings expose [ProofOptions](PyProofOptions), the examples of the [examples](../) crate
+//! (via the [Example](PyExample) class), and functions for inspecting serialized proofs. This
+//! makes it possible to drive parameter sweeps (e.g., of proof size and security level against the
+//! number of queries or the blowup factor) from Python.
+//!
+//! A Python module is built from this crate via [maturin](https://www.maturin.rs/) with the
+//! `extension-module` feature enabled; see the README for details.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use examples::{
+    fibonacci, merkle, multiset, range_check, rescue, rescue_raps, vdf, Blake3_192, Blake3_256,
+    Example, Sha3_256,
+};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyBytes};
+use winterfell::{
+    crypto::ElementHasher, math::fields::f128::BaseElement, FieldExtension, Proof, ProofOptions,
+};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Names of the examples which can be instantiated via the [Example](PyExample) class.
+pub const EXAMPLE_NAMES: [&str; 10] = [
+    "fib",
+    "fib8",
+    "mulfib",
+    "mulfib8",
+    "vdf",
+    "rescue",
+    "rescue-raps",
+    "multiset",
+    "range-check",
+    "merkle",
+];
+
+/// Names of the hash functions which can be used with the examples.
+pub const HASH_FN_NAMES: [&str; 3] = ["blake3_192", "blake3_256", "sha3_256"];
+
+// PYTHON MODULE
+// ================================================================================================
+
+#[pymodule]
+fn winterfell_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyProofOptions>()?;
+    m.add_class::<PyExample>()?;
+    m.add_function(wrap_pyfunction!(proof_options, m)?)?;
+    m.add_function(wrap_pyfunction!(security_level, m)?)?;
+    m.add("EXAMPLE_NAMES", EXAMPLE_NAMES.to_vec())?;
+    m.add("HASH_FN_NAMES", HASH_FN_NAMES.to_vec())?;
+    Ok(())
+}
+
+// PROOF OPTIONS
+// ================================================================================================
+
+/// STARK protocol parameters; see [ProofOptions] for details.
+#[pyclass(name = "ProofOptions", module = "winterfell_py", frozen)]
+#[derive(Clone)]
+pub struct PyProofOptions(ProofOptions);
+
+#[pymethods]
+impl PyProofOptions {
+    /// Returns new proof options; `field_extension` is the degree of the extension field (1, 2,
+    /// or 3).
+    ///
+    /// Raises `ValueError` if any of the parameters is invalid.
+    #[new]
+    #[pyo3(signature = (
+        num_queries = 28,
+        blowup_factor = 8,
+        grinding_factor = 16,
+        field_extension = 1,
+        fri_folding_factor = 8,
+        fri_remainder_max_degree = 31,
+    ))]
+    pub fn new(
+        num_queries: usize,
+        blowup_factor: usize,
+        grinding_factor: u32,
+        field_extension: u32,
+        fri_folding_factor: usize,
+        fri_remainder_max_degree: usize,
+    ) -> PyResult<Self> {
+        let field_extension = match field_extension {
+            1 => FieldExtension::None,
+            2 => FieldExtension::Quadratic,
+            3 => FieldExtension::Cubic,
+            val => {
+                return Err(PyValueError::new_err(format!(
+                    "'{val}' is not a valid field extension degree"
+                )))
+            },
+        };
+
+        // proof options constructor panics on invalid parameters
+        catch_unwind(|| {
+            ProofOptions::new(
+                num_queries,
+                blowup_factor,
+                grinding_factor,
+                field_extension,
+                fri_folding_factor,
+                fri_remainder_max_degree,
+            )
+        })
+        .map(Self)
+        .map_err(|err| PyValueError::new_err(panic_message(err)))
+    }
+
+    #[getter]
+    pub fn num_queries(&self) -> usize {
+        self.0.num_queries()
+    }
+
+    #[getter]
+    pub fn blowup_factor(&self) -> usize {
+        self.0.blowup_factor()
+    }
+
+    #[getter]
+    pub fn grinding_factor(&self) -> u32 {
+        self.0.grinding_factor()
+    }
+
+    #[getter]
+    pub fn field_extension(&self) -> u32 {
+        self.0.field_extension().degree()
+    }
+
+    #[getter]
+    pub fn fri_folding_factor(&self) -> usize {
+        self.0.to_fri_options().folding_factor()
+    }
+
+    #[getter]
+    pub fn fri_remainder_max_degree(&self) -> usize {
+        self.0.to_fri_options().remainder_max_degree()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ProofOptions(num_queries={}, blowup_factor={}, grinding_factor={}, \
+             field_extension={}, fri_folding_factor={}, fri_remainder_max_degree={})",
+            self.num_queries(),
+            self.blowup_factor(),
+            self.grinding_factor(),
+            self.field_extension(),
+            self.fri_folding_factor(),
+            self.fri_remainder_max_degree(),
+        )
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+// EXAMPLE
+// ================================================================================================
+
+/// An instance of one of the bundled examples.
+///
+/// The inputs of some examples are generated randomly when the example is instantiated, and
+/// thus, a proof generated by an instance can be verified only by the same instance.
+#[pyclass(name = "Example", module = "winterfell_py", unsendable)]
+pub struct PyExample {
+    name: String,
+    size: usize,
+    hash_fn: String,
+    example: Box<dyn Example>,
+}
+
+#[pymethods]
+impl PyExample {
+    /// Instantiates the example with the specified name (see `EXAMPLE_NAMES`); `size` is the
+    /// size parameter of the example (e.g., the length of the sequence for Fibonacci examples).
+    ///
+    /// Raises `ValueError` if the example or the hash function is not known, or if the example
+    /// cannot be instantiated with the specified parameters.
+    #[new]
+    #[pyo3(signature = (name, size, options, hash_fn = "blake3_256"))]
+    pub fn new(name: &str, size: usize, options: &PyProofOptions, hash_fn: &str) -> PyResult<Self> {
+        let options = options.0.clone();
+        let example = catch_unwind(AssertUnwindSafe(|| match hash_fn {
+            "blake3_192" => build_example::<Blake3_192>(name, size, options),
+            "blake3_256" => build_example::<Blake3_256>(name, size, options),
+            "sha3_256" => build_example::<Sha3_256>(name, size, options),
+            val => Err(format!("'{val}' is not a valid hash function option")),
+        }))
+        .map_err(|err| PyValueError::new_err(panic_message(err)))?
+        .map_err(PyValueError::new_err)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            size,
+            hash_fn: hash_fn.to_string(),
+            example,
+        })
+    }
+
+    /// Generates a proof of the computation described by this example, and returns the proof
+    /// serialized into bytes.
+    pub fn prove<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        let proof = self.example.prove();
+        PyBytes::new_bound(py, &proof.to_bytes())
+    }
+
+    /// Verifies a proof serialized into `proof_bytes` against the inputs of this example.
+    ///
+    /// Raises `ValueError` if the proof could not be parsed or is not valid.
+    pub fn verify(&self, proof_bytes: &[u8]) -> PyResult<()> {
+        let proof = parse_proof(proof_bytes)?;
+        self.example.verify(proof).map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Verifies a proof serialized into `proof_bytes` against inputs different from the inputs of
+    /// this example; this should always fail.
+    ///
+    /// Raises `ValueError` if the proof could not be parsed or is not valid.
+    pub fn verify_with_wrong_inputs(&self, proof_bytes: &[u8]) -> PyResult<()> {
+        let proof = parse_proof(proof_bytes)?;
+        self.example
+            .verify_with_wrong_inputs(proof)
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    #[getter]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[getter]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    #[getter]
+    pub fn hash_fn(&self) -> &str {
+        &self.hash_fn
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Example(name='{}', size={}, hash_fn='{}')", self.name, self.size, self.hash_fn)
+    }
+}
+
+// PROOF INSPECTION
+// ================================================================================================
+
+/// Returns the options which were used to generate a proof serialized into `proof_bytes`.
+///
+/// Raises `ValueError` if the proof could not be parsed.
+#[pyfunction]
+pub fn proof_options(proof_bytes: &[u8]) -> PyResult<PyProofOptions> {
+    Ok(PyProofOptions(parse_proof(proof_bytes)?.options().clone()))
+}
+
+/// Returns the security level (in bits) of a proof serialized into `proof_bytes` generated using
+/// the specified hash function; the proven security level is returned if `conjectured` is false.
+///
+/// Raises `ValueError` if the proof could not be parsed or the hash function is not known.
+#[pyfunction]
+#[pyo3(signature = (proof_bytes, hash_fn = "blake3_256", conjectured = true))]
+pub fn security_level(proof_bytes: &[u8], hash_fn: &str, conjectured: bool) -> PyResult<u32> {
+    let proof = parse_proof(proof_bytes)?;
+    match hash_fn {
+        "blake3_192" => Ok(proof.security_level::<Blake3_192>(conjectured)),
+        "blake3_256" => Ok(proof.security_level::<Blake3_256>(conjectured)),
+        "sha3_256" => Ok(proof.security_level::<Sha3_256>(conjectured)),
+        val => Err(PyValueError::new_err(format!("'{val}' is not a valid hash function option"))),
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Instantiates the example with the specified name using hash function `H`.
+fn build_example<H>(
+    name: &str,
+    size: usize,
+    options: ProofOptions,
+) -> Result<Box<dyn Example>, String>
+where
+    H: ElementHasher<BaseField = BaseElement> + 'static,
+{
+    let example: Box<dyn Example> = match name {
+        "fib" => Box::new(fibonacci::fib2::FibExample::<H>::new(size, options)),
+        "fib8" => Box::new(fibonacci::fib8::Fib8Example::<H>::new(size, options)),
+        "mulfib" => Box::new(fibonacci::mulfib2::MulFib2Example::<H>::new(size, options)),
+        "mulfib8" => Box::new(fibonacci::mulfib8::MulFib8Example::<H>::new(size, options)),
+        "vdf" => Box::new(vdf::regular::VdfExample::<H>::new(size, options)),
+        "rescue" => Box::new(rescue::RescueExample::<H>::new(size, options)),
+        "rescue-raps" => Box::new(rescue_raps::RescueRapsExample::<H>::new(size, options)),
+        "multiset" => Box::new(multiset::MultisetExample::<H>::new(size, options)),
+        "range-check" => Box::new(range_check::RangeCheckExample::<H>::new(size, options)),
+        "merkle" => Box::new(merkle::MerkleExample::<H>::new(size, options)),
+        val => return Err(format!("'{val}' is not a valid example name")),
+    };
+    Ok(example)
+}
+
+fn parse_proof(proof_bytes: &[u8]) -> PyResult<Proof> {
+    Proof::from_bytes(proof_bytes)
+        .map_err(|err| PyValueError::new_err(format!("failed to parse proof: {err}")))
+}
+
+/// Returns the message of a caught panic.
+fn panic_message(err: Box<dyn std::any::Any + Send>) -> String {
+    match err.downcast::<String>() {
+        Ok(message) => *message,
+        Err(err) => match err.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown error".to_string(),
+        },
+    }
+}
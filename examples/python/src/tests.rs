@@ -0,0 +1,40 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use pyo3::prelude::*;
+
+use super::{proof_options, security_level, PyExample, PyProofOptions};
+
+#[test]
+fn prove_and_verify_example() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let options = PyProofOptions::new(28, 8, 0, 2, 4, 7).unwrap();
+        let example = PyExample::new("fib", 1024, &options, "blake3_256").unwrap();
+        let proof_bytes = example.prove(py);
+        let proof_bytes = proof_bytes.as_bytes();
+
+        assert!(example.verify(proof_bytes).is_ok());
+        assert!(example.verify_with_wrong_inputs(proof_bytes).is_err());
+        assert!(example.verify(&proof_bytes[1..]).is_err());
+
+        assert!(proof_options(proof_bytes).unwrap().__eq__(&options));
+        let conjectured = security_level(proof_bytes, "blake3_256", true).unwrap();
+        let proven = security_level(proof_bytes, "blake3_256", false).unwrap();
+        assert!(proven <= conjectured);
+    });
+}
+
+#[test]
+fn invalid_parameters() {
+    pyo3::prepare_freethreaded_python();
+    assert!(PyProofOptions::new(28, 3, 0, 1, 4, 7).is_err());
+    assert!(PyProofOptions::new(28, 8, 0, 4, 4, 7).is_err());
+
+    let options = PyProofOptions::new(28, 8, 0, 1, 4, 7).unwrap();
+    assert!(PyExample::new("fib", 1024, &options, "md5").is_err());
+    assert!(PyExample::new("unknown", 1024, &options, "blake3_256").is_err());
+    assert!(PyExample::new("fib", 1000, &options, "blake3_256").is_err());
+}
@@ -3,6 +3,8 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use std::path::PathBuf;
+
 use structopt::StructOpt;
 use winterfell::{
     crypto::hashers::{Rp64_256, RpJive64_256},
@@ -15,6 +17,12 @@ pub mod fibonacci;
 pub mod lamport;
 #[cfg(feature = "std")]
 pub mod merkle;
+#[cfg(feature = "std")]
+pub mod multiset;
+#[cfg(feature = "std")]
+pub mod proof_aggregation;
+#[cfg(feature = "std")]
+pub mod range_check;
 pub mod rescue;
 #[cfg(feature = "std")]
 pub mod rescue_raps;
@@ -69,6 +77,15 @@ pub struct ExampleOptions {
     /// Folding factor for FRI protocol
     #[structopt(short = "f", long = "folding", default_value = "8")]
     folding_factor: usize,
+
+    /// Skip FRI for short traces by sending the DEEP composition polynomial directly
+    #[structopt(long = "skip_fri")]
+    skip_fri: bool,
+
+    /// File into which the proof is written; if specified, the proof is read back from this file
+    /// before it is verified
+    #[structopt(short = "o", long = "proof_file", parse(from_os_str))]
+    pub proof_file: Option<PathBuf>,
 }
 
 impl ExampleOptions {
@@ -91,17 +108,21 @@ impl ExampleOptions {
             val => panic!("'{val}' is not a valid hash function option"),
         };
 
-        (
-            ProofOptions::new(
-                num_queries,
-                blowup_factor,
-                self.grinding_factor,
-                field_extension,
-                self.folding_factor,
-                31,
-            ),
-            hash_fn,
-        )
+        let options = ProofOptions::new(
+            num_queries,
+            blowup_factor,
+            self.grinding_factor,
+            field_extension,
+            self.folding_factor,
+            31,
+        );
+        let options = if self.skip_fri {
+            options.with_max_fri_remainder_degree(255)
+        } else {
+            options
+        };
+
+        (options, hash_fn)
     }
 
     /// Returns security level of the input proof in bits.
@@ -177,6 +198,21 @@ pub enum ExampleType {
         #[structopt(short = "n", default_value = "1024")]
         chain_length: usize,
     },
+    /// Prove that a sequence of values is a permutation of a sequence of consecutive integers
+    #[cfg(feature = "std")]
+    Multiset {
+        /// Length of the sequence (including one padding value); must be a power of two and at
+        /// least 8
+        #[structopt(short = "n", default_value = "1048576")]
+        sequence_length: usize,
+    },
+    /// Execute a VM which adds 16-bit immediate values range-checked via a LogUp argument
+    #[cfg(feature = "std")]
+    RangeCheck {
+        /// Number of steps to execute; must be one less than a power of two and greater than 256
+        #[structopt(short = "n", default_value = "65535")]
+        num_steps: usize,
+    },
     /// Compute a root of a Merkle path using Rescue hash function
     #[cfg(feature = "std")]
     Merkle {
@@ -184,6 +220,13 @@ pub enum ExampleType {
         #[structopt(short = "n", default_value = "7")]
         tree_depth: usize,
     },
+    /// Aggregate proofs of a small computation by verifying their trace openings in a single proof
+    #[cfg(feature = "std")]
+    ProofAggregation {
+        /// Number of proofs to aggregate
+        #[structopt(short = "n", default_value = "8")]
+        num_proofs: usize,
+    },
     /// Compute an aggregate Lamport+ signature
     #[cfg(feature = "std")]
     LamportA {
@@ -7,7 +7,7 @@ use std::time::Instant;
 
 use examples::{fibonacci, rescue, vdf, ExampleOptions, ExampleType};
 #[cfg(feature = "std")]
-use examples::{lamport, merkle, rescue_raps};
+use examples::{lamport, merkle, multiset, proof_aggregation, range_check, rescue_raps};
 use structopt::StructOpt;
 use tracing::info_span;
 #[cfg(feature = "tracing-forest")]
@@ -73,8 +73,18 @@ fn main() {
             rescue_raps::get_example(&options, chain_length)
         },
         #[cfg(feature = "std")]
+        ExampleType::Multiset { sequence_length } => {
+            multiset::get_example(&options, sequence_length)
+        },
+        #[cfg(feature = "std")]
+        ExampleType::RangeCheck { num_steps } => range_check::get_example(&options, num_steps),
+        #[cfg(feature = "std")]
         ExampleType::Merkle { tree_depth } => merkle::get_example(&options, tree_depth),
         #[cfg(feature = "std")]
+        ExampleType::ProofAggregation { num_proofs } => {
+            proof_aggregation::get_example(&options, num_proofs)
+        },
+        #[cfg(feature = "std")]
         ExampleType::LamportA { num_signatures } => {
             lamport::aggregate::get_example(&options, num_signatures)
         },
@@ -109,6 +119,16 @@ fn main() {
     #[cfg(feature = "std")]
     println!("Proof hash: {}", hex::encode(blake3::hash(&proof_bytes).as_bytes()));
 
+    // write the proof into a file, and read it back from the file
+    let proof_bytes = match options.proof_file.as_ref() {
+        Some(path) => {
+            std::fs::write(path, &proof_bytes).expect("failed to write the proof to a file");
+            println!("Proof written to {}", path.display());
+            std::fs::read(path).expect("failed to read the proof from a file")
+        },
+        None => proof_bytes,
+    };
+
     // verify the proof
     println!("---------------------");
     let parsed_proof = Proof::from_bytes(&proof_bytes).unwrap();
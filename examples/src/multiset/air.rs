@@ -0,0 +1,127 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{
+    math::ToElements, Air, AirContext, Assertion, EvaluationFrame, TraceInfo,
+    TransitionConstraintDegree,
+};
+
+use super::{BaseElement, ExtensionOf, FieldElement, ProofOptions, AUX_TRACE_WIDTH, TRACE_WIDTH};
+use crate::utils::are_equal;
+
+// MULTISET AIR
+// ================================================================================================
+
+pub struct PublicInputs {
+    pub start: BaseElement,
+}
+
+impl ToElements<BaseElement> for PublicInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        vec![self.start]
+    }
+}
+
+/// AIR for proving that the values in the first `n - 1` rows of two main trace columns are equal
+/// as multisets, where `n` is the length of the trace.
+///
+/// The first column `a` must contain consecutive integers starting with the public value, while
+/// the values in the second column `b` are not constrained by the main trace. After the main
+/// trace is committed to, the verifier draws a random element `α`, and the prover builds an
+/// auxiliary column `z` such that:
+///
+/// * `z[0] = 1`,
+/// * `z[i + 1] = z[i] * (α - a[i]) / (α - b[i])`.
+///
+/// The last value of `z` is then the ratio of the polynomials `∏(α - a[i])` and `∏(α - b[i])`.
+/// These polynomials are equal iff the values in both columns are equal as multisets, and thus,
+/// asserting that `z[n - 1] = 1` proves the multiset equality (except with a negligible
+/// probability over the choice of `α`).
+pub struct MultisetAir {
+    context: AirContext<BaseElement>,
+    start: BaseElement,
+}
+
+impl Air for MultisetAir {
+    type BaseField = BaseElement;
+    type PublicInputs = PublicInputs;
+    type GkrProof = ();
+    type GkrVerifier = ();
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        assert_eq!(TRACE_WIDTH, trace_info.main_trace_width());
+        assert_eq!(AUX_TRACE_WIDTH, trace_info.aux_segment_width());
+        let main_degrees = vec![TransitionConstraintDegree::new(1)];
+        let aux_degrees = vec![TransitionConstraintDegree::new(2)];
+        MultisetAir {
+            context: AirContext::new_multi_segment(
+                trace_info,
+                main_degrees,
+                aux_degrees,
+                1,
+                2,
+                None,
+                options,
+            ),
+            start: pub_inputs.start,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        // values in the first column must be consecutive integers
+        result[0] = are_equal(next[0], current[0] + E::ONE);
+    }
+
+    fn evaluate_aux_transition<F, E>(
+        &self,
+        main_frame: &EvaluationFrame<F>,
+        aux_frame: &EvaluationFrame<E>,
+        _periodic_values: &[F],
+        aux_rand_elements: &[E],
+        result: &mut [E],
+    ) where
+        F: FieldElement<BaseField = Self::BaseField>,
+        E: FieldElement<BaseField = Self::BaseField> + ExtensionOf<F>,
+    {
+        let main_current = main_frame.current();
+        let aux_current = aux_frame.current();
+        let aux_next = aux_frame.next();
+        let alpha = aux_rand_elements[0];
+
+        // z[i + 1] * (α - b[i]) = z[i] * (α - a[i])
+        result[0] = are_equal(
+            aux_next[0] * (alpha - main_current[1].into()),
+            aux_current[0] * (alpha - main_current[0].into()),
+        );
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        vec![Assertion::single(0, 0, self.start)]
+    }
+
+    fn get_aux_assertions<E>(&self, _aux_rand_elements: &[E]) -> Vec<Assertion<E>>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        let last_step = self.trace_length() - 1;
+        vec![Assertion::single(0, 0, E::ONE), Assertion::single(0, last_step, E::ONE)]
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::marker::PhantomData;
+use std::time::Instant;
+
+use rand_utils::shuffle;
+use tracing::{field, info_span};
+use winterfell::{
+    crypto::{DefaultRandomCoin, ElementHasher},
+    math::{fields::f128::BaseElement, ExtensionOf, FieldElement},
+    Proof, ProofOptions, Prover, Trace, VerifierError,
+};
+
+use crate::{Blake3_192, Blake3_256, Example, ExampleOptions, HashFunction, Sha3_256};
+
+mod air;
+use air::{MultisetAir, PublicInputs};
+
+mod prover;
+use prover::MultisetProver;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+const TRACE_WIDTH: usize = 2;
+const AUX_TRACE_WIDTH: usize = 1;
+
+// MULTISET EQUALITY EXAMPLE
+// ================================================================================================
+
+pub fn get_example(
+    options: &ExampleOptions,
+    sequence_length: usize,
+) -> Result<Box<dyn Example>, String> {
+    let (options, hash_fn) = options.to_proof_options(42, 4);
+
+    match hash_fn {
+        HashFunction::Blake3_192 => {
+            Ok(Box::new(MultisetExample::<Blake3_192>::new(sequence_length, options)))
+        },
+        HashFunction::Blake3_256 => {
+            Ok(Box::new(MultisetExample::<Blake3_256>::new(sequence_length, options)))
+        },
+        HashFunction::Sha3_256 => {
+            Ok(Box::new(MultisetExample::<Sha3_256>::new(sequence_length, options)))
+        },
+        _ => Err("The specified hash function cannot be used with this example.".to_string()),
+    }
+}
+
+/// Proves that a sequence of values is a permutation of a sequence of consecutive integers.
+///
+/// The main trace consists of two columns: the first column contains consecutive integers
+/// starting with a public value, and the second column contains the same integers in a random
+/// order. The (single) auxiliary column accumulates a running product which is used to prove that
+/// the values in both columns are equal as multisets; see [MultisetAir] for details.
+pub struct MultisetExample<H: ElementHasher> {
+    options: ProofOptions,
+    start: BaseElement,
+    values: Vec<BaseElement>,
+    permuted_values: Vec<BaseElement>,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> MultisetExample<H> {
+    pub fn new(sequence_length: usize, options: ProofOptions) -> Self {
+        assert!(sequence_length.is_power_of_two(), "sequence length must be a power of 2");
+        assert!(sequence_length >= 8, "sequence length must be at least 8");
+
+        // build the sequence of values and its random permutation; the last value of the sequence
+        // is not part of the multiset, and thus, it is not permuted
+        let now = Instant::now();
+        let start = BaseElement::new(42);
+        let values = (0..sequence_length as u64)
+            .map(|i| start + BaseElement::new(i as u128))
+            .collect::<Vec<_>>();
+        let mut permuted_values = values.clone();
+        shuffle(&mut permuted_values[..sequence_length - 1]);
+        println!(
+            "Permuted a sequence of {} values in {} ms",
+            sequence_length - 1,
+            now.elapsed().as_millis()
+        );
+
+        MultisetExample {
+            options,
+            start,
+            values,
+            permuted_values,
+            _hasher: PhantomData,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl<H: ElementHasher> Example for MultisetExample<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    fn prove(&self) -> Proof {
+        println!("Generating proof for permuting a sequence of {} values", self.values.len() - 1);
+
+        // create a prover
+        let prover = MultisetProver::<H>::new(self.options.clone());
+
+        // generate execution trace
+        let trace =
+            info_span!("generate_execution_trace", num_cols = TRACE_WIDTH, steps = field::Empty)
+                .in_scope(|| {
+                    let trace = MultisetProver::<H>::build_trace(
+                        self.values.clone(),
+                        self.permuted_values.clone(),
+                    );
+                    tracing::Span::current().record("steps", trace.length());
+                    trace
+                });
+
+        // generate the proof
+        prover.prove(trace).unwrap()
+    }
+
+    fn verify(&self, proof: Proof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs { start: self.start };
+        let acceptable_options =
+            winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+        winterfell::verify::<MultisetAir, H, DefaultRandomCoin<H>>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+        )
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: Proof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs { start: self.start + BaseElement::ONE };
+        let acceptable_options =
+            winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+        winterfell::verify::<MultisetAir, H, DefaultRandomCoin<H>>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+        )
+    }
+}
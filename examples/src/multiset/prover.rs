@@ -0,0 +1,146 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{
+    math::batch_inversion, matrix::ColMatrix, AuxRandElements, ConstraintCompositionCoefficients,
+    DefaultConstraintEvaluator, DefaultTraceLde, EvaluationFrame, StarkDomain, Trace, TraceInfo,
+    TracePolyTable,
+};
+
+use super::{
+    BaseElement, DefaultRandomCoin, ElementHasher, FieldElement, MultisetAir, PhantomData,
+    ProofOptions, Prover, PublicInputs, AUX_TRACE_WIDTH, TRACE_WIDTH,
+};
+
+// MULTISET TRACE
+// ================================================================================================
+
+/// Main segment of the execution trace for the multiset equality example; the trace is declared
+/// to have a single auxiliary column which is built from a single random element.
+pub struct MultisetTrace {
+    info: TraceInfo,
+    trace: ColMatrix<BaseElement>,
+}
+
+impl MultisetTrace {
+    pub fn new(columns: Vec<Vec<BaseElement>>) -> Self {
+        let length = columns[0].len();
+        Self {
+            info: TraceInfo::new_multi_segment(TRACE_WIDTH, AUX_TRACE_WIDTH, 1, length, vec![]),
+            trace: ColMatrix::new(columns),
+        }
+    }
+}
+
+impl Trace for MultisetTrace {
+    type BaseField = BaseElement;
+
+    fn info(&self) -> &TraceInfo {
+        &self.info
+    }
+
+    fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<Self::BaseField>) {
+        let next_row_idx = (row_idx + 1) % self.info.length();
+        self.trace.read_row_into(row_idx, frame.current_mut());
+        self.trace.read_row_into(next_row_idx, frame.next_mut());
+    }
+
+    fn main_segment(&self) -> &ColMatrix<BaseElement> {
+        &self.trace
+    }
+}
+
+// MULTISET PROVER
+// ================================================================================================
+
+pub struct MultisetProver<H: ElementHasher> {
+    options: ProofOptions,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> MultisetProver<H> {
+    pub fn new(options: ProofOptions) -> Self {
+        Self { options, _hasher: PhantomData }
+    }
+
+    /// Builds the main segment of the execution trace from the sequence of `values` and its
+    /// permutation.
+    pub fn build_trace(
+        values: Vec<BaseElement>,
+        permuted_values: Vec<BaseElement>,
+    ) -> MultisetTrace {
+        debug_assert_eq!(values.len(), permuted_values.len());
+        MultisetTrace::new(vec![values, permuted_values])
+    }
+}
+
+impl<H: ElementHasher> Prover for MultisetProver<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    type BaseField = BaseElement;
+    type Air = MultisetAir;
+    type Trace = MultisetTrace;
+    type HashFn = H;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> PublicInputs {
+        PublicInputs { start: trace.main_segment().get(0, 0) }
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+
+    fn build_aux_trace<E>(
+        &self,
+        trace: &Self::Trace,
+        aux_rand_elements: &AuxRandElements<E>,
+    ) -> ColMatrix<E>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        let main_trace = trace.main_segment();
+        let alpha = aux_rand_elements.rand_elements()[0];
+        let values = main_trace.get_column(0);
+        let permuted_values = main_trace.get_column(1);
+
+        // compute 1 / (α - b[i]) for all rows at once, and then build the running product
+        // z[i + 1] = z[i] * (α - a[i]) / (α - b[i]); the last row of the main trace does not
+        // affect the running product
+        let denominators = permuted_values.iter().map(|&b| alpha - b.into()).collect::<Vec<_>>();
+        let inv_denominators = batch_inversion(&denominators);
+
+        let mut running_product = Vec::with_capacity(main_trace.num_rows());
+        running_product.push(E::ONE);
+        for i in 0..main_trace.num_rows() - 1 {
+            let next = running_product[i] * (alpha - values[i].into()) * inv_denominators[i];
+            running_product.push(next);
+        }
+
+        ColMatrix::new(vec![running_product])
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{FieldExtension, ProofOptions};
+
+use super::Blake3_256;
+
+#[test]
+fn multiset_test_basic_proof_verification() {
+    let multiset_eg =
+        Box::new(super::MultisetExample::<Blake3_256>::new(128, build_options(false)));
+    crate::tests::test_basic_proof_verification(multiset_eg);
+}
+
+#[test]
+fn multiset_test_basic_proof_verification_extension() {
+    let multiset_eg = Box::new(super::MultisetExample::<Blake3_256>::new(128, build_options(true)));
+    crate::tests::test_basic_proof_verification(multiset_eg);
+}
+
+#[test]
+fn multiset_test_basic_proof_verification_fail() {
+    let multiset_eg =
+        Box::new(super::MultisetExample::<Blake3_256>::new(128, build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(multiset_eg);
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, extension, 4, 31)
+}
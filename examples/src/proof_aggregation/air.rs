@@ -0,0 +1,317 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{
+    math::ToElements, Air, AirContext, Assertion, EvaluationFrame, TraceInfo,
+    TransitionConstraintDegree,
+};
+
+use super::{
+    inner, BaseElement, FieldElement, ProofOptions, Rp64_256, TraceOpenings, HASH_CYCLE_LEN,
+    STATE_WIDTH, TRACE_WIDTH,
+};
+use crate::utils::{are_equal, is_binary, EvaluationResult};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Index of the column holding the index of the current node in the Merkle tree.
+pub const INDEX_COL: usize = STATE_WIDTH;
+
+/// Index of the column holding the bit of the node index consumed by the current permutation.
+pub const BIT_COL: usize = STATE_WIDTH + 1;
+
+/// Number of rounds in the Rp64_256 permutation.
+const NUM_HASH_ROUNDS: usize = Rp64_256::NUM_ROUNDS;
+
+/// Columns of the hasher state into which the rate portion of the state starts; the digest is
+/// read from the first 4 elements of the rate.
+const RATE_START: usize = 4;
+
+// PROOF AGGREGATION AIR
+// ================================================================================================
+
+#[derive(Clone)]
+pub struct PublicInputs {
+    /// Trace commitments of the inner proofs.
+    pub trace_roots: Vec<[BaseElement; 4]>,
+    /// Positions queried by the verifier of each inner proof.
+    pub positions: Vec<Vec<usize>>,
+    /// Depth of the Merkle trees of trace commitments of the inner proofs.
+    pub tree_depth: usize,
+}
+
+impl PublicInputs {
+    pub fn new(openings: &[TraceOpenings]) -> Self {
+        PublicInputs {
+            trace_roots: openings.iter().map(|o| o.trace_root).collect(),
+            positions: openings.iter().map(|o| o.positions.clone()).collect(),
+            tree_depth: openings[0].tree_depth(),
+        }
+    }
+}
+
+impl ToElements<BaseElement> for PublicInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        let mut result = vec![BaseElement::from(self.tree_depth as u32)];
+        for (root, positions) in self.trace_roots.iter().zip(self.positions.iter()) {
+            result.extend_from_slice(root);
+            result.push(BaseElement::from(positions.len() as u32));
+            result.extend(positions.iter().map(|&p| BaseElement::from(p as u32)));
+        }
+        result
+    }
+}
+
+/// AIR for verifying openings of trace commitments of many inner proofs.
+///
+/// Every opening occupies a segment of the trace consisting of a power-of-two number of hash
+/// cycles: the first cycle computes the hash of the opened row, the next `tree_depth` cycles
+/// compute the nodes along the Merkle authentication path, and the remaining cycles (if any) are
+/// unused.
+pub struct AggregationAir {
+    context: AirContext<BaseElement>,
+    trace_roots: Vec<[BaseElement; 4]>,
+    positions: Vec<Vec<usize>>,
+    tree_depth: usize,
+    opening_len: usize,
+}
+
+impl Air for AggregationAir {
+    type BaseField = BaseElement;
+    type PublicInputs = PublicInputs;
+    type GkrProof = ();
+    type GkrVerifier = ();
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        let opening_len = get_opening_len(pub_inputs.tree_depth);
+
+        let mut degrees =
+            vec![TransitionConstraintDegree::with_cycles(7, vec![HASH_CYCLE_LEN]); STATE_WIDTH];
+        degrees.extend(vec![TransitionConstraintDegree::with_cycles(2, vec![opening_len]); 4]);
+        degrees.push(TransitionConstraintDegree::with_cycles(1, vec![opening_len]));
+        degrees.push(TransitionConstraintDegree::with_cycles(1, vec![HASH_CYCLE_LEN]));
+        degrees.push(TransitionConstraintDegree::new(2));
+
+        let num_assertions =
+            (STATE_WIDTH - inner::TRACE_WIDTH) + pub_inputs.tree_depth * RATE_START + 6;
+
+        assert_eq!(TRACE_WIDTH, trace_info.width());
+        AggregationAir {
+            context: AirContext::new(trace_info, degrees, num_assertions, options),
+            trace_roots: pub_inputs.trace_roots,
+            positions: pub_inputs.positions,
+            tree_depth: pub_inputs.tree_depth,
+            opening_len,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        // split periodic values into masks and round constants
+        let round_flag = periodic_values[0];
+        let merge_flag = periodic_values[1];
+        let ark = &periodic_values[2..];
+
+        // when round_flag = 1, constraints for Rp64_256 round are enforced
+        enforce_round(result, &current[..STATE_WIDTH], &next[..STATE_WIDTH], ark, round_flag);
+
+        // when merge_flag = 1, the next permutation merges the current digest with its sibling;
+        // the current digest must be placed into the first half of the rate when the index bit
+        // of the next permutation is 0, and into the second half otherwise
+        let bit = next[BIT_COL];
+        for i in 0..4 {
+            let left = next[RATE_START + i];
+            let right = next[RATE_START + 4 + i];
+            let digest = current[RATE_START + i];
+            result.agg_constraint(
+                STATE_WIDTH + i,
+                merge_flag,
+                are_equal(left + bit * (right - left), digest),
+            );
+        }
+
+        // node index and index bit do not change within a permutation; when moving to the parent
+        // node, the index must be halved with the index bit being the remainder
+        let index = current[INDEX_COL];
+        let next_index = next[INDEX_COL];
+        result.agg_constraint(STATE_WIDTH + 4, round_flag, are_equal(next_index, index));
+        result.agg_constraint(
+            STATE_WIDTH + 4,
+            merge_flag,
+            are_equal(index, next_index.double() + bit),
+        );
+        result.agg_constraint(
+            STATE_WIDTH + 5,
+            round_flag,
+            are_equal(next[BIT_COL], current[BIT_COL]),
+        );
+
+        // index bits must always be binary
+        result[STATE_WIDTH + 6] = is_binary(current[BIT_COL]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let mut assertions = Vec::new();
+
+        // the first permutation of every opening hashes a trace row: the first capacity element
+        // is set to the number of hashed elements, and the unused part of the rate is zero
+        let leaf_len = BaseElement::from(inner::TRACE_WIDTH as u32);
+        assertions.push(Assertion::periodic(0, 0, self.opening_len, leaf_len));
+        for col in (1..RATE_START).chain(RATE_START + inner::TRACE_WIDTH..STATE_WIDTH) {
+            assertions.push(Assertion::periodic(col, 0, self.opening_len, BaseElement::ZERO));
+        }
+
+        // the next permutations merge 2 digests: the first capacity element is set to 8
+        let merge_len = BaseElement::from(8u32);
+        for i in 1..=self.tree_depth {
+            let step = i * HASH_CYCLE_LEN;
+            assertions.push(Assertion::periodic(0, step, self.opening_len, merge_len));
+            for col in 1..RATE_START {
+                assertions.push(Assertion::periodic(
+                    col,
+                    step,
+                    self.opening_len,
+                    BaseElement::ZERO,
+                ));
+            }
+        }
+
+        // every opening starts at the queried position, and ends at the trace commitment; the
+        // openings which pad the trace to a power of two repeat the last opening
+        let num_openings = self.trace_length() / self.opening_len;
+        let mut positions = Vec::with_capacity(num_openings);
+        let mut roots = Vec::with_capacity(num_openings);
+        for (root, root_positions) in self.trace_roots.iter().zip(self.positions.iter()) {
+            for &position in root_positions {
+                positions.push(BaseElement::from(position as u32));
+                roots.push(*root);
+            }
+        }
+        positions.resize(num_openings, *positions.last().expect("no openings"));
+        roots.resize(num_openings, *roots.last().expect("no openings"));
+
+        let end = self.tree_depth * HASH_CYCLE_LEN;
+        assertions.push(Assertion::sequence(INDEX_COL, 0, self.opening_len, positions));
+        assertions.push(Assertion::periodic(INDEX_COL, end, self.opening_len, BaseElement::ZERO));
+        for i in 0..4 {
+            let values = roots.iter().map(|root| root[i]).collect();
+            assertions.push(Assertion::sequence(
+                RATE_START + i,
+                end + NUM_HASH_ROUNDS,
+                self.opening_len,
+                values,
+            ));
+        }
+
+        assertions
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        let mut round_mask = vec![BaseElement::ONE; HASH_CYCLE_LEN];
+        round_mask[NUM_HASH_ROUNDS] = BaseElement::ZERO;
+
+        let mut merge_mask = vec![BaseElement::ZERO; self.opening_len];
+        for i in 0..self.tree_depth {
+            merge_mask[i * HASH_CYCLE_LEN + NUM_HASH_ROUNDS] = BaseElement::ONE;
+        }
+
+        let mut result = vec![round_mask, merge_mask];
+        result.append(&mut get_round_constants());
+        result
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of trace rows occupied by a single opening of a Merkle tree of the
+/// specified depth.
+pub fn get_opening_len(tree_depth: usize) -> usize {
+    (tree_depth + 1).next_power_of_two() * HASH_CYCLE_LEN
+}
+
+/// Returns Rp64_256 round constants arranged in column-major form; the constants for the last
+/// step of every hash cycle are zeros.
+fn get_round_constants() -> Vec<Vec<BaseElement>> {
+    let mut constants = vec![vec![BaseElement::ZERO; HASH_CYCLE_LEN]; STATE_WIDTH * 2];
+    for (round, (ark1, ark2)) in Rp64_256::ARK1.iter().zip(Rp64_256::ARK2.iter()).enumerate() {
+        for (i, (&k1, &k2)) in ark1.iter().zip(ark2.iter()).enumerate() {
+            constants[i][round] = k1;
+            constants[STATE_WIDTH + i][round] = k2;
+        }
+    }
+    constants
+}
+
+/// When flag = 1, enforces that the next state is the result of applying a single Rp64_256
+/// round to the current state.
+///
+/// The round is split in the middle: the state after the first half of the round is computed
+/// from the current state, and it is compared to the state obtained by inverting the second
+/// half of the round on the next state; this keeps the degree of the constraints at 7.
+fn enforce_round<E: FieldElement + From<BaseElement>>(
+    result: &mut [E],
+    current: &[E],
+    next: &[E],
+    ark: &[E],
+    flag: E,
+) {
+    // apply the first half of the round to the current state
+    let mut step1 = [E::ZERO; STATE_WIDTH];
+    step1.copy_from_slice(current);
+    step1.iter_mut().for_each(|v| *v = exp7(*v));
+    let mut step1 = apply_mds(&step1, &Rp64_256::MDS);
+    for i in 0..STATE_WIDTH {
+        step1[i] += ark[i];
+    }
+
+    // invert the second half of the round on the next state
+    let mut step2 = [E::ZERO; STATE_WIDTH];
+    for i in 0..STATE_WIDTH {
+        step2[i] = next[i] - ark[STATE_WIDTH + i];
+    }
+    let mut step2 = apply_mds(&step2, &Rp64_256::INV_MDS);
+    step2.iter_mut().for_each(|v| *v = exp7(*v));
+
+    for i in 0..STATE_WIDTH {
+        result.agg_constraint(i, flag, are_equal(step2[i], step1[i]));
+    }
+}
+
+fn apply_mds<E: FieldElement + From<BaseElement>>(
+    state: &[E; STATE_WIDTH],
+    matrix: &[[BaseElement; STATE_WIDTH]; STATE_WIDTH],
+) -> [E; STATE_WIDTH] {
+    let mut result = [E::ZERO; STATE_WIDTH];
+    for (row, value) in matrix.iter().zip(result.iter_mut()) {
+        for (&m, &s) in row.iter().zip(state.iter()) {
+            *value += E::from(m) * s;
+        }
+    }
+    result
+}
+
+#[inline(always)]
+fn exp7<E: FieldElement>(x: E) -> E {
+    let x2 = x.square();
+    let x4 = x2.square();
+    x4 * x2 * x
+}
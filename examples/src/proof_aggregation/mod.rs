@@ -0,0 +1,213 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Aggregation of many small proofs into a single proof.
+//!
+//! In this example, a number of proofs of a small computation (a Fibonacci sequence starting from
+//! a distinct seed, see [inner]) are generated using [Rp64_256] hash function. Then, a single
+//! outer proof is generated which attests that, for every inner proof, the trace rows at all
+//! positions queried by the verifier of the inner proof open to the trace commitment of that
+//! proof. That is, the outer AIR re-computes all leaf hashes and Merkle authentication paths for
+//! the queried trace rows of the inner proofs using the round function of Rp64_256, which is
+//! possible only because the hash function is algebraic.
+//!
+//! The data which the outer AIR consumes is extracted from the inner proofs by [TraceOpenings];
+//! the query positions are taken from the [VerifierTrace](winterfell::VerifierTrace) returned by
+//! [verify_with_trace()](winterfell::verify_with_trace).
+//!
+//! # Limitations
+//! The outer proof covers only the part of the verification of the inner proofs which is the
+//! most expensive one to perform in a circuit: checking Merkle authentication paths for the
+//! trace queries. To verify inner proofs fully inside the outer AIR, the following is still
+//! needed:
+//! * The public coin of the inner proofs must be re-computed in the outer AIR; this requires
+//!   reseeding and drawing logic of [DefaultRandomCoin] to be exposed as a hash-function-level
+//!   specification (e.g., the exact sequence of permutations a draw invokes), so that an AIR can
+//!   mirror it. Until then, the query positions are passed to the outer AIR as public inputs.
+//! * Constraints of the inner AIR must be evaluated at the out-of-domain point inside the outer
+//!   AIR; this requires a way to evaluate transition constraints of an [Air](winterfell::Air)
+//!   symbolically, rather than only over concrete field elements.
+//! * Queried values of the constraint composition polynomial and of all FRI layers, together
+//!   with their Merkle authentication paths, must be exposed by the verifier in the same way as
+//!   the trace query positions are exposed via [VerifierTrace](winterfell::VerifierTrace) (the
+//!   openings are currently parsed from the proof directly, as done by [TraceOpenings]).
+//! * Arithmetic in the extension field used by the inner proofs must be emulated in the outer
+//!   AIR.
+
+use core::marker::PhantomData;
+use std::time::Instant;
+
+use tracing::{field, info_span};
+use winterfell::{
+    crypto::{DefaultRandomCoin, ElementHasher},
+    math::{fields::f64::BaseElement, FieldElement},
+    FieldExtension, Proof, ProofOptions, Prover, Trace, VerifierError,
+};
+
+use crate::{Example, ExampleOptions, HashFunction};
+
+pub mod inner;
+use inner::InnerProver;
+
+mod openings;
+pub use openings::TraceOpenings;
+
+mod air;
+use air::{AggregationAir, PublicInputs};
+
+mod prover;
+use prover::AggregationProver;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS AND TYPES
+// ================================================================================================
+
+/// Width of the Rp64_256 hasher state.
+const STATE_WIDTH: usize = 12;
+
+/// Number of rows in the outer trace occupied by a single Rp64_256 permutation; the permutation
+/// consists of 7 rounds, and the last row of each cycle holds the output of the permutation.
+const HASH_CYCLE_LEN: usize = 8;
+
+/// Number of columns in the outer trace: hasher state, node index, and index bit.
+const TRACE_WIDTH: usize = STATE_WIDTH + 2;
+
+/// Length of the trace of each inner proof.
+const INNER_TRACE_LENGTH: usize = 16;
+
+type Blake3_256 = winterfell::crypto::hashers::Blake3_256<BaseElement>;
+type Sha3_256 = winterfell::crypto::hashers::Sha3_256<BaseElement>;
+type Rp64_256 = winterfell::crypto::hashers::Rp64_256;
+type RpJive64_256 = winterfell::crypto::hashers::RpJive64_256;
+
+// PROOF AGGREGATION EXAMPLE
+// ================================================================================================
+
+pub fn get_example(
+    options: &ExampleOptions,
+    num_proofs: usize,
+) -> Result<Box<dyn Example>, String> {
+    let (options, hash_fn) = options.to_proof_options(28, 8);
+
+    match hash_fn {
+        HashFunction::Blake3_256 => {
+            Ok(Box::new(ProofAggregationExample::<Blake3_256>::new(num_proofs, options)))
+        },
+        HashFunction::Sha3_256 => {
+            Ok(Box::new(ProofAggregationExample::<Sha3_256>::new(num_proofs, options)))
+        },
+        HashFunction::Rp64_256 => {
+            Ok(Box::new(ProofAggregationExample::<Rp64_256>::new(num_proofs, options)))
+        },
+        HashFunction::RpJive64_256 => {
+            Ok(Box::new(ProofAggregationExample::<RpJive64_256>::new(num_proofs, options)))
+        },
+        _ => Err("The specified hash function cannot be used with this example.".to_string()),
+    }
+}
+
+pub struct ProofAggregationExample<H: ElementHasher> {
+    options: ProofOptions,
+    openings: Vec<TraceOpenings>,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> ProofAggregationExample<H> {
+    pub fn new(num_proofs: usize, options: ProofOptions) -> Self {
+        assert!(num_proofs > 0, "number of proofs must be greater than zero");
+
+        // generate inner proofs; every proof starts the sequence from a distinct seed
+        let now = Instant::now();
+        let inner_options = inner_proof_options();
+        let inner_prover = InnerProver::new(inner_options);
+        let proofs = (0..num_proofs)
+            .map(|i| {
+                let seed = [BaseElement::from(i as u32), BaseElement::ONE];
+                let trace = inner_prover.build_trace(seed, INNER_TRACE_LENGTH);
+                let pub_inputs = inner_prover.get_pub_inputs(&trace);
+                (inner_prover.prove(trace).unwrap(), pub_inputs)
+            })
+            .collect::<Vec<_>>();
+        println!("Generated {} inner proofs in {} ms", num_proofs, now.elapsed().as_millis());
+
+        // verify inner proofs and extract the openings of trace queries from them
+        let now = Instant::now();
+        let openings = proofs
+            .into_iter()
+            .map(|(proof, pub_inputs)| TraceOpenings::new(proof, pub_inputs).unwrap())
+            .collect::<Vec<_>>();
+        println!(
+            "Verified inner proofs and extracted {} trace openings in {} ms",
+            openings.iter().map(TraceOpenings::num_openings).sum::<usize>(),
+            now.elapsed().as_millis()
+        );
+
+        ProofAggregationExample { options, openings, _hasher: PhantomData }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl<H: ElementHasher> Example for ProofAggregationExample<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    fn prove(&self) -> Proof {
+        println!("Generating proof for aggregating {} inner proofs", self.openings.len());
+
+        // create a prover
+        let prover = AggregationProver::<H>::new(&self.openings, self.options.clone());
+
+        // generate execution trace
+        let trace =
+            info_span!("generate_execution_trace", num_cols = TRACE_WIDTH, steps = field::Empty)
+                .in_scope(|| {
+                    let trace = prover.build_trace(&self.openings);
+                    tracing::Span::current().record("steps", trace.length());
+                    trace
+                });
+
+        // generate the proof
+        prover.prove(trace).unwrap()
+    }
+
+    fn verify(&self, proof: Proof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs::new(&self.openings);
+        let acceptable_options =
+            winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        winterfell::verify::<AggregationAir, H, DefaultRandomCoin<H>>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+        )
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: Proof) -> Result<(), VerifierError> {
+        let mut pub_inputs = PublicInputs::new(&self.openings);
+        pub_inputs.positions[0][0] ^= 1;
+        let acceptable_options =
+            winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        winterfell::verify::<AggregationAir, H, DefaultRandomCoin<H>>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+        )
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the options used to generate inner proofs.
+///
+/// The trace of an inner proof is committed to with a blowup factor of 8, and thus, the Merkle
+/// tree of the trace commitment has depth 7. Together with the leaf hash, every opening takes 8
+/// permutations, which makes the number of rows per opening in the outer trace a power of two.
+fn inner_proof_options() -> ProofOptions {
+    ProofOptions::new(16, 8, 0, FieldExtension::Quadratic, 4, 7)
+}
@@ -0,0 +1,100 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{
+    crypto::Hasher, math::fields::QuadExtension, AcceptableOptions, Proof, VerifierError,
+};
+
+use super::{
+    inner::{InnerAir, PublicInputs, TRACE_WIDTH},
+    inner_proof_options, BaseElement, DefaultRandomCoin, Rp64_256,
+};
+
+// TRACE OPENINGS
+// ================================================================================================
+
+/// Openings of the trace commitment of an inner proof at the positions queried by the verifier.
+///
+/// This contains all the data which the outer AIR needs to re-compute the trace commitment from
+/// the queried trace rows: for every query position, the queried row of the trace and the Merkle
+/// authentication path from the hash of the row to the trace commitment.
+#[derive(Debug, Clone)]
+pub struct TraceOpenings {
+    /// Commitment to the main trace segment of the inner proof.
+    pub trace_root: [BaseElement; 4],
+    /// Sorted and deduplicated positions in the LDE domain queried by the verifier.
+    pub positions: Vec<usize>,
+    /// Trace rows at the queried positions.
+    pub rows: Vec<[BaseElement; TRACE_WIDTH]>,
+    /// Sibling nodes along the Merkle authentication path from each queried row to the trace
+    /// commitment, starting with the sibling of the leaf.
+    pub paths: Vec<Vec<[BaseElement; 4]>>,
+}
+
+impl TraceOpenings {
+    /// Verifies the specified inner proof against the specified public inputs, and extracts
+    /// openings of the trace commitment from the proof.
+    ///
+    /// The query positions are taken from the intermediate values computed by the verifier, and
+    /// the opened rows and authentication paths are parsed from the proof itself.
+    ///
+    /// # Errors
+    /// Returns an error if the proof is not valid.
+    pub fn new(proof: Proof, pub_inputs: PublicInputs) -> Result<Self, VerifierError> {
+        let acceptable_options = AcceptableOptions::OptionSet(vec![inner_proof_options()]);
+        let verifier_trace = winterfell::verify_with_trace::<
+            InnerAir,
+            QuadExtension<BaseElement>,
+            Rp64_256,
+            DefaultRandomCoin<Rp64_256>,
+        >(proof.clone(), pub_inputs, &acceptable_options)?;
+        let positions = verifier_trace.query_positions;
+
+        // parse the trace commitment; the proof has already been verified, and thus, parsing
+        // errors are not expected here
+        let lde_domain_size = proof.lde_domain_size();
+        let num_fri_layers = proof.options().to_fri_options().num_fri_layers(lde_domain_size);
+        let (trace_roots, ..) = proof
+            .commitments
+            .parse::<Rp64_256>(1, num_fri_layers)
+            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+
+        // parse the queried rows and split the batch Merkle proof into individual paths
+        let (merkle_proof, table) = proof.trace_queries[0]
+            .clone()
+            .parse::<Rp64_256, BaseElement>(lde_domain_size, positions.len(), TRACE_WIDTH)
+            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+        let paths = merkle_proof
+            .into_paths(&positions)
+            .map_err(|_| VerifierError::TraceQueryDoesNotMatchCommitment)?;
+
+        Ok(TraceOpenings {
+            trace_root: digest_to_elements(&trace_roots[0]),
+            rows: table.rows().map(|row| row.try_into().unwrap()).collect(),
+            paths: paths
+                .iter()
+                .map(|path| path[1..].iter().map(digest_to_elements).collect())
+                .collect(),
+            positions,
+        })
+    }
+
+    /// Returns the number of openings.
+    pub fn num_openings(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns the depth of the Merkle tree of the trace commitment.
+    pub fn tree_depth(&self) -> usize {
+        self.paths[0].len()
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn digest_to_elements(digest: &<Rp64_256 as Hasher>::Digest) -> [BaseElement; 4] {
+    digest.as_elements().try_into().unwrap()
+}
@@ -0,0 +1,174 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{
+    matrix::ColMatrix, AuxRandElements, ConstraintCompositionCoefficients,
+    DefaultConstraintEvaluator, DefaultTraceLde, StarkDomain, TraceInfo, TracePolyTable,
+    TraceTable,
+};
+
+use super::{
+    air::{get_opening_len, AggregationAir, PublicInputs, BIT_COL, INDEX_COL},
+    inner, BaseElement, DefaultRandomCoin, ElementHasher, FieldElement, PhantomData, ProofOptions,
+    Prover, Rp64_256, TraceOpenings, HASH_CYCLE_LEN, STATE_WIDTH, TRACE_WIDTH,
+};
+
+// PROOF AGGREGATION PROVER
+// ================================================================================================
+
+pub struct AggregationProver<H: ElementHasher> {
+    pub_inputs: PublicInputs,
+    options: ProofOptions,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> AggregationProver<H> {
+    pub fn new(openings: &[TraceOpenings], options: ProofOptions) -> Self {
+        Self {
+            pub_inputs: PublicInputs::new(openings),
+            options,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Builds an execution trace which re-computes the trace commitments of the inner proofs
+    /// from the specified openings.
+    ///
+    /// To make the length of the trace a power of two, the last opening is repeated as many
+    /// times as needed; the repeated openings are not bound to any public inputs.
+    pub fn build_trace(&self, openings: &[TraceOpenings]) -> TraceTable<BaseElement> {
+        let tree_depth = openings[0].tree_depth();
+        assert!(
+            openings.iter().all(|o| o.tree_depth() == tree_depth),
+            "all inner proofs must have trace commitments of the same depth"
+        );
+
+        let mut segments = Vec::new();
+        for opening in openings {
+            for i in 0..opening.num_openings() {
+                segments.push(build_opening_segment(opening, i));
+            }
+        }
+        let num_segments = segments.len().next_power_of_two();
+        let last_segment = segments.last().expect("no openings").clone();
+        segments.resize(num_segments, last_segment);
+
+        let opening_len = get_opening_len(tree_depth);
+        let mut trace = TraceTable::new(TRACE_WIDTH, num_segments * opening_len);
+        for (i, segment) in segments.iter().enumerate() {
+            for (j, row) in segment.iter().enumerate() {
+                trace.update_row(i * opening_len + j, row);
+            }
+        }
+
+        trace
+    }
+}
+
+impl<H: ElementHasher> Prover for AggregationProver<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    type BaseField = BaseElement;
+    type Air = AggregationAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = H;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, _trace: &Self::Trace) -> PublicInputs {
+        self.pub_inputs.clone()
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Builds the rows of the trace which re-compute the trace commitment from the opening at the
+/// specified index.
+fn build_opening_segment(opening: &TraceOpenings, index: usize) -> Vec<[BaseElement; TRACE_WIDTH]> {
+    let tree_depth = opening.tree_depth();
+    let opening_len = get_opening_len(tree_depth);
+    let position = opening.positions[index];
+    let mut rows = Vec::with_capacity(opening_len);
+
+    // hash the opened row
+    let mut state = [BaseElement::ZERO; STATE_WIDTH];
+    state[0] = BaseElement::from(inner::TRACE_WIDTH as u32);
+    state[4..4 + inner::TRACE_WIDTH].copy_from_slice(&opening.rows[index]);
+    append_permutation(&mut rows, &mut state, position, 0);
+
+    // merge the digest with the nodes along the authentication path
+    for (i, sibling) in opening.paths[index].iter().enumerate() {
+        let bit = (position >> i) & 1;
+        let digest: [BaseElement; 4] = state[4..8].try_into().unwrap();
+        let (left, right) = if bit == 0 {
+            (digest, *sibling)
+        } else {
+            (*sibling, digest)
+        };
+        state = [BaseElement::ZERO; STATE_WIDTH];
+        state[0] = BaseElement::from(8u32);
+        state[4..8].copy_from_slice(&left);
+        state[8..12].copy_from_slice(&right);
+        append_permutation(&mut rows, &mut state, position >> (i + 1), bit);
+    }
+    debug_assert_eq!(opening.trace_root, state[4..8], "opening does not match commitment");
+
+    // fill the unused hash cycles by continuing to apply the permutation
+    let bit = (position >> (tree_depth - 1)) & 1;
+    while rows.len() < opening_len {
+        append_permutation(&mut rows, &mut state, 0, bit);
+    }
+
+    rows
+}
+
+/// Appends the rows for applying Rp64_256 permutation to the specified state.
+fn append_permutation(
+    rows: &mut Vec<[BaseElement; TRACE_WIDTH]>,
+    state: &mut [BaseElement; STATE_WIDTH],
+    index: usize,
+    bit: usize,
+) {
+    let mut push_row = |state: &[BaseElement; STATE_WIDTH]| {
+        let mut row = [BaseElement::ZERO; TRACE_WIDTH];
+        row[..STATE_WIDTH].copy_from_slice(state);
+        row[INDEX_COL] = BaseElement::from(index as u32);
+        row[BIT_COL] = BaseElement::from(bit as u32);
+        rows.push(row);
+    };
+
+    push_row(state);
+    for round in 0..Rp64_256::NUM_ROUNDS {
+        Rp64_256::apply_round(state, round);
+        push_row(state);
+    }
+    debug_assert_eq!(rows.len() % HASH_CYCLE_LEN, 0);
+}
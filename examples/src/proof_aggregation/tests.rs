@@ -0,0 +1,24 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{FieldExtension, ProofOptions};
+
+use super::Rp64_256;
+
+#[test]
+fn proof_aggregation_test_basic_proof_verification() {
+    let example = Box::new(super::ProofAggregationExample::<Rp64_256>::new(2, build_options()));
+    crate::tests::test_basic_proof_verification(example);
+}
+
+#[test]
+fn proof_aggregation_test_basic_proof_verification_fail() {
+    let example = Box::new(super::ProofAggregationExample::<Rp64_256>::new(2, build_options()));
+    crate::tests::test_basic_proof_verification_fail(example);
+}
+
+fn build_options() -> ProofOptions {
+    ProofOptions::new(28, 8, 0, FieldExtension::Quadratic, 4, 31)
+}
@@ -0,0 +1,172 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{
+    math::ToElements, Air, AirContext, Assertion, EvaluationFrame, TraceInfo,
+    TransitionConstraintDegree,
+};
+
+use super::{
+    BaseElement, ExtensionOf, FieldElement, ProofOptions, AUX_TRACE_WIDTH, LIMB_BITS, TABLE_SIZE,
+    TRACE_WIDTH,
+};
+use crate::utils::are_equal;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Column holding the accumulator of the VM.
+pub const ACC_COL: usize = 0;
+/// Column holding the immediate value added to the accumulator at a given step.
+pub const IMM_COL: usize = 1;
+/// Column holding the lower limb of the immediate value.
+pub const LO_COL: usize = 2;
+/// Column holding the upper limb of the immediate value.
+pub const HI_COL: usize = 3;
+/// Column holding the values of the lookup table.
+pub const TABLE_COL: usize = 4;
+/// Column holding the number of times a table value is looked up.
+pub const MULTIPLICITY_COL: usize = 5;
+
+// RANGE CHECK AIR
+// ================================================================================================
+
+pub struct PublicInputs {
+    pub result: BaseElement,
+}
+
+impl ToElements<BaseElement> for PublicInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        vec![self.result]
+    }
+}
+
+/// AIR for a VM which adds a range-checked immediate value `v` to its accumulator at every step
+/// (the last row of the trace holds the result, and thus, its immediate value is ignored).
+///
+/// The main trace enforces that `v = lo + 2^8 * hi`, and that the table column `t` starts at 0,
+/// ends at 255, and increases by either 0 or 1 at every step (thus, it contains every 8-bit value
+/// at least once). The prover also provides a multiplicity column `m` which records how many times
+/// each table value is looked up.
+///
+/// After the main trace is committed to, the verifier draws a random element `α`, and the prover
+/// builds an auxiliary column `s` which accumulates the LogUp sum:
+///
+/// * `s[0] = 0`,
+/// * `s[i + 1] = s[i] + 1 / (α - lo[i]) + 1 / (α - hi[i]) - m[i] / (α - t[i])`.
+///
+/// Asserting that `s[n - 1] = 0` proves that all limbs are contained in the table (except with a
+/// negligible probability over the choice of `α`), and thus, that all immediate values fit into
+/// 16 bits.
+pub struct RangeCheckAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for RangeCheckAir {
+    type BaseField = BaseElement;
+    type PublicInputs = PublicInputs;
+    type GkrProof = ();
+    type GkrVerifier = ();
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        assert_eq!(TRACE_WIDTH, trace_info.main_trace_width());
+        assert_eq!(AUX_TRACE_WIDTH, trace_info.aux_segment_width());
+        let main_degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(2),
+        ];
+        let aux_degrees = vec![TransitionConstraintDegree::new(4)];
+        RangeCheckAir {
+            context: AirContext::new_multi_segment(
+                trace_info,
+                main_degrees,
+                aux_degrees,
+                4,
+                2,
+                None,
+                options,
+            ),
+            result: pub_inputs.result,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        // the immediate value is added to the accumulator
+        result[0] = are_equal(next[ACC_COL], current[ACC_COL] + current[IMM_COL]);
+
+        // the immediate value is composed of two limbs
+        let limb_base = E::from(1u32 << LIMB_BITS);
+        result[1] = are_equal(current[IMM_COL], current[LO_COL] + limb_base * current[HI_COL]);
+
+        // the table column increases by either 0 or 1
+        let delta = next[TABLE_COL] - current[TABLE_COL];
+        result[2] = delta * (delta - E::ONE);
+    }
+
+    fn evaluate_aux_transition<F, E>(
+        &self,
+        main_frame: &EvaluationFrame<F>,
+        aux_frame: &EvaluationFrame<E>,
+        _periodic_values: &[F],
+        aux_rand_elements: &[E],
+        result: &mut [E],
+    ) where
+        F: FieldElement<BaseField = Self::BaseField>,
+        E: FieldElement<BaseField = Self::BaseField> + ExtensionOf<F>,
+    {
+        let main_current = main_frame.current();
+        let aux_current = aux_frame.current();
+        let aux_next = aux_frame.next();
+        let alpha = aux_rand_elements[0];
+
+        let lo = alpha - main_current[LO_COL].into();
+        let hi = alpha - main_current[HI_COL].into();
+        let table = alpha - main_current[TABLE_COL].into();
+        let multiplicity: E = main_current[MULTIPLICITY_COL].into();
+
+        // (s[i + 1] - s[i]) * (α - lo) * (α - hi) * (α - t)
+        //   = (α - hi) * (α - t) + (α - lo) * (α - t) - m * (α - lo) * (α - hi)
+        result[0] = are_equal(
+            (aux_next[0] - aux_current[0]) * lo * hi * table,
+            hi * table + lo * table - multiplicity * lo * hi,
+        );
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(ACC_COL, 0, BaseElement::ZERO),
+            Assertion::single(ACC_COL, last_step, self.result),
+            Assertion::single(TABLE_COL, 0, BaseElement::ZERO),
+            Assertion::single(TABLE_COL, last_step, BaseElement::from(TABLE_SIZE as u32 - 1)),
+        ]
+    }
+
+    fn get_aux_assertions<E>(&self, _aux_rand_elements: &[E]) -> Vec<Assertion<E>>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        let last_step = self.trace_length() - 1;
+        vec![Assertion::single(0, 0, E::ZERO), Assertion::single(0, last_step, E::ZERO)]
+    }
+}
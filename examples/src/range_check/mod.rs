@@ -0,0 +1,162 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::marker::PhantomData;
+use std::time::Instant;
+
+use rand_utils::rand_value;
+use tracing::{field, info_span};
+use winterfell::{
+    crypto::{DefaultRandomCoin, ElementHasher},
+    math::{fields::f128::BaseElement, ExtensionOf, FieldElement},
+    Proof, ProofOptions, Prover, Trace, VerifierError,
+};
+
+use crate::{Blake3_192, Blake3_256, Example, ExampleOptions, HashFunction, Sha3_256};
+
+mod air;
+use air::{PublicInputs, RangeCheckAir};
+
+mod prover;
+use prover::RangeCheckProver;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+const TRACE_WIDTH: usize = 6;
+const AUX_TRACE_WIDTH: usize = 1;
+
+/// Number of bits in each limb of a range-checked value.
+const LIMB_BITS: u32 = 8;
+
+/// Number of values in the lookup table; each limb must be one of these values.
+const TABLE_SIZE: usize = 1 << LIMB_BITS;
+
+// RANGE-CHECKED VM EXAMPLE
+// ================================================================================================
+
+pub fn get_example(options: &ExampleOptions, num_steps: usize) -> Result<Box<dyn Example>, String> {
+    let (options, hash_fn) = options.to_proof_options(42, 8);
+
+    match hash_fn {
+        HashFunction::Blake3_192 => {
+            Ok(Box::new(RangeCheckExample::<Blake3_192>::new(num_steps, options)))
+        },
+        HashFunction::Blake3_256 => {
+            Ok(Box::new(RangeCheckExample::<Blake3_256>::new(num_steps, options)))
+        },
+        HashFunction::Sha3_256 => {
+            Ok(Box::new(RangeCheckExample::<Sha3_256>::new(num_steps, options)))
+        },
+        _ => Err("The specified hash function cannot be used with this example.".to_string()),
+    }
+}
+
+/// Proves execution of a tiny VM which adds a 16-bit immediate value to its accumulator at every
+/// step.
+///
+/// Each immediate value is decomposed into two 8-bit limbs, and the limbs are range-checked by
+/// looking them up in a table of all 8-bit values using a LogUp argument over an auxiliary trace
+/// column; see [RangeCheckAir] for details.
+pub struct RangeCheckExample<H: ElementHasher> {
+    options: ProofOptions,
+    immediates: Vec<u16>,
+    result: BaseElement,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> RangeCheckExample<H> {
+    pub fn new(num_steps: usize, options: ProofOptions) -> Self {
+        assert!(
+            (num_steps + 1).is_power_of_two(),
+            "number of steps must be one less than a power of 2"
+        );
+        assert!(num_steps > TABLE_SIZE, "number of steps must be greater than {TABLE_SIZE}");
+
+        // generate random immediate values and execute the VM
+        let now = Instant::now();
+        let immediates = (0..num_steps).map(|_| rand_value::<u64>() as u16).collect::<Vec<_>>();
+        let result = execute_vm(&immediates);
+        println!(
+            "Executed {} steps of the range-checked VM in {} ms",
+            num_steps,
+            now.elapsed().as_millis()
+        );
+
+        RangeCheckExample {
+            options,
+            immediates,
+            result,
+            _hasher: PhantomData,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl<H: ElementHasher> Example for RangeCheckExample<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    fn prove(&self) -> Proof {
+        println!(
+            "Generating proof for executing {} steps of the range-checked VM",
+            self.immediates.len()
+        );
+
+        // create a prover
+        let prover = RangeCheckProver::<H>::new(self.options.clone());
+
+        // generate execution trace
+        let trace =
+            info_span!("generate_execution_trace", num_cols = TRACE_WIDTH, steps = field::Empty)
+                .in_scope(|| {
+                    let trace = RangeCheckProver::<H>::build_trace(&self.immediates);
+                    tracing::Span::current().record("steps", trace.length());
+                    trace
+                });
+
+        // generate the proof
+        prover.prove(trace).unwrap()
+    }
+
+    fn verify(&self, proof: Proof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs { result: self.result };
+        let acceptable_options =
+            winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+        winterfell::verify::<RangeCheckAir, H, DefaultRandomCoin<H>>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+        )
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: Proof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs { result: self.result + BaseElement::ONE };
+        let acceptable_options =
+            winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+        winterfell::verify::<RangeCheckAir, H, DefaultRandomCoin<H>>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+        )
+    }
+}
+
+// VM EXECUTION
+// ================================================================================================
+
+/// Returns the value of the accumulator after all immediate values have been added to it.
+fn execute_vm(immediates: &[u16]) -> BaseElement {
+    immediates
+        .iter()
+        .fold(BaseElement::ZERO, |acc, &imm| acc + BaseElement::from(imm))
+}
@@ -0,0 +1,181 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{
+    math::batch_inversion, matrix::ColMatrix, AuxRandElements, ConstraintCompositionCoefficients,
+    DefaultConstraintEvaluator, DefaultTraceLde, EvaluationFrame, StarkDomain, Trace, TraceInfo,
+    TracePolyTable,
+};
+
+use super::{
+    air::{ACC_COL, HI_COL, IMM_COL, LO_COL, MULTIPLICITY_COL, TABLE_COL},
+    BaseElement, DefaultRandomCoin, ElementHasher, FieldElement, PhantomData, ProofOptions, Prover,
+    PublicInputs, RangeCheckAir, AUX_TRACE_WIDTH, LIMB_BITS, TABLE_SIZE, TRACE_WIDTH,
+};
+
+// RANGE CHECK TRACE
+// ================================================================================================
+
+/// Main segment of the execution trace for the range-checked VM; the trace is declared to have a
+/// single auxiliary column which is built from a single random element.
+pub struct RangeCheckTrace {
+    info: TraceInfo,
+    trace: ColMatrix<BaseElement>,
+}
+
+impl RangeCheckTrace {
+    pub fn new(columns: Vec<Vec<BaseElement>>) -> Self {
+        let length = columns[0].len();
+        Self {
+            info: TraceInfo::new_multi_segment(TRACE_WIDTH, AUX_TRACE_WIDTH, 1, length, vec![]),
+            trace: ColMatrix::new(columns),
+        }
+    }
+}
+
+impl Trace for RangeCheckTrace {
+    type BaseField = BaseElement;
+
+    fn info(&self) -> &TraceInfo {
+        &self.info
+    }
+
+    fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<Self::BaseField>) {
+        let next_row_idx = (row_idx + 1) % self.info.length();
+        self.trace.read_row_into(row_idx, frame.current_mut());
+        self.trace.read_row_into(next_row_idx, frame.next_mut());
+    }
+
+    fn main_segment(&self) -> &ColMatrix<BaseElement> {
+        &self.trace
+    }
+}
+
+// RANGE CHECK PROVER
+// ================================================================================================
+
+pub struct RangeCheckProver<H: ElementHasher> {
+    options: ProofOptions,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> RangeCheckProver<H> {
+    pub fn new(options: ProofOptions) -> Self {
+        Self { options, _hasher: PhantomData }
+    }
+
+    /// Builds the main segment of the execution trace for adding the specified immediate values
+    /// to the accumulator; the trace contains one more row than there are immediate values.
+    pub fn build_trace(immediates: &[u16]) -> RangeCheckTrace {
+        let trace_length = immediates.len() + 1;
+        debug_assert!(trace_length > TABLE_SIZE + 1);
+        let mut columns = vec![vec![BaseElement::ZERO; trace_length]; TRACE_WIDTH];
+
+        // execute the VM and record the limbs of all immediate values
+        let mut multiplicities = vec![0u32; TABLE_SIZE];
+        let mut acc = BaseElement::ZERO;
+        for (i, &imm) in immediates.iter().enumerate() {
+            let lo = imm as usize % TABLE_SIZE;
+            let hi = imm as usize >> LIMB_BITS;
+            multiplicities[lo] += 1;
+            multiplicities[hi] += 1;
+
+            columns[ACC_COL][i] = acc;
+            columns[IMM_COL][i] = BaseElement::from(imm);
+            columns[LO_COL][i] = BaseElement::from(lo as u32);
+            columns[HI_COL][i] = BaseElement::from(hi as u32);
+            acc += BaseElement::from(imm);
+        }
+        columns[ACC_COL][trace_length - 1] = acc;
+
+        // the table contains all values exactly once within the rows which are included in the
+        // LogUp sum, and then repeats the last value; the repeated values are looked up 0 times
+        for (i, value) in columns[TABLE_COL].iter_mut().enumerate() {
+            *value = BaseElement::from(i.min(TABLE_SIZE - 1) as u32);
+        }
+        for (i, &multiplicity) in multiplicities.iter().enumerate() {
+            columns[MULTIPLICITY_COL][i] = BaseElement::from(multiplicity);
+        }
+
+        RangeCheckTrace::new(columns)
+    }
+}
+
+impl<H: ElementHasher> Prover for RangeCheckProver<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    type BaseField = BaseElement;
+    type Air = RangeCheckAir;
+    type Trace = RangeCheckTrace;
+    type HashFn = H;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> PublicInputs {
+        let last_step = trace.length() - 1;
+        PublicInputs {
+            result: trace.main_segment().get(ACC_COL, last_step),
+        }
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+
+    fn build_aux_trace<E>(
+        &self,
+        trace: &Self::Trace,
+        aux_rand_elements: &AuxRandElements<E>,
+    ) -> ColMatrix<E>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        let main_trace = trace.main_segment();
+        let alpha = aux_rand_elements.rand_elements()[0];
+        let num_rows = main_trace.num_rows();
+
+        // compute 1 / (α - lo[i]), 1 / (α - hi[i]), and 1 / (α - t[i]) for all rows at once
+        let denominators = [LO_COL, HI_COL, TABLE_COL]
+            .iter()
+            .flat_map(|&col| main_trace.get_column(col).iter().map(|&v| alpha - v.into()))
+            .collect::<Vec<_>>();
+        let inverses = batch_inversion(&denominators);
+        let (inv_lo, rest) = inverses.split_at(num_rows);
+        let (inv_hi, inv_table) = rest.split_at(num_rows);
+
+        // accumulate the LogUp sum; the last row of the main trace does not affect the sum
+        let multiplicities = main_trace.get_column(MULTIPLICITY_COL);
+        let mut running_sum = Vec::with_capacity(num_rows);
+        running_sum.push(E::ZERO);
+        for i in 0..num_rows - 1 {
+            let next =
+                running_sum[i] + inv_lo[i] + inv_hi[i] - inv_table[i].mul_base(multiplicities[i]);
+            running_sum.push(next);
+        }
+
+        ColMatrix::new(vec![running_sum])
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{FieldExtension, ProofOptions};
+
+use super::Blake3_256;
+
+#[test]
+fn range_check_test_basic_proof_verification() {
+    let range_check_eg =
+        Box::new(super::RangeCheckExample::<Blake3_256>::new(511, build_options(false)));
+    crate::tests::test_basic_proof_verification(range_check_eg);
+}
+
+#[test]
+fn range_check_test_basic_proof_verification_extension() {
+    let range_check_eg =
+        Box::new(super::RangeCheckExample::<Blake3_256>::new(511, build_options(true)));
+    crate::tests::test_basic_proof_verification(range_check_eg);
+}
+
+#[test]
+fn range_check_test_basic_proof_verification_fail() {
+    let range_check_eg =
+        Box::new(super::RangeCheckExample::<Blake3_256>::new(511, build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(range_check_eg);
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, extension, 4, 31)
+}
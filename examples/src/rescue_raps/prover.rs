@@ -3,7 +3,6 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use core_utils::uninit_vector;
 use winterfell::{
     matrix::ColMatrix, AuxRandElements, ConstraintCompositionCoefficients,
     DefaultConstraintEvaluator, DefaultTraceLde, StarkDomain, Trace, TraceInfo, TracePolyTable,
@@ -148,8 +147,8 @@ where
         let main_trace = trace.main_segment();
         let rand_elements = aux_rand_elements.rand_elements();
 
-        let mut current_row = unsafe { uninit_vector(main_trace.num_cols()) };
-        let mut next_row = unsafe { uninit_vector(main_trace.num_cols()) };
+        let mut current_row = vec![BaseElement::ZERO; main_trace.num_cols()];
+        let mut next_row = vec![BaseElement::ZERO; main_trace.num_cols()];
         main_trace.read_row_into(0, &mut current_row);
         let mut aux_columns = vec![vec![E::ZERO; main_trace.num_rows()]; trace.aux_trace_width()];
 
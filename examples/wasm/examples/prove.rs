@@ -0,0 +1,53 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Generates a proof for the `fib` example using the native prover, and writes the proof and the
+//! computed Fibonacci term into `www/proof.bin` and `www/result.bin` respectively.
+//!
+//! When invoked with the `--bundled` flag, a proof for sequence length 1024 is written into
+//! `proofs/fib_1024.bin` and `proofs/fib_1024_result.bin` instead; this proof is bundled into the
+//! compiled verifier.
+//!
+//! Usage: `cargo run --release -p wasm-verifier-example --example prove [sequence_length |
+//! --bundled]`
+
+use std::{env, fs, path::Path};
+
+use examples::{fibonacci::fib2::FibExample, Blake3_256, Example};
+use verifier::{
+    math::{fields::f128::BaseElement, FieldElement},
+    FieldExtension, ProofOptions, Serializable,
+};
+
+fn main() {
+    let arg = env::args().nth(1);
+    let bundled = arg.as_deref() == Some("--bundled");
+    let sequence_length = match arg {
+        _ if bundled => 1024,
+        Some(arg) => arg.parse::<usize>().expect("sequence length must be an integer"),
+        None => 1_048_576,
+    };
+
+    let options = ProofOptions::new(28, 8, 16, FieldExtension::None, 4, 7);
+    let fib = FibExample::<Blake3_256>::new(sequence_length, options);
+    let proof = fib.prove();
+
+    let mut t0 = BaseElement::ONE;
+    let mut t1 = BaseElement::ONE;
+    for _ in 0..(sequence_length - 1) {
+        t1 = t0 + t1;
+        core::mem::swap(&mut t0, &mut t1);
+    }
+
+    let (out_dir, proof_file, result_file) = if bundled {
+        ("proofs", "fib_1024.bin", "fib_1024_result.bin")
+    } else {
+        ("www", "proof.bin", "result.bin")
+    };
+    let out_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(out_dir);
+    fs::write(out_dir.join(proof_file), proof.to_bytes()).expect("failed to write the proof");
+    fs::write(out_dir.join(result_file), t1.to_bytes()).expect("failed to write the result");
+    println!("Wrote the proof and the result into {}", out_dir.display());
+}
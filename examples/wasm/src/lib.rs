@@ -0,0 +1,162 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! An example of verifying Winterfell STARK proofs in a web browser.
+//!
+//! This crate compiles the verifier for the `fib` example (see the [examples](../) crate) to
+//! WebAssembly. Proofs are generated natively (see `examples/prove.rs`), and are verified in the
+//! browser by calling the [verify()] function exported to JavaScript. A small proof is also
+//! bundled into the compiled module, and can be verified via [verify_bundled()] without fetching
+//! any files.
+//!
+//! The verifier is compiled with its `std` feature disabled: `winter-verifier` and its
+//! dependencies do not rely on a clock, an operating system source of randomness, or threads, and
+//! thus, they require only a global allocator. This crate itself uses the standard library only
+//! for the allocator and the panic handler provided by it.
+
+use verifier::{
+    crypto::{hashers::Blake3_256, DefaultRandomCoin},
+    math::{fields::f128::BaseElement, FieldElement},
+    verify_bytes, AcceptableOptions, Air, AirContext, Assertion, EvaluationFrame, LazyProof,
+    ProofOptions, TraceInfo, TransitionConstraintDegree, VerifierError,
+};
+use wasm_bindgen::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+const TRACE_WIDTH: usize = 2;
+
+/// Minimum conjectured security level (in bits) of proofs accepted by the verifier.
+pub const MIN_CONJECTURED_SECURITY: u32 = 95;
+
+/// A proof for the `fib` example with sequence length 1024 bundled into the compiled module.
+///
+/// The proof can be regenerated via `examples/prove.rs` with the `--bundled` flag.
+pub const BUNDLED_PROOF: &[u8] = include_bytes!("../proofs/fib_1024.bin");
+
+/// The Fibonacci term computed by the [BUNDLED_PROOF].
+pub const BUNDLED_RESULT: &[u8] = include_bytes!("../proofs/fib_1024_result.bin");
+
+type Hasher = Blake3_256<BaseElement>;
+
+// WASM INTERFACE
+// ================================================================================================
+
+/// Verifies a proof that the Fibonacci term serialized into `result_bytes` was computed correctly.
+///
+/// Both the proof and the result are expected to be serialized via `Serializable::to_bytes()`.
+/// On failure, a description of the error is returned to JavaScript.
+#[wasm_bindgen]
+pub fn verify(proof_bytes: &[u8], result_bytes: &[u8]) -> Result<(), JsValue> {
+    // FibAir panics when instantiated for a trace of a different width, and a panic would abort
+    // the module; thus, the width is checked before the AIR is built
+    check_trace_width(proof_bytes).map_err(|err| JsValue::from_str(&err))?;
+    verify_fib_proof(proof_bytes, result_bytes).map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+/// Verifies the proof bundled into the compiled module (see [BUNDLED_PROOF]).
+///
+/// On failure, a description of the error is returned to JavaScript.
+#[wasm_bindgen]
+pub fn verify_bundled() -> Result<(), JsValue> {
+    verify(BUNDLED_PROOF, BUNDLED_RESULT)
+}
+
+/// Returns the size (in bytes) of the proof bundled into the compiled module.
+#[wasm_bindgen]
+pub fn bundled_proof_size() -> usize {
+    BUNDLED_PROOF.len()
+}
+
+/// Verifies a proof that the Fibonacci term serialized into `result_bytes` was computed correctly.
+///
+/// This is the function exported to JavaScript via [verify()]; unlike [verify()], it returns
+/// errors as [VerifierError]s and can be called from Rust on any target.
+pub fn verify_fib_proof(proof_bytes: &[u8], result_bytes: &[u8]) -> Result<(), VerifierError> {
+    let acceptable_options = AcceptableOptions::MinConjecturedSecurity(MIN_CONJECTURED_SECURITY);
+    verify_bytes::<FibAir, Hasher, DefaultRandomCoin<Hasher>>(
+        proof_bytes,
+        result_bytes,
+        &acceptable_options,
+    )
+}
+
+/// Returns an error if the proof serialized into `proof_bytes` could not be parsed, or if it
+/// attests to a trace whose width differs from the width of the `fib` example trace.
+pub fn check_trace_width(proof_bytes: &[u8]) -> Result<(), String> {
+    let proof = LazyProof::read_from_slice(proof_bytes)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()).to_string())?;
+    let trace_width = proof.context.trace_info().width();
+    if trace_width != TRACE_WIDTH {
+        return Err(format!(
+            "expected a proof for a trace of width {TRACE_WIDTH}, \
+             but the trace width was {trace_width}"
+        ));
+    }
+    Ok(())
+}
+
+// FIBONACCI AIR
+// ================================================================================================
+
+/// AIR of the `fib` example: computes a Fibonacci term using a trace table with 2 columns, where
+/// each step in the trace table advances the sequence by 2 terms.
+pub struct FibAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+    type GkrProof = ();
+    type GkrVerifier = ();
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        assert_eq!(TRACE_WIDTH, trace_info.width());
+        FibAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+
+        // constraints of Fibonacci sequence (2 terms per step):
+        // s_{0, i+1} = s_{0, i} + s_{1, i}
+        // s_{1, i+1} = s_{1, i} + s_{0, i+1}
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        // a valid Fibonacci sequence should start with two ones and terminate with
+        // the expected result
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, Self::BaseField::ONE),
+            Assertion::single(1, 0, Self::BaseField::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
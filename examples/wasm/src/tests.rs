@@ -0,0 +1,76 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use examples::{
+    fibonacci::{fib2::FibExample, mulfib8::MulFib8Example},
+    Blake3_256, Example,
+};
+use verifier::{
+    math::{fields::f128::BaseElement, FieldElement},
+    FieldExtension, ProofOptions, Serializable, VerifierError,
+};
+
+use super::{check_trace_width, verify_bundled, verify_fib_proof, BUNDLED_PROOF, BUNDLED_RESULT};
+
+#[test]
+fn verify_native_proof() {
+    let options = ProofOptions::new(28, 8, 16, FieldExtension::None, 4, 7);
+    let fib = FibExample::<Blake3_256>::new(1024, options);
+    let proof_bytes = fib.prove().to_bytes();
+    let result = compute_fib_term(1024);
+
+    assert!(verify_fib_proof(&proof_bytes, &result.to_bytes()).is_ok());
+
+    let wrong_result = result + BaseElement::ONE;
+    assert!(verify_fib_proof(&proof_bytes, &wrong_result.to_bytes()).is_err());
+
+    // proofs which do not provide enough security are rejected
+    let options = ProofOptions::new(8, 8, 0, FieldExtension::None, 4, 7);
+    let fib = FibExample::<Blake3_256>::new(1024, options);
+    let proof_bytes = fib.prove().to_bytes();
+    assert!(matches!(
+        verify_fib_proof(&proof_bytes, &result.to_bytes()),
+        Err(VerifierError::InsufficientConjecturedSecurity(..))
+    ));
+}
+
+#[test]
+fn check_proof_trace_width() {
+    assert!(check_trace_width(BUNDLED_PROOF).is_ok());
+    assert!(check_trace_width(&BUNDLED_PROOF[1..]).is_err());
+
+    // proofs of computations with a different trace width are rejected before the AIR is built
+    let options = ProofOptions::new(28, 8, 16, FieldExtension::None, 4, 7);
+    let mulfib = MulFib8Example::<Blake3_256>::new(1024, options);
+    let proof_bytes = mulfib.prove().to_bytes();
+    assert_eq!(
+        Err("expected a proof for a trace of width 2, but the trace width was 8".to_string()),
+        check_trace_width(&proof_bytes)
+    );
+}
+
+#[test]
+fn verify_bundled_proof() {
+    assert!(verify_bundled().is_ok());
+    assert_eq!(compute_fib_term(1024).to_bytes(), BUNDLED_RESULT);
+
+    // a bundled proof is rejected if any of its bytes is modified
+    let mut proof_bytes = BUNDLED_PROOF.to_vec();
+    let last_byte = proof_bytes.len() - 1;
+    proof_bytes[last_byte / 2] ^= 1;
+    assert!(verify_fib_proof(&proof_bytes, BUNDLED_RESULT).is_err());
+}
+
+fn compute_fib_term(n: usize) -> BaseElement {
+    let mut t0 = BaseElement::ONE;
+    let mut t1 = BaseElement::ONE;
+
+    for _ in 0..(n - 1) {
+        t1 = t0 + t1;
+        core::mem::swap(&mut t0, &mut t1);
+    }
+
+    t1
+}
@@ -0,0 +1,191 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Contains functions for proving low-degreeness of several polynomials with a single FRI proof.
+//!
+//! Given polynomials p_0, ..., p_{k-1} of degrees at most d_0, ..., d_{k-1}, and a degree bound
+//! D such that each d_i <= D, the prover and the verifier run a single instance of FRI against
+//! the polynomial:
+//!
+//! c(x) = \sum_{i=0}^{k-1} (α_i + β_i * x^{D - d_i}) * p_i(x)
+//!
+//! where α_i and β_i are random coefficients drawn by the verifier after the prover has committed
+//! to the polynomials. The degree-correction terms β_i * x^{D - d_i} ensure that c(x) is of
+//! degree at most D only if each p_i(x) is of degree at most d_i (with high probability).
+//!
+//! The protocol proceeds as follows:
+//! 1. The prover commits to the evaluations of all polynomials over the evaluation domain, and
+//!    the commitment is used to reseed the public coin.
+//! 2. Both parties draw [BatchingCoefficients] from the public coin.
+//! 3. The prover computes evaluations of c(x) via [combine_evaluations()] and runs the commit
+//!    and query phases of FRI against them, with D as the maximum polynomial degree.
+//! 4. The verifier checks the queried evaluations of the polynomials against the prover's
+//!    commitment, computes evaluations of c(x) at the queried positions via
+//!    [combine_queried_evaluations()], and passes them to [FriVerifier::verify()] of a verifier
+//!    instantiated with D as the maximum polynomial degree.
+//!
+//! Committing to the polynomials and checking the queried evaluations against the commitment is
+//! the responsibility of the application.
+//!
+//! [FriVerifier::verify()]: crate::FriVerifier::verify
+
+use alloc::vec::Vec;
+
+use crypto::{RandomCoin, RandomCoinError};
+use math::{FieldElement, StarkField};
+
+// BATCHING COEFFICIENTS
+// ================================================================================================
+
+/// Random coefficients for combining several polynomials into a single polynomial.
+///
+/// For each polynomial, the coefficients consist of a tuple (α, β), where α is the coefficient of
+/// the polynomial itself and β is the coefficient of its degree-correction term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchingCoefficients<E: FieldElement> {
+    /// Coefficients (α, β) for each of the combined polynomials.
+    pub values: Vec<(E, E)>,
+}
+
+impl<E: FieldElement> BatchingCoefficients<E> {
+    /// Draws coefficients for combining `num_polys` polynomials from the specified public coin.
+    ///
+    /// # Errors
+    /// Returns an error if the coefficients could not be drawn from the public coin.
+    pub fn draw<R: RandomCoin<BaseField = E::BaseField>>(
+        public_coin: &mut R,
+        num_polys: usize,
+    ) -> Result<Self, RandomCoinError> {
+        let mut values = Vec::with_capacity(num_polys);
+        for _ in 0..num_polys {
+            values.push((public_coin.draw()?, public_coin.draw()?));
+        }
+        Ok(Self { values })
+    }
+}
+
+// COMBINATION
+// ================================================================================================
+
+/// Returns evaluations of a random linear combination of the polynomials implied by the
+/// specified `evaluations`, with degree-correction terms which lift the degree of each polynomial
+/// to `max_degree`.
+///
+/// Evaluations of all polynomials must be over the same domain; the j-th evaluation of each
+/// polynomial is assumed to be at x = `domain_offset` * g^j, where g is the generator of the
+/// multiplicative subgroup of the size of the domain. The i-th polynomial is assumed to be of
+/// degree at most `degrees[i]`.
+///
+/// # Panics
+/// Panics if:
+/// * The number of `evaluations`, `degrees`, and `coefficients` is not the same.
+/// * Evaluations of the polynomials are not all of the same length, or the length is not a power
+///   of two.
+/// * Any of the `degrees` is greater than `max_degree`.
+pub fn combine_evaluations<E: FieldElement>(
+    evaluations: &[Vec<E>],
+    degrees: &[usize],
+    max_degree: usize,
+    domain_offset: E::BaseField,
+    coefficients: &BatchingCoefficients<E>,
+) -> Vec<E> {
+    assert_eq!(evaluations.len(), degrees.len(), "expected a degree for each polynomial");
+    assert_eq!(
+        evaluations.len(),
+        coefficients.values.len(),
+        "expected coefficients for each polynomial"
+    );
+    let domain_size = evaluations.first().map_or(0, |p| p.len());
+    assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+    let g = E::BaseField::get_root_of_unity(domain_size.ilog2());
+
+    let mut result = vec![E::ZERO; domain_size];
+    for ((poly, &degree), &(alpha, beta)) in
+        evaluations.iter().zip(degrees).zip(coefficients.values.iter())
+    {
+        assert_eq!(
+            domain_size,
+            poly.len(),
+            "all polynomials must be evaluated over the same domain"
+        );
+        let shift = degree_shift(degree, max_degree);
+
+        // x^shift for consecutive domain points is computed incrementally as
+        // offset^shift * (g^shift)^j
+        let step = g.exp_vartime((shift as u64).into());
+        let mut x_shift = domain_offset.exp_vartime((shift as u64).into());
+        for (result, &value) in result.iter_mut().zip(poly.iter()) {
+            *result += (alpha + beta * E::from(x_shift)) * value;
+            x_shift *= step;
+        }
+    }
+    result
+}
+
+/// Returns evaluations of a random linear combination of polynomials at the specified positions
+/// of the evaluation domain, computed from the evaluations of the polynomials at these positions.
+///
+/// This is the verifier's counterpart of [combine_evaluations()]: `queried_evaluations[i][j]`
+/// is expected to be the evaluation of the i-th polynomial at position `positions[j]` of the
+/// domain of the specified size.
+///
+/// # Panics
+/// Panics if:
+/// * The number of `queried_evaluations`, `degrees`, and `coefficients` is not the same.
+/// * The number of queried evaluations of any of the polynomials is not equal to the number of
+///   positions.
+/// * `domain_size` is not a power of two.
+/// * Any of the `degrees` is greater than `max_degree`.
+pub fn combine_queried_evaluations<E: FieldElement>(
+    queried_evaluations: &[Vec<E>],
+    positions: &[usize],
+    domain_size: usize,
+    degrees: &[usize],
+    max_degree: usize,
+    domain_offset: E::BaseField,
+    coefficients: &BatchingCoefficients<E>,
+) -> Vec<E> {
+    assert_eq!(
+        queried_evaluations.len(),
+        degrees.len(),
+        "expected a degree for each polynomial"
+    );
+    assert_eq!(
+        queried_evaluations.len(),
+        coefficients.values.len(),
+        "expected coefficients for each polynomial"
+    );
+    assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+    let g = E::BaseField::get_root_of_unity(domain_size.ilog2());
+    let xs = positions
+        .iter()
+        .map(|&position| domain_offset * g.exp_vartime((position as u64).into()))
+        .collect::<Vec<_>>();
+
+    let mut result = vec![E::ZERO; positions.len()];
+    for ((values, &degree), &(alpha, beta)) in
+        queried_evaluations.iter().zip(degrees).zip(coefficients.values.iter())
+    {
+        assert_eq!(positions.len(), values.len(), "expected an evaluation for each position");
+        let shift = (degree_shift(degree, max_degree) as u64).into();
+        for ((result, &value), &x) in result.iter_mut().zip(values.iter()).zip(xs.iter()) {
+            *result += (alpha + beta * E::from(x.exp_vartime(shift))) * value;
+        }
+    }
+    result
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the degree by which a polynomial of the specified degree needs to be lifted to reach
+/// `max_degree`.
+fn degree_shift(degree: usize, max_degree: usize) -> usize {
+    assert!(
+        degree <= max_degree,
+        "polynomial degree {degree} is greater than the maximum degree {max_degree}"
+    );
+    max_degree - degree
+}
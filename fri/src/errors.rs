@@ -6,6 +6,7 @@
 use core::fmt;
 
 use crypto::RandomCoinError;
+use utils::DeserializationError;
 
 // VERIFIER ERROR
 // ================================================================================================
@@ -15,8 +16,10 @@ use crypto::RandomCoinError;
 pub enum VerifierError {
     /// Attempt to draw a random value from a public coin failed.
     RandomCoinError(RandomCoinError),
+    /// Parts of the FRI proof read from the verifier channel could not be parsed.
+    DeserializationError(DeserializationError),
     /// Folding factor specified for the protocol is not supported. Currently, supported folding
-    /// factors are: 4, 8, and 16.
+    /// factors are: 2, 4, 8, and 16, and additionally 32 for the first layer.
     UnsupportedFoldingFactor(usize),
     /// Number of query positions does not match the number of provided evaluations.
     NumPositionEvaluationMismatch(usize, usize),
@@ -34,6 +37,9 @@ pub enum VerifierError {
     RemainderDegreeMismatch(usize),
     /// Polynomial degree at one of the FRI layers could not be divided evenly by the folding factor.
     DegreeTruncation(usize, usize, usize),
+    /// Number of partitions specified by the FRI proof is greater than the size of the domain of
+    /// the last FRI layer.
+    InvalidNumPartitions(usize, usize),
 }
 
 impl fmt::Display for VerifierError {
@@ -43,6 +49,9 @@ impl fmt::Display for VerifierError {
             Self::RandomCoinError(err) => {
                 write!(f, "failed to draw a random value from the public coin: {err}")
             }
+            Self::DeserializationError(err) => {
+                write!(f, "failed to parse FRI proof: {err}")
+            }
             Self::UnsupportedFoldingFactor(value) => {
                 write!(f, "folding factor {value} is not currently supported")
             }
@@ -70,6 +79,9 @@ impl fmt::Display for VerifierError {
             Self::DegreeTruncation(degree, folding, layer) => {
                 write!(f, "degree reduction from {degree} by {folding} at layer {layer} results in degree truncation")
             }
+            Self::InvalidNumPartitions(num_partitions, domain_size) => {
+                write!(f, "number of partitions {num_partitions} cannot be greater than the size of the last FRI layer domain {domain_size}")
+            }
         }
     }
 }
@@ -0,0 +1,259 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Contains functions for estimating the size and the soundness of FRI proofs analytically.
+//!
+//! The estimates are computed from protocol parameters alone (i.e., without generating a proof),
+//! and thus, they can be used to tune [FriOptions] for a specific use case; e.g., to compare
+//! folding schedules, or to find the smallest number of queries which achieves a given security
+//! level.
+
+use core::cmp;
+
+use crate::FriOptions;
+
+// CONSTANTS
+// ================================================================================================
+
+/// The smallest value of the proximity parameter m considered for the proven security estimate.
+const MIN_PROXIMITY_PARAMETER: usize = 3;
+
+/// The largest value of the proximity parameter m considered for the proven security estimate.
+const MAX_PROXIMITY_PARAMETER: usize = 1000;
+
+// FRI ESTIMATE
+// ================================================================================================
+
+/// An estimate of the size and the soundness of a FRI proof.
+///
+/// Soundness is expressed in bits; i.e., a security level of `k` bits means that the soundness
+/// error of the protocol is at most 2^-k.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FriEstimate {
+    /// Expected size of the proof in bytes, including layer commitments.
+    ///
+    /// Query positions are assumed to be drawn uniformly at random. Thus, the estimate accounts
+    /// for duplicate positions at each layer, and for Merkle authentication paths of different
+    /// queries sharing nodes.
+    pub proof_size: usize,
+    /// Size of the proof in bytes, including layer commitments, assuming that none of the query
+    /// positions are duplicates and that no Merkle authentication paths share nodes.
+    pub max_proof_size: usize,
+    /// Proven security level of the protocol in bits.
+    pub proven_security: u32,
+    /// Conjectured security level of the protocol in bits.
+    pub conjectured_security: u32,
+}
+
+impl FriEstimate {
+    /// Returns an estimate of the size and the soundness of a FRI proof for a polynomial evaluated
+    /// over a domain of the specified size, with FRI executed using the specified options.
+    ///
+    /// The remaining parameters are:
+    /// * `num_queries` - number of queries made by the verifier.
+    /// * `field_bits` - number of bits in the field from which folding challenges are drawn (for
+    ///   an extension field, this is the size of the base field in bits times the degree of the
+    ///   extension).
+    /// * `element_size` - size of a single evaluation of the polynomial in bytes.
+    /// * `digest_size` - size of a digest of the hash function used for commitments in bytes.
+    ///
+    /// Proven security is estimated in the list-decoding regime following the analysis in
+    /// <https://eprint.iacr.org/2022/1216.pdf>, taking into account the folding factor of each
+    /// layer, and choosing the proximity parameter m which maximizes the security level.
+    /// Conjectured security is estimated as `log2(blowup_factor)` bits per query, bounded by the
+    /// size of the field. Neither estimate includes contributions of grinding.
+    ///
+    /// # Panics
+    /// Panics if `domain_size` is not a power of two or is smaller than the blowup factor.
+    pub fn new(
+        options: &FriOptions,
+        domain_size: usize,
+        num_queries: usize,
+        field_bits: u32,
+        element_size: usize,
+        digest_size: usize,
+    ) -> Self {
+        assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+        assert!(
+            domain_size >= options.blowup_factor(),
+            "domain size cannot be smaller than the blowup factor"
+        );
+        let folding_factors = options.folding_factors(domain_size);
+
+        let (proof_size, max_proof_size) = get_proof_size(
+            options.blowup_factor(),
+            &folding_factors,
+            domain_size,
+            num_queries,
+            element_size,
+            digest_size,
+        );
+        let proven_security = get_proven_security(
+            options.blowup_factor(),
+            &folding_factors,
+            domain_size,
+            num_queries,
+            field_bits,
+        );
+        let conjectured_security =
+            get_conjectured_security(options.blowup_factor(), domain_size, num_queries, field_bits);
+
+        FriEstimate {
+            proof_size,
+            max_proof_size,
+            proven_security,
+            conjectured_security,
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns expected and maximum sizes of a FRI proof in bytes.
+fn get_proof_size(
+    blowup_factor: usize,
+    folding_factors: &[usize],
+    mut domain_size: usize,
+    num_queries: usize,
+    element_size: usize,
+    digest_size: usize,
+) -> (usize, usize) {
+    // a commitment for each layer and for the remainder, and the number of layers, the number of
+    // partitions and the length of the remainder
+    let mut proof_size = (folding_factors.len() + 1) * digest_size + 3;
+    let mut max_proof_size = proof_size;
+    let num_queries_f = num_queries as f64;
+
+    for &folding_factor in folding_factors {
+        domain_size /= folding_factor;
+
+        // each layer is serialized with the lengths of its values and paths; the leaves are
+        // cosets of the folded domain, each consisting of `folding_factor` evaluations
+        proof_size += 8;
+        max_proof_size += 8;
+        let num_leaves = domain_size as f64;
+        let num_distinct_queries = num_leaves * (1.0 - powf(1.0 - 1.0 / num_leaves, num_queries_f));
+        proof_size += (num_distinct_queries * (folding_factor * element_size) as f64) as usize;
+        max_proof_size += num_queries * folding_factor * element_size;
+
+        // an authentication path requires a node only if the node's sibling is not on the
+        // path of another query; for q queries and a level with n nodes, the expected number of
+        // such nodes is n * ((1 - 1/n)^q - (1 - 2/n)^q)
+        let tree_depth = domain_size.ilog2() as usize;
+        let mut num_nodes = 0.0;
+        for level in 1..=tree_depth {
+            let n = (1usize << level) as f64;
+            num_nodes +=
+                n * (powf(1.0 - 1.0 / n, num_queries_f) - powf(1.0 - 2.0 / n, num_queries_f));
+        }
+        proof_size += (num_nodes * digest_size as f64) as usize;
+        max_proof_size += num_queries * tree_depth * digest_size;
+    }
+
+    // the remainder polynomial is sent in coefficient form
+    let remainder_size = domain_size / blowup_factor * element_size;
+    (proof_size + remainder_size, max_proof_size + remainder_size)
+}
+
+/// Computes conjectured security level of FRI for the specified parameters.
+fn get_conjectured_security(
+    blowup_factor: usize,
+    domain_size: usize,
+    num_queries: usize,
+    field_bits: u32,
+) -> u32 {
+    let field_security = field_bits.saturating_sub(domain_size.ilog2());
+    let query_security = blowup_factor.ilog2() * num_queries as u32;
+    cmp::min(field_security, query_security).saturating_sub(1)
+}
+
+/// Estimates proven security level of FRI for the specified parameters by choosing the value of
+/// the proximity parameter m which maximizes the security level.
+fn get_proven_security(
+    blowup_factor: usize,
+    folding_factors: &[usize],
+    domain_size: usize,
+    num_queries: usize,
+    field_bits: u32,
+) -> u32 {
+    (MIN_PROXIMITY_PARAMETER..=MAX_PROXIMITY_PARAMETER)
+        .map(|m| {
+            proven_security_for_m(
+                blowup_factor,
+                folding_factors,
+                domain_size,
+                num_queries,
+                field_bits,
+                m,
+            )
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Computes proven security level of FRI for the specified parameters for a fixed value of the
+/// proximity parameter m in the list-decoding regime.
+fn proven_security_for_m(
+    blowup_factor: usize,
+    folding_factors: &[usize],
+    domain_size: usize,
+    num_queries: usize,
+    field_bits: u32,
+    m: usize,
+) -> u32 {
+    let field_bits = field_bits as f64;
+    let m = m as f64;
+    let rho = 1.0 / blowup_factor as f64;
+    let n = domain_size as f64;
+    let sum_folding_factors = folding_factors.iter().sum::<usize>() as f64;
+
+    // commit-phase error consists of the error of the first folding challenge, which dominates
+    // for all practical parameters, and of the errors of folding challenges of other layers,
+    // which grow with the folding factor of each layer
+    let commit_err = 0.5 * powf(m + 0.5, 7.0) / powf(rho, 1.5) * powf(n, 2.0)
+        + (2.0 * m + 1.0) * (n + 1.0) / sqrt(rho) * sum_folding_factors;
+    let commit_err_bits = field_bits - log2(commit_err);
+
+    // query-phase error for an agreement parameter alpha = (1 + 1/2m) * sqrt(rho)
+    let alpha = (1.0 + 0.5 / m) * sqrt(rho);
+    let query_err_bits = -(num_queries as f64) * log2(alpha);
+
+    let err_bits = commit_err_bits.min(query_err_bits);
+    if err_bits < 1.0 {
+        return 0;
+    }
+    err_bits as u32 - 1
+}
+
+#[cfg(feature = "std")]
+fn log2(value: f64) -> f64 {
+    value.log2()
+}
+
+#[cfg(not(feature = "std"))]
+fn log2(value: f64) -> f64 {
+    libm::log2(value)
+}
+
+#[cfg(feature = "std")]
+fn sqrt(value: f64) -> f64 {
+    value.sqrt()
+}
+
+#[cfg(not(feature = "std"))]
+fn sqrt(value: f64) -> f64 {
+    libm::sqrt(value)
+}
+
+#[cfg(feature = "std")]
+fn powf(value: f64, exp: f64) -> f64 {
+    value.powf(exp)
+}
+
+#[cfg(not(feature = "std"))]
+fn powf(value: f64, exp: f64) -> f64 {
+    libm::pow(value, exp)
+}
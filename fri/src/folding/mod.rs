@@ -16,7 +16,7 @@ use math::{
 };
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
-use utils::{iter_mut, uninit_vector};
+use utils::{batch_iter_mut, Arena, UninitBuffer};
 
 // DEGREE-RESPECTING PROJECTION
 // ================================================================================================
@@ -88,33 +88,108 @@ where
     B: StarkField,
     E: FieldElement<BaseField = B>,
 {
-    // build offset inverses and twiddles used during polynomial interpolation
     let inv_offsets = get_inv_offsets(values.len(), domain_offset, N);
+    apply_drp_with_inv_offsets(values, &inv_offsets, alpha)
+}
+
+/// Applies degree-respecting projection to evaluations of a polynomial using the provided arena
+/// for temporary buffers.
+///
+/// This works just like [apply_drp()], but the inverses of the domain offsets used during
+/// polynomial interpolation are computed into a buffer allocated in `arena` rather than into a
+/// newly allocated vector. Thus, when the same arena is reset and reused for every FRI layer, only
+/// the first layer requests memory for this buffer from the global allocator.
+///
+/// ```
+/// # use math::{fields::f128::BaseElement, FieldElement, StarkField};
+/// # use rand_utils::rand_vector;
+/// # use utils::{transpose_slice, Arena};
+/// # use winter_fri::folding::{apply_drp, apply_drp_in};
+/// let evaluations = rand_vector::<BaseElement>(32);
+/// let transposed_evaluations = transpose_slice::<BaseElement, 4>(&evaluations);
+/// let alpha = BaseElement::new(7);
+/// let offset = BaseElement::GENERATOR;
+///
+/// let arena = Arena::new();
+/// assert_eq!(
+///     apply_drp(&transposed_evaluations, offset, alpha),
+///     apply_drp_in(&transposed_evaluations, offset, alpha, &arena)
+/// );
+/// ```
+pub fn apply_drp_in<B, E, const N: usize>(
+    values: &[[E; N]],
+    domain_offset: B,
+    alpha: E,
+    arena: &Arena,
+) -> Vec<E>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+{
+    let inv_offsets = arena.alloc_slice_fill(values.len(), B::ONE);
+    fill_inv_offsets(inv_offsets, domain_offset, N);
+    apply_drp_with_inv_offsets(values, inv_offsets, alpha)
+}
+
+/// Populates `inv_offsets` with the inverses of the domain offsets for each group of
+/// `folding_factor` evaluations of a polynomial over a domain of `inv_offsets.len()` *
+/// `folding_factor` points.
+///
+/// When `concurrent` feature is enabled, the offsets are computed in multiple threads.
+pub(crate) fn fill_inv_offsets<B: StarkField>(
+    inv_offsets: &mut [B],
+    domain_offset: B,
+    folding_factor: usize,
+) {
+    let g_inv = B::get_root_of_unity((inv_offsets.len() * folding_factor).ilog2()).inv();
+    let offset_inv = domain_offset.inv();
+    batch_iter_mut!(inv_offsets, 1024, |batch: &mut [B], batch_offset: usize| {
+        let mut inv_offset = offset_inv * g_inv.exp((batch_offset as u64).into());
+        for value in batch.iter_mut() {
+            *value = inv_offset;
+            inv_offset *= g_inv;
+        }
+    });
+}
+
+/// Applies degree-respecting projection to evaluations of a polynomial given the inverses of the
+/// domain offsets for each group of `N` evaluations.
+///
+/// When `concurrent` feature is enabled, the evaluations are split into contiguous batches (one
+/// per thread) and each batch is folded in a separate thread.
+pub(crate) fn apply_drp_with_inv_offsets<B, E, const N: usize>(
+    values: &[[E; N]],
+    inv_offsets: &[B],
+    alpha: E,
+) -> Vec<E>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+{
+    // build twiddles used during polynomial interpolation
     let inv_twiddles = get_inv_twiddles::<B>(N);
     let len_offset = E::inv((N as u32).into());
 
-    let mut result = unsafe { uninit_vector(values.len()) };
-    iter_mut!(result)
-        .zip(values)
-        .zip(inv_offsets)
-        .for_each(|((result, values), domain_offset)| {
+    UninitBuffer::new(values.len()).fill_batches(256, |batch, batch_offset| {
+        let values = &values[batch_offset..batch_offset + batch.len()];
+        let inv_offsets = &inv_offsets[batch_offset..batch_offset + batch.len()];
+        for (values, domain_offset) in values.iter().zip(inv_offsets) {
             // interpolate the values into a polynomial; this is similar to interpolation with
             // offset implemented in math::fft module
             let mut poly = *values;
             serial_fft(&mut poly, &inv_twiddles);
 
             let mut offset = len_offset;
-            let domain_offset = E::from(domain_offset);
+            let domain_offset = E::from(*domain_offset);
             for coeff in poly.iter_mut() {
                 *coeff *= offset;
                 offset *= domain_offset;
             }
 
             // evaluate the polynomial at alpha, and save the result
-            *result = polynom::eval(&poly, alpha)
-        });
-
-    result
+            batch.push(polynom::eval(&poly, alpha))
+        }
+    })
 }
 
 // POSITION FOLDING
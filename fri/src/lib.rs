@@ -33,8 +33,9 @@
 //! # Proof verification
 //! FRI proofs are verified by a [FriVerifier] as follows:
 //! 1. First, a FRI proof needs to be converted into a [VerifierChannel]. This crate provides a
-//!    default implementation of the verifier channel, but when FRI proof verification is executed
-//!    as a part of the larger STARK protocol, STARK verifier handles this conversion.
+//!    default implementation of the verifier channel, as well as a [BorrowedVerifierChannel]
+//!    which reads data directly from a borrowed proof, but when FRI proof verification is
+//!    executed as a part of the larger STARK protocol, STARK verifier handles this conversion.
 //! 2. Then, a [FriVerifier] should be instantiated (via [new()](FriVerifier::new()) function).
 //!    This will execute the commit phase of the FRI protocol from the verifier's perspective -
 //!    i.e., the verifier will read FRI layer commitments from the channel, and generates
@@ -44,6 +45,25 @@
 //!    are provided to the [verify()](FriVerifier::verify()) function directly. The values at
 //!    remaining layers, the verifier reads from the specified verifier channel.
 //!
+//! # Layer commitments
+//! Each FRI layer is committed to with a binary Merkle tree in which every leaf is a hash of all
+//! `folding_factor` evaluations which are folded into a single evaluation of the next layer (i.e.,
+//! of an entire coset of the folded domain). Thus, regardless of the folding factor, a query
+//! requires a single Merkle authentication path per layer which opens the whole coset, and the
+//! depth of the tree is reduced by log2 of the folding factor relative to a tree with a leaf per
+//! evaluation. Trees of higher arity would not reduce the number of paths any further, and would
+//! increase the number of sibling nodes included in each path.
+//!
+//! # Batching
+//! Low-degreeness of several polynomials (possibly of different degrees) can be proven with a
+//! single FRI proof by running FRI against a random linear combination of the polynomials with
+//! degree-correction terms; functions for computing such combinations are provided in the
+//! [batching] module.
+//!
+//! # Estimates
+//! The expected size and the soundness of FRI proofs for given protocol parameters can be
+//! estimated without generating a proof via [FriEstimate](estimate::FriEstimate).
+//!
 //! # Protocol parameters
 //! The current implementation supports executing FRI protocol with dynamically configurable
 //! parameters including:
@@ -66,19 +86,24 @@
 #[macro_use]
 extern crate alloc;
 
+pub mod batching;
+pub mod estimate;
 pub mod folding;
+pub mod positions;
 
 mod prover;
-pub use prover::{DefaultProverChannel, FriProver, ProverChannel};
+pub use prover::{DefaultProverChannel, FriProver, LayerStore, MemoryLayerStore, ProverChannel};
 
 mod verifier;
-pub use verifier::{DefaultVerifierChannel, FriVerifier, VerifierChannel};
+pub use verifier::{
+    BorrowedVerifierChannel, DefaultVerifierChannel, FriFinalLayer, FriVerifier, VerifierChannel,
+};
 
 mod options;
 pub use options::FriOptions;
 
 mod proof;
-pub use proof::FriProof;
+pub use proof::{FriProof, FriProofRef};
 
 mod errors;
 pub use errors::VerifierError;
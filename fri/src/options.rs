@@ -3,17 +3,26 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use alloc::vec::Vec;
+
 use math::StarkField;
 
 // FRI OPTIONS
 // ================================================================================================
 
 /// FRI protocol config options for proof generation and verification.
+///
+/// By default, the degree of a polynomial is reduced by the same `folding_factor` at every FRI
+/// layer. A per-layer folding schedule can be specified via
+/// [with_folding_schedule()](FriOptions::with_folding_schedule); in this case, the folding
+/// factors in the schedule are applied to the first layers, and the remaining layers (if any) are
+/// folded by `folding_factor`.
 #[derive(Clone, PartialEq, Eq)]
 pub struct FriOptions {
     folding_factor: usize,
     remainder_max_degree: usize,
     blowup_factor: usize,
+    folding_schedule: Vec<usize>,
 }
 
 impl FriOptions {
@@ -30,17 +39,39 @@ impl FriOptions {
             "blowup factor must be a power of two, but was {blowup_factor}"
         );
         assert!(
-            folding_factor == 2
-                || folding_factor == 4
-                || folding_factor == 8
-                || folding_factor == 16,
+            is_supported_folding_factor(folding_factor),
             "folding factor {folding_factor} is not supported"
         );
         FriOptions {
             folding_factor,
             remainder_max_degree,
             blowup_factor,
+            folding_schedule: Vec::new(),
+        }
+    }
+
+    /// Returns a new [FriOptions] struct with the per-layer folding schedule set to the specified
+    /// folding factors.
+    ///
+    /// The i-th folding factor in the schedule is used to fold the i-th FRI layer; layers beyond
+    /// the end of the schedule are folded by the default `folding_factor`.
+    ///
+    /// The first layer can be folded by a factor of up to 32. When the initial domain is large,
+    /// folding it by a large factor skips several layers (and thus, several Merkle authentication
+    /// paths per query) while keeping subsequent layers at smaller folding factors.
+    ///
+    /// # Panics
+    /// Panics if the first folding factor in the schedule is not 2, 4, 8, 16, or 32, or if any
+    /// other folding factor in the schedule is not 2, 4, 8, or 16.
+    pub fn with_folding_schedule(mut self, folding_schedule: &[usize]) -> Self {
+        for (depth, &folding_factor) in folding_schedule.iter().enumerate() {
+            assert!(
+                is_supported_folding_factor_at(depth, folding_factor),
+                "folding factor {folding_factor} is not supported at FRI layer {depth}"
+            );
         }
+        self.folding_schedule = folding_schedule.to_vec();
+        self
     }
 
     /// Returns the offset by which the evaluation domain is shifted.
@@ -61,10 +92,28 @@ impl FriOptions {
         self.folding_factor
     }
 
+    /// Returns the per-layer folding schedule; the schedule is empty if the same folding factor
+    /// is used for all layers.
+    pub fn folding_schedule(&self) -> &[usize] {
+        &self.folding_schedule
+    }
+
+    /// Returns the factor by which the degree of a polynomial is reduced at the FRI layer at the
+    /// specified depth.
+    pub fn folding_factor_at(&self, depth: usize) -> usize {
+        self.folding_schedule.get(depth).copied().unwrap_or(self.folding_factor)
+    }
+
     /// Returns maximum allowed remainder polynomial degree.
     ///
     /// In combination with `folding_factor` this property defines how many FRI layers are needed
-    /// for an evaluation domain of a given size.
+    /// for an evaluation domain of a given size: the degree of the polynomial is reduced until it
+    /// does not exceed the maximum remainder degree. The remainder polynomial is sent to the
+    /// verifier in coefficient form, and thus, the maximum remainder degree also bounds the number
+    /// of remainder coefficients in a FRI proof. The degree does not need to be one less than a
+    /// power of two; however, since each FRI layer reduces the degree by a power of two, the
+    /// actual degree bound of the remainder is the largest power of two minus one which does not
+    /// exceed the maximum remainder degree.
     pub fn remainder_max_degree(&self) -> usize {
         self.remainder_max_degree
     }
@@ -80,15 +129,91 @@ impl FriOptions {
 
     /// Computes and return the number of FRI layers required for a domain of the specified size.
     ///
-    /// The number of layers for a given domain size is defined by the `folding_factor` and
-    /// `remainder_max_degree` and `blowup_factor` settings.
-    pub fn num_fri_layers(&self, mut domain_size: usize) -> usize {
-        let mut result = 0;
+    /// The number of layers for a given domain size is defined by the `folding_factor` (and the
+    /// folding schedule, if any) and `remainder_max_degree` and `blowup_factor` settings.
+    pub fn num_fri_layers(&self, domain_size: usize) -> usize {
+        self.folding_factors(domain_size).len()
+    }
+
+    /// Returns the folding factors applied to each of the FRI layers required for a domain of the
+    /// specified size.
+    pub fn folding_factors(&self, mut domain_size: usize) -> Vec<usize> {
+        let mut result = Vec::new();
         let max_remainder_size = (self.remainder_max_degree + 1) * self.blowup_factor;
         while domain_size > max_remainder_size {
-            domain_size /= self.folding_factor;
-            result += 1;
+            let folding_factor = self.folding_factor_at(result.len());
+            domain_size /= folding_factor;
+            result.push(folding_factor);
         }
         result
     }
+
+    /// Returns a folding schedule which minimizes the size of a FRI proof for a domain of the
+    /// specified size.
+    ///
+    /// The size of a proof is estimated per query: at each layer, a query requires as many field
+    /// elements as the folding factor of the layer (each `element_size` bytes long), and a Merkle
+    /// authentication path with one digest (`digest_size` bytes long) per level of the layer
+    /// tree. The number of FRI layers is chosen so that the degree of the remainder polynomial
+    /// does not exceed `remainder_max_degree`.
+    ///
+    /// The returned schedule can be passed to
+    /// [with_folding_schedule()](FriOptions::with_folding_schedule).
+    pub fn optimal_folding_schedule(
+        &self,
+        domain_size: usize,
+        element_size: usize,
+        digest_size: usize,
+    ) -> Vec<usize> {
+        let blowup_depth = self.blowup_factor.ilog2() as usize;
+        let degree_depth = (domain_size.ilog2() as usize).saturating_sub(blowup_depth);
+        let remainder_depth = (self.remainder_max_degree + 1).ilog2() as usize;
+
+        // best[d] holds the estimated size of the proof for a polynomial of degree 2^d - 1 and
+        // the log2 of the folding factor of the first layer which achieves this size
+        let mut best = vec![(0, 0); degree_depth + 1];
+        for depth in (remainder_depth + 1)..=degree_depth {
+            // only the first layer can be folded by the largest factor
+            let max_folding_depth = if depth == degree_depth {
+                MAX_FIRST_LAYER_FOLDING_DEPTH
+            } else {
+                MAX_FOLDING_DEPTH
+            };
+            best[depth] = (1..=max_folding_depth.min(depth))
+                .map(|folding_depth| {
+                    let tree_depth = depth + blowup_depth - folding_depth;
+                    let layer_size = (element_size << folding_depth) + tree_depth * digest_size;
+                    (layer_size + best[depth - folding_depth].0, folding_depth)
+                })
+                .min()
+                .expect("at least one folding factor is possible");
+        }
+
+        let mut result = Vec::new();
+        let mut depth = degree_depth;
+        while depth > remainder_depth {
+            let folding_depth = best[depth].1;
+            result.push(1 << folding_depth);
+            depth -= folding_depth;
+        }
+        result
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Log2 of the largest supported folding factor.
+const MAX_FOLDING_DEPTH: usize = 4;
+
+/// Log2 of the largest supported folding factor for the first FRI layer.
+const MAX_FIRST_LAYER_FOLDING_DEPTH: usize = 5;
+
+fn is_supported_folding_factor(folding_factor: usize) -> bool {
+    matches!(folding_factor, 2 | 4 | 8 | 16)
+}
+
+fn is_supported_folding_factor_at(depth: usize, folding_factor: usize) -> bool {
+    is_supported_folding_factor(folding_factor)
+        || (depth == 0 && folding_factor == 1 << MAX_FIRST_LAYER_FOLDING_DEPTH)
 }
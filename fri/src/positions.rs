@@ -0,0 +1,118 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Contains functions for deriving query positions for a STARK proof and for all FRI layers.
+//!
+//! Query positions are drawn once for the evaluation domain via [draw_query_positions()], which is
+//! used by the STARK prover and verifier for queries against trace and constraint commitments and
+//! against the first FRI layer. Positions at subsequent FRI layers are derived from these
+//! positions via [fold_positions_for_layers()]. These functions are public so that external
+//! implementations of the verifier (e.g., a verifier implemented in a circuit) can reproduce the
+//! query positions exactly.
+
+use alloc::vec::Vec;
+
+use crypto::{RandomCoin, RandomCoinError};
+
+use crate::folding::fold_positions;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum number of rounds in which query positions are drawn when some positions are excluded
+/// from sampling (see [draw_query_positions_with_exclusions()]).
+pub const MAX_DRAW_ROUNDS: usize = 100;
+
+// QUERY POSITIONS
+// ================================================================================================
+
+/// Draws `num_queries` positions in a domain of size `domain_size` from the specified public
+/// coin, using `nonce` as the proof-of-work nonce.
+///
+/// The positions are drawn uniformly at random, and then sorted and de-duplicated via
+/// [dedup_positions()]; thus, the returned vector may contain fewer than `num_queries` positions.
+///
+/// # Errors
+/// Returns an error if the positions could not be drawn from the public coin.
+pub fn draw_query_positions<R: RandomCoin>(
+    public_coin: &mut R,
+    num_queries: usize,
+    domain_size: usize,
+    nonce: u64,
+) -> Result<Vec<usize>, RandomCoinError> {
+    draw_query_positions_with_exclusions(public_coin, num_queries, domain_size, nonce, |_| false)
+}
+
+/// Draws `num_queries` positions in a domain of size `domain_size` from the specified public
+/// coin, skipping all positions for which `is_excluded` returns true.
+///
+/// The positions are drawn in rounds: in each round, `num_queries` integers are drawn from the
+/// public coin via [RandomCoin::draw_integers()], and integers which are not excluded are kept
+/// until `num_queries` integers are collected. The collected integers are then sorted and
+/// de-duplicated via [dedup_positions()]. When no positions are excluded, the result is the same
+/// as the one of [draw_query_positions()].
+///
+/// # Errors
+/// Returns an error if the positions could not be drawn from the public coin, or if fewer than
+/// `num_queries` allowed positions were drawn in [MAX_DRAW_ROUNDS] rounds.
+pub fn draw_query_positions_with_exclusions<R, F>(
+    public_coin: &mut R,
+    num_queries: usize,
+    domain_size: usize,
+    nonce: u64,
+    is_excluded: F,
+) -> Result<Vec<usize>, RandomCoinError>
+where
+    R: RandomCoin,
+    F: Fn(usize) -> bool,
+{
+    let mut positions = Vec::with_capacity(num_queries);
+    for _ in 0..MAX_DRAW_ROUNDS {
+        let values = public_coin.draw_integers(num_queries, domain_size, nonce)?;
+        let num_missing = num_queries - positions.len();
+        positions.extend(values.into_iter().filter(|&p| !is_excluded(p)).take(num_missing));
+        if positions.len() == num_queries {
+            dedup_positions(&mut positions);
+            return Ok(positions);
+        }
+    }
+
+    Err(RandomCoinError::FailedToDrawIntegers(
+        num_queries,
+        positions.len(),
+        MAX_DRAW_ROUNDS,
+    ))
+}
+
+/// Sorts the specified positions in ascending order and removes duplicates from them.
+///
+/// Each position is opened in a proof only once, and openings of positions which are siblings in
+/// a Merkle tree share their authentication paths.
+pub fn dedup_positions(positions: &mut Vec<usize>) {
+    positions.sort_unstable();
+    positions.dedup();
+}
+
+/// Returns positions queried at each FRI layer for the specified positions in the evaluation
+/// domain of size `domain_size`, where the i-th FRI layer is folded by the i-th of the specified
+/// `folding_factors` (see [FriOptions::folding_factors()](crate::FriOptions::folding_factors)).
+///
+/// The i-th element of the result contains positions of the leaves of the i-th layer's Merkle
+/// tree which are opened for the queries, as computed by [fold_positions()]. These positions are
+/// not sorted; they are listed in the order in which they are first reached from `positions`.
+pub fn fold_positions_for_layers(
+    positions: &[usize],
+    mut domain_size: usize,
+    folding_factors: &[usize],
+) -> Vec<Vec<usize>> {
+    let mut result = Vec::with_capacity(folding_factors.len());
+    let mut positions = positions.to_vec();
+    for &folding_factor in folding_factors {
+        positions = fold_positions(&positions, domain_size, folding_factor);
+        result.push(positions.clone());
+        domain_size /= folding_factor;
+    }
+    result
+}
@@ -3,9 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use alloc::{string::ToString, vec::Vec};
+use alloc::{format, string::ToString, vec::Vec};
 
-use crypto::{BatchMerkleProof, ElementHasher, Hasher};
+use crypto::{BatchMerkleProof, ElementHasher, Hasher, MerkleNodeDictionary};
 use math::FieldElement;
 use utils::{
     ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
@@ -102,6 +102,20 @@ impl FriProof {
         2usize.pow(self.num_partitions as u32)
     }
 
+    /// Returns the layers of this proof.
+    pub(crate) fn layers(&self) -> &[FriProofLayer] {
+        &self.layers
+    }
+
+    /// Returns a borrowed view of this proof.
+    pub fn as_borrowed(&self) -> FriProofRef<'_> {
+        FriProofRef {
+            layers: self.layers.iter().map(FriProofLayer::as_borrowed).collect(),
+            remainder: &self.remainder,
+            num_partitions: self.num_partitions,
+        }
+    }
+
     /// Returns the size of this proof in bytes.
     pub fn size(&self) -> usize {
         // +1 for number of layers, +1 for remainder length, +1 for number of partitions
@@ -128,22 +142,247 @@ impl FriProof {
     #[allow(clippy::type_complexity)]
     pub fn parse_layers<H, E>(
         self,
-        mut domain_size: usize,
+        domain_size: usize,
         folding_factor: usize,
     ) -> Result<(Vec<Vec<E>>, Vec<BatchMerkleProof<H>>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        let folding_factors = vec![folding_factor; self.layers.len()];
+        self.parse_layers_with_folding_factors(domain_size, &folding_factors)
+    }
+
+    /// Decomposes this proof into vectors of query values for each layer and corresponding Merkle
+    /// authentication paths for each query (grouped into batch Merkle proofs), assuming that the
+    /// i-th layer was folded by the i-th of the specified `folding_factors`.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is not a power of two.
+    /// * Any of the `folding_factors` is smaller than two or is not a power of two.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * This proof is not consistent with the specified `domain_size` and `folding_factors`.
+    /// * This proof contains more layers than the number of specified `folding_factors`.
+    /// * Any of the layers could not be parsed successfully.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_layers_with_folding_factors<H, E>(
+        self,
+        domain_size: usize,
+        folding_factors: &[usize],
+    ) -> Result<(Vec<Vec<E>>, Vec<BatchMerkleProof<H>>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        self.as_borrowed()
+            .parse_layers_with_folding_factors(domain_size, folding_factors)
+    }
+
+    /// Returns a vector of remainder values (last FRI layer) parsed from this proof.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of remainder values implied by a combination of `E` type parameter and
+    ///   the number of remainder bytes in this proof is not a power of two.
+    /// * Any of the remainder values could not be parsed correctly.
+    /// * Not all bytes have been consumed while parsing remainder values.
+    pub fn parse_remainder<E: FieldElement>(&self) -> Result<Vec<E>, DeserializationError> {
+        self.as_borrowed().parse_remainder()
+    }
+}
+
+// SERIALIZATION / DESERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl FriProof {
+    /// Writes this proof into the `target` in a compact form in which Merkle tree nodes already
+    /// present in the `nodes` dictionary are replaced with references to them.
+    ///
+    /// # Errors
+    /// Returns an error if Merkle paths of any of the proof layers could not be parsed.
+    pub fn write_compressed<H: Hasher, W: ByteWriter>(
+        &self,
+        target: &mut W,
+        nodes: &mut MerkleNodeDictionary<H>,
+    ) -> Result<(), DeserializationError> {
+        target.write_u8(self.layers.len() as u8);
+        for layer in self.layers.iter() {
+            layer.write_compressed(target, nodes)?;
+        }
+
+        target.write_u16(self.remainder.len() as u16);
+        target.write_bytes(&self.remainder);
+        target.write_u8(self.num_partitions);
+
+        Ok(())
+    }
+
+    /// Reads a proof previously written via [write_compressed()](Self::write_compressed) from the
+    /// `source`, resolving references to Merkle tree nodes against the `nodes` dictionary.
+    ///
+    /// # Errors
+    /// Returns an error if a valid proof could not be read from the source.
+    pub fn read_compressed<H: Hasher, R: ByteReader>(
+        source: &mut R,
+        nodes: &mut MerkleNodeDictionary<H>,
+    ) -> Result<Self, DeserializationError> {
+        let num_layers = source.read_u8()? as usize;
+        let mut layers = Vec::with_capacity(num_layers);
+        for _ in 0..num_layers {
+            layers.push(FriProofLayer::read_compressed(source, nodes)?);
+        }
+
+        let num_remainder_bytes = source.read_u16()? as usize;
+        let remainder = source.read_vec(num_remainder_bytes)?;
+        let num_partitions = read_num_partitions(source)?;
+
+        Ok(FriProof { layers, remainder, num_partitions })
+    }
+}
+
+impl Serializable for FriProof {
+    /// Serializes `self` and writes the resulting bytes into the `target` writer.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        // write layers
+        target.write_u8(self.layers.len() as u8);
+        for layer in self.layers.iter() {
+            layer.write_into(target);
+        }
+
+        // write remainder
+        target.write_u16(self.remainder.len() as u16);
+        target.write_bytes(&self.remainder);
+
+        // write number of partitions
+        target.write_u8(self.num_partitions);
+    }
+}
+
+impl Deserializable for FriProof {
+    /// Reads a FRI proof from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid proof could not be read from the source.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        // read layers
+        let layers = source.read_field("layers", |source| {
+            let num_layers = source.read_u8()? as usize;
+            let mut layers = Vec::with_capacity(num_layers);
+            for i in 0..num_layers {
+                layers.push(source.read_indexed(i, FriProofLayer::read_from)?);
+            }
+            Ok(layers)
+        })?;
+
+        // read remainder
+        let remainder = source.read_field("remainder", |source| {
+            let num_remainder_bytes = source.read_u16()? as usize;
+            source.read_vec(num_remainder_bytes)
+        })?;
+
+        // read number of partitions
+        let num_partitions = source.read_field("num_partitions", read_num_partitions)?;
+
+        Ok(FriProof { layers, remainder, num_partitions })
+    }
+}
+
+// BORROWED FRI PROOF
+// ================================================================================================
+
+/// A borrowed view of a [FriProof].
+///
+/// The view references bytes of layers and of the remainder in a buffer holding a serialized
+/// proof (or in an owned [FriProof] via [FriProof::as_borrowed()]), and thus, it can be read from
+/// a buffer via [read_from()](FriProofRef::read_from) without copying any of these bytes. The
+/// values contained in the view are parsed in the same way as the ones of an owned proof.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FriProofRef<'a> {
+    layers: Vec<FriProofLayerRef<'a>>,
+    remainder: &'a [u8],
+    num_partitions: u8, // stored as power of 2
+}
+
+impl<'a> FriProofRef<'a> {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of layers in this proof.
+    pub fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns the number of remainder elements in this proof.
+    ///
+    /// The number of elements is computed by dividing the number of remainder bytes by the size
+    /// of the field element specified by `E` type parameter.
+    pub fn num_remainder_elements<E: FieldElement>(&self) -> usize {
+        self.remainder.len() / E::ELEMENT_BYTES
+    }
+
+    /// Returns the number of partitions used during proof generation.
+    pub fn num_partitions(&self) -> usize {
+        2usize.pow(self.num_partitions as u32)
+    }
+
+    /// Returns an owned copy of this proof.
+    pub fn to_owned_proof(&self) -> FriProof {
+        FriProof {
+            layers: self.layers.iter().map(FriProofLayerRef::to_owned_layer).collect(),
+            remainder: self.remainder.to_vec(),
+            num_partitions: self.num_partitions,
+        }
+    }
+
+    // PARSING
+    // --------------------------------------------------------------------------------------------
+
+    /// Decomposes this proof into vectors of query values for each layer and corresponding Merkle
+    /// authentication paths for each query (grouped into batch Merkle proofs), assuming that the
+    /// i-th layer was folded by the i-th of the specified `folding_factors`.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is not a power of two.
+    /// * Any of the `folding_factors` is smaller than two or is not a power of two.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * This proof is not consistent with the specified `domain_size` and `folding_factors`.
+    /// * This proof contains more layers than the number of specified `folding_factors`.
+    /// * Any of the layers could not be parsed successfully.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_layers_with_folding_factors<H, E>(
+        &self,
+        mut domain_size: usize,
+        folding_factors: &[usize],
+    ) -> Result<(Vec<Vec<E>>, Vec<BatchMerkleProof<H>>), DeserializationError>
     where
         E: FieldElement,
         H: ElementHasher<BaseField = E::BaseField>,
     {
         assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
-        assert!(folding_factor.is_power_of_two(), "folding factor must be a power of two");
-        assert!(folding_factor > 1, "folding factor must be greater than 1");
+        for &folding_factor in folding_factors {
+            assert!(folding_factor.is_power_of_two(), "folding factor must be a power of two");
+            assert!(folding_factor > 1, "folding factor must be greater than 1");
+        }
+        if self.layers.len() > folding_factors.len() {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected at most {} FRI layers, but the proof contains {}",
+                folding_factors.len(),
+                self.layers.len()
+            )));
+        }
 
         let mut layer_proofs = Vec::new();
         let mut layer_queries = Vec::new();
 
         // parse all layers
-        for (i, layer) in self.layers.into_iter().enumerate() {
+        for (i, layer) in self.layers.iter().enumerate() {
+            let folding_factor = folding_factors[i];
             domain_size /= folding_factor;
             let (qv, mp) = layer.parse(domain_size, folding_factor).map_err(|err| {
                 DeserializationError::InvalidValue(format!("failed to parse FRI layer {i}: {err}"))
@@ -170,7 +409,7 @@ impl FriProof {
                 "number of remainder values must be a power of two, but {num_elements} was implied"
             )));
         }
-        let mut reader = SliceReader::new(&self.remainder);
+        let mut reader = SliceReader::new(self.remainder);
         let remainder = reader.read_many(num_elements).map_err(|err| {
             DeserializationError::InvalidValue(format!("failed to parse FRI remainder: {err}"))
         })?;
@@ -179,47 +418,36 @@ impl FriProof {
         }
         Ok(remainder)
     }
-}
-
-// SERIALIZATION / DESERIALIZATION
-// ------------------------------------------------------------------------------------------------
-
-impl Serializable for FriProof {
-    /// Serializes `self` and writes the resulting bytes into the `target` writer.
-    fn write_into<W: ByteWriter>(&self, target: &mut W) {
-        // write layers
-        target.write_u8(self.layers.len() as u8);
-        for layer in self.layers.iter() {
-            layer.write_into(target);
-        }
-
-        // write remainder
-        target.write_u16(self.remainder.len() as u16);
-        target.write_bytes(&self.remainder);
 
-        // write number of partitions
-        target.write_u8(self.num_partitions);
-    }
-}
+    // DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
 
-impl Deserializable for FriProof {
-    /// Reads a FRI proof from the specified `source` and returns the result.
+    /// Reads a view of a FRI proof serialized via [FriProof::to_bytes()](Serializable::to_bytes)
+    /// from the specified `source`; the view borrows the bytes of the source.
     ///
     /// # Errors
     /// Returns an error if a valid proof could not be read from the source.
-    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+    pub fn read_from(source: &mut SliceReader<'a>) -> Result<Self, DeserializationError> {
         // read layers
-        let num_layers = source.read_u8()? as usize;
-        let layers = source.read_many(num_layers)?;
+        let layers = source.read_field("layers", |source| {
+            let num_layers = source.read_u8()? as usize;
+            let mut layers = Vec::with_capacity(num_layers);
+            for i in 0..num_layers {
+                layers.push(source.read_indexed(i, FriProofLayerRef::read_from)?);
+            }
+            Ok(layers)
+        })?;
 
         // read remainder
-        let num_remainder_bytes = source.read_u16()? as usize;
-        let remainder = source.read_vec(num_remainder_bytes)?;
+        let remainder = source.read_field("remainder", |source| {
+            let num_remainder_bytes = source.read_u16()? as usize;
+            source.read_borrowed_slice(num_remainder_bytes)
+        })?;
 
         // read number of partitions
-        let num_partitions = source.read_u8()?;
+        let num_partitions = source.read_field("num_partitions", read_num_partitions)?;
 
-        Ok(FriProof { layers, remainder, num_partitions })
+        Ok(FriProofRef { layers, remainder, num_partitions })
     }
 }
 
@@ -269,6 +497,11 @@ impl FriProofLayer {
         self.values.len() + 4 + self.paths.len() + 4
     }
 
+    /// Returns a borrowed view of this proof layer.
+    fn as_borrowed(&self) -> FriProofLayerRef<'_> {
+        FriProofLayerRef { values: &self.values, paths: &self.paths }
+    }
+
     // PARSING
     // --------------------------------------------------------------------------------------------
     /// Decomposes this layer into a combination of query values and corresponding Merkle
@@ -280,7 +513,122 @@ impl FriProofLayer {
     /// * Parsing of any of the query values or the corresponding Merkle paths fails.
     /// * Not all bytes have been consumed while parsing this layer.
     pub fn parse<H, E>(
-        self,
+        &self,
+        domain_size: usize,
+        folding_factor: usize,
+    ) -> Result<(Vec<E>, BatchMerkleProof<H>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        self.as_borrowed().parse(domain_size, folding_factor)
+    }
+}
+
+// SERIALIZATION / DESERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl FriProofLayer {
+    /// Writes this layer into the `target` replacing Merkle tree nodes already present in the
+    /// `nodes` dictionary with references to them.
+    fn write_compressed<H: Hasher, W: ByteWriter>(
+        &self,
+        target: &mut W,
+        nodes: &mut MerkleNodeDictionary<H>,
+    ) -> Result<(), DeserializationError> {
+        target.write_usize(self.values.len());
+        target.write_bytes(&self.values);
+        nodes.write_nodes(&self.paths, target)
+    }
+
+    /// Reads a layer previously written via [write_compressed()](Self::write_compressed) from
+    /// the `source`.
+    fn read_compressed<H: Hasher, R: ByteReader>(
+        source: &mut R,
+        nodes: &mut MerkleNodeDictionary<H>,
+    ) -> Result<Self, DeserializationError> {
+        let num_value_bytes = source.read_usize()?;
+        if num_value_bytes == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "a FRI proof layer must contain at least one queried evaluation".to_string(),
+            ));
+        }
+        let values = source.read_vec(num_value_bytes)?;
+        let paths = nodes.read_nodes(source)?;
+
+        Ok(FriProofLayer { values, paths })
+    }
+}
+
+impl Serializable for FriProofLayer {
+    /// Serializes this proof layer and writes the resulting bytes to the specified `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        // write value bytes
+        target.write_u32(self.values.len() as u32);
+        target.write_bytes(&self.values);
+
+        // write path bytes
+        target.write_u32(self.paths.len() as u32);
+        target.write_bytes(&self.paths);
+    }
+}
+
+impl Deserializable for FriProofLayer {
+    /// Reads a single proof layer form the `source` and returns it.
+    ///
+    /// # Errors
+    /// Returns an error if a valid layer could not be read from the specified source.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        // read values
+        let values = source.read_field("values", |source| {
+            let num_value_bytes = source.read_u32()?;
+            if num_value_bytes == 0 {
+                return Err(DeserializationError::InvalidValue(
+                    "a FRI proof layer must contain at least one queried evaluation".to_string(),
+                ));
+            }
+            source.read_vec(num_value_bytes as usize)
+        })?;
+
+        // read paths
+        let paths = source.read_field("paths", |source| {
+            let num_paths_bytes = source.read_u32()?;
+            source.read_vec(num_paths_bytes as usize)
+        })?;
+
+        Ok(FriProofLayer { values, paths })
+    }
+}
+
+// BORROWED FRI PROOF LAYER
+// ================================================================================================
+
+/// A borrowed view of a [FriProofLayer].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FriProofLayerRef<'a> {
+    values: &'a [u8],
+    paths: &'a [u8],
+}
+
+impl<'a> FriProofLayerRef<'a> {
+    /// Returns an owned copy of this proof layer.
+    fn to_owned_layer(&self) -> FriProofLayer {
+        FriProofLayer {
+            values: self.values.to_vec(),
+            paths: self.paths.to_vec(),
+        }
+    }
+
+    /// Decomposes this layer into a combination of query values and corresponding Merkle
+    /// authentication paths (grouped together into a single batch Merkle proof).
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * This layer does not contain at least one query.
+    /// * Parsing of any of the query values or the corresponding Merkle paths fails.
+    /// * Not all bytes have been consumed while parsing this layer.
+    pub fn parse<H, E>(
+        &self,
         domain_size: usize,
         folding_factor: usize,
     ) -> Result<(Vec<E>, BatchMerkleProof<H>), DeserializationError>
@@ -303,23 +651,25 @@ impl FriProofLayer {
                 "a FRI layer must contain at least one query".to_string(),
             ));
         }
-        let mut hashed_queries = vec![H::Digest::default(); num_queries];
-        let mut query_values = Vec::with_capacity(num_queries * folding_factor);
-
-        // read bytes corresponding to each query, convert them into field elements,
-        // and also hash them to build leaf nodes of the batch Merkle proof
-        let mut reader = SliceReader::new(&self.values);
-        for query_hash in hashed_queries.iter_mut() {
-            let mut qe = reader.read_many(folding_factor)?;
-            *query_hash = H::hash_elements(&qe);
-            query_values.append(&mut qe);
-        }
+
+        // read the values of all queries directly into the spare capacity of the vector; the
+        // number of values is implied by the length of the value bytes, and thus, all bytes will
+        // be consumed if the read succeeds
+        let num_values = num_queries * folding_factor;
+        let mut query_values = Vec::with_capacity(num_values);
+        let mut reader = SliceReader::new(self.values);
+        reader.read_many_into(&mut query_values.spare_capacity_mut()[..num_values])?;
+        // SAFETY: all `num_values` elements have been initialized by the read above
+        unsafe { query_values.set_len(num_values) };
         if reader.has_more_bytes() {
             return Err(DeserializationError::UnconsumedBytes);
         }
 
+        // hash the values of each query to build leaf nodes of the batch Merkle proof
+        let hashed_queries = query_values.chunks(folding_factor).map(H::hash_elements).collect();
+
         // build batch Merkle proof
-        let mut reader = SliceReader::new(&self.paths);
+        let mut reader = SliceReader::new(self.paths);
         let tree_depth = domain_size.ilog2() as u8;
         let merkle_proof = BatchMerkleProof::deserialize(&mut reader, hashed_queries, tree_depth)?;
         if reader.has_more_bytes() {
@@ -328,43 +678,46 @@ impl FriProofLayer {
 
         Ok((query_values, merkle_proof))
     }
-}
-
-// SERIALIZATION / DESERIALIZATION
-// ------------------------------------------------------------------------------------------------
-
-impl Serializable for FriProofLayer {
-    /// Serializes this proof layer and writes the resulting bytes to the specified `target`.
-    fn write_into<W: ByteWriter>(&self, target: &mut W) {
-        // write value bytes
-        target.write_u32(self.values.len() as u32);
-        target.write_bytes(&self.values);
-
-        // write path bytes
-        target.write_u32(self.paths.len() as u32);
-        target.write_bytes(&self.paths);
-    }
-}
 
-impl Deserializable for FriProofLayer {
-    /// Reads a single proof layer form the `source` and returns it.
+    /// Reads a view of a single proof layer from the `source`; the view borrows the bytes of the
+    /// source.
     ///
     /// # Errors
     /// Returns an error if a valid layer could not be read from the specified source.
-    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+    fn read_from(source: &mut SliceReader<'a>) -> Result<Self, DeserializationError> {
         // read values
-        let num_value_bytes = source.read_u32()?;
-        if num_value_bytes == 0 {
-            return Err(DeserializationError::InvalidValue(
-                "a FRI proof layer must contain at least one queried evaluation".to_string(),
-            ));
-        }
-        let values = source.read_vec(num_value_bytes as usize)?;
+        let values = source.read_field("values", |source| {
+            let num_value_bytes = source.read_u32()?;
+            if num_value_bytes == 0 {
+                return Err(DeserializationError::InvalidValue(
+                    "a FRI proof layer must contain at least one queried evaluation".to_string(),
+                ));
+            }
+            source.read_borrowed_slice(num_value_bytes as usize)
+        })?;
 
         // read paths
-        let num_paths_bytes = source.read_u32()?;
-        let paths = source.read_vec(num_paths_bytes as usize)?;
+        let paths = source.read_field("paths", |source| {
+            let num_paths_bytes = source.read_u32()?;
+            source.read_borrowed_slice(num_paths_bytes as usize)
+        })?;
 
-        Ok(FriProofLayer { values, paths })
+        Ok(FriProofLayerRef { values, paths })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads the number of partitions of a FRI proof (stored as a power of two) from the `source`.
+fn read_num_partitions<R: ByteReader>(source: &mut R) -> Result<u8, DeserializationError> {
+    let num_partitions = source.read_u8()?;
+    if num_partitions as u32 >= usize::BITS {
+        return Err(DeserializationError::InvalidValue(format!(
+            "number of partitions cannot be greater than or equal to 2^{}, but was 2^{}",
+            usize::BITS,
+            num_partitions
+        )));
     }
+    Ok(num_partitions)
 }
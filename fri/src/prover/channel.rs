@@ -9,6 +9,8 @@ use core::marker::PhantomData;
 use crypto::{ElementHasher, Hasher, RandomCoin};
 use math::FieldElement;
 
+use crate::positions;
+
 // PROVER CHANNEL TRAIT
 // ================================================================================================
 
@@ -100,16 +102,20 @@ where
     /// layer should be queried.
     ///
     /// The positions are pseudo-randomly generated based on the values the prover has written
-    /// into this channel and a PoW nonce.
+    /// into this channel and a PoW nonce, and are sorted and de-duplicated in the same way as for
+    /// STARK proofs (see [draw_query_positions()](crate::positions::draw_query_positions)).
     ///
     /// # Panics
-    /// Panics if the specified number of unique positions could not be drawn from the specified
-    /// domain. Both number of queried positions and domain size are specified during
-    /// construction of the channel.
+    /// Panics if the positions could not be drawn from the specified domain. Both number of
+    /// queried positions and domain size are specified during construction of the channel.
     pub fn draw_query_positions(&mut self, nonce: u64) -> Vec<usize> {
-        self.public_coin
-            .draw_integers(self.num_queries, self.domain_size, nonce)
-            .expect("failed to draw query position")
+        positions::draw_query_positions(
+            &mut self.public_coin,
+            self.num_queries,
+            self.domain_size,
+            nonce,
+        )
+        .expect("failed to draw query position")
     }
 
     /// Returns a list of FRI layer commitments written by the prover into this channel.
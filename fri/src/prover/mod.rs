@@ -8,10 +8,11 @@ use core::marker::PhantomData;
 
 use crypto::{ElementHasher, Hasher, MerkleTree};
 use math::{fft, FieldElement, StarkField};
-use utils::{flatten_vector_elements, group_slice_elements, transpose_slice};
+use utils::{flatten_vector_elements, group_slice_elements, transpose_slice, Arena};
 
 use crate::{
-    folding::{apply_drp, fold_positions},
+    folding::{apply_drp_with_inv_offsets, fill_inv_offsets},
+    positions::fold_positions_for_layers,
     proof::{FriProof, FriProofLayer},
     utils::hash_values,
     FriOptions,
@@ -20,6 +21,9 @@ use crate::{
 mod channel;
 pub use channel::{DefaultProverChannel, ProverChannel};
 
+mod store;
+pub use store::{LayerStore, MemoryLayerStore};
+
 #[cfg(test)]
 mod tests;
 
@@ -42,6 +46,8 @@ mod tests;
 /// * `C` specifies the type used to simulate prover-verifier interaction.
 /// * `H` specifies the hash function used to build layer Merkle trees. The same hash function
 ///   must be used in the prover channel to generate pseudo random values.
+/// * `S` specifies the type used to store FRI layers between the commit phase and the query
+///   phase. By default, the layers are kept in memory (see [MemoryLayerStore]).
 ///
 /// Proof generation is performed in two phases: commit phase and query phase.
 ///
@@ -67,8 +73,9 @@ mod tests;
 /// sends it to the prover. In the non-interactive version, α is pseudo-randomly generated based
 /// on the values the prover has written into the channel up to that point.
 ///
-/// The prover keeps all FRI layers (consisting of evaluations and corresponding Merkle trees) in
-/// its internal state.
+/// The prover hands off all FRI layers (consisting of evaluations and corresponding Merkle trees)
+/// to its [LayerStore]. A prover with a custom store can be instantiated via
+/// [with_store()](FriProver::with_store()).
 ///
 /// # Query phase
 /// In the query phase, which is executed via [build_proof()](FriProver::build_proof()) function,
@@ -85,29 +92,26 @@ mod tests;
 /// layer. To map these positions to the positions in all subsequent layers, the prover uses
 /// [fold_positions] procedure.
 ///
-/// After the proof is generated, the prover deletes all internally stored FRI layers.
+/// After the proof is generated, the prover clears its layer store.
 ///
 /// Calling [build_layers()](FriProver::build_layers()) when the internal state is dirty, or
 /// calling [build_proof()](FriProver::build_proof()) on a clean state will result in a panic.
-pub struct FriProver<B, E, C, H>
+pub struct FriProver<B, E, C, H, S = MemoryLayerStore<E, H>>
 where
     B: StarkField,
     E: FieldElement<BaseField = B>,
     C: ProverChannel<E, Hasher = H>,
     H: ElementHasher<BaseField = B>,
+    S: LayerStore<E, H>,
 {
     options: FriOptions,
-    layers: Vec<FriLayer<B, E, H>>,
+    store: S,
+    domain_size: usize,
+    layer_alphas: Vec<E>,
     remainder_poly: FriRemainder<E>,
     _channel: PhantomData<C>,
 }
 
-struct FriLayer<B: StarkField, E: FieldElement<BaseField = B>, H: Hasher> {
-    tree: MerkleTree<H>,
-    evaluations: Vec<E>,
-    _base_field: PhantomData<B>,
-}
-
 struct FriRemainder<E: FieldElement>(Vec<E>);
 
 // PROVER IMPLEMENTATION
@@ -122,11 +126,33 @@ where
 {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// Returns a new FRI prover instantiated with the provided `options`.
+    /// Returns a new FRI prover instantiated with the provided `options` which keeps FRI layers
+    /// in memory.
     pub fn new(options: FriOptions) -> Self {
+        Self::with_store(options, MemoryLayerStore::new())
+    }
+}
+
+impl<B, E, C, H, S> FriProver<B, E, C, H, S>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    C: ProverChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = B>,
+    S: LayerStore<E, H>,
+{
+    /// Returns a new FRI prover instantiated with the provided `options` which hands off FRI
+    /// layers to the provided `store`.
+    ///
+    /// # Panics
+    /// Panics if the `store` is not empty.
+    pub fn with_store(options: FriOptions, store: S) -> Self {
+        assert_eq!(store.num_layers(), 0, "layer store must be empty");
         FriProver {
             options,
-            layers: Vec::new(),
+            store,
+            domain_size: 0,
+            layer_alphas: Vec::new(),
             remainder_poly: FriRemainder(vec![]),
             _channel: PhantomData,
         }
@@ -148,12 +174,32 @@ where
     /// Returns number of FRI layers computed during the last execution of the
     /// [build_layers()](FriProver::build_layers()) method.
     pub fn num_layers(&self) -> usize {
-        self.layers.len()
+        self.store.num_layers()
     }
 
-    /// Clears a vector of internally stored layers.
+    /// Returns the random values drawn from the channel for folding each FRI layer during the last
+    /// execution of the [build_layers()](FriProver::build_layers()) method.
+    ///
+    /// The values are available until the proof is built via
+    /// [build_proof()](FriProver::build_proof()).
+    pub fn layer_alphas(&self) -> &[E] {
+        &self.layer_alphas
+    }
+
+    /// Returns coefficients of the remainder polynomial computed during the last execution of the
+    /// [build_layers()](FriProver::build_layers()) method.
+    ///
+    /// Together with [layer_alphas()](FriProver::layer_alphas), this allows a recursive prover to
+    /// defer the final low-degree check of the FRI protocol. The polynomial is available until the
+    /// proof is built via [build_proof()](FriProver::build_proof()).
+    pub fn remainder_poly(&self) -> &[E] {
+        &self.remainder_poly.0
+    }
+
+    /// Clears the layer store of this prover.
     pub fn reset(&mut self) {
-        self.layers.clear();
+        self.store.clear();
+        self.layer_alphas.clear();
         self.remainder_poly.0.clear();
     }
 
@@ -174,19 +220,33 @@ where
     /// Panics if the prover state is dirty (the vector of layers is not empty).
     pub fn build_layers(&mut self, channel: &mut C, mut evaluations: Vec<E>) {
         assert!(
-            self.layers.is_empty(),
+            self.store.num_layers() == 0,
             "a prior proof generation request has not been completed yet"
         );
-
-        // reduce the degree by folding_factor at each iteration until the remaining polynomial
-        // has small enough degree
-        for _ in 0..self.options.num_fri_layers(evaluations.len()) {
-            match self.folding_factor() {
-                2 => self.build_layer::<2>(channel, &mut evaluations),
-                4 => self.build_layer::<4>(channel, &mut evaluations),
-                8 => self.build_layer::<8>(channel, &mut evaluations),
-                16 => self.build_layer::<16>(channel, &mut evaluations),
-                _ => unimplemented!("folding factor {} is not supported", self.folding_factor()),
+        self.domain_size = evaluations.len();
+
+        // scratch memory for temporary buffers; the arena is reset and reused for every layer
+        let mut scratch = Arena::new();
+
+        // reduce the degree by the folding factor of each layer at each iteration until the
+        // remaining polynomial has small enough degree
+        for folding_factor in self.options.folding_factors(evaluations.len()) {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::info_span!(
+                "build_fri_layer",
+                layer_idx = self.store.num_layers(),
+                domain_size = evaluations.len(),
+                folding_factor
+            )
+            .entered();
+
+            match folding_factor {
+                2 => self.build_layer::<2>(channel, &mut evaluations, &mut scratch),
+                4 => self.build_layer::<4>(channel, &mut evaluations, &mut scratch),
+                8 => self.build_layer::<8>(channel, &mut evaluations, &mut scratch),
+                16 => self.build_layer::<16>(channel, &mut evaluations, &mut scratch),
+                32 => self.build_layer::<32>(channel, &mut evaluations, &mut scratch),
+                _ => unimplemented!("folding factor {} is not supported", folding_factor),
             }
         }
 
@@ -195,30 +255,61 @@ where
 
     /// Builds a single FRI layer by first committing to the `evaluations`, then drawing a random
     /// alpha from the channel and use it to perform degree-respecting projection.
-    fn build_layer<const N: usize>(&mut self, channel: &mut C, evaluations: &mut Vec<E>) {
+    ///
+    /// When `concurrent` feature is enabled, the Merkle tree for the layer is built concurrently
+    /// with the part of the folding which does not depend on alpha.
+    fn build_layer<const N: usize>(
+        &mut self,
+        channel: &mut C,
+        evaluations: &mut Vec<E>,
+        scratch: &mut Arena,
+    ) {
         // commit to the evaluations at the current layer; we do this by first transposing the
         // evaluations into a matrix of N columns, and then building a Merkle tree from the
         // rows of this matrix; we do this so that we could de-commit to N values with a single
         // Merkle authentication path.
         let transposed_evaluations = transpose_slice(evaluations);
         let hashed_evaluations = hash_values::<H, E, N>(&transposed_evaluations);
-        let evaluation_tree =
-            MerkleTree::<H>::new(hashed_evaluations).expect("failed to construct FRI layer tree");
+
+        // alpha is drawn only after the layer is committed to, and thus, the layer cannot be
+        // folded until its Merkle tree is built; however, the inverses of domain offsets used
+        // during folding do not depend on alpha, and so, we compute them while the tree is being
+        // built. the buffer for the offsets is taken from the scratch arena, which is reused for
+        // all layers
+        scratch.reset();
+        let inv_offsets = scratch.alloc_slice_fill(transposed_evaluations.len(), B::ONE);
+        let domain_offset = self.options.domain_offset();
+        let build_tree = || {
+            MerkleTree::<H>::new(hashed_evaluations).expect("failed to construct FRI layer tree")
+        };
+        let build_inv_offsets = move || {
+            fill_inv_offsets(inv_offsets, domain_offset, N);
+            inv_offsets
+        };
+
+        #[cfg(feature = "concurrent")]
+        let (evaluation_tree, inv_offsets) = utils::rayon::join(build_tree, build_inv_offsets);
+
+        #[cfg(not(feature = "concurrent"))]
+        let (evaluation_tree, inv_offsets) = (build_tree(), build_inv_offsets());
+
         channel.commit_fri_layer(*evaluation_tree.root());
 
         // draw a pseudo-random coefficient from the channel, and use it in degree-respecting
         // projection to reduce the degree of evaluations by N
         let alpha = channel.draw_fri_alpha();
-        *evaluations = apply_drp(&transposed_evaluations, self.domain_offset(), alpha);
-        self.layers.push(FriLayer {
-            tree: evaluation_tree,
-            evaluations: flatten_vector_elements(transposed_evaluations),
-            _base_field: PhantomData,
-        });
+        self.layer_alphas.push(alpha);
+        *evaluations = apply_drp_with_inv_offsets(&transposed_evaluations, inv_offsets, alpha);
+        self.store
+            .store_layer(flatten_vector_elements(transposed_evaluations), evaluation_tree);
     }
 
     /// Creates remainder polynomial in coefficient form from a vector of `evaluations` over a domain.
     fn set_remainder(&mut self, channel: &mut C, evaluations: &mut [E]) {
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::info_span!("build_fri_remainder", domain_size = evaluations.len()).entered();
+
         let inv_twiddles = fft::get_inv_twiddles(evaluations.len());
         fft::interpolate_poly_with_offset(evaluations, &inv_twiddles, self.options.domain_offset());
         let remainder_poly_size = evaluations.len() / self.options.blowup_factor();
@@ -242,29 +333,35 @@ where
     pub fn build_proof(&mut self, positions: &[usize]) -> FriProof {
         assert!(!self.remainder_poly.0.is_empty(), "FRI layers have not been built yet");
 
-        let mut layers = Vec::with_capacity(self.layers.len());
+        let num_layers = self.store.num_layers();
+        let mut layers = Vec::with_capacity(num_layers);
 
-        if !self.layers.is_empty() {
-            let mut positions = positions.to_vec();
-            let mut domain_size = self.layers[0].evaluations.len();
-            let folding_factor = self.options.folding_factor();
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::info_span!("build_fri_proof", num_queries = positions.len(), num_layers)
+                .entered();
 
-            // for all FRI layers, except the last one, record tree root, determine a set of query
-            // positions, and query the layer at these positions.
-            for i in 0..self.layers.len() {
-                positions = fold_positions(&positions, domain_size, folding_factor);
+        if num_layers > 0 {
+            // determine a set of query positions for each of the FRI layers, except the remainder
+            let folding_factors = self.options.folding_factors(self.domain_size);
+            let layer_positions =
+                fold_positions_for_layers(positions, self.domain_size, &folding_factors);
 
+            // query each layer at its positions
+            for (i, (&folding_factor, positions)) in
+                folding_factors.iter().zip(layer_positions.iter()).enumerate()
+            {
                 // sort of a static dispatch for folding_factor parameter
                 let proof_layer = match folding_factor {
-                    2 => query_layer::<B, E, H, 2>(&self.layers[i], &positions),
-                    4 => query_layer::<B, E, H, 4>(&self.layers[i], &positions),
-                    8 => query_layer::<B, E, H, 8>(&self.layers[i], &positions),
-                    16 => query_layer::<B, E, H, 16>(&self.layers[i], &positions),
+                    2 => query_layer::<E, H, S, 2>(&self.store, i, positions),
+                    4 => query_layer::<E, H, S, 4>(&self.store, i, positions),
+                    8 => query_layer::<E, H, S, 8>(&self.store, i, positions),
+                    16 => query_layer::<E, H, S, 16>(&self.store, i, positions),
+                    32 => query_layer::<E, H, S, 32>(&self.store, i, positions),
                     _ => unimplemented!("folding factor {} is not supported", folding_factor),
                 };
 
                 layers.push(proof_layer);
-                domain_size /= folding_factor;
             }
         }
 
@@ -281,26 +378,21 @@ where
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Builds a single proof layer by querying the evaluations of the passed in FRI layer at the
-/// specified positions.
-fn query_layer<B: StarkField, E: FieldElement<BaseField = B>, H: Hasher, const N: usize>(
-    layer: &FriLayer<B, E, H>,
+/// Builds a single proof layer by querying the evaluations of the specified FRI layer in the
+/// layer store at the specified positions.
+fn query_layer<E: FieldElement, H: Hasher, S: LayerStore<E, H>, const N: usize>(
+    store: &S,
+    layer: usize,
     positions: &[usize],
 ) -> FriProofLayer {
     // build Merkle authentication paths for all query positions
-    let proof = layer
-        .tree
-        .prove_batch(positions)
-        .expect("failed to generate a Merkle proof for FRI layer queries");
+    let proof = store.get_layer_proof(layer, positions);
 
     // build a list of polynomial evaluations at each position; since evaluations in FRI layers
     // are stored in transposed form, a position refers to N evaluations which are committed
     // in a single leaf
-    let evaluations: &[[E; N]] = group_slice_elements(&layer.evaluations);
-    let mut queried_values: Vec<[E; N]> = Vec::with_capacity(positions.len());
-    for &position in positions.iter() {
-        queried_values.push(evaluations[position]);
-    }
+    let evaluations = store.get_layer_values(layer, positions, N);
+    let queried_values: Vec<[E; N]> = group_slice_elements(&evaluations).to_vec();
 
     FriProofLayer::new(queried_values, proof)
 }
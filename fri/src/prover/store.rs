@@ -0,0 +1,100 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use crypto::{BatchMerkleProof, Hasher, MerkleTree};
+use math::FieldElement;
+
+// LAYER STORE TRAIT
+// ================================================================================================
+
+/// Defines an interface for storing FRI layers built by a [FriProver](crate::FriProver).
+///
+/// During the commit phase, the prover hands off the evaluations of each layer together with the
+/// Merkle tree built from them to the store. During the query phase, the prover retrieves from the
+/// store only the data needed to answer the queries: the evaluations committed to in the queried
+/// leaves, and the Merkle authentication paths for these leaves. Thus, an implementation of this
+/// trait can keep the layers outside of memory (e.g., on disk or in a remote object store) to
+/// enable FRI instances whose layers do not fit into memory.
+///
+/// The evaluations of a layer are stored in transposed form: every `folding_factor` consecutive
+/// evaluations are the ones committed to in a single leaf of the layer's Merkle tree.
+///
+/// The methods of this trait are infallible; implementations which can fail to store or retrieve
+/// data are expected to panic in such cases, as the prover cannot recover from them.
+pub trait LayerStore<E: FieldElement, H: Hasher> {
+    /// Stores the evaluations and the Merkle tree of the next FRI layer.
+    fn store_layer(&mut self, evaluations: Vec<E>, tree: MerkleTree<H>);
+
+    /// Returns the number of layers in this store.
+    fn num_layers(&self) -> usize;
+
+    /// Returns the evaluations of the specified layer committed to in the leaves at the specified
+    /// positions.
+    ///
+    /// For each position, `folding_factor` evaluations are returned, and the evaluations for all
+    /// positions are concatenated in the order of the positions.
+    fn get_layer_values(&self, layer: usize, positions: &[usize], folding_factor: usize) -> Vec<E>;
+
+    /// Returns Merkle authentication paths against the root of the specified layer's tree for the
+    /// leaves at the specified positions.
+    fn get_layer_proof(&self, layer: usize, positions: &[usize]) -> BatchMerkleProof<H>;
+
+    /// Removes all layers from this store.
+    fn clear(&mut self);
+}
+
+// MEMORY LAYER STORE
+// ================================================================================================
+
+/// Provides a default implementation of the [LayerStore] trait which keeps all layers in memory.
+pub struct MemoryLayerStore<E: FieldElement, H: Hasher> {
+    layers: Vec<(Vec<E>, MerkleTree<H>)>,
+}
+
+impl<E: FieldElement, H: Hasher> MemoryLayerStore<E, H> {
+    /// Returns a new store which does not contain any layers.
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+}
+
+impl<E: FieldElement, H: Hasher> Default for MemoryLayerStore<E, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: FieldElement, H: Hasher> LayerStore<E, H> for MemoryLayerStore<E, H> {
+    fn store_layer(&mut self, evaluations: Vec<E>, tree: MerkleTree<H>) {
+        self.layers.push((evaluations, tree));
+    }
+
+    fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    fn get_layer_values(&self, layer: usize, positions: &[usize], folding_factor: usize) -> Vec<E> {
+        let evaluations = &self.layers[layer].0;
+        let mut result = Vec::with_capacity(positions.len() * folding_factor);
+        for &position in positions {
+            let start = position * folding_factor;
+            result.extend_from_slice(&evaluations[start..start + folding_factor]);
+        }
+        result
+    }
+
+    fn get_layer_proof(&self, layer: usize, positions: &[usize]) -> BatchMerkleProof<H> {
+        self.layers[layer]
+            .1
+            .prove_batch(positions)
+            .expect("failed to generate a Merkle proof for FRI layer queries")
+    }
+
+    fn clear(&mut self) {
+        self.layers.clear();
+    }
+}
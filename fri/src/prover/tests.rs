@@ -4,14 +4,25 @@
 // LICENSE file in the root directory of this source tree.
 
 use alloc::vec::Vec;
+use core::cell::Cell;
 
-use crypto::{hashers::Blake3_256, DefaultRandomCoin, Hasher, RandomCoin};
+use crypto::{
+    hashers::Blake3_256, BatchMerkleProof, DefaultRandomCoin, Hasher, MerkleTree, RandomCoin,
+    RandomCoinError,
+};
 use math::{fft, fields::f128::BaseElement, FieldElement};
 use utils::{Deserializable, Serializable, SliceReader};
 
-use super::{DefaultProverChannel, FriProver};
+use super::{DefaultProverChannel, FriProver, LayerStore, MemoryLayerStore};
 use crate::{
-    verifier::{DefaultVerifierChannel, FriVerifier},
+    batching::{combine_evaluations, combine_queried_evaluations, BatchingCoefficients},
+    estimate::FriEstimate,
+    folding::fold_positions,
+    positions::{
+        draw_query_positions, draw_query_positions_with_exclusions, fold_positions_for_layers,
+        MAX_DRAW_ROUNDS,
+    },
+    verifier::{BorrowedVerifierChannel, DefaultVerifierChannel, FriVerifier, VerifierChannel},
     FriOptions, FriProof, VerifierError,
 };
 
@@ -38,6 +49,510 @@ fn fri_folding_4() {
     fri_prove_verify(trace_length_e, lde_blowup_e, folding_factor_e, max_remainder_degree)
 }
 
+#[test]
+fn fri_remainder_max_degree() {
+    let trace_length = 1 << 12;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+
+    // the remainder degree does not need to be one less than a power of two
+    let options = FriOptions::new(lde_blowup, 4, 100);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+
+    // the remainder is sent in coefficient form
+    assert_eq!(64, proof.num_remainder_elements::<BaseElement>());
+
+    let result = verify_proof(
+        proof,
+        commitments,
+        &evaluations,
+        trace_length - 1,
+        domain_size,
+        &positions,
+        &options,
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+}
+
+#[test]
+fn fri_folding_schedule() {
+    let trace_length = 1 << 12;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+    let uniform_options = FriOptions::new(lde_blowup, 4, 7);
+
+    // the optimal schedule reduces the degree of the polynomial to the remainder degree
+    let schedule = uniform_options.optimal_folding_schedule(domain_size, 16, 32);
+    assert_eq!(trace_length / 8, schedule.iter().product::<usize>());
+
+    for schedule in [vec![8, 4], vec![16, 2, 4], vec![32, 4], schedule] {
+        let options = uniform_options.clone().with_folding_schedule(&schedule);
+        let mut channel = build_prover_channel(trace_length, &options);
+        let evaluations = build_evaluations(trace_length, lde_blowup);
+
+        let mut prover = FriProver::new(options.clone());
+        prover.build_layers(&mut channel, evaluations.clone());
+        let positions = channel.draw_query_positions(0);
+        let proof = prover.build_proof(&positions);
+        let commitments = channel.layer_commitments().to_vec();
+
+        let result = verify_proof(
+            proof.clone(),
+            commitments.clone(),
+            &evaluations,
+            trace_length - 1,
+            domain_size,
+            &positions,
+            &options,
+        );
+        assert!(result.is_ok(), "{:}", result.err().unwrap());
+
+        // the schedule is enforced by the verifier
+        let result = verify_proof(
+            proof,
+            commitments,
+            &evaluations,
+            trace_length - 1,
+            domain_size,
+            &positions,
+            &uniform_options,
+        );
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn fri_batched_polynomials() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let domain_offset = options.domain_offset::<BaseElement>();
+
+    // evaluate polynomials of different degrees over the shifted evaluation domain
+    let twiddles = fft::get_twiddles::<BaseElement>(trace_length);
+    let degrees = [trace_length - 1, trace_length / 2 - 1, trace_length / 8 - 1];
+    let evaluations = degrees
+        .iter()
+        .map(|&degree| {
+            let mut p = (0..=degree as u128).map(BaseElement::new).collect::<Vec<_>>();
+            p.resize(trace_length, BaseElement::ZERO);
+            fft::evaluate_poly_with_offset(&p, &twiddles, domain_offset, lde_blowup)
+        })
+        .collect::<Vec<_>>();
+
+    let verify = |declared_degrees: &[usize]| {
+        // in practice, the coefficients are drawn after the prover commits to the polynomials
+        let mut coin = DefaultRandomCoin::<Blake3>::new(&[BaseElement::ONE]);
+        let coefficients = BatchingCoefficients::<BaseElement>::draw(&mut coin, 3).unwrap();
+        let max_degree = trace_length - 1;
+
+        // run a single FRI instance against the combined polynomial
+        let combined = combine_evaluations(
+            &evaluations,
+            declared_degrees,
+            max_degree,
+            domain_offset,
+            &coefficients,
+        );
+        let mut channel = build_prover_channel(trace_length, &options);
+        let mut prover = FriProver::new(options.clone());
+        prover.build_layers(&mut channel, combined);
+        let positions = channel.draw_query_positions(0);
+        let proof = prover.build_proof(&positions);
+
+        // the verifier combines the queried evaluations of the polynomials
+        let queried_evaluations = evaluations
+            .iter()
+            .map(|p| positions.iter().map(|&position| p[position]).collect())
+            .collect::<Vec<_>>();
+        let queried_combined = combine_queried_evaluations(
+            &queried_evaluations,
+            &positions,
+            domain_size,
+            declared_degrees,
+            max_degree,
+            domain_offset,
+            &coefficients,
+        );
+
+        let mut verifier_channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+            proof,
+            channel.layer_commitments().to_vec(),
+            domain_size,
+            options.folding_factor(),
+        )
+        .unwrap();
+        let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+        let verifier =
+            FriVerifier::new(&mut verifier_channel, &mut coin, options.clone(), max_degree)?;
+        verifier.verify(&mut verifier_channel, &queried_combined, &positions)
+    };
+
+    assert!(verify(&degrees).is_ok());
+
+    // the degree-correction terms expose a polynomial whose degree exceeds the declared degree
+    let understated_degrees = [degrees[0], degrees[2], degrees[2]];
+    assert!(verify(&understated_degrees).is_err());
+}
+
+#[test]
+fn fri_deferred_remainder_check() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let prover_alphas = prover.layer_alphas().to_vec();
+    let prover_remainder = prover.remainder_poly().to_vec();
+    assert_eq!(prover.num_layers(), prover_alphas.len());
+
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+
+    let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+        proof,
+        channel.layer_commitments().to_vec(),
+        trace_length * lde_blowup,
+        options.folding_factor(),
+    )
+    .unwrap();
+    let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+    let verifier =
+        FriVerifier::new(&mut channel, &mut coin, options.clone(), trace_length - 1).unwrap();
+    let queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+    let mut final_layer = verifier
+        .verify_deferred(&mut channel, &queried_evaluations, &positions)
+        .unwrap();
+
+    // the prover and the verifier agree on the folding challenges and on the remainder
+    assert_eq!(prover_alphas, verifier.layer_alphas()[..prover_alphas.len()]);
+    assert_eq!(prover_remainder, final_layer.remainder_poly);
+    assert_eq!(final_layer.positions.len(), final_layer.evaluations.len());
+
+    // the deferred check succeeds for valid evaluations, and fails otherwise
+    assert!(final_layer.verify().is_ok());
+    final_layer.evaluations[0] += BaseElement::ONE;
+    assert_eq!(Err(VerifierError::InvalidRemainderFolding), final_layer.verify());
+}
+
+#[test]
+fn fri_borrowed_verifier_channel() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+    let folding_factors = options.folding_factors(domain_size);
+    let queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+
+    // the proof is not consumed by the channel, and thus, it can be verified more than once
+    for _ in 0..2 {
+        let mut channel = BorrowedVerifierChannel::<BaseElement, Blake3>::new(
+            &proof,
+            commitments.clone(),
+            domain_size,
+            &folding_factors,
+        )
+        .unwrap();
+        let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+        let verifier =
+            FriVerifier::new(&mut channel, &mut coin, options.clone(), trace_length - 1).unwrap();
+        assert!(verifier.verify(&mut channel, &queried_evaluations, &positions).is_ok());
+
+        // reading past the last layer results in an error rather than a panic
+        let result = VerifierChannel::<BaseElement>::try_take_next_fri_layer_proof(&mut channel);
+        assert!(matches!(result, Err(VerifierError::DeserializationError(_))));
+    }
+
+    // a proof with more layers than folding factors is rejected
+    let result = BorrowedVerifierChannel::<BaseElement, Blake3>::new(
+        &proof,
+        commitments,
+        domain_size,
+        &folding_factors[..proof.num_layers() - 1],
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn fri_estimate() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+    let num_queries = 32;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations);
+    let positions = channel.draw_query_positions(0);
+    assert_eq!(num_queries, positions.len());
+    let proof = prover.build_proof(&positions);
+    let proof_size = proof.size() + channel.layer_commitments().len() * 32;
+
+    // the actual size of the proof is bounded by the maximum size
+    let estimate = FriEstimate::new(&options, domain_size, num_queries, 128, 16, 32);
+    assert!(proof_size <= estimate.max_proof_size);
+    assert!(estimate.proof_size <= estimate.max_proof_size);
+
+    // conjectured security is 3 bits per query minus one bit, and proven security is lower
+    assert_eq!(95, estimate.conjectured_security);
+    assert!(estimate.proven_security < estimate.conjectured_security);
+
+    // more queries improve security at the cost of a larger proof
+    let estimate2 = FriEstimate::new(&options, domain_size, 2 * num_queries, 128, 16, 32);
+    assert!(estimate2.proven_security > estimate.proven_security);
+    assert!(estimate2.proof_size > estimate.proof_size);
+
+    // security is bounded by the size of the field
+    let estimate3 = FriEstimate::new(&options, domain_size, 2 * num_queries, 64, 8, 32);
+    assert_eq!(64 - domain_size.ilog2() - 1, estimate3.conjectured_security);
+}
+
+#[test]
+fn fri_custom_layer_store() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    let mut channel = build_prover_channel(trace_length, &options);
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions(0);
+    let expected_proof = prover.build_proof(&positions);
+
+    // the proof does not depend on how the layers are stored
+    let mut channel = build_prover_channel(trace_length, &options);
+    let mut prover = FriProver::with_store(options.clone(), CountingLayerStore::default());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let num_layers = prover.num_layers();
+    assert_eq!(num_layers, prover.store.inner.num_layers());
+    let proof = prover.build_proof(&positions);
+    assert_eq!(expected_proof, proof);
+
+    // only the values at the queried positions are retrieved from the store, and the store is
+    // cleared after the proof is built
+    assert_eq!(num_layers, prover.store.num_queried_layers.get());
+    assert!(prover.store.num_queried_values.get() <= num_layers * positions.len() * 4);
+    assert_eq!(0, prover.num_layers());
+
+    let commitments = channel.layer_commitments().to_vec();
+    let result = verify_proof(
+        proof,
+        commitments,
+        &evaluations,
+        trace_length - 1,
+        domain_size,
+        &positions,
+        &options,
+    );
+    assert!(result.is_ok(), "{result:?}");
+}
+
+#[test]
+fn fri_prover_is_sync() {
+    fn assert_sync<T: Sync>() {}
+    assert_sync::<
+        FriProver<
+            BaseElement,
+            BaseElement,
+            DefaultProverChannel<BaseElement, Blake3, DefaultRandomCoin<Blake3>>,
+            Blake3,
+        >,
+    >();
+}
+
+#[test]
+fn draw_query_positions_with_excluded_positions() {
+    let new_coin = || DefaultRandomCoin::<Blake3>::new(&[BaseElement::ONE]);
+    let (num_queries, domain_size) = (16, 64);
+
+    // when no positions are excluded, the positions are the same as without exclusions
+    let expected = draw_query_positions(&mut new_coin(), num_queries, domain_size, 0).unwrap();
+    let positions =
+        draw_query_positions_with_exclusions(&mut new_coin(), num_queries, domain_size, 0, |_| {
+            false
+        })
+        .unwrap();
+    assert_eq!(expected, positions);
+
+    // excluded positions are never drawn
+    let is_excluded = |position: usize| position % 2 == 0;
+    let positions = draw_query_positions_with_exclusions(
+        &mut new_coin(),
+        num_queries,
+        domain_size,
+        0,
+        is_excluded,
+    )
+    .unwrap();
+    assert!(!positions.is_empty());
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    assert!(positions.iter().all(|&position| !is_excluded(position)));
+
+    // drawing fails when all positions are excluded
+    assert_eq!(
+        Err(RandomCoinError::FailedToDrawIntegers(num_queries, 0, MAX_DRAW_ROUNDS)),
+        draw_query_positions_with_exclusions(&mut new_coin(), num_queries, domain_size, 0, |_| {
+            true
+        })
+    );
+}
+
+#[test]
+fn fri_query_positions() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+    let options = FriOptions::new(lde_blowup, 4, 7).with_folding_schedule(&[8]);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, build_evaluations(trace_length, lde_blowup));
+
+    // positions are sorted and de-duplicated
+    let positions = channel.draw_query_positions(0);
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+
+    // positions at each layer are derived by folding positions of the previous layer
+    let folding_factors = options.folding_factors(domain_size);
+    let layer_positions = fold_positions_for_layers(&positions, domain_size, &folding_factors);
+    assert_eq!(prover.num_layers(), layer_positions.len());
+    let mut expected_positions = positions.clone();
+    let mut layer_domain_size = domain_size;
+    for (&folding_factor, positions) in folding_factors.iter().zip(layer_positions.iter()) {
+        expected_positions = fold_positions(&expected_positions, layer_domain_size, folding_factor);
+        layer_domain_size /= folding_factor;
+        assert_eq!(&expected_positions, positions);
+        assert!(positions.iter().all(|&p| p < layer_domain_size));
+    }
+
+    // the proof opens each layer at exactly these positions
+    let proof = prover.build_proof(&positions);
+    let (layer_queries, _) = proof
+        .parse_layers_with_folding_factors::<Blake3, BaseElement>(domain_size, &folding_factors)
+        .unwrap();
+    for ((queries, positions), &folding_factor) in
+        layer_queries.iter().zip(layer_positions.iter()).zip(folding_factors.iter())
+    {
+        assert_eq!(positions.len() * folding_factor, queries.len());
+    }
+}
+
+#[cfg(feature = "unsound-testing")]
+#[test]
+fn fri_sampled_queries() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let mut positions = channel.draw_query_positions(0);
+    positions.sort_unstable();
+    positions.dedup();
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+
+    let verify = |sampled_evaluations: &[BaseElement], sampled_positions: &[usize]| {
+        let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+            proof.clone(),
+            commitments.clone(),
+            trace_length * lde_blowup,
+            options.folding_factor(),
+        )
+        .unwrap();
+        let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+        let verifier =
+            FriVerifier::new(&mut channel, &mut coin, options.clone(), trace_length - 1)?;
+        verifier.verify_sampled_queries(
+            &mut channel,
+            sampled_evaluations,
+            &positions,
+            sampled_positions,
+        )
+    };
+
+    // checking every fourth query succeeds when the sampled evaluations are valid
+    let sampled_positions = positions.iter().step_by(4).copied().collect::<Vec<_>>();
+    let mut sampled_evaluations =
+        sampled_positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+    assert!(verify(&sampled_evaluations, &sampled_positions).is_ok());
+
+    // an invalid sampled evaluation is detected
+    sampled_evaluations[1] += BaseElement::ONE;
+    assert_eq!(
+        Err(VerifierError::InvalidLayerFolding(0)),
+        verify(&sampled_evaluations, &sampled_positions)
+    );
+}
+
+#[test]
+fn fri_constant_time_verification() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let mut positions = channel.draw_query_positions(0);
+    positions.sort_unstable();
+    positions.dedup();
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+
+    let verify = |queried_evaluations: &[BaseElement]| {
+        let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+            proof.clone(),
+            commitments.clone(),
+            trace_length * lde_blowup,
+            options.folding_factor(),
+        )
+        .unwrap();
+        let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+        let verifier =
+            FriVerifier::new(&mut channel, &mut coin, options.clone(), trace_length - 1)?;
+        verifier.verify_constant_time(&mut channel, queried_evaluations, &positions)
+    };
+
+    let mut queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+    assert_eq!(Ok(true), verify(&queried_evaluations));
+
+    // an invalid evaluation is reported without an error
+    queried_evaluations[1] += BaseElement::ONE;
+    assert_eq!(Ok(false), verify(&queried_evaluations));
+
+    // malformed inputs are still reported as errors
+    assert_eq!(
+        Err(VerifierError::NumPositionEvaluationMismatch(positions.len(), 1)),
+        verify(&queried_evaluations[..1])
+    );
+}
+
 // TEST UTILS
 // ================================================================================================
 
@@ -76,19 +591,56 @@ pub fn verify_proof(
     let proof = FriProof::read_from(&mut reader).unwrap();
 
     // verify the proof
-    let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+    let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::with_folding_factors(
         proof,
         commitments,
         domain_size,
-        options.folding_factor(),
+        &options.folding_factors(domain_size),
     )
-    .unwrap();
+    .map_err(VerifierError::DeserializationError)?;
     let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
     let verifier = FriVerifier::new(&mut channel, &mut coin, options.clone(), max_degree)?;
     let queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
     verifier.verify(&mut channel, &queried_evaluations, positions)
 }
 
+#[derive(Default)]
+struct CountingLayerStore {
+    inner: MemoryLayerStore<BaseElement, Blake3>,
+    num_queried_layers: Cell<usize>,
+    num_queried_values: Cell<usize>,
+}
+
+impl LayerStore<BaseElement, Blake3> for CountingLayerStore {
+    fn store_layer(&mut self, evaluations: Vec<BaseElement>, tree: MerkleTree<Blake3>) {
+        self.inner.store_layer(evaluations, tree)
+    }
+
+    fn num_layers(&self) -> usize {
+        self.inner.num_layers()
+    }
+
+    fn get_layer_values(
+        &self,
+        layer: usize,
+        positions: &[usize],
+        folding_factor: usize,
+    ) -> Vec<BaseElement> {
+        let values = self.inner.get_layer_values(layer, positions, folding_factor);
+        self.num_queried_layers.set(self.num_queried_layers.get() + 1);
+        self.num_queried_values.set(self.num_queried_values.get() + values.len());
+        values
+    }
+
+    fn get_layer_proof(&self, layer: usize, positions: &[usize]) -> BatchMerkleProof<Blake3> {
+        self.inner.get_layer_proof(layer, positions)
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear()
+    }
+}
+
 fn fri_prove_verify(
     trace_length_e: usize,
     lde_blowup_e: usize,
@@ -7,9 +7,7 @@ use alloc::vec::Vec;
 
 use crypto::ElementHasher;
 use math::FieldElement;
-#[cfg(feature = "concurrent")]
-use utils::iterators::*;
-use utils::{iter_mut, uninit_vector};
+use utils::UninitBuffer;
 
 /// Maps positions in the evaluation domain to indexes of commitment Merkle tree.
 pub fn map_positions_to_indexes(
@@ -44,9 +42,8 @@ where
     E: FieldElement,
     H: ElementHasher<BaseField = E::BaseField>,
 {
-    let mut result: Vec<H::Digest> = unsafe { uninit_vector(values.len()) };
-    iter_mut!(result, 1024).zip(values).for_each(|(r, v)| {
-        *r = H::hash_elements(v);
-    });
-    result
+    UninitBuffer::new(values.len()).fill_batches(1024, |batch, batch_offset| {
+        let values = &values[batch_offset..batch_offset + batch.len()];
+        batch.extend(values.iter().map(|v| H::hash_elements(v)));
+    })
 }
@@ -5,9 +5,9 @@
 
 use alloc::vec::Vec;
 
-use crypto::{BatchMerkleProof, ElementHasher, Hasher, MerkleTree};
+use crypto::{BatchMerkleProof, Digest, ElementHasher, Hasher, MerkleTree};
 use math::FieldElement;
-use utils::{group_slice_elements, DeserializationError};
+use utils::{constant_time_eq, group_slice_elements, DeserializationError};
 
 use crate::{FriProof, VerifierError};
 
@@ -71,6 +71,33 @@ pub trait VerifierChannel<E: FieldElement> {
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Reads and removes from the channel Merkle authentication paths for queried evaluations for
+    /// the next FRI layer.
+    ///
+    /// Works just like [take_next_fri_layer_proof()](VerifierChannel::take_next_fri_layer_proof),
+    /// but allows channels which parse the proof lazily to report parsing errors. The default
+    /// implementation never fails.
+    ///
+    /// # Errors
+    /// Returns an error if the authentication paths could not be read from the channel.
+    fn try_take_next_fri_layer_proof(
+        &mut self,
+    ) -> Result<BatchMerkleProof<Self::Hasher>, VerifierError> {
+        Ok(self.take_next_fri_layer_proof())
+    }
+
+    /// Reads and removes the remainder polynomial from the channel.
+    ///
+    /// Works just like [take_fri_remainder()](VerifierChannel::take_fri_remainder), but allows
+    /// channels which parse the proof lazily to report parsing errors. The default implementation
+    /// never fails.
+    ///
+    /// # Errors
+    /// Returns an error if the remainder could not be read from the channel.
+    fn try_take_fri_remainder(&mut self) -> Result<Vec<E>, VerifierError> {
+        Ok(self.take_fri_remainder())
+    }
+
     /// Returns FRI query values at the specified positions from the current FRI layer and advances
     /// layer pointer by one.
     ///
@@ -83,7 +110,7 @@ pub trait VerifierChannel<E: FieldElement> {
         positions: &[usize],
         commitment: &<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest,
     ) -> Result<Vec<[E; N]>, VerifierError> {
-        let layer_proof = self.take_next_fri_layer_proof();
+        let layer_proof = self.try_take_next_fri_layer_proof()?;
         MerkleTree::<Self::Hasher>::verify_batch(commitment, positions, &layer_proof)
             .map_err(|_| VerifierError::LayerCommitmentMismatch)?;
 
@@ -93,9 +120,37 @@ pub trait VerifierChannel<E: FieldElement> {
         Ok(group_slice_elements(&layer_queries).to_vec())
     }
 
+    /// Returns FRI query values at the specified positions from the current FRI layer and advances
+    /// layer pointer by one.
+    ///
+    /// Works just like [read_layer_queries()](VerifierChannel::read_layer_queries), but instead of
+    /// returning an error when the query values do not match the layer commitment, returns a flag
+    /// indicating whether they match; the roots are compared in constant time.
+    ///
+    /// # Errors
+    /// Returns an error if the number of query values read from the channel is not equal to the
+    /// number of positions, as this depends only on the structure of the proof.
+    fn read_layer_queries_constant_time<const N: usize>(
+        &mut self,
+        positions: &[usize],
+        commitment: &<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest,
+    ) -> Result<(Vec<[E; N]>, bool), VerifierError> {
+        let layer_proof = self.try_take_next_fri_layer_proof()?;
+        let is_valid = layer_proof
+            .get_root(positions)
+            .is_ok_and(|root| constant_time_eq(&root.as_bytes(), &commitment.as_bytes()));
+
+        let layer_queries = self.take_next_fri_layer_queries();
+        let layer_queries = group_slice_elements::<E, N>(&layer_queries).to_vec();
+        if layer_queries.len() != positions.len() {
+            return Err(VerifierError::LayerCommitmentMismatch);
+        }
+        Ok((layer_queries, is_valid))
+    }
+
     /// Returns FRI remainder polynomial read from this channel.
     fn read_remainder(&mut self) -> Result<Vec<E>, VerifierError> {
-        let remainder = self.take_fri_remainder();
+        let remainder = self.try_take_fri_remainder()?;
 
         Ok(remainder)
     }
@@ -132,12 +187,28 @@ where
         layer_commitments: Vec<H::Digest>,
         domain_size: usize,
         folding_factor: usize,
+    ) -> Result<Self, DeserializationError> {
+        let folding_factors = vec![folding_factor; proof.num_layers()];
+        Self::with_folding_factors(proof, layer_commitments, domain_size, &folding_factors)
+    }
+
+    /// Builds a new verifier channel from the specified [FriProof] for which the i-th layer was
+    /// folded by the i-th of the specified `folding_factors` (see
+    /// [FriOptions::folding_factors()](crate::FriOptions::folding_factors)).
+    ///
+    /// # Errors
+    /// Returns an error if the specified `proof` could not be parsed correctly.
+    pub fn with_folding_factors(
+        proof: FriProof,
+        layer_commitments: Vec<H::Digest>,
+        domain_size: usize,
+        folding_factors: &[usize],
     ) -> Result<Self, DeserializationError> {
         let num_partitions = proof.num_partitions();
 
         let remainder = proof.parse_remainder()?;
         let (layer_queries, layer_proofs) =
-            proof.parse_layers::<H, E>(domain_size, folding_factor)?;
+            proof.parse_layers_with_folding_factors::<H, E>(domain_size, folding_factors)?;
 
         Ok(DefaultVerifierChannel {
             layer_commitments,
@@ -176,3 +247,150 @@ where
         self.remainder.clone()
     }
 }
+
+// BORROWED VERIFIER CHANNEL IMPLEMENTATION
+// ================================================================================================
+
+/// Provides an implementation of the [VerifierChannel] trait which reads data directly from a
+/// borrowed [FriProof].
+///
+/// Unlike [DefaultVerifierChannel], this channel does not take ownership of the proof and does
+/// not parse the entire proof upfront. Instead, query values and Merkle authentication paths of
+/// each layer are parsed from the bytes of the proof only when the layer is read from the channel,
+/// and the remainder is parsed only when it is read. Thus, at any point, only the data of the
+/// layer currently being verified is held in memory, and the proof can be reused after
+/// verification.
+///
+/// Parsing errors are reported by the fallible methods of the [VerifierChannel] trait (e.g.,
+/// [read_layer_queries()](VerifierChannel::read_layer_queries)) which are used by the
+/// [FriVerifier](crate::FriVerifier).
+pub struct BorrowedVerifierChannel<'a, E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    proof: &'a FriProof,
+    layer_commitments: Vec<H::Digest>,
+    folding_factors: Vec<usize>,
+    domain_size: usize,
+    num_read_layers: usize,
+    layer_queries: Option<Vec<E>>,
+}
+
+impl<'a, E, H> BorrowedVerifierChannel<'a, E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    /// Builds a new verifier channel over the specified [FriProof] for which the i-th layer was
+    /// folded by the i-th of the specified `folding_factors` (see
+    /// [FriOptions::folding_factors()](crate::FriOptions::folding_factors)).
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is not a power of two.
+    /// * Any of the `folding_factors` is smaller than two or is not a power of two.
+    ///
+    /// # Errors
+    /// Returns an error if the specified `proof` contains more layers than there are
+    /// `folding_factors`.
+    pub fn new(
+        proof: &'a FriProof,
+        layer_commitments: Vec<H::Digest>,
+        domain_size: usize,
+        folding_factors: &[usize],
+    ) -> Result<Self, DeserializationError> {
+        assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+        for &folding_factor in folding_factors {
+            assert!(folding_factor.is_power_of_two(), "folding factor must be a power of two");
+            assert!(folding_factor > 1, "folding factor must be greater than 1");
+        }
+        if proof.num_layers() > folding_factors.len() {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected at most {} FRI layers, but the proof contains {}",
+                folding_factors.len(),
+                proof.num_layers()
+            )));
+        }
+
+        Ok(BorrowedVerifierChannel {
+            proof,
+            layer_commitments,
+            folding_factors: folding_factors.to_vec(),
+            domain_size,
+            num_read_layers: 0,
+            layer_queries: None,
+        })
+    }
+
+    /// Parses the next layer of the proof, returns its Merkle authentication paths, and retains
+    /// its query values until they are read from the channel.
+    fn parse_next_layer(&mut self) -> Result<BatchMerkleProof<H>, DeserializationError> {
+        let index = self.num_read_layers;
+        let layer = self.proof.layers().get(index).ok_or_else(|| {
+            DeserializationError::InvalidValue(format!("FRI layer {index} is not in the proof"))
+        })?;
+
+        let folding_factor = self.folding_factors[index];
+        let domain_size = self.domain_size / folding_factor;
+        let (layer_queries, layer_proof) =
+            layer.parse::<H, E>(domain_size, folding_factor).map_err(|err| {
+                DeserializationError::InvalidValue(format!(
+                    "failed to parse FRI layer {index}: {err}"
+                ))
+            })?;
+
+        self.domain_size = domain_size;
+        self.num_read_layers += 1;
+        self.layer_queries = Some(layer_queries);
+        Ok(layer_proof)
+    }
+}
+
+impl<E, H> VerifierChannel<E> for BorrowedVerifierChannel<'_, E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    type Hasher = H;
+
+    fn read_fri_num_partitions(&self) -> usize {
+        self.proof.num_partitions()
+    }
+
+    fn read_fri_layer_commitments(&mut self) -> Vec<H::Digest> {
+        self.layer_commitments.drain(..).collect()
+    }
+
+    /// # Panics
+    /// Panics if the authentication paths of the next layer could not be parsed; use
+    /// [try_take_next_fri_layer_proof()](VerifierChannel::try_take_next_fri_layer_proof) to
+    /// handle parsing errors.
+    fn take_next_fri_layer_proof(&mut self) -> BatchMerkleProof<H> {
+        self.parse_next_layer().expect("failed to parse FRI layer")
+    }
+
+    /// # Panics
+    /// Panics if the authentication paths of the layer have not been read from the channel yet.
+    fn take_next_fri_layer_queries(&mut self) -> Vec<E> {
+        self.layer_queries
+            .take()
+            .expect("FRI layer proof must be read before FRI layer queries")
+    }
+
+    /// # Panics
+    /// Panics if the remainder could not be parsed; use
+    /// [try_take_fri_remainder()](VerifierChannel::try_take_fri_remainder) to handle parsing
+    /// errors.
+    fn take_fri_remainder(&mut self) -> Vec<E> {
+        self.proof.parse_remainder().expect("failed to parse FRI remainder")
+    }
+
+    fn try_take_next_fri_layer_proof(&mut self) -> Result<BatchMerkleProof<H>, VerifierError> {
+        self.parse_next_layer().map_err(VerifierError::DeserializationError)
+    }
+
+    fn try_take_fri_remainder(&mut self) -> Result<Vec<E>, VerifierError> {
+        self.proof.parse_remainder().map_err(VerifierError::DeserializationError)
+    }
+}
@@ -6,15 +6,18 @@
 //! Contains an implementation of FRI verifier and associated components.
 
 use alloc::vec::Vec;
-use core::{marker::PhantomData, mem};
+use core::marker::PhantomData;
 
 use crypto::{ElementHasher, RandomCoin};
 use math::{polynom, FieldElement, StarkField};
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
+use utils::{constant_time_eq, iter};
 
 use crate::{folding::fold_positions, utils::map_positions_to_indexes, FriOptions, VerifierError};
 
 mod channel;
-pub use channel::{DefaultVerifierChannel, VerifierChannel};
+pub use channel::{BorrowedVerifierChannel, DefaultVerifierChannel, VerifierChannel};
 
 // FRI VERIFIER
 // ================================================================================================
@@ -117,6 +120,7 @@ where
         let layer_commitments = channel.read_fri_layer_commitments();
         let mut layer_alphas = Vec::with_capacity(layer_commitments.len());
         let mut max_degree_plus_1 = max_poly_degree + 1;
+        let mut last_domain_size = domain_size;
         for (depth, commitment) in layer_commitments.iter().enumerate() {
             public_coin.reseed(*commitment);
             let alpha = public_coin.draw().map_err(VerifierError::RandomCoinError)?;
@@ -124,16 +128,22 @@ where
 
             // make sure the degree can be reduced by the folding factor at all layers
             // but the remainder layer
-            if depth != layer_commitments.len() - 1
-                && max_degree_plus_1 % options.folding_factor() != 0
-            {
+            let folding_factor = options.folding_factor_at(depth);
+            if depth != layer_commitments.len() - 1 && max_degree_plus_1 % folding_factor != 0 {
                 return Err(VerifierError::DegreeTruncation(
                     max_degree_plus_1 - 1,
-                    options.folding_factor(),
+                    folding_factor,
                     depth,
                 ));
             }
-            max_degree_plus_1 /= options.folding_factor();
+            max_degree_plus_1 /= folding_factor;
+            last_domain_size /= folding_factor;
+        }
+
+        // positions in all FRI layers are mapped to indexes of commitment trees assuming that
+        // every partition contains at least one element
+        if num_partitions > last_domain_size {
+            return Err(VerifierError::InvalidNumPartitions(num_partitions, last_domain_size));
         }
 
         Ok(FriVerifier {
@@ -178,6 +188,22 @@ where
         &self.options
     }
 
+    /// Returns the random values drawn from the public coin for folding each FRI layer.
+    ///
+    /// The values are returned in the order of the layers; the last value corresponds to the
+    /// commitment to the remainder polynomial.
+    pub fn layer_alphas(&self) -> &[E] {
+        &self.layer_alphas
+    }
+
+    /// Returns the FRI layer commitments read from the channel.
+    ///
+    /// The commitments are returned in the order of the layers; the last commitment is the
+    /// commitment to the remainder polynomial.
+    pub fn layer_commitments(&self) -> &[H::Digest] {
+        &self.layer_commitments
+    }
+
     // VERIFICATION PROCEDURE
     // --------------------------------------------------------------------------------------------
     /// Executes the query phase of the FRI protocol.
@@ -209,6 +235,59 @@ where
         evaluations: &[E],
         positions: &[usize],
     ) -> Result<(), VerifierError> {
+        self.verify_with_folded_evaluations(channel, evaluations, positions).map(|_| ())
+    }
+
+    /// Executes the query phase of the FRI protocol, and returns the values computed by folding
+    /// each FRI layer.
+    ///
+    /// Works just like [verify()](FriVerifier::verify), but on success, returns a vector with an
+    /// entry for each FRI layer. The entry for a layer contains the evaluations of the folded
+    /// polynomial at the folded query positions for this layer; these are the values which are
+    /// checked against the queries of the next layer (or against the remainder polynomial for the
+    /// last layer).
+    ///
+    /// # Errors
+    /// Returns the same errors as [verify()](FriVerifier::verify).
+    pub fn verify_with_folded_evaluations(
+        &self,
+        channel: &mut C,
+        evaluations: &[E],
+        positions: &[usize],
+    ) -> Result<Vec<Vec<E>>, VerifierError> {
+        if evaluations.len() != positions.len() {
+            return Err(VerifierError::NumPositionEvaluationMismatch(
+                positions.len(),
+                evaluations.len(),
+            ));
+        }
+
+        self.dispatch_verification(channel, evaluations, positions, positions, false)
+            .map(|(folded_evaluations, _)| folded_evaluations)
+    }
+
+    /// Executes the query phase of the FRI protocol without revealing which of the checks failed
+    /// via the time it takes to verify the proof.
+    ///
+    /// Works just like [verify()](FriVerifier::verify), but instead of returning an error as soon
+    /// as query values do not match a layer commitment or a folding is found to be invalid, all of
+    /// the checks are performed and their results are combined. Commitments and evaluations are
+    /// compared in constant time. Returns `Ok(true)` if all checks passed, and `Ok(false)`
+    /// otherwise.
+    ///
+    /// # Errors
+    /// Returns an error only if the inputs or the proof are malformed; i.e., if:
+    /// * The length of `evaluations` is not equal to the length of `positions`.
+    /// * An unsupported folding factor was specified by the `options` for this verifier.
+    /// * Merkle authentication paths for any of the FRI layers could not be parsed.
+    /// * The degree of the remainder at the last FRI layer is greater than the degree implied by
+    ///   `max_poly_degree` reduced by the folding factor at each FRI layer.
+    pub fn verify_constant_time(
+        &self,
+        channel: &mut C,
+        evaluations: &[E],
+        positions: &[usize],
+    ) -> Result<bool, VerifierError> {
         if evaluations.len() != positions.len() {
             return Err(VerifierError::NumPositionEvaluationMismatch(
                 positions.len(),
@@ -216,114 +295,362 @@ where
             ));
         }
 
-        // static dispatch for folding factor parameter
-        let folding_factor = self.options.folding_factor();
-        match folding_factor {
-            2 => self.verify_generic::<2>(channel, evaluations, positions),
-            4 => self.verify_generic::<4>(channel, evaluations, positions),
-            8 => self.verify_generic::<8>(channel, evaluations, positions),
-            16 => self.verify_generic::<16>(channel, evaluations, positions),
-            _ => Err(VerifierError::UnsupportedFoldingFactor(folding_factor)),
+        self.dispatch_verification(channel, evaluations, positions, positions, true)
+            .map(|(_, is_valid)| is_valid)
+    }
+
+    /// Executes the query phase of the FRI protocol for all FRI layers except for the remainder.
+    ///
+    /// Works just like [verify()](FriVerifier::verify), but instead of checking the remainder
+    /// polynomial against the evaluations at the last FRI layer, returns the evaluations at the
+    /// last layer together with the remainder polynomial. This allows a recursive verifier to
+    /// defer the final low-degree check; the check can be performed via
+    /// [FriFinalLayer::verify()]. Random values used for folding each FRI layer are available via
+    /// [layer_alphas()](FriVerifier::layer_alphas).
+    ///
+    /// **The FRI proof is not fully verified until the final check is performed.**
+    ///
+    /// # Errors
+    /// Returns an error if the length of `evaluations` is not equal to the length of `positions`,
+    /// or if verification of any of the FRI layers (except for the remainder) fails.
+    pub fn verify_deferred(
+        &self,
+        channel: &mut C,
+        evaluations: &[E],
+        positions: &[usize],
+    ) -> Result<FriFinalLayer<E>, VerifierError> {
+        if evaluations.len() != positions.len() {
+            return Err(VerifierError::NumPositionEvaluationMismatch(
+                positions.len(),
+                evaluations.len(),
+            ));
         }
+
+        let (_, state) = self.verify_layers(channel, evaluations, positions, positions, false)?;
+        self.read_final_layer(channel, state)
     }
 
-    /// This is the actual implementation of the verification procedure described above, but it
-    /// also takes folding factor as a generic parameter N.
-    fn verify_generic<const N: usize>(
+    /// Executes the query phase of the FRI protocol for a subset of the queried positions.
+    ///
+    /// Works just like [verify()](FriVerifier::verify), but the `evaluations` are expected to be
+    /// evaluations of the polynomial at the `sampled_positions` only. Decommitments to the layer
+    /// evaluations are still read from the `channel` and checked against the layer commitments
+    /// for all of the `positions`, but the layer folding and the remainder polynomial are checked
+    /// only for the sampled positions.
+    ///
+    /// **This is unsound**: a proof may pass this check even though a full verification of the
+    /// proof would fail. This method is intended only for speeding up tests.
+    ///
+    /// # Errors
+    /// Returns an error if the length of `evaluations` is not equal to the length of
+    /// `sampled_positions`, and the same errors as [verify()](FriVerifier::verify) otherwise.
+    ///
+    /// # Panics
+    /// Panics if any of the `sampled_positions` is not one of the `positions`.
+    #[cfg(feature = "unsound-testing")]
+    pub fn verify_sampled_queries(
         &self,
         channel: &mut C,
         evaluations: &[E],
         positions: &[usize],
+        sampled_positions: &[usize],
     ) -> Result<(), VerifierError> {
-        // pre-compute roots of unity used in computing x coordinates in the folded domain
-        let folding_roots = (0..N)
-            .map(|i| self.domain_generator.exp_vartime(((self.domain_size / N * i) as u64).into()))
-            .collect::<Vec<_>>();
+        if evaluations.len() != sampled_positions.len() {
+            return Err(VerifierError::NumPositionEvaluationMismatch(
+                sampled_positions.len(),
+                evaluations.len(),
+            ));
+        }
+        assert!(
+            sampled_positions.iter().all(|position| positions.contains(position)),
+            "sampled positions must be a subset of queried positions"
+        );
+
+        self.dispatch_verification(channel, evaluations, positions, sampled_positions, false)
+            .map(|_| ())
+    }
 
+    /// This is the actual implementation of the verification procedure described above.
+    ///
+    /// Decommitments are read for all `positions`, while the folding is checked only for the
+    /// `sampled_positions`; for a full verification, both are the same.
+    ///
+    /// When `constant_time` is true, failed checks do not cause an early return; instead, the
+    /// combined result of all checks is returned together with the folded evaluations.
+    fn dispatch_verification(
+        &self,
+        channel: &mut C,
+        evaluations: &[E],
+        positions: &[usize],
+        sampled_positions: &[usize],
+        constant_time: bool,
+    ) -> Result<(Vec<Vec<E>>, bool), VerifierError> {
         // 1 ----- verify the recursive components of the FRI proof -----------------------------------
-        let mut domain_generator = self.domain_generator;
-        let mut domain_size = self.domain_size;
-        let mut max_degree_plus_1 = self.max_poly_degree + 1;
-        let mut positions = positions.to_vec();
-        let mut evaluations = evaluations.to_vec();
-
-        for depth in 0..self.options.num_fri_layers(self.domain_size) {
-            // determine which evaluations were queried in the folded layer
-            let mut folded_positions =
-                fold_positions(&positions, domain_size, self.options.folding_factor());
-            // determine where these evaluations are in the commitment Merkle tree
-            let position_indexes = map_positions_to_indexes(
-                &folded_positions,
-                domain_size,
-                self.options.folding_factor(),
-                self.num_partitions,
+        let (folded_evaluations, state) =
+            self.verify_layers(channel, evaluations, positions, sampled_positions, constant_time)?;
+
+        // 2 ----- verify the remainder polynomial of the FRI proof -------------------------------
+
+        // read the remainder polynomial from the channel and make sure it agrees with the evaluations
+        // from the previous layer.
+        let mut is_valid = state.is_valid;
+        let final_layer = self.read_final_layer(channel, state)?;
+        if constant_time {
+            final_layer.check_remainder_degree()?;
+            is_valid &= constant_time_eq(
+                E::elements_as_bytes(&final_layer.evaluations),
+                E::elements_as_bytes(&final_layer.remainder_evaluations()),
             );
-            // read query values from the specified indexes in the Merkle tree
-            let layer_commitment = self.layer_commitments[depth];
-            // TODO: add layer depth to the potential error message
-            let layer_values = channel.read_layer_queries(&position_indexes, &layer_commitment)?;
-            let query_values =
-                get_query_values::<E, N>(&layer_values, &positions, &folded_positions, domain_size);
-            if evaluations != query_values {
-                return Err(VerifierError::InvalidLayerFolding(depth));
+        } else {
+            final_layer.verify()?;
+        }
+
+        Ok((folded_evaluations, is_valid))
+    }
+
+    /// Verifies all FRI layers except for the remainder, and returns the folded evaluations at
+    /// each layer together with the state of the query phase at the last layer.
+    fn verify_layers(
+        &self,
+        channel: &mut C,
+        evaluations: &[E],
+        positions: &[usize],
+        sampled_positions: &[usize],
+        constant_time: bool,
+    ) -> Result<(Vec<Vec<E>>, QueryState<E>), VerifierError> {
+        let mut state = QueryState {
+            domain_generator: self.domain_generator,
+            domain_size: self.domain_size,
+            max_degree_plus_1: self.max_poly_degree + 1,
+            positions: positions.to_vec(),
+            sampled_positions: sampled_positions.to_vec(),
+            evaluations: evaluations.to_vec(),
+            is_sampled: sampled_positions.len() != positions.len(),
+            is_valid: true,
+        };
+        let mut folded_evaluations = Vec::with_capacity(self.layer_commitments.len());
+
+        for (depth, folding_factor) in
+            self.options.folding_factors(self.domain_size).into_iter().enumerate()
+        {
+            // static dispatch for folding factor parameter
+            match folding_factor {
+                2 => self.verify_layer::<2>(channel, &mut state, depth, constant_time)?,
+                4 => self.verify_layer::<4>(channel, &mut state, depth, constant_time)?,
+                8 => self.verify_layer::<8>(channel, &mut state, depth, constant_time)?,
+                16 => self.verify_layer::<16>(channel, &mut state, depth, constant_time)?,
+                32 => self.verify_layer::<32>(channel, &mut state, depth, constant_time)?,
+                _ => return Err(VerifierError::UnsupportedFoldingFactor(folding_factor)),
             }
+            folded_evaluations.push(state.evaluations.clone());
+        }
 
-            // build a set of x coordinates for each row polynomial
-            #[rustfmt::skip]
-            let xs = folded_positions.iter().map(|&i| {
-                let xe = domain_generator.exp_vartime((i as u64).into()) * self.options.domain_offset();
-                folding_roots.iter()
-                    .map(|&r| E::from(xe * r))
-                    .collect::<Vec<_>>().try_into().unwrap()
-            })
-            .collect::<Vec<_>>();
+        Ok((folded_evaluations, state))
+    }
 
-            // interpolate x and y values into row polynomials
-            let row_polys = polynom::interpolate_batch(&xs, &layer_values);
+    /// Reads the remainder polynomial from the channel and combines it with the evaluations at
+    /// the last FRI layer described by the `state`.
+    fn read_final_layer(
+        &self,
+        channel: &mut C,
+        state: QueryState<E>,
+    ) -> Result<FriFinalLayer<E>, VerifierError> {
+        let remainder_poly = channel.read_remainder()?;
+        let max_remainder_size =
+            state.max_degree_plus_1.min(self.options.remainder_max_degree() + 1);
 
-            // calculate the pseudo-random value used for linear combination in layer folding
-            let alpha = self.layer_alphas[depth];
+        let offset: E::BaseField = self.options().domain_offset();
+        let domain_generator = state.domain_generator;
+        let points = iter!(state.sampled_positions)
+            .map(|&position| offset * domain_generator.exp_vartime((position as u64).into()))
+            .collect();
+
+        Ok(FriFinalLayer {
+            positions: state.sampled_positions,
+            points,
+            evaluations: state.evaluations,
+            remainder_poly,
+            max_remainder_degree: max_remainder_size - 1,
+        })
+    }
 
-            // check that when the polynomials are evaluated at alpha, the result is equal to
-            // the corresponding column value
-            evaluations = row_polys.iter().map(|p| polynom::eval(p, alpha)).collect();
+    /// Verifies a single FRI layer at the specified depth, and updates the `state` of the query
+    /// phase to describe the next layer. The folding factor of the layer is specified via the
+    /// generic parameter N.
+    fn verify_layer<const N: usize>(
+        &self,
+        channel: &mut C,
+        state: &mut QueryState<E>,
+        depth: usize,
+        constant_time: bool,
+    ) -> Result<(), VerifierError> {
+        let domain_size = state.domain_size;
+        let domain_generator = state.domain_generator;
 
-            // make sure next degree reduction does not result in degree truncation
-            if max_degree_plus_1 % N != 0 {
-                return Err(VerifierError::DegreeTruncation(max_degree_plus_1 - 1, N, depth));
-            }
+        // pre-compute roots of unity used in computing x coordinates in the folded domain
+        let folding_roots = (0..N)
+            .map(|i| domain_generator.exp_vartime(((domain_size / N * i) as u64).into()))
+            .collect::<Vec<_>>();
 
-            // update variables for the next iteration of the loop
-            domain_generator = domain_generator.exp_vartime((N as u32).into());
-            max_degree_plus_1 /= N;
-            domain_size /= N;
-            mem::swap(&mut positions, &mut folded_positions);
+        // determine which evaluations were queried in the folded layer
+        let folded_positions = fold_positions(&state.positions, domain_size, N);
+        // determine where these evaluations are in the commitment Merkle tree
+        let position_indexes =
+            map_positions_to_indexes(&folded_positions, domain_size, N, self.num_partitions);
+        // read query values from the specified indexes in the Merkle tree
+        let layer_commitment = self.layer_commitments[depth];
+        // TODO: add layer depth to the potential error message
+        let (mut layer_values, is_layer_valid) = if constant_time {
+            channel.read_layer_queries_constant_time(&position_indexes, &layer_commitment)?
+        } else {
+            (channel.read_layer_queries(&position_indexes, &layer_commitment)?, true)
+        };
+        state.is_valid &= is_layer_valid;
+
+        let query_values = get_query_values::<E, N>(
+            &layer_values,
+            &state.sampled_positions,
+            &folded_positions,
+            domain_size,
+        );
+        if constant_time {
+            state.is_valid &= constant_time_eq(
+                E::elements_as_bytes(&state.evaluations),
+                E::elements_as_bytes(&query_values),
+            );
+        } else if state.evaluations != query_values {
+            return Err(VerifierError::InvalidLayerFolding(depth));
         }
 
-        // 2 ----- verify the remainder polynomial of the FRI proof -------------------------------
+        // when only some of the queries are checked, keep only the rows which contain the
+        // sampled positions
+        let folded_sampled_positions = if state.is_sampled {
+            let folded_sampled_positions = fold_positions(&state.sampled_positions, domain_size, N);
+            layer_values = folded_sampled_positions
+                .iter()
+                .map(|p| {
+                    let idx = folded_positions.iter().position(|v| v == p).unwrap();
+                    layer_values[idx]
+                })
+                .collect();
+            folded_sampled_positions
+        } else {
+            folded_positions.clone()
+        };
+
+        // build a set of x coordinates for each row polynomial; when the crate is compiled with
+        // `concurrent` feature enabled, this and other per-query work is done in parallel
+        let domain_offset = self.options.domain_offset();
+        #[rustfmt::skip]
+        let xs = iter!(folded_sampled_positions).map(|&i| {
+            let xe = domain_generator.exp_vartime((i as u64).into()) * domain_offset;
+            folding_roots.iter()
+                .map(|&r| E::from(xe * r))
+                .collect::<Vec<_>>().try_into().unwrap()
+        })
+        .collect::<Vec<_>>();
 
-        // read the remainder polynomial from the channel and make sure it agrees with the evaluations
-        // from the previous layer.
-        let remainder_poly = channel.read_remainder()?;
-        if remainder_poly.len() > max_degree_plus_1 {
-            return Err(VerifierError::RemainderDegreeMismatch(max_degree_plus_1 - 1));
+        // interpolate x and y values into row polynomials
+        let row_polys = polynom::interpolate_batch(&xs, &layer_values);
+
+        // calculate the pseudo-random value used for linear combination in layer folding
+        let alpha = self.layer_alphas[depth];
+
+        // check that when the polynomials are evaluated at alpha, the result is equal to
+        // the corresponding column value
+        state.evaluations = iter!(row_polys).map(|p| polynom::eval(p, alpha)).collect();
+
+        // make sure next degree reduction does not result in degree truncation
+        if state.max_degree_plus_1 % N != 0 {
+            return Err(VerifierError::DegreeTruncation(state.max_degree_plus_1 - 1, N, depth));
         }
-        let offset: E::BaseField = self.options().domain_offset();
 
-        for (&position, evaluation) in positions.iter().zip(evaluations) {
-            let comp_eval = eval_horner::<E>(
-                &remainder_poly,
-                offset * domain_generator.exp_vartime((position as u64).into()),
-            );
-            if comp_eval != evaluation {
-                return Err(VerifierError::InvalidRemainderFolding);
-            }
+        // update the state for the next layer
+        state.domain_generator = domain_generator.exp_vartime((N as u32).into());
+        state.max_degree_plus_1 /= N;
+        state.domain_size /= N;
+        state.positions = folded_positions;
+        state.sampled_positions = folded_sampled_positions;
+
+        Ok(())
+    }
+}
+
+// FINAL LAYER
+// ================================================================================================
+
+/// Queried evaluations at the last FRI layer together with the remainder polynomial.
+///
+/// The final layer is returned by [FriVerifier::verify_deferred()], which verifies all FRI layers
+/// except for the remainder. This allows a recursive verifier to defer the final low-degree check
+/// (e.g., to aggregate the checks of many FRI instances); the check can be performed via
+/// [verify()](FriFinalLayer::verify).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FriFinalLayer<E: FieldElement> {
+    /// Query positions in the domain of the last FRI layer.
+    pub positions: Vec<usize>,
+    /// Domain points corresponding to the query positions.
+    pub points: Vec<E::BaseField>,
+    /// Evaluations at the query positions implied by folding of the previous FRI layers.
+    pub evaluations: Vec<E>,
+    /// Coefficients of the remainder polynomial sent by the prover.
+    pub remainder_poly: Vec<E>,
+    /// Maximum degree of the remainder polynomial accepted by the verifier.
+    pub max_remainder_degree: usize,
+}
+
+impl<E: FieldElement> FriFinalLayer<E> {
+    /// Returns evaluations of the remainder polynomial at the domain points of the queries.
+    ///
+    /// The remainder is received in coefficient form, and thus, it is evaluated directly at the
+    /// query points; the cost of this is linear in the number of queries times the number of
+    /// coefficients, and does not depend on the size of the remainder domain (i.e., the remainder
+    /// is never interpolated over the whole domain).
+    pub fn remainder_evaluations(&self) -> Vec<E> {
+        iter!(self.points).map(|&x| eval_horner::<E>(&self.remainder_poly, x)).collect()
+    }
+
+    /// Performs the final low-degree check of the FRI protocol.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The degree of the remainder polynomial is greater than `max_remainder_degree`.
+    /// * The evaluations of the remainder polynomial at the query points do not match the
+    ///   evaluations implied by folding of the previous FRI layers.
+    pub fn verify(&self) -> Result<(), VerifierError> {
+        self.check_remainder_degree()?;
+        if self.evaluations != self.remainder_evaluations() {
+            return Err(VerifierError::InvalidRemainderFolding);
         }
+        Ok(())
+    }
 
+    /// Makes sure the degree of the remainder polynomial does not exceed the maximum degree; the
+    /// remainder is sent in coefficient form, and thus, its degree is checked by the number of
+    /// coefficients.
+    fn check_remainder_degree(&self) -> Result<(), VerifierError> {
+        if self.remainder_poly.len() > self.max_remainder_degree + 1 {
+            return Err(VerifierError::RemainderDegreeMismatch(self.max_remainder_degree));
+        }
         Ok(())
     }
 }
 
+// QUERY STATE
+// ================================================================================================
+
+/// State of the query phase of the FRI protocol carried from one FRI layer to the next.
+struct QueryState<E: FieldElement> {
+    domain_generator: E::BaseField,
+    domain_size: usize,
+    max_degree_plus_1: usize,
+    positions: Vec<usize>,
+    sampled_positions: Vec<usize>,
+    evaluations: Vec<E>,
+    is_sampled: bool,
+    is_valid: bool,
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 fn get_query_values<E: FieldElement, const N: usize>(
@@ -5,7 +5,7 @@
 
 use alloc::vec::Vec;
 
-use utils::{iterators::*, rayon, uninit_vector};
+use utils::{iterators::*, rayon, UninitBuffer};
 
 use super::fft_inputs::FftInputs;
 use crate::field::{FieldElement, StarkField};
@@ -31,18 +31,16 @@ pub fn evaluate_poly_with_offset<B: StarkField, E: FieldElement<BaseField = B>>(
 ) -> Vec<E> {
     let domain_size = p.len() * blowup_factor;
     let g = B::get_root_of_unity(domain_size.ilog2());
-    let mut result = unsafe { uninit_vector(domain_size) };
-
-    result
-        .as_mut_slice()
-        .par_chunks_mut(p.len())
-        .enumerate()
-        .for_each(|(i, chunk)| {
-            let idx = super::permute_index(blowup_factor, i) as u64;
-            let offset = g.exp(idx.into()) * domain_offset;
-            clone_and_shift(p, chunk, offset);
-            split_radix_fft(chunk, twiddles);
-        });
+    let mut result = UninitBuffer::new(domain_size).par_fill_chunks(p.len(), |i, chunk| {
+        let idx = super::permute_index(blowup_factor, i) as u64;
+        let offset = g.exp(idx.into()) * domain_offset;
+        let mut factor = B::ONE;
+        for c in p.iter() {
+            chunk.push((*c).mul_base(factor));
+            factor *= offset;
+        }
+        split_radix_fft(chunk.as_mut_slice(), twiddles);
+    });
 
     permute(&mut result);
     result
@@ -216,21 +214,3 @@ fn transpose_square_2<T>(matrix: &mut [T], size: usize) {
         }
     }
 }
-
-// HELPER FUNCTIONS
-// ================================================================================================
-
-fn clone_and_shift<E: FieldElement>(source: &[E], destination: &mut [E], offset: E::BaseField) {
-    let batch_size = source.len() / rayon::current_num_threads().next_power_of_two();
-    source
-        .par_chunks(batch_size)
-        .zip(destination.par_chunks_mut(batch_size))
-        .enumerate()
-        .for_each(|(i, (source, destination))| {
-            let mut factor = offset.exp(((i * batch_size) as u64).into());
-            for (s, d) in source.iter().zip(destination.iter_mut()) {
-                *d = (*s).mul_base(factor);
-                factor *= offset;
-            }
-        });
-}
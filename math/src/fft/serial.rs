@@ -5,7 +5,7 @@
 
 use alloc::vec::Vec;
 
-use utils::uninit_vector;
+use utils::UninitBuffer;
 
 use super::fft_inputs::FftInputs;
 use crate::{field::StarkField, FieldElement};
@@ -38,17 +38,16 @@ where
 {
     let domain_size = p.len() * blowup_factor;
     let g = B::get_root_of_unity(domain_size.ilog2());
-    let mut result = unsafe { uninit_vector(domain_size) };
 
-    result.as_mut_slice().chunks_mut(p.len()).enumerate().for_each(|(i, chunk)| {
+    let mut result = UninitBuffer::new(domain_size).fill_chunks(p.len(), |i, chunk| {
         let idx = super::permute_index(blowup_factor, i) as u64;
         let offset = g.exp(idx.into()) * domain_offset;
         let mut factor = E::BaseField::ONE;
-        for (d, c) in chunk.iter_mut().zip(p.iter()) {
-            *d = (*c).mul_base(factor);
+        for c in p.iter() {
+            chunk.push((*c).mul_base(factor));
             factor *= offset;
         }
-        chunk.fft_in_place(twiddles);
+        chunk.as_mut_slice().fft_in_place(twiddles);
     });
 
     result.permute();
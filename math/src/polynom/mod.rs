@@ -703,7 +703,7 @@ where
 /// assert_eq!(expected_poly, poly);
 /// ```
 pub fn poly_from_roots<E: FieldElement>(xs: &[E]) -> Vec<E> {
-    let mut result = unsafe { utils::uninit_vector(xs.len() + 1) };
+    let mut result = vec![E::ZERO; xs.len() + 1];
     fill_zero_roots(xs, &mut result);
     result
 }
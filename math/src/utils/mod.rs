@@ -7,7 +7,7 @@ use alloc::vec::Vec;
 
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
-use utils::{batch_iter_mut, iter_mut, uninit_vector};
+use utils::{iter_mut, UninitBuffer, UninitChunk};
 
 use crate::{field::FieldElement, ExtensionOf};
 
@@ -39,12 +39,10 @@ pub fn get_power_series<E>(b: E, n: usize) -> Vec<E>
 where
     E: FieldElement,
 {
-    let mut result = unsafe { uninit_vector(n) };
-    batch_iter_mut!(&mut result, 1024, |batch: &mut [E], batch_offset: usize| {
+    UninitBuffer::new(n).fill_batches(1024, |batch, batch_offset| {
         let start = b.exp((batch_offset as u64).into());
         fill_power_series(batch, b, start);
-    });
-    result
+    })
 }
 
 /// Returns a vector containing successive powers of a given base offset by the specified value.
@@ -74,12 +72,10 @@ pub fn get_power_series_with_offset<E>(b: E, s: E, n: usize) -> Vec<E>
 where
     E: FieldElement,
 {
-    let mut result = unsafe { uninit_vector(n) };
-    batch_iter_mut!(&mut result, 1024, |batch: &mut [E], batch_offset: usize| {
+    UninitBuffer::new(n).fill_batches(1024, |batch, batch_offset| {
         let start = s * b.exp((batch_offset as u64).into());
         fill_power_series(batch, b, start);
-    });
-    result
+    })
 }
 
 /// Computes element-wise sum of the provided vectors, and stores the result in the first vector.
@@ -174,30 +170,29 @@ pub fn batch_inversion<E>(values: &[E]) -> Vec<E>
 where
     E: FieldElement,
 {
-    let mut result: Vec<E> = unsafe { uninit_vector(values.len()) };
-    batch_iter_mut!(&mut result, 1024, |batch: &mut [E], batch_offset: usize| {
+    UninitBuffer::new(values.len()).fill_batches(1024, |batch, batch_offset| {
         let start = batch_offset;
         let end = start + batch.len();
         serial_batch_inversion(&values[start..end], batch);
-    });
-    result
+    })
 }
 
 // HELPER FUNCTIONS
 // ------------------------------------------------------------------------------------------------
 
 #[inline(always)]
-fn fill_power_series<E: FieldElement>(result: &mut [E], base: E, start: E) {
-    result[0] = start;
-    for i in 1..result.len() {
-        result[i] = result[i - 1] * base;
+fn fill_power_series<E: FieldElement>(result: &mut UninitChunk<E>, base: E, start: E) {
+    let mut value = start;
+    while !result.is_full() {
+        result.push(value);
+        value *= base;
     }
 }
 
-fn serial_batch_inversion<E: FieldElement>(values: &[E], result: &mut [E]) {
+fn serial_batch_inversion<E: FieldElement>(values: &[E], result: &mut UninitChunk<E>) {
     let mut last = E::ONE;
-    for (result, &value) in result.iter_mut().zip(values.iter()) {
-        *result = last;
+    for &value in values.iter() {
+        result.push(last);
         if value != E::ZERO {
             last *= value;
         }
@@ -205,6 +200,8 @@ fn serial_batch_inversion<E: FieldElement>(values: &[E], result: &mut [E]) {
 
     last = last.inv();
 
+    let result = result.as_mut_slice();
+
     for i in (0..values.len()).rev() {
         if values[i] == E::ZERO {
             result[i] = E::ZERO;
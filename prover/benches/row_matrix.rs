@@ -6,10 +6,11 @@
 use std::time::Duration;
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use crypto::hashers::Blake3_256;
 use math::{fft, fields::f64::BaseElement, StarkField};
 use rand_utils::rand_vector;
 use winter_prover::{
-    matrix::{ColMatrix, RowMatrix},
+    matrix::{ColMatrix, MatrixStorage, RowMatrix},
     StarkDomain,
 };
 
@@ -63,6 +64,33 @@ fn evaluate_matrix(c: &mut Criterion) {
     group.finish();
 }
 
+fn evaluate_and_commit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matrix_evaluate_and_commit");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    for &num_poly in NUM_POLYS.iter() {
+        let columns: Vec<Vec<BaseElement>> = (0..num_poly).map(|_| rand_vector(SIZE)).collect();
+        let column_matrix = ColMatrix::new(columns);
+        for &blowup_factor in BLOWUP_FACTOR.iter() {
+            let params = BenchmarkParams { num_poly, blowup_factor };
+            let twiddles = fft::get_twiddles::<BaseElement>(SIZE);
+            let stark_domain =
+                StarkDomain::from_twiddles(twiddles, blowup_factor, BaseElement::GENERATOR);
+            group.bench_function(BenchmarkId::new(SIZE.to_string(), params), |bench| {
+                bench.iter_with_large_drop(|| {
+                    RowMatrix::evaluate_polys_over_and_commit::<Blake3_256<BaseElement>, 8>(
+                        &column_matrix,
+                        &stark_domain,
+                        &MatrixStorage::Heap,
+                    )
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
 /// Benchmark parameters.
 struct BenchmarkParams {
     num_poly: usize,
@@ -75,5 +103,5 @@ impl std::fmt::Display for BenchmarkParams {
     }
 }
 
-criterion_group!(matrix_group, evaluate_columns, evaluate_matrix);
+criterion_group!(matrix_group, evaluate_columns, evaluate_matrix, evaluate_and_commit);
 criterion_main!(matrix_group);
@@ -7,14 +7,16 @@ use alloc::vec::Vec;
 use core::marker::PhantomData;
 
 use air::{
-    proof::{Commitments, Context, OodFrame, Proof, Queries, TraceOodFrame},
-    Air, ConstraintCompositionCoefficients, DeepCompositionCoefficients,
+    proof::{Commitments, Context, OodFrame, Proof, ProofExtension, Queries, TraceOodFrame},
+    Air, AuxRandElements, ConstraintCompositionCoefficients, DeepCompositionCoefficients,
+    Transcript,
 };
 use crypto::{ElementHasher, RandomCoin};
-use fri::FriProof;
+use fri::{positions::draw_query_positions_with_exclusions, FriProof};
 use math::{FieldElement, ToElements};
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
+use utils::Serializable;
 
 // TYPES AND INTERFACES
 // ================================================================================================
@@ -32,6 +34,7 @@ where
     commitments: Commitments,
     ood_frame: OodFrame,
     pow_nonce: u64,
+    transcript: Option<&'a mut Transcript>,
     _field_element: PhantomData<E>,
 }
 
@@ -48,7 +51,14 @@ where
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     /// Creates a new prover channel for the specified `air` and public inputs.
-    pub fn new(air: &'a A, mut pub_inputs_elements: Vec<A::BaseField>) -> Self {
+    ///
+    /// If a `transcript` is provided, all commitments made and all challenges drawn via this
+    /// channel are recorded in it.
+    pub fn new(
+        air: &'a A,
+        mut pub_inputs_elements: Vec<A::BaseField>,
+        transcript: Option<&'a mut Transcript>,
+    ) -> Self {
         let context = Context::new::<A::BaseField>(air.trace_info().clone(), air.options().clone());
 
         // build a seed for the public coin; the initial seed is a hash of the proof context and
@@ -64,6 +74,7 @@ where
             commitments: Commitments::default(),
             ood_frame: OodFrame::default(),
             pow_nonce: 0,
+            transcript,
             _field_element: PhantomData,
         }
     }
@@ -73,12 +84,38 @@ where
 
     /// Commits the prover the extended execution trace.
     pub fn commit_trace(&mut self, trace_root: H::Digest) {
+        self.record_commitment("trace_root", &[trace_root]);
         self.commitments.add::<H>(&trace_root);
         self.public_coin.reseed(trace_root);
     }
 
+    /// Commits the prover to the extended main trace segment partitioned into tables.
+    ///
+    /// Commitments to all tables are included in the proof, but the public coin is reseeded only
+    /// once with the commitments merged via [Commitments::merge_main_trace_table_roots()]. For a
+    /// single table, this is the same as [commit_trace()](Self::commit_trace).
+    pub fn commit_main_trace_tables(&mut self, table_roots: &[H::Digest]) {
+        self.record_commitment("trace_root", table_roots);
+        for table_root in table_roots {
+            self.commitments.add::<H>(table_root);
+        }
+        self.public_coin
+            .reseed(Commitments::merge_main_trace_table_roots::<H>(table_roots));
+    }
+
+    /// Merges the commitment to the preprocessed columns of the main trace segment into the
+    /// public coin.
+    ///
+    /// This commitment is known to the verifier in advance, and thus, it is not included in the
+    /// proof.
+    pub fn commit_preprocessed_trace(&mut self, preprocessed_root: H::Digest) {
+        self.record_commitment("preprocessed_trace_root", &[preprocessed_root]);
+        self.public_coin.reseed(preprocessed_root);
+    }
+
     /// Commits the prover to the evaluations of the constraint composition polynomial.
     pub fn commit_constraints(&mut self, constraint_root: H::Digest) {
+        self.record_commitment("constraint_root", &[constraint_root]);
         self.commitments.add::<H>(&constraint_root);
         self.public_coin.reseed(constraint_root);
     }
@@ -87,6 +124,7 @@ where
     /// also reseeds the public coin with the hashes of the evaluation frame states.
     pub fn send_ood_trace_states(&mut self, trace_ood_frame: &TraceOodFrame<E>) {
         let trace_states_hash = self.ood_frame.set_trace_states::<E, H>(trace_ood_frame);
+        self.record_commitment("ood_trace_states_hash", &[trace_states_hash]);
         self.public_coin.reseed(trace_states_hash);
     }
 
@@ -94,6 +132,7 @@ where
     /// point. This also reseeds the public coin wit the hash of the evaluations.
     pub fn send_ood_constraint_evaluations(&mut self, evaluations: &[E]) {
         self.ood_frame.set_constraint_evaluations(evaluations);
+        self.record_commitment("ood_constraint_evaluations", evaluations);
         self.public_coin.reseed(H::hash_elements(evaluations));
     }
 
@@ -109,41 +148,71 @@ where
     ///
     /// The coefficients are drawn from the public coin uniformly at random.
     pub fn get_constraint_composition_coeffs(&mut self) -> ConstraintCompositionCoefficients<E> {
-        self.air
+        let coefficients = self
+            .air
             .get_constraint_composition_coefficients(&mut self.public_coin)
-            .expect("failed to draw composition coefficients")
+            .expect("failed to draw composition coefficients");
+
+        self.record_challenge("transition_composition_coeffs", &coefficients.transition);
+        self.record_challenge("boundary_composition_coeffs", &coefficients.boundary);
+        if let Some(lagrange) = &coefficients.lagrange {
+            self.record_challenge("lagrange_transition_composition_coeffs", &lagrange.transition);
+            self.record_challenge("lagrange_boundary_composition_coeff", &[lagrange.boundary]);
+        }
+
+        coefficients
     }
 
     /// Returns an out-of-domain point drawn uniformly at random from the public coin.
     pub fn get_ood_point(&mut self) -> E {
-        self.public_coin.draw().expect("failed to draw OOD point")
+        let z = self.public_coin.draw().expect("failed to draw OOD point");
+        self.record_challenge("ood_point", &[z]);
+        z
     }
 
     /// Returns a set of coefficients for constructing a DEEP composition polynomial.
     ///
     /// The coefficients are drawn from the public coin uniformly at random.
     pub fn get_deep_composition_coeffs(&mut self) -> DeepCompositionCoefficients<E> {
-        self.air
+        let coefficients = self
+            .air
             .get_deep_composition_coefficients(&mut self.public_coin)
-            .expect("failed to draw DEEP composition coefficients")
+            .expect("failed to draw DEEP composition coefficients");
+
+        self.record_challenge("deep_trace_coeffs", &coefficients.trace);
+        self.record_challenge("deep_constraint_coeffs", &coefficients.constraints);
+        if let Some(lagrange) = coefficients.lagrange {
+            self.record_challenge("deep_lagrange_coeff", &[lagrange]);
+        }
+
+        coefficients
     }
 
     /// Returns a set of positions in the LDE domain against which the evaluations of trace and
     /// constraint composition polynomials should be queried.
     ///
-    /// The positions are drawn from the public coin uniformly at random. Duplicate positions
-    /// are removed from the returned vector.
+    /// The positions are drawn from the public coin uniformly at random, skipping positions
+    /// excluded by [Air::is_query_position_excluded()]. Duplicate positions are removed from the
+    /// returned vector, and the positions are sorted in ascending order; thus, each position is
+    /// opened only once, and openings of positions which are siblings in a Merkle tree share
+    /// their authentication paths (see [crypto::BatchMerkleProof]).
     pub fn get_query_positions(&mut self) -> Vec<usize> {
         let num_queries = self.context.options().num_queries();
         let lde_domain_size = self.context.lde_domain_size();
-        let mut positions = self
-            .public_coin
-            .draw_integers(num_queries, lde_domain_size, self.pow_nonce)
-            .expect("failed to draw query position");
+        let air = self.air;
+        let positions = draw_query_positions_with_exclusions(
+            &mut self.public_coin,
+            num_queries,
+            lde_domain_size,
+            self.pow_nonce,
+            |position| air.is_query_position_excluded(position),
+        )
+        .expect("failed to draw query position");
 
-        // remove any duplicate positions from the list
-        positions.sort_unstable();
-        positions.dedup();
+        if let Some(transcript) = self.transcript.as_deref_mut() {
+            let positions = positions.iter().map(|&p| p as u64).collect::<Vec<_>>();
+            transcript.record_challenge("query_positions", &positions);
+        }
 
         positions
     }
@@ -166,6 +235,35 @@ where
             .expect("nonce not found");
 
         self.pow_nonce = nonce;
+        self.record_commitment("pow_nonce", &[nonce]);
+    }
+
+    // TRANSCRIPT METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Records the random elements drawn for the auxiliary trace segment in the transcript.
+    ///
+    /// The auxiliary random elements are drawn directly from the public coin (see
+    /// [public_coin()](Self::public_coin)), and thus, they need to be recorded explicitly.
+    pub fn record_aux_rand_elements(&mut self, aux_rand_elements: &AuxRandElements<E>) {
+        if let Some(lagrange) = aux_rand_elements.lagrange() {
+            self.record_challenge("lagrange_kernel_rand_elements", lagrange);
+        }
+        self.record_challenge("aux_rand_elements", aux_rand_elements.rand_elements());
+    }
+
+    /// Records a commitment in the transcript, if the transcript is being recorded.
+    fn record_commitment<T: Serializable>(&mut self, label: &'static str, values: &[T]) {
+        if let Some(transcript) = self.transcript.as_deref_mut() {
+            transcript.record_commitment(label, values);
+        }
+    }
+
+    /// Records a challenge in the transcript, if the transcript is being recorded.
+    fn record_challenge<T: Serializable>(&mut self, label: &'static str, values: &[T]) {
+        if let Some(transcript) = self.transcript.as_deref_mut() {
+            transcript.record_challenge(label, values);
+        }
     }
 
     // PROOF BUILDER
@@ -179,11 +277,16 @@ where
         fri_proof: FriProof,
         num_query_positions: usize,
         gkr_proof: Option<Vec<u8>>,
+        extensions: Vec<ProofExtension>,
     ) -> Proof {
         assert!(num_query_positions <= u8::MAX as usize, "num_query_positions too big");
 
+        let num_main_trace_tables = self.air.context().num_main_trace_tables();
+        assert!(num_main_trace_tables <= u8::MAX as usize, "too many main trace tables");
+
         Proof {
             context: self.context,
+            num_main_trace_tables: num_main_trace_tables as u8,
             commitments: self.commitments,
             ood_frame: self.ood_frame,
             trace_queries,
@@ -192,6 +295,7 @@ where
             pow_nonce: self.pow_nonce,
             num_unique_queries: num_query_positions as u8,
             gkr_proof,
+            extensions,
         }
     }
 }
@@ -210,12 +314,15 @@ where
 
     /// Commits the prover to a FRI layer.
     fn commit_fri_layer(&mut self, layer_root: H::Digest) {
+        self.record_commitment("fri_layer_root", &[layer_root]);
         self.commitments.add::<H>(&layer_root);
         self.public_coin.reseed(layer_root);
     }
 
     /// Returns a new alpha drawn from the public coin.
     fn draw_fri_alpha(&mut self) -> E {
-        self.public_coin.draw().expect("failed to draw FRI alpha")
+        let alpha = self.public_coin.draw().expect("failed to draw FRI alpha");
+        self.record_challenge("fri_alpha", &[alpha]);
+        alpha
     }
 }
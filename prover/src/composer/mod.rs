@@ -8,11 +8,11 @@ use air::{proof::TraceOodFrame, DeepCompositionCoefficients};
 use math::{
     add_in_place, fft, mul_acc,
     polynom::{self, syn_div_roots_in_place},
-    ExtensionOf, FieldElement, StarkField,
+    FieldElement, StarkField,
 };
-use utils::iter_mut;
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
+use utils::{batch_iter_mut, iter_mut};
 
 use super::{constraints::CompositionPoly, StarkDomain, TracePolyTable};
 
@@ -74,7 +74,7 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
     /// separately via `ood_trace_state`.
     pub fn add_trace_polys(
         &mut self,
-        trace_polys: TracePolyTable<E>,
+        trace_polys: &TracePolyTable<E>,
         ood_trace_states: TraceOodFrame<E>,
     ) {
         assert!(self.coefficients.is_empty());
@@ -85,59 +85,25 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
         let g = E::from(E::BaseField::get_root_of_unity(trace_length.ilog2()));
         let next_z = self.z * g;
 
-        // combine trace polynomials into 2 composition polynomials T'(x) and T''(x)
-        let mut t1_composition = vec![E::ZERO; trace_length];
-        let mut t2_composition = vec![E::ZERO; trace_length];
-
-        // index of a trace polynomial; we declare it here so that we can maintain index continuity
-        // across all trace segments
-        let mut i = 0;
-
-        // --- merge polynomials of the main trace segment ----------------------------------------
-        for poly in trace_polys.main_trace_polys() {
-            // compute T'(x) = T(x) - T(z), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E::BaseField, E>(
-                &mut t1_composition,
-                poly,
-                ood_trace_states.current_row()[i],
-                self.cc.trace[i],
-            );
-
-            // compute T''(x) = T(x) - T(z * g), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E::BaseField, E>(
-                &mut t2_composition,
-                poly,
-                ood_trace_states.next_row()[i],
-                self.cc.trace[i],
-            );
-
-            i += 1;
-        }
-
-        // --- merge polynomials of the auxiliary trace segment ----------------------------------
-        for poly in trace_polys.aux_trace_polys() {
-            // compute T'(x) = T(x) - T(z), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E, E>(
-                &mut t1_composition,
-                poly,
-                ood_trace_states.current_row()[i],
-                self.cc.trace[i],
-            );
-
-            // compute T''(x) = T(x) - T(z * g), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E, E>(
-                &mut t2_composition,
-                poly,
-                ood_trace_states.next_row()[i],
-                self.cc.trace[i],
-            );
-
-            i += 1;
-        }
+        // T'(x) and T''(x) share the same random linear combination of trace polynomials and
+        // differ only in the constant term; so, we compute sum(T_i(x) * cc_i) once and derive
+        // both composition polynomials from it
+        let main_trace_polys = trace_polys.main_trace_polys().collect::<Vec<_>>();
+        let aux_trace_polys = trace_polys.aux_trace_polys().collect::<Vec<_>>();
+        let mut t1_composition =
+            combine_trace_polys(trace_length, &main_trace_polys, &aux_trace_polys, &self.cc.trace);
+
+        // compute sum(T_i(z) * cc_i) and sum(T_i(z * g) * cc_i) over all trace polynomials
+        let num_trace_polys = main_trace_polys.len() + aux_trace_polys.len();
+        let cc = &self.cc.trace[..num_trace_polys];
+        let t1_at_z = inner_product(&ood_trace_states.current_row()[..num_trace_polys], cc);
+        let t2_at_z = inner_product(&ood_trace_states.next_row()[..num_trace_polys], cc);
+
+        // compute T'(x) = sum((T_i(x) - T_i(z)) * cc_i) and
+        // T''(x) = sum((T_i(x) - T_i(z * g)) * cc_i)
+        let mut t2_composition = t1_composition.clone();
+        t1_composition[0] -= t1_at_z;
+        t2_composition[0] -= t2_at_z;
 
         // divide the composition polynomials by (x - z) and (x - z * g), respectively,
         // and add the resulting polynomials together; the output of this step
@@ -259,13 +225,43 @@ fn merge_trace_compositions<E: FieldElement>(mut polys: Vec<Vec<E>>, divisors: V
     result
 }
 
-/// Computes (P(x) - value) * k and saves the result into the accumulator.
-fn acc_trace_poly<F, E>(accumulator: &mut [E], poly: &[F], value: E, k: E)
-where
-    F: FieldElement,
-    E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
-{
-    mul_acc(accumulator, poly, k);
-    let adjusted_tz = value * k;
-    accumulator[0] -= adjusted_tz;
+/// Computes a random linear combination sum(T_i(x) * cc_i) of the main and auxiliary trace
+/// polynomials.
+///
+/// When `concurrent` feature is enabled, the domain of the polynomials is split into chunks and
+/// all polynomials are accumulated into each chunk in a separate thread. Main trace polynomials
+/// are in the base field and are combined using base field multiplications.
+fn combine_trace_polys<E: FieldElement>(
+    poly_size: usize,
+    main_trace_polys: &[&[E::BaseField]],
+    aux_trace_polys: &[&[E]],
+    cc: &[E],
+) -> Vec<E> {
+    let (main_cc, aux_cc) = cc.split_at(main_trace_polys.len());
+    let mut result = vec![E::ZERO; poly_size];
+
+    batch_iter_mut!(
+        &mut result,
+        1024, // min batch size
+        |batch: &mut [E], batch_offset: usize| {
+            let range = batch_offset..batch_offset + batch.len();
+            for (poly, &k) in main_trace_polys.iter().zip(main_cc) {
+                for (acc, &c) in batch.iter_mut().zip(&poly[range.clone()]) {
+                    *acc += k.mul_base(c);
+                }
+            }
+            for (poly, &k) in aux_trace_polys.iter().zip(aux_cc) {
+                for (acc, &c) in batch.iter_mut().zip(&poly[range.clone()]) {
+                    *acc += k * c;
+                }
+            }
+        }
+    );
+
+    result
+}
+
+/// Computes sum(values_i * k_i) for all i.
+fn inner_product<E: FieldElement>(values: &[E], k: &[E]) -> E {
+    values.iter().zip(k).fold(E::ZERO, |acc, (&v, &k)| acc + v * k)
 }
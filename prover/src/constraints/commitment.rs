@@ -3,8 +3,6 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use alloc::vec::Vec;
-
 use air::proof::Queries;
 use crypto::{ElementHasher, MerkleTree};
 use math::FieldElement;
@@ -42,6 +40,17 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintComm
         *self.commitment.root()
     }
 
+    /// Returns the evaluations of the composition polynomial columns over the LDE domain.
+    pub fn evaluations(&self) -> &RowMatrix<E> {
+        &self.evaluations
+    }
+
+    /// Returns the commitment Merkle tree; each leaf of the tree is a hash of a row of the
+    /// evaluation matrix.
+    pub fn tree(&self) -> &MerkleTree<H> {
+        &self.commitment
+    }
+
     /// Returns the depth of the commitment Merkle tree.
     #[allow(unused)]
     pub fn tree_depth(&self) -> usize {
@@ -58,11 +67,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintComm
             .expect("failed to generate a Merkle proof for constraint queries");
 
         // determine a set of evaluations corresponding to each position
-        let mut evaluations = Vec::new();
-        for &position in positions {
-            let row = self.evaluations.row(position).to_vec();
-            evaluations.push(row);
-        }
+        let evaluations = self.evaluations.gather_rows(positions);
 
         Queries::new(merkle_proof, evaluations)
     }
@@ -5,7 +5,9 @@
 
 use alloc::vec::Vec;
 
-use math::{fft, polynom::degree_of, FieldElement};
+#[cfg(debug_assertions)]
+use math::polynom::degree_of;
+use math::{fft, FieldElement};
 
 use super::{ColMatrix, StarkDomain};
 
@@ -70,6 +72,20 @@ impl<E: FieldElement> CompositionPoly<E> {
         let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(trace.len());
         fft::interpolate_poly_with_offset(&mut trace, &inv_twiddles, domain.offset());
 
+        // in debug mode, make sure the polynomial fits into the specified number of columns;
+        // otherwise, the coefficients which do not fit would be silently dropped and the proof
+        // would fail verification without a clear indication of the cause
+        #[cfg(debug_assertions)]
+        {
+            let degree = degree_of(&trace);
+            let max_degree = domain.trace_length() * num_cols - 1;
+            assert!(
+                degree <= max_degree,
+                "constraint composition polynomial has degree {degree}, but {num_cols} column(s) of length {} can hold a polynomial of degree at most {max_degree}; this usually means that some constraint has a higher degree than the one declared for it",
+                domain.trace_length()
+            );
+        }
+
         let polys = segment(trace, domain.trace_length(), num_cols);
 
         CompositionPoly { data: ColMatrix::new(polys) }
@@ -123,8 +139,6 @@ fn segment<E: FieldElement>(
     trace_len: usize,
     num_cols: usize,
 ) -> Vec<Vec<E>> {
-    debug_assert!(degree_of(&coefficients) < trace_len * num_cols);
-
     coefficients
         .chunks(trace_len)
         .take(num_cols)
@@ -157,4 +171,28 @@ mod tests {
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "constraint composition polynomial has degree 16")]
+    fn composition_poly_degree_overflow() {
+        use math::{fft, FieldElement, StarkField};
+
+        use super::{CompositionPoly, CompositionPolyTrace};
+        use crate::StarkDomain;
+
+        let trace_length = 8;
+        let domain =
+            StarkDomain::from_twiddles(fft::get_twiddles(trace_length), 4, BaseElement::GENERATOR);
+
+        // evaluate x^16 over the constraint evaluation domain; this polynomial does not fit into
+        // two columns of length 8
+        let mut evaluations = vec![BaseElement::ZERO; 4 * trace_length];
+        evaluations[16] = BaseElement::ONE;
+        let twiddles = fft::get_twiddles::<BaseElement>(evaluations.len());
+        let evaluations =
+            fft::evaluate_poly_with_offset(&evaluations, &twiddles, domain.offset(), 1);
+
+        CompositionPoly::new(CompositionPolyTrace::new(evaluations), &domain, 2);
+    }
 }
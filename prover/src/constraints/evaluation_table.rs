@@ -12,14 +12,14 @@ use math::fft;
 use math::{batch_inversion, FieldElement, StarkField};
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
-use utils::{batch_iter_mut, iter_mut, uninit_vector};
+use utils::{batch_iter_mut, iter_mut, UninitBuffer};
 
 use super::{ConstraintDivisor, StarkDomain};
 
 // CONSTANTS
 // ================================================================================================
 
-const MIN_FRAGMENT_SIZE: usize = 16;
+pub(crate) const MIN_FRAGMENT_SIZE: usize = 16;
 
 // CONSTRAINT EVALUATION TABLE
 // ================================================================================================
@@ -50,7 +50,7 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
         let num_columns = divisors.len();
         let num_rows = domain.ce_domain_size();
         ConstraintEvaluationTable {
-            evaluations: uninit_matrix(num_columns, num_rows),
+            evaluations: zeroed_matrix(num_columns, num_rows),
             divisors,
             domain,
         }
@@ -76,11 +76,11 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
             build_transition_constraint_degrees(transition_constraints, domain.trace_length());
 
         ConstraintEvaluationTable {
-            evaluations: uninit_matrix(num_columns, num_rows),
+            evaluations: zeroed_matrix(num_columns, num_rows),
             divisors,
             domain,
-            main_transition_evaluations: uninit_matrix(num_tm_columns, num_rows),
-            aux_transition_evaluations: uninit_matrix(num_ta_columns, num_rows),
+            main_transition_evaluations: zeroed_matrix(num_tm_columns, num_rows),
+            aux_transition_evaluations: zeroed_matrix(num_ta_columns, num_rows),
             expected_transition_degrees,
         }
     }
@@ -228,6 +228,31 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
             self.num_rows()
         );
     }
+
+    /// Checks that the quotient of each constraint group (i.e., each column of the table divided
+    /// by its divisor) has degree at most `max_degree`, which is the degree bound of the
+    /// constraint composition polynomial.
+    ///
+    /// If a group exceeds the bound, the part of the composition polynomial above the bound would
+    /// be silently dropped when the polynomial is split into columns, and the proof would fail
+    /// verification later on. Thus, we panic with a message identifying the offending group.
+    #[cfg(debug_assertions)]
+    pub fn validate_composition_degrees(&self, max_degree: usize) {
+        let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(self.num_rows());
+        for (i, (column, divisor)) in self.evaluations.iter().zip(self.divisors.iter()).enumerate()
+        {
+            let mut quotient = vec![E::ZERO; self.num_rows()];
+            acc_column(column.clone(), divisor, self.domain, &mut quotient);
+            fft::interpolate_poly(&mut quotient, &inv_twiddles);
+            let degree = math::polynom::degree_of(&quotient);
+
+            let group = if i == 0 { "transition" } else { "boundary" };
+            assert!(
+                degree <= max_degree,
+                "{group} constraint group {i} with divisor {divisor} produced a quotient of degree {degree}, but the constraint composition polynomial can have degree at most {max_degree}; make sure the degrees declared for the constraints in this group are correct"
+            );
+        }
+    }
 }
 
 // TABLE FRAGMENTS
@@ -286,9 +311,9 @@ impl<'a, E: FieldElement> EvaluationTableFragment<'a, E> {
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Allocates memory for a two-dimensional data structure without initializing it.
-fn uninit_matrix<E: FieldElement>(num_cols: usize, num_rows: usize) -> Vec<Vec<E>> {
-    unsafe { (0..num_cols).map(|_| uninit_vector(num_rows)).collect() }
+/// Allocates memory for a two-dimensional data structure and initializes it with zeros.
+fn zeroed_matrix<E: FieldElement>(num_cols: usize, num_rows: usize) -> Vec<Vec<E>> {
+    (0..num_cols).map(|_| vec![E::ZERO; num_rows]).collect()
 }
 
 /// Breaks the source data into a mutable set of fragments such that each fragment has the same
@@ -389,16 +414,14 @@ fn get_inv_evaluation<B: StarkField>(
     let domain_offset_exp = domain.offset().exp(a.into());
 
     // compute x^a - b for all x
-    let mut evaluations = unsafe { uninit_vector(n) };
-    batch_iter_mut!(
-        &mut evaluations,
+    let evaluations = UninitBuffer::new(n).fill_batches(
         128, // min batch size
-        |batch: &mut [B], batch_offset: usize| {
-            for (i, evaluation) in batch.iter_mut().enumerate() {
-                let x = domain.get_ce_x_power_at(batch_offset + i, a, domain_offset_exp);
-                *evaluation = x - b;
+        |batch, batch_offset| {
+            for i in batch_offset..batch_offset + batch.len() {
+                let x = domain.get_ce_x_power_at(i, a, domain_offset_exp);
+                batch.push(x - b);
             }
-        }
+        },
     );
 
     // compute 1 / (x^a - b)
@@ -4,16 +4,18 @@
 // LICENSE file in the root directory of this source tree.
 
 use air::{
-    Air, AuxRandElements, ConstraintCompositionCoefficients, EvaluationFrame, TransitionConstraints,
+    Air, AuxRandElements, ConstraintCompositionCoefficients, EvaluationFrame, EvaluationFrameBatch,
+    TransitionConstraints,
 };
 use math::FieldElement;
 use tracing::instrument;
-use utils::iter_mut;
+use utils::Arena;
 #[cfg(feature = "concurrent")]
 use utils::{iterators::*, rayon};
 
 use super::{
-    super::EvaluationTableFragment, lagrange::LagrangeKernelConstraintsBatchEvaluator,
+    super::{EvaluationTableFragment, MIN_FRAGMENT_SIZE},
+    lagrange::LagrangeKernelConstraintsBatchEvaluator,
     BoundaryConstraints, CompositionPolyTrace, ConstraintEvaluationTable, ConstraintEvaluator,
     PeriodicValueTable, StarkDomain, TraceLde,
 };
@@ -24,6 +26,9 @@ use super::{
 #[cfg(feature = "concurrent")]
 const MIN_CONCURRENT_DOMAIN_SIZE: usize = 8192;
 
+/// Maximum number of evaluation frames passed to [Air::evaluate_transition_batch()] at once.
+const MAX_FRAME_BATCH_SIZE: usize = 64;
+
 // DEFAULT CONSTRAINT EVALUATOR
 // ================================================================================================
 
@@ -33,16 +38,32 @@ const MIN_CONCURRENT_DOMAIN_SIZE: usize = 8192;
 /// evaluates constraints over these frames one-by-one. Constraint evaluations are merged together
 /// using random linear combinations and in the end, only a single column is returned.
 ///
+/// Transition and boundary constraints against the main segment of the execution trace are
+/// evaluated over base field frames even when the proof is generated in an extension field; the
+/// evaluations are lifted into the extension field only when they are multiplied by composition
+/// coefficients.
+///
+/// Transition constraints against the main segment are evaluated over blocks of consecutive
+/// evaluation frames via [Air::evaluate_transition_batch()]; this allows AIRs to vectorize or
+/// offload the evaluation of these constraints.
+///
 /// When `concurrent` feature is enabled, the extended execution trace is split into sets of
 /// sequential evaluation frames (called fragments), and frames in each fragment are evaluated
-/// in separate threads.
+/// in separate threads. By default, the number of fragments is the number of threads rounded up
+/// to the next power of two; the size of fragments can also be set explicitly via
+/// [with_fragment_size()](DefaultConstraintEvaluator::with_fragment_size).
+///
+/// All buffers used to evaluate a fragment are allocated by the thread which evaluates the
+/// fragment, and the rows of the evaluation table covered by the fragment are left uninitialized
+/// until this thread writes them. Thus, on systems with a first-touch memory placement policy
+/// (e.g., Linux), this memory is placed on the NUMA node of the thread evaluating the fragment.
 pub struct DefaultConstraintEvaluator<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> {
     air: &'a A,
     boundary_constraints: BoundaryConstraints<E>,
     transition_constraints: TransitionConstraints<E>,
     lagrange_constraints_evaluator: Option<LagrangeKernelConstraintsBatchEvaluator<E>>,
     aux_rand_elements: Option<AuxRandElements<E>>,
-    periodic_values: PeriodicValueTable<E::BaseField>,
+    fragment_size: Option<usize>,
 }
 
 impl<'a, A, E> ConstraintEvaluator<E> for DefaultConstraintEvaluator<'a, A, E>
@@ -90,32 +111,69 @@ where
         // then don't bother with concurrent evaluation
 
         #[cfg(not(feature = "concurrent"))]
-        let num_fragments = 1;
+        let default_num_fragments = 1;
 
         #[cfg(feature = "concurrent")]
-        let num_fragments = if domain.ce_domain_size() >= MIN_CONCURRENT_DOMAIN_SIZE {
+        let default_num_fragments = if domain.ce_domain_size() >= MIN_CONCURRENT_DOMAIN_SIZE {
             rayon::current_num_threads().next_power_of_two()
         } else {
             1
         };
 
+        // if the size of fragments was set explicitly, it takes precedence over the default
+        let num_fragments = match self.fragment_size {
+            Some(fragment_size) => (domain.ce_domain_size() / fragment_size).max(1),
+            None => default_num_fragments,
+        };
+
+        // periodic column values are usually expanded over the constraint evaluation domain when
+        // the domain is built, and are shared by all proofs generated over the same domain; for
+        // domains not built from an AIR, we expand periodic column values here
+        let local_periodic_values;
+        let periodic_values = match domain.periodic_values() {
+            Some(periodic_values) => periodic_values,
+            None => {
+                local_periodic_values = PeriodicValueTable::new(self.air);
+                &local_periodic_values
+            },
+        };
+
         // evaluate constraints for each fragment; if the trace consist of multiple segments
         // we evaluate constraints for all segments. otherwise, we evaluate constraints only
-        // for the main segment.
+        // for the main segment. scratch buffers for each fragment are allocated in an arena which
+        // is reset and reused for all fragments processed by the same thread.
         let mut fragments = evaluation_table.fragments(num_fragments);
-        iter_mut!(fragments).for_each(|fragment| {
+        let evaluate_fragment = |arena: &mut Arena, fragment: &mut EvaluationTableFragment<E>| {
             if self.air.trace_info().is_multi_segment() {
-                self.evaluate_fragment_full(trace, domain, fragment);
+                self.evaluate_fragment_full(trace, domain, periodic_values, fragment, arena);
             } else {
-                self.evaluate_fragment_main(trace, domain, fragment);
+                self.evaluate_fragment_main(trace, domain, periodic_values, fragment, arena);
             }
-        });
+        };
+
+        #[cfg(not(feature = "concurrent"))]
+        {
+            let mut arena = Arena::new();
+            fragments
+                .iter_mut()
+                .for_each(|fragment| evaluate_fragment(&mut arena, fragment));
+        }
+
+        #[cfg(feature = "concurrent")]
+        fragments.par_iter_mut().for_each_init(Arena::new, evaluate_fragment);
 
         // when in debug mode, make sure expected transition constraint degrees align with
         // actual degrees we got during constraint evaluation
         #[cfg(debug_assertions)]
         evaluation_table.validate_transition_degrees();
 
+        // when in debug mode, also make sure that no constraint group overflows the degree of the
+        // constraint composition polynomial
+        #[cfg(debug_assertions)]
+        evaluation_table.validate_composition_degrees(
+            domain.trace_length() * self.air.context().num_constraint_composition_columns() - 1,
+        );
+
         // combine all constraint evaluations into a single column, including the evaluations of the
         // Lagrange kernel constraints (if present)
         let combined_evaluations = {
@@ -147,9 +205,6 @@ where
         // evaluations
         let transition_constraints =
             air.get_transition_constraints(&composition_coefficients.transition);
-        // build periodic value table
-        let periodic_values = PeriodicValueTable::new(air);
-
         // build boundary constraint groups; these will be used to evaluate and compose boundary
         // constraint evaluations.
         let boundary_constraints = BoundaryConstraints::new(
@@ -183,10 +238,30 @@ where
             transition_constraints,
             lagrange_constraints_evaluator,
             aux_rand_elements,
-            periodic_values,
+            fragment_size: None,
         }
     }
 
+    /// Returns this evaluator with the number of rows in each fragment of the constraint
+    /// evaluation domain set to the specified value.
+    ///
+    /// Smaller fragments let the threads balance the work among themselves at the cost of a
+    /// slightly higher per-fragment overhead; larger fragments keep each thread working over a
+    /// longer contiguous region of memory. If the constraint evaluation domain is smaller than
+    /// `fragment_size`, the domain is evaluated as a single fragment.
+    ///
+    /// # Panics
+    /// Panics if `fragment_size` is not a power of two or is smaller than 16.
+    pub fn with_fragment_size(mut self, fragment_size: usize) -> Self {
+        assert!(fragment_size.is_power_of_two(), "fragment size must be a power of two");
+        assert!(
+            fragment_size >= MIN_FRAGMENT_SIZE,
+            "fragment size must be at least {MIN_FRAGMENT_SIZE}, but was {fragment_size}"
+        );
+        self.fragment_size = Some(fragment_size);
+        self
+    }
+
     // EVALUATION HELPERS
     // --------------------------------------------------------------------------------------------
 
@@ -197,12 +272,27 @@ where
         &self,
         trace: &T,
         domain: &StarkDomain<A::BaseField>,
+        periodic_values: &PeriodicValueTable<A::BaseField>,
         fragment: &mut EvaluationTableFragment<E>,
+        arena: &mut Arena,
     ) {
-        // initialize buffers to hold trace values and evaluation results at each step;
+        // initialize buffers to hold trace values and evaluation results at each step; the
+        // buffers live only as long as this fragment is being evaluated, and thus, they are all
+        // allocated in the provided arena after memory handed out for prior fragments is reclaimed
+        arena.reset();
+        let arena = &*arena;
         let mut main_frame = EvaluationFrame::new(trace.trace_info().main_trace_width());
-        let mut evaluations = vec![E::ZERO; fragment.num_columns()];
-        let mut t_evaluations = vec![E::BaseField::ZERO; self.num_main_transition_constraints()];
+        let evaluations = arena.alloc_slice_fill(fragment.num_columns(), E::ZERO);
+        #[cfg(debug_assertions)]
+        let t_evaluations =
+            arena.alloc_slice_fill(self.num_main_transition_constraints(), E::BaseField::ZERO);
+        let mut batch = MainTransitionBatch::new(
+            arena,
+            trace.trace_info().main_trace_width(),
+            periodic_values.get_row(0).len(),
+            self.num_main_transition_constraints(),
+            MAX_FRAME_BATCH_SIZE.min(fragment.num_rows()),
+        );
 
         // this will be used to convert steps in constraint evaluation domain to steps in
         // LDE domain
@@ -211,22 +301,28 @@ where
         for i in 0..fragment.num_rows() {
             let step = i + fragment.offset();
 
-            // update evaluation frame buffer with data from the execution trace; this will
-            // read current and next rows from the trace into the buffer; data in the trace
-            // table is extended over the LDE domain, so, we need to convert step in constraint
-            // evaluation domain, into a step in LDE domain, in case these domains are different
-            trace.read_main_trace_frame_into(step << lde_shift, &mut main_frame);
+            // at the start of each batch, read the next block of evaluation frames from the
+            // execution trace and evaluate transition constraints over all of them
+            let batch_idx = i % batch.num_frames();
+            if batch_idx == 0 {
+                batch.read_frames(trace, periodic_values, step, lde_shift, &mut main_frame);
+                self.evaluate_main_transition_batch(&mut batch);
+            }
 
-            // evaluate transition constraints and save the merged result the first slot of the
+            // save the merged transition constraint evaluations into the first slot of the
             // evaluations buffer
-            evaluations[0] = self.evaluate_main_transition(&main_frame, step, &mut t_evaluations);
+            evaluations[0] = batch.merged_evaluations[batch_idx];
 
             // when in debug mode, save transition constraint evaluations
             #[cfg(debug_assertions)]
-            fragment.update_transition_evaluations(i, &t_evaluations, &[]);
+            {
+                batch.read_evaluations_into(batch_idx, t_evaluations);
+                fragment.update_transition_evaluations(i, t_evaluations, &[]);
+            }
 
             // evaluate boundary constraints; the results go into remaining slots of the
             // evaluations buffer
+            batch.frames.read_frame_into(batch_idx, &mut main_frame);
             let main_state = main_frame.current();
             self.boundary_constraints.evaluate_main(
                 main_state,
@@ -236,7 +332,7 @@ where
             );
 
             // record the result in the evaluation table
-            fragment.update_row(i, &evaluations);
+            fragment.update_row(i, evaluations);
         }
     }
 
@@ -248,14 +344,29 @@ where
         &self,
         trace: &T,
         domain: &StarkDomain<A::BaseField>,
+        periodic_values: &PeriodicValueTable<A::BaseField>,
         fragment: &mut EvaluationTableFragment<E>,
+        arena: &mut Arena,
     ) {
-        // initialize buffers to hold trace values and evaluation results at each step
+        // initialize buffers to hold trace values and evaluation results at each step; the
+        // buffers live only as long as this fragment is being evaluated, and thus, they are all
+        // allocated in the provided arena after memory handed out for prior fragments is reclaimed
+        arena.reset();
+        let arena = &*arena;
         let mut main_frame = EvaluationFrame::new(trace.trace_info().main_trace_width());
         let mut aux_frame = EvaluationFrame::new(trace.trace_info().aux_segment_width());
-        let mut tm_evaluations = vec![E::BaseField::ZERO; self.num_main_transition_constraints()];
-        let mut ta_evaluations = vec![E::ZERO; self.num_aux_transition_constraints()];
-        let mut evaluations = vec![E::ZERO; fragment.num_columns()];
+        #[cfg(debug_assertions)]
+        let tm_evaluations =
+            arena.alloc_slice_fill(self.num_main_transition_constraints(), E::BaseField::ZERO);
+        let ta_evaluations = arena.alloc_slice_fill(self.num_aux_transition_constraints(), E::ZERO);
+        let evaluations = arena.alloc_slice_fill(fragment.num_columns(), E::ZERO);
+        let mut batch = MainTransitionBatch::new(
+            arena,
+            trace.trace_info().main_trace_width(),
+            periodic_values.get_row(0).len(),
+            self.num_main_transition_constraints(),
+            MAX_FRAME_BATCH_SIZE.min(fragment.num_rows()),
+        );
 
         // this will be used to convert steps in constraint evaluation domain to steps in
         // LDE domain
@@ -264,21 +375,36 @@ where
         for i in 0..fragment.num_rows() {
             let step = i + fragment.offset();
 
-            // read both the main and the auxiliary evaluation frames from the trace
-            trace.read_main_trace_frame_into(step << lde_shift, &mut main_frame);
+            // at the start of each batch, read the next block of main evaluation frames from the
+            // execution trace and evaluate main transition constraints over all of them
+            let batch_idx = i % batch.num_frames();
+            if batch_idx == 0 {
+                batch.read_frames(trace, periodic_values, step, lde_shift, &mut main_frame);
+                self.evaluate_main_transition_batch(&mut batch);
+            }
+
+            // read both the main and the auxiliary evaluation frames for the current step
+            batch.frames.read_frame_into(batch_idx, &mut main_frame);
             trace.read_aux_trace_frame_into(step << lde_shift, &mut aux_frame);
 
-            // evaluate transition constraints and save the merged result the first slot of the
-            // evaluations buffer; we evaluate and compose constraints in the same function, we
+            // save the merged transition constraint evaluations into the first slot of the
+            // evaluations buffer; since we evaluate and compose constraints at the same time, we
             // can just add up the results of evaluating main and auxiliary constraints.
-            evaluations[0] = self.evaluate_main_transition(&main_frame, step, &mut tm_evaluations);
-
-            evaluations[0] +=
-                self.evaluate_aux_transition(&main_frame, &aux_frame, step, &mut ta_evaluations);
+            let periodic_values = periodic_values.get_row(step);
+            evaluations[0] = batch.merged_evaluations[batch_idx];
+            evaluations[0] += self.evaluate_aux_transition(
+                &main_frame,
+                &aux_frame,
+                periodic_values,
+                ta_evaluations,
+            );
 
             // when in debug mode, save transition constraint evaluations
             #[cfg(debug_assertions)]
-            fragment.update_transition_evaluations(i, &tm_evaluations, &ta_evaluations);
+            {
+                batch.read_evaluations_into(batch_idx, tm_evaluations);
+                fragment.update_transition_evaluations(i, tm_evaluations, ta_evaluations);
+            }
 
             // evaluate boundary constraints; the results go into remaining slots of the
             // evaluations buffer
@@ -293,7 +419,7 @@ where
             );
 
             // record the result in the evaluation table
-            fragment.update_row(i, &evaluations);
+            fragment.update_row(i, evaluations);
         }
     }
 
@@ -323,53 +449,63 @@ where
     // TRANSITION CONSTRAINT EVALUATORS
     // --------------------------------------------------------------------------------------------
 
-    /// Evaluates transition constraints of the main execution trace at the specified step of the
-    /// constraint evaluation domain.
+    /// Evaluates transition constraints of the main execution trace over a batch of consecutive
+    /// evaluation frames and saves the merged evaluations for each frame into the batch.
     ///
-    /// `x` is the corresponding domain value at the specified step. That is, x = s * g^step,
-    /// where g is the generator of the constraint evaluation domain, and s is the domain offset.
-    fn evaluate_main_transition(
-        &self,
-        main_frame: &EvaluationFrame<E::BaseField>,
-        step: usize,
-        evaluations: &mut [E::BaseField],
-    ) -> E {
+    /// Constraints are evaluated in the base field; the evaluations are lifted into the extension
+    /// field only when they are merged using composition coefficients.
+    fn evaluate_main_transition_batch(&self, batch: &mut MainTransitionBatch<'_, E>) {
         // TODO: use a more efficient way to zero out memory
-        evaluations.fill(E::BaseField::ZERO);
-
-        // get periodic values at the evaluation step
-        let periodic_values = self.periodic_values.get_row(step);
+        batch.evaluations.fill(E::BaseField::ZERO);
 
         // evaluate transition constraints over the main segment of the execution trace and save
         // the results into evaluations buffer
-        self.air.evaluate_transition(main_frame, periodic_values, evaluations);
+        self.air
+            .evaluate_transition_batch(&batch.frames, batch.periodic_values, batch.evaluations);
+
+        // multiply evaluations of constraints gated by selector columns by the values of these
+        // columns in the current row of each frame
+        let num_frames = batch.num_frames();
+        for group in self.air.context().selector_groups() {
+            let selectors = batch.frames.current(group.selector_column());
+            let constraints = group.main_constraints();
+            let gated_evaluations = &mut batch.evaluations
+                [constraints.start * num_frames..constraints.end * num_frames];
+            for evaluations in gated_evaluations.chunks_mut(num_frames) {
+                for (evaluation, &selector) in evaluations.iter_mut().zip(selectors) {
+                    *evaluation *= selector;
+                }
+            }
+        }
 
-        // merge transition constraint evaluations into a single value and return it;
-        // we can do this here because all transition constraints have the same divisor.
-        evaluations
-            .iter()
+        // merge transition constraint evaluations into a single value for each frame; we can do
+        // this here because all transition constraints have the same divisor.
+        batch.merged_evaluations.fill(E::ZERO);
+        for (evaluations, &coef) in batch
+            .evaluations
+            .chunks(num_frames)
             .zip(self.transition_constraints.main_constraint_coef().iter())
-            .fold(E::ZERO, |acc, (&const_eval, &coef)| acc + coef.mul_base(const_eval))
+        {
+            for (result, &const_eval) in batch.merged_evaluations.iter_mut().zip(evaluations) {
+                *result += coef.mul_base(const_eval);
+            }
+        }
     }
 
-    /// Evaluates all transition constraints (i.e., for main and the auxiliary trace segment) at the
-    /// specified step of the constraint evaluation domain.
+    /// Evaluates all transition constraints (i.e., for main and the auxiliary trace segment) at a
+    /// single step of the constraint evaluation domain.
     ///
-    /// `x` is the corresponding domain value at the specified step. That is, x = s * g^step,
-    /// where g is the generator of the constraint evaluation domain, and s is the domain offset.
+    /// `periodic_values` are the values of periodic columns at the evaluation step.
     fn evaluate_aux_transition(
         &self,
         main_frame: &EvaluationFrame<E::BaseField>,
         aux_frame: &EvaluationFrame<E>,
-        step: usize,
+        periodic_values: &[E::BaseField],
         evaluations: &mut [E],
     ) -> E {
         // TODO: use a more efficient way to zero out memory
         evaluations.fill(E::ZERO);
 
-        // get periodic values at the evaluation step
-        let periodic_values = self.periodic_values.get_row(step);
-
         // evaluate transition constraints over the auxiliary trace segment and save the results into
         // evaluations buffer
         self.air.evaluate_aux_transition(
@@ -382,6 +518,7 @@ where
                 .rand_elements(),
             evaluations,
         );
+        self.air.context().apply_aux_selectors(main_frame.current(), evaluations);
 
         // merge transition constraint evaluations into a single value and return it;
         // we can do this here because all transition constraints have the same divisor.
@@ -405,3 +542,77 @@ where
         self.transition_constraints.num_aux_constraints()
     }
 }
+
+// MAIN TRANSITION BATCH
+// ================================================================================================
+
+/// Buffers for evaluating main transition constraints over a batch of consecutive evaluation
+/// frames.
+///
+/// Periodic values and constraint evaluations are stored in the same structure-of-arrays layout
+/// as the frames (see [EvaluationFrameBatch]), and are allocated in an arena.
+struct MainTransitionBatch<'a, E: FieldElement> {
+    frames: EvaluationFrameBatch<E::BaseField>,
+    periodic_values: &'a mut [E::BaseField],
+    evaluations: &'a mut [E::BaseField],
+    merged_evaluations: &'a mut [E],
+}
+
+impl<'a, E: FieldElement> MainTransitionBatch<'a, E> {
+    /// Returns new buffers for the batch of the specified number of frames allocated in the
+    /// provided arena.
+    fn new(
+        arena: &'a Arena,
+        num_columns: usize,
+        num_periodic_values: usize,
+        num_constraints: usize,
+        num_frames: usize,
+    ) -> Self {
+        Self {
+            frames: EvaluationFrameBatch::new(num_columns, num_frames),
+            periodic_values: arena
+                .alloc_slice_fill(num_periodic_values * num_frames, E::BaseField::ZERO),
+            evaluations: arena.alloc_slice_fill(num_constraints * num_frames, E::BaseField::ZERO),
+            merged_evaluations: arena.alloc_slice_fill(num_frames, E::ZERO),
+        }
+    }
+
+    /// Returns the number of frames in this batch.
+    fn num_frames(&self) -> usize {
+        self.frames.num_frames()
+    }
+
+    /// Reads the evaluation frames and the periodic values for the consecutive steps of the
+    /// constraint evaluation domain starting at `first_step` into this batch.
+    ///
+    /// Data in the trace is extended over the LDE domain, so, steps in the constraint evaluation
+    /// domain are converted into steps in the LDE domain using `lde_shift`; `frame` is used as a
+    /// scratch buffer.
+    fn read_frames<T: TraceLde<E>>(
+        &mut self,
+        trace: &T,
+        periodic_values: &PeriodicValueTable<E::BaseField>,
+        first_step: usize,
+        lde_shift: u32,
+        frame: &mut EvaluationFrame<E::BaseField>,
+    ) {
+        let num_frames = self.num_frames();
+        for i in 0..num_frames {
+            let step = first_step + i;
+            trace.read_main_trace_frame_into(step << lde_shift, frame);
+            self.frames.set_frame(i, frame);
+            for (j, &value) in periodic_values.get_row(step).iter().enumerate() {
+                self.periodic_values[j * num_frames + i] = value;
+            }
+        }
+    }
+
+    /// Copies evaluations of all transition constraints for the specified frame into `result`.
+    #[cfg(debug_assertions)]
+    fn read_evaluations_into(&self, frame_idx: usize, result: &mut [E::BaseField]) {
+        let num_frames = self.num_frames();
+        for (j, value) in result.iter_mut().enumerate() {
+            *value = self.evaluations[j * num_frames + frame_idx];
+        }
+    }
+}
@@ -17,7 +17,7 @@ use boundary::BoundaryConstraints;
 mod lagrange;
 
 mod periodic_table;
-use periodic_table::PeriodicValueTable;
+pub(crate) use periodic_table::PeriodicValueTable;
 
 // CONSTRAINT EVALUATOR TRAIT
 // ================================================================================================
@@ -27,6 +27,13 @@ use periodic_table::PeriodicValueTable;
 /// The logic for evaluating AIR constraints over a single evaluation frame is defined by the [Air]
 /// associated type, and the purpose of this trait is to execute this logic over all evaluation
 /// frames in an extended execution trace.
+///
+/// When proofs are generated in an extension field, constraints against the main segment of the
+/// execution trace involve only base field values. Implementations should evaluate such
+/// constraints in the base field, and lift the results into the extension field only when
+/// combining them with composition coefficients (e.g., via
+/// [mul_base()](math::ExtensionOf::mul_base)), as this is significantly cheaper than evaluating
+/// the constraints over extension field elements.
 pub trait ConstraintEvaluator<E: FieldElement> {
     /// AIR constraints for the computation described by this evaluator.
     type Air: Air<BaseField = E::BaseField>;
@@ -7,9 +7,14 @@ use alloc::{collections::BTreeMap, vec::Vec};
 
 use air::Air;
 use math::{fft, StarkField};
-use utils::uninit_vector;
+use utils::UninitBuffer;
 
+/// A table of periodic column values expanded over the constraint evaluation domain.
+///
+/// The table depends only on the AIR and on the STARK domain, and thus, it is built together with
+/// the domain and is shared by all constraint evaluations performed over that domain.
 pub struct PeriodicValueTable<B: StarkField> {
+    polys: Vec<Vec<B>>,
     values: Vec<B>,
     length: usize,
     width: usize,
@@ -26,7 +31,12 @@ impl<B: StarkField> PeriodicValueTable<B> {
         // periodic columns return an empty table
         let polys = air.get_periodic_column_polys();
         if polys.is_empty() {
-            return PeriodicValueTable { values: Vec::new(), length: 0, width: 0 };
+            return PeriodicValueTable {
+                polys,
+                values: Vec::new(),
+                length: 0,
+                width: 0,
+            };
         }
 
         // determine the size of the biggest polynomial in the set. unwrap is OK here
@@ -54,14 +64,14 @@ impl<B: StarkField> PeriodicValueTable<B> {
         // table in such a way that values for the same row are adjacent to each other.
         let row_width = polys.len();
         let column_length = max_poly_size * air.ce_blowup_factor();
-        let mut values = unsafe { uninit_vector(row_width * column_length) };
+        let mut values = UninitBuffer::new(row_width * column_length);
         for i in 0..column_length {
-            for (j, column) in evaluations.iter().enumerate() {
-                values[i * row_width + j] = column[i % column.len()];
-            }
+            values.extend(evaluations.iter().map(|column| column[i % column.len()]));
         }
+        let values = values.into_vec();
 
         PeriodicValueTable {
+            polys,
             values,
             length: column_length,
             width: row_width,
@@ -75,6 +85,11 @@ impl<B: StarkField> PeriodicValueTable<B> {
         self.width == 0
     }
 
+    /// Returns true if this table contains the periodic columns of the specified AIR.
+    pub fn is_compatible_with<A: Air<BaseField = B>>(&self, air: &A) -> bool {
+        self.polys == air.get_periodic_column_polys()
+    }
+
     pub fn get_row(&self, ce_step: usize) -> &[B] {
         if self.is_empty() {
             &[]
@@ -143,6 +158,25 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn periodic_value_table_compatibility() {
+        let trace_length = 32;
+        let col1 = vec![1u128, 2].into_iter().map(BaseElement::new).collect::<Vec<_>>();
+        let col2 = vec![3u128, 4, 5, 6].into_iter().map(BaseElement::new).collect::<Vec<_>>();
+
+        let air = MockAir::with_periodic_columns(vec![col1.clone(), col2.clone()], trace_length);
+        let table = super::PeriodicValueTable::new(&air);
+        assert!(table.is_compatible_with(&air));
+
+        // the table is compatible with AIRs defining the same periodic columns
+        let air = MockAir::with_periodic_columns(vec![col1.clone(), col2], trace_length);
+        assert!(table.is_compatible_with(&air));
+
+        // but not with AIRs defining different periodic columns
+        let air = MockAir::with_periodic_columns(vec![col1], trace_length);
+        assert!(!table.is_compatible_with(&air));
+    }
+
     fn build_ce_domain(domain_size: usize, domain_offset: BaseElement) -> Vec<BaseElement> {
         let g = BaseElement::get_root_of_unity(domain_size.ilog2());
         get_power_series_with_offset(g, domain_offset, domain_size)
@@ -8,6 +8,8 @@ use alloc::vec::Vec;
 use air::Air;
 use math::{fft, get_power_series, StarkField};
 
+use crate::constraints::PeriodicValueTable;
+
 // TYPES AND INTERFACES
 // ================================================================================================
 
@@ -30,6 +32,10 @@ pub struct StarkDomain<B: StarkField> {
 
     /// Offset of the low-degree extension domain.
     domain_offset: B,
+
+    /// Values of periodic columns expanded over the constraint evaluation domain; this is set only
+    /// for domains built for a specific AIR.
+    periodic_values: Option<PeriodicValueTable<B>>,
 }
 
 // STARK DOMAIN IMPLEMENTATION
@@ -50,6 +56,7 @@ impl<B: StarkField> StarkDomain<B> {
             ce_to_lde_blowup: air.lde_domain_size() / air.ce_domain_size(),
             ce_domain_mod_mask: air.ce_domain_size() - 1,
             domain_offset: air.domain_offset(),
+            periodic_values: Some(PeriodicValueTable::new(air)),
         }
     }
 
@@ -72,9 +79,23 @@ impl<B: StarkField> StarkDomain<B> {
             ce_to_lde_blowup: 1,
             ce_domain_mod_mask: ce_domain_size - 1,
             domain_offset,
+            periodic_values: None,
         }
     }
 
+    /// Returns true if this domain can be used to generate proofs for the computation described by
+    /// the specified AIR.
+    pub fn is_compatible_with<A: Air<BaseField = B>>(&self, air: &A) -> bool {
+        self.trace_length() == air.trace_length()
+            && self.ce_domain_size() == air.ce_domain_size()
+            && self.lde_domain_size() == air.lde_domain_size()
+            && self.offset() == air.domain_offset()
+            && self
+                .periodic_values
+                .as_ref()
+                .map_or(true, |periodic_values| periodic_values.is_compatible_with(air))
+    }
+
     // EXECUTION TRACE
     // --------------------------------------------------------------------------------------------
 
@@ -152,4 +173,13 @@ impl<B: StarkField> StarkDomain<B> {
     pub fn offset(&self) -> B {
         self.domain_offset
     }
+
+    // PERIODIC COLUMNS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns values of periodic columns expanded over the constraint evaluation domain, if this
+    /// domain was built for a specific AIR.
+    pub(crate) fn periodic_values(&self) -> Option<&PeriodicValueTable<B>> {
+        self.periodic_values.as_ref()
+    }
 }
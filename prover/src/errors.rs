@@ -5,8 +5,16 @@
 
 //! Contains common error types for prover and verifier.
 
+#[cfg(feature = "self-verify")]
+use alloc::boxed::Box;
+use alloc::string::String;
 use core::fmt;
 
+use crypto::HashFunctionId;
+
+#[cfg(feature = "self-verify")]
+use crate::SelfVerificationFailure;
+
 // PROVER ERROR
 // ================================================================================================
 /// Represents an error returned by the prover during an execution of the protocol.
@@ -21,6 +29,49 @@ pub enum ProverError {
     /// This error occurs when the base field specified by the AIR does not support field extension
     /// of degree specified by proof options.
     UnsupportedFieldExtension(usize),
+    /// This error occurs when a precomputed commitment to the main trace segment is not
+    /// consistent with the execution trace for which the proof is being generated.
+    InconsistentMainTraceCommitment(String),
+    /// This error occurs when a proof is generated from a precomputed commitment to the main trace
+    /// segment, but the prover does not support building a trace LDE from such a commitment.
+    UnsupportedMainTraceCommitment,
+    /// This error occurs when the hash functions specified by proof options are not the hash
+    /// functions used by the prover for commitments and for the public coin.
+    MismatchedHashFunctions,
+    /// This error occurs when the digest truncation width specified by proof options is not the
+    /// size of the digests of the hash function used by the prover for commitments; the first
+    /// value is the truncation width, and the second value is the digest size (both in bytes).
+    MismatchedDigestSize(usize, usize),
+    /// This error occurs when the parameters digest specified by proof options is not the digest
+    /// of the protocol configuration of the prover (i.e., its base field, hash functions, and
+    /// proof options).
+    MismatchedParametersDigest,
+    /// This error occurs when the preprocessed columns of the main trace segment declared by the
+    /// AIR are not provided by the prover, or are not consistent with the execution trace for
+    /// which the proof is being generated.
+    InvalidPreprocessedTrace(String),
+    /// This error occurs when the tables into which the main trace segment is partitioned by the
+    /// trace LDE do not match the tables declared by the AIR.
+    InvalidMainTraceTables(String),
+    /// This error occurs when a prover cannot be instantiated with the specified hash function
+    /// at runtime.
+    UnsupportedHashFunction(HashFunctionId),
+    /// This error occurs when the estimated peak memory needed to generate a proof exceeds the
+    /// memory budget of the prover; the first value is the estimate, and the second value is the
+    /// budget (both in bytes).
+    MemoryBudgetExceeded(u64, u64),
+    /// This error occurs when the AIR excludes more positions from querying than allowed by
+    /// [AirContext::max_excluded_query_positions()](air::AirContext::max_excluded_query_positions).
+    TooManyExcludedQueryPositions { num_excluded: usize, max_excluded: usize },
+    /// This error occurs when the AIR excludes more positions from querying via
+    /// [Air::is_query_position_excluded()](air::Air::is_query_position_excluded) than it reports
+    /// via [Air::num_excluded_query_positions()](air::Air::num_excluded_query_positions). This is
+    /// checked only in debug builds.
+    UnderreportedExcludedQueryPositions { num_excluded: usize, num_reported: usize },
+    /// This error occurs when a proof generated via
+    /// [Prover::prove_and_verify()](crate::Prover::prove_and_verify) fails verification.
+    #[cfg(feature = "self-verify")]
+    SelfVerificationFailed(Box<SelfVerificationFailure>),
 }
 
 impl fmt::Display for ProverError {
@@ -36,6 +87,43 @@ impl fmt::Display for ProverError {
             Self::UnsupportedFieldExtension(degree) => {
                 write!(f, "field extension of degree {degree} is not supported for the specified base field")
             }
+            Self::InconsistentMainTraceCommitment(reason) => {
+                write!(f, "the main trace commitment is inconsistent with the execution trace: {reason}")
+            }
+            Self::UnsupportedMainTraceCommitment => {
+                write!(f, "the prover does not support proof generation from precomputed main trace commitments")
+            }
+            Self::MismatchedHashFunctions => {
+                write!(f, "the hash functions specified by proof options do not match the hash functions used by the prover")
+            }
+            Self::MismatchedDigestSize(expected, actual) => {
+                write!(f, "proof options specify {expected}-byte digests, but the commitment hash function of the prover produces {actual}-byte digests")
+            }
+            Self::MismatchedParametersDigest => {
+                write!(f, "the parameters digest specified by proof options does not match the protocol configuration of the prover")
+            }
+            Self::InvalidPreprocessedTrace(reason) => {
+                write!(f, "the preprocessed trace is invalid: {reason}")
+            }
+            Self::InvalidMainTraceTables(reason) => {
+                write!(f, "the main trace tables are invalid: {reason}")
+            }
+            Self::UnsupportedHashFunction(hash_fn) => {
+                write!(f, "hash function {hash_fn:?} is not supported by the runtime hash function dispatch")
+            }
+            Self::MemoryBudgetExceeded(estimate, budget) => {
+                write!(f, "proof generation requires an estimated {estimate} bytes of memory, but the memory budget is {budget} bytes")
+            }
+            Self::TooManyExcludedQueryPositions { num_excluded, max_excluded } => {
+                write!(f, "the AIR excludes {num_excluded} positions from querying, but at most {max_excluded} positions can be excluded")
+            }
+            Self::UnderreportedExcludedQueryPositions { num_excluded, num_reported } => {
+                write!(f, "the AIR excludes {num_excluded} positions from querying, but reports only {num_reported} excluded positions")
+            }
+            #[cfg(feature = "self-verify")]
+            Self::SelfVerificationFailed(failure) => {
+                write!(f, "{failure}")
+            }
         }
     }
 }
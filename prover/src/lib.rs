@@ -12,7 +12,9 @@
 //! When the crate is compiled with `concurrent` feature enabled, proof generation will be
 //! performed in multiple threads (usually, as many threads as there are logical cores on the
 //! machine). The number of threads can be configured via `RAYON_NUM_THREADS` environment
-//! variable.
+//! variable. Alternatively, a proof can be generated in a dedicated thread pool via
+//! `Prover::prove_in_thread_pool()` method; this allows multiple proving jobs to run in the same
+//! process without competing for the threads of the global pool.
 //!
 //! # Usage
 //! To generate a proof that a computation was executed correctly, you'll need to do the
@@ -42,18 +44,28 @@
 #[macro_use]
 extern crate alloc;
 
-#[cfg(feature = "async")]
+#[cfg(feature = "mmap")]
+extern crate std;
+
+#[cfg(all(feature = "mmap", not(unix)))]
+compile_error!("`mmap` feature is supported only on unix targets");
+
+#[cfg(any(feature = "async", feature = "self-verify"))]
 use alloc::boxed::Box;
+use alloc::{string::ToString, vec::Vec};
 
 use air::AuxRandElements;
 pub use air::{
-    proof, proof::Proof, Air, AirContext, Assertion, BoundaryConstraint, BoundaryConstraintGroup,
+    proof,
+    proof::{Proof, ProofExtension},
+    Air, AirContext, Assertion, BoundaryConstraint, BoundaryConstraintGroup,
     ConstraintCompositionCoefficients, ConstraintDivisor, DeepCompositionCoefficients,
-    EvaluationFrame, FieldExtension, LagrangeKernelRandElements, ProofOptions, TraceInfo,
+    EvaluationFrame, EvaluationFrameBatch, FieldExtension, LagrangeKernelRandElements,
+    ProofOptions, SelectorGroup, TraceInfo, Transcript, TranscriptEntry, TranscriptEntryKind,
     TransitionConstraintDegree,
 };
 pub use crypto;
-use crypto::{ElementHasher, RandomCoin};
+use crypto::{ElementHasher, Hasher, RandomCoin};
 use fri::FriProver;
 pub use math;
 use math::{
@@ -63,6 +75,8 @@ use math::{
 };
 use maybe_async::maybe_async;
 use tracing::{event, info_span, instrument, Level};
+#[cfg(all(feature = "concurrent", not(feature = "async")))]
+use utils::iterators::ThreadPool;
 pub use utils::{
     iterators, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
     SliceReader,
@@ -85,8 +99,9 @@ use composer::DeepCompositionPoly;
 
 mod trace;
 pub use trace::{
-    AuxTraceWithMetadata, DefaultTraceLde, Trace, TraceLde, TracePolyTable, TraceTable,
-    TraceTableFragment,
+    AuxTraceBuilder, AuxTraceWithMetadata, BuiltTrace, ConstraintViolation, DefaultTraceLde,
+    MainTraceCommitment, PreprocessedTrace, Trace, TraceBuilder, TraceLde, TracePadding,
+    TracePolyTable, TraceTable, TraceTableFragment,
 };
 
 mod channel;
@@ -95,6 +110,14 @@ use channel::ProverChannel;
 mod errors;
 pub use errors::ProverError;
 
+#[cfg(feature = "self-verify")]
+mod self_verify;
+#[cfg(feature = "self-verify")]
+pub use self_verify::SelfVerificationFailure;
+
+mod memory;
+pub use memory::MemoryEstimate;
+
 #[cfg(test)]
 pub mod tests;
 
@@ -172,6 +195,32 @@ pub trait Prover {
     /// proof size, and proof security level.
     fn options(&self) -> &ProofOptions;
 
+    /// Returns the maximum amount of memory (in bytes) which this prover may use to generate a
+    /// proof, or None if the memory is not limited.
+    ///
+    /// Before any memory-intensive work is performed, the peak memory needed to generate a proof
+    /// is estimated via [MemoryEstimate::new()]; if the estimate exceeds the budget, proof
+    /// generation fails with [ProverError::MemoryBudgetExceeded] instead of running out of memory.
+    ///
+    /// By default, the memory is not limited.
+    fn memory_budget(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns an estimate of the peak memory needed to generate a proof for the specified
+    /// execution trace.
+    ///
+    /// This can be used to schedule proof generation jobs without starting them; see
+    /// [MemoryEstimate] for details on how the estimate is computed.
+    fn estimate_memory(&self, trace: &Self::Trace) -> MemoryEstimate {
+        let air = Self::Air::new(
+            trace.info().clone(),
+            self.get_pub_inputs(trace),
+            self.options().clone(),
+        );
+        MemoryEstimate::new::<Self::BaseField, Self::HashFn>(air.context())
+    }
+
     /// Takes the main trace segment columns as input, interpolates them into polynomials in
     /// coefficient form, and evaluates the polynomials over the LDE domain.
     ///
@@ -214,6 +263,11 @@ pub trait Prover {
     }
 
     /// Builds and returns the auxiliary trace.
+    ///
+    /// The random elements drawn for the auxiliary trace segment are supplied by the prover, and
+    /// the returned segment is extended and committed to automatically. In most cases, the
+    /// segment can be described via a set of column-filling closures passed to an
+    /// [AuxTraceBuilder].
     #[allow(unused_variables)]
     async fn build_aux_trace<E>(
         &self,
@@ -226,6 +280,116 @@ pub trait Prover {
         unimplemented!("`Prover::build_aux_trace` needs to be implemented when the trace has an auxiliary segment.")
     }
 
+    /// Returns a new [TraceLde] instance built from a precomputed LDE of the main trace segment
+    /// and the commitment to it.
+    ///
+    /// This is used by [prove_with_commitments()](Prover::prove_with_commitments) method. If the
+    /// prover uses [DefaultTraceLde], this can be implemented via
+    /// [DefaultTraceLde::from_main_trace_commitment()].
+    ///
+    /// By default, this returns [ProverError::UnsupportedMainTraceCommitment]; thus, provers which
+    /// do not override this method reject proof generation from precomputed commitments.
+    #[allow(unused_variables)]
+    async fn new_trace_lde_from_commitment<E>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace_commitment: MainTraceCommitment<Self::BaseField, Self::HashFn>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> Result<Self::TraceLde<E>, ProverError>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        Err(ProverError::UnsupportedMainTraceCommitment)
+    }
+
+    /// Returns the preprocessed columns of the main trace segment for traces described by the
+    /// specified `trace_info`.
+    ///
+    /// This must return a [PreprocessedTrace] whenever the AIR of this prover declares
+    /// preprocessed columns (see [AirContext::num_preprocessed_columns()]). Since cloning a
+    /// [PreprocessedTrace] is cheap, the prover can build a preprocessed trace once (for every
+    /// supported trace length) and return its clones for every proof. If the AIR does not declare
+    /// any preprocessed columns, leave unimplemented.
+    #[allow(unused_variables)]
+    fn preprocessed_trace(
+        &self,
+        trace_info: &TraceInfo,
+    ) -> Option<PreprocessedTrace<Self::BaseField, Self::HashFn>> {
+        None
+    }
+
+    /// Takes the main trace segment columns as input, and works just like
+    /// [new_trace_lde()](Prover::new_trace_lde) except that the first columns of the main trace
+    /// segment are not interpolated, extended, or committed to; instead, their polynomials and LDE
+    /// are taken from the provided `preprocessed_trace`.
+    ///
+    /// If the prover uses [DefaultTraceLde], this can be implemented via
+    /// [DefaultTraceLde::with_preprocessed_trace()].
+    ///
+    /// By default, this returns [ProverError::InvalidPreprocessedTrace]; thus, provers which do
+    /// not override this method reject AIRs which declare preprocessed columns.
+    #[allow(unused_variables)]
+    async fn new_trace_lde_with_preprocessed_trace<E>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        preprocessed_trace: PreprocessedTrace<Self::BaseField, Self::HashFn>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> Result<(Self::TraceLde<E>, TracePolyTable<E>), ProverError>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        Err(ProverError::InvalidPreprocessedTrace(
+            "the prover does not support preprocessed columns".to_string(),
+        ))
+    }
+
+    /// Returns application-defined extensions which are to be appended to the proof.
+    ///
+    /// This method is invoked after query positions have been drawn, and receives everything
+    /// needed to open the committed data at additional positions or columns: the trace
+    /// polynomials, the trace LDE (which can be opened at any position of the LDE domain via
+    /// [TraceLde::query()]), the commitment to the evaluations of the constraint composition
+    /// polynomial, and the (sorted and deduplicated) query positions of the STARK protocol. The
+    /// verifier can recover the same query positions via `verify_with_query_positions()` function
+    /// of the verifier crate.
+    ///
+    /// The trace polynomials are provided only if
+    /// [extensions_need_trace_polys()](Prover::extensions_need_trace_polys) returns true;
+    /// otherwise, they are released before the DEEP composition polynomial is evaluated, and
+    /// `trace_polys` is None.
+    ///
+    /// The extensions are not absorbed into the public coin. Thus, openings of committed data are
+    /// bound to the proof via the commitments it contains, but any other content of the
+    /// extensions must be validated by the application.
+    ///
+    /// By default, no extensions are appended to the proof.
+    #[allow(unused_variables)]
+    fn build_proof_extensions<E>(
+        &self,
+        air: &Self::Air,
+        trace_polys: Option<&TracePolyTable<E>>,
+        trace_lde: &Self::TraceLde<E>,
+        constraint_commitment: &ConstraintCommitment<E, Self::HashFn>,
+        query_positions: &[usize],
+    ) -> Vec<ProofExtension>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        Vec::new()
+    }
+
+    /// Returns true if [build_proof_extensions()](Prover::build_proof_extensions) needs access
+    /// to the trace polynomials.
+    ///
+    /// Keeping the trace polynomials until the proof extensions are built increases peak memory
+    /// consumption of proof generation by the size of the trace polynomial table; thus, by
+    /// default, this returns false and the trace polynomials are released as soon as they have
+    /// been merged into the DEEP composition polynomial.
+    fn extensions_need_trace_polys(&self) -> bool {
+        false
+    }
+
     /// Returns a STARK proof attesting to a correct execution of a computation defined by the
     /// provided trace.
     ///
@@ -239,34 +403,265 @@ pub trait Prover {
         <Self::Air as Air>::PublicInputs: Send,
         <Self::Air as Air>::GkrProof: Send,
     {
+        self.dispatch_proof_generation(trace, &mut Vec::new(), None, None).await
+    }
+
+    /// Returns STARK proofs attesting to correct executions of a computation defined by the
+    /// provided traces.
+    ///
+    /// The proofs are identical to the ones which would be returned by calling
+    /// [prove()](Prover::prove) for every trace individually. However, STARK domains (i.e., FFT
+    /// twiddles, constraint evaluation domains, and periodic column evaluations) are built only
+    /// once for every distinct trace length and are shared by all instances of that length. This
+    /// amortizes the setup cost for use cases where many small statements need to be proven.
+    ///
+    /// No other state is shared between the proofs of a batch. In particular:
+    /// * Commitments to preprocessed columns are not cached by this method; to avoid rebuilding
+    ///   them, the prover can build a [PreprocessedTrace] once and return its clones from
+    ///   [preprocessed_trace()](Prover::preprocessed_trace).
+    /// * Proofs are generated one after another in the current rayon thread pool; to bound the
+    ///   number of threads used by the batch, this method can be invoked from within a dedicated
+    ///   pool.
+    ///
+    /// # Errors
+    /// Returns an error if proof generation fails for any of the traces.
+    async fn prove_batch(&self, traces: Vec<Self::Trace>) -> Result<Vec<Proof>, ProverError>
+    where
+        <Self::Air as Air>::PublicInputs: Send,
+        <Self::Air as Air>::GkrProof: Send,
+    {
+        let mut domains = Vec::new();
+        let mut proofs = Vec::with_capacity(traces.len());
+        for trace in traces {
+            proofs.push(self.dispatch_proof_generation(trace, &mut domains, None, None).await?);
+        }
+
+        Ok(proofs)
+    }
+
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace using a precomputed commitment to the main trace segment.
+    ///
+    /// The proof is identical to the one returned by [prove()](Prover::prove). However, instead of
+    /// extending the main trace segment and committing to the extension, the prover uses the
+    /// provided `main_trace_commitment` (see [MainTraceCommitment] for details) and proceeds
+    /// directly to building the auxiliary trace segment and evaluating constraints. This requires
+    /// [new_trace_lde_from_commitment()](Prover::new_trace_lde_from_commitment) to be implemented.
+    ///
+    /// Only the extension of the main trace segment and the Merkle tree built from it are reused:
+    /// the main trace segment is still interpolated, because the trace polynomials are needed
+    /// for the rest of the protocol and for checking the commitment against the `trace`.
+    ///
+    /// # Errors
+    /// Returns an error if the dimensions of the provided commitment are not consistent with the
+    /// computation, if spot checks of the LDE against the `trace` fail, or if the prover does not
+    /// override [new_trace_lde_from_commitment()](Prover::new_trace_lde_from_commitment).
+    async fn prove_with_commitments(
+        &self,
+        trace: Self::Trace,
+        main_trace_commitment: MainTraceCommitment<Self::BaseField, Self::HashFn>,
+    ) -> Result<Proof, ProverError>
+    where
+        <Self::Air as Air>::PublicInputs: Send,
+        <Self::Air as Air>::GkrProof: Send,
+    {
+        self.dispatch_proof_generation(trace, &mut Vec::new(), None, Some(main_trace_commitment))
+            .await
+    }
+
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace together with the transcript of the proof generation.
+    ///
+    /// The proof is identical to the one returned by [prove()](Prover::prove). The transcript
+    /// contains all commitments made by the prover and all challenges drawn from the public coin
+    /// during proof generation (see [Transcript] for details).
+    async fn prove_with_transcript(
+        &self,
+        trace: Self::Trace,
+    ) -> Result<(Proof, Transcript), ProverError>
+    where
+        <Self::Air as Air>::PublicInputs: Send,
+        <Self::Air as Air>::GkrProof: Send,
+    {
+        let mut transcript = Transcript::new();
+        let proof = self
+            .dispatch_proof_generation(trace, &mut Vec::new(), Some(&mut transcript), None)
+            .await?;
+
+        Ok((proof, transcript))
+    }
+
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, after making sure that the proof passes verification.
+    ///
+    /// This is a debugging aid for developing AIRs and custom prover components: the proof is
+    /// generated via [prove_with_transcript()](Prover::prove_with_transcript), and is then
+    /// verified against the public inputs returned by [get_pub_inputs()](Prover::get_pub_inputs)
+    /// with the transcript of the verifier being recorded as well. If the verification fails,
+    /// [ProverError::SelfVerificationFailed] is returned; the error contains both transcripts
+    /// with the first point at which the verifier disagrees with the prover highlighted (see
+    /// [SelfVerificationFailure]).
+    ///
+    /// Verification roughly doubles the cost of the proof in terms of memory, and adds the time
+    /// needed to verify the proof; thus, this method is not meant to be used in production.
+    /// Computations with preprocessed trace columns are not supported.
+    #[cfg(feature = "self-verify")]
+    async fn prove_and_verify(&self, trace: Self::Trace) -> Result<Proof, ProverError>
+    where
+        <Self::Air as Air>::PublicInputs: Send,
+        <Self::Air as Air>::GkrProof: Send,
+    {
+        let pub_inputs = self.get_pub_inputs(&trace);
+        let (proof, prover_transcript) = self.prove_with_transcript(trace).await?;
+
+        let acceptable_options =
+            verifier::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        let (result, verifier_transcript) =
+            verifier::verify_with_transcript::<Self::Air, Self::HashFn, Self::RandomCoin>(
+                proof.clone(),
+                pub_inputs,
+                &acceptable_options,
+            );
+
+        match result {
+            Ok(()) => Ok(proof),
+            Err(err) => Err(ProverError::SelfVerificationFailed(Box::new(
+                SelfVerificationFailure::new(err, prover_transcript, verifier_transcript),
+            ))),
+        }
+    }
+
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace; the proof is generated using only the threads of the specified pool.
+    ///
+    /// The proof is identical to the one returned by [prove()](Prover::prove). However, all
+    /// concurrent steps of proof generation (including FFTs, Merkle tree construction, and
+    /// constraint evaluation) are executed in the provided `pool` rather than in the global rayon
+    /// thread pool. This makes it possible to bound the number of threads used by a single
+    /// proving job, and to run several jobs side by side without oversubscribing the machine.
+    /// A pool with a given number of threads can be built as follows:
+    ///
+    /// ```ignore
+    /// let pool = iterators::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+    /// let proof = prover.prove_in_thread_pool(trace, &pool)?;
+    /// ```
+    #[cfg(all(feature = "concurrent", not(feature = "async")))]
+    fn prove_in_thread_pool(
+        &self,
+        trace: Self::Trace,
+        pool: &ThreadPool,
+    ) -> Result<Proof, ProverError>
+    where
+        Self: Sync,
+        <Self::Air as Air>::PublicInputs: Send,
+        <Self::Air as Air>::GkrProof: Send,
+    {
+        pool.install(|| self.dispatch_proof_generation(trace, &mut Vec::new(), None, None))
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Selects the extension field specified by the proof options and generates the proof that
+    /// the provided execution `trace` is valid against this prover's AIR in that field.
+    #[doc(hidden)]
+    async fn dispatch_proof_generation(
+        &self,
+        trace: Self::Trace,
+        domains: &mut Vec<StarkDomain<Self::BaseField>>,
+        transcript: Option<&mut Transcript>,
+        main_trace_commitment: Option<MainTraceCommitment<Self::BaseField, Self::HashFn>>,
+    ) -> Result<Proof, ProverError>
+    where
+        <Self::Air as Air>::PublicInputs: Send,
+        <Self::Air as Air>::GkrProof: Send,
+    {
+        // make sure the hash functions recorded in the proof options (if any) are the ones used
+        // by this prover
+        if let Some(hash_functions) = self.options().hash_functions() {
+            let expected = (Self::HashFn::ID, Self::RandomCoin::HASH_FUNCTION_ID);
+            if hash_functions != expected {
+                return Err(ProverError::MismatchedHashFunctions);
+            }
+        }
+
+        // make sure the digest truncation width recorded in the proof options (if any) is the
+        // size of the digests of the commitment hash function
+        if let Some(num_digest_bytes) = self.options().digest_truncation() {
+            let digest_size = <Self::HashFn as Hasher>::Digest::default().serialized_size();
+            if num_digest_bytes != digest_size {
+                return Err(ProverError::MismatchedDigestSize(num_digest_bytes, digest_size));
+            }
+        }
+
+        // make sure the parameters digest recorded in the proof options (if any) is the digest of
+        // the protocol configuration of this prover
+        if let Some(parameters_digest) = self.options().recorded_parameters_digest() {
+            let expected = self
+                .options()
+                .parameters_digest::<Self::BaseField, Self::HashFn, Self::RandomCoin>();
+            if parameters_digest != expected {
+                return Err(ProverError::MismatchedParametersDigest);
+            }
+        }
+
         // figure out which version of the generic proof generation procedure to run. this is a sort
         // of static dispatch for selecting two generic parameter: extension field and hash
         // function.
         match self.options().field_extension() {
-            FieldExtension::None => self.generate_proof::<Self::BaseField>(trace).await,
+            FieldExtension::None => {
+                self.generate_proof::<Self::BaseField>(
+                    trace,
+                    domains,
+                    transcript,
+                    main_trace_commitment,
+                )
+                .await
+            },
             FieldExtension::Quadratic => {
                 if !<QuadExtension<Self::BaseField>>::is_supported() {
                     return Err(ProverError::UnsupportedFieldExtension(2));
                 }
-                self.generate_proof::<QuadExtension<Self::BaseField>>(trace).await
+                self.generate_proof::<QuadExtension<Self::BaseField>>(
+                    trace,
+                    domains,
+                    transcript,
+                    main_trace_commitment,
+                )
+                .await
             },
             FieldExtension::Cubic => {
                 if !<CubeExtension<Self::BaseField>>::is_supported() {
                     return Err(ProverError::UnsupportedFieldExtension(3));
                 }
-                self.generate_proof::<CubeExtension<Self::BaseField>>(trace).await
+                self.generate_proof::<CubeExtension<Self::BaseField>>(
+                    trace,
+                    domains,
+                    transcript,
+                    main_trace_commitment,
+                )
+                .await
             },
         }
     }
 
-    // HELPER METHODS
-    // --------------------------------------------------------------------------------------------
-
     /// Performs the actual proof generation procedure, generating the proof that the provided
     /// execution `trace` is valid against this prover's AIR.
+    ///
+    /// STARK domains built during proof generation are stored in `domains`, and the domains already
+    /// present there are reused whenever they are compatible with the computation. If a
+    /// `transcript` is provided, all messages exchanged during proof generation are recorded in it.
+    /// If a `main_trace_commitment` is provided, it is used instead of extending and committing to
+    /// the main trace segment.
     /// TODO: make this function un-callable externally?
     #[doc(hidden)]
-    async fn generate_proof<E>(&self, trace: Self::Trace) -> Result<Proof, ProverError>
+    async fn generate_proof<E>(
+        &self,
+        trace: Self::Trace,
+        domains: &mut Vec<StarkDomain<Self::BaseField>>,
+        transcript: Option<&mut Transcript>,
+        main_trace_commitment: Option<MainTraceCommitment<Self::BaseField, Self::HashFn>>,
+    ) -> Result<Proof, ProverError>
     where
         E: FieldElement<BaseField = Self::BaseField>,
         <Self::Air as Air>::PublicInputs: Send,
@@ -283,27 +678,114 @@ pub trait Prover {
         // execution of the computation for the provided public inputs.
         let air = Self::Air::new(trace.info().clone(), pub_inputs, self.options().clone());
 
+        // make sure proof generation fits into the memory budget (if any) before allocating any
+        // large data structures
+        if let Some(budget) = self.memory_budget() {
+            let estimate = MemoryEstimate::new::<Self::BaseField, Self::HashFn>(air.context());
+            if estimate.total() > budget {
+                return Err(ProverError::MemoryBudgetExceeded(estimate.total(), budget));
+            }
+        }
+
+        // make sure the AIR does not exclude too many positions from querying; the verifier relies
+        // on the reported number of excluded positions, and otherwise, the security level
+        // estimates would not hold
+        let num_excluded_positions = air.num_excluded_query_positions();
+        let max_excluded_positions = air.context().max_excluded_query_positions();
+        if num_excluded_positions > max_excluded_positions {
+            return Err(ProverError::TooManyExcludedQueryPositions {
+                num_excluded: num_excluded_positions,
+                max_excluded: max_excluded_positions,
+            });
+        }
+
+        // in debug mode, also make sure the AIR does not exclude more positions than it reports;
+        // this requires a pass over the entire LDE domain, and thus, is skipped in release mode
+        #[cfg(debug_assertions)]
+        {
+            let num_actually_excluded = (0..air.lde_domain_size())
+                .filter(|&p| air.is_query_position_excluded(p))
+                .count();
+            if num_actually_excluded > num_excluded_positions {
+                return Err(ProverError::UnderreportedExcludedQueryPositions {
+                    num_excluded: num_actually_excluded,
+                    num_reported: num_excluded_positions,
+                });
+            }
+        }
+
         // create a channel which is used to simulate interaction between the prover and the
         // verifier; the channel will be used to commit to values and to draw randomness that
         // should come from the verifier.
         let mut channel = ProverChannel::<Self::Air, E, Self::HashFn, Self::RandomCoin>::new(
             &air,
             pub_inputs_elements,
+            transcript,
         );
 
         // 1 ----- Commit to the execution trace --------------------------------------------------
 
-        // build computation domain; this is used later for polynomial evaluations
+        // build computation domain (or reuse a previously built one); this is used later for
+        // polynomial evaluations
         let lde_domain_size = air.lde_domain_size();
         let trace_length = air.trace_length();
-        let domain = info_span!("build_domain", trace_length, lde_domain_size)
-            .in_scope(|| StarkDomain::new(&air));
+        let domain_idx = match domains.iter().position(|domain| domain.is_compatible_with(&air)) {
+            Some(domain_idx) => domain_idx,
+            None => {
+                let domain = info_span!("build_domain", trace_length, lde_domain_size)
+                    .in_scope(|| StarkDomain::new(&air));
+                domains.push(domain);
+                domains.len() - 1
+            },
+        };
+        let domain = &domains[domain_idx];
         assert_eq!(domain.lde_domain_size(), lde_domain_size);
         assert_eq!(domain.trace_length(), trace_length);
 
-        // commit to the main trace segment
-        let (mut trace_lde, mut trace_polys) =
-            self.commit_to_main_trace_segment(&trace, &domain, &mut channel).await;
+        // commit to the main trace segment, or use the precomputed commitment if one was provided;
+        // if the main trace segment has preprocessed columns, the commitment to these columns is
+        // merged into the transcript first
+        let num_preprocessed_columns = air.context().num_preprocessed_columns();
+        let num_main_trace_tables = air.context().num_main_trace_tables();
+        let (mut trace_lde, mut trace_polys) = match main_trace_commitment {
+            Some(_) if num_main_trace_tables > 1 => {
+                return Err(ProverError::InvalidMainTraceTables(
+                    "multiple main trace tables cannot be combined with a precomputed main trace commitment"
+                        .to_string(),
+                ));
+            },
+            Some(_) if num_preprocessed_columns > 0 => {
+                return Err(ProverError::InvalidPreprocessedTrace(
+                    "preprocessed columns cannot be combined with a precomputed main trace commitment"
+                        .to_string(),
+                ));
+            },
+            Some(main_trace_commitment) => {
+                self.commit_to_precomputed_main_trace_segment(
+                    &trace,
+                    main_trace_commitment,
+                    domain,
+                    &mut channel,
+                )
+                .await?
+            },
+            None if num_preprocessed_columns > 0 => {
+                self.commit_to_preprocessed_main_trace_segment(
+                    &trace,
+                    num_preprocessed_columns,
+                    domain,
+                    &mut channel,
+                )
+                .await?
+            },
+            None => self.commit_to_main_trace_segment(&trace, domain, &mut channel).await,
+        };
+        let num_table_commitments = trace_lde.get_main_trace_table_commitments().len();
+        if num_table_commitments != num_main_trace_tables {
+            return Err(ProverError::InvalidMainTraceTables(format!(
+                "the AIR declares {num_main_trace_tables} main trace table(s), but the trace LDE committed to {num_table_commitments}"
+            )));
+        }
 
         // build the auxiliary trace segment, and append the resulting segments to trace commitment
         // and trace polynomial table structs
@@ -325,6 +807,7 @@ pub trait Prover {
 
                 AuxRandElements::new_with_lagrange(rand_elements, lagrange_rand_elements)
             };
+            channel.record_aux_rand_elements(&aux_rand_elements);
 
             let aux_trace = self.build_aux_trace(&trace, &aux_rand_elements).await;
 
@@ -332,9 +815,14 @@ pub trait Prover {
             let aux_segment_polys = {
                 // extend the auxiliary trace segment and build a Merkle tree from the extended
                 // trace
-                let span = info_span!("commit_to_aux_trace_segment").entered();
+                let span = info_span!(
+                    "commit_to_aux_trace_segment",
+                    num_cols = aux_trace.num_cols(),
+                    trace_length
+                )
+                .entered();
                 let (aux_segment_polys, aux_segment_root) =
-                    trace_lde.set_aux_trace(&aux_trace, &domain);
+                    trace_lde.set_aux_trace(&aux_trace, domain);
 
                 // commit to the LDE of the extended auxiliary trace segment by writing the root of
                 // its Merkle tree into the channel
@@ -376,12 +864,12 @@ pub trait Prover {
         let composition_poly_trace = self
             .new_evaluator(&air, aux_rand_elements, channel.get_constraint_composition_coeffs())
             .await
-            .evaluate(&trace_lde, &domain);
+            .evaluate(&trace_lde, domain);
         assert_eq!(composition_poly_trace.num_rows(), ce_domain_size);
 
         // 3 ----- commit to constraint evaluations -----------------------------------------------
         let (constraint_commitment, composition_poly) = self
-            .commit_to_constraint_evaluations(&air, composition_poly_trace, &domain, &mut channel)
+            .commit_to_constraint_evaluations(&air, composition_poly_trace, domain, &mut channel)
             .await;
 
         // 4 ----- build DEEP composition polynomial ----------------------------------------------
@@ -414,18 +902,21 @@ pub trait Prover {
 
             // combine all trace polynomials together and merge them into the DEEP composition
             // polynomial
-            deep_composition_poly.add_trace_polys(trace_polys, ood_trace_states);
+            deep_composition_poly.add_trace_polys(&trace_polys, ood_trace_states);
 
             // merge columns of constraint composition polynomial into the DEEP composition
             // polynomial
             deep_composition_poly.add_composition_poly(composition_poly, ood_evaluations);
 
-            event!(Level::DEBUG, "degree: {}", deep_composition_poly.degree());
+            event!(Level::DEBUG, degree = deep_composition_poly.degree());
 
             drop(span);
             deep_composition_poly
         };
 
+        // release the trace polynomials unless they are needed to build proof extensions
+        let trace_polys = self.extensions_need_trace_polys().then_some(trace_polys);
+
         // make sure the degree of the DEEP composition polynomial is equal to trace polynomial
         // degree minus 1.
         assert_eq!(trace_length - 2, deep_composition_poly.degree());
@@ -433,7 +924,7 @@ pub trait Prover {
         // 5 ----- evaluate DEEP composition polynomial over LDE domain ---------------------------
         let deep_evaluations = {
             let span = info_span!("evaluate_deep_composition_poly").entered();
-            let deep_evaluations = deep_composition_poly.evaluate(&domain);
+            let deep_evaluations = deep_composition_poly.evaluate(domain);
             // we check the following condition in debug mode only because infer_degree is an
             // expensive operation
             debug_assert_eq!(trace_length - 2, infer_degree(&deep_evaluations, domain.offset()));
@@ -446,7 +937,7 @@ pub trait Prover {
         let fri_options = air.options().to_fri_options();
         let num_layers = fri_options.num_fri_layers(lde_domain_size);
         let mut fri_prover = FriProver::new(fri_options);
-        info_span!("compute_fri_layers", num_layers)
+        info_span!("compute_fri_layers", num_layers, lde_domain_size)
             .in_scope(|| fri_prover.build_layers(&mut channel, deep_evaluations));
 
         // 7 ----- determine query positions ------------------------------------------------------
@@ -461,7 +952,7 @@ pub trait Prover {
 
             // generate pseudo-random query positions
             let query_positions = channel.get_query_positions();
-            event!(Level::DEBUG, "query_positions_len: {}", query_positions.len());
+            event!(Level::DEBUG, num_query_positions = query_positions.len());
 
             drop(span);
             query_positions
@@ -469,7 +960,8 @@ pub trait Prover {
 
         // 8 ----- build proof object -------------------------------------------------------------
         let proof = {
-            let span = info_span!("build_proof_object").entered();
+            let span =
+                info_span!("build_proof_object", num_queries = query_positions.len()).entered();
             // generate FRI proof
             let fri_proof = fri_prover.build_proof(&query_positions);
 
@@ -477,6 +969,17 @@ pub trait Prover {
             // state of the trace at that position + Merkle authentication path
             let trace_queries = trace_lde.query(&query_positions);
 
+            // build application-defined proof extensions; this needs to happen before the
+            // constraint commitment is consumed by the query below
+            let extensions = self.build_proof_extensions(
+                &air,
+                trace_polys.as_ref(),
+                &trace_lde,
+                &constraint_commitment,
+                &query_positions,
+            );
+            drop(trace_polys);
+
             // query the constraint commitment at the selected positions; for each query, we need
             // just a Merkle authentication path. this is because constraint evaluations for each
             // step are merged into a single value and Merkle authentication paths contain these
@@ -490,6 +993,7 @@ pub trait Prover {
                 fri_proof,
                 query_positions.len(),
                 gkr_proof.map(|gkr_proof| gkr_proof.to_bytes()),
+                extensions,
             );
 
             drop(span);
@@ -569,14 +1073,85 @@ pub trait Prover {
         let (trace_lde, trace_polys) =
             self.new_trace_lde(trace.info(), trace.main_segment(), domain).await;
 
-        // get the commitment to the main trace segment LDE
-        let main_trace_root = trace_lde.get_main_trace_commitment();
+        // commit to the LDE of the main trace by writing the root of its Merkle tree into
+        // the channel; if the main trace is partitioned into tables, the root of each table's
+        // Merkle tree is written in the order of the tables
+        channel.commit_main_trace_tables(&trace_lde.get_main_trace_table_commitments());
+
+        (trace_lde, trace_polys)
+    }
+
+    #[doc(hidden)]
+    #[instrument(skip_all)]
+    async fn commit_to_precomputed_main_trace_segment<E>(
+        &self,
+        trace: &Self::Trace,
+        main_trace_commitment: MainTraceCommitment<Self::BaseField, Self::HashFn>,
+        domain: &StarkDomain<Self::BaseField>,
+        channel: &mut ProverChannel<Self::Air, E, Self::HashFn, Self::RandomCoin>,
+    ) -> Result<(Self::TraceLde<E>, TracePolyTable<E>), ProverError>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        // make sure the precomputed LDE is an extension of the provided trace
+        let main_trace_polys = trace.main_segment().interpolate_columns();
+        main_trace_commitment.check_consistency(&main_trace_polys, domain)?;
+
+        let trace_lde = self
+            .new_trace_lde_from_commitment(trace.info(), main_trace_commitment, domain)
+            .await?;
 
         // commit to the LDE of the main trace by writing the root of its Merkle tree into
         // the channel
-        channel.commit_trace(main_trace_root);
+        channel.commit_trace(trace_lde.get_main_trace_commitment());
 
-        (trace_lde, trace_polys)
+        Ok((trace_lde, TracePolyTable::new(main_trace_polys)))
+    }
+
+    #[doc(hidden)]
+    #[instrument(skip_all)]
+    async fn commit_to_preprocessed_main_trace_segment<E>(
+        &self,
+        trace: &Self::Trace,
+        num_preprocessed_columns: usize,
+        domain: &StarkDomain<Self::BaseField>,
+        channel: &mut ProverChannel<Self::Air, E, Self::HashFn, Self::RandomCoin>,
+    ) -> Result<(Self::TraceLde<E>, TracePolyTable<E>), ProverError>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        // make sure the preprocessed columns are the first columns of the provided trace
+        let preprocessed_trace = self.preprocessed_trace(trace.info()).ok_or_else(|| {
+            ProverError::InvalidPreprocessedTrace(
+                "the AIR declares preprocessed columns, but the prover did not provide them"
+                    .to_string(),
+            )
+        })?;
+        preprocessed_trace.check_consistency(
+            num_preprocessed_columns,
+            trace.main_segment(),
+            domain,
+        )?;
+
+        // merge the commitment to the preprocessed columns into the public coin; the verifier
+        // knows this commitment in advance, and thus, it is not sent in the proof
+        channel.commit_preprocessed_trace(preprocessed_trace.root());
+
+        // extend the rest of the main execution trace and build a Merkle tree from it
+        let (trace_lde, trace_polys) = self
+            .new_trace_lde_with_preprocessed_trace(
+                trace.info(),
+                trace.main_segment(),
+                preprocessed_trace,
+                domain,
+            )
+            .await?;
+
+        // commit to the LDE of the main trace by writing the root of its Merkle tree into
+        // the channel
+        channel.commit_trace(trace_lde.get_main_trace_commitment());
+
+        Ok((trace_lde, trace_polys))
     }
 
     #[doc(hidden)]
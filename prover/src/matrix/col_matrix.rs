@@ -10,10 +10,18 @@ use crypto::{ElementHasher, MerkleTree};
 use math::{fft, polynom, FieldElement};
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
-use utils::{batch_iter_mut, iter, iter_mut, uninit_vector};
+#[allow(deprecated)]
+use utils::uninit_vector;
+use utils::{batch_iter_mut, iter, iter_mut, UninitBuffer};
 
 use crate::StarkDomain;
 
+// CONSTANTS
+// ================================================================================================
+
+/// Number of rows and columns in a single block processed during a cache-blocked transpose.
+const TRANSPOSE_BLOCK_SIZE: usize = 16;
+
 // COLUMN-MAJOR MATRIX
 // ================================================================================================
 
@@ -57,6 +65,117 @@ impl<E: FieldElement> ColMatrix<E> {
         Self { columns }
     }
 
+    /// Returns a new [ColMatrix] with the specified number of rows and columns without
+    /// initializing the underlying memory.
+    ///
+    /// This is useful when all values of the matrix are overwritten immediately after allocation
+    /// (e.g., by FFT outputs). For large matrices, zero-initializing the memory is a full
+    /// sequential pass over what can be several gigabytes of data, and all of this work is wasted
+    /// when the values are overwritten anyway. Allocating uninitialized memory also means that
+    /// physical pages are first touched by the code which writes the actual values. Where
+    /// possible, prefer [ColMatrix::from_fn()], which also avoids zero-initialization but
+    /// guarantees that every value is written before the matrix is returned.
+    ///
+    /// # Safety
+    /// Reading values of the returned matrix before initializing them leads to undefined
+    /// behavior.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `num_cols` is zero.
+    /// * `num_rows` is smaller than or equal to 1.
+    /// * `num_rows` is not a power of two.
+    pub unsafe fn uninit(num_rows: usize, num_cols: usize) -> Self {
+        assert!(num_cols > 0, "a matrix must contain at least one column");
+        assert!(num_rows > 1, "number of rows in a matrix must be greater than one");
+        assert!(num_rows.is_power_of_two(), "number of rows in a matrix must be a power of 2");
+
+        // uninit_vector() is deprecated in favor of UninitBuffer, but the caller of this function
+        // explicitly opts into uninitialized memory
+        #[allow(deprecated)]
+        let columns = (0..num_cols).map(|_| uninit_vector(num_rows)).collect();
+        Self { columns }
+    }
+
+    /// Returns a new [ColMatrix] with the specified number of rows and columns in which the value
+    /// at column `i` and row `j` is set to `f(i, j)`.
+    ///
+    /// Columns are built directly from the values returned by `f` without zero-initializing
+    /// their memory first. When `concurrent` feature is enabled, columns are filled in multiple
+    /// threads.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `num_cols` is zero.
+    /// * `num_rows` is smaller than or equal to 1.
+    /// * `num_rows` is not a power of two.
+    pub fn from_fn<F>(num_rows: usize, num_cols: usize, f: F) -> Self
+    where
+        F: Fn(usize, usize) -> E + Send + Sync,
+    {
+        assert!(num_cols > 0, "a matrix must contain at least one column");
+        assert!(num_rows > 1, "number of rows in a matrix must be greater than one");
+        assert!(num_rows.is_power_of_two(), "number of rows in a matrix must be a power of 2");
+
+        let col_indices = (0..num_cols).collect::<Vec<_>>();
+        let columns = iter!(col_indices)
+            .map(|&col_idx| (0..num_rows).map(|row_idx| f(col_idx, row_idx)).collect())
+            .collect();
+        Self { columns }
+    }
+
+    /// Returns a new [ColMatrix] instantiated from data arranged in row-major order.
+    ///
+    /// The first `num_cols` elements of `data` are interpreted as the first row of the matrix, the
+    /// next `num_cols` elements as the second row, and so on. The data is transposed into
+    /// column-major order using a cache-blocked transpose; when `concurrent` feature is enabled,
+    /// blocks of columns are transposed in multiple threads.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `num_cols` is zero.
+    /// * The length of `data` is not divisible by `num_cols`.
+    /// * Number of rows is smaller than or equal to 1.
+    /// * Number of rows is not a power of two.
+    pub fn from_row_major(data: &[E], num_cols: usize) -> Self {
+        assert!(num_cols > 0, "a matrix must contain at least one column");
+        assert_eq!(
+            data.len() % num_cols,
+            0,
+            "data length must be divisible by {num_cols}, but was {}",
+            data.len()
+        );
+        let num_rows = data.len() / num_cols;
+        assert!(num_rows > 1, "number of rows in a matrix must be greater than one");
+        assert!(num_rows.is_power_of_two(), "number of rows in a matrix must be a power of 2");
+
+        // define a closure which transposes a block of TRANSPOSE_BLOCK_SIZE columns; within the
+        // block, rows are also processed in blocks of TRANSPOSE_BLOCK_SIZE so that both the reads
+        // from the source and the writes into the columns stay cache-local.
+        let transpose_block = |col_offset: usize| {
+            let col_end = (col_offset + TRANSPOSE_BLOCK_SIZE).min(num_cols);
+            let mut block: Vec<UninitBuffer<E>> =
+                (col_offset..col_end).map(|_| UninitBuffer::new(num_rows)).collect();
+            for row_start in (0..num_rows).step_by(TRANSPOSE_BLOCK_SIZE) {
+                let row_end = (row_start + TRANSPOSE_BLOCK_SIZE).min(num_rows);
+                for (i, column) in block.iter_mut().enumerate() {
+                    let col_idx = col_offset + i;
+                    let rows =
+                        data[row_start * num_cols..row_end * num_cols].chunks_exact(num_cols);
+                    column.extend(rows.map(|row| row[col_idx]));
+                }
+            }
+            block.into_iter().map(UninitBuffer::into_vec).collect::<Vec<_>>()
+        };
+
+        let block_offsets = (0..num_cols).step_by(TRANSPOSE_BLOCK_SIZE).collect::<Vec<_>>();
+        let blocks =
+            iter!(block_offsets).map(|&offset| transpose_block(offset)).collect::<Vec<_>>();
+        let columns = blocks.into_iter().flatten().collect();
+
+        Self { columns }
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -133,6 +252,63 @@ impl<E: FieldElement> ColMatrix<E> {
         }
     }
 
+    /// Copies values of all columns at the specified rows into consecutive slices of `target`,
+    /// such that `target` holds the rows in row-major order.
+    ///
+    /// The rows are copied in blocks of 16 rows and 16 columns so that both the reads from the
+    /// columns and the writes into `target` stay cache-local.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The length of `target` is not divisible by the number of columns in this matrix.
+    /// * Any of the copied rows is out of bounds for this matrix.
+    pub fn read_rows_into(&self, row_offset: usize, target: &mut [E]) {
+        let num_cols = self.num_cols();
+        assert_eq!(
+            target.len() % num_cols,
+            0,
+            "target length must be divisible by {num_cols}, but was {}",
+            target.len()
+        );
+        let num_rows = target.len() / num_cols;
+        for row_start in (0..num_rows).step_by(TRANSPOSE_BLOCK_SIZE) {
+            let row_end = (row_start + TRANSPOSE_BLOCK_SIZE).min(num_rows);
+            for col_start in (0..num_cols).step_by(TRANSPOSE_BLOCK_SIZE) {
+                let col_end = (col_start + TRANSPOSE_BLOCK_SIZE).min(num_cols);
+                for (i, column) in self.columns[col_start..col_end].iter().enumerate() {
+                    let values = &column[row_offset + row_start..row_offset + row_end];
+                    for (j, &value) in values.iter().enumerate() {
+                        target[(row_start + j) * num_cols + col_start + i] = value;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the rows of this matrix at the specified positions.
+    ///
+    /// For each column, values at all positions are read before moving to the next column; thus,
+    /// if the positions are sorted, each column is read sequentially. When `concurrent` feature is
+    /// enabled, the positions are split into batches which are gathered in multiple threads.
+    ///
+    /// # Panics
+    /// Panics if any of the positions is out of bounds for this matrix.
+    pub fn gather_rows(&self, positions: &[usize]) -> Vec<Vec<E>> {
+        let mut rows = vec![Vec::new(); positions.len()];
+        batch_iter_mut!(&mut rows, 16, |batch: &mut [Vec<E>], batch_offset: usize| {
+            let positions = &positions[batch_offset..batch_offset + batch.len()];
+            for row in batch.iter_mut() {
+                *row = Vec::with_capacity(self.num_cols());
+            }
+            for column in self.columns.iter() {
+                for (row, &position) in batch.iter_mut().zip(positions) {
+                    row.push(column[position]);
+                }
+            }
+        });
+        rows
+    }
+
     /// Updates a row in this matrix at the specified index to the provided data.
     ///
     /// # Panics
@@ -264,22 +440,23 @@ impl<E: FieldElement> ColMatrix<E> {
     where
         H: ElementHasher<BaseField = E::BaseField>,
     {
-        // allocate vector to store row hashes
-        let mut row_hashes = unsafe { uninit_vector::<H::Digest>(self.num_rows()) };
-
         // iterate though matrix rows, hashing each row; the hashing is done by first copying a
-        // row into row_buf to avoid heap allocations, and then by applying the hash function to
-        // the buffer.
-        batch_iter_mut!(
-            &mut row_hashes,
+        // block of rows into rows_buf (via a cache-blocked transpose, to avoid strided accesses to
+        // the columns and heap allocations), and then by applying the hash function to each row
+        // in the buffer.
+        let row_hashes = UninitBuffer::new(self.num_rows()).fill_batches(
             128, // min batch size
-            |batch: &mut [H::Digest], batch_offset: usize| {
-                let mut row_buf = vec![E::ZERO; self.num_cols()];
-                for (i, row_hash) in batch.iter_mut().enumerate() {
-                    self.read_row_into(i + batch_offset, &mut row_buf);
-                    *row_hash = H::hash_elements(&row_buf);
+            |batch, batch_offset| {
+                let num_cols = self.num_cols();
+                let batch_end = batch_offset + batch.len();
+                let mut rows_buf = vec![E::ZERO; TRANSPOSE_BLOCK_SIZE * num_cols];
+                for row_offset in (batch_offset..batch_end).step_by(TRANSPOSE_BLOCK_SIZE) {
+                    let num_rows = TRANSPOSE_BLOCK_SIZE.min(batch_end - row_offset);
+                    let rows = &mut rows_buf[..num_rows * num_cols];
+                    self.read_rows_into(row_offset, rows);
+                    batch.extend(rows.chunks_exact(num_cols).map(H::hash_elements));
                 }
-            }
+            },
         );
 
         // build Merkle tree out of hashed rows
@@ -289,6 +466,32 @@ impl<E: FieldElement> ColMatrix<E> {
     // CONVERSIONS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns the data of this matrix arranged in row-major order.
+    ///
+    /// This is the inverse of [from_row_major()](ColMatrix::from_row_major): the data is
+    /// transposed using a cache-blocked transpose, and when `concurrent` feature is enabled,
+    /// blocks of rows are transposed in multiple threads.
+    pub fn to_row_major(&self) -> Vec<E> {
+        let num_cols = self.num_cols();
+
+        // transpose blocks of TRANSPOSE_BLOCK_SIZE rows into rows_buf, and then append them to
+        // the result
+        UninitBuffer::new(self.num_rows() * num_cols).fill_batches(
+            TRANSPOSE_BLOCK_SIZE * num_cols, // min batch size
+            |batch, batch_offset| {
+                let batch_end = (batch_offset + batch.len()) / num_cols;
+                let mut rows_buf = vec![E::ZERO; TRANSPOSE_BLOCK_SIZE * num_cols];
+                for row_offset in (batch_offset / num_cols..batch_end).step_by(TRANSPOSE_BLOCK_SIZE)
+                {
+                    let num_rows = TRANSPOSE_BLOCK_SIZE.min(batch_end - row_offset);
+                    let rows = &mut rows_buf[..num_rows * num_cols];
+                    self.read_rows_into(row_offset, rows);
+                    batch.extend(rows.iter().copied());
+                }
+            },
+        )
+    }
+
     /// Returns the columns of this matrix as a list of vectors.
     ///
     /// TODO: replace this with an iterator.
@@ -14,5 +14,10 @@ pub use col_matrix::{ColMatrix, ColumnIter};
 mod segments;
 pub use segments::Segment;
 
+mod storage;
+pub use storage::MatrixStorage;
+#[cfg(feature = "mmap")]
+pub use storage::MmapBuffer;
+
 #[cfg(test)]
 mod tests;
@@ -9,9 +9,9 @@ use crypto::{ElementHasher, MerkleTree};
 use math::{fft, FieldElement, StarkField};
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
-use utils::{batch_iter_mut, flatten_vector_elements, uninit_vector};
+use utils::{flatten_vector_elements, iter, iter_mut, UninitBuffer, UninitChunk};
 
-use super::{ColMatrix, Segment};
+use super::{storage::MatrixData, ColMatrix, MatrixStorage, Segment};
 use crate::StarkDomain;
 
 // ROW-MAJOR MATRIX
@@ -31,7 +31,7 @@ use crate::StarkDomain;
 #[derive(Clone, Debug)]
 pub struct RowMatrix<E: FieldElement> {
     /// Field elements stored in the matrix.
-    data: Vec<E::BaseField>,
+    data: MatrixData<E::BaseField>,
     /// Total number of base field elements stored in a single row.
     row_width: usize,
     /// Number of field elements in a single row accessible via the [RowMatrix::row()] method. This
@@ -98,6 +98,176 @@ impl<E: FieldElement> RowMatrix<E> {
         Self::from_segments(segments, polys.num_base_cols())
     }
 
+    /// Returns a new [RowMatrix] constructed by evaluating the provided polynomials over the
+    /// specified [StarkDomain], with the data of the matrix stored as specified by `storage`.
+    ///
+    /// This works similarly to [RowMatrix::evaluate_polys_over()], but instead of evaluating all
+    /// segments upfront and transposing them into a single matrix, segments are evaluated one at
+    /// a time and each segment is written into the resulting matrix before the next one is
+    /// evaluated. Thus, at most one segment needs to be held in memory in addition to the matrix
+    /// itself, which is desirable when the matrix is stored in a memory-mapped region.
+    ///
+    /// To improve performance, polynomials are evaluated in batches specified by the `N` type
+    /// parameter. Minimum batch size is 1.
+    pub fn evaluate_polys_over_with_storage<const N: usize>(
+        polys: &ColMatrix<E>,
+        domain: &StarkDomain<E::BaseField>,
+        storage: &MatrixStorage,
+    ) -> Self {
+        if *storage == MatrixStorage::Heap {
+            return Self::evaluate_polys_over::<N>(polys, domain);
+        }
+        assert!(N > 0, "batch size N must be greater than zero");
+
+        // pre-compute offsets for each row
+        let poly_size = polys.num_rows();
+        let offsets =
+            get_evaluation_offsets::<E>(poly_size, domain.trace_to_lde_blowup(), domain.offset());
+
+        // allocate memory for the entire matrix
+        let num_segments = polys.num_base_cols().div_ceil(N);
+        let row_width = num_segments * N;
+        let mut data = MatrixData::new(offsets.len() * row_width, storage);
+
+        // evaluate polynomials one segment at a time, and copy each segment into its column
+        // range of the matrix
+        for segment_idx in 0..num_segments {
+            let segment = Segment::<E::BaseField, N>::new(
+                polys,
+                segment_idx * N,
+                &offsets,
+                domain.trace_twiddles(),
+            );
+            let col_offset = segment_idx * N;
+            let copy_row = |(row, values): (&mut [E::BaseField], &[E::BaseField; N])| {
+                row[col_offset..col_offset + N].copy_from_slice(values);
+            };
+
+            #[cfg(not(feature = "concurrent"))]
+            data.chunks_mut(row_width).zip(segment.iter()).for_each(copy_row);
+
+            #[cfg(feature = "concurrent")]
+            data.par_chunks_mut(row_width).zip(segment.par_iter()).for_each(copy_row);
+        }
+
+        RowMatrix {
+            data,
+            row_width,
+            elements_per_row: polys.num_base_cols(),
+        }
+    }
+
+    /// Returns a new [RowMatrix] constructed by evaluating the provided polynomials over the
+    /// specified [StarkDomain] together with a commitment to the rows of this matrix.
+    ///
+    /// The result is the same as evaluating the polynomials via
+    /// [RowMatrix::evaluate_polys_over_with_storage()] and then committing to the resulting matrix
+    /// via [RowMatrix::commit_to_rows()], but the two steps are pipelined: the LDE domain is
+    /// split into cosets of the polynomial domain, and the rows of one coset are hashed while the
+    /// polynomials are being evaluated over the next coset. When `concurrent` feature is enabled,
+    /// hashing and evaluation are executed at the same time, and thus, the latency of hashing is
+    /// largely hidden behind polynomial evaluation.
+    ///
+    /// To improve performance, polynomials are evaluated in batches specified by the `N` type
+    /// parameter. Minimum batch size is 1.
+    pub fn evaluate_polys_over_and_commit<H, const N: usize>(
+        polys: &ColMatrix<E>,
+        domain: &StarkDomain<E::BaseField>,
+        storage: &MatrixStorage,
+    ) -> (Self, MerkleTree<H>)
+    where
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        assert!(N > 0, "batch size N must be greater than zero");
+
+        // pre-compute offsets for each row
+        let poly_size = polys.num_rows();
+        let blowup_factor = domain.trace_to_lde_blowup();
+        let offsets = get_evaluation_offsets::<E>(poly_size, blowup_factor, domain.offset());
+        let twiddles = domain.trace_twiddles();
+
+        // allocate memory for the entire matrix and for the row hashes of each coset
+        let num_segments = polys.num_base_cols().div_ceil(N);
+        let row_width = num_segments * N;
+        let elements_per_row = polys.num_base_cols();
+        let mut data = MatrixData::new(offsets.len() * row_width, storage);
+        let mut coset_hashes = Vec::with_capacity(blowup_factor);
+
+        // allocate two sets of coset buffers: while polynomials are being evaluated into one of
+        // them, the rows of the other one are being hashed; only the padding columns of the last
+        // segment are initialized (to zeros) because all other values are overwritten by the
+        // evaluations before they are read
+        let new_buffers = || {
+            (0..num_segments)
+                .map(|segment_idx| {
+                    let num_polys = (elements_per_row - segment_idx * N).min(N);
+                    Segment::<E::BaseField, N>::new_buffer(poly_size, num_polys)
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut evaluated = new_buffers();
+        let mut pending = new_buffers();
+
+        evaluate_coset::<E, N>(polys, &offsets[..poly_size], twiddles, &mut evaluated);
+        for coset_idx in 0..blowup_factor {
+            core::mem::swap(&mut evaluated, &mut pending);
+
+            // commit to the rows of the last evaluated coset, and evaluate polynomials over the
+            // next coset (if any)
+            let next_offsets = (coset_idx + 1 < blowup_factor)
+                .then(|| &offsets[(coset_idx + 1) * poly_size..(coset_idx + 2) * poly_size]);
+
+            #[cfg(not(feature = "concurrent"))]
+            {
+                coset_hashes.push(commit_to_coset::<E, H, N>(
+                    &pending,
+                    coset_idx,
+                    blowup_factor,
+                    elements_per_row,
+                    &mut data,
+                ));
+                if let Some(offsets) = next_offsets {
+                    evaluate_coset::<E, N>(polys, offsets, twiddles, &mut evaluated);
+                }
+            }
+
+            #[cfg(feature = "concurrent")]
+            {
+                let (hashes, _) = utils::rayon::join(
+                    || {
+                        commit_to_coset::<E, H, N>(
+                            &pending,
+                            coset_idx,
+                            blowup_factor,
+                            elements_per_row,
+                            &mut data,
+                        )
+                    },
+                    || {
+                        if let Some(offsets) = next_offsets {
+                            evaluate_coset::<E, N>(polys, offsets, twiddles, &mut evaluated);
+                        }
+                    },
+                );
+                coset_hashes.push(hashes);
+            }
+        }
+
+        // the i-th row in every block of `blowup_factor` consecutive rows belongs to the coset
+        // with index permute_index(blowup_factor, i)
+        let row_hashes =
+            UninitBuffer::new(offsets.len()).fill_chunks(blowup_factor, |block_idx, hashes| {
+                for row_idx in 0..blowup_factor {
+                    let coset_idx = fft::permute_index(blowup_factor, row_idx);
+                    hashes.push(coset_hashes[coset_idx][block_idx]);
+                }
+            });
+
+        let matrix = RowMatrix { data, row_width, elements_per_row };
+        let tree = MerkleTree::new(row_hashes).expect("failed to construct trace Merkle tree");
+        (matrix, tree)
+    }
+
     /// Returns a new [RowMatrix] instantiated from the specified matrix segments.
     ///
     /// `elements_per_row` specifies how many base field elements are considered to form a single
@@ -127,7 +297,7 @@ impl<E: FieldElement> RowMatrix<E> {
 
         // flatten the result to be a simple vector of elements and return
         RowMatrix {
-            data: flatten_vector_elements(result),
+            data: flatten_vector_elements(result).into(),
             row_width,
             elements_per_row,
         }
@@ -164,6 +334,16 @@ impl<E: FieldElement> RowMatrix<E> {
         E::slice_from_base_elements(&self.data[start..start + self.elements_per_row])
     }
 
+    /// Returns the rows of this matrix at the specified positions.
+    ///
+    /// When `concurrent` feature is enabled, the rows are copied in multiple threads.
+    ///
+    /// # Panics
+    /// Panics if any of the positions is out of bounds for this matrix.
+    pub fn gather_rows(&self, positions: &[usize]) -> Vec<Vec<E>> {
+        iter!(positions).map(|&position| self.row(position).to_vec()).collect()
+    }
+
     /// Returns the data in this matrix as a slice of field elements.
     pub fn data(&self) -> &[E::BaseField] {
         &self.data
@@ -184,18 +364,14 @@ impl<E: FieldElement> RowMatrix<E> {
     where
         H: ElementHasher<BaseField = E::BaseField>,
     {
-        // allocate vector to store row hashes
-        let mut row_hashes = unsafe { uninit_vector::<H::Digest>(self.num_rows()) };
-
         // iterate though matrix rows, hashing each row
-        batch_iter_mut!(
-            &mut row_hashes,
+        let row_hashes = UninitBuffer::new(self.num_rows()).fill_batches(
             128, // min batch size
-            |batch: &mut [H::Digest], batch_offset: usize| {
-                for (i, row_hash) in batch.iter_mut().enumerate() {
-                    *row_hash = H::hash_elements(self.row(batch_offset + i));
+            |batch, batch_offset| {
+                for i in batch_offset..batch_offset + batch.len() {
+                    batch.push(H::hash_elements(self.row(i)));
                 }
-            }
+            },
         );
 
         // build Merkle tree out of hashed rows
@@ -219,17 +395,17 @@ pub fn get_evaluation_offsets<E: FieldElement>(
     let g = E::BaseField::get_root_of_unity(domain_size.ilog2());
 
     // allocate memory to hold the offsets
-    let mut offsets = unsafe { uninit_vector(domain_size) };
+    let offsets = UninitBuffer::new(domain_size);
 
     // define a closure to compute offsets for a given chunk of the result; the number of chunks
     // is defined by the blowup factor. for example, for blowup factor = 2, the number of chunks
     // will be 2, for blowup factor = 8, the number of chunks will be 8 etc.
-    let compute_offsets = |(chunk_idx, chunk): (usize, &mut [E::BaseField])| {
+    let compute_offsets = |chunk_idx: usize, chunk: &mut UninitChunk<E::BaseField>| {
         let idx = fft::permute_index(blowup_factor, chunk_idx) as u64;
         let offset = g.exp_vartime(idx.into()) * domain_offset;
         let mut factor = E::BaseField::ONE;
-        for res in chunk.iter_mut() {
-            *res = factor;
+        while !chunk.is_full() {
+            chunk.push(factor);
             factor *= offset;
         }
     };
@@ -237,14 +413,73 @@ pub fn get_evaluation_offsets<E: FieldElement>(
     // compute offsets for each chunk using either parallel or regular iterators
 
     #[cfg(not(feature = "concurrent"))]
-    offsets.chunks_mut(poly_size).enumerate().for_each(compute_offsets);
+    let offsets = offsets.fill_chunks(poly_size, compute_offsets);
 
     #[cfg(feature = "concurrent")]
-    offsets.par_chunks_mut(poly_size).enumerate().for_each(compute_offsets);
+    let offsets = offsets.par_fill_chunks(poly_size, compute_offsets);
 
     offsets
 }
 
+/// Evaluates all polynomials in the specified matrix over a single coset of the domain described
+/// by `offsets`, and writes the results into the provided segment buffers (one buffer per
+/// segment).
+fn evaluate_coset<E: FieldElement, const N: usize>(
+    polys: &ColMatrix<E>,
+    offsets: &[E::BaseField],
+    twiddles: &[E::BaseField],
+    buffers: &mut [Vec<[E::BaseField; N]>],
+) {
+    iter_mut!(buffers).enumerate().for_each(|(segment_idx, buffer)| {
+        Segment::evaluate_coset(buffer, polys, segment_idx * N, offsets, twiddles)
+    });
+}
+
+/// Copies the evaluations of polynomials over the specified coset from the segment buffers into
+/// the matrix `data`, and returns the hashes of the resulting rows.
+///
+/// Evaluations in the buffers are expected to be in bit-reversed order. Thus, the i-th evaluation
+/// in the buffers belongs to the row `permute_index(poly_size, i) * blowup_factor +
+/// permute_index(blowup_factor, coset_idx)` of the matrix; this means that every block of
+/// `blowup_factor` consecutive rows of the matrix contains exactly one row of the coset. The
+/// returned hashes are ordered by block.
+fn commit_to_coset<E, H, const N: usize>(
+    buffers: &[Vec<[E::BaseField; N]>],
+    coset_idx: usize,
+    blowup_factor: usize,
+    elements_per_row: usize,
+    data: &mut [E::BaseField],
+) -> Vec<H::Digest>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    let poly_size = buffers[0].len();
+    let row_width = buffers.len() * N;
+    let row_idx = fft::permute_index(blowup_factor, coset_idx);
+
+    let commit_row = |(block_idx, rows): (usize, &mut [E::BaseField])| {
+        let eval_idx = fft::permute_index(poly_size, block_idx);
+        let row = &mut rows[row_idx * row_width..(row_idx + 1) * row_width];
+        for (values, buffer) in row.chunks_mut(N).zip(buffers) {
+            values.copy_from_slice(&buffer[eval_idx]);
+        }
+        H::hash_elements(E::slice_from_base_elements(&row[..elements_per_row]))
+    };
+
+    #[cfg(not(feature = "concurrent"))]
+    let result = data.chunks_mut(row_width * blowup_factor).enumerate().map(commit_row).collect();
+
+    #[cfg(feature = "concurrent")]
+    let result = data
+        .par_chunks_mut(row_width * blowup_factor)
+        .enumerate()
+        .map(commit_row)
+        .collect();
+
+    result
+}
+
 /// Returns matrix segments constructed by evaluating polynomials in the specified matrix over the
 /// domain defined by twiddles and offsets.
 pub fn build_segments<E: FieldElement, const N: usize>(
@@ -283,7 +518,7 @@ fn transpose<B: StarkField, const N: usize>(mut segments: Vec<Segment<B, N>>) ->
 
     // allocate memory to hold the transposed result;
     // TODO: investigate transposing in-place
-    let mut result = unsafe { uninit_vector::<[B; N]>(result_len) };
+    let result = UninitBuffer::new(result_len);
 
     // determine number of batches in which transposition will be preformed; if `concurrent`
     // feature is not enabled, the number of batches will always be 1
@@ -291,13 +526,11 @@ fn transpose<B: StarkField, const N: usize>(mut segments: Vec<Segment<B, N>>) ->
     let rows_per_batch = num_rows / num_batches;
 
     // define a closure for transposing a given batch
-    let transpose_batch = |(batch_idx, batch): (usize, &mut [[B; N]])| {
+    let transpose_batch = |batch_idx: usize, batch: &mut UninitChunk<[B; N]>| {
         let row_offset = batch_idx * rows_per_batch;
-        for i in 0..rows_per_batch {
-            let row_idx = i + row_offset;
-            for j in 0..num_segs {
-                let v = &segments[j][row_idx];
-                batch[i * num_segs + j].copy_from_slice(v);
+        for row_idx in row_offset..row_offset + rows_per_batch {
+            for segment in segments.iter() {
+                batch.push(segment[row_idx]);
             }
         }
     };
@@ -306,13 +539,10 @@ fn transpose<B: StarkField, const N: usize>(mut segments: Vec<Segment<B, N>>) ->
     // iterator (for multi-threaded transposition)
 
     #[cfg(not(feature = "concurrent"))]
-    transpose_batch((0, &mut result));
+    let result = result.fill_chunks(result_len / num_batches, transpose_batch);
 
     #[cfg(feature = "concurrent")]
-    result
-        .par_chunks_mut(result_len / num_batches)
-        .enumerate()
-        .for_each(transpose_batch);
+    let result = result.par_fill_chunks(result_len / num_batches, transpose_batch);
 
     result
 }
@@ -9,7 +9,6 @@ use core::ops::Deref;
 use math::{fft::fft_inputs::FftInputs, FieldElement, StarkField};
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
-use utils::uninit_vector;
 
 use super::ColMatrix;
 
@@ -63,15 +62,9 @@ impl<B: StarkField, const N: usize> Segment<B, N> {
         assert_eq!(poly_size, twiddles.len() * 2);
         assert!(poly_offset < polys.num_base_cols());
 
-        // allocate memory for the segment
-        let data = if polys.num_base_cols() - poly_offset >= N {
-            // if we will fill the entire segment, we allocate uninitialized memory
-            unsafe { uninit_vector::<[B; N]>(domain_size) }
-        } else {
-            // but if some columns in the segment will remain unfilled, we allocate memory initialized
-            // to zeros to make sure we don't end up with memory with undefined values
-            vec![[B::ZERO; N]; domain_size]
-        };
+        // allocate memory for the segment; columns which will remain unfilled are set to zeros
+        let num_polys = (polys.num_base_cols() - poly_offset).min(N);
+        let data = Self::new_buffer(domain_size, num_polys);
 
         Self::new_with_buffer(data, polys, poly_offset, offsets, twiddles)
     }
@@ -169,9 +162,56 @@ impl<B: StarkField, const N: usize> Segment<B, N> {
         self.data
     }
 
+    /// Evaluates polynomials from the provided [ColMatrix] starting at the specified offset over
+    /// a single coset of the domain (described by `offsets`), and writes the results into `dest`.
+    ///
+    /// The evaluations are written into `dest` in bit-reversed order. Columns of `dest` which do
+    /// not correspond to any polynomial are left unchanged.
+    ///
+    /// # Panics
+    /// Panics if the length of `dest` or `offsets` is not equal to the polynomial size.
+    pub fn evaluate_coset<E>(
+        dest: &mut [[B; N]],
+        polys: &ColMatrix<E>,
+        poly_offset: usize,
+        offsets: &[B],
+        twiddles: &[B],
+    ) where
+        E: FieldElement<BaseField = B>,
+    {
+        let poly_size = polys.num_rows();
+        assert_eq!(dest.len(), poly_size);
+        assert_eq!(offsets.len(), poly_size);
+        assert_eq!(poly_size, twiddles.len() * 2);
+
+        let num_polys = (polys.num_base_cols() - poly_offset).min(N);
+        if num_polys == N {
+            Self::copy_polys(dest, polys, poly_offset, offsets);
+        } else {
+            Self::copy_polys_partial(dest, polys, poly_offset, num_polys, offsets);
+        }
+
+        if cfg!(feature = "concurrent") && poly_size >= MIN_CONCURRENT_SIZE {
+            #[cfg(feature = "concurrent")]
+            concurrent::split_radix_fft(dest, twiddles);
+        } else {
+            dest.fft_in_place(twiddles);
+        }
+    }
+
     // HELPER METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Allocates a buffer of `len` rows into which evaluations of `num_polys` polynomials can be
+    /// written (e.g., via [Segment::evaluate_coset()]).
+    ///
+    /// The buffer is initialized with zeros; the first `num_polys` columns are overwritten by the
+    /// evaluations, while the remaining columns stay zero.
+    pub(crate) fn new_buffer(len: usize, num_polys: usize) -> Vec<[B; N]> {
+        debug_assert!(num_polys > 0 && num_polys <= N);
+        vec![[B::ZERO; N]; len]
+    }
+
     /// Copies N polynomials starting at the specified base column offset (`poly_offset`) into the
     /// specified destination. Each polynomial coefficient is offset by the specified offset.
     fn copy_polys<E: FieldElement<BaseField = B>>(
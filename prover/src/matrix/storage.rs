@@ -0,0 +1,272 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+#[cfg(feature = "mmap")]
+pub use mmap::MmapBuffer;
+
+// MATRIX STORAGE
+// ================================================================================================
+
+/// Specifies where the data of large prover matrices (e.g., trace LDEs) is stored.
+///
+/// By default, all data is stored on the heap. When the crate is compiled with `mmap` feature
+/// enabled, data can also be stored in memory-mapped regions; pages of such regions can be
+/// evicted by the operating system and read back on demand.
+///
+/// Currently, only trace LDEs can be stored in memory-mapped regions (see
+/// [DefaultTraceLde::new_with_storage()](crate::DefaultTraceLde::new_with_storage)). The
+/// execution trace, trace polynomials, Merkle trees, and constraint evaluations are always stored
+/// on the heap, and the FFT and Merkle tree construction phases access the LDE in the same order
+/// as for heap-backed matrices. Thus, memory-mapped storage reduces peak heap usage, but it does
+/// not allow proving computations whose total memory footprint exceeds available RAM.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MatrixStorage {
+    /// Matrix data is stored on the heap.
+    #[default]
+    Heap,
+    /// Matrix data is stored in an anonymous memory mapping. Such mappings are not backed by a
+    /// file, but can still be swapped out by the operating system.
+    #[cfg(feature = "mmap")]
+    Anonymous,
+    /// Matrix data is stored in a memory-mapped temporary file created in the specified directory.
+    /// The file is removed as soon as it is mapped, and the disk space is reclaimed once the
+    /// matrix is dropped.
+    #[cfg(feature = "mmap")]
+    TempFile(std::path::PathBuf),
+}
+
+// MATRIX DATA
+// ================================================================================================
+
+/// A contiguous buffer of elements backing a matrix; the buffer is allocated according to the
+/// specified [MatrixStorage].
+#[derive(Debug, Clone)]
+pub(crate) enum MatrixData<T: Copy> {
+    Heap(Vec<T>),
+    #[cfg(feature = "mmap")]
+    Mapped(MmapBuffer<T>),
+}
+
+impl<T: Copy> MatrixData<T> {
+    /// Returns a new buffer for `len` elements allocated according to the specified storage.
+    ///
+    /// Heap buffers are initialized to the default value of `T`, while memory-mapped buffers are
+    /// initialized to zero bytes; callers are expected to overwrite all elements of the buffer.
+    ///
+    /// # Panics
+    /// Panics if a memory-mapped buffer could not be allocated.
+    pub fn new(len: usize, storage: &MatrixStorage) -> Self
+    where
+        T: Default,
+    {
+        match storage {
+            MatrixStorage::Heap => Self::Heap(vec![T::default(); len]),
+            #[cfg(feature = "mmap")]
+            MatrixStorage::Anonymous => Self::Mapped(
+                MmapBuffer::anonymous(len).expect("failed to allocate anonymous memory mapping"),
+            ),
+            #[cfg(feature = "mmap")]
+            MatrixStorage::TempFile(dir) => Self::Mapped(
+                MmapBuffer::temp_file(dir, len).expect("failed to allocate file memory mapping"),
+            ),
+        }
+    }
+}
+
+impl<T: Copy> From<Vec<T>> for MatrixData<T> {
+    fn from(data: Vec<T>) -> Self {
+        Self::Heap(data)
+    }
+}
+
+impl<T: Copy> Deref for MatrixData<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Heap(data) => data,
+            #[cfg(feature = "mmap")]
+            Self::Mapped(data) => data,
+        }
+    }
+}
+
+impl<T: Copy> DerefMut for MatrixData<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Heap(data) => data,
+            #[cfg(feature = "mmap")]
+            Self::Mapped(data) => data,
+        }
+    }
+}
+
+// MEMORY-MAPPED BUFFER
+// ================================================================================================
+
+#[cfg(feature = "mmap")]
+mod mmap {
+    use core::{
+        fmt, mem,
+        ops::{Deref, DerefMut},
+        ptr::{self, NonNull},
+        slice,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use std::{fs::OpenOptions, io, os::unix::io::AsRawFd, path::Path, process};
+
+    /// Counter used to generate unique names for temporary files backing memory mappings.
+    static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    /// A fixed-size buffer of `Copy` elements stored in a memory-mapped region.
+    ///
+    /// The region can be either anonymous or backed by a temporary file. In both cases, the
+    /// region is unmapped when the buffer is dropped.
+    pub struct MmapBuffer<T: Copy> {
+        ptr: NonNull<T>,
+        len: usize,
+    }
+
+    impl<T: Copy> MmapBuffer<T> {
+        // CONSTRUCTORS
+        // ----------------------------------------------------------------------------------------
+
+        /// Returns a new buffer for `len` elements backed by an anonymous memory mapping.
+        ///
+        /// The mapping is created without reserving swap space, and thus, the operating system
+        /// will allocate physical pages only as they are written to. The contents of the buffer
+        /// are initialized to zeros.
+        pub fn anonymous(len: usize) -> io::Result<Self> {
+            let flags = libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | map_noreserve();
+            unsafe { Self::map(len, flags, -1) }
+        }
+
+        /// Returns a new buffer for `len` elements backed by a temporary file in the specified
+        /// directory.
+        ///
+        /// The file is unlinked right after it is mapped, so it does not outlive the buffer even
+        /// if the process terminates abnormally. The contents of the buffer are initialized to
+        /// zeros.
+        pub fn temp_file(dir: &Path, len: usize) -> io::Result<Self> {
+            let file_name = format!(
+                "winter-matrix-{}-{}.bin",
+                process::id(),
+                TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+            );
+            let path = dir.join(file_name);
+            let file = OpenOptions::new().read(true).write(true).create_new(true).open(&path)?;
+            let result = file
+                .set_len(byte_len::<T>(len)? as u64)
+                .and_then(|_| unsafe { Self::map(len, libc::MAP_SHARED, file.as_raw_fd()) });
+            std::fs::remove_file(&path)?;
+            result
+        }
+
+        /// Maps a region large enough to hold `len` elements using the specified flags and file
+        /// descriptor.
+        unsafe fn map(len: usize, flags: libc::c_int, fd: libc::c_int) -> io::Result<Self> {
+            let num_bytes = byte_len::<T>(len)?;
+            if num_bytes == 0 {
+                return Ok(Self { ptr: NonNull::dangling(), len });
+            }
+
+            let ptr = libc::mmap(
+                ptr::null_mut(),
+                num_bytes,
+                libc::PROT_READ | libc::PROT_WRITE,
+                flags,
+                fd,
+                0,
+            );
+            if ptr == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self {
+                ptr: NonNull::new_unchecked(ptr as *mut T),
+                len,
+            })
+        }
+
+        // PUBLIC ACCESSORS
+        // ----------------------------------------------------------------------------------------
+
+        /// Returns the number of elements in this buffer.
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        /// Returns true if this buffer contains no elements.
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+    }
+
+    impl<T: Copy> Drop for MmapBuffer<T> {
+        fn drop(&mut self) {
+            let num_bytes = self.len * mem::size_of::<T>();
+            if num_bytes != 0 {
+                unsafe { libc::munmap(self.ptr.as_ptr() as *mut libc::c_void, num_bytes) };
+            }
+        }
+    }
+
+    impl<T: Copy> Clone for MmapBuffer<T> {
+        /// Copies the contents of this buffer into a new anonymous memory mapping.
+        fn clone(&self) -> Self {
+            let mut result =
+                Self::anonymous(self.len).expect("failed to allocate anonymous memory mapping");
+            result.copy_from_slice(self);
+            result
+        }
+    }
+
+    impl<T: Copy> Deref for MmapBuffer<T> {
+        type Target = [T];
+
+        fn deref(&self) -> &Self::Target {
+            unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+        }
+    }
+
+    impl<T: Copy> DerefMut for MmapBuffer<T> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+        }
+    }
+
+    impl<T: Copy> fmt::Debug for MmapBuffer<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("MmapBuffer").field("len", &self.len).finish()
+        }
+    }
+
+    // the buffer exclusively owns the mapped region, and thus, it is safe to send and share it
+    // across threads whenever the elements can be sent and shared.
+    unsafe impl<T: Copy + Send> Send for MmapBuffer<T> {}
+    unsafe impl<T: Copy + Sync> Sync for MmapBuffer<T> {}
+
+    // HELPER FUNCTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of bytes needed to store `len` elements of type `T`.
+    fn byte_len<T>(len: usize) -> io::Result<usize> {
+        len.checked_mul(mem::size_of::<T>())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "mapping size overflow"))
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn map_noreserve() -> libc::c_int {
+        libc::MAP_NORESERVE
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn map_noreserve() -> libc::c_int {
+        0
+    }
+}
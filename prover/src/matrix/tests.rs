@@ -41,6 +41,135 @@ fn test_eval_poly_with_offset_matrix() {
     }
 }
 
+#[test]
+fn test_col_matrix_from_row_major() {
+    // use dimensions which are not multiples of the transpose block size
+    for (num_rows, num_cols) in [(2, 1), (64, 5), (128, 37)] {
+        let data: Vec<BaseElement> = rand_vector(num_rows * num_cols);
+        let matrix = ColMatrix::from_row_major(&data, num_cols);
+
+        assert_eq!(num_rows, matrix.num_rows());
+        assert_eq!(num_cols, matrix.num_cols());
+        for (row_idx, row) in data.chunks(num_cols).enumerate() {
+            for (col_idx, &value) in row.iter().enumerate() {
+                assert_eq!(value, matrix.get(col_idx, row_idx));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_col_matrix_to_row_major() {
+    for (num_rows, num_cols) in [(2, 1), (64, 5), (128, 37)] {
+        let data: Vec<BaseElement> = rand_vector(num_rows * num_cols);
+        let matrix = ColMatrix::from_row_major(&data, num_cols);
+        assert_eq!(data, matrix.to_row_major());
+    }
+}
+
+#[test]
+fn test_gather_rows() {
+    let num_rows = 128;
+    let columns: Vec<Vec<BaseElement>> = (0..37).map(|_| rand_vector(num_rows)).collect();
+    let col_matrix = ColMatrix::new(columns.clone());
+    let row_matrix = RowMatrix::evaluate_polys::<8>(&col_matrix, 2);
+
+    let positions = [0, 3, 3, 17, 64, 127];
+    let expected = positions.iter().map(|&pos| get_row(&columns, pos)).collect::<Vec<_>>();
+    assert_eq!(expected, col_matrix.gather_rows(&positions));
+
+    let expected = positions.iter().map(|&pos| row_matrix.row(pos).to_vec()).collect::<Vec<_>>();
+    assert_eq!(expected, row_matrix.gather_rows(&positions));
+}
+
+#[test]
+fn test_col_matrix_from_fn() {
+    let (num_rows, num_cols) = (64, 5);
+    let matrix =
+        ColMatrix::from_fn(num_rows, num_cols, |i, j| BaseElement::new((i * num_rows + j) as u64));
+
+    assert_eq!(num_rows, matrix.num_rows());
+    assert_eq!(num_cols, matrix.num_cols());
+    for col_idx in 0..num_cols {
+        for row_idx in 0..num_rows {
+            let expected = BaseElement::new((col_idx * num_rows + row_idx) as u64);
+            assert_eq!(expected, matrix.get(col_idx, row_idx));
+        }
+    }
+}
+
+#[test]
+fn test_eval_polys_and_commit() {
+    use crypto::hashers::Blake3_256;
+
+    use crate::{math::fields::QuadExtension, matrix::MatrixStorage, StarkDomain};
+
+    // polynomials are large enough to be evaluated concurrently when `concurrent` feature is
+    // enabled
+    let n = 1024;
+    let blowup_factor = 4;
+    let twiddles = crate::math::fft::get_twiddles::<BaseElement>(n);
+    let domain = StarkDomain::from_twiddles(twiddles, blowup_factor, BaseElement::GENERATOR);
+
+    // use numbers of columns which do and do not fill the last segment entirely
+    for num_polys in [1, 8, 13] {
+        let polys: ColMatrix<BaseElement> =
+            ColMatrix::new((0..num_polys).map(|_| rand_vector(n)).collect());
+
+        let expected = RowMatrix::evaluate_polys_over::<8>(&polys, &domain);
+        let expected_tree = expected.commit_to_rows::<Blake3_256<BaseElement>>();
+        let (matrix, tree) = RowMatrix::evaluate_polys_over_and_commit::<Blake3_256<_>, 8>(
+            &polys,
+            &domain,
+            &MatrixStorage::Heap,
+        );
+        assert_eq!(expected.data(), matrix.data());
+        assert_eq!(expected_tree.root(), tree.root());
+    }
+
+    // polynomials in an extension field are decomposed into base field columns
+    let polys: ColMatrix<QuadExtension<BaseElement>> =
+        ColMatrix::new((0..5).map(|_| rand_vector(n)).collect());
+    let expected = RowMatrix::evaluate_polys_over::<8>(&polys, &domain);
+    let expected_tree = expected.commit_to_rows::<Blake3_256<BaseElement>>();
+    let (matrix, tree) = RowMatrix::evaluate_polys_over_and_commit::<Blake3_256<_>, 8>(
+        &polys,
+        &domain,
+        &MatrixStorage::Heap,
+    );
+    assert_eq!(expected.data(), matrix.data());
+    assert_eq!(expected_tree.root(), tree.root());
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_eval_polys_with_mapped_storage() {
+    use crate::{matrix::MatrixStorage, StarkDomain};
+
+    let n = 256;
+    let num_polys = 13;
+    let blowup_factor = 8;
+
+    let polys: ColMatrix<BaseElement> =
+        ColMatrix::new((0..num_polys).map(|_| rand_vector(n)).collect());
+    let twiddles = crate::math::fft::get_twiddles::<BaseElement>(n);
+    let domain = StarkDomain::from_twiddles(twiddles, blowup_factor, BaseElement::GENERATOR);
+
+    // evaluate polynomials into a heap-allocated matrix
+    let expected = RowMatrix::evaluate_polys_over::<8>(&polys, &domain);
+
+    // evaluate the same polynomials into memory-mapped matrices and compare the results
+    let storages = [MatrixStorage::Anonymous, MatrixStorage::TempFile(std::env::temp_dir())];
+    for storage in storages {
+        let matrix = RowMatrix::evaluate_polys_over_with_storage::<8>(&polys, &domain, &storage);
+        assert_eq!(expected.num_rows(), matrix.num_rows());
+        assert_eq!(expected.num_cols(), matrix.num_cols());
+        for row in 0..n * blowup_factor {
+            assert_eq!(expected.row(row), matrix.row(row));
+        }
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
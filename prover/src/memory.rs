@@ -0,0 +1,199 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::{fmt, mem};
+
+use air::AirContext;
+use crypto::Hasher;
+use math::StarkField;
+
+// MEMORY ESTIMATE
+// ================================================================================================
+
+/// An estimate of the peak amount of memory (in bytes) needed to generate a proof.
+///
+/// The estimate is computed from the dimensions of the execution trace, the blowup factor, the
+/// field extension, and the size of digests of the commitment hash function (see
+/// [MemoryEstimate::new()]). It accounts for the data structures allocated by the default prover
+/// components ([DefaultTraceLde](crate::DefaultTraceLde) and
+/// [DefaultConstraintEvaluator](crate::DefaultConstraintEvaluator)), and assumes that all of them
+/// are alive at the same time; thus, it is an upper bound on the memory used by these
+/// components. Memory allocated by custom components, by the execution trace builder, or as
+/// allocator overhead is not included.
+///
+/// All values are computed as u64 (saturating at u64::MAX) so that the estimate does not depend
+/// on the pointer width of the target; thus, on 32-bit targets, an estimate for a computation
+/// which does not fit into the address space exceeds any memory budget instead of overflowing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// Memory needed for the execution trace, the trace polynomials, the low-degree extensions
+    /// of the trace segments, and the Merkle trees built over these extensions.
+    pub trace: u64,
+    /// Memory needed for the constraint evaluations, the constraint composition polynomial, its
+    /// low-degree extension, and the Merkle tree built over this extension.
+    pub constraints: u64,
+    /// Memory needed for the evaluations of the DEEP composition polynomial and for the FRI
+    /// layers built from them.
+    pub fri: u64,
+    /// Memory needed for the STARK domain (i.e., twiddles and the constraint evaluation domain).
+    pub domain: u64,
+}
+
+impl MemoryEstimate {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an estimate of the peak memory needed to generate a proof for the computation
+    /// described by the provided AIR context, with commitments computed using the hash function
+    /// `H`.
+    pub fn new<B: StarkField, H: Hasher>(context: &AirContext<B>) -> Self {
+        let options = context.options();
+        let trace_info = context.trace_info();
+        let trace_length = context.trace_len();
+        let lde_domain_size = context.lde_domain_size();
+        let ce_domain_size = context.ce_domain_size();
+
+        let base_bytes = B::ELEMENT_BYTES;
+        let ext_bytes = base_bytes * options.field_extension().degree() as usize;
+        let column_bytes = |num_rows: usize, width: usize, element_bytes: usize| -> u64 {
+            (num_rows as u64)
+                .saturating_mul(width as u64)
+                .saturating_mul(element_bytes as u64)
+        };
+        let tree_bytes =
+            |num_leaves: usize| column_bytes(num_leaves, 2, mem::size_of::<H::Digest>());
+
+        // the main trace segment consists of base field elements, while the auxiliary trace
+        // segment consists of extension field elements; for each segment, the trace and its
+        // polynomials take up the same amount of memory
+        let segment_bytes = |width: usize, element_bytes: usize| {
+            if width == 0 {
+                return 0;
+            }
+            column_bytes(trace_length, 2 * width, element_bytes)
+                .saturating_add(column_bytes(lde_domain_size, width, element_bytes))
+                .saturating_add(tree_bytes(lde_domain_size))
+        };
+        let trace = segment_bytes(trace_info.main_trace_width(), base_bytes)
+            .saturating_add(segment_bytes(trace_info.aux_segment_width(), ext_bytes));
+
+        // constraint evaluations are combined into a single column over the constraint evaluation
+        // domain, which is then interpolated and split into composition polynomial columns
+        let num_composition_columns = context.num_constraint_composition_columns();
+        let constraints = column_bytes(ce_domain_size, 2, ext_bytes)
+            .saturating_add(column_bytes(lde_domain_size, num_composition_columns, ext_bytes))
+            .saturating_add(tree_bytes(lde_domain_size));
+
+        // the DEEP composition polynomial is evaluated over the LDE domain, and each FRI layer
+        // keeps the evaluations of the folded polynomial together with a Merkle tree built over
+        // groups of these evaluations
+        let mut fri = column_bytes(lde_domain_size, 1, ext_bytes);
+        let mut layer_domain_size = lde_domain_size;
+        for folding_factor in options.to_fri_options().folding_factors(lde_domain_size) {
+            fri = fri
+                .saturating_add(column_bytes(layer_domain_size, 1, ext_bytes))
+                .saturating_add(tree_bytes(layer_domain_size / folding_factor));
+            layer_domain_size /= folding_factor;
+        }
+
+        let domain = column_bytes(trace_length / 2 + ce_domain_size, 1, base_bytes);
+
+        Self { trace, constraints, fri, domain }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the total estimated amount of memory (in bytes).
+    ///
+    /// The total saturates at u64::MAX.
+    pub fn total(&self) -> u64 {
+        self.trace
+            .saturating_add(self.constraints)
+            .saturating_add(self.fri)
+            .saturating_add(self.domain)
+    }
+}
+
+impl fmt::Display for MemoryEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} bytes (trace: {}, constraints: {}, fri: {}, domain: {})",
+            self.total(),
+            self.trace,
+            self.constraints,
+            self.fri,
+            self.domain
+        )
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use air::{AirContext, FieldExtension, ProofOptions, TraceInfo, TransitionConstraintDegree};
+    use crypto::hashers::Blake3_256;
+    use math::fields::f64::BaseElement;
+
+    use super::MemoryEstimate;
+
+    type Hasher = Blake3_256<BaseElement>;
+
+    fn build_context(
+        trace_length: usize,
+        blowup_factor: usize,
+        field_extension: FieldExtension,
+    ) -> AirContext<BaseElement> {
+        let options = ProofOptions::new(28, blowup_factor, 0, field_extension, 4, 7);
+        let degrees = vec![TransitionConstraintDegree::new(2)];
+        AirContext::new(TraceInfo::new(4, trace_length), degrees, 1, options)
+    }
+
+    #[test]
+    fn memory_estimate() {
+        let estimate =
+            MemoryEstimate::new::<_, Hasher>(&build_context(1024, 8, FieldExtension::None));
+
+        // 4 columns of 8-byte elements: trace and polynomials over 1024 rows, LDE over 8192 rows,
+        // and a Merkle tree with 8192 leaves of 32-byte digests
+        assert_eq!(2 * 1024 * 4 * 8 + 8192 * 4 * 8 + 2 * 8192 * 32, estimate.trace);
+        assert_eq!(
+            estimate.trace + estimate.constraints + estimate.fri + estimate.domain,
+            estimate.total()
+        );
+
+        // the estimate grows with the trace length, the blowup factor, and the field extension
+        let longer =
+            MemoryEstimate::new::<_, Hasher>(&build_context(2048, 8, FieldExtension::None));
+        assert!(longer.total() > estimate.total());
+        let wider =
+            MemoryEstimate::new::<_, Hasher>(&build_context(1024, 16, FieldExtension::None));
+        assert!(wider.total() > estimate.total());
+        let extended =
+            MemoryEstimate::new::<_, Hasher>(&build_context(1024, 8, FieldExtension::Quadratic));
+        assert_eq!(estimate.trace, extended.trace);
+        assert!(extended.fri > estimate.fri);
+    }
+
+    #[test]
+    fn memory_estimate_oversized_context() {
+        // 255 columns over an LDE domain of 2^32 rows do not fit into 32-bit address space; the
+        // estimate must still be exact
+        let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 7);
+        let degrees = vec![TransitionConstraintDegree::new(2)];
+        let context =
+            AirContext::<BaseElement>::new(TraceInfo::new(255, 1 << 29), degrees, 1, options);
+
+        let estimate = MemoryEstimate::new::<_, Hasher>(&context);
+        assert_eq!(
+            2 * (1 << 29) * 255 * 8 + (1 << 32) * 255 * 8 + 2 * (1 << 32) * 32,
+            estimate.trace
+        );
+        assert!(estimate.total() > u32::MAX as u64);
+    }
+}
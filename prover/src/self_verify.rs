@@ -0,0 +1,235 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::fmt;
+
+use air::{Transcript, TranscriptEntry};
+use verifier::VerifierError;
+
+// SELF-VERIFICATION FAILURE
+// ================================================================================================
+
+/// Describes a proof which was generated via
+/// [Prover::prove_and_verify()](crate::Prover::prove_and_verify) but failed verification.
+///
+/// The failure contains the error returned by the verifier together with the transcripts
+/// recorded by the prover and by the verifier. Its [Display](fmt::Display) implementation dumps
+/// both transcripts entry by entry: entries on which the prover and the verifier agree are printed
+/// once, entries on which they disagree are printed for both sides, and the first divergence is
+/// marked with `>>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfVerificationFailure {
+    error: VerifierError,
+    prover_transcript: Transcript,
+    verifier_transcript: Transcript,
+}
+
+impl SelfVerificationFailure {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new failure for the specified verifier error and transcripts.
+    pub(crate) fn new(
+        error: VerifierError,
+        prover_transcript: Transcript,
+        verifier_transcript: Transcript,
+    ) -> Self {
+        Self {
+            error,
+            prover_transcript,
+            verifier_transcript,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the error returned by the verifier.
+    pub fn error(&self) -> &VerifierError {
+        &self.error
+    }
+
+    /// Returns the transcript recorded by the prover during proof generation.
+    pub fn prover_transcript(&self) -> &Transcript {
+        &self.prover_transcript
+    }
+
+    /// Returns the transcript recorded by the verifier up to the point of failure.
+    pub fn verifier_transcript(&self) -> &Transcript {
+        &self.verifier_transcript
+    }
+
+    /// Returns the index of the first transcript entry on which the prover and the verifier
+    /// disagree, or None if the transcripts are identical.
+    ///
+    /// If the verifier agreed with all messages it processed before failing, this is the number
+    /// of entries in the verifier transcript.
+    pub fn first_divergence(&self) -> Option<usize> {
+        self.prover_transcript.first_divergence(&self.verifier_transcript)
+    }
+}
+
+impl fmt::Display for SelfVerificationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prover_entries = self.prover_transcript.entries();
+        let verifier_entries = self.verifier_transcript.entries();
+        let first_divergence = self.first_divergence();
+
+        writeln!(f, "verification of the generated proof failed: {}", self.error)?;
+        match first_divergence {
+            Some(idx) => writeln!(f, "transcripts diverge at entry {idx}:")?,
+            None => writeln!(f, "transcripts are identical:")?,
+        }
+
+        for idx in 0..prover_entries.len().max(verifier_entries.len()) {
+            let marker = if first_divergence == Some(idx) { ">>" } else { "  " };
+            let prover_entry = prover_entries.get(idx);
+            let verifier_entry = verifier_entries.get(idx);
+            if prover_entry == verifier_entry {
+                writeln!(f, "{marker}{idx:>4} {}", MaybeEntry(prover_entry))?;
+            } else {
+                writeln!(f, "{marker}{idx:>4} prover:   {}", MaybeEntry(prover_entry))?;
+                writeln!(f, "{marker}     verifier: {}", MaybeEntry(verifier_entry))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Displays a transcript entry which may be missing from one of the transcripts.
+struct MaybeEntry<'a>(Option<&'a TranscriptEntry>);
+
+impl fmt::Display for MaybeEntry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(entry) => write!(f, "{entry}"),
+            None => write!(f, "<none>"),
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(all(test, not(feature = "async")))]
+mod tests {
+    use alloc::string::ToString;
+
+    use air::{AuxRandElements, ConstraintCompositionCoefficients, ProofOptions, TraceInfo};
+    use crypto::DefaultRandomCoin;
+    use math::{fields::f128::BaseElement, FieldElement};
+    use verifier::VerifierError;
+
+    use crate::{
+        matrix::ColMatrix,
+        tests::fib::{build_proof_options, Blake3, FibAir, FibProver},
+        CompositionPolyTrace, ConstraintEvaluator, DefaultConstraintEvaluator, DefaultTraceLde,
+        Prover, ProverError, StarkDomain, TraceLde, TracePolyTable, TraceTable,
+    };
+
+    #[test]
+    fn prove_and_verify() {
+        let prover = FibProver::<Blake3>::new(build_proof_options(false));
+
+        // a valid proof is returned as is
+        let proof = prover.prove_and_verify(prover.build_trace(16)).unwrap();
+        assert_eq!(proof, prover.prove(prover.build_trace(16)).unwrap());
+
+        // a proof with inconsistent constraint evaluations fails the out-of-domain consistency
+        // check; the verifier agrees with all messages up to this check, and stops there
+        let prover = ShiftedEvaluatorProver(prover);
+        let err = prover.prove_and_verify(prover.0.build_trace(16)).unwrap_err();
+        let failure = match err {
+            ProverError::SelfVerificationFailed(failure) => failure,
+            err => panic!("unexpected error: {err}"),
+        };
+        assert_eq!(failure.error(), &VerifierError::InconsistentOodConstraintEvaluations);
+
+        let verifier_transcript = failure.verifier_transcript();
+        assert_eq!(
+            verifier_transcript.entries().last().map(|entry| entry.label()),
+            Some("ood_constraint_evaluations")
+        );
+        assert_eq!(failure.first_divergence(), Some(verifier_transcript.len()));
+        assert!(failure.prover_transcript().len() > verifier_transcript.len());
+        assert_eq!(
+            &failure.prover_transcript().entries()[..verifier_transcript.len()],
+            verifier_transcript.entries()
+        );
+
+        // the dump marks the first divergence
+        let dump = failure.to_string();
+        let marked = dump.lines().find(|line| line.starts_with(">>")).unwrap();
+        assert!(marked.contains("deep_trace_coeffs"));
+    }
+
+    /// Constraint evaluator which shifts all constraint evaluations by ONE; this is consistent with
+    /// the degree bounds of the AIR, but not with the evaluations computed by the verifier.
+    struct ShiftedConstraintEvaluator<'a, E: FieldElement<BaseField = BaseElement>>(
+        DefaultConstraintEvaluator<'a, FibAir, E>,
+    );
+
+    impl<E: FieldElement<BaseField = BaseElement>> ConstraintEvaluator<E>
+        for ShiftedConstraintEvaluator<'_, E>
+    {
+        type Air = FibAir;
+
+        fn evaluate<T: TraceLde<E>>(
+            self,
+            trace: &T,
+            domain: &StarkDomain<BaseElement>,
+        ) -> CompositionPolyTrace<E> {
+            let evaluations = self.0.evaluate(trace, domain).into_inner();
+            CompositionPolyTrace::new(evaluations.into_iter().map(|value| value + E::ONE).collect())
+        }
+    }
+
+    struct ShiftedEvaluatorProver(FibProver<Blake3>);
+
+    impl Prover for ShiftedEvaluatorProver {
+        type BaseField = BaseElement;
+        type Air = FibAir;
+        type Trace = TraceTable<BaseElement>;
+        type HashFn = Blake3;
+        type RandomCoin = DefaultRandomCoin<Blake3>;
+        type TraceLde<E: FieldElement<BaseField = Self::BaseField>> =
+            DefaultTraceLde<E, Self::HashFn>;
+        type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+            ShiftedConstraintEvaluator<'a, E>;
+
+        fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+            self.0.get_pub_inputs(trace)
+        }
+
+        fn options(&self) -> &ProofOptions {
+            self.0.options()
+        }
+
+        fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+            &self,
+            trace_info: &TraceInfo,
+            main_trace: &ColMatrix<Self::BaseField>,
+            domain: &StarkDomain<Self::BaseField>,
+        ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+            DefaultTraceLde::new(trace_info, main_trace, domain)
+        }
+
+        fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+            &self,
+            air: &'a Self::Air,
+            aux_rand_elements: Option<AuxRandElements<E>>,
+            composition_coefficients: ConstraintCompositionCoefficients<E>,
+        ) -> Self::ConstraintEvaluator<'a, E> {
+            ShiftedConstraintEvaluator(DefaultConstraintEvaluator::new(
+                air,
+                aux_rand_elements,
+                composition_coefficients,
+            ))
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Fibonacci computation shared by the end-to-end tests of the prover.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{
+    matrix::ColMatrix, DefaultConstraintEvaluator, DefaultTraceLde, MainTraceCommitment, Prover,
+    ProverError, StarkDomain, Trace, TracePolyTable, TraceTable,
+};
+use air::{
+    Air, AirContext, AirWithOutputs, Assertion, AuxRandElements, ConstraintCompositionCoefficients,
+    EvaluationFrame, FieldExtension, ProofOptions, TraceInfo, TransitionConstraintDegree,
+};
+use crypto::{hashers::Blake3_256, DefaultRandomCoin, ElementHasher, RandomCoin};
+use math::{fields::f128::BaseElement, FieldElement};
+
+pub type Blake3 = Blake3_256<BaseElement>;
+
+// FIBONACCI AIR
+// ================================================================================================
+
+/// AIR of a Fibonacci sequence computed in a trace of 2 columns, such that each row advances the
+/// sequence by 2 terms; the public input is the last term of the sequence.
+pub struct FibAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+    type GkrProof = ();
+    type GkrVerifier = ();
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        FibAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, Self::BaseField::ONE),
+            Assertion::single(1, 0, Self::BaseField::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+impl AirWithOutputs for FibAir {
+    type PublicOutputs = BaseElement;
+
+    fn public_output_cells(&self) -> Vec<(usize, usize)> {
+        vec![(1, self.trace_length() - 1)]
+    }
+
+    fn build_public_outputs(&self, values: Vec<Self::BaseField>) -> Self::PublicOutputs {
+        values[0]
+    }
+}
+
+// FIBONACCI PROVER
+// ================================================================================================
+
+pub struct FibProver<H, R = DefaultRandomCoin<H>> {
+    options: ProofOptions,
+    _hasher: PhantomData<(H, R)>,
+}
+
+impl<H, R> FibProver<H, R> {
+    pub fn new(options: ProofOptions) -> Self {
+        Self { options, _hasher: PhantomData }
+    }
+
+    /// Builds an execution trace for computing a Fibonacci sequence of the specified length.
+    pub fn build_trace(&self, sequence_length: usize) -> TraceTable<BaseElement> {
+        assert!(sequence_length.is_power_of_two(), "sequence length must be a power of 2");
+
+        let mut trace = TraceTable::new(2, sequence_length / 2);
+        trace.fill(
+            |state| {
+                state[0] = BaseElement::ONE;
+                state[1] = BaseElement::ONE;
+            },
+            |_, state| {
+                state[0] += state[1];
+                state[1] += state[0];
+            },
+        );
+        trace
+    }
+}
+
+impl<H, R> Prover for FibProver<H, R>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+    R: RandomCoin<BaseField = BaseElement, Hasher = H> + Send,
+{
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = H;
+    type RandomCoin = R;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        trace.get(1, trace.length() - 1)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+
+    fn new_trace_lde_from_commitment<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace_commitment: MainTraceCommitment<Self::BaseField, Self::HashFn>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> Result<Self::TraceLde<E>, ProverError> {
+        Ok(DefaultTraceLde::from_main_trace_commitment(
+            trace_info,
+            main_trace_commitment,
+            domain,
+        ))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the n-th term of the Fibonacci sequence starting with two ones.
+pub fn compute_fib_term<E: FieldElement>(n: usize) -> E {
+    let mut t0 = E::ONE;
+    let mut t1 = E::ONE;
+    for _ in 0..(n - 1) {
+        t1 = t0 + t1;
+        core::mem::swap(&mut t0, &mut t1);
+    }
+    t1
+}
+
+/// Returns proof options with 28 queries and blowup factor of 8, optionally using a quadratic
+/// extension field.
+pub fn build_proof_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, extension, 4, 7)
+}
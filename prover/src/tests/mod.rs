@@ -13,6 +13,18 @@ use math::{fields::f128::BaseElement, FieldElement, StarkField};
 
 use crate::TraceTable;
 
+#[cfg(not(feature = "async"))]
+pub mod fib;
+
+#[cfg(not(feature = "async"))]
+mod preprocessed;
+
+#[cfg(not(feature = "async"))]
+mod proofs;
+
+#[cfg(not(feature = "async"))]
+mod trace_tables;
+
 // FIBONACCI TRACE BUILDER
 // ================================================================================================
 
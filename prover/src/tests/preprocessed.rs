@@ -0,0 +1,219 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use air::{
+    Air, AirContext, Assertion, AuxRandElements, ConstraintCompositionCoefficients,
+    EvaluationFrame, ProofOptions, TraceInfo, TransitionConstraintDegree,
+};
+use crypto::DefaultRandomCoin;
+use math::{fields::f128::BaseElement, FieldElement};
+use verifier::{AcceptableOptions, VerifierError};
+
+use super::fib::{build_proof_options, Blake3};
+use crate::{
+    matrix::ColMatrix, proof::Proof, DefaultConstraintEvaluator, DefaultTraceLde,
+    PreprocessedTrace, Prover, ProverError, StarkDomain, Trace, TracePolyTable, TraceTable,
+};
+
+type Coin = DefaultRandomCoin<Blake3>;
+
+#[test]
+fn proof_with_preprocessed_trace() {
+    let options = build_proof_options(false);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options.clone()]);
+
+    let trace = build_counter_fib_trace(64, 0);
+    let result = trace.get(2, trace.length() - 1);
+    let air = CounterFibAir::new(trace.info().clone(), result, options.clone());
+    let domain = StarkDomain::new(&air);
+    let preprocessed_trace = build_preprocessed_trace(&trace, &domain);
+    let commitment = preprocessed_trace.root();
+
+    // the proof verifies against the commitment to the preprocessed columns
+    let prover = CounterFibProver {
+        options: options.clone(),
+        preprocessed_trace,
+    };
+    let proof = prover.prove(trace).unwrap();
+    assert_eq!(proof, Proof::from_bytes(&proof.to_bytes()).unwrap());
+    let compressed_proof = proof.compress::<Blake3>().unwrap();
+    assert_eq!(proof, Proof::decompress::<Blake3, BaseElement>(&compressed_proof).unwrap());
+    let verification = verifier::verify_with_preprocessed_trace::<CounterFibAir, Blake3, Coin>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+        commitment,
+    );
+    assert!(verification.is_ok());
+
+    // the verifier must be provided with the commitment to the preprocessed columns
+    let verification =
+        verifier::verify::<CounterFibAir, Blake3, Coin>(proof, result, &acceptable_options);
+    assert_eq!(Err(VerifierError::InvalidPreprocessedTraceCommitment), verification);
+
+    // the prover rejects preprocessed columns which are not the columns of the trace
+    let other_trace = build_counter_fib_trace(64, 1);
+    let other_result = other_trace.get(2, other_trace.length() - 1);
+    let proof = prover.prove(build_counter_fib_trace(64, 1));
+    assert!(matches!(proof, Err(ProverError::InvalidPreprocessedTrace(_))));
+
+    // a proof built from different preprocessed columns does not verify against the commitment
+    let prover = CounterFibProver {
+        options,
+        preprocessed_trace: build_preprocessed_trace(&other_trace, &domain),
+    };
+    let proof = prover.prove(other_trace).unwrap();
+    let verification = verifier::verify_with_preprocessed_trace::<CounterFibAir, Blake3, Coin>(
+        proof,
+        other_result,
+        &acceptable_options,
+        commitment,
+    );
+    assert!(verification.is_err());
+}
+
+// FIBONACCI AIR WITH PRECOMPUTED COUNTER
+// ================================================================================================
+
+/// A variant of the Fibonacci computation in which a step counter is added to every pair of terms.
+/// The counter is kept in the first column of the trace, which is a preprocessed column: its
+/// values are not constrained by the AIR, but are instead committed to ahead of time.
+struct CounterFibAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for CounterFibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+    type GkrProof = ();
+    type GkrVerifier = ();
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        let context =
+            AirContext::new(trace_info, degrees, 3, options).set_num_preprocessed_columns(1);
+        CounterFibAir { context, result: pub_inputs }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[1] - (current[1] + current[2] + current[0]);
+        result[1] = next[2] - (current[2] + next[1]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(1, 0, Self::BaseField::ONE),
+            Assertion::single(2, 0, Self::BaseField::ONE),
+            Assertion::single(2, last_step, self.result),
+        ]
+    }
+}
+
+/// Builds a trace of the specified length for [CounterFibAir] with the counter starting at
+/// `counter_start`.
+fn build_counter_fib_trace(length: usize, counter_start: u64) -> TraceTable<BaseElement> {
+    let mut trace = TraceTable::new(3, length);
+    trace.fill(
+        |state| {
+            state[0] = BaseElement::from(counter_start);
+            state[1] = BaseElement::ONE;
+            state[2] = BaseElement::ONE;
+        },
+        |_, state| {
+            state[1] += state[2] + state[0];
+            state[2] += state[1];
+            state[0] += BaseElement::ONE;
+        },
+    );
+    trace
+}
+
+/// Builds the preprocessed trace consisting of the counter column of the provided trace.
+fn build_preprocessed_trace(
+    trace: &TraceTable<BaseElement>,
+    domain: &StarkDomain<BaseElement>,
+) -> PreprocessedTrace<BaseElement, Blake3> {
+    let counter = trace.main_segment().get_column(0).to_vec();
+    PreprocessedTrace::new(&ColMatrix::new(vec![counter]), domain)
+}
+
+struct CounterFibProver {
+    options: ProofOptions,
+    preprocessed_trace: PreprocessedTrace<BaseElement, Blake3>,
+}
+
+impl Prover for CounterFibProver {
+    type BaseField = BaseElement;
+    type Air = CounterFibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = Coin;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        trace.get(2, trace.length() - 1)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+
+    fn preprocessed_trace(
+        &self,
+        _trace_info: &TraceInfo,
+    ) -> Option<PreprocessedTrace<Self::BaseField, Self::HashFn>> {
+        Some(self.preprocessed_trace.clone())
+    }
+
+    fn new_trace_lde_with_preprocessed_trace<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        preprocessed_trace: PreprocessedTrace<Self::BaseField, Self::HashFn>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> Result<(Self::TraceLde<E>, TracePolyTable<E>), ProverError> {
+        Ok(DefaultTraceLde::with_preprocessed_trace(
+            trace_info,
+            main_trace,
+            preprocessed_trace,
+            domain,
+        ))
+    }
+}
@@ -0,0 +1,579 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::{string::ToString, vec::Vec};
+
+use air::{
+    Air, AirContext, Assertion, AuxRandElements, ConstraintCompositionCoefficients,
+    EvaluationFrame, ProofOptions, TraceInfo,
+};
+use crypto::{
+    hashers::{Sha3_256, Truncated},
+    DefaultRandomCoin, DualHashRandomCoin, HashFunctionId, MerkleTree,
+};
+use math::{fields::f128::BaseElement, FieldElement};
+use utils::{Deserializable, Serializable};
+use verifier::{AcceptableOptions, VerifierError};
+
+use super::fib::{build_proof_options, compute_fib_term, Blake3, FibAir, FibProver};
+use crate::{
+    matrix::ColMatrix,
+    proof::{Proof, ProofExtension, Queries},
+    ConstraintCommitment, DefaultConstraintEvaluator, DefaultTraceLde, MainTraceCommitment,
+    MemoryEstimate, Prover, ProverError, StarkDomain, Trace, TraceLde, TracePolyTable, TraceTable,
+};
+
+type Coin = DefaultRandomCoin<Blake3>;
+
+// PROOF GENERATION TESTS
+// ================================================================================================
+
+#[test]
+fn prove_batch() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let sequence_lengths = [16, 32, 16];
+    let traces = sequence_lengths.iter().map(|&n| prover.build_trace(n)).collect();
+    let proofs = prover.prove_batch(traces).unwrap();
+    assert_eq!(proofs.len(), sequence_lengths.len());
+
+    for (proof, sequence_length) in proofs.into_iter().zip(sequence_lengths) {
+        assert_eq!(proof, prover.prove(prover.build_trace(sequence_length)).unwrap());
+        assert!(verify::<Blake3, Coin>(proof, compute_fib_term(sequence_length)).is_ok());
+    }
+}
+
+#[test]
+fn prove_with_transcript() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let (proof, transcript) = prover.prove_with_transcript(prover.build_trace(16)).unwrap();
+    assert_eq!(proof, prover.prove(prover.build_trace(16)).unwrap());
+
+    let labels = transcript.entries().iter().map(|entry| entry.label()).collect::<Vec<_>>();
+    assert_eq!(labels.first(), Some(&"trace_root"));
+    assert_eq!(labels.last(), Some(&"query_positions"));
+    assert!(labels.contains(&"constraint_root"));
+    assert!(labels.contains(&"ood_point"));
+    assert_eq!(transcript.to_string().lines().count(), transcript.len());
+}
+
+#[test]
+fn prove_with_commitments() {
+    let options = build_proof_options(false);
+    let prover = FibProver::<Blake3>::new(options.clone());
+    let trace = prover.build_trace(1024);
+    let air = FibAir::new(trace.info().clone(), compute_fib_term(1024), options);
+    let domain = StarkDomain::new(&air);
+
+    // a commitment to the trace produces the same proof as the one built by the prover
+    let commitment = MainTraceCommitment::from_trace(trace.main_segment(), &domain);
+    let proof = prover.prove_with_commitments(prover.build_trace(1024), commitment).unwrap();
+    assert_eq!(proof, prover.prove(trace).unwrap());
+
+    // a commitment to a different trace is rejected
+    let mut other_trace = prover.build_trace(1024);
+    other_trace.set(0, 5, BaseElement::ONE);
+    let commitment = MainTraceCommitment::from_trace(other_trace.main_segment(), &domain);
+    let result = prover.prove_with_commitments(prover.build_trace(1024), commitment);
+    assert!(matches!(result, Err(ProverError::InconsistentMainTraceCommitment(_))));
+
+    // a prover which does not build trace LDEs from commitments rejects the commitment
+    let commitment =
+        MainTraceCommitment::from_trace(prover.build_trace(1024).main_segment(), &domain);
+    let prover = NeighborOpeningsProver(prover);
+    let result = prover.prove_with_commitments(prover.0.build_trace(1024), commitment);
+    assert_eq!(Err(ProverError::UnsupportedMainTraceCommitment), result);
+}
+
+#[test]
+fn prove_with_dual_hash_functions() {
+    type DualCoin = DualHashRandomCoin<Blake3, Sha3_256<BaseElement>>;
+    let options = build_proof_options(false)
+        .with_hash_functions(HashFunctionId::Blake3_256, HashFunctionId::Sha3_256);
+
+    // the proof verifies when the verifier uses the same hash functions as the prover
+    let prover = FibProver::<Blake3, DualCoin>::new(options.clone());
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    assert_eq!(options, *proof.options());
+    assert!(verify::<Blake3, DualCoin>(proof.clone(), compute_fib_term(16)).is_ok());
+
+    // the verifier rejects the proof when it uses a different random coin
+    let result = verify::<Blake3, Coin>(proof, compute_fib_term(16));
+    assert_eq!(Err(VerifierError::MismatchedHashFunctions), result);
+
+    // the prover rejects the options which do not match the hash functions it uses
+    let prover = FibProver::<Blake3>::new(options);
+    let result = prover.prove(prover.build_trace(16));
+    assert_eq!(Err(ProverError::MismatchedHashFunctions), result.map(|_| ()));
+}
+
+#[test]
+fn prove_with_truncated_digests() {
+    type Blake3_20 = Truncated<Blake3, 20>;
+    type Blake3_24 = Truncated<Blake3, 24>;
+
+    // proofs with truncated digests are smaller than proofs with full digests
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let full_proof = prover.prove(prover.build_trace(1024)).unwrap();
+    let options = build_proof_options(false).with_digest_truncation(20);
+    let prover = FibProver::<Blake3_20>::new(options.clone());
+    let proof = prover.prove(prover.build_trace(1024)).unwrap();
+    assert_eq!(Some(20), proof.options().digest_truncation());
+    assert!(proof.to_bytes().len() < full_proof.to_bytes().len());
+    assert!(proof.security_level::<Blake3_20>(true) < full_proof.security_level::<Blake3>(true));
+    let result =
+        verify::<Blake3_20, DefaultRandomCoin<Blake3_20>>(proof.clone(), compute_fib_term(1024));
+    assert!(result.is_ok());
+
+    // the verifier rejects the proof when its digests have a different size
+    let result = verify::<Blake3_24, DefaultRandomCoin<Blake3_24>>(proof, compute_fib_term(1024));
+    assert_eq!(Err(VerifierError::MismatchedDigestSize(20, 24)), result);
+
+    // the prover rejects the options which do not match the digests of its hash function
+    let prover = FibProver::<Blake3>::new(options);
+    let result = prover.prove(prover.build_trace(1024));
+    assert_eq!(Err(ProverError::MismatchedDigestSize(20, 32)), result.map(|_| ()));
+}
+
+#[test]
+fn prove_with_parameters_digest() {
+    type Sha3 = Sha3_256<BaseElement>;
+    let options = build_proof_options(false).with_parameters_digest::<BaseElement, Blake3, Coin>();
+
+    // the proof verifies when the verifier uses the same protocol configuration as the prover
+    let prover = FibProver::<Blake3>::new(options.clone());
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    assert_eq!(options, *proof.options());
+    assert!(verify::<Blake3, Coin>(proof.clone(), compute_fib_term(16)).is_ok());
+
+    // the verifier rejects the proof when it uses a different hash function
+    let result = verify::<Sha3, DefaultRandomCoin<Sha3>>(proof, compute_fib_term(16));
+    assert_eq!(Err(VerifierError::MismatchedParametersDigest), result);
+
+    // the prover rejects the options recorded for a different hash function
+    let prover = FibProver::<Sha3>::new(options);
+    let result = prover.prove(prover.build_trace(16));
+    assert_eq!(Err(ProverError::MismatchedParametersDigest), result.map(|_| ()));
+}
+
+#[test]
+fn prove_with_fri_folding_schedule() {
+    let options = build_proof_options(false);
+    let lde_domain_size = 1024 / 2 * options.blowup_factor();
+    let schedule = options.to_fri_options().optimal_folding_schedule(lde_domain_size, 16, 32);
+    for schedule in [vec![16, 2], schedule] {
+        let options = options.clone().with_fri_folding_schedule(&schedule);
+        let prover = FibProver::<Blake3>::new(options);
+        let proof = prover.prove(prover.build_trace(1024)).unwrap();
+        assert_eq!(schedule, proof.options().fri_folding_schedule());
+        assert!(verify::<Blake3, Coin>(proof, compute_fib_term(1024)).is_ok());
+    }
+}
+
+#[test]
+fn prove_without_fri() {
+    for extension in [false, true] {
+        let options = build_proof_options(extension);
+        let fri_options = options.clone().with_max_fri_remainder_degree(255);
+        assert!(fri_options.skips_fri(32));
+
+        let prover = FibProver::<Blake3>::new(options);
+        let proof = prover.prove(prover.build_trace(64)).unwrap();
+        let prover = FibProver::<Blake3>::new(fri_options);
+        let proof_without_fri = prover.prove(prover.build_trace(64)).unwrap();
+
+        // the proof contains no FRI layers, and is smaller than the proof with FRI layers
+        assert!(proof.num_fri_layers() > 0);
+        assert_eq!(0, proof_without_fri.num_fri_layers());
+        assert!(proof_without_fri.to_bytes().len() < proof.to_bytes().len());
+
+        // the proof verifies against the correct result only
+        assert!(verify::<Blake3, Coin>(proof_without_fri.clone(), compute_fib_term(64)).is_ok());
+        let wrong_result = compute_fib_term::<BaseElement>(64) + BaseElement::ONE;
+        assert!(verify::<Blake3, Coin>(proof_without_fri, wrong_result).is_err());
+    }
+}
+
+#[cfg(feature = "concurrent")]
+#[test]
+fn prove_in_thread_pool() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let pool = utils::iterators::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    let proof = prover.prove_in_thread_pool(prover.build_trace(1024), &pool).unwrap();
+    assert_eq!(proof, prover.prove(prover.build_trace(1024)).unwrap());
+}
+
+#[test]
+fn prove_with_extensions() {
+    let options = build_proof_options(false);
+    let prover = NeighborOpeningsProver(FibProver::new(options.clone()));
+    let proof = prover.prove(prover.0.build_trace(16)).unwrap();
+
+    // the extension survives serialization
+    assert_eq!(proof, Proof::from_bytes(&proof.to_bytes()).unwrap());
+    let extension = proof.get_extension(NEIGHBOR_OPENINGS_ID).unwrap().clone();
+
+    let lde_domain_size = proof.lde_domain_size();
+    let num_fri_layers = options.to_fri_options().num_fri_layers(lde_domain_size);
+    let (trace_roots, ..) = proof.commitments.clone().parse::<Blake3>(1, num_fri_layers).unwrap();
+
+    // the proof verifies, and the verifier recovers the query positions used by the prover
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options]);
+    let query_positions = verifier::verify_with_query_positions::<FibAir, Blake3, Coin>(
+        proof,
+        compute_fib_term(16),
+        &acceptable_options,
+    )
+    .unwrap();
+
+    // the openings in the extension are valid against the main trace commitment
+    let positions = neighbor_positions(&query_positions);
+    let queries = Queries::read_from_bytes(extension.data()).unwrap();
+    let (merkle_proof, states) = queries
+        .parse::<Blake3, BaseElement>(lde_domain_size, positions.len(), 2)
+        .unwrap();
+    assert_eq!(positions.len(), states.num_rows());
+    assert!(MerkleTree::verify_batch(&trace_roots[0], &positions, &merkle_proof).is_ok());
+    assert!(MerkleTree::verify_batch(&trace_roots[0], &query_positions, &merkle_proof).is_err());
+}
+
+#[test]
+fn prove_with_memory_budget() {
+    let options = build_proof_options(false);
+    let fib_prover = FibProver::<Blake3>::new(options.clone());
+    let trace = fib_prover.build_trace(64);
+
+    // the estimate is derived from the AIR context of the computation
+    let estimate = fib_prover.estimate_memory(&trace);
+    let air = FibAir::new(trace.info().clone(), compute_fib_term(64), options);
+    assert_eq!(MemoryEstimate::new::<BaseElement, Blake3>(air.context()), estimate);
+
+    // proof generation fails if the estimate exceeds the budget
+    let prover = BudgetedProver(fib_prover, estimate.total() - 1);
+    let result = prover.prove(trace.clone());
+    assert_eq!(
+        Err(ProverError::MemoryBudgetExceeded(estimate.total(), estimate.total() - 1)),
+        result.map(|_| ())
+    );
+
+    // and succeeds if the estimate fits into the budget
+    let prover = BudgetedProver(prover.0, estimate.total());
+    let proof = prover.prove(trace).unwrap();
+    assert!(verify::<Blake3, Coin>(proof, compute_fib_term(64)).is_ok());
+}
+
+#[test]
+fn prove_with_constraint_evaluation_fragments() {
+    let options = build_proof_options(false);
+    let fib_prover = FibProver::<Blake3>::new(options.clone());
+    let trace = fib_prover.build_trace(1024);
+    let expected = fib_prover.prove(trace.clone()).unwrap();
+
+    // the proof does not depend on how the constraint evaluation domain is split into fragments;
+    // the last fragment size exceeds the size of the domain
+    for fragment_size in [16, 256, 1 << 20] {
+        let prover = FragmentedProver(FibProver::new(options.clone()), fragment_size);
+        let proof = prover.prove(trace.clone()).unwrap();
+        assert_eq!(expected.to_bytes(), proof.to_bytes());
+    }
+}
+
+#[test]
+fn prove_with_excluded_query_positions() {
+    // for a trace of 512 rows, the LDE domain has 4096 positions, and thus, with 28 queries, at
+    // most 73 positions can be excluded
+    let prover = ExcludingProver::<64, 64>(FibProver::new(build_proof_options(false)));
+    let proof = prover.prove(prover.0.build_trace(1024)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let query_positions = verifier::verify_with_query_positions::<
+        ExcludingFibAir<64, 64>,
+        Blake3,
+        Coin,
+    >(proof.clone(), compute_fib_term(1024), &acceptable_options)
+    .unwrap();
+    assert!(query_positions.iter().all(|&position| position >= 64));
+
+    // excluding query positions costs up to 1 bit of security
+    let security_levels = verifier::verify_with_security_levels::<
+        ExcludingFibAir<64, 64>,
+        Blake3,
+        Coin,
+    >(proof.clone(), compute_fib_term(1024), &acceptable_options)
+    .unwrap();
+    let expected = verifier::SecurityLevels::new::<Blake3>(&proof);
+    assert_eq!(expected.conjectured - 1, security_levels.conjectured);
+    assert_eq!(expected.proven - 1, security_levels.proven);
+
+    let prover = ExcludingProver::<128, 128>(FibProver::new(build_proof_options(false)));
+    let result = prover.prove(prover.0.build_trace(1024));
+    assert!(matches!(
+        result,
+        Err(ProverError::TooManyExcludedQueryPositions { num_excluded: 128, max_excluded: 73 })
+    ));
+
+    // in debug mode, the number of excluded positions reported by the AIR is checked against
+    // the positions which are actually excluded
+    #[cfg(debug_assertions)]
+    {
+        let prover = ExcludingProver::<64, 32>(FibProver::new(build_proof_options(false)));
+        let result = prover.prove(prover.0.build_trace(1024));
+        assert!(matches!(
+            result,
+            Err(ProverError::UnderreportedExcludedQueryPositions {
+                num_excluded: 64,
+                num_reported: 32
+            })
+        ));
+    }
+}
+
+// AIR WITH EXCLUDED QUERY POSITIONS
+// ================================================================================================
+
+/// [FibAir] which excludes the first `N` positions of the LDE domain from querying and reports
+/// `R` excluded positions.
+struct ExcludingFibAir<const N: usize, const R: usize>(FibAir);
+
+impl<const N: usize, const R: usize> Air for ExcludingFibAir<N, R> {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+    type GkrProof = ();
+    type GkrVerifier = ();
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        Self(FibAir::new(trace_info, pub_inputs, options))
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        self.0.context()
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.0.get_assertions()
+    }
+
+    fn is_query_position_excluded(&self, position: usize) -> bool {
+        position < N
+    }
+
+    fn num_excluded_query_positions(&self) -> usize {
+        R
+    }
+}
+
+// PROVERS WITH CUSTOM COMPONENTS
+// ================================================================================================
+
+/// Identifier of the proof extension which opens the trace at positions neighboring the query
+/// positions.
+const NEIGHBOR_OPENINGS_ID: u16 = 7;
+
+/// A prover for [FibAir] which appends openings of the main trace segment at positions
+/// neighboring the query positions to every proof.
+struct NeighborOpeningsProver(FibProver<Blake3>);
+
+impl Prover for NeighborOpeningsProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = Coin;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        self.0.get_pub_inputs(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+
+    fn build_proof_extensions<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        _air: &Self::Air,
+        _trace_polys: Option<&TracePolyTable<E>>,
+        trace_lde: &Self::TraceLde<E>,
+        _constraint_commitment: &ConstraintCommitment<E, Self::HashFn>,
+        query_positions: &[usize],
+    ) -> Vec<ProofExtension> {
+        let queries = trace_lde.query(&neighbor_positions(query_positions));
+        vec![ProofExtension::new(NEIGHBOR_OPENINGS_ID, queries[0].to_bytes())]
+    }
+}
+
+/// A prover for [FibAir] which may use at most the specified amount of memory.
+struct BudgetedProver(FibProver<Blake3>, u64);
+
+impl Prover for BudgetedProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = Coin;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        self.0.get_pub_inputs(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+
+    fn memory_budget(&self) -> Option<u64> {
+        Some(self.1)
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+/// A prover for [FibAir] which splits the constraint evaluation domain into fragments of the
+/// specified size.
+struct FragmentedProver(FibProver<Blake3>, usize);
+
+impl Prover for FragmentedProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = Coin;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        self.0.get_pub_inputs(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+            .with_fragment_size(self.1)
+    }
+}
+
+/// A prover for [ExcludingFibAir].
+struct ExcludingProver<const N: usize, const R: usize>(FibProver<Blake3>);
+
+impl<const N: usize, const R: usize> Prover for ExcludingProver<N, R> {
+    type BaseField = BaseElement;
+    type Air = ExcludingFibAir<N, R>;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = Coin;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        self.0.get_pub_inputs(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Verifies a proof of the [FibAir] computation against the options the proof was generated with.
+fn verify<H, R>(proof: Proof, result: BaseElement) -> Result<(), VerifierError>
+where
+    H: crypto::ElementHasher<BaseField = BaseElement>,
+    R: crypto::RandomCoin<BaseField = BaseElement, Hasher = H>,
+{
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    verifier::verify::<FibAir, H, R>(proof, result, &acceptable_options)
+}
+
+/// Returns the positions which differ from the provided ones in the lowest bit.
+fn neighbor_positions(query_positions: &[usize]) -> Vec<usize> {
+    let mut positions = query_positions.iter().map(|&p| p ^ 1).collect::<Vec<_>>();
+    positions.sort_unstable();
+    positions.dedup();
+    positions
+}
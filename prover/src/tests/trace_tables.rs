@@ -0,0 +1,347 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use air::{
+    Air, AirContext, Assertion, AuxRandElements, ConstraintCompositionCoefficients,
+    EvaluationFrame, ProofOptions, TraceInfo, TransitionConstraintDegree,
+};
+use crypto::DefaultRandomCoin;
+use math::{fields::f128::BaseElement, ExtensionOf, FieldElement};
+use verifier::{AcceptableOptions, VerifierError};
+
+use super::fib::{build_proof_options, Blake3};
+use crate::{
+    matrix::ColMatrix,
+    proof::{Commitments, Proof},
+    AuxTraceWithMetadata, DefaultConstraintEvaluator, DefaultTraceLde, Prover, StarkDomain, Trace,
+    TraceLde, TracePolyTable, TraceTable,
+};
+
+type Coin = DefaultRandomCoin<Blake3>;
+
+#[test]
+fn proof_with_main_trace_tables() {
+    let options = build_proof_options(false);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options.clone()]);
+    let prover = BusFibProver { options: options.clone() };
+
+    let trace = BusFibTrace::new(32);
+    let result = prover.get_pub_inputs(&trace);
+    let proof = prover.prove(trace).unwrap();
+    assert_eq!(2, proof.num_main_trace_tables);
+    assert_eq!(proof, Proof::from_bytes(&proof.to_bytes()).unwrap());
+    let compressed_proof = proof.compress::<Blake3>().unwrap();
+    assert_eq!(proof, Proof::decompress::<Blake3, BaseElement>(&compressed_proof).unwrap());
+
+    // the proof verifies against the commitments to both tables
+    let verification =
+        verifier::verify::<BusFibAir, Blake3, Coin>(proof.clone(), result, &acceptable_options);
+    assert!(verification.is_ok());
+
+    // the proof does not verify if the commitments to the tables are swapped
+    let lde_domain_size = proof.lde_domain_size();
+    let num_fri_layers = options.to_fri_options().num_fri_layers(lde_domain_size);
+    let (mut trace_roots, constraint_root, fri_roots) =
+        proof.commitments.clone().parse::<Blake3>(3, num_fri_layers).unwrap();
+    trace_roots.swap(0, 1);
+    let mut swapped_proof = proof.clone();
+    swapped_proof.commitments = Commitments::new::<Blake3>(trace_roots, constraint_root, fri_roots);
+    let verification =
+        verifier::verify::<BusFibAir, Blake3, Coin>(swapped_proof, result, &acceptable_options);
+    assert!(verification.is_err());
+
+    // the proof does not verify if it claims a different number of tables than the AIR
+    let mut proof = proof;
+    proof.num_main_trace_tables = 1;
+    let verification =
+        verifier::verify::<BusFibAir, Blake3, Coin>(proof, result, &acceptable_options);
+    assert!(matches!(verification, Err(VerifierError::ProofDeserializationError(_))));
+}
+
+#[test]
+fn main_trace_table_opening() {
+    let options = build_proof_options(false);
+    let prover = BusFibProver { options: options.clone() };
+
+    let trace = BusFibTrace::new(32);
+    let result = prover.get_pub_inputs(&trace);
+    let air = BusFibAir::new(trace.info().clone(), result, options);
+    let bus_table_idx = air.context().main_trace_table_index("bus").unwrap();
+    assert_eq!(1, bus_table_idx);
+    assert_eq!(2..3, air.context().main_trace_table_columns(bus_table_idx));
+    assert_eq!(None, air.context().main_trace_table_index("memory"));
+
+    // the trace LDE built by the prover commits to the same tables as the proof
+    let domain = StarkDomain::new(&air);
+    let (trace_lde, _) = DefaultTraceLde::<BaseElement, Blake3>::with_main_trace_tables(
+        trace.info(),
+        trace.main_segment(),
+        &BUS_TABLE_WIDTHS,
+        &domain,
+    );
+    let proof = prover.prove(trace).unwrap();
+    let table_roots = proof.main_trace_table_commitments::<Blake3>().unwrap();
+    assert_eq!(trace_lde.get_main_trace_table_commitments(), table_roots);
+
+    // the bus table can be opened without opening the Fibonacci table
+    let positions = vec![3, 17, 40, 101];
+    let opening = trace_lde.query_main_trace_table(bus_table_idx, &positions);
+    assert_eq!(trace_lde.query(&positions)[bus_table_idx], opening);
+    let rows = verifier::verify_main_trace_table_opening::<BaseElement, Blake3>(
+        &table_roots[bus_table_idx],
+        domain.lde_domain_size(),
+        1,
+        &positions,
+        &opening,
+    )
+    .unwrap();
+    assert_eq!(positions.len(), rows.num_rows());
+    assert_eq!(1, rows.num_columns());
+
+    // the opening does not verify against the commitment to the other table
+    let verification = verifier::verify_main_trace_table_opening::<BaseElement, Blake3>(
+        &table_roots[0],
+        domain.lde_domain_size(),
+        1,
+        &positions,
+        &opening,
+    );
+    assert!(matches!(verification, Err(VerifierError::TraceQueryDoesNotMatchCommitment)));
+}
+
+#[test]
+fn main_trace_tables_bus_violation() {
+    let options = build_proof_options(false);
+    let prover = BusFibProver { options: options.clone() };
+
+    let mut trace = BusFibTrace::new(32);
+    let result = prover.get_pub_inputs(&trace);
+    let air = BusFibAir::new(trace.info().clone(), result, options);
+    let aux_rand_elements = AuxRandElements::new(vec![BaseElement::new(123456789)]);
+
+    // the bus is balanced for a valid trace
+    let aux_trace = AuxTraceWithMetadata {
+        aux_trace: prover.build_aux_trace(&trace, &aux_rand_elements),
+        aux_rand_elements: aux_rand_elements.clone(),
+        gkr_proof: None,
+    };
+    assert_eq!(Ok(()), trace.check_constraints(&air, Some(&aux_trace)));
+
+    // changing a value in the second table unbalances the bus
+    trace.main_trace.set(2, 5, BaseElement::new(42));
+    let aux_trace = AuxTraceWithMetadata {
+        aux_trace: prover.build_aux_trace(&trace, &aux_rand_elements),
+        aux_rand_elements,
+        gkr_proof: None,
+    };
+    assert!(trace.check_constraints(&air, Some(&aux_trace)).is_err());
+}
+
+// FIBONACCI AIR WITH A SHARED BUS
+// ================================================================================================
+
+/// Names and widths of the column groups into which the main trace segment of [BusFibAir] is
+/// partitioned.
+const BUS_COLUMN_GROUPS: [(&str, usize); 2] = [("fib", 2), ("bus", 1)];
+
+/// Widths of the tables into which the main trace segment of [BusFibAir] is partitioned.
+const BUS_TABLE_WIDTHS: [usize; 2] = [BUS_COLUMN_GROUPS[0].1, BUS_COLUMN_GROUPS[1].1];
+
+/// A Fibonacci computation proven jointly with a second table over a shared bus.
+///
+/// The first table contains the Fibonacci terms as in [FibAir](super::fib::FibAir). The second
+/// table consists of a single column whose values over the first `n - 1` steps must be a
+/// permutation of the values of the first column of the first table over the same steps. This is
+/// enforced by a running product column `z` in the auxiliary trace segment:
+/// $z' \cdot (\alpha - b) = z \cdot (\alpha - a)$, with `z` starting and ending at 1.
+struct BusFibAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for BusFibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+    type GkrProof = ();
+    type GkrVerifier = ();
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
+        let main_degrees =
+            vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        let aux_degrees = vec![TransitionConstraintDegree::new(2)];
+        let context = AirContext::new_multi_segment(
+            trace_info,
+            main_degrees,
+            aux_degrees,
+            3,
+            2,
+            None,
+            options,
+        )
+        .set_main_trace_column_groups(&BUS_COLUMN_GROUPS)
+        .unwrap();
+        BusFibAir { context, result: pub_inputs }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn evaluate_aux_transition<F, E>(
+        &self,
+        main_frame: &EvaluationFrame<F>,
+        aux_frame: &EvaluationFrame<E>,
+        _periodic_values: &[F],
+        aux_rand_elements: &[E],
+        result: &mut [E],
+    ) where
+        F: FieldElement<BaseField = Self::BaseField>,
+        E: FieldElement<BaseField = Self::BaseField> + ExtensionOf<F>,
+    {
+        let alpha = aux_rand_elements[0];
+        let main = main_frame.current();
+        let z = aux_frame.current()[0];
+        let z_next = aux_frame.next()[0];
+        result[0] = z_next * (alpha - main[2].into()) - z * (alpha - main[0].into());
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, Self::BaseField::ONE),
+            Assertion::single(1, 0, Self::BaseField::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+
+    fn get_aux_assertions<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        _aux_rand_elements: &[E],
+    ) -> Vec<Assertion<E>> {
+        let last_step = self.trace_length() - 1;
+        vec![Assertion::single(0, 0, E::ONE), Assertion::single(0, last_step, E::ONE)]
+    }
+}
+
+/// Execution trace of [BusFibAir].
+struct BusFibTrace {
+    info: TraceInfo,
+    main_trace: ColMatrix<BaseElement>,
+}
+
+impl BusFibTrace {
+    /// Builds a trace with `length` steps in which the second table contains the first column of
+    /// the first table in reverse order.
+    fn new(length: usize) -> Self {
+        let mut fib = TraceTable::new(2, length);
+        fib.fill(
+            |state| {
+                state[0] = BaseElement::ONE;
+                state[1] = BaseElement::ONE;
+            },
+            |_, state| {
+                state[0] += state[1];
+                state[1] += state[0];
+            },
+        );
+
+        let terms = fib.get_column(0).to_vec();
+        let mut bus = terms[..length - 1].iter().rev().copied().collect::<Vec<_>>();
+        bus.push(terms[length - 1]);
+
+        let info = TraceInfo::new_multi_segment(3, 1, 1, length, Vec::new());
+        let main_trace = ColMatrix::new(vec![terms, fib.get_column(1).to_vec(), bus]);
+        BusFibTrace { info, main_trace }
+    }
+}
+
+impl Trace for BusFibTrace {
+    type BaseField = BaseElement;
+
+    fn info(&self) -> &TraceInfo {
+        &self.info
+    }
+
+    fn main_segment(&self) -> &ColMatrix<BaseElement> {
+        &self.main_trace
+    }
+
+    fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<BaseElement>) {
+        let next_row_idx = (row_idx + 1) % self.length();
+        self.main_trace.read_row_into(row_idx, frame.current_mut());
+        self.main_trace.read_row_into(next_row_idx, frame.next_mut());
+    }
+}
+
+struct BusFibProver {
+    options: ProofOptions,
+}
+
+impl Prover for BusFibProver {
+    type BaseField = BaseElement;
+    type Air = BusFibAir;
+    type Trace = BusFibTrace;
+    type HashFn = Blake3;
+    type RandomCoin = Coin;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        trace.main_segment().get(1, trace.length() - 1)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::with_main_trace_tables(trace_info, main_trace, &BUS_TABLE_WIDTHS, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+
+    fn build_aux_trace<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace: &Self::Trace,
+        aux_rand_elements: &AuxRandElements<E>,
+    ) -> ColMatrix<E> {
+        let main_trace = trace.main_segment();
+        let alpha = aux_rand_elements.rand_elements()[0];
+        let mut z = vec![E::ONE; trace.length()];
+        for i in 0..trace.length() - 1 {
+            let a = E::from(main_trace.get(0, i));
+            let b = E::from(main_trace.get(2, i));
+            z[i + 1] = z[i] * (alpha - a) / (alpha - b);
+        }
+        ColMatrix::new(vec![z])
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use air::{AuxRandElements, EvaluationFrame};
+use math::FieldElement;
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
+use utils::UninitBuffer;
+
+use super::{ColMatrix, Trace};
+
+// AUXILIARY TRACE BUILDER
+// ================================================================================================
+
+/// A helper for building the auxiliary trace segment from a set of column-filling closures.
+///
+/// Building an auxiliary trace segment usually follows the same pattern: allocate the columns,
+/// iterate over the rows of the main trace, and fill the auxiliary columns using the random
+/// elements drawn by the verifier. This builder takes care of the allocation and iteration, and
+/// lets the prover describe only how the values of auxiliary columns are computed. It is
+/// intended to be used from [Prover::build_aux_trace()](crate::Prover::build_aux_trace) as
+/// follows:
+///
+/// ```ignore
+/// AuxTraceBuilder::new(trace, aux_rand_elements)
+///     .fill(1, |row, rand, state| { ... }, |step, frame, rand, state| { ... })
+///     .map(2, |step, row, rand, result| { ... })
+///     .build()
+/// ```
+///
+/// Columns are added to the auxiliary segment in the order in which the closures are supplied.
+/// The builder also makes sure that the number of built columns matches the width of the
+/// auxiliary segment specified by the trace info.
+pub struct AuxTraceBuilder<'a, T, E>
+where
+    T: Trace,
+    E: FieldElement<BaseField = T::BaseField>,
+{
+    trace: &'a T,
+    rand_elements: &'a [E],
+    columns: Vec<Vec<E>>,
+}
+
+impl<'a, T, E> AuxTraceBuilder<'a, T, E>
+where
+    T: Trace + Sync,
+    E: FieldElement<BaseField = T::BaseField>,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new auxiliary trace builder for the specified main trace and random elements.
+    pub fn new(trace: &'a T, aux_rand_elements: &'a AuxRandElements<E>) -> Self {
+        Self {
+            trace,
+            rand_elements: aux_rand_elements.rand_elements(),
+            columns: Vec::with_capacity(trace.aux_trace_width()),
+        }
+    }
+
+    // COLUMN BUILDERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Appends `num_columns` columns to the auxiliary segment which are filled sequentially, one
+    /// row at a time.
+    ///
+    /// This is intended for columns where the value in a given row depends on the values in the
+    /// previous row (e.g., running products of permutation arguments). The columns are filled by
+    /// executing the provided closures as follows:
+    /// - `init` closure is used to initialize the first row of the columns; it receives the first
+    ///   row of the main trace, the random elements, and a mutable reference to the first state
+    ///   initialized to all zeros.
+    /// - `update` closure is used to populate all subsequent rows; it receives the index of the
+    ///   last updated row, an evaluation frame containing this row and the next row of the main
+    ///   trace, the random elements, and a mutable reference to the last updated state. The
+    ///   closure must update the state to the values of the next row.
+    ///
+    /// # Panics
+    /// Panics if `num_columns` is zero.
+    pub fn fill<I, U>(mut self, num_columns: usize, init: I, mut update: U) -> Self
+    where
+        I: FnOnce(&[T::BaseField], &[E], &mut [E]),
+        U: FnMut(usize, &EvaluationFrame<T::BaseField>, &[E], &mut [E]),
+    {
+        assert!(num_columns > 0, "number of columns must be greater than zero");
+        let num_rows = self.trace.length();
+        let mut columns: Vec<UninitBuffer<E>> =
+            (0..num_columns).map(|_| UninitBuffer::new(num_rows)).collect();
+
+        let mut frame = EvaluationFrame::new(self.trace.main_trace_width());
+        let mut state = vec![E::ZERO; num_columns];
+
+        self.trace.read_main_frame(0, &mut frame);
+        init(frame.current(), self.rand_elements, &mut state);
+        push_row(&mut columns, &state);
+
+        for step in 0..num_rows - 1 {
+            self.trace.read_main_frame(step, &mut frame);
+            update(step, &frame, self.rand_elements, &mut state);
+            push_row(&mut columns, &state);
+        }
+
+        self.columns.extend(columns.into_iter().map(UninitBuffer::into_vec));
+        self
+    }
+
+    /// Appends `num_columns` columns to the auxiliary segment in which every row is computed
+    /// independently from all other rows.
+    ///
+    /// For every row of the trace, the closure `f` receives the index of the row, the row of the
+    /// main trace, the random elements, and a mutable reference to the row of the new columns
+    /// initialized to all zeros. When `concurrent` feature is enabled, the rows are computed in
+    /// multiple threads.
+    ///
+    /// # Panics
+    /// Panics if `num_columns` is zero.
+    pub fn map<F>(mut self, num_columns: usize, f: F) -> Self
+    where
+        F: Fn(usize, &[T::BaseField], &[E], &mut [E]) + Send + Sync,
+    {
+        assert!(num_columns > 0, "number of columns must be greater than zero");
+        let num_rows = self.trace.length();
+        let main_trace_width = self.trace.main_trace_width();
+        let main_segment = self.trace.main_segment();
+        let rand_elements = self.rand_elements;
+
+        // the new columns are first computed in row-major order, and then transposed
+        let mut data = vec![E::ZERO; num_rows * num_columns];
+        let fill_row = |main_row: &mut Vec<T::BaseField>, (step, row): (usize, &mut [E])| {
+            main_segment.read_row_into(step, main_row);
+            f(step, main_row, rand_elements, row);
+        };
+
+        #[cfg(not(feature = "concurrent"))]
+        {
+            let mut main_row = vec![T::BaseField::ZERO; main_trace_width];
+            data.chunks_mut(num_columns)
+                .enumerate()
+                .for_each(|item| fill_row(&mut main_row, item));
+        }
+
+        #[cfg(feature = "concurrent")]
+        data.par_chunks_mut(num_columns)
+            .enumerate()
+            .for_each_init(|| vec![T::BaseField::ZERO; main_trace_width], fill_row);
+
+        self.columns
+            .append(&mut ColMatrix::from_row_major(&data, num_columns).into_columns());
+        self
+    }
+
+    // BUILDER
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the auxiliary trace segment built from all previously added columns.
+    ///
+    /// # Panics
+    /// Panics if the number of added columns is not equal to the width of the auxiliary trace
+    /// segment specified by the trace info.
+    pub fn build(self) -> ColMatrix<E> {
+        assert_eq!(
+            self.columns.len(),
+            self.trace.aux_trace_width(),
+            "expected {} auxiliary columns, but {} were built",
+            self.trace.aux_trace_width(),
+            self.columns.len()
+        );
+        ColMatrix::new(self.columns)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Appends values of the provided state as the next row of the columns.
+fn push_row<E: FieldElement>(columns: &mut [UninitBuffer<E>], state: &[E]) {
+    for (column, &value) in columns.iter_mut().zip(state) {
+        column.push(value);
+    }
+}
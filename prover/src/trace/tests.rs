@@ -5,9 +5,15 @@
 
 use alloc::vec::Vec;
 
-use math::fields::f128::BaseElement;
+use air::{Assertion, AuxRandElements, EvaluationFrame, TraceInfo};
+use math::{fields::f128::BaseElement, FieldElement};
 
-use crate::{tests::build_fib_trace, Trace};
+use crate::{
+    matrix::ColMatrix,
+    tests::{build_fib_trace, MockAir},
+    AuxTraceBuilder, BuiltTrace, ConstraintViolation, Trace, TraceBuilder, TracePadding,
+    TraceTable,
+};
 
 #[test]
 fn new_trace_table() {
@@ -29,3 +35,230 @@ fn new_trace_table() {
         .collect();
     assert_eq!(expected, trace.get_column(1));
 }
+
+#[test]
+fn new_trace_table_row_major() {
+    let trace = build_fib_trace(16);
+
+    // re-arrange the trace into row-major order and build a new trace table from it
+    let mut data = Vec::with_capacity(trace.main_trace_width() * trace.length());
+    let mut row = vec![BaseElement::ZERO; trace.main_trace_width()];
+    for step in 0..trace.length() {
+        trace.read_row_into(step, &mut row);
+        data.extend_from_slice(&row);
+    }
+    let row_major_trace = TraceTable::init_row_major(trace.main_trace_width(), &data);
+
+    assert_eq!(trace.main_trace_width(), row_major_trace.main_trace_width());
+    assert_eq!(trace.length(), row_major_trace.length());
+    for col_idx in 0..trace.main_trace_width() {
+        assert_eq!(trace.get_column(col_idx), row_major_trace.get_column(col_idx));
+    }
+}
+
+#[test]
+fn build_trace_from_state_machine() {
+    // a Fibonacci sequence which advances by two terms per row, with an active selector and a
+    // selector for every fourth row
+    let fib_step = |state: &mut [BaseElement; 2], _row: usize| {
+        state[0] += state[1];
+        state[1] += state[0];
+    };
+    let BuiltTrace { trace, final_state, final_step } = TraceBuilder::new([BaseElement::ONE; 2], 5)
+        .with_active_selector()
+        .with_selector(|row| row % 4 == 0)
+        .build(fib_step);
+
+    assert_eq!(4, trace.main_trace_width());
+    assert_eq!(8, trace.length());
+    assert_eq!(4, final_step);
+    assert_eq!([BaseElement::from(34u8), BaseElement::from(55u8)], final_state);
+
+    let expected = to_elements(&[1, 2, 5, 13, 34, 34, 34, 34]);
+    assert_eq!(expected, trace.get_column(0));
+    assert_eq!(to_elements(&[1, 1, 1, 1, 1, 0, 0, 0]), trace.get_column(2));
+    assert_eq!(to_elements(&[1, 0, 0, 0, 1, 0, 0, 0]), trace.get_column(3));
+
+    // continuing the execution in the padding rows yields the same trace as TraceTable::fill()
+    let BuiltTrace { trace, final_state, final_step } = TraceBuilder::new([BaseElement::ONE; 2], 5)
+        .with_min_length(16)
+        .with_padding(TracePadding::Continue)
+        .build(fib_step);
+
+    assert_eq!(16, trace.length());
+    assert_eq!(4, final_step);
+    assert_eq!([BaseElement::from(34u8), BaseElement::from(55u8)], final_state);
+    let expected = build_fib_trace(32);
+    assert_eq!(expected.get_column(0), trace.get_column(0));
+    assert_eq!(expected.get_column(1), trace.get_column(1));
+}
+
+#[test]
+fn build_aux_trace_from_closures() {
+    let trace = MultiSegmentTrace::new(build_fib_trace(16), 3);
+    let rand_elements = AuxRandElements::new(vec![BaseElement::from(7u8), BaseElement::from(3u8)]);
+
+    let aux_trace = AuxTraceBuilder::new(&trace, &rand_elements)
+        .fill(
+            1,
+            |_, _, state| state[0] = BaseElement::ONE,
+            |_, frame, rand, state| state[0] *= frame.current()[0] + rand[0],
+        )
+        .map(2, |step, row, rand, result| {
+            result[0] = BaseElement::from(step as u32);
+            result[1] = row[0] * rand[1] + row[1];
+        })
+        .build();
+
+    assert_eq!(3, aux_trace.num_cols());
+    assert_eq!(trace.length(), aux_trace.num_rows());
+
+    let main = trace.main_segment();
+    let mut running_product = BaseElement::ONE;
+    for step in 0..trace.length() {
+        assert_eq!(running_product, aux_trace.get(0, step));
+        assert_eq!(BaseElement::from(step as u32), aux_trace.get(1, step));
+        assert_eq!(
+            main.get(0, step) * BaseElement::from(3u8) + main.get(1, step),
+            aux_trace.get(2, step)
+        );
+        running_product *= main.get(0, step) + BaseElement::from(7u8);
+    }
+}
+
+#[test]
+#[should_panic(expected = "expected 3 auxiliary columns, but 1 were built")]
+fn build_aux_trace_with_wrong_width() {
+    let trace = MultiSegmentTrace::new(build_fib_trace(16), 3);
+    let rand_elements = AuxRandElements::new(vec![BaseElement::ONE]);
+
+    AuxTraceBuilder::new(&trace, &rand_elements).map(1, |_, _, _, _| {}).build();
+}
+
+#[test]
+fn check_trace_assertions() {
+    let trace_length = 16;
+    let columns = (0..4u32)
+        .map(|i| {
+            (0..trace_length as u32)
+                .map(|step| BaseElement::from(i * 100 + step % 4))
+                .collect()
+        })
+        .collect();
+    let trace = TraceTable::init(columns);
+
+    // all assertions hold
+    let assertions = vec![
+        Assertion::single(0, 0, BaseElement::from(0u32)),
+        Assertion::periodic(2, 1, 4, BaseElement::from(201u32)),
+    ];
+    let air = MockAir::with_assertions(assertions.clone(), trace_length);
+    assert_eq!(Ok(()), trace.check_constraints::<_, BaseElement>(&air, None));
+    assert!(trace.find_constraint_violations::<_, BaseElement>(&air, None).is_empty());
+
+    // the periodic assertion is violated at all steps but the first one
+    let assertions = vec![
+        Assertion::single(0, 0, BaseElement::from(0u32)),
+        Assertion::periodic(2, 1, 4, BaseElement::from(301u32)),
+    ];
+    let air = MockAir::with_assertions(assertions, trace_length);
+    let expected = ConstraintViolation::MainAssertion {
+        column: 2,
+        step: 1,
+        expected: BaseElement::from(301u32),
+        actual: BaseElement::from(201u32),
+    };
+    assert_eq!(Err(expected.clone()), trace.check_constraints(&air, None));
+
+    let violations = trace.find_constraint_violations::<_, BaseElement>(&air, None);
+    assert_eq!(4, violations.len());
+    assert_eq!(expected, violations[0]);
+    assert_eq!(vec![1, 5, 9, 13], violations.iter().map(|v| v.step()).collect::<Vec<_>>());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn check_trace_transitions() {
+    use air::Air;
+
+    use crate::tests::fib::{build_proof_options, compute_fib_term, Blake3, FibAir, FibProver};
+
+    let options = build_proof_options(false);
+    let mut trace = FibProver::<Blake3>::new(options.clone()).build_trace(16);
+    let air = FibAir::new(trace.info().clone(), compute_fib_term(16), options);
+    assert_eq!(Ok(()), trace.check_constraints::<_, BaseElement>(&air, None));
+
+    // corrupting a single cell breaks the transitions into and out of its row
+    trace.set(1, 3, BaseElement::ZERO);
+    let violation = trace.check_constraints::<_, BaseElement>(&air, None).unwrap_err();
+    match violation {
+        ConstraintViolation::MainTransition {
+            constraint,
+            step,
+            evaluation,
+            current,
+            next,
+        } => {
+            assert_eq!((1, 2), (constraint, step));
+            assert_eq!(next[1] - current[1] - next[0], evaluation);
+            assert_eq!(BaseElement::ZERO, next[1]);
+        },
+        _ => panic!("expected a main transition constraint violation, but was {violation}"),
+    }
+
+    let violations = trace.find_constraint_violations::<_, BaseElement>(&air, None);
+    assert_eq!(vec![2, 3, 3], violations.iter().map(|v| v.step()).collect::<Vec<_>>());
+}
+
+#[test]
+#[should_panic(
+    expected = "trace does not satisfy assertion main_trace(3, 2) == 5; the value was 302"
+)]
+fn validate_trace_with_invalid_assertion() {
+    let mut trace = TraceTable::init(vec![vec![BaseElement::from(300u32); 8]; 4]);
+    trace.set(3, 2, BaseElement::from(302u32));
+    let air = MockAir::with_assertions(vec![Assertion::single(3, 2, BaseElement::from(5u32))], 8);
+    trace.validate::<_, BaseElement>(&air, None);
+}
+
+// HELPERS
+// ================================================================================================
+
+/// A trace with an auxiliary segment of the specified width wrapping the specified main trace.
+struct MultiSegmentTrace {
+    info: TraceInfo,
+    main_trace: TraceTable<BaseElement>,
+}
+
+impl MultiSegmentTrace {
+    fn new(main_trace: TraceTable<BaseElement>, aux_width: usize) -> Self {
+        let info = TraceInfo::new_multi_segment(
+            main_trace.main_trace_width(),
+            aux_width,
+            2,
+            main_trace.length(),
+            vec![],
+        );
+        Self { info, main_trace }
+    }
+}
+
+impl Trace for MultiSegmentTrace {
+    type BaseField = BaseElement;
+
+    fn info(&self) -> &TraceInfo {
+        &self.info
+    }
+
+    fn main_segment(&self) -> &ColMatrix<BaseElement> {
+        self.main_trace.main_segment()
+    }
+
+    fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<BaseElement>) {
+        self.main_trace.read_main_frame(row_idx, frame);
+    }
+}
+
+fn to_elements(values: &[u32]) -> Vec<BaseElement> {
+    values.iter().map(|&value| BaseElement::from(value)).collect()
+}
@@ -0,0 +1,172 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{cmp, marker::PhantomData};
+
+use air::TraceInfo;
+use math::StarkField;
+
+use super::TraceTable;
+
+// TRACE BUILDER
+// ================================================================================================
+
+/// A helper for building execution traces of computations described as state machines.
+///
+/// The computation is described by an initial state and a step function which advances the state
+/// by one row; the builder runs the step function for the specified number of rows, pads the
+/// trace to a valid length, and optionally appends selector columns to it. It is intended to be
+/// used as follows:
+///
+/// ```ignore
+/// let BuiltTrace { trace, final_state, final_step } = TraceBuilder::new(initial_state, num_rows)
+///     .with_active_selector()
+///     .with_selector(|row| row % 8 == 7)
+///     .build(|state, row| { ... });
+/// ```
+///
+/// The state can be of any type which can be viewed as a slice of field elements (e.g., an array
+/// or a vector); the elements of the state form the first columns of the trace, and the selector
+/// columns follow them in the order in which they were added.
+///
+/// The length of the trace is the smallest power of two which is not smaller than the number of
+/// rows, [TraceInfo::MIN_TRACE_LENGTH], and the minimum length specified via
+/// [TraceBuilder::with_min_length()]. Rows added to reach this length are filled as specified by
+/// [TracePadding].
+pub struct TraceBuilder<B: StarkField, S> {
+    initial_state: S,
+    num_rows: usize,
+    min_length: usize,
+    padding: TracePadding,
+    selectors: Vec<Box<dyn Fn(usize) -> bool>>,
+    _field: PhantomData<B>,
+}
+
+/// Specifies how the rows of the trace after the last row of the execution are filled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TracePadding {
+    /// Every padding row is a copy of the last row of the execution.
+    #[default]
+    RepeatLast,
+    /// Padding rows are computed by continuing to apply the step function.
+    Continue,
+}
+
+/// An execution trace built by [TraceBuilder], together with the state at the last row of the
+/// execution.
+///
+/// The final state and its step can be used to define boundary assertions against the result of
+/// the computation.
+#[derive(Debug, Clone)]
+pub struct BuiltTrace<B: StarkField, S> {
+    /// The execution trace, including the padding rows and the selector columns.
+    pub trace: TraceTable<B>,
+    /// The state at the last row of the execution (i.e., before any padding).
+    pub final_state: S,
+    /// Index of the last row of the execution in the trace.
+    pub final_step: usize,
+}
+
+impl<B: StarkField, S: AsRef<[B]> + Clone> TraceBuilder<B, S> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new trace builder for an execution which starts in `initial_state` and lasts for
+    /// `num_rows` rows (i.e., the step function is applied `num_rows - 1` times).
+    ///
+    /// # Panics
+    /// Panics if `initial_state` is empty or if `num_rows` is zero.
+    pub fn new(initial_state: S, num_rows: usize) -> Self {
+        assert!(!initial_state.as_ref().is_empty(), "state must contain at least one element");
+        assert!(num_rows > 0, "number of rows must be greater than zero");
+        Self {
+            initial_state,
+            num_rows,
+            min_length: TraceInfo::MIN_TRACE_LENGTH,
+            padding: TracePadding::default(),
+            selectors: Vec::new(),
+            _field: PhantomData,
+        }
+    }
+
+    // CONFIGURATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Makes the built trace contain at least `min_length` rows.
+    pub fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = cmp::max(min_length, TraceInfo::MIN_TRACE_LENGTH);
+        self
+    }
+
+    /// Specifies how the padding rows of the trace are filled.
+    pub fn with_padding(mut self, padding: TracePadding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Appends a selector column which is set to ONE in every row for which `selector` returns
+    /// true, and to ZERO in all other rows; the closure receives the index of the row.
+    pub fn with_selector<F>(mut self, selector: F) -> Self
+    where
+        F: Fn(usize) -> bool + 'static,
+    {
+        self.selectors.push(Box::new(selector));
+        self
+    }
+
+    /// Appends a selector column which is set to ONE in the rows of the execution, and to ZERO in
+    /// the padding rows.
+    pub fn with_active_selector(self) -> Self {
+        let num_rows = self.num_rows;
+        self.with_selector(move |row| row < num_rows)
+    }
+
+    // TRACE BUILDING
+    // --------------------------------------------------------------------------------------------
+
+    /// Builds the trace by applying `step` to the state; the closure receives a mutable reference
+    /// to the state in the specified row, and must update it to the state in the next row.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The length of the state changes while the trace is built.
+    /// * The width of the trace (including the selector columns) is greater than 255, or the
+    ///   length of the trace is greater than the biggest multiplicative subgroup in the field `B`.
+    pub fn build<F>(self, mut step: F) -> BuiltTrace<B, S>
+    where
+        F: FnMut(&mut S, usize),
+    {
+        let state_width = self.initial_state.as_ref().len();
+        let trace_length = cmp::max(self.num_rows, self.min_length).next_power_of_two();
+        let mut trace = TraceTable::new(state_width + self.selectors.len(), trace_length);
+
+        let final_step = self.num_rows - 1;
+        let mut state = self.initial_state;
+        let mut final_state = None;
+        let mut row = vec![B::ZERO; trace.width()];
+        for i in 0..trace_length {
+            assert_eq!(state_width, state.as_ref().len(), "state width changed in row {i}");
+            row[..state_width].copy_from_slice(state.as_ref());
+            for (value, selector) in row[state_width..].iter_mut().zip(self.selectors.iter()) {
+                *value = if selector(i) { B::ONE } else { B::ZERO };
+            }
+            trace.update_row(i, &row);
+
+            if i == final_step {
+                final_state = Some(state.clone());
+            }
+            if i < final_step || (self.padding == TracePadding::Continue && i + 1 < trace_length) {
+                step(&mut state, i);
+            }
+        }
+
+        BuiltTrace {
+            trace,
+            final_state: final_state.expect("final state not captured"),
+            final_step,
+        }
+    }
+}
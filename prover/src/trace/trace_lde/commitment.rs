@@ -0,0 +1,164 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crypto::{ElementHasher, MerkleTree};
+use math::{polynom, StarkField};
+use tracing::info_span;
+
+use super::{ColMatrix, StarkDomain};
+use crate::{matrix::MatrixStorage, ProverError, RowMatrix, DEFAULT_SEGMENT_WIDTH};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of rows of a precomputed LDE which are checked against the trace polynomials and the
+/// Merkle tree before the LDE is used for proof generation.
+const NUM_CONSISTENCY_CHECKS: usize = 8;
+
+// MAIN TRACE COMMITMENT
+// ================================================================================================
+
+/// A precomputed low-degree extension of the main trace segment together with the Merkle tree
+/// built from its rows.
+///
+/// Extending and committing to the main trace segment is usually the most expensive step of proof
+/// generation which depends only on the main trace. This struct allows performing this step
+/// outside of the prover (e.g., on dedicated hardware, or once for several proofs of the same
+/// main trace), and then passing the result to
+/// [Prover::prove_with_commitments()](crate::Prover::prove_with_commitments).
+///
+/// The LDE is expected to be computed over the LDE domain of the computation (i.e., the domain
+/// described by [StarkDomain]), and the leaves of the Merkle tree are expected to be hashes of
+/// the LDE rows; this is exactly what [MainTraceCommitment::from_trace()] computes.
+#[derive(Debug)]
+pub struct MainTraceCommitment<B: StarkField, H: ElementHasher<BaseField = B>> {
+    lde: RowMatrix<B>,
+    tree: MerkleTree<H>,
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> MainTraceCommitment<B, H> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new commitment consisting of the provided LDE of the main trace segment and the
+    /// Merkle tree built from the rows of this LDE.
+    ///
+    /// The validity of the commitment is not checked here; instead, it is checked by the prover
+    /// before the commitment is used for proof generation.
+    pub fn new(lde: RowMatrix<B>, tree: MerkleTree<H>) -> Self {
+        Self { lde, tree }
+    }
+
+    /// Extends the provided main trace segment over the LDE domain, commits to the rows of the
+    /// extended trace, and returns the result.
+    pub fn from_trace(main_trace: &ColMatrix<B>, domain: &StarkDomain<B>) -> Self {
+        let trace_polys =
+            info_span!("interpolate_execution_trace", num_cols = main_trace.num_cols())
+                .in_scope(|| main_trace.interpolate_columns());
+
+        let (lde, tree) = info_span!(
+            "extend_and_commit_to_execution_trace",
+            num_cols = main_trace.num_cols(),
+            blowup = domain.trace_to_lde_blowup()
+        )
+        .in_scope(|| {
+            RowMatrix::evaluate_polys_over_and_commit::<H, DEFAULT_SEGMENT_WIDTH>(
+                &trace_polys,
+                domain,
+                &MatrixStorage::Heap,
+            )
+        });
+
+        Self { lde, tree }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the root of the Merkle tree built from the rows of the LDE.
+    pub fn root(&self) -> H::Digest {
+        *self.tree.root()
+    }
+
+    /// Returns the LDE of the main trace segment.
+    pub fn lde(&self) -> &RowMatrix<B> {
+        &self.lde
+    }
+
+    /// Returns the Merkle tree built from the rows of the LDE.
+    pub fn tree(&self) -> &MerkleTree<H> {
+        &self.tree
+    }
+
+    /// Consumes this commitment and returns the underlying LDE and Merkle tree.
+    pub fn into_parts(self) -> (RowMatrix<B>, MerkleTree<H>) {
+        (self.lde, self.tree)
+    }
+
+    // CONSISTENCY CHECKS
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that this commitment is consistent with the provided polynomials of the main trace
+    /// segment extended over the specified domain.
+    ///
+    /// The shapes of the LDE and of the Merkle tree are checked exhaustively. The values of the
+    /// LDE are spot-checked at a small number of rows spread across the domain: for each such row,
+    /// the values must be equal to evaluations of the trace polynomials at the corresponding
+    /// domain point, and the hash of the row must be included in the Merkle tree.
+    pub(crate) fn check_consistency(
+        &self,
+        trace_polys: &ColMatrix<B>,
+        domain: &StarkDomain<B>,
+    ) -> Result<(), ProverError> {
+        let lde_domain_size = domain.lde_domain_size();
+        if self.lde.num_cols() != trace_polys.num_cols() {
+            return Err(ProverError::InconsistentMainTraceCommitment(format!(
+                "expected the LDE to have {} columns, but it had {}",
+                trace_polys.num_cols(),
+                self.lde.num_cols()
+            )));
+        }
+        if self.lde.num_rows() != lde_domain_size {
+            return Err(ProverError::InconsistentMainTraceCommitment(format!(
+                "expected the LDE to have {lde_domain_size} rows, but it had {}",
+                self.lde.num_rows()
+            )));
+        }
+        if self.tree.leaves().len() != lde_domain_size {
+            return Err(ProverError::InconsistentMainTraceCommitment(format!(
+                "expected the Merkle tree to have {lde_domain_size} leaves, but it had {}",
+                self.tree.leaves().len()
+            )));
+        }
+
+        // spread the checked rows across the domain and offset them slightly so that they do not
+        // all fall onto the trace domain
+        let g = B::get_root_of_unity(lde_domain_size.ilog2());
+        let step = (lde_domain_size / NUM_CONSISTENCY_CHECKS).max(1);
+        for i in 0..NUM_CONSISTENCY_CHECKS.min(lde_domain_size) {
+            let position = (i * step + i) % lde_domain_size;
+            let row = self.lde.row(position);
+
+            let x = domain.offset() * g.exp((position as u64).into());
+            for (col_idx, &value) in row.iter().enumerate() {
+                if polynom::eval(trace_polys.get_column(col_idx), x) != value {
+                    return Err(ProverError::InconsistentMainTraceCommitment(format!(
+                        "LDE value in column {col_idx} at row {position} does not match the trace"
+                    )));
+                }
+            }
+
+            let leaf = H::hash_elements(row);
+            let path = self.tree.prove(position).expect("position must be within the tree");
+            if path[0] != leaf || MerkleTree::<H>::verify(self.root(), position, &path).is_err() {
+                return Err(ProverError::InconsistentMainTraceCommitment(format!(
+                    "LDE row {position} is not committed to by the Merkle tree"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -10,10 +10,10 @@ use crypto::MerkleTree;
 use tracing::info_span;
 
 use super::{
-    ColMatrix, ElementHasher, EvaluationFrame, FieldElement, Hasher, Queries, StarkDomain,
-    TraceInfo, TraceLde, TracePolyTable,
+    ColMatrix, ElementHasher, EvaluationFrame, FieldElement, Hasher, MainTraceCommitment,
+    PreprocessedTrace, Queries, StarkDomain, TraceInfo, TraceLde, TracePolyTable,
 };
-use crate::{RowMatrix, DEFAULT_SEGMENT_WIDTH};
+use crate::{matrix::MatrixStorage, RowMatrix, DEFAULT_SEGMENT_WIDTH};
 
 #[cfg(test)]
 mod tests;
@@ -28,17 +28,31 @@ mod tests;
 ///   will always be elements in the base field (even when an extension field is used).
 /// - Auxiliary segments: a list of 0 or more segments for traces generated after the prover
 ///   commits to the first trace segment. Currently, at most 1 auxiliary segment is possible.
+///
+/// If the main segment contains preprocessed columns, the LDE of these columns and the commitment
+/// to it are stored separately from the rest of the main segment (see [PreprocessedTrace]).
+///
+/// If the main segment is partitioned into several tables (see
+/// [DefaultTraceLde::with_main_trace_tables()]), the LDE of each table is committed to
+/// separately.
 pub struct DefaultTraceLde<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
-    // low-degree extension of the main segment of the trace
+    // low-degree extension of the preprocessed columns of the main segment and the commitment to it
+    preprocessed_segment: Option<PreprocessedTrace<E::BaseField, H>>,
+    // low-degree extension of the main segment of the trace (excluding preprocessed columns)
     main_segment_lde: RowMatrix<E::BaseField>,
     // commitment to the main segment of the trace
     main_segment_tree: MerkleTree<H>,
+    // low-degree extensions of the tables of the main segment following the first one, and the
+    // commitments to them; this is empty unless the main segment is partitioned into tables
+    main_segment_tables: Vec<(RowMatrix<E::BaseField>, MerkleTree<H>)>,
     // low-degree extensions of the auxiliary segment of the trace
     aux_segment_lde: Option<RowMatrix<E>>,
     // commitment to the auxiliary segment of the trace
     aux_segment_tree: Option<MerkleTree<H>>,
     blowup: usize,
     trace_info: TraceInfo,
+    // specifies where the data of segment LDEs is stored
+    storage: MatrixStorage,
 }
 
 impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> DefaultTraceLde<E, H> {
@@ -53,24 +67,188 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> DefaultTraceLd
         trace_info: &TraceInfo,
         main_trace: &ColMatrix<E::BaseField>,
         domain: &StarkDomain<E::BaseField>,
+    ) -> (Self, TracePolyTable<E>) {
+        Self::new_with_storage(trace_info, main_trace, domain, MatrixStorage::Heap)
+    }
+
+    /// Works just like [DefaultTraceLde::new()], but the LDEs of the main trace segment and of
+    /// the auxiliary trace segment (once it is set) are stored as specified by `storage`.
+    ///
+    /// This can be used to move the trace LDE, which is usually the largest data structure held
+    /// by the prover, into memory-mapped regions. All other data (e.g., the execution trace, the
+    /// trace polynomials, and the Merkle trees) is still stored on the heap.
+    pub fn new_with_storage(
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+        storage: MatrixStorage,
     ) -> (Self, TracePolyTable<E>) {
         // extend the main execution trace and build a Merkle tree from the extended trace
         let (main_segment_lde, main_segment_tree, main_segment_polys) =
-            build_trace_commitment::<E, E::BaseField, H>(main_trace, domain);
+            build_trace_commitment::<E, E::BaseField, H>(main_trace, domain, &storage);
 
         let trace_poly_table = TracePolyTable::new(main_segment_polys);
         let trace_lde = DefaultTraceLde {
+            preprocessed_segment: None,
             main_segment_lde,
             main_segment_tree,
+            main_segment_tables: Vec::new(),
             aux_segment_lde: None,
             aux_segment_tree: None,
             blowup: domain.trace_to_lde_blowup(),
             trace_info: trace_info.clone(),
+            storage,
         };
 
         (trace_lde, trace_poly_table)
     }
 
+    /// Creates a new [DefaultTraceLde] from a precomputed LDE of the main trace segment and the
+    /// commitment to it.
+    ///
+    /// The auxiliary trace segment (once it is set) is stored on the heap.
+    pub fn from_main_trace_commitment(
+        trace_info: &TraceInfo,
+        main_trace_commitment: MainTraceCommitment<E::BaseField, H>,
+        domain: &StarkDomain<E::BaseField>,
+    ) -> Self {
+        let (main_segment_lde, main_segment_tree) = main_trace_commitment.into_parts();
+        DefaultTraceLde {
+            preprocessed_segment: None,
+            main_segment_lde,
+            main_segment_tree,
+            main_segment_tables: Vec::new(),
+            aux_segment_lde: None,
+            aux_segment_tree: None,
+            blowup: domain.trace_to_lde_blowup(),
+            trace_info: trace_info.clone(),
+            storage: MatrixStorage::Heap,
+        }
+    }
+
+    /// Works just like [DefaultTraceLde::new()], but the first columns of the main trace segment
+    /// are treated as preprocessed columns whose polynomials, LDE, and commitment are taken from
+    /// `preprocessed_trace`.
+    ///
+    /// Only the remaining columns of the main trace segment are interpolated, extended, and
+    /// committed to; the commitment returned by
+    /// [get_main_trace_commitment()](TraceLde::get_main_trace_commitment) covers only these
+    /// columns. The returned [TracePolyTable] contains polynomials for all columns of the main
+    /// trace segment, including the preprocessed ones.
+    ///
+    /// # Panics
+    /// Panics if the number of rows in the LDE of the preprocessed trace is not equal to the size
+    /// of the LDE domain, or if the main trace segment does not have more columns than the
+    /// preprocessed trace.
+    pub fn with_preprocessed_trace(
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<E::BaseField>,
+        preprocessed_trace: PreprocessedTrace<E::BaseField, H>,
+        domain: &StarkDomain<E::BaseField>,
+    ) -> (Self, TracePolyTable<E>) {
+        let num_preprocessed_cols = preprocessed_trace.num_cols();
+        assert!(
+            main_trace.num_cols() > num_preprocessed_cols,
+            "main trace segment must have more than {num_preprocessed_cols} columns, but had {}",
+            main_trace.num_cols()
+        );
+        assert_eq!(
+            preprocessed_trace.lde().num_rows(),
+            domain.lde_domain_size(),
+            "preprocessed trace LDE must have the same number of rows as the LDE domain"
+        );
+
+        // extend the non-preprocessed columns of the main execution trace and build a Merkle tree
+        // from the extended trace
+        let main_trace = ColMatrix::new(
+            main_trace
+                .columns()
+                .skip(num_preprocessed_cols)
+                .map(|col| col.to_vec())
+                .collect(),
+        );
+        let (main_segment_lde, main_segment_tree, main_segment_polys) =
+            build_trace_commitment::<E, E::BaseField, H>(&main_trace, domain, &MatrixStorage::Heap);
+
+        // the polynomials of the preprocessed columns go first
+        let polys = preprocessed_trace
+            .polys()
+            .columns()
+            .map(|col| col.to_vec())
+            .chain(main_segment_polys.into_columns())
+            .collect();
+
+        let trace_lde = DefaultTraceLde {
+            preprocessed_segment: Some(preprocessed_trace),
+            main_segment_lde,
+            main_segment_tree,
+            main_segment_tables: Vec::new(),
+            aux_segment_lde: None,
+            aux_segment_tree: None,
+            blowup: domain.trace_to_lde_blowup(),
+            trace_info: trace_info.clone(),
+            storage: MatrixStorage::Heap,
+        };
+
+        (trace_lde, TracePolyTable::new(ColMatrix::new(polys)))
+    }
+
+    /// Works just like [DefaultTraceLde::new()], but the main trace segment is partitioned into
+    /// consecutive tables of the specified widths, and the LDE of each table is committed to
+    /// separately. All tables have the same number of rows as the main trace segment.
+    ///
+    /// The commitments to the tables are returned by
+    /// [get_main_trace_table_commitments()](TraceLde::get_main_trace_table_commitments) in the
+    /// order of the tables. The returned [TracePolyTable] contains polynomials for all columns of
+    /// the main trace segment.
+    ///
+    /// # Panics
+    /// Panics if `table_widths` is empty, contains a zero, or if the widths do not add up to the
+    /// number of columns in the main trace segment.
+    pub fn with_main_trace_tables(
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<E::BaseField>,
+        table_widths: &[usize],
+        domain: &StarkDomain<E::BaseField>,
+    ) -> (Self, TracePolyTable<E>) {
+        assert!(!table_widths.is_empty(), "at least one main trace table must be specified");
+        assert!(table_widths.iter().all(|&width| width > 0), "main trace tables cannot be empty");
+        assert_eq!(
+            table_widths.iter().sum::<usize>(),
+            main_trace.num_cols(),
+            "widths of main trace tables must add up to the width of the main trace segment"
+        );
+
+        // extend each table of the main execution trace and build a Merkle tree from it
+        let mut columns = main_trace.columns();
+        let mut tables = Vec::with_capacity(table_widths.len());
+        let mut polys = Vec::with_capacity(main_trace.num_cols());
+        for &width in table_widths {
+            let table =
+                ColMatrix::new(columns.by_ref().take(width).map(|col| col.to_vec()).collect());
+            let (table_lde, table_tree, table_polys) =
+                build_trace_commitment::<E, E::BaseField, H>(&table, domain, &MatrixStorage::Heap);
+            polys.extend(table_polys.into_columns());
+            tables.push((table_lde, table_tree));
+        }
+
+        let mut tables = tables.into_iter();
+        let (main_segment_lde, main_segment_tree) = tables.next().expect("no main trace tables");
+        let trace_lde = DefaultTraceLde {
+            preprocessed_segment: None,
+            main_segment_lde,
+            main_segment_tree,
+            main_segment_tables: tables.collect(),
+            aux_segment_lde: None,
+            aux_segment_tree: None,
+            blowup: domain.trace_to_lde_blowup(),
+            trace_info: trace_info.clone(),
+            storage: MatrixStorage::Heap,
+        };
+
+        (trace_lde, TracePolyTable::new(ColMatrix::new(polys)))
+    }
+
     // TEST HELPERS
     // --------------------------------------------------------------------------------------------
 
@@ -78,6 +256,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> DefaultTraceLd
     #[cfg(test)]
     pub fn main_segment_width(&self) -> usize {
         self.main_segment_lde.num_cols()
+            + self.main_segment_tables.iter().map(|(lde, _)| lde.num_cols()).sum::<usize>()
     }
 
     /// Returns a reference to [Matrix] representing the main trace segment.
@@ -108,6 +287,15 @@ where
         *root_hash
     }
 
+    /// Returns the commitments to the low-degree extensions of all tables of the main trace
+    /// segment.
+    fn get_main_trace_table_commitments(&self) -> Vec<<Self::HashFn as Hasher>::Digest> {
+        core::iter::once(&self.main_segment_tree)
+            .chain(self.main_segment_tables.iter().map(|(_, tree)| tree))
+            .map(|tree| *tree.root())
+            .collect()
+    }
+
     /// Takes auxiliary trace segment columns as input, interpolates them into polynomials in
     /// coefficient form, evaluates the polynomials over the LDE domain, and commits to the
     /// polynomial evaluations.
@@ -127,7 +315,7 @@ where
     ) -> (ColMatrix<E>, <Self::HashFn as Hasher>::Digest) {
         // extend the auxiliary trace segment and build a Merkle tree from the extended trace
         let (aux_segment_lde, aux_segment_tree, aux_segment_polys) =
-            build_trace_commitment::<E, E, H>(aux_trace, domain);
+            build_trace_commitment::<E, E, H>(aux_trace, domain, &self.storage);
 
         // check errors
         assert!(
@@ -157,9 +345,25 @@ where
         // at the end of the trace, next state wraps around and we read the first step again
         let next_lde_step = (lde_step + self.blowup()) % self.trace_len();
 
-        // copy main trace segment values into the frame
-        frame.current_mut().copy_from_slice(self.main_segment_lde.row(lde_step));
-        frame.next_mut().copy_from_slice(self.main_segment_lde.row(next_lde_step));
+        // copy preprocessed column values into the frame (if any), followed by the values of the
+        // rest of the main trace segment (table by table)
+        let mut offset = match self.preprocessed_segment {
+            Some(ref segment) => {
+                let width = segment.num_cols();
+                frame.current_mut()[..width].copy_from_slice(segment.lde().row(lde_step));
+                frame.next_mut()[..width].copy_from_slice(segment.lde().row(next_lde_step));
+                width
+            },
+            None => 0,
+        };
+        let tables = core::iter::once(&self.main_segment_lde)
+            .chain(self.main_segment_tables.iter().map(|(lde, _)| lde));
+        for table in tables {
+            let width = table.num_cols();
+            frame.current_mut()[offset..offset + width].copy_from_slice(table.row(lde_step));
+            frame.next_mut()[offset..offset + width].copy_from_slice(table.row(next_lde_step));
+            offset += width;
+        }
     }
 
     /// Reads current and next rows from the auxiliary trace segment into the specified frame.
@@ -203,12 +407,15 @@ where
     /// Returns trace table rows at the specified positions along with Merkle authentication paths
     /// from the commitment root to these rows.
     fn query(&self, positions: &[usize]) -> Vec<Queries> {
-        // build queries for the main trace segment
+        // build queries for the main trace segment, one set for each of its tables
         let mut result = vec![build_segment_queries(
             &self.main_segment_lde,
             &self.main_segment_tree,
             positions,
         )];
+        for (table_lde, table_tree) in self.main_segment_tables.iter() {
+            result.push(build_segment_queries(table_lde, table_tree, positions));
+        }
 
         // build queries for the auxiliary trace segment
         if let Some(ref segment_tree) = self.aux_segment_tree {
@@ -217,9 +424,34 @@ where
             result.push(build_segment_queries(segment_lde, segment_tree, positions));
         }
 
+        // build queries for the preprocessed columns of the main trace segment
+        if let Some(ref segment) = self.preprocessed_segment {
+            result.push(build_segment_queries(segment.lde(), segment.tree(), positions));
+        }
+
         result
     }
 
+    /// Returns rows of the specified main trace table at the specified positions along with
+    /// Merkle authentication paths from the commitment to this table to these rows.
+    fn query_main_trace_table(&self, table_idx: usize, positions: &[usize]) -> Queries {
+        if table_idx == 0 {
+            return build_segment_queries(
+                &self.main_segment_lde,
+                &self.main_segment_tree,
+                positions,
+            );
+        }
+
+        let num_tables = self.main_segment_tables.len() + 1;
+        assert!(
+            table_idx < num_tables,
+            "table index must be smaller than {num_tables}, but was {table_idx}"
+        );
+        let (table_lde, table_tree) = &self.main_segment_tables[table_idx - 1];
+        build_segment_queries(table_lde, table_tree, positions)
+    }
+
     /// Returns the number of rows in the execution trace.
     fn trace_len(&self) -> usize {
         self.main_segment_lde.num_rows()
@@ -242,44 +474,47 @@ where
 /// Computes a low-degree extension (LDE) of the provided execution trace over the specified
 /// domain and builds a commitment to the extended trace.
 ///
+/// The data of the extended trace is stored as specified by `storage`.
+///
 /// The extension is performed by interpolating each column of the execution trace into a
 /// polynomial of degree = trace_length - 1, and then evaluating the polynomial over the LDE
 /// domain.
 ///
 /// The trace commitment is computed by hashing each row of the extended execution trace, then
-/// building a Merkle tree from the resulting hashes.
+/// building a Merkle tree from the resulting hashes. Hashing of rows is pipelined with the
+/// evaluation of the polynomials (see [RowMatrix::evaluate_polys_over_and_commit()]).
 fn build_trace_commitment<E, F, H>(
     trace: &ColMatrix<F>,
     domain: &StarkDomain<E::BaseField>,
+    storage: &MatrixStorage,
 ) -> (RowMatrix<F>, MerkleTree<H>, ColMatrix<F>)
 where
     E: FieldElement,
     F: FieldElement<BaseField = E::BaseField>,
     H: ElementHasher<BaseField = E::BaseField>,
 {
-    // extend the execution trace
-    let (trace_lde, trace_polys) = {
-        let span = info_span!(
-            "extend_execution_trace",
-            num_cols = trace.num_cols(),
-            blowup = domain.trace_to_lde_blowup()
+    // interpolate the execution trace
+    let trace_polys = info_span!("interpolate_execution_trace", num_cols = trace.num_cols())
+        .in_scope(|| trace.interpolate_columns());
+    assert_eq!(trace_polys.num_rows(), trace.num_rows());
+
+    // extend the execution trace and build trace commitment
+    let tree_depth = domain.lde_domain_size().ilog2() as usize;
+    let (trace_lde, trace_tree) = info_span!(
+        "extend_and_commit_to_execution_trace",
+        num_cols = trace.num_cols(),
+        blowup = domain.trace_to_lde_blowup(),
+        tree_depth
+    )
+    .in_scope(|| {
+        RowMatrix::evaluate_polys_over_and_commit::<H, DEFAULT_SEGMENT_WIDTH>(
+            &trace_polys,
+            domain,
+            storage,
         )
-        .entered();
-        let trace_polys = trace.interpolate_columns();
-        let trace_lde =
-            RowMatrix::evaluate_polys_over::<DEFAULT_SEGMENT_WIDTH>(&trace_polys, domain);
-        drop(span);
-
-        (trace_lde, trace_polys)
-    };
+    });
     assert_eq!(trace_lde.num_cols(), trace.num_cols());
-    assert_eq!(trace_polys.num_rows(), trace.num_rows());
     assert_eq!(trace_lde.num_rows(), domain.lde_domain_size());
-
-    // build trace commitment
-    let tree_depth = trace_lde.num_rows().ilog2() as usize;
-    let trace_tree = info_span!("compute_execution_trace_commitment", tree_depth)
-        .in_scope(|| trace_lde.commit_to_rows());
     assert_eq!(trace_tree.depth(), tree_depth);
 
     (trace_lde, trace_tree, trace_polys)
@@ -296,8 +531,7 @@ where
 {
     // for each position, get the corresponding row from the trace segment LDE and put all these
     // rows into a single vector
-    let trace_states =
-        positions.iter().map(|&pos| segment_lde.row(pos).to_vec()).collect::<Vec<_>>();
+    let trace_states = segment_lde.gather_rows(positions);
 
     // build Merkle authentication paths to the leaves specified by positions
     let trace_proof = segment_tree
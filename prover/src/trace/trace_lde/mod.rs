@@ -11,9 +11,15 @@ use crypto::{ElementHasher, Hasher};
 use super::{ColMatrix, EvaluationFrame, FieldElement, TracePolyTable};
 use crate::StarkDomain;
 
+mod commitment;
+pub use commitment::MainTraceCommitment;
+
 mod default;
 pub use default::DefaultTraceLde;
 
+mod preprocessed;
+pub use preprocessed::PreprocessedTrace;
+
 // TRACE LOW DEGREE EXTENSION
 // ================================================================================================
 /// Contains all segments of the extended execution trace and their commitments.
@@ -30,6 +36,17 @@ pub trait TraceLde<E: FieldElement>: Sync {
     /// Returns the commitment to the low-degree extension of the main trace segment.
     fn get_main_trace_commitment(&self) -> <Self::HashFn as Hasher>::Digest;
 
+    /// Returns the commitments to the low-degree extensions of the tables of the main trace
+    /// segment, in the order of the tables.
+    ///
+    /// The main trace segment consists of a single table unless the AIR partitions it into
+    /// several tables (see
+    /// [AirContext::main_trace_table_widths()](air::AirContext::main_trace_table_widths)); by
+    /// default, this returns the commitment to the entire main trace segment.
+    fn get_main_trace_table_commitments(&self) -> Vec<<Self::HashFn as Hasher>::Digest> {
+        vec![self.get_main_trace_commitment()]
+    }
+
     /// Takes auxiliary trace segment columns as input, interpolates them into polynomials in
     /// coefficient form, evaluates the polynomials over the LDE domain, and commits to the
     /// polynomial evaluations.
@@ -72,8 +89,32 @@ pub trait TraceLde<E: FieldElement>: Sync {
 
     /// Returns trace table rows at the specified positions along with Merkle authentication paths
     /// from the commitment root to these rows.
+    ///
+    /// Queries are returned for each trace segment in order, with a separate set of queries for
+    /// each table of the main trace segment; if the main trace segment contains preprocessed
+    /// columns, the queries against these columns are returned last.
     fn query(&self, positions: &[usize]) -> Vec<Queries>;
 
+    /// Returns rows of the specified main trace table at the specified positions along with
+    /// Merkle authentication paths from the commitment to this table to these rows.
+    ///
+    /// This makes it possible to open a single group of main trace columns (see
+    /// [AirContext::set_main_trace_column_groups()](air::AirContext::set_main_trace_column_groups))
+    /// without revealing the values of the other columns. The returned queries are the same as
+    /// the queries against this table returned by [query()](Self::query); by default, all
+    /// trace segments are queried and all other queries are discarded.
+    ///
+    /// # Panics
+    /// Panics if `table_idx` is not smaller than the number of main trace tables.
+    fn query_main_trace_table(&self, table_idx: usize, positions: &[usize]) -> Queries {
+        let num_tables = self.get_main_trace_table_commitments().len();
+        assert!(
+            table_idx < num_tables,
+            "table index must be smaller than {num_tables}, but was {table_idx}"
+        );
+        self.query(positions).swap_remove(table_idx)
+    }
+
     /// Returns the number of rows in the execution trace.
     fn trace_len(&self) -> usize;
 
@@ -0,0 +1,197 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::sync::Arc;
+
+use crypto::{ElementHasher, MerkleTree};
+use math::{polynom, StarkField};
+use tracing::info_span;
+
+use super::{ColMatrix, StarkDomain};
+use crate::{matrix::MatrixStorage, ProverError, RowMatrix, DEFAULT_SEGMENT_WIDTH};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of steps of the execution trace at which preprocessed columns are checked against the
+/// trace before they are used for proof generation.
+const NUM_CONSISTENCY_CHECKS: usize = 8;
+
+// PREPROCESSED TRACE
+// ================================================================================================
+
+/// Polynomials, low-degree extension, and commitment of the preprocessed columns of the main
+/// trace segment.
+///
+/// Preprocessed columns are the first columns of the main trace segment whose values do not
+/// depend on the inputs of a computation (see
+/// [AirContext::num_preprocessed_columns()](air::AirContext::num_preprocessed_columns)). Thus,
+/// the work of interpolating these columns, extending them over the LDE domain, and committing to
+/// the extension can be performed once, and the result can be reused for all proofs generated for
+/// traces of the same length. The result can also be computed ahead of time (e.g., at build time)
+/// and loaded via [PreprocessedTrace::from_parts()].
+///
+/// The root of the Merkle tree built from the rows of the LDE must be known to the verifier in
+/// advance; it is not included in the proof, but is used to reseed the public coin before the
+/// commitment to the rest of the main trace segment.
+///
+/// The underlying data is reference-counted, and thus, cloning a preprocessed trace is cheap.
+#[derive(Debug)]
+pub struct PreprocessedTrace<B: StarkField, H: ElementHasher<BaseField = B>> {
+    inner: Arc<PreprocessedTraceData<B, H>>,
+}
+
+#[derive(Debug)]
+struct PreprocessedTraceData<B: StarkField, H: ElementHasher<BaseField = B>> {
+    polys: ColMatrix<B>,
+    lde: RowMatrix<B>,
+    tree: MerkleTree<H>,
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> PreprocessedTrace<B, H> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Interpolates the provided preprocessed columns into polynomials, extends them over the LDE
+    /// domain, commits to the rows of the extension, and returns the result.
+    pub fn new(columns: &ColMatrix<B>, domain: &StarkDomain<B>) -> Self {
+        let polys = info_span!("interpolate_preprocessed_trace", num_cols = columns.num_cols())
+            .in_scope(|| columns.interpolate_columns());
+
+        let (lde, tree) = info_span!(
+            "extend_and_commit_to_preprocessed_trace",
+            num_cols = columns.num_cols(),
+            blowup = domain.trace_to_lde_blowup()
+        )
+        .in_scope(|| {
+            RowMatrix::evaluate_polys_over_and_commit::<H, DEFAULT_SEGMENT_WIDTH>(
+                &polys,
+                domain,
+                &MatrixStorage::Heap,
+            )
+        });
+
+        Self::from_parts(polys, lde, tree)
+    }
+
+    /// Returns a new preprocessed trace consisting of the provided column polynomials in
+    /// coefficient form, their evaluations over the LDE domain, and the Merkle tree built from
+    /// the rows of these evaluations.
+    ///
+    /// The consistency of the provided parts is not checked; a proof generated from inconsistent
+    /// parts will fail verification.
+    ///
+    /// # Panics
+    /// Panics if the number of columns in `polys` differs from the number of columns in `lde`, or
+    /// if the number of rows in `lde` differs from the number of leaves in `tree`.
+    pub fn from_parts(polys: ColMatrix<B>, lde: RowMatrix<B>, tree: MerkleTree<H>) -> Self {
+        assert_eq!(
+            polys.num_cols(),
+            lde.num_cols(),
+            "number of polynomials must be the same as the number of LDE columns"
+        );
+        assert_eq!(
+            lde.num_rows(),
+            tree.leaves().len(),
+            "number of LDE rows must be the same as the number of Merkle tree leaves"
+        );
+
+        Self {
+            inner: Arc::new(PreprocessedTraceData { polys, lde, tree }),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the root of the Merkle tree built from the rows of the LDE.
+    ///
+    /// This is the commitment which the verifier needs to know to verify proofs generated using
+    /// this preprocessed trace.
+    pub fn root(&self) -> H::Digest {
+        *self.inner.tree.root()
+    }
+
+    /// Returns the number of preprocessed columns.
+    pub fn num_cols(&self) -> usize {
+        self.inner.polys.num_cols()
+    }
+
+    /// Returns the preprocessed column polynomials in coefficient form.
+    pub fn polys(&self) -> &ColMatrix<B> {
+        &self.inner.polys
+    }
+
+    /// Returns the LDE of the preprocessed columns.
+    pub fn lde(&self) -> &RowMatrix<B> {
+        &self.inner.lde
+    }
+
+    /// Returns the Merkle tree built from the rows of the LDE.
+    pub fn tree(&self) -> &MerkleTree<H> {
+        &self.inner.tree
+    }
+
+    // CONSISTENCY CHECKS
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that this preprocessed trace describes the first `num_preprocessed_cols` columns of
+    /// the provided main trace segment extended over the specified domain.
+    ///
+    /// The shapes of the polynomials and of the LDE are checked exhaustively. The values of the
+    /// columns are spot-checked at a small number of steps spread across the trace: for each
+    /// such step, evaluations of the preprocessed polynomials must be equal to the values in the
+    /// main trace segment.
+    pub(crate) fn check_consistency(
+        &self,
+        num_preprocessed_cols: usize,
+        main_trace: &ColMatrix<B>,
+        domain: &StarkDomain<B>,
+    ) -> Result<(), ProverError> {
+        let trace_length = domain.trace_length();
+        if self.num_cols() != num_preprocessed_cols {
+            return Err(ProverError::InvalidPreprocessedTrace(format!(
+                "expected {num_preprocessed_cols} preprocessed columns, but there were {}",
+                self.num_cols()
+            )));
+        }
+        if self.polys().num_rows() != trace_length {
+            return Err(ProverError::InvalidPreprocessedTrace(format!(
+                "expected polynomials with {trace_length} coefficients, but they had {}",
+                self.polys().num_rows()
+            )));
+        }
+        if self.lde().num_rows() != domain.lde_domain_size() {
+            return Err(ProverError::InvalidPreprocessedTrace(format!(
+                "expected the LDE to have {} rows, but it had {}",
+                domain.lde_domain_size(),
+                self.lde().num_rows()
+            )));
+        }
+
+        let g = B::get_root_of_unity(trace_length.ilog2());
+        let step = (trace_length / NUM_CONSISTENCY_CHECKS).max(1);
+        for i in 0..NUM_CONSISTENCY_CHECKS.min(trace_length) {
+            let trace_step = (i * step + i) % trace_length;
+            let x = g.exp((trace_step as u64).into());
+            for col_idx in 0..num_preprocessed_cols {
+                let value = polynom::eval(self.polys().get_column(col_idx), x);
+                if value != main_trace.get(col_idx, trace_step) {
+                    return Err(ProverError::InvalidPreprocessedTrace(format!(
+                        "preprocessed column {col_idx} does not match the trace at step {trace_step}"
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> Clone for PreprocessedTrace<B, H> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
@@ -7,7 +7,6 @@ use alloc::vec::Vec;
 
 use air::{EvaluationFrame, TraceInfo};
 use math::StarkField;
-use utils::uninit_vector;
 #[cfg(feature = "concurrent")]
 use utils::{iterators::*, rayon};
 
@@ -33,6 +32,10 @@ const MIN_FRAGMENT_LENGTH: usize = 2;
 /// 1. Lengths of all columns in the execution trace must be the same.
 /// 2. The length of the columns must be some power of two.
 ///
+/// If it is more natural to build the trace one row at a time, you can instead use the
+/// [TraceTable::init_row_major()] function, which takes the trace data arranged in row-major
+/// order, and transposes it into columns using a cache-blocked transpose.
+///
 /// The other approach is to use [TraceTable::new()] function, which takes trace width and
 /// length as parameters. This function will allocate memory for the trace, but will not fill it
 /// with data. To fill the execution trace, you can use the [fill()](TraceTable::fill) method,
@@ -70,7 +73,7 @@ impl<B: StarkField> TraceTable<B> {
 
     /// Creates a new execution trace of the specified width and length.
     ///
-    /// This allocates all the required memory for the trace, but does not initialize it. It is
+    /// This allocates all the required memory for the trace and initializes it with zeros. It is
     /// expected that the trace will be filled using one of the data mutator methods.
     ///
     /// # Panics
@@ -85,7 +88,7 @@ impl<B: StarkField> TraceTable<B> {
     /// Creates a new execution trace of the specified width and length, and with the specified
     /// metadata.
     ///
-    /// This allocates all the required memory for the trace, but does not initialize it. It is
+    /// This allocates all the required memory for the trace and initializes it with zeros. It is
     /// expected that the trace will be filled using one of the data mutator methods.
     ///
     /// # Panics
@@ -103,7 +106,7 @@ impl<B: StarkField> TraceTable<B> {
             length.ilog2()
         );
 
-        let columns = unsafe { (0..width).map(|_| uninit_vector(length)).collect() };
+        let columns = (0..width).map(|_| vec![B::ZERO; length]).collect();
 
         Self { info, trace: ColMatrix::new(columns) }
     }
@@ -136,6 +139,44 @@ impl<B: StarkField> TraceTable<B> {
         Self { info, trace: ColMatrix::new(columns) }
     }
 
+    /// Creates a new execution trace from trace data arranged in row-major order.
+    ///
+    /// The first `width` elements of `data` are interpreted as the first row of the trace, the
+    /// next `width` elements as the second row, and so on. This is convenient for computations
+    /// which naturally produce the trace one row at a time; the data is transposed into the
+    /// column-major form expected by the prover using a cache-blocked transpose.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `width` is zero or greater than 255.
+    /// * The length of `data` is not divisible by `width`.
+    /// * The number of rows is smaller than 8, greater than the biggest multiplicative subgroup
+    ///   in the field `B`, or is not a power of two.
+    pub fn init_row_major(width: usize, data: &[B]) -> Self {
+        assert!(width > 0, "execution trace must consist of at least one column");
+        assert_eq!(
+            data.len() % width,
+            0,
+            "trace data length must be divisible by trace width {width}, but was {}",
+            data.len()
+        );
+
+        let trace_length = data.len() / width;
+        let info = TraceInfo::with_meta(width, trace_length, Vec::new());
+
+        assert!(
+            trace_length.ilog2() <= B::TWO_ADICITY,
+            "execution trace length cannot exceed 2^{} steps, but was 2^{}",
+            B::TWO_ADICITY,
+            trace_length.ilog2()
+        );
+
+        Self {
+            info,
+            trace: ColMatrix::from_row_major(data, width),
+        }
+    }
+
     // DATA MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -0,0 +1,366 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+use core::{fmt, ops::ControlFlow};
+
+use air::{Air, EvaluationFrame, LagrangeKernelBoundaryConstraint};
+use math::{polynom, FieldElement};
+
+use super::{AirAuxTraceWithMetadata, ColMatrix, Trace};
+
+// CONSTRAINT VIOLATION
+// ================================================================================================
+
+/// Describes a constraint of an AIR which is not satisfied by an execution trace.
+///
+/// Violations are reported by [Trace::check_constraints()] and
+/// [Trace::find_constraint_violations()] methods. All values are lifted into the extension field
+/// `E` so that violations of constraints against both trace segments can be described by the
+/// same type.
+///
+/// Rows of the trace attached to transition constraint violations contain the values of all
+/// columns of the main trace segment followed by the values of all columns of the auxiliary trace
+/// segment (if the auxiliary segment was provided).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstraintViolation<E: FieldElement> {
+    /// An assertion against the main trace segment does not hold.
+    MainAssertion {
+        column: usize,
+        step: usize,
+        expected: E,
+        actual: E,
+    },
+    /// An assertion against the auxiliary trace segment does not hold.
+    AuxAssertion {
+        column: usize,
+        step: usize,
+        expected: E,
+        actual: E,
+    },
+    /// The boundary constraint of the Lagrange kernel column does not hold.
+    LagrangeKernelAssertion { column: usize, expected: E, actual: E },
+    /// A transition constraint against the main trace segment evaluated to a non-zero value.
+    MainTransition {
+        constraint: usize,
+        step: usize,
+        evaluation: E,
+        current: Vec<E>,
+        next: Vec<E>,
+    },
+    /// A transition constraint against the auxiliary trace segment evaluated to a non-zero value.
+    AuxTransition {
+        constraint: usize,
+        step: usize,
+        evaluation: E,
+        current: Vec<E>,
+        next: Vec<E>,
+    },
+    /// A transition constraint of the Lagrange kernel column evaluated to a non-zero value.
+    LagrangeKernelTransition {
+        constraint: usize,
+        step: usize,
+        evaluation: E,
+    },
+}
+
+impl<E: FieldElement> ConstraintViolation<E> {
+    /// Returns the step of the trace at which the violated constraint was evaluated.
+    pub fn step(&self) -> usize {
+        match self {
+            Self::MainAssertion { step, .. }
+            | Self::AuxAssertion { step, .. }
+            | Self::MainTransition { step, .. }
+            | Self::AuxTransition { step, .. }
+            | Self::LagrangeKernelTransition { step, .. } => *step,
+            Self::LagrangeKernelAssertion { .. } => 0,
+        }
+    }
+}
+
+impl<E: FieldElement> fmt::Display for ConstraintViolation<E> {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MainAssertion { column, step, expected, actual } => {
+                write!(f, "trace does not satisfy assertion main_trace({column}, {step}) == {expected}; the value was {actual}")
+            },
+            Self::AuxAssertion { column, step, expected, actual } => {
+                write!(f, "trace does not satisfy assertion aux_trace({column}, {step}) == {expected}; the value was {actual}")
+            },
+            Self::LagrangeKernelAssertion { column, expected, actual } => {
+                write!(f, "trace does not satisfy Lagrange kernel assertion aux_trace({column}, 0) == {expected}; the value was {actual}")
+            },
+            Self::MainTransition { constraint, step, evaluation, current, next } => {
+                write!(f, "main transition constraint {constraint} did not evaluate to ZERO at step {step}; evaluated to {evaluation} for current row {current:?} and next row {next:?}")
+            },
+            Self::AuxTransition { constraint, step, evaluation, current, next } => {
+                write!(f, "auxiliary transition constraint {constraint} did not evaluate to ZERO at step {step}; evaluated to {evaluation} for current row {current:?} and next row {next:?}")
+            },
+            Self::LagrangeKernelTransition { constraint, step, evaluation } => {
+                write!(f, "Lagrange transition constraint {constraint} did not evaluate to ZERO at step {step}; evaluated to {evaluation}")
+            },
+        }
+    }
+}
+
+// TRACE CHECKER
+// ================================================================================================
+
+/// Checks the specified trace against all constraints of the specified AIR, passing every found
+/// violation to the `on_violation` closure; the check stops as soon as the closure returns
+/// [ControlFlow::Break].
+///
+/// # Panics
+/// Panics if the width of the trace is not consistent with the AIR, or if the auxiliary trace is
+/// not provided for an AIR with an auxiliary trace segment.
+pub(super) fn check_trace<T, A, E, F>(
+    trace: &T,
+    air: &A,
+    aux_trace_with_metadata: Option<&AirAuxTraceWithMetadata<A, E>>,
+    mut on_violation: F,
+) -> ControlFlow<()>
+where
+    T: Trace,
+    A: Air<BaseField = T::BaseField>,
+    E: FieldElement<BaseField = T::BaseField>,
+    F: FnMut(ConstraintViolation<E>) -> ControlFlow<()>,
+{
+    // make sure the width align; if they don't something went terribly wrong
+    assert_eq!(
+        trace.main_trace_width(),
+        air.trace_info().main_trace_width(),
+        "inconsistent trace width: expected {}, but was {}",
+        trace.main_trace_width(),
+        air.trace_info().main_trace_width(),
+    );
+
+    // --- 1. make sure the assertions are valid --------------------------------------------------
+
+    // first, check assertions against the main segment of the execution trace
+    for assertion in air.get_assertions() {
+        let mut result = ControlFlow::Continue(());
+        assertion.apply(trace.length(), |step, expected| {
+            let actual = trace.main_segment().get(assertion.column(), step);
+            if result.is_continue() && expected != actual {
+                result = on_violation(ConstraintViolation::MainAssertion {
+                    column: assertion.column(),
+                    step,
+                    expected: expected.into(),
+                    actual: actual.into(),
+                });
+            }
+        });
+        result?;
+    }
+
+    // then, check assertions against the auxiliary trace segment
+    if let Some(aux_trace_with_metadata) = aux_trace_with_metadata {
+        let aux_trace = &aux_trace_with_metadata.aux_trace;
+        let aux_rand_elements = &aux_trace_with_metadata.aux_rand_elements;
+
+        for assertion in air.get_aux_assertions(aux_rand_elements.rand_elements()) {
+            let mut result = ControlFlow::Continue(());
+            assertion.apply(trace.length(), |step, expected| {
+                let actual = aux_trace.get(assertion.column(), step);
+                if result.is_continue() && expected != actual {
+                    result = on_violation(ConstraintViolation::AuxAssertion {
+                        column: assertion.column(),
+                        step,
+                        expected,
+                        actual,
+                    });
+                }
+            });
+            result?;
+        }
+
+        // then, check the Lagrange kernel assertion, if any
+        if let Some(lagrange_kernel_col_idx) = air.context().lagrange_kernel_aux_column_idx() {
+            let expected = LagrangeKernelBoundaryConstraint::assertion_value(
+                aux_rand_elements
+                    .lagrange()
+                    .expect("expected Lagrange kernel rand elements to be present"),
+            );
+            let actual = aux_trace.get(lagrange_kernel_col_idx, 0);
+            if expected != actual {
+                on_violation(ConstraintViolation::LagrangeKernelAssertion {
+                    column: lagrange_kernel_col_idx,
+                    expected,
+                    actual,
+                })?;
+            }
+        }
+    }
+
+    // --- 2. make sure this trace satisfies all transition constraints ---------------------------
+
+    // collect the info needed to build periodic values for a specific step
+    let g = air.trace_domain_generator();
+    let periodic_values_polys = air.get_periodic_column_polys();
+    let mut periodic_values = vec![T::BaseField::ZERO; periodic_values_polys.len()];
+
+    // initialize buffers to hold evaluation frames and results of constraint evaluations
+    let mut x = T::BaseField::ONE;
+    let mut main_frame = EvaluationFrame::new(trace.main_trace_width());
+    let mut aux_frame = if air.trace_info().is_multi_segment() {
+        Some(EvaluationFrame::<E>::new(trace.aux_trace_width()))
+    } else {
+        None
+    };
+    let mut main_evaluations =
+        vec![T::BaseField::ZERO; air.context().num_main_transition_constraints()];
+    let mut aux_evaluations = vec![E::ZERO; air.context().num_aux_transition_constraints()];
+
+    // we check transition constraints on all steps except the last k steps, where k is the
+    // number of steps exempt from transition constraints (guaranteed to be at least 1)
+    for step in 0..trace.length() - air.context().num_transition_exemptions() {
+        // build periodic values
+        for (p, v) in periodic_values_polys.iter().zip(periodic_values.iter_mut()) {
+            let num_cycles = air.trace_length() / p.len();
+            let x = x.exp((num_cycles as u32).into());
+            *v = polynom::eval(p, x);
+        }
+
+        // evaluate transition constraints for the main trace segment and make sure they all
+        // evaluate to zeros
+        trace.read_main_frame(step, &mut main_frame);
+        air.evaluate_transition(&main_frame, &periodic_values, &mut main_evaluations);
+        air.context().apply_main_selectors(main_frame.current(), &mut main_evaluations);
+
+        // read the auxiliary frame (if any) upfront so that it can be reported with violations
+        // of main transition constraints as well
+        if let Some(ref mut aux_frame) = aux_frame {
+            let aux_trace_with_metadata =
+                aux_trace_with_metadata.expect("expected aux trace to be present");
+            read_aux_frame(&aux_trace_with_metadata.aux_trace, step, aux_frame);
+        }
+
+        for (i, &evaluation) in main_evaluations.iter().enumerate() {
+            if evaluation != T::BaseField::ZERO {
+                let (current, next) = build_rows(&main_frame, aux_frame.as_ref());
+                on_violation(ConstraintViolation::MainTransition {
+                    constraint: i,
+                    step,
+                    evaluation: evaluation.into(),
+                    current,
+                    next,
+                })?;
+            }
+        }
+
+        // evaluate transition constraints for the auxiliary trace segment (if any) and make
+        // sure they all evaluate to zeros
+        if let Some(ref aux_frame) = aux_frame {
+            let aux_trace_with_metadata =
+                aux_trace_with_metadata.expect("expected aux trace to be present");
+            air.evaluate_aux_transition(
+                &main_frame,
+                aux_frame,
+                &periodic_values,
+                aux_trace_with_metadata.aux_rand_elements.rand_elements(),
+                &mut aux_evaluations,
+            );
+            air.context().apply_aux_selectors(main_frame.current(), &mut aux_evaluations);
+            for (i, &evaluation) in aux_evaluations.iter().enumerate() {
+                if evaluation != E::ZERO {
+                    let (current, next) = build_rows(&main_frame, Some(aux_frame));
+                    on_violation(ConstraintViolation::AuxTransition {
+                        constraint: i,
+                        step,
+                        evaluation,
+                        current,
+                        next,
+                    })?;
+                }
+            }
+        }
+
+        // update x coordinate of the domain
+        x *= g;
+    }
+
+    // evaluate transition constraints for Lagrange kernel column (if any) and make sure
+    // they all evaluate to zeros
+    if let Some(col_idx) = air.context().lagrange_kernel_aux_column_idx() {
+        let aux_trace_with_metadata =
+            aux_trace_with_metadata.expect("expected aux trace to be present");
+        let aux_trace = &aux_trace_with_metadata.aux_trace;
+        let aux_rand_elements = &aux_trace_with_metadata.aux_rand_elements;
+
+        let c = aux_trace.get_column(col_idx);
+        let v = trace.length().ilog2() as usize;
+        let r = aux_rand_elements.lagrange().expect("expected Lagrange column to be present");
+
+        // Loop over every constraint
+        for constraint_idx in 1..v + 1 {
+            let domain_step = 2_usize.pow((v - constraint_idx + 1) as u32);
+            let domain_half_step = 2_usize.pow((v - constraint_idx) as u32);
+
+            // Every transition constraint has a different enforcement domain (i.e. the rows to which it applies).
+            let enforcement_dom_len = trace.length() / domain_step;
+            for dom_idx in 0..enforcement_dom_len {
+                let x_current = dom_idx * domain_step;
+                let x_next = x_current + domain_half_step;
+
+                let evaluation = (r[v - constraint_idx] * c[x_current])
+                    - ((E::ONE - r[v - constraint_idx]) * c[x_next]);
+
+                if evaluation != E::ZERO {
+                    on_violation(ConstraintViolation::LagrangeKernelTransition {
+                        constraint: constraint_idx,
+                        step: x_current,
+                        evaluation,
+                    })?;
+                }
+            }
+        }
+    }
+
+    ControlFlow::Continue(())
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads an evaluation frame from the provided auxiliary segment.
+///
+/// This is probably not the most efficient implementation, but since we call this function only
+/// for trace validation purposes (which is done in debug mode only), we don't care all that much
+/// about its performance.
+fn read_aux_frame<E>(aux_segment: &ColMatrix<E>, row_idx: usize, frame: &mut EvaluationFrame<E>)
+where
+    E: FieldElement,
+{
+    for (current_frame_cell, aux_segment_col) in
+        frame.current_mut().iter_mut().zip(aux_segment.columns())
+    {
+        *current_frame_cell = aux_segment_col[row_idx];
+    }
+
+    let next_row_idx = (row_idx + 1) % aux_segment.num_rows();
+    for (next_frame_cell, aux_segment_col) in frame.next_mut().iter_mut().zip(aux_segment.columns())
+    {
+        *next_frame_cell = aux_segment_col[next_row_idx];
+    }
+}
+
+/// Concatenates the rows of the main and auxiliary frames into the current and the next rows of
+/// the full trace.
+fn build_rows<E: FieldElement>(
+    main_frame: &EvaluationFrame<E::BaseField>,
+    aux_frame: Option<&EvaluationFrame<E>>,
+) -> (Vec<E>, Vec<E>) {
+    let build_row = |main_row: &[E::BaseField], aux_row: Option<&[E]>| {
+        let mut row: Vec<E> = main_row.iter().map(|&value| value.into()).collect();
+        row.extend_from_slice(aux_row.unwrap_or(&[]));
+        row
+    };
+
+    (
+        build_row(main_frame.current(), aux_frame.map(|frame| frame.current())),
+        build_row(main_frame.next(), aux_frame.map(|frame| frame.next())),
+    )
+}
@@ -0,0 +1,218 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::{
+    alloc::{alloc, dealloc, handle_alloc_error, Layout},
+    vec::Vec,
+};
+use core::{
+    cell::{Cell, RefCell},
+    ptr::NonNull,
+    slice,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Alignment of memory chunks managed by an arena; values of any type with alignment not
+/// exceeding this can be allocated in an arena.
+const CHUNK_ALIGN: usize = 64;
+
+/// Size (in bytes) of the smallest memory chunk allocated by an arena.
+const MIN_CHUNK_SIZE: usize = 4096;
+
+// ARENA
+// ================================================================================================
+
+/// A bump allocator for short-lived buffers.
+///
+/// An arena requests memory from the global allocator in large chunks, and hands out slices of
+/// these chunks by bumping an offset. Individual allocations are never freed; instead, all memory
+/// handed out by an arena is reclaimed at once when the arena is [reset](Arena::reset()) or
+/// dropped. This makes allocations very cheap, and an arena which is reset and reused for
+/// repeated work of the same shape (e.g., for every proof generated by a long-running prover)
+/// stops requesting memory from the global allocator after the first iteration.
+///
+/// Only values of types implementing [Copy] can be allocated in an arena since destructors of
+/// allocated values are never run.
+///
+/// An arena can be sent to another thread, but cannot be shared between threads; each thread
+/// which needs scratch memory should use its own arena.
+#[derive(Debug, Default)]
+pub struct Arena {
+    chunks: RefCell<Vec<Chunk>>,
+    // offset of the first free byte in the last chunk
+    offset: Cell<usize>,
+    // total number of bytes handed out since the arena was created or last reset
+    allocated: Cell<usize>,
+}
+
+impl Arena {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new arena which does not hold any memory yet.
+    pub const fn new() -> Self {
+        Self {
+            chunks: RefCell::new(Vec::new()),
+            offset: Cell::new(0),
+            allocated: Cell::new(0),
+        }
+    }
+
+    /// Returns a new arena which can hand out at least `num_bytes` bytes before it needs to
+    /// request more memory from the global allocator.
+    pub fn with_capacity(num_bytes: usize) -> Self {
+        let arena = Self::new();
+        if num_bytes > 0 {
+            arena.chunks.borrow_mut().push(Chunk::new(num_bytes));
+        }
+        arena
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of bytes of memory held by this arena.
+    pub fn capacity(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| chunk.size).sum()
+    }
+
+    /// Returns the number of bytes handed out by this arena since it was created or last reset.
+    ///
+    /// This does not include padding inserted to satisfy alignment requirements.
+    pub fn allocated_bytes(&self) -> usize {
+        self.allocated.get()
+    }
+
+    // ALLOCATION METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Allocates a slice of `len` elements in this arena and sets all elements to `value`.
+    ///
+    /// # Panics
+    /// Panics if the alignment of `T` exceeds 64 bytes.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_fill<T: Copy>(&self, len: usize, value: T) -> &mut [T] {
+        let result = unsafe { self.alloc_slice_uninit(len) };
+        result.fill(value);
+        result
+    }
+
+    /// Allocates a copy of the provided `values` in this arena.
+    ///
+    /// # Panics
+    /// Panics if the alignment of `T` exceeds 64 bytes.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_copy<T: Copy>(&self, values: &[T]) -> &mut [T] {
+        let result = unsafe { self.alloc_slice_uninit(values.len()) };
+        result.copy_from_slice(values);
+        result
+    }
+
+    /// Allocates a slice of `len` elements in this arena without initializing the elements.
+    ///
+    /// # Safety
+    /// Using values from the returned slice before initializing them will lead to undefined
+    /// behavior.
+    ///
+    /// # Panics
+    /// Panics if the alignment of `T` exceeds 64 bytes.
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn alloc_slice_uninit<T: Copy>(&self, len: usize) -> &mut [T] {
+        let layout = Layout::array::<T>(len).expect("allocation too large");
+        let ptr = self.alloc_layout(layout);
+        slice::from_raw_parts_mut(ptr.as_ptr() as *mut T, len)
+    }
+
+    // RESET
+    // --------------------------------------------------------------------------------------------
+
+    /// Reclaims all memory handed out by this arena so that it can be reused for new allocations.
+    ///
+    /// If the arena had to request more than one chunk of memory since it was last reset, the
+    /// chunks are replaced with a single chunk of the combined size; thus, an arena which is reset
+    /// between iterations of the same work needs to request memory from the global allocator only
+    /// during the first iteration.
+    pub fn reset(&mut self) {
+        let chunks = self.chunks.get_mut();
+        if chunks.len() > 1 {
+            let capacity = chunks.iter().map(|chunk| chunk.size).sum();
+            chunks.clear();
+            chunks.push(Chunk::new(capacity));
+        }
+        self.offset.set(0);
+        self.allocated.set(0);
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a pointer to a memory region in this arena described by the specified layout.
+    fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
+        assert!(
+            layout.align() <= CHUNK_ALIGN,
+            "alignment must not exceed {CHUNK_ALIGN} bytes, but was {}",
+            layout.align()
+        );
+        if layout.size() == 0 {
+            // zero-sized allocations need only a well-aligned pointer
+            return NonNull::new(layout.align() as *mut u8).expect("alignment is never zero");
+        }
+        self.allocated.set(self.allocated.get() + layout.size());
+
+        // try to fit the allocation into the current chunk
+        let mut chunks = self.chunks.borrow_mut();
+        let offset = self.offset.get().next_multiple_of(layout.align());
+        if let Some(chunk) = chunks.last() {
+            if offset + layout.size() <= chunk.size {
+                self.offset.set(offset + layout.size());
+                return unsafe { NonNull::new_unchecked(chunk.ptr.as_ptr().add(offset)) };
+            }
+        }
+
+        // otherwise, request a new chunk which is at least twice as big as the current one; the
+        // memory of previous chunks remains valid as it is owned by the chunks and never moves
+        let last_chunk_size = chunks.last().map_or(0, |chunk| chunk.size);
+        let chunk_size = layout.size().max(2 * last_chunk_size).max(MIN_CHUNK_SIZE);
+        let chunk = Chunk::new(chunk_size);
+        let ptr = chunk.ptr;
+        chunks.push(chunk);
+        self.offset.set(layout.size());
+        ptr
+    }
+}
+
+// The arena exclusively owns the memory of its chunks, and thus, it can be moved to another thread.
+unsafe impl Send for Arena {}
+
+// MEMORY CHUNK
+// ================================================================================================
+
+/// A region of memory requested from the global allocator.
+#[derive(Debug)]
+struct Chunk {
+    ptr: NonNull<u8>,
+    size: usize,
+}
+
+impl Chunk {
+    fn new(size: usize) -> Self {
+        let layout = Self::layout(size);
+        let ptr = unsafe { alloc(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout));
+        Self { ptr, size }
+    }
+
+    fn layout(size: usize) -> Layout {
+        Layout::from_size_align(size, CHUNK_ALIGN).expect("chunk too large")
+    }
+}
+
+impl Drop for Chunk {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.ptr.as_ptr(), Self::layout(self.size)) }
+    }
+}
@@ -0,0 +1,326 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+use core::{mem::MaybeUninit, slice};
+
+#[cfg(feature = "concurrent")]
+use crate::iterators::*;
+
+// UNINIT BUFFER
+// ================================================================================================
+
+/// A buffer of a fixed length which is initialized incrementally, without writing any default
+/// values into it first.
+///
+/// The buffer tracks how many of its elements have been initialized, and can be converted into a
+/// vector only once all of its elements have been initialized. Thus, unlike
+/// [uninit_vector()](crate::uninit_vector), the buffer can be used without writing any unsafe
+/// code.
+///
+/// Elements can be appended to the buffer one at a time via [push()](UninitBuffer::push), or the
+/// uninitialized part of the buffer can be split into chunks which are initialized independently
+/// (and, possibly, concurrently) via [fill_chunks()](UninitBuffer::fill_chunks) and
+/// [fill_batches()](UninitBuffer::fill_batches).
+///
+/// # Examples
+/// ```
+/// # use winter_utils::UninitBuffer;
+/// let mut buffer = UninitBuffer::new(4);
+/// buffer.push(1u32);
+/// let values = buffer.fill_chunks(1, |i, chunk| chunk.push(i as u32 + 2));
+/// assert_eq!(vec![1, 2, 3, 4], values);
+/// ```
+#[derive(Debug)]
+pub struct UninitBuffer<T> {
+    values: Vec<T>,
+    len: usize,
+}
+
+impl<T> UninitBuffer<T> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new buffer of the specified length; none of the elements of the buffer are
+    /// initialized.
+    pub fn new(len: usize) -> Self {
+        Self { values: Vec::with_capacity(len), len }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the length of this buffer (i.e., the number of elements in the vector into which
+    /// this buffer can be converted).
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the length of this buffer is zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements of this buffer which have been initialized.
+    pub fn num_initialized(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if all elements of this buffer have been initialized.
+    pub fn is_full(&self) -> bool {
+        self.values.len() == self.len
+    }
+
+    /// Returns the initialized elements of this buffer.
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    // INITIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Initializes the next element of this buffer to the specified value.
+    ///
+    /// # Panics
+    /// Panics if all elements of this buffer have already been initialized.
+    pub fn push(&mut self, value: T) {
+        assert!(
+            !self.is_full(),
+            "all {} elements of the buffer are already initialized",
+            self.len
+        );
+        self.values.push(value);
+    }
+
+    /// Initializes the next elements of this buffer to the values of the specified iterator.
+    ///
+    /// # Panics
+    /// Panics if the iterator yields more values than there are uninitialized elements in this
+    /// buffer.
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+
+    /// Splits the uninitialized part of this buffer into chunks of `chunk_size` elements (the
+    /// last chunk may be shorter), initializes each chunk via the provided closure, and returns
+    /// the resulting vector.
+    ///
+    /// The closure is invoked with the index of a chunk (counting from the first uninitialized
+    /// element) and the chunk itself; chunks are initialized one after another.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is zero, or if any of the chunks was not fully initialized by the
+    /// closure.
+    pub fn fill_chunks<F>(mut self, chunk_size: usize, mut init: F) -> Vec<T>
+    where
+        F: FnMut(usize, &mut UninitChunk<T>),
+    {
+        assert!(chunk_size > 0, "chunk size must be greater than zero");
+        let all_initialized =
+            self.spare_elements().chunks_mut(chunk_size).enumerate().all(|(i, chunk)| {
+                let mut chunk = UninitChunk::new(chunk);
+                init(i, &mut chunk);
+                chunk.is_full()
+            });
+        self.assume_full(all_initialized)
+    }
+
+    /// Same as [fill_chunks()](UninitBuffer::fill_chunks), but the chunks are initialized in
+    /// multiple threads.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is zero, or if any of the chunks was not fully initialized by the
+    /// closure.
+    #[cfg(feature = "concurrent")]
+    pub fn par_fill_chunks<F>(mut self, chunk_size: usize, init: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(usize, &mut UninitChunk<T>) + Send + Sync,
+    {
+        assert!(chunk_size > 0, "chunk size must be greater than zero");
+        let all_initialized =
+            self.spare_elements().par_chunks_mut(chunk_size).enumerate().all(|(i, chunk)| {
+                let mut chunk = UninitChunk::new(chunk);
+                init(i, &mut chunk);
+                chunk.is_full()
+            });
+        self.assume_full(all_initialized)
+    }
+
+    /// Splits the uninitialized part of this buffer into batches, initializes each batch via the
+    /// provided closure, and returns the resulting vector.
+    ///
+    /// The closure is invoked with a batch and the position of the first element of the batch in
+    /// this buffer. When `concurrent` feature is enabled, the batches are initialized in multiple
+    /// threads, and the size of each batch is at least `min_batch_size` elements; otherwise, the
+    /// entire uninitialized part of the buffer is initialized as a single batch.
+    ///
+    /// # Panics
+    /// Panics if any of the batches was not fully initialized by the closure.
+    #[cfg(not(feature = "concurrent"))]
+    pub fn fill_batches<F>(mut self, _min_batch_size: usize, init: F) -> Vec<T>
+    where
+        F: Fn(&mut UninitChunk<T>, usize),
+    {
+        let offset = self.values.len();
+        let mut batch = UninitChunk::new(self.spare_elements());
+        init(&mut batch, offset);
+        let all_initialized = batch.is_full();
+        self.assume_full(all_initialized)
+    }
+
+    /// Splits the uninitialized part of this buffer into batches, initializes each batch via the
+    /// provided closure, and returns the resulting vector.
+    ///
+    /// The closure is invoked with a batch and the position of the first element of the batch in
+    /// this buffer. When `concurrent` feature is enabled, the batches are initialized in multiple
+    /// threads, and the size of each batch is at least `min_batch_size` elements; otherwise, the
+    /// entire uninitialized part of the buffer is initialized as a single batch.
+    ///
+    /// # Panics
+    /// Panics if any of the batches was not fully initialized by the closure.
+    #[cfg(feature = "concurrent")]
+    pub fn fill_batches<F>(mut self, min_batch_size: usize, init: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&mut UninitChunk<T>, usize) + Send + Sync,
+    {
+        let offset = self.values.len();
+        let elements = self.spare_elements();
+        let batch_size = elements.len() / rayon::current_num_threads().next_power_of_two();
+        let all_initialized = if batch_size < min_batch_size.max(1) {
+            let mut batch = UninitChunk::new(elements);
+            init(&mut batch, offset);
+            batch.is_full()
+        } else {
+            elements.par_chunks_mut(batch_size).enumerate().all(|(i, batch)| {
+                let mut batch = UninitChunk::new(batch);
+                init(&mut batch, offset + i * batch_size);
+                batch.is_full()
+            })
+        };
+        self.assume_full(all_initialized)
+    }
+
+    // CONVERSIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Converts this buffer into a vector.
+    ///
+    /// # Panics
+    /// Panics if not all elements of this buffer have been initialized.
+    pub fn into_vec(self) -> Vec<T> {
+        assert!(
+            self.is_full(),
+            "only {} out of {} elements of the buffer have been initialized",
+            self.values.len(),
+            self.len
+        );
+        self.values
+    }
+
+    /// Converts this buffer into a vector, or returns the buffer back if not all of its elements
+    /// have been initialized.
+    pub fn try_into_vec(self) -> Result<Vec<T>, Self> {
+        if self.is_full() {
+            Ok(self.values)
+        } else {
+            Err(self)
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the uninitialized elements of this buffer.
+    fn spare_elements(&mut self) -> &mut [MaybeUninit<T>] {
+        let num_uninitialized = self.len - self.values.len();
+        &mut self.values.spare_capacity_mut()[..num_uninitialized]
+    }
+
+    /// Marks all elements of this buffer as initialized and returns the resulting vector.
+    ///
+    /// # Panics
+    /// Panics if `all_initialized` is false; in this case, the elements which have been written
+    /// into the uninitialized part of the buffer are leaked.
+    fn assume_full(mut self, all_initialized: bool) -> Vec<T> {
+        assert!(all_initialized, "not all elements of the buffer have been initialized");
+        // SAFETY: every element of the uninitialized part of the buffer belongs to a chunk which
+        // the caller has verified to be fully initialized
+        unsafe { self.values.set_len(self.len) };
+        self.values
+    }
+}
+
+// UNINIT CHUNK
+// ================================================================================================
+
+/// A contiguous part of an [UninitBuffer] which is initialized from its first element onwards.
+#[derive(Debug)]
+pub struct UninitChunk<'a, T> {
+    elements: &'a mut [MaybeUninit<T>],
+    num_initialized: usize,
+}
+
+impl<'a, T> UninitChunk<'a, T> {
+    fn new(elements: &'a mut [MaybeUninit<T>]) -> Self {
+        Self { elements, num_initialized: 0 }
+    }
+
+    /// Returns the length of this chunk.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns true if the length of this chunk is zero.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Returns the number of elements of this chunk which have been initialized.
+    pub fn num_initialized(&self) -> usize {
+        self.num_initialized
+    }
+
+    /// Returns true if all elements of this chunk have been initialized.
+    pub fn is_full(&self) -> bool {
+        self.num_initialized == self.elements.len()
+    }
+
+    /// Initializes the next element of this chunk to the specified value.
+    ///
+    /// # Panics
+    /// Panics if all elements of this chunk have already been initialized.
+    pub fn push(&mut self, value: T) {
+        assert!(
+            !self.is_full(),
+            "all {} elements of the chunk are already initialized",
+            self.len()
+        );
+        self.elements[self.num_initialized].write(value);
+        self.num_initialized += 1;
+    }
+
+    /// Initializes the next elements of this chunk to the values of the specified iterator.
+    ///
+    /// # Panics
+    /// Panics if the iterator yields more values than there are uninitialized elements in this
+    /// chunk.
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+
+    /// Returns the initialized elements of this chunk.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first `num_initialized` elements of the chunk have been initialized
+        unsafe {
+            slice::from_raw_parts_mut(self.elements.as_mut_ptr() as *mut T, self.num_initialized)
+        }
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Contains functions for encoding values which implement [Serializable] as text.
+//!
+//! Two encodings of the canonical binary representation of a value (i.e., the one produced by
+//! [Serializable::to_bytes()]) are supported:
+//! * Hex: lowercase hexadecimal digits without a `0x` prefix.
+//! * Base64: the standard alphabet with padding, as defined in
+//!   [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648#section-4).
+//!
+//! Decoding is strict: every value has exactly one valid encoding. Specifically, decoding fails
+//! if the input is not in the exact form described above (e.g., it contains uppercase hex digits,
+//! characters outside of the alphabet, missing padding, or non-zero padding bits), if not all of
+//! the decoded bytes are consumed when reading the value, or if the value does not serialize back
+//! into the decoded bytes.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{ByteReader, Deserializable, DeserializationError, Serializable, SliceReader};
+
+// HEX ENCODING
+// ================================================================================================
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Returns the canonical binary representation of the specified value encoded as a lowercase hex
+/// string.
+pub fn to_hex<T: Serializable + ?Sized>(value: &T) -> String {
+    encode_hex(&value.to_bytes())
+}
+
+/// Returns a value decoded from the specified hex string.
+///
+/// # Errors
+/// Returns an error if the string is not a canonical hex encoding of a valid value of type `T`.
+pub fn from_hex<T: Serializable + Deserializable>(source: &str) -> Result<T, DeserializationError> {
+    read_canonical(&decode_hex(source)?)
+}
+
+/// Encodes the specified bytes as a lowercase hex string.
+pub fn encode_hex(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() * 2);
+    for &byte in bytes {
+        result.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        result.push(HEX_DIGITS[(byte & 0xf) as usize] as char);
+    }
+    result
+}
+
+/// Decodes bytes from the specified lowercase hex string.
+///
+/// # Errors
+/// Returns an error if the string has an odd number of characters, or contains characters other
+/// than lowercase hex digits.
+pub fn decode_hex(source: &str) -> Result<Vec<u8>, DeserializationError> {
+    let source = source.as_bytes();
+    if source.len() % 2 != 0 {
+        return Err(DeserializationError::InvalidValue(
+            "hex string must have an even number of digits".into(),
+        ));
+    }
+    source
+        .chunks(2)
+        .map(|pair| Ok((decode_hex_digit(pair[0])? << 4) | decode_hex_digit(pair[1])?))
+        .collect()
+}
+
+fn decode_hex_digit(digit: u8) -> Result<u8, DeserializationError> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        _ => Err(DeserializationError::InvalidValue(format!(
+            "{:?} is not a lowercase hex digit",
+            digit as char
+        ))),
+    }
+}
+
+// BASE64 ENCODING
+// ================================================================================================
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const BASE64_PADDING: u8 = b'=';
+
+/// Returns the canonical binary representation of the specified value encoded as a padded Base64
+/// string using the standard alphabet.
+pub fn to_base64<T: Serializable + ?Sized>(value: &T) -> String {
+    encode_base64(&value.to_bytes())
+}
+
+/// Returns a value decoded from the specified Base64 string.
+///
+/// # Errors
+/// Returns an error if the string is not a canonical Base64 encoding of a valid value of type
+/// `T`.
+pub fn from_base64<T: Serializable + Deserializable>(
+    source: &str,
+) -> Result<T, DeserializationError> {
+    read_canonical(&decode_base64(source)?)
+}
+
+/// Encodes the specified bytes as a padded Base64 string using the standard alphabet.
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let group = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+
+        // a chunk of n bytes is encoded with n + 1 characters followed by padding
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                result.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                result.push(BASE64_PADDING as char);
+            }
+        }
+    }
+    result
+}
+
+/// Decodes bytes from the specified padded Base64 string using the standard alphabet.
+///
+/// # Errors
+/// Returns an error if the length of the string is not a multiple of 4, if the string contains
+/// characters outside of the alphabet or misplaced padding, or if the padding bits of the last
+/// character are not zeros.
+pub fn decode_base64(source: &str) -> Result<Vec<u8>, DeserializationError> {
+    let source = source.as_bytes();
+    if source.len() % 4 != 0 {
+        return Err(DeserializationError::InvalidValue(
+            "length of a base64 string must be a multiple of 4".into(),
+        ));
+    }
+
+    let num_groups = source.len() / 4;
+    let mut result = Vec::with_capacity(num_groups * 3);
+    for (i, group) in source.chunks(4).enumerate() {
+        // padding is allowed only at the end of the last group, and only in the last 2 positions
+        let num_padding = group.iter().rev().take_while(|&&c| c == BASE64_PADDING).count();
+        if num_padding > 2 || (num_padding > 0 && i != num_groups - 1) {
+            return Err(DeserializationError::InvalidValue("invalid base64 padding".into()));
+        }
+
+        let mut value = 0u32;
+        for &c in group[..4 - num_padding].iter() {
+            value = (value << 6) | decode_base64_digit(c)?;
+        }
+        value <<= 6 * num_padding;
+
+        // the bits which are not part of the encoded bytes must be zeros; otherwise multiple
+        // strings would decode into the same bytes
+        let num_bytes = 3 - num_padding;
+        if value & ((1 << (8 * num_padding)) - 1) != 0 {
+            return Err(DeserializationError::InvalidValue(
+                "base64 string has non-zero padding bits".into(),
+            ));
+        }
+        result.extend_from_slice(&value.to_be_bytes()[1..1 + num_bytes]);
+    }
+    Ok(result)
+}
+
+fn decode_base64_digit(digit: u8) -> Result<u32, DeserializationError> {
+    match digit {
+        b'A'..=b'Z' => Ok((digit - b'A') as u32),
+        b'a'..=b'z' => Ok((digit - b'a') as u32 + 26),
+        b'0'..=b'9' => Ok((digit - b'0') as u32 + 52),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(DeserializationError::InvalidValue(format!(
+            "{:?} is not a base64 digit",
+            digit as char
+        ))),
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads a value from the specified bytes, and checks that the bytes are the canonical binary
+/// representation of the value.
+fn read_canonical<T: Serializable + Deserializable>(
+    bytes: &[u8],
+) -> Result<T, DeserializationError> {
+    let mut reader = SliceReader::new(bytes);
+    let value = T::read_from(&mut reader)?;
+    if reader.has_more_bytes() {
+        return Err(DeserializationError::UnconsumedBytes);
+    }
+    if value.to_bytes() != bytes {
+        return Err(DeserializationError::InvalidValue(
+            "bytes are not a canonical encoding of the value".into(),
+        ));
+    }
+    Ok(value)
+}
@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use alloc::string::String;
+use alloc::{boxed::Box, string::String};
 use core::fmt;
 
 // DESERIALIZATION ERROR
@@ -20,6 +20,80 @@ pub enum DeserializationError {
     UnconsumedBytes,
     /// An unknown error has occurred.
     UnknownError(String),
+    /// An error occurred while reading a part of a larger value.
+    WithContext {
+        /// Path to the part of the value which was being read (e.g., `fri_proof.layers[3].paths`).
+        path: String,
+        /// Offset of the byte in the input at which the error occurred, if known.
+        offset: Option<usize>,
+        /// The underlying error.
+        error: Box<DeserializationError>,
+    },
+}
+
+impl DeserializationError {
+    /// Returns this error annotated with the name of the field which was being read when the
+    /// error occurred.
+    ///
+    /// If the error has already been annotated, the field name is prepended to the existing path,
+    /// and the existing offset is retained; otherwise, the specified `offset` is recorded.
+    pub fn in_field(self, name: &str, offset: Option<usize>) -> Self {
+        self.with_path_segment(String::from(name), offset)
+    }
+
+    /// Returns this error annotated with the index of the element of a collection which was being
+    /// read when the error occurred.
+    ///
+    /// This works in the same way as [in_field()](Self::in_field), but the index is recorded in
+    /// the path as `[index]`.
+    pub fn in_element(self, index: usize, offset: Option<usize>) -> Self {
+        self.with_path_segment(format!("[{index}]"), offset)
+    }
+
+    /// Returns the underlying error stripped of all annotations.
+    pub fn root_cause(&self) -> &Self {
+        match self {
+            Self::WithContext { error, .. } => error.root_cause(),
+            _ => self,
+        }
+    }
+
+    /// Returns the path to the part of the value which was being read when the error occurred,
+    /// if known.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Self::WithContext { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Returns the offset of the byte in the input at which the error occurred, if known.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Self::WithContext { offset, .. } => *offset,
+            _ => None,
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    fn with_path_segment(self, mut segment: String, offset: Option<usize>) -> Self {
+        match self {
+            Self::WithContext { path, offset, error } => {
+                if !path.starts_with('[') {
+                    segment.push('.');
+                }
+                segment.push_str(&path);
+                Self::WithContext { path: segment, offset, error }
+            },
+            error => Self::WithContext {
+                path: segment,
+                offset,
+                error: Box::new(error),
+            },
+        }
+    }
 }
 
 impl fmt::Display for DeserializationError {
@@ -29,6 +103,12 @@ impl fmt::Display for DeserializationError {
             Self::UnexpectedEOF => write!(f, "unexpected EOF"),
             Self::UnconsumedBytes => write!(f, "not all bytes were consumed"),
             Self::UnknownError(err_msg) => write!(f, "unknown error: {err_msg}"),
+            Self::WithContext { path, offset: Some(offset), error } => {
+                write!(f, "failed to read {path} at byte {offset}: {error}")
+            },
+            Self::WithContext { path, offset: None, error } => {
+                write!(f, "failed to read {path}: {error}")
+            },
         }
     }
 }
@@ -5,11 +5,43 @@
 
 //! Components needed for parallel iterators.
 //!
-//! When `concurrent` feature is enabled, this module re-exports `rayon::prelude`. Otherwise,
-//! this is an empty module.
+//! When `concurrent` feature is enabled, this module re-exports `rayon::prelude` together with
+//! rayon's thread pool types. Otherwise, this is an empty module.
+//!
+//! All parallel iterators created via the macros of this module are executed in the current
+//! rayon thread pool, which is the global pool unless the computation is run via
+//! `ThreadPool::install()`.
+//!
+//! The way in which [batch_iter_mut!](crate::batch_iter_mut!) splits a slice into batches depends
+//! on the number of threads in the pool. When the same split is needed regardless of the number of
+//! threads and of whether the `concurrent` feature is enabled (e.g., to get identical intermediate
+//! values when debugging transcripts of parallel and sequential builds),
+//! [chunk_iter_mut!](crate::chunk_iter_mut!) can be used instead; it splits a slice into chunks of
+//! a size which depends only on the length of the slice.
 
 #[cfg(feature = "concurrent")]
-pub use rayon::{current_num_threads as rayon_num_threads, prelude::*};
+pub use rayon::{
+    current_num_threads as rayon_num_threads, prelude::*, ThreadPool, ThreadPoolBuilder,
+};
+
+// DETERMINISTIC CHUNKING
+// ================================================================================================
+
+/// Maximum number of chunks into which a slice is split by
+/// [chunk_iter_mut!](crate::chunk_iter_mut!), unless a larger number of chunks is implied by the
+/// minimum chunk size.
+pub const MAX_NUM_CHUNKS: usize = 64;
+
+/// Returns the size of chunks into which [chunk_iter_mut!](crate::chunk_iter_mut!) splits a slice
+/// of the specified length.
+///
+/// The chunk size depends only on `len` and `min_chunk_size`: it is the smallest size which splits
+/// the slice into at most [MAX_NUM_CHUNKS] chunks, but it is never smaller than `min_chunk_size`
+/// (or 1). For lengths which are powers of two, the chunk size is a power of two as well, as long
+/// as `min_chunk_size` is a power of two.
+pub fn chunk_size(len: usize, min_chunk_size: usize) -> usize {
+    len.div_ceil(MAX_NUM_CHUNKS).max(min_chunk_size).max(1)
+}
 
 /// Returns either a regular or a parallel iterator depending on whether `concurrent` feature
 /// is enabled.
@@ -115,3 +147,32 @@ macro_rules! batch_iter_mut {
         $c($e, 0);
     };
 }
+
+/// Applies a procedure to each chunk of the provided slice either in a single thread or multiple
+/// threads based on whether `concurrent` feature is enabled.
+///
+/// Unlike [batch_iter_mut!], the slice is always split into the same chunks: the size of the
+/// chunks is determined via [chunk_size()](crate::iterators::chunk_size) from the length of the
+/// slice and the specified `min_chunk_size`, and does not depend on the number of threads or on
+/// whether `concurrent` feature is enabled. When `concurrent` feature is enabled, the chunks are
+/// processed in multiple threads; otherwise, the chunks are processed one after another in a
+/// single thread.
+///
+/// The procedure is invoked with a chunk and the position of the first element of the chunk in
+/// the slice.
+#[macro_export]
+macro_rules! chunk_iter_mut {
+    ($e: expr, $min_chunk_size: expr, $c: expr) => {{
+        let chunk_size = $crate::iterators::chunk_size($e.len(), $min_chunk_size);
+
+        #[cfg(feature = "concurrent")]
+        $e.par_chunks_mut(chunk_size).enumerate().for_each(|(i, chunk)| {
+            $c(chunk, i * chunk_size);
+        });
+
+        #[cfg(not(feature = "concurrent"))]
+        $e.chunks_mut(chunk_size).enumerate().for_each(|(i, chunk)| {
+            $c(chunk, i * chunk_size);
+        });
+    }};
+}
@@ -19,21 +19,33 @@ use alloc::vec::Vec;
 use core::{mem, slice};
 
 mod serde;
-#[cfg(feature = "std")]
-pub use serde::ReadAdapter;
+#[cfg(feature = "async")]
+pub use serde::{AsyncReadAdapter, AsyncWriteAdapter};
 pub use serde::{ByteReader, ByteWriter, Deserializable, Serializable, SliceReader};
+#[cfg(feature = "std")]
+pub use serde::{ReadAdapter, WriteAdapter};
 
 mod errors;
 pub use errors::DeserializationError;
 
+mod arena;
+pub use arena::Arena;
+
+mod buffer;
+pub use buffer::{UninitBuffer, UninitChunk};
+
+#[cfg(feature = "encoding")]
+pub mod encoding;
+
+#[cfg(feature = "serde")]
+pub mod serde_compat;
+
 #[cfg(test)]
 mod tests;
 
 // FEATURE-BASED RE-EXPORTS
 // ================================================================================================
 
-#[cfg(feature = "concurrent")]
-use iterators::*;
 #[cfg(feature = "concurrent")]
 pub use rayon;
 
@@ -73,10 +85,13 @@ impl<const N: usize> AsBytes for [[u8; N]] {
 /// Returns a vector of the specified length with un-initialized memory.
 ///
 /// This is usually faster than requesting a vector with initialized memory and is useful when we
-/// overwrite all contents of the vector immediately after memory allocation.
+/// overwrite all contents of the vector immediately after memory allocation. However, the
+/// returned vector exposes un-initialized values through safe code; [UninitBuffer] provides the
+/// same benefit without this hazard and should be used instead.
 ///
 /// # Safety
 /// Using values from the returned vector before initializing them will lead to undefined behavior.
+#[deprecated(since = "0.9.2", note = "use UninitBuffer instead")]
 #[allow(clippy::uninit_vec)]
 pub unsafe fn uninit_vector<T>(length: usize) -> Vec<T> {
     let mut vector = Vec::with_capacity(length);
@@ -84,6 +99,30 @@ pub unsafe fn uninit_vector<T>(length: usize) -> Vec<T> {
     vector
 }
 
+// CONSTANT-TIME COMPARISON
+// ================================================================================================
+
+/// Returns true if the two provided byte slices are equal.
+///
+/// For slices of the same length, all bytes of both slices are always compared; i.e., the time it
+/// takes to compare the slices does not depend on the position of the first mismatched byte.
+/// Slices of different lengths are never equal (and their lengths are not secret).
+///
+/// # Example
+/// ```
+/// # use winter_utils::constant_time_eq;
+/// assert!(constant_time_eq(&[1, 2, 3], &[1, 2, 3]));
+/// assert!(!constant_time_eq(&[1, 2, 3], &[1, 2, 4]));
+/// assert!(!constant_time_eq(&[1, 2, 3], &[1, 2]));
+/// ```
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    core::hint::black_box(diff) == 0
+}
+
 // GROUPING / UN-GROUPING FUNCTIONS
 // ================================================================================================
 
@@ -173,13 +212,11 @@ pub fn transpose_slice<T: Copy + Send + Sync, const N: usize>(source: &[T]) -> V
         source.len()
     );
 
-    let mut result: Vec<[T; N]> = unsafe { uninit_vector(row_count) };
-    iter_mut!(result, 1024).enumerate().for_each(|(i, element)| {
-        for j in 0..N {
-            element[j] = source[i + j * row_count]
+    UninitBuffer::new(row_count).fill_batches(1024, |batch, batch_offset| {
+        for i in batch_offset..batch_offset + batch.len() {
+            batch.push(core::array::from_fn(|j| source[i + j * row_count]));
         }
-    });
-    result
+    })
 }
 
 // RANDOMNESS
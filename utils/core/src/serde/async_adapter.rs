@@ -0,0 +1,210 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::{string::ToString, vec::Vec};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::{ByteReader, Deserializable, DeserializationError, Serializable, SliceReader};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bytes requested from the underlying reader when the adapter buffer is first filled.
+const MIN_READ_SIZE: usize = 4096;
+
+// ASYNC READ ADAPTER
+// ================================================================================================
+
+/// An adapter which reads [Deserializable] values from any type that implements
+/// [tokio::io::AsyncRead].
+///
+/// Bytes are read from the underlying reader as they become available, and a value is
+/// deserialized as soon as all of its bytes have been received; the executor is not blocked while
+/// waiting for the remaining bytes. Bytes received past the end of a value are retained by the
+/// adapter and are used when reading the next value, and thus, several values can be read from
+/// the same stream.
+pub struct AsyncReadAdapter<R> {
+    reader: R,
+    // Bytes received from `reader` which have not been consumed yet.
+    buf: Vec<u8>,
+    // This is set once `reader` has reported end-of-file.
+    eof: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncReadAdapter<R> {
+    /// Creates a new adapter for the specified implementation of [tokio::io::AsyncRead].
+    pub fn new(reader: R) -> Self {
+        Self { reader, buf: Vec::new(), eof: false }
+    }
+
+    /// Reads the next value of type `T` from the underlying reader.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The underlying reader reached end-of-file before all bytes of the value were received.
+    /// * Reading from the underlying reader failed.
+    /// * The received bytes do not represent a valid value of type `T`.
+    pub async fn read<T: Deserializable>(&mut self) -> Result<T, DeserializationError> {
+        let mut read_size = MIN_READ_SIZE;
+        loop {
+            let mut source = SliceReader::new(&self.buf);
+            match T::read_from(&mut source) {
+                Ok(value) => {
+                    let num_consumed = source.position().expect("slice reader tracks its position");
+                    self.buf.drain(..num_consumed);
+                    return Ok(value);
+                },
+                // the value may be incomplete; request more bytes from the underlying reader and
+                // retry, unless the reader has nothing more to give
+                Err(err)
+                    if err.root_cause() == &DeserializationError::UnexpectedEOF && !self.eof =>
+                {
+                    self.fill_buf(read_size).await?;
+                    // grow the requested size geometrically so that large values which are
+                    // readily available are received (and re-read) in few chunks
+                    read_size = read_size.max(self.buf.len());
+                },
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Returns true if there are more bytes to read from the adapter.
+    ///
+    /// Unlike a synchronous [ByteReader], this may need to wait for the
+    /// underlying reader to determine whether it has reached end-of-file.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the underlying reader failed.
+    pub async fn has_more_bytes(&mut self) -> Result<bool, DeserializationError> {
+        if self.buf.is_empty() && !self.eof {
+            self.fill_buf(MIN_READ_SIZE).await?;
+        }
+        Ok(!self.buf.is_empty())
+    }
+
+    /// Returns the underlying reader together with the bytes which have been received from it but
+    /// have not been consumed yet.
+    pub fn into_inner(self) -> (R, Vec<u8>) {
+        (self.reader, self.buf)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Reads up to `num_bytes` bytes from the underlying reader and appends them to the buffer.
+    async fn fill_buf(&mut self, num_bytes: usize) -> Result<(), DeserializationError> {
+        let len = self.buf.len();
+        self.buf.resize(len + num_bytes, 0);
+        let result = self.reader.read(&mut self.buf[len..]).await;
+        let num_read = *result.as_ref().unwrap_or(&0);
+        self.buf.truncate(len + num_read);
+        match result {
+            Ok(0) => self.eof = true,
+            Ok(_) => (),
+            Err(err) => return Err(DeserializationError::UnknownError(err.to_string())),
+        }
+        Ok(())
+    }
+}
+
+// ASYNC WRITE ADAPTER
+// ================================================================================================
+
+/// An adapter which writes [Serializable] values into any type that implements
+/// [tokio::io::AsyncWrite].
+///
+/// A value is first serialized into a buffer owned by the adapter (the buffer is reused across
+/// writes), and the resulting bytes are then written into the underlying writer without blocking
+/// the executor.
+pub struct AsyncWriteAdapter<W> {
+    writer: W,
+    buf: Vec<u8>,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWriteAdapter<W> {
+    /// Creates a new adapter for the specified implementation of [tokio::io::AsyncWrite].
+    pub fn new(writer: W) -> Self {
+        Self { writer, buf: Vec::new() }
+    }
+
+    /// Serializes the specified value and writes the resulting bytes into the underlying writer.
+    ///
+    /// # Errors
+    /// Returns an error if writing into the underlying writer failed.
+    pub async fn write<T: Serializable + ?Sized>(&mut self, value: &T) -> std::io::Result<()> {
+        self.buf.clear();
+        self.buf.reserve(value.get_size_hint());
+        value.write_into(&mut self.buf);
+        self.writer.write_all(&self.buf).await
+    }
+
+    /// Flushes the underlying writer.
+    ///
+    /// # Errors
+    /// Returns an error if flushing the underlying writer failed.
+    pub async fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush().await
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<F: core::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+    }
+
+    #[test]
+    fn async_adapters_round_trip() {
+        let values: Vec<Vec<u64>> = vec![vec![], vec![1, 2, 3], (0..10_000).collect()];
+
+        // use a small pipe so that writes have to wait for the reader, and values are received
+        // in many chunks
+        let (client, server) = tokio::io::duplex(64);
+        let received = block_on(async {
+            let writer = async {
+                let mut writer = AsyncWriteAdapter::new(client);
+                for value in values.iter() {
+                    writer.write(value).await.unwrap();
+                }
+                writer.flush().await.unwrap();
+            };
+            let reader = async {
+                let mut reader = AsyncReadAdapter::new(server);
+                let mut received = Vec::new();
+                while reader.has_more_bytes().await.unwrap() {
+                    received.push(reader.read::<Vec<u64>>().await.unwrap());
+                }
+                received
+            };
+            tokio::join!(writer, reader).1
+        });
+        assert_eq!(values, received);
+    }
+
+    #[test]
+    fn async_read_adapter_errors() {
+        // a value which is cut short should result in an EOF error
+        let mut bytes = vec![1u64, 2, 3].to_bytes();
+        bytes.truncate(bytes.len() - 1);
+        let result = block_on(AsyncReadAdapter::new(bytes.as_slice()).read::<Vec<u64>>());
+        assert_eq!(&DeserializationError::UnexpectedEOF, result.unwrap_err().root_cause());
+
+        // invalid bytes should be rejected without waiting for more input
+        let result = block_on(AsyncReadAdapter::new([2u8].as_slice()).read::<Option<u64>>());
+        assert!(matches!(result, Err(DeserializationError::InvalidValue(_))));
+    }
+}
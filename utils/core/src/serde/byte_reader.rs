@@ -8,6 +8,7 @@ use alloc::string::ToString;
 use alloc::{string::String, vec::Vec};
 #[cfg(feature = "std")]
 use core::cell::{Ref, RefCell};
+use core::mem::MaybeUninit;
 #[cfg(feature = "std")]
 use std::io::BufRead;
 
@@ -64,6 +65,45 @@ pub trait ByteReader {
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns the number of bytes read from `self` so far, if the reader keeps track of it.
+    ///
+    /// The position is used to report where in the input a deserialization error has occurred.
+    /// By default, this returns `None`.
+    fn position(&self) -> Option<usize> {
+        None
+    }
+
+    /// Reads a value from `self` using the specified `read` function, and annotates any error
+    /// returned by it with the specified field `name` and the current position of the reader.
+    ///
+    /// Nested annotations are combined into a path, and thus, the resulting errors identify the
+    /// exact part of a value which could not be read (see [DeserializationError::in_field()]).
+    ///
+    /// # Errors
+    /// Returns the error returned by `read`, if any.
+    fn read_field<T, F>(&mut self, name: &str, read: F) -> Result<T, DeserializationError>
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self) -> Result<T, DeserializationError>,
+    {
+        read(self).map_err(|err| err.in_field(name, self.position()))
+    }
+
+    /// Reads a value from `self` using the specified `read` function, and annotates any error
+    /// returned by it with the specified element `index` and the current position of the reader.
+    ///
+    /// See [read_field()](ByteReader::read_field) for details.
+    ///
+    /// # Errors
+    /// Returns the error returned by `read`, if any.
+    fn read_indexed<T, F>(&mut self, index: usize, read: F) -> Result<T, DeserializationError>
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self) -> Result<T, DeserializationError>,
+    {
+        read(self).map_err(|err| err.in_element(index, self.position()))
+    }
+
     /// Returns a boolean value read from `self` consuming 1 byte from the reader.
     ///
     /// # Errors
@@ -198,6 +238,32 @@ pub trait ByteReader {
         }
         Ok(result)
     }
+
+    /// Reads a sequence of bytes from `self`, deserializes these bytes into `target.len()`
+    /// elements of type `D`, and writes the elements into the specified `target`.
+    ///
+    /// This allows reading many elements directly into uninitialized memory (e.g., the spare
+    /// capacity of a vector), and thus, avoids initializing large buffers twice.
+    ///
+    /// If this function returns `Ok`, all elements of `target` have been initialized. If an error
+    /// is returned, the elements of `target` may be only partially initialized; the elements which
+    /// have been written are not dropped.
+    ///
+    /// # Errors
+    /// Returns a [DeserializationError] if `target.len()` elements could not be read from `self`.
+    fn read_many_into<D>(
+        &mut self,
+        target: &mut [MaybeUninit<D>],
+    ) -> Result<(), DeserializationError>
+    where
+        Self: Sized,
+        D: Deserializable,
+    {
+        for element in target.iter_mut() {
+            element.write(D::read_from(self)?);
+        }
+        Ok(())
+    }
 }
 
 // STANDARD LIBRARY ADAPTER
@@ -636,6 +702,22 @@ impl<'a> SliceReader<'a> {
     pub fn new(source: &'a [u8]) -> Self {
         SliceReader { source, pos: 0 }
     }
+
+    /// Returns a slice of the next `len` bytes of the underlying source, and advances the reader
+    /// past these bytes.
+    ///
+    /// Unlike [read_slice()](ByteReader::read_slice), the returned slice borrows the underlying
+    /// source rather than the reader, and thus, it can outlive the reader. This makes it possible
+    /// to build borrowed views of values without copying their bytes.
+    ///
+    /// # Errors
+    /// Returns an error if fewer than `len` bytes remain in the source.
+    pub fn read_borrowed_slice(&mut self, len: usize) -> Result<&'a [u8], DeserializationError> {
+        self.check_eor(len)?;
+        let result = &self.source[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(result)
+    }
 }
 
 impl<'a> ByteReader for SliceReader<'a> {
@@ -676,6 +758,10 @@ impl<'a> ByteReader for SliceReader<'a> {
     fn has_more_bytes(&self) -> bool {
         self.pos < self.source.len()
     }
+
+    fn position(&self) -> Option<usize> {
+        Some(self.pos)
+    }
 }
 
 #[cfg(all(test, feature = "std"))]
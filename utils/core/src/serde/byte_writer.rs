@@ -138,6 +138,95 @@ impl ByteWriter for alloc::vec::Vec<u8> {
     }
 }
 
+/// Writes bytes into the beginning of the slice, and advances the slice past the written bytes
+/// (in the same way as [std::io::Write] is implemented for `&mut [u8]`).
+///
+/// With the `std` feature enabled, this is covered by the implementation for [std::io::Write].
+#[cfg(not(feature = "std"))]
+impl ByteWriter for &mut [u8] {
+    fn write_u8(&mut self, value: u8) {
+        self.write_bytes(&[value]);
+    }
+
+    fn write_bytes(&mut self, values: &[u8]) {
+        assert!(values.len() <= self.len(), "write failed: not enough space in the target slice");
+        let (head, tail) = core::mem::take(self).split_at_mut(values.len());
+        head.copy_from_slice(values);
+        *self = tail;
+    }
+}
+
+// STANDARD LIBRARY ADAPTER
+// ================================================================================================
+
+/// An adapter of [ByteWriter] to any type that implements [std::io::Write] which, unlike the
+/// implementation of [ByteWriter] for [std::io::Write], does not panic when writing fails.
+///
+/// Instead, the first error returned by the underlying writer is retained, all subsequent writes
+/// are ignored, and the error is returned by [finish()](WriteAdapter::finish).
+#[cfg(feature = "std")]
+pub struct WriteAdapter<'a> {
+    writer: &'a mut dyn std::io::Write,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<'a> WriteAdapter<'a> {
+    /// Creates a new [ByteWriter] adapter for the given implementation of [std::io::Write].
+    pub fn new(writer: &'a mut dyn std::io::Write) -> Self {
+        Self { writer, error: None }
+    }
+
+    /// Serializes the specified value into the underlying writer, and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if writing into the underlying writer failed.
+    pub fn write_value<S: Serializable + ?Sized>(mut self, value: &S) -> std::io::Result<()> {
+        value.write_into(&mut self);
+        self.finish()
+    }
+
+    /// Consumes the adapter and returns the first error which occurred while writing into the
+    /// underlying writer, if any.
+    pub fn finish(self) -> std::io::Result<()> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ByteWriter for WriteAdapter<'_> {
+    fn write_u8(&mut self, value: u8) {
+        self.write_bytes(&[value]);
+    }
+
+    fn write_bytes(&mut self, values: &[u8]) {
+        if self.error.is_none() {
+            if let Err(error) = self.writer.write_all(values) {
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+// BYTE COUNTER
+// ================================================================================================
+
+/// A [ByteWriter] which discards the written bytes, and only counts them.
+pub(super) struct ByteCounter(pub usize);
+
+impl ByteWriter for ByteCounter {
+    fn write_u8(&mut self, _value: u8) {
+        self.0 += 1;
+    }
+
+    fn write_bytes(&mut self, values: &[u8]) {
+        self.0 += values.len();
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -168,4 +257,36 @@ mod tests {
         let mut writer = Cursor::new([0; 2]);
         writer.write_bytes(b"nope");
     }
+
+    #[test]
+    fn write_into_slice() {
+        let mut buf = [0u8; 8];
+        let mut target = &mut buf[..];
+        target.write_u8(1);
+        target.write_bytes(b"nope");
+        assert_eq!(3, target.len());
+        assert_eq!(&[1, b'n', b'o', b'p', b'e', 0, 0, 0], &buf);
+    }
+
+    #[test]
+    #[should_panic]
+    fn write_into_slice_out_of_capacity() {
+        let mut buf = [0u8; 2];
+        let mut target = &mut buf[..];
+        target.write_bytes(b"nope");
+    }
+
+    #[test]
+    fn write_adapter_errors() {
+        let mut buf = [0u8; 16];
+        let mut writer = &mut buf[..];
+        WriteAdapter::new(&mut writer).write_value(&[1u64, 2]).unwrap();
+        assert_eq!(&1u64.to_le_bytes(), &buf[..8]);
+
+        // writing past the end of the slice returns an error instead of panicking
+        let mut buf = [0u8; 8];
+        let mut writer = &mut buf[..];
+        let result = WriteAdapter::new(&mut writer).write_value(&[1u64, 2]);
+        assert_eq!(std::io::ErrorKind::WriteZero, result.unwrap_err().kind());
+    }
 }
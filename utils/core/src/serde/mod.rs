@@ -17,7 +17,15 @@ pub use byte_reader::ReadAdapter;
 pub use byte_reader::{ByteReader, SliceReader};
 
 mod byte_writer;
+use byte_writer::ByteCounter;
 pub use byte_writer::ByteWriter;
+#[cfg(feature = "std")]
+pub use byte_writer::WriteAdapter;
+
+#[cfg(feature = "async")]
+mod async_adapter;
+#[cfg(feature = "async")]
+pub use async_adapter::{AsyncReadAdapter, AsyncWriteAdapter};
 
 // SERIALIZABLE TRAIT
 // ================================================================================================
@@ -45,6 +53,17 @@ pub trait Serializable {
     fn get_size_hint(&self) -> usize {
         0
     }
+
+    /// Returns the exact number of bytes into which `self` is serialized.
+    ///
+    /// This can be used to pre-size a buffer before serializing `self` into it (e.g., via the
+    /// implementation of [ByteWriter] for `&mut [u8]`). The default implementation serializes
+    /// `self` into a writer which only counts the written bytes, and thus, does not allocate.
+    fn serialized_size(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        self.write_into(&mut counter);
+        counter.0
+    }
 }
 
 impl<T: Serializable> Serializable for &T {
@@ -0,0 +1,91 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Contains functions for implementing [serde](https://serde.rs) traits for types which implement
+//! [Serializable] and [Deserializable].
+//!
+//! Values are encoded using their canonical binary representation (i.e., the one produced by
+//! [Serializable::to_bytes()]). For human-readable formats (e.g., JSON), the bytes are encoded as
+//! a lowercase hex string (see [encoding](crate::encoding)); for binary formats (e.g., CBOR), the
+//! bytes are encoded as a byte array. Thus, a value deserialized via serde is validated in the
+//! same way as a value read from bytes.
+//!
+//! The functions can be used to implement serde traits directly, or via the field attribute
+//! `#[serde(with = "winter_utils::serde_compat")]`.
+
+use alloc::vec::Vec;
+use core::{fmt, marker::PhantomData};
+
+use ::serde::{de, Deserializer, Serializer};
+
+use crate::{
+    encoding::{decode_hex, encode_hex},
+    Deserializable, Serializable,
+};
+
+// SERIALIZATION
+// ================================================================================================
+
+/// Serializes the specified value with the provided serde serializer.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serializable,
+    S: Serializer,
+{
+    let bytes = value.to_bytes();
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&encode_hex(&bytes))
+    } else {
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+/// Deserializes a value from the provided serde deserializer.
+///
+/// # Errors
+/// Returns an error if the input is not a valid encoding of a byte sequence, or if the bytes do
+/// not represent a valid value of type `T`.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Deserializable,
+    D: Deserializer<'de>,
+{
+    let visitor = BytesVisitor(PhantomData);
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(visitor)
+    } else {
+        deserializer.deserialize_bytes(visitor)
+    }
+}
+
+// BYTES VISITOR
+// ================================================================================================
+
+struct BytesVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserializable> de::Visitor<'de> for BytesVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a hex string or a byte array")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        let bytes = decode_hex(value).map_err(E::custom)?;
+        self.visit_bytes(&bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<T, E> {
+        T::read_from_bytes(value).map_err(E::custom)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
@@ -7,7 +7,12 @@ use alloc::vec::Vec;
 
 use proptest::prelude::{any, proptest};
 
-use super::{ByteReader, ByteWriter, Serializable, SliceReader};
+#[cfg(feature = "concurrent")]
+use super::iterators::*;
+use super::{
+    chunk_iter_mut, iterators, Arena, ByteReader, ByteWriter, DeserializationError, Serializable,
+    SliceReader, UninitBuffer,
+};
 
 // SLICE READER TESTS
 // ================================================================================================
@@ -69,6 +74,67 @@ fn read_u8_vec() {
     assert!(a.read_vec(2).is_err());
 }
 
+#[test]
+fn read_many_into() {
+    let source = [1u8, 0, 2, 0, 3, 0];
+
+    let mut values = Vec::<u16>::with_capacity(3);
+    let mut a = SliceReader::new(&source);
+    a.read_many_into(&mut values.spare_capacity_mut()[..3]).unwrap();
+    // SAFETY: all 3 elements were initialized by the successful read above
+    unsafe { values.set_len(3) };
+    assert_eq!(vec![1u16, 2, 3], values);
+    assert!(!a.has_more_bytes());
+
+    let mut values = Vec::<u16>::with_capacity(4);
+    let mut a = SliceReader::new(&source);
+    assert!(a.read_many_into(&mut values.spare_capacity_mut()[..4]).is_err());
+}
+
+#[test]
+fn read_borrowed_slice() {
+    let source = [1u8, 3, 5, 7];
+    let mut a = SliceReader::new(&source);
+
+    // the borrowed slice remains usable while the reader is advanced
+    let first = a.read_borrowed_slice(3).unwrap();
+    assert_eq!(7, a.read_u8().unwrap());
+    assert_eq!(&[1, 3, 5], first);
+
+    let mut a = SliceReader::new(&source);
+    assert!(a.read_borrowed_slice(5).is_err());
+}
+
+// DESERIALIZATION ERROR TESTS
+// ================================================================================================
+
+#[test]
+fn deserialization_error_context() {
+    use alloc::string::ToString;
+
+    let source = [1u8, 2, 3];
+    let mut reader = SliceReader::new(&source);
+    let err = reader
+        .read_field("layers", |reader| {
+            reader.read_u8()?;
+            reader.read_indexed(3, |reader| reader.read_field("paths", |reader| reader.read_u32()))
+        })
+        .unwrap_err()
+        .in_field("fri_proof", None);
+
+    assert_eq!(&DeserializationError::UnexpectedEOF, err.root_cause());
+    assert_eq!(Some("fri_proof.layers[3].paths"), err.path());
+    assert_eq!(Some(1), err.offset());
+    assert_eq!(
+        "failed to read fri_proof.layers[3].paths at byte 1: unexpected EOF",
+        err.to_string()
+    );
+
+    // errors without context have neither a path nor an offset
+    assert_eq!(None, DeserializationError::UnexpectedEOF.path());
+    assert_eq!(None, DeserializationError::UnexpectedEOF.offset());
+}
+
 // SERIALIZATION TESTS
 // ================================================================================================
 
@@ -87,6 +153,20 @@ fn write_serializable() {
     assert_eq!(234567u128, reader.read_u128().unwrap());
 }
 
+#[test]
+fn serialized_size() {
+    let value = (vec![1u64, 2, 3], 234567usize, Some(7u8));
+    let size = value.serialized_size();
+    assert_eq!(value.to_bytes().len(), size);
+
+    // a value can be serialized into a buffer of exactly the reported size
+    let mut buf = vec![0u8; size];
+    let mut target = buf.as_mut_slice();
+    value.write_into(&mut target);
+    assert!(target.is_empty());
+    assert_eq!(value.to_bytes(), buf);
+}
+
 #[test]
 fn write_serializable_usize() {
     let mut target: Vec<u8> = Vec::new();
@@ -146,6 +226,201 @@ fn write_serializable_array_batch() {
     }
 }
 
+// ARENA TESTS
+// ================================================================================================
+
+#[test]
+fn arena_allocations() {
+    let arena = Arena::new();
+    assert_eq!(0, arena.capacity());
+
+    let a = arena.alloc_slice_fill(3, 7u8);
+    let b = arena.alloc_slice_copy(&[1u128, 2, 3]);
+    let c = arena.alloc_slice_fill(0, 0u64);
+    assert_eq!(&[7, 7, 7], a);
+    assert_eq!(&[1, 2, 3], b);
+    assert!(c.is_empty());
+    assert_eq!(0, b.as_ptr() as usize % core::mem::align_of::<u128>());
+
+    // writing into one allocation does not affect the others
+    a[1] = 0;
+    b[0] = 5;
+    assert_eq!(&[7, 0, 7], a);
+    assert_eq!(&[5, 2, 3], b);
+    assert_eq!(3 + 48, arena.allocated_bytes());
+}
+
+#[test]
+fn arena_reset_reuses_memory() {
+    let mut arena = Arena::with_capacity(64);
+    assert_eq!(64, arena.capacity());
+
+    // allocations which do not fit into the current chunk are served from new chunks
+    let a = arena.alloc_slice_fill(8, 1u64);
+    let b = arena.alloc_slice_fill(1000, 2u64);
+    assert_eq!(&[1; 8], a);
+    assert_eq!(&[2; 1000][..], b);
+    let capacity = arena.capacity();
+    assert!(capacity >= 64 + 8000);
+
+    // after a reset, the same allocations are served from a single chunk
+    arena.reset();
+    assert_eq!(0, arena.allocated_bytes());
+    assert_eq!(capacity, arena.capacity());
+    let a = arena.alloc_slice_fill(8, 3u64);
+    let b = arena.alloc_slice_fill(1000, 4u64);
+    assert_eq!(&[3; 8], a);
+    assert_eq!(&[4; 1000][..], b);
+    assert_eq!(capacity, arena.capacity());
+}
+
+// ITERATOR TESTS
+// ================================================================================================
+
+#[test]
+fn chunk_iter_mut_is_deterministic() {
+    assert_eq!(1, iterators::chunk_size(0, 0));
+    assert_eq!(16, iterators::chunk_size(1024, 4));
+    assert_eq!(100, iterators::chunk_size(1024, 100));
+    assert_eq!(2, iterators::chunk_size(65, 1));
+
+    // every chunk records the offset of its first element; the result is the same with and
+    // without the concurrent feature
+    let mut values = vec![0usize; 1000];
+    chunk_iter_mut!(&mut values, 1, |chunk: &mut [usize], offset: usize| {
+        chunk.fill(offset);
+    });
+    let expected = (0..1000).map(|i| i - i % 16).collect::<Vec<_>>();
+    assert_eq!(expected, values);
+}
+
+// UNINIT BUFFER TESTS
+// ================================================================================================
+
+#[test]
+fn uninit_buffer_push() {
+    let mut buffer = UninitBuffer::new(3);
+    buffer.push(1u64);
+    buffer.extend([2, 3]);
+    assert!(buffer.is_full());
+    assert_eq!(vec![1, 2, 3], buffer.into_vec());
+
+    // a partially initialized buffer cannot be converted into a vector
+    let mut buffer = UninitBuffer::new(2);
+    buffer.push(1u64);
+    let buffer = buffer.try_into_vec().unwrap_err();
+    assert_eq!(1, buffer.num_initialized());
+    assert_eq!(&[1], buffer.as_slice());
+}
+
+#[test]
+fn uninit_buffer_fill() {
+    let values = UninitBuffer::new(10).fill_chunks(4, |i, chunk| {
+        let len = chunk.len();
+        chunk.extend((0..len).map(|j| (i * 4 + j) as u64));
+    });
+    assert_eq!((0..10).collect::<Vec<u64>>(), values);
+
+    let values = UninitBuffer::new(5000).fill_batches(16, |batch, offset| {
+        let len = batch.len();
+        batch.extend((offset..offset + len).map(|i| i as u64 * 2));
+    });
+    assert_eq!((0..5000).map(|i| i * 2).collect::<Vec<u64>>(), values);
+}
+
+#[test]
+#[should_panic]
+fn uninit_buffer_fill_incomplete_chunk() {
+    UninitBuffer::new(4).fill_chunks(2, |i, chunk| {
+        if i == 0 {
+            chunk.extend([1u8, 2]);
+        }
+    });
+}
+
+// ENCODING TESTS
+// ================================================================================================
+
+#[cfg(feature = "encoding")]
+#[test]
+fn encoding_base64_test_vectors() {
+    use super::encoding::{decode_base64, encode_base64};
+
+    // test vectors from RFC 4648
+    let vectors = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+    for (bytes, encoded) in vectors {
+        assert_eq!(encoded, encode_base64(bytes.as_bytes()));
+        assert_eq!(bytes.as_bytes(), decode_base64(encoded).unwrap());
+    }
+
+    // non-canonical encodings are rejected
+    for encoded in ["Zg", "Zh==", "Zm9=", "Zg==Zm9v", "Z===", "Zm9v YmFy", "Zm9v\n"] {
+        assert!(decode_base64(encoded).is_err(), "{encoded}");
+    }
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn encoding_round_trip() {
+    use super::encoding;
+
+    let value: Vec<u64> = vec![1, 2, u64::MAX];
+    let hex = encoding::to_hex(&value);
+    assert_eq!(encode_hex(&value.to_bytes()), hex);
+    assert_eq!(value, encoding::from_hex::<Vec<u64>>(&hex).unwrap());
+    let base64 = encoding::to_base64(&value);
+    assert_eq!(value, encoding::from_base64::<Vec<u64>>(&base64).unwrap());
+
+    // uppercase digits and prefixes are rejected
+    assert!(encoding::from_hex::<u8>("0A").is_err());
+    assert!(encoding::from_hex::<u8>("0x0a").is_err());
+    assert_eq!(10, encoding::from_hex::<u8>("0a").unwrap());
+
+    // trailing bytes are rejected
+    assert_eq!(Err(DeserializationError::UnconsumedBytes), encoding::from_hex::<u8>("0a0b"));
+}
+
+// SERDE TESTS
+// ================================================================================================
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_compat_round_trip() {
+    use super::serde_compat;
+
+    let value: Vec<u64> = vec![1, 2, u64::MAX];
+
+    let mut json = Vec::new();
+    serde_compat::serialize(&value, &mut serde_json::Serializer::new(&mut json)).unwrap();
+    let json = alloc::string::String::from_utf8(json).unwrap();
+    assert_eq!(format!("\"{}\"", encode_hex(&value.to_bytes())), json);
+
+    let mut deserializer = serde_json::Deserializer::from_str(&json);
+    let result: Vec<u64> = serde_compat::deserialize(&mut deserializer).unwrap();
+    assert_eq!(value, result);
+
+    // bytes which do not encode a valid value should be rejected
+    let mut deserializer = serde_json::Deserializer::from_str("\"0300\"");
+    assert!(serde_compat::deserialize::<Vec<u64>, _>(&mut deserializer).is_err());
+
+    // strings which are not valid hex should be rejected
+    let mut deserializer = serde_json::Deserializer::from_str("\"0g\"");
+    assert!(serde_compat::deserialize::<Vec<u64>, _>(&mut deserializer).is_err());
+}
+
+#[cfg(feature = "encoding")]
+fn encode_hex(bytes: &[u8]) -> alloc::string::String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
 // UTILS - RANDOMIZED - UINT SERIALIZATION AND DESERIALIZATION
 // ================================================================================================
 proptest! {
@@ -6,25 +6,30 @@
 use alloc::{string::ToString, vec::Vec};
 
 use air::{
-    proof::{Proof, Queries, Table, TraceOodFrame},
-    Air,
+    proof::{ProofRef, QueriesRef, Table, TraceOodFrame},
+    Air, Transcript,
 };
-use crypto::{BatchMerkleProof, ElementHasher, MerkleTree};
+use crypto::{BatchMerkleProof, Digest, ElementHasher, MerkleTree};
 use fri::VerifierChannel as FriVerifierChannel;
 use math::{FieldElement, StarkField};
+use utils::{constant_time_eq, Serializable};
 
-use crate::VerifierError;
+#[cfg(feature = "diagnostics")]
+use crate::diagnostics::{ErrorContext, MerklePathMismatch};
+use crate::{diagnostics::ErrorContextSlot, Checks, VerifierError};
 
 // VERIFIER CHANNEL
 // ================================================================================================
 
-/// A view into a [Proof] for a computation structured to simulate an "interactive" channel.
+/// A view into a [Proof](air::proof::Proof) for a computation structured to simulate an
+/// "interactive" channel.
 ///
 /// A channel is instantiated for a specific proof, which is parsed into structs over the
 /// appropriate field (specified by type parameter `E`). This also validates that the proof is
 /// well-formed in the context of the computation for the specified [Air].
 pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
     // trace queries
+    preprocessed_trace_root: Option<H::Digest>,
     trace_roots: Vec<H::Digest>,
     trace_queries: Option<TraceQueries<E, H>>,
     // constraint queries
@@ -33,6 +38,8 @@ pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::Base
     // FRI proof
     fri_roots: Option<Vec<H::Digest>>,
     fri_layer_proofs: Vec<BatchMerkleProof<H>>,
+    #[cfg_attr(not(feature = "diagnostics"), allow(dead_code))]
+    num_fri_layers: usize,
     fri_layer_queries: Vec<Vec<E>>,
     fri_remainder: Option<Vec<E>>,
     fri_num_partitions: usize,
@@ -42,19 +49,33 @@ pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::Base
     // query proof-of-work
     pow_nonce: u64,
     gkr_proof: Option<Vec<u8>>,
+    // context of a verification failure
+    error_context: ErrorContextSlot,
+    // messages exchanged during the verification
+    transcript: Option<Transcript>,
 }
 
 impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChannel<E, H> {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     /// Creates and returns a new [VerifierChannel] initialized from the specified `proof`.
+    ///
+    /// The proof is provided as a borrowed view, and thus, the channel can be built directly from
+    /// a buffer holding a serialized proof (see [ProofRef::read_from_slice()]); field elements and
+    /// Merkle authentication paths are parsed straight from the bytes referenced by the view.
+    ///
+    /// If the main trace segment of the computation contains preprocessed columns, the commitment
+    /// to these columns must be provided via `preprocessed_trace_root` as this commitment is not
+    /// included in the proof.
     pub fn new<A: Air<BaseField = E::BaseField>>(
         air: &A,
-        proof: Proof,
+        proof: ProofRef<'_>,
+        preprocessed_trace_root: Option<H::Digest>,
     ) -> Result<Self, VerifierError> {
-        let Proof {
+        let ProofRef {
             context,
             num_unique_queries,
+            num_main_trace_tables: proof_num_main_trace_tables,
             commitments,
             trace_queries,
             constraint_queries,
@@ -62,6 +83,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
             fri_proof,
             pow_nonce,
             gkr_proof,
+            extensions: _,
         } = proof;
 
         // make sure AIR and proof base fields are the same
@@ -70,7 +92,17 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
         }
         let constraint_frame_width = air.context().num_constraint_composition_columns();
 
-        let num_trace_segments = air.trace_info().num_segments();
+        // make sure the main trace segment of the proof is partitioned into as many tables as
+        // specified by the AIR
+        let num_main_trace_tables = air.context().num_main_trace_tables();
+        if num_main_trace_tables != proof_num_main_trace_tables as usize {
+            return Err(VerifierError::ProofDeserializationError(format!(
+                "expected {num_main_trace_tables} main trace table(s), but the proof contained {proof_num_main_trace_tables}"
+            )));
+        }
+
+        // each table of the main trace segment is committed to separately
+        let num_trace_segments = air.trace_info().num_segments() + num_main_trace_tables - 1;
         let main_trace_width = air.trace_info().main_trace_width();
         let aux_trace_width = air.trace_info().aux_segment_width();
         let lde_domain_size = air.lde_domain_size();
@@ -82,6 +114,11 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
             .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
 
         // --- parse trace and constraint queries -------------------------------------------------
+        if num_unique_queries == 0 {
+            return Err(VerifierError::ProofDeserializationError(
+                "proof must contain at least one query".to_string(),
+            ));
+        }
         let trace_queries = TraceQueries::new(trace_queries, air, num_unique_queries as usize)?;
         let constraint_queries =
             ConstraintQueries::new(constraint_queries, air, num_unique_queries as usize)?;
@@ -92,7 +129,10 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
             .parse_remainder()
             .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
         let (fri_layer_queries, fri_layer_proofs) = fri_proof
-            .parse_layers::<H, E>(lde_domain_size, fri_options.folding_factor())
+            .parse_layers_with_folding_factors::<H, E>(
+                lde_domain_size,
+                &fri_options.folding_factors(lde_domain_size),
+            )
             .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
 
         // --- parse out-of-domain evaluation frame -----------------------------------------------
@@ -102,6 +142,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
 
         Ok(VerifierChannel {
             // trace queries
+            preprocessed_trace_root,
             trace_roots,
             trace_queries: Some(trace_queries),
             // constraint queries
@@ -109,6 +150,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
             constraint_queries: Some(constraint_queries),
             // FRI proof
             fri_roots: Some(fri_roots),
+            num_fri_layers: fri_layer_proofs.len(),
             fri_layer_proofs,
             fri_layer_queries,
             fri_remainder: Some(fri_remainder),
@@ -118,17 +160,27 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
             ood_constraint_evaluations: Some(ood_constraint_evaluations),
             // query seed
             pow_nonce,
-            gkr_proof,
+            gkr_proof: gkr_proof.map(<[u8]>::to_vec),
+            error_context: ErrorContextSlot::default(),
+            transcript: None,
         })
     }
 
     // DATA READERS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns the commitment to the preprocessed columns of the main trace segment, if any.
+    ///
+    /// This commitment is not sent by the prover; instead, it is known to the verifier in advance.
+    pub fn read_preprocessed_trace_commitment(&self) -> Option<H::Digest> {
+        self.preprocessed_trace_root
+    }
+
     /// Returns execution trace commitments sent by the prover.
     ///
     /// For computations requiring multiple trace segment, the returned slice will contain a
-    /// commitment for each trace segment.
+    /// commitment for each trace segment. If the main trace segment is partitioned into tables,
+    /// the slice starts with a commitment for each table.
     pub fn read_trace_commitments(&self) -> &[H::Digest] {
         &self.trace_roots
     }
@@ -166,20 +218,56 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
     /// Returns trace states at the specified positions of the LDE domain. This also checks if
     /// the trace states are valid against the trace commitment sent by the prover.
     ///
+    /// If the main trace segment contains preprocessed columns, the states of these columns are
+    /// checked against the commitment to the preprocessed columns, and are included in the
+    /// returned main trace states as the first columns.
+    ///
     /// For computations requiring multiple trace segments, trace states for auxiliary segments
     /// are also included as the second value of the returned tuple (trace states for all auxiliary
     /// segments are merged into a single table). Otherwise, the second value is None.
+    ///
+    /// The results of the checks are reported to `checks`; in constant-time mode, a failed check
+    /// does not cause an early return, and the commitments are compared in constant time.
     #[allow(clippy::type_complexity)]
     pub fn read_queried_trace_states(
         &mut self,
         positions: &[usize],
+        checks: &mut Checks,
     ) -> Result<(Table<E::BaseField>, Option<Table<E>>), VerifierError> {
         let queries = self.trace_queries.take().expect("already read");
 
+        // in constant-time mode, failed commitment checks do not cause an early return; thus, make
+        // sure that the number of queried states is as expected, as this depends only on the
+        // structure of the proof
+        if checks.is_constant_time() && queries.main_states.num_rows() != positions.len() {
+            return Err(VerifierError::TraceQueryDoesNotMatchCommitment);
+        }
+
         // make sure the states included in the proof correspond to the trace commitment
-        for (root, proof) in self.trace_roots.iter().zip(queries.query_proofs.iter()) {
-            MerkleTree::verify_batch(root, positions, proof)
-                .map_err(|_| VerifierError::TraceQueryDoesNotMatchCommitment)?;
+        #[cfg_attr(not(feature = "diagnostics"), allow(unused_variables))]
+        for (commitment, (root, proof)) in
+            self.trace_roots.iter().zip(queries.query_proofs.iter()).enumerate()
+        {
+            let is_valid = verify_batch(root, positions, proof, checks.is_constant_time());
+            #[cfg(feature = "diagnostics")]
+            if !is_valid {
+                self.error_context.record(ErrorContext::TraceQuery {
+                    commitment,
+                    mismatch: MerklePathMismatch::new(root, positions, proof),
+                });
+            }
+            checks.check(is_valid, VerifierError::TraceQueryDoesNotMatchCommitment)?;
+        }
+        if let Some(ref proof) = queries.preprocessed_query_proof {
+            let root = self.preprocessed_trace_root.as_ref().expect("no preprocessed trace root");
+            let is_valid = verify_batch(root, positions, proof, checks.is_constant_time());
+            #[cfg(feature = "diagnostics")]
+            if !is_valid {
+                self.error_context.record(ErrorContext::PreprocessedTraceQuery(
+                    MerklePathMismatch::new(root, positions, proof),
+                ));
+            }
+            checks.check(is_valid, VerifierError::TraceQueryDoesNotMatchCommitment)?;
         }
 
         Ok((queries.main_states, queries.aux_states))
@@ -188,17 +276,106 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
     /// Returns constraint evaluations at the specified positions of the LDE domain. This also
     /// checks if the constraint evaluations are valid against the constraint commitment sent by
     /// the prover.
+    ///
+    /// The result of the check is reported to `checks` in the same way as for
+    /// [read_queried_trace_states()](Self::read_queried_trace_states).
     pub fn read_constraint_evaluations(
         &mut self,
         positions: &[usize],
+        checks: &mut Checks,
     ) -> Result<Table<E>, VerifierError> {
         let queries = self.constraint_queries.take().expect("already read");
 
-        MerkleTree::verify_batch(&self.constraint_root, positions, &queries.query_proofs)
-            .map_err(|_| VerifierError::ConstraintQueryDoesNotMatchCommitment)?;
+        if checks.is_constant_time() && queries.evaluations.num_rows() != positions.len() {
+            return Err(VerifierError::ConstraintQueryDoesNotMatchCommitment);
+        }
+
+        let is_valid = verify_batch(
+            &self.constraint_root,
+            positions,
+            &queries.query_proofs,
+            checks.is_constant_time(),
+        );
+        #[cfg(feature = "diagnostics")]
+        if !is_valid {
+            self.error_context.record(ErrorContext::ConstraintQuery(MerklePathMismatch::new(
+                &self.constraint_root,
+                positions,
+                &queries.query_proofs,
+            )));
+        }
+        checks.check(is_valid, VerifierError::ConstraintQueryDoesNotMatchCommitment)?;
 
         Ok(queries.evaluations)
     }
+
+    // ERROR CONTEXT
+    // --------------------------------------------------------------------------------------------
+
+    /// Records the context of a verification failure; a context is recorded only for the first
+    /// failure.
+    #[cfg(feature = "diagnostics")]
+    pub fn record_error_context(&mut self, context: ErrorContext) {
+        self.error_context.record(context);
+    }
+
+    /// Records the context of a failure of the FRI verifier, if the error allows to determine
+    /// which part of the FRI proof is invalid.
+    #[cfg(feature = "diagnostics")]
+    pub fn record_fri_error_context(&mut self, error: &fri::VerifierError) {
+        use fri::VerifierError as FriError;
+        let context = match error {
+            FriError::InvalidLayerFolding(depth) | FriError::DegreeTruncation(_, _, depth) => {
+                ErrorContext::FriLayer(*depth)
+            },
+            // layer proofs are consumed one at a time, and thus, the failed layer is the last one
+            // which was read from the channel
+            FriError::LayerCommitmentMismatch => {
+                ErrorContext::FriLayer(self.num_fri_layers - self.fri_layer_proofs.len() - 1)
+            },
+            FriError::RemainderCommitmentMismatch
+            | FriError::InvalidRemainderFolding
+            | FriError::RemainderDegreeMismatch(_) => ErrorContext::FriRemainder,
+            _ => return,
+        };
+        self.error_context.record(context);
+    }
+
+    /// Returns the context of the first verification failure recorded by this channel; the
+    /// context is recorded only if the crate is compiled with `diagnostics` feature enabled.
+    pub fn take_error_context(&mut self) -> ErrorContextSlot {
+        core::mem::take(&mut self.error_context)
+    }
+
+    // TRANSCRIPT
+    // --------------------------------------------------------------------------------------------
+
+    /// Makes this channel record all messages read from the proof and all challenges drawn by
+    /// the verifier in a [Transcript].
+    pub fn record_transcript(&mut self) {
+        self.transcript = Some(Transcript::new());
+    }
+
+    /// Records a commitment read from the proof in the transcript, if the transcript is being
+    /// recorded.
+    pub fn record_commitment<T: Serializable>(&mut self, label: &'static str, values: &[T]) {
+        if let Some(transcript) = self.transcript.as_mut() {
+            transcript.record_commitment(label, values);
+        }
+    }
+
+    /// Records a challenge drawn by the verifier in the transcript, if the transcript is being
+    /// recorded.
+    pub fn record_challenge<T: Serializable>(&mut self, label: &'static str, values: &[T]) {
+        if let Some(transcript) = self.transcript.as_mut() {
+            transcript.record_challenge(label, values);
+        }
+    }
+
+    /// Returns the transcript recorded by this channel, if any.
+    pub fn take_transcript(&mut self) -> Option<Transcript> {
+        self.transcript.take()
+    }
 }
 
 // FRI VERIFIER CHANNEL IMPLEMENTATION
@@ -239,9 +416,13 @@ where
 /// * Queried states for all trace segments.
 /// * Merkle authentication paths for all queries.
 ///
-/// Trace states for all auxiliary segments are stored in a single table.
+/// Trace states for all auxiliary segments are stored in a single table, and so are trace states
+/// for all tables of the main trace segment. Trace states for the preprocessed columns of the
+/// main trace segment (if any) are stored in the main trace segment table, but their Merkle
+/// authentication paths are stored separately.
 struct TraceQueries<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
     query_proofs: Vec<BatchMerkleProof<H>>,
+    preprocessed_query_proof: Option<BatchMerkleProof<H>>,
     main_states: Table<E::BaseField>,
     aux_states: Option<Table<E>>,
 }
@@ -250,32 +431,72 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
     /// Parses the provided trace queries into trace states in the specified field and
     /// corresponding Merkle authentication paths.
     pub fn new<A: Air<BaseField = E::BaseField>>(
-        mut queries: Vec<Queries>,
+        mut queries: Vec<QueriesRef<'_>>,
         air: &A,
         num_queries: usize,
     ) -> Result<Self, VerifierError> {
-        assert_eq!(
-            queries.len(),
-            air.trace_info().num_segments(),
-            "expected {} trace segment queries, but received {}",
-            air.trace_info().num_segments(),
-            queries.len()
-        );
+        let num_preprocessed_columns = air.context().num_preprocessed_columns();
+        let has_preprocessed_columns = num_preprocessed_columns > 0;
+        let main_trace_table_widths = air.context().main_trace_table_widths();
+        let num_expected_queries = air.trace_info().num_segments() + main_trace_table_widths.len()
+            - 1
+            + usize::from(has_preprocessed_columns);
+        if queries.len() != num_expected_queries {
+            return Err(VerifierError::ProofDeserializationError(format!(
+                "expected {num_expected_queries} trace segment queries, but received {}",
+                queries.len()
+            )));
+        }
 
-        // parse main trace segment queries; parsing also validates that hashes of each table row
-        // form the leaves of Merkle authentication paths in the proofs
-        let main_segment_width = air.trace_info().main_trace_width();
-        let main_segment_queries = queries.remove(0);
-        let (main_segment_query_proofs, main_segment_states) = main_segment_queries
-            .parse::<H, E::BaseField>(air.lde_domain_size(), num_queries, main_segment_width)
-            .map_err(|err| {
-                VerifierError::ProofDeserializationError(format!(
-                    "main trace segment query deserialization failed: {err}"
-                ))
-            })?;
+        // parse preprocessed column queries (if any); these are always the last ones
+        let preprocessed_queries = if has_preprocessed_columns {
+            let segment_queries = queries.pop().expect("no preprocessed column queries");
+            let (query_proof, states) = segment_queries
+                .parse::<H, E::BaseField>(
+                    air.lde_domain_size(),
+                    num_queries,
+                    num_preprocessed_columns,
+                )
+                .map_err(|err| {
+                    VerifierError::ProofDeserializationError(format!(
+                        "preprocessed trace column query deserialization failed: {err}"
+                    ))
+                })?;
+            Some((query_proof, states))
+        } else {
+            None
+        };
 
         // all query proofs will be aggregated into a single vector
-        let mut query_proofs = vec![main_segment_query_proofs];
+        let mut query_proofs = Vec::with_capacity(queries.len());
+
+        // parse main trace segment queries for each table of the main segment, and merge the
+        // resulting tables into a single table; parsing also validates that hashes of each table
+        // row form the leaves of Merkle authentication paths in the proofs
+        let mut main_segment_tables = Vec::with_capacity(main_trace_table_widths.len());
+        for (table_idx, &table_width) in main_trace_table_widths.iter().enumerate() {
+            // preprocessed columns are allowed only when there is a single table
+            let table_width = table_width - num_preprocessed_columns;
+            let table_queries = queries.remove(0);
+            let (table_query_proof, table_states) = table_queries
+                .parse::<H, E::BaseField>(air.lde_domain_size(), num_queries, table_width)
+                .map_err(|err| {
+                    VerifierError::ProofDeserializationError(format!(
+                        "main trace table {table_idx} query deserialization failed: {err}"
+                    ))
+                })?;
+            query_proofs.push(table_query_proof);
+            main_segment_tables.push(table_states);
+        }
+        let main_segment_states = Table::merge(main_segment_tables);
+
+        // the states of the preprocessed columns go before the rest of the main segment states
+        let (preprocessed_query_proof, main_segment_states) = match preprocessed_queries {
+            Some((query_proof, states)) => {
+                (Some(query_proof), Table::merge(vec![states, main_segment_states]))
+            },
+            None => (None, main_segment_states),
+        };
 
         // parse auxiliary trace segment queries (if any), and merge resulting tables into a
         // single table; parsing also validates that hashes of each table row form the leaves
@@ -303,6 +524,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
 
         Ok(Self {
             query_proofs,
+            preprocessed_query_proof,
             main_states: main_segment_states,
             aux_states: aux_trace_states,
         })
@@ -324,7 +546,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintQuer
     /// Parses the provided constraint queries into evaluations in the specified field and
     /// corresponding Merkle authentication paths.
     pub fn new<A: Air<BaseField = E::BaseField>>(
-        queries: Queries,
+        queries: QueriesRef<'_>,
         air: &A,
         num_queries: usize,
     ) -> Result<Self, VerifierError> {
@@ -341,3 +563,23 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintQuer
         Ok(Self { query_proofs, evaluations })
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns true if the specified batch Merkle proof resolves to the `root` for the specified
+/// positions; when `constant_time` is true, the roots are compared in constant time.
+fn verify_batch<H: ElementHasher>(
+    root: &H::Digest,
+    positions: &[usize],
+    proof: &BatchMerkleProof<H>,
+    constant_time: bool,
+) -> bool {
+    if constant_time {
+        proof
+            .get_root(positions)
+            .is_ok_and(|actual_root| constant_time_eq(&actual_root.as_bytes(), &root.as_bytes()))
+    } else {
+        MerkleTree::verify_batch(root, positions, proof).is_ok()
+    }
+}
@@ -9,6 +9,9 @@ use air::{
     proof::Table, Air, DeepCompositionCoefficients, EvaluationFrame, LagrangeKernelEvaluationFrame,
 };
 use math::{batch_inversion, polynom, FieldElement};
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
+use utils::{iter, iter_mut};
 
 // DEEP COMPOSER
 // ================================================================================================
@@ -32,8 +35,7 @@ impl<E: FieldElement> DeepComposer<E> {
         // compute LDE domain coordinates for all query positions
         let g_lde = air.lde_domain_generator();
         let domain_offset = air.domain_offset();
-        let x_coordinates: Vec<E> = query_positions
-            .iter()
+        let x_coordinates: Vec<E> = iter!(query_positions)
             .map(|&p| E::from(g_lde.exp_vartime((p as u64).into()) * domain_offset))
             .collect();
         let g_trace = air.trace_domain_generator();
@@ -84,35 +86,37 @@ impl<E: FieldElement> DeepComposer<E> {
 
         // compose columns of of the main trace segment; we do this separately for numerators of
         // each query; we also track common denominator for each query separately; this way we can
-        // use a batch inversion in the end.
+        // use a batch inversion in the end. when the crate is compiled with `concurrent` feature
+        // enabled, the queries are composed in parallel.
         let n = queried_main_trace_states.num_rows();
-        let mut result_num = Vec::<E>::with_capacity(n);
-        let mut result_den = Vec::<E>::with_capacity(n);
-
-        for ((_, row), &x) in (0..n).zip(queried_main_trace_states.rows()).zip(&self.x_coordinates)
-        {
-            let mut t1_num = E::ZERO;
-            let mut t2_num = E::ZERO;
-
-            for (i, &value) in row.iter().enumerate() {
-                let value = E::from(value);
-                // compute the numerator of T'_i(x) as (T_i(x) - T_i(z)), multiply it by a
-                // composition coefficient, and add the result to the numerator aggregator
-                t1_num += (value - ood_main_trace_states[0][i]) * self.cc.trace[i];
-
-                // compute the numerator of T''_i(x) as (T_i(x) - T_i(z * g)), multiply it by a
-                // composition coefficient, and add the result to the numerator aggregator
-                t2_num += (value - ood_main_trace_states[1][i]) * self.cc.trace[i];
-            }
-            // compute the common denominator as (x - z) * (x - z * g)
-            let t1_den = x - self.z[0];
-            let t2_den = x - self.z[1];
-            result_den.push(t1_den * t2_den);
-
-            // add the numerators of T'_i(x) and T''_i(x) together; we can do this because later on
-            // we'll use the common denominator computed above.
-            result_num.push(t1_num * t2_den + t2_num * t1_den);
-        }
+        assert_eq!(n, self.x_coordinates.len());
+
+        let (mut result_num, mut result_den): (Vec<E>, Vec<E>) = iter!(self.x_coordinates)
+            .enumerate()
+            .map(|(j, &x)| {
+                let row = queried_main_trace_states.get_row(j);
+                let mut t1_num = E::ZERO;
+                let mut t2_num = E::ZERO;
+
+                for (i, &value) in row.iter().enumerate() {
+                    let value = E::from(value);
+                    // compute the numerator of T'_i(x) as (T_i(x) - T_i(z)), multiply it by a
+                    // composition coefficient, and add the result to the numerator aggregator
+                    t1_num += (value - ood_main_trace_states[0][i]) * self.cc.trace[i];
+
+                    // compute the numerator of T''_i(x) as (T_i(x) - T_i(z * g)), multiply it by a
+                    // composition coefficient, and add the result to the numerator aggregator
+                    t2_num += (value - ood_main_trace_states[1][i]) * self.cc.trace[i];
+                }
+                // compute the common denominator as (x - z) * (x - z * g)
+                let t1_den = x - self.z[0];
+                let t2_den = x - self.z[1];
+
+                // add the numerators of T'_i(x) and T''_i(x) together; we can do this because later on
+                // we'll use the common denominator computed above.
+                (t1_num * t2_den + t2_num * t1_den, t1_den * t2_den)
+            })
+            .unzip();
 
         // if the trace has auxiliary segments, compose columns from these segments as well; we
         // also do this separately for numerators and denominators.
@@ -128,38 +132,37 @@ impl<E: FieldElement> DeepComposer<E> {
             let lagrange_ker_col_idx =
                 self.lagrange_kernel_column_idx.unwrap_or(ood_aux_trace_states[0].len());
 
-            for ((j, row), &x) in
-                (0..n).zip(queried_aux_trace_states.rows()).zip(&self.x_coordinates)
-            {
-                let mut t1_num = E::ZERO;
-                let mut t2_num = E::ZERO;
-
-                let row = &row[..lagrange_ker_col_idx];
-                for (i, &value) in row.iter().enumerate() {
-                    // compute the numerator of T'_i(x) as (T_i(x) - T_i(z)), multiply it by a
-                    // composition coefficient, and add the result to the numerator aggregator
-                    t1_num += (value - ood_aux_trace_states[0][i]) * self.cc.trace[cc_offset + i];
-
-                    // compute the numerator of T''_i(x) as (T_i(x) - T_i(z * g)), multiply it by a
-                    // composition coefficient, and add the result to the numerator aggregator
-                    t2_num += (value - ood_aux_trace_states[1][i]) * self.cc.trace[cc_offset + i];
-                }
-
-                // compute the common denominators (x - z) and (x - z * g), and use the to aggregate
-                // numerators into the common numerator computed for the main trace of this query
-                let t1_den = x - self.z[0];
-                let t2_den = x - self.z[1];
-                result_num[j] += t1_num * t2_den + t2_num * t1_den;
-            }
+            iter_mut!(result_num).zip(iter!(self.x_coordinates)).enumerate().for_each(
+                |(j, (result, &x))| {
+                    let mut t1_num = E::ZERO;
+                    let mut t2_num = E::ZERO;
+
+                    let row = &queried_aux_trace_states.get_row(j)[..lagrange_ker_col_idx];
+                    for (i, &value) in row.iter().enumerate() {
+                        // compute the numerator of T'_i(x) as (T_i(x) - T_i(z)), multiply it by a
+                        // composition coefficient, and add the result to the numerator aggregator
+                        t1_num +=
+                            (value - ood_aux_trace_states[0][i]) * self.cc.trace[cc_offset + i];
+
+                        // compute the numerator of T''_i(x) as (T_i(x) - T_i(z * g)), multiply it by a
+                        // composition coefficient, and add the result to the numerator aggregator
+                        t2_num +=
+                            (value - ood_aux_trace_states[1][i]) * self.cc.trace[cc_offset + i];
+                    }
+
+                    // compute the common denominators (x - z) and (x - z * g), and use the to aggregate
+                    // numerators into the common numerator computed for the main trace of this query
+                    let t1_den = x - self.z[0];
+                    let t2_den = x - self.z[1];
+                    *result += t1_num * t2_den + t2_num * t1_den;
+                },
+            );
 
             // if a Lagrange kernel trace polynomial is present, we include its associated term
             // separately. Note that, for performance reasons, we divide by Z_{S^{'}} instead of
             // Z_S, where  $S^{'} := {z.g^2, ..., z.g^{2^{log_2(\nu) - 1}}}$. This is done as
             // the final linear combination is divided by `(x - z) . (x - z.g)`.
             if let Some(ood_lagrange_kernel_frame) = ood_lagrange_kernel_frame {
-                let mut result_lag_num = Vec::<E>::with_capacity(n);
-                let mut result_lag_den = Vec::<E>::with_capacity(n);
-
                 let log_trace_len_plus_1 = ood_lagrange_kernel_frame.num_rows();
                 let mut xs = Vec::with_capacity(log_trace_len_plus_1);
                 let mut ys = Vec::with_capacity(log_trace_len_plus_1);
@@ -181,15 +184,16 @@ impl<E: FieldElement> DeepComposer<E> {
                 let p_s = polynom::interpolate(&xs, &ys, true);
                 let z_s_prime = polynom::poly_from_roots(&xs[2..]);
 
-                for (row, &x) in queried_aux_trace_states.rows().zip(&self.x_coordinates) {
-                    let value = row[lagrange_ker_col_idx];
-                    let cc = self.cc.lagrange.unwrap();
-
-                    result_lag_num.push((value - polynom::eval(&p_s, x)) * cc);
-                    result_lag_den.push(polynom::eval(&z_s_prime, x));
-                }
+                let cc = self.cc.lagrange.unwrap();
+                let (result_lag_num, result_lag_den): (Vec<E>, Vec<E>) = iter!(self.x_coordinates)
+                    .enumerate()
+                    .map(|(j, &x)| {
+                        let value = queried_aux_trace_states.get_row(j)[lagrange_ker_col_idx];
+                        ((value - polynom::eval(&p_s, x)) * cc, polynom::eval(&z_s_prime, x))
+                    })
+                    .unzip();
 
-                result_lag_den = batch_inversion(&result_lag_den);
+                let result_lag_den = batch_inversion(&result_lag_den);
                 for (j, res) in result_num.iter_mut().enumerate() {
                     *res += result_lag_num[j] * result_lag_den[j];
                 }
@@ -218,26 +222,24 @@ impl<E: FieldElement> DeepComposer<E> {
     ) -> Vec<E> {
         assert_eq!(queried_evaluations.num_rows(), self.x_coordinates.len());
 
-        let n = queried_evaluations.num_rows();
-        let mut result_num = Vec::<E>::with_capacity(n);
-        let mut result_den = Vec::<E>::with_capacity(n);
-
         let z = self.z[0];
 
         // combine composition polynomial columns separately for numerators and denominators;
         // this way we can use batch inversion in the end.
-        for (query_values, &x) in queried_evaluations.rows().zip(&self.x_coordinates) {
-            let mut composition_num = E::ZERO;
-            for (i, &evaluation) in query_values.iter().enumerate() {
-                // compute the numerator of H'_i(x) as (H_i(x) - H_i(z)), multiply it by a
-                // composition coefficient, and add the result to the numerator aggregator
-                composition_num += (evaluation - ood_evaluations[i]) * self.cc.constraints[i];
-            }
-            result_num.push(composition_num);
-            result_den.push(x - z);
-        }
+        let (result_num, result_den): (Vec<E>, Vec<E>) = iter!(self.x_coordinates)
+            .enumerate()
+            .map(|(j, &x)| {
+                let mut composition_num = E::ZERO;
+                for (i, &evaluation) in queried_evaluations.get_row(j).iter().enumerate() {
+                    // compute the numerator of H'_i(x) as (H_i(x) - H_i(z)), multiply it by a
+                    // composition coefficient, and add the result to the numerator aggregator
+                    composition_num += (evaluation - ood_evaluations[i]) * self.cc.constraints[i];
+                }
+                (composition_num, x - z)
+            })
+            .unzip();
 
-        result_den = batch_inversion(&result_den);
+        let result_den = batch_inversion(&result_den);
         result_num.iter().zip(result_den).map(|(n, d)| *n * d).collect()
     }
 
@@ -0,0 +1,237 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Contains types describing where in a proof the verification failed.
+//!
+//! Public types of this module are available only when the crate is compiled with `diagnostics`
+//! feature enabled.
+
+#[cfg(feature = "diagnostics")]
+use alloc::{boxed::Box, string::String, vec::Vec};
+#[cfg(feature = "diagnostics")]
+use core::fmt;
+
+#[cfg(feature = "diagnostics")]
+use crypto::{BatchMerkleProof, Digest, Hasher, MerkleTree};
+
+#[cfg(feature = "diagnostics")]
+use crate::VerifierError;
+
+// DIAGNOSED ERROR
+// ================================================================================================
+
+/// A [VerifierError] together with the context describing where in the proof the verification
+/// failed.
+#[cfg(feature = "diagnostics")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosedError {
+    error: VerifierError,
+    context: Option<Box<ErrorContext>>,
+}
+
+#[cfg(feature = "diagnostics")]
+impl DiagnosedError {
+    pub(crate) fn new(error: VerifierError, context: Option<ErrorContext>) -> Self {
+        Self { error, context: context.map(Box::new) }
+    }
+
+    /// Returns the error returned by the verifier.
+    pub fn error(&self) -> &VerifierError {
+        &self.error
+    }
+
+    /// Returns the context of the error, if the verifier was able to determine it.
+    ///
+    /// The context is available for errors caused by invalid Merkle authentication paths,
+    /// inconsistent out-of-domain evaluations, and invalid FRI layers.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        self.context.as_deref()
+    }
+
+    /// Consumes this error and returns the underlying [VerifierError].
+    pub fn into_error(self) -> VerifierError {
+        self.error
+    }
+}
+
+#[cfg(feature = "diagnostics")]
+impl From<VerifierError> for DiagnosedError {
+    fn from(error: VerifierError) -> Self {
+        Self { error, context: None }
+    }
+}
+
+#[cfg(feature = "diagnostics")]
+impl fmt::Display for DiagnosedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.context {
+            Some(context) => write!(f, "{} ({context})", self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+// ERROR CONTEXT
+// ================================================================================================
+
+/// Describes where in a proof the verification failed.
+#[cfg(feature = "diagnostics")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorContext {
+    /// Merkle authentication paths of trace queries did not resolve to one of the trace
+    /// commitments. The commitment is identified by its index in the list of trace commitments
+    /// included in the proof.
+    TraceQuery {
+        commitment: usize,
+        mismatch: MerklePathMismatch,
+    },
+    /// Merkle authentication paths of trace queries did not resolve to the commitment to the
+    /// preprocessed columns of the main trace segment.
+    PreprocessedTraceQuery(MerklePathMismatch),
+    /// Merkle authentication paths of constraint evaluation queries did not resolve to the
+    /// constraint commitment.
+    ConstraintQuery(MerklePathMismatch),
+    /// Constraints evaluated over the out-of-domain trace frame (`from_trace`) did not match the
+    /// evaluation of the constraint composition polynomial at the out-of-domain point
+    /// (`from_composition`) computed from the values sent by the prover.
+    OodConstraintEvaluations {
+        from_trace: String,
+        from_composition: String,
+    },
+    /// Verification of the FRI layer at the specified depth failed.
+    FriLayer(usize),
+    /// Verification of the FRI remainder failed.
+    FriRemainder,
+}
+
+#[cfg(feature = "diagnostics")]
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TraceQuery { commitment, mismatch } => {
+                write!(f, "trace commitment {commitment}: {mismatch}")
+            },
+            Self::PreprocessedTraceQuery(mismatch) => {
+                write!(f, "preprocessed trace commitment: {mismatch}")
+            },
+            Self::ConstraintQuery(mismatch) => write!(f, "constraint commitment: {mismatch}"),
+            Self::OodConstraintEvaluations { from_trace, from_composition } => {
+                write!(f, "evaluated constraints to {from_trace}, but composition polynomial evaluated to {from_composition}")
+            },
+            Self::FriLayer(depth) => write!(f, "FRI layer {depth}"),
+            Self::FriRemainder => write!(f, "FRI remainder"),
+        }
+    }
+}
+
+// MERKLE PATH MISMATCH
+// ================================================================================================
+
+/// Describes Merkle authentication paths which did not resolve to the expected commitment.
+#[cfg(feature = "diagnostics")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerklePathMismatch {
+    /// Commitment against which the paths were checked.
+    pub expected_root: [u8; 32],
+    /// Root to which the batch of paths resolved, or None if the paths did not resolve to a
+    /// single root.
+    pub actual_root: Option<[u8; 32]>,
+    /// Queries whose individual authentication paths did not resolve to the expected commitment,
+    /// as (query index, LDE domain position) tuples. Queries are indexed in the order of their
+    /// sorted and deduplicated positions.
+    pub failed_queries: Vec<(usize, usize)>,
+}
+
+#[cfg(feature = "diagnostics")]
+impl MerklePathMismatch {
+    /// Checks the paths in the specified batch `proof` against the `expected_root` and returns a
+    /// description of the mismatch.
+    pub(crate) fn new<H: Hasher>(
+        expected_root: &H::Digest,
+        positions: &[usize],
+        proof: &BatchMerkleProof<H>,
+    ) -> Self {
+        let actual_root = proof.get_root(positions).ok().map(|root| root.as_bytes());
+        // the derived implementation of Clone requires the hasher to implement Clone as well
+        let proof_copy = BatchMerkleProof::<H> {
+            leaves: proof.leaves.clone(),
+            nodes: proof.nodes.clone(),
+            depth: proof.depth,
+        };
+        let failed_queries = match proof_copy.into_paths(positions) {
+            Ok(paths) => positions
+                .iter()
+                .zip(paths.iter())
+                .enumerate()
+                .filter(|(_, (&position, path))| {
+                    MerkleTree::<H>::verify(*expected_root, position, path).is_err()
+                })
+                .map(|(query_idx, (&position, _))| (query_idx, position))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        Self {
+            expected_root: expected_root.as_bytes(),
+            actual_root,
+            failed_queries,
+        }
+    }
+}
+
+#[cfg(feature = "diagnostics")]
+impl fmt::Display for MerklePathMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected root {}, ", to_hex(&self.expected_root))?;
+        match self.actual_root {
+            Some(root) => write!(f, "but paths resolved to {}", to_hex(&root))?,
+            None => write!(f, "but paths did not resolve to a single root")?,
+        }
+        if !self.failed_queries.is_empty() {
+            write!(f, "; failed queries (index, position): {:?}", self.failed_queries)?;
+        }
+        Ok(())
+    }
+}
+
+// ERROR CONTEXT SLOT
+// ================================================================================================
+
+/// Holds the context of a verification failure.
+///
+/// When the crate is compiled without `diagnostics` feature, the slot is empty, and no context is
+/// recorded.
+#[derive(Debug, Default)]
+pub(crate) struct ErrorContextSlot {
+    #[cfg(feature = "diagnostics")]
+    context: Option<ErrorContext>,
+}
+
+#[cfg(feature = "diagnostics")]
+impl ErrorContextSlot {
+    /// Records the specified context unless a context has been recorded already.
+    pub fn record(&mut self, context: ErrorContext) {
+        self.context.get_or_insert(context);
+    }
+
+    /// Returns the recorded context, if any.
+    pub fn into_context(self) -> Option<ErrorContext> {
+        self.context
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+#[cfg(feature = "diagnostics")]
+fn to_hex(bytes: &[u8]) -> String {
+    use core::fmt::Write;
+
+    let mut result = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        write!(result, "{byte:02x}").expect("writing to a string never fails");
+    }
+    result
+}
@@ -8,6 +8,8 @@
 use alloc::string::String;
 use core::fmt;
 
+use crypto::HashFunctionId;
+
 // VERIFIER ERROR
 // ================================================================================================
 /// Represents an error returned by the verifier during an execution of the protocol.
@@ -23,6 +25,9 @@ pub enum VerifierError {
     UnsupportedFieldExtension(usize),
     /// This error occurs when a verifier cannot deserialize the specified proof.
     ProofDeserializationError(String),
+    /// This error occurs when a verifier cannot deserialize the public inputs against which the
+    /// proof should be verified.
+    PublicInputsDeserializationError(String),
     /// This error occurs when a verifier fails to draw a random value from a random coin
     /// within a specified number of tries.
     RandomCoinError,
@@ -54,6 +59,53 @@ pub enum VerifierError {
     /// This error occurs when the parameters, that were used to generate the proof, do not match
     /// any of the set of parameters expected by the verifier.
     UnacceptableProofOptions,
+    /// This error occurs when the hash functions recorded in the proof context do not match the
+    /// hash functions used by the verifier for commitments and for the public coin.
+    MismatchedHashFunctions,
+    /// This error occurs when the digest truncation width recorded in the proof context is not
+    /// the size of the digests of the hash function used by the verifier for commitments; the
+    /// first value is the truncation width, and the second value is the digest size (both in
+    /// bytes).
+    MismatchedDigestSize(usize, usize),
+    /// This error occurs when the parameters digest recorded in the proof context is not the
+    /// digest of the protocol configuration of the verifier (i.e., its base field, hash functions,
+    /// and the proof options recorded in the proof). This indicates that the prover and the
+    /// verifier were built with different protocol parameters.
+    MismatchedParametersDigest,
+    /// This error occurs when the verifier is not provided with a commitment to the preprocessed
+    /// columns of the main trace segment for a computation which declares such columns, or is
+    /// provided with such a commitment for a computation which does not declare them.
+    InvalidPreprocessedTraceCommitment,
+    /// This error occurs when the proof was generated using a hash function which is not allowed
+    /// by the verification policy.
+    UnacceptableHashFunction,
+    /// This error occurs when the proof was generated using a field extension of a degree which
+    /// is not allowed by the verification policy.
+    UnacceptableFieldExtension(u32),
+    /// This error occurs when the execution trace for which the proof was generated is longer
+    /// than the maximum trace length allowed by the verification policy or supported by the base
+    /// field.
+    TraceLengthTooLarge(usize, usize),
+    /// This error occurs when the field in which the verifier is asked to perform verification
+    /// is an extension of the base field of a degree different from the one specified by the
+    /// proof options.
+    MismatchedFieldExtension(u32, u32),
+    /// This error occurs when the hash functions used to generate the proof need to be determined
+    /// from the proof context, but the proof context does not record them.
+    UnspecifiedHashFunctions,
+    /// This error occurs when the hash function recorded in the proof context is not supported by
+    /// the runtime hash function dispatch.
+    UnsupportedHashFunction(HashFunctionId),
+    /// This error occurs when a trace cell declared as a public output of the computation is not
+    /// bound by any of the assertions of the computation.
+    PublicOutputNotAsserted(usize, usize),
+    /// This error occurs when the AIR excludes more positions from querying than allowed by
+    /// [AirContext::max_excluded_query_positions()](air::AirContext::max_excluded_query_positions).
+    TooManyExcludedQueryPositions { num_excluded: usize, max_excluded: usize },
+    /// This error occurs when one or more of the checks performed by the constant-time
+    /// verification procedure fail. To avoid revealing which of the checks failed, the error
+    /// does not describe the failure.
+    ProofVerificationFailed,
 }
 
 impl fmt::Display for VerifierError {
@@ -72,6 +124,9 @@ impl fmt::Display for VerifierError {
             Self::ProofDeserializationError(msg) => {
                 write!(f, "proof deserialization failed: {msg}")
             }
+            Self::PublicInputsDeserializationError(msg) => {
+                write!(f, "public inputs deserialization failed: {msg}")
+            }
             Self::RandomCoinError => {
                 write!(f, "failed to draw a random value from a random coin")
             }
@@ -97,6 +152,45 @@ impl fmt::Display for VerifierError {
                 write!(f, "insufficient proof security level: expected at least {minimal_security} bits of proven security, but was {proof_security} bits")
             }
             Self::UnacceptableProofOptions => {write!(f, "invalid proof options: security parameters do not match the acceptable parameter set")}
+            Self::MismatchedHashFunctions => {
+                write!(f, "hash functions recorded in the proof do not match the hash functions used by the verifier")
+            }
+            Self::MismatchedDigestSize(expected, actual) => {
+                write!(f, "the proof records {expected}-byte digests, but the commitment hash function of the verifier produces {actual}-byte digests")
+            }
+            Self::MismatchedParametersDigest => {
+                write!(f, "the parameters digest recorded in the proof does not match the protocol configuration of the verifier")
+            }
+            Self::InvalidPreprocessedTraceCommitment => {
+                write!(f, "a commitment to preprocessed trace columns must be provided if and only if the computation declares such columns")
+            }
+            Self::UnacceptableHashFunction => {
+                write!(f, "the proof was generated using a hash function not allowed by the verification policy")
+            }
+            Self::UnacceptableFieldExtension(degree) => {
+                write!(f, "field extension of degree {degree} is not allowed by the verification policy")
+            }
+            Self::TraceLengthTooLarge(max_length, length) => {
+                write!(f, "expected trace length of at most {max_length}, but was {length}")
+            }
+            Self::MismatchedFieldExtension(expected, actual) => {
+                write!(f, "expected field extension of degree {expected}, but the proof uses field extension of degree {actual}")
+            }
+            Self::UnspecifiedHashFunctions => {
+                write!(f, "the proof does not record the hash functions used to generate it")
+            }
+            Self::UnsupportedHashFunction(hash_fn) => {
+                write!(f, "hash function {hash_fn:?} is not supported by the runtime hash function dispatch")
+            }
+            Self::PublicOutputNotAsserted(column, step) => {
+                write!(f, "public output in column {column} at step {step} is not bound by an assertion")
+            }
+            Self::TooManyExcludedQueryPositions { num_excluded, max_excluded } => {
+                write!(f, "the AIR excludes {num_excluded} positions from querying, but at most {max_excluded} positions can be excluded")
+            }
+            Self::ProofVerificationFailed => {
+                write!(f, "proof verification failed")
+            }
         }
     }
 }
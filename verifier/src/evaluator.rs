@@ -3,18 +3,21 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use alloc::vec::Vec;
-
 use air::{
     Air, AuxRandElements, ConstraintCompositionCoefficients, EvaluationFrame,
     LagrangeKernelEvaluationFrame,
 };
-use math::{polynom, FieldElement};
+use math::FieldElement;
+
+use crate::ConstraintEvaluations;
 
 // CONSTRAINT EVALUATION
 // ================================================================================================
 
 /// Evaluates constraints for the specified evaluation frame.
+///
+/// The evaluations are returned split by the kind of constraint; the evaluation of the constraint
+/// composition polynomial at `x` is then given by [ConstraintEvaluations::combined()].
 pub fn evaluate_constraints<A: Air, E: FieldElement<BaseField = A::BaseField>>(
     air: &A,
     composition_coefficients: ConstraintCompositionCoefficients<E>,
@@ -23,26 +26,20 @@ pub fn evaluate_constraints<A: Air, E: FieldElement<BaseField = A::BaseField>>(
     lagrange_kernel_frame: Option<&LagrangeKernelEvaluationFrame<E>>,
     aux_rand_elements: Option<&AuxRandElements<E>>,
     x: E,
-) -> E {
+) -> ConstraintEvaluations<E> {
     // 1 ----- evaluate transition constraints ----------------------------------------------------
 
     // initialize a buffer to hold transition constraint evaluations
     let t_constraints = air.get_transition_constraints(&composition_coefficients.transition);
 
     // compute values of periodic columns at x
-    let periodic_values = air
-        .get_periodic_column_polys()
-        .iter()
-        .map(|poly| {
-            let num_cycles = air.trace_length() / poly.len();
-            let x = x.exp_vartime((num_cycles as u32).into());
-            polynom::eval(poly, x)
-        })
-        .collect::<Vec<_>>();
+    let periodic_values = air.evaluate_periodic_columns(x);
 
     // evaluate transition constraints for the main trace segment
     let mut t_evaluations1 = vec![E::ZERO; t_constraints.num_main_constraints()];
     air.evaluate_transition(main_trace_frame, &periodic_values, &mut t_evaluations1);
+    air.context()
+        .apply_main_selectors(main_trace_frame.current(), &mut t_evaluations1);
 
     // evaluate transition constraints for the auxiliary trace segment (if any)
     let mut t_evaluations2 = vec![E::ZERO; t_constraints.num_aux_constraints()];
@@ -57,12 +54,14 @@ pub fn evaluate_constraints<A: Air, E: FieldElement<BaseField = A::BaseField>>(
             aux_rand_elements.rand_elements(),
             &mut t_evaluations2,
         );
+        air.context()
+            .apply_aux_selectors(main_trace_frame.current(), &mut t_evaluations2);
     }
 
     // merge all constraint evaluations into a single value by computing their random linear
     // combination using coefficients drawn from the public coin. this also divides the result
     // by the divisor of transition constraints.
-    let mut result = t_constraints.combine_evaluations::<E>(&t_evaluations1, &t_evaluations2, x);
+    let transition = t_constraints.combine_evaluations::<E>(&t_evaluations1, &t_evaluations2, x);
 
     // 2 ----- evaluate boundary constraints ------------------------------------------------------
 
@@ -75,8 +74,9 @@ pub fn evaluate_constraints<A: Air, E: FieldElement<BaseField = A::BaseField>>(
     // iterate over boundary constraint groups for the main trace segment (each group has a
     // distinct divisor), evaluate constraints in each group and add their combination to the
     // result
+    let mut boundary = E::ZERO;
     for group in b_constraints.main_constraints().iter() {
-        result += group.evaluate_at(main_trace_frame.current(), x);
+        boundary += group.evaluate_at(main_trace_frame.current(), x);
     }
 
     // iterate over boundary constraint groups for the auxiliary trace segment (each group has a
@@ -84,12 +84,13 @@ pub fn evaluate_constraints<A: Air, E: FieldElement<BaseField = A::BaseField>>(
     // result
     if let Some(aux_trace_frame) = aux_trace_frame {
         for group in b_constraints.aux_constraints().iter() {
-            result += group.evaluate_at(aux_trace_frame.current(), x);
+            boundary += group.evaluate_at(aux_trace_frame.current(), x);
         }
     }
 
     // 3 ----- evaluate Lagrange kernel constraints ------------------------------------
 
+    let mut lagrange_kernel = E::ZERO;
     if let Some(lagrange_kernel_column_frame) = lagrange_kernel_frame {
         let lagrange_coefficients = composition_coefficients
             .lagrange
@@ -110,14 +111,21 @@ pub fn evaluate_constraints<A: Air, E: FieldElement<BaseField = A::BaseField>>(
             )
             .expect("expected Lagrange kernel constraints to be present");
 
-        result += lagrange_constraints.transition.evaluate_and_combine::<E>(
+        lagrange_kernel += lagrange_constraints.transition.evaluate_and_combine::<E>(
             lagrange_kernel_column_frame,
             lagrange_kernel_aux_rand_elements,
             x,
         );
 
-        result += lagrange_constraints.boundary.evaluate_at(x, lagrange_kernel_column_frame);
+        lagrange_kernel +=
+            lagrange_constraints.boundary.evaluate_at(x, lagrange_kernel_column_frame);
     }
 
-    result
+    ConstraintEvaluations {
+        main_transition: t_evaluations1,
+        aux_transition: t_evaluations2,
+        transition,
+        boundary,
+        lagrange_kernel,
+    }
 }
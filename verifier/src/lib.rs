@@ -15,6 +15,11 @@
 //! 2. Execute [verify()] function and supply the AIR of your computation together with the
 //!    [Proof] and related public inputs as parameters.
 //!
+//! Many proofs of the same computation can be verified at once using [verify_batch()]. When the
+//! crate is compiled with `concurrent` feature enabled, the proofs are verified in parallel.
+//! Proofs and public inputs received in serialized form can be verified directly using
+//! [verify_bytes()].
+//!
 //! # Performance
 //! Proof verification is extremely fast and is nearly independent of the complexity of the
 //! computation being verified. In vast majority of cases proofs can be verified in 3 - 5 ms
@@ -25,6 +30,24 @@
 //! asserted values. But for the impact to be noticeable, the number of asserted values would
 //! need to be in tens of thousands. And even for hundreds of thousands of asserted values, the
 //! verification time should not exceed 50 ms.
+//!
+//! When the crate is compiled with `concurrent` feature enabled, the work performed for every
+//! query (i.e., computing evaluations of the DEEP composition polynomial and folding FRI layers)
+//! is done in parallel. This reduces verification latency for proofs with a large number of
+//! queries.
+//!
+//! # Constrained environments
+//! When compiled without the `std` feature, the verifier relies only on the `alloc` crate, and
+//! all heap allocations are made through the global allocator. Thus, on embedded or enclave
+//! targets with a constrained heap, the memory used by the verifier can be confined to a fixed
+//! region by registering a bounded `#[global_allocator]` (e.g., a bump or a linked-list allocator
+//! over a static buffer). Parameterizing the internal collections by an allocator is not possible
+//! on stable Rust, as the `allocator_api` feature is not stabilized yet.
+//!
+//! The amount of memory needed is proportional to the size of the proof: verifying a proof from
+//! a byte buffer via [verify_bytes()] reads query values and authentication paths directly from
+//! the buffer (see [ProofRef]), and the additional allocations are dominated by the parsed query
+//! values and the decommitted Merkle paths.
 
 #![no_std]
 
@@ -34,19 +57,32 @@ extern crate alloc;
 use alloc::{string::ToString, vec::Vec};
 
 pub use air::{
-    proof::Proof, Air, AirContext, Assertion, BoundaryConstraint, BoundaryConstraintGroup,
+    evaluate_periodic_columns_at,
+    proof::{DetachedProof, DetachedSection, LazyProof, Proof, ProofRef, ProofSection},
+    Air, AirContext, AirWithOutputs, Assertion, BoundaryConstraint, BoundaryConstraintGroup,
     ConstraintCompositionCoefficients, ConstraintDivisor, DeepCompositionCoefficients,
-    EvaluationFrame, FieldExtension, ProofOptions, TraceInfo, TransitionConstraintDegree,
+    EvaluationFrame, FieldExtension, ProofOptions, SelectorGroup, TraceInfo, Transcript,
+    TranscriptEntry, TranscriptEntryKind, TransitionConstraintDegree,
+};
+use air::{
+    proof::{Commitments, Context, Queries, Table},
+    AuxRandElements, GkrVerifier,
 };
-use air::{AuxRandElements, GkrVerifier};
 pub use crypto;
-use crypto::{ElementHasher, Hasher, RandomCoin};
-use fri::FriVerifier;
+use crypto::{
+    hashers::{Blake3_192, Blake3_256, Sha3_256},
+    DefaultRandomCoin, DualHashRandomCoin, ElementHasher, HashFunctionId, Hasher, MerkleTree,
+    RandomCoin,
+};
+use fri::{positions::draw_query_positions_with_exclusions, FriVerifier};
 pub use math;
 use math::{
     fields::{CubeExtension, QuadExtension},
-    FieldElement, ToElements,
+    FieldElement, StarkField, ToElements,
 };
+use utils::constant_time_eq;
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
 pub use utils::{
     ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
 };
@@ -57,12 +93,31 @@ use channel::VerifierChannel;
 mod evaluator;
 use evaluator::evaluate_constraints;
 
+mod ood;
+use ood::OodEvaluations;
+pub use ood::{check_ood_consistency, ConstraintEvaluations, OodConsistencyReport};
+
 mod composer;
 use composer::DeepComposer;
 
 mod errors;
 pub use errors::VerifierError;
 
+mod policy;
+pub use policy::VerificationPolicy;
+
+mod trace;
+pub use trace::VerifierTrace;
+
+mod witness;
+use witness::build_snark_witness;
+pub use witness::{MerkleOpenings, SnarkWitness, WitnessElement};
+
+mod diagnostics;
+use diagnostics::ErrorContextSlot;
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::{DiagnosedError, ErrorContext, MerklePathMismatch};
+
 // VERIFIER
 // ================================================================================================
 
@@ -78,6 +133,8 @@ pub use errors::VerifierError;
 /// - The specified proof was generated for a different computation.
 /// - The specified proof was generated for this computation but for different public inputs.
 /// - The specified proof was generated with parameters not providing an acceptable security level.
+/// - The specified proof records hash functions different from `HashFn` and the hash function
+///   used by `RandCoin`.
 pub fn verify<AIR, HashFn, RandCoin>(
     proof: Proof,
     pub_inputs: AIR::PublicInputs,
@@ -87,64 +144,1002 @@ where
     AIR: Air,
     HashFn: ElementHasher<BaseField = AIR::BaseField>,
     RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    dispatch_verification::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options, None)
+        .map(|_| ())
+}
+
+/// Verifies that the specified computation, whose main trace segment contains preprocessed
+/// columns, was executed correctly against the specified inputs.
+///
+/// Works just like [verify()], but is intended for computations which declare preprocessed
+/// columns (see [AirContext::num_preprocessed_columns()]). The commitment to these columns is not
+/// included in the proof; instead, it must be known to the verifier in advance and supplied via
+/// `preprocessed_commitment`.
+///
+/// # Errors
+/// In addition to the errors returned by [verify()], returns an error if the computation does not
+/// declare any preprocessed columns, or if the proof was generated for preprocessed columns
+/// different from the ones described by `preprocessed_commitment`.
+pub fn verify_with_preprocessed_trace<AIR, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    preprocessed_commitment: HashFn::Digest,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    dispatch_verification::<AIR, HashFn, RandCoin>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        Some(preprocessed_commitment),
+    )
+    .map(|_| ())
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and returns the positions at which the execution trace and the constraint composition
+/// polynomial were queried.
+///
+/// Works just like [verify()], but on success, returns the sorted and deduplicated query
+/// positions drawn from the public coin. These are the same positions which the prover passed to
+/// `Prover::build_proof_extensions()`, and thus, they can be used to check application-defined
+/// openings stored in [Proof::extensions] against the commitments contained in the proof. Since
+/// the proof is consumed, the extensions need to be retrieved from the proof before calling this
+/// function.
+///
+/// # Errors
+/// Returns the same errors as [verify()].
+pub fn verify_with_query_positions<AIR, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<Vec<usize>, VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    dispatch_verification::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options, None)
+}
+
+/// Checks an opening of a single table of the main trace segment against the commitment to this
+/// table, and returns the opened rows.
+///
+/// The opening is expected to be produced by `TraceLde::query_main_trace_table()` of the prover
+/// crate for the specified `positions` (which must be sorted and deduplicated), and the
+/// commitment can be retrieved via [Proof::main_trace_table_commitments()]. Thus, an application
+/// can open a group of main trace columns (e.g., for an auxiliary protocol) without revealing the
+/// other columns of the trace. `lde_domain_size` is the size of the LDE domain of the proof, and
+/// `table_width` is the number of columns in the table.
+///
+/// This function only checks that the opened rows are consistent with the commitment; the proof
+/// containing the commitment must be verified separately.
+///
+/// # Errors
+/// Returns an error if the opening could not be parsed, or if the Merkle authentication paths of
+/// the opening do not resolve to `table_commitment`.
+pub fn verify_main_trace_table_opening<B, HashFn>(
+    table_commitment: &HashFn::Digest,
+    lde_domain_size: usize,
+    table_width: usize,
+    positions: &[usize],
+    opening: &Queries,
+) -> Result<Table<B>, VerifierError>
+where
+    B: StarkField,
+    HashFn: ElementHasher<BaseField = B>,
+{
+    if positions.is_empty() {
+        return Err(VerifierError::ProofDeserializationError(
+            "main trace table opening must contain at least one position".to_string(),
+        ));
+    }
+    let (merkle_proof, rows) = opening
+        .as_borrowed()
+        .parse::<HashFn, B>(lde_domain_size, positions.len(), table_width)
+        .map_err(|err| {
+            VerifierError::ProofDeserializationError(format!(
+                "main trace table opening deserialization failed: {err}"
+            ))
+        })?;
+    MerkleTree::verify_batch(table_commitment, positions, &merkle_proof)
+        .map_err(|_| VerifierError::TraceQueryDoesNotMatchCommitment)?;
+
+    Ok(rows)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs
+/// using a proof serialized via [Proof::compress()].
+///
+/// The proof is first decompressed using `HashFn` and the base field of the `AIR`, and is then
+/// verified in the same way as by the [verify()] function.
+///
+/// # Errors
+/// Returns an error if the compressed proof could not be decompressed, or if the decompressed
+/// proof and public inputs do not attest to a correct execution of the computation (see
+/// [verify()] for details).
+pub fn verify_compressed<AIR, HashFn, RandCoin>(
+    compressed_proof: &[u8],
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let proof = Proof::decompress::<HashFn, AIR::BaseField>(compressed_proof)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    verify::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs
+/// using a proof with detached sections (see [Proof::detach()]).
+///
+/// The serialized bytes of each detached section are requested from `fetch`; they are checked
+/// against the hashes recorded in the detached proof using `HashFn`, and the restored proof is
+/// then verified in the same way as by the [verify()] function.
+///
+/// # Errors
+/// Returns an error if any of the detached sections could not be fetched, did not match its hash,
+/// or could not be deserialized, or if the restored proof and public inputs do not attest to a
+/// correct execution of the computation (see [verify()] for details).
+pub fn verify_detached<AIR, HashFn, RandCoin, F>(
+    proof: DetachedProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    fetch: F,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    F: FnMut(&DetachedSection) -> Option<Vec<u8>>,
+{
+    let proof = proof
+        .attach::<HashFn, F>(fetch)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    verify::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and describes where in the proof the verification failed if it was not successful.
+///
+/// Works just like [verify()], but on failure, the returned error contains the context of the
+/// failure in addition to the [VerifierError]; e.g., for an invalid trace query, the context
+/// identifies the trace commitment, the expected and actual Merkle roots, and the queries whose
+/// authentication paths are invalid. Collecting this context requires additional work only when
+/// the verification fails.
+///
+/// This function is available only when the crate is compiled with `diagnostics` feature enabled.
+///
+/// # Errors
+/// Returns the same errors as [verify()], together with their context.
+#[cfg(feature = "diagnostics")]
+pub fn verify_with_diagnostics<AIR, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), DiagnosedError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    validate_proof_parameters::<HashFn, RandCoin>(&proof.context, acceptable_options)?;
+    let mut error_context = ErrorContextSlot::default();
+    run_verification::<AIR, HashFn, RandCoin>(proof, pub_inputs, None, &mut error_context)
+        .map(|_| ())
+        .map_err(|err| DiagnosedError::new(err, error_context.into_context()))
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// with both the proof and the public inputs provided in serialized form.
+///
+/// The proof is read into a borrowed view via [LazyProof::read_from_slice()], and thus, query
+/// values and Merkle authentication paths are parsed directly from `proof_bytes` without first
+/// being copied into an owned [Proof]. Moreover, proof parameters are validated against
+/// `acceptable_options` before the query sections of the proof are parsed. The public inputs are
+/// deserialized via their [Deserializable] implementation, and the result is verified in the same
+/// way as by the [verify()] function. This makes it possible to verify proofs without handling any
+/// of the intermediate types, e.g., across an FFI boundary.
+///
+/// # Errors
+/// Returns an error if the proof or the public inputs could not be deserialized, if any bytes
+/// remain after deserializing either of them, or if the proof and public inputs do not attest to
+/// a correct execution of the computation (see [verify()] for details). Since this function does
+/// not accept a commitment to preprocessed trace columns, proofs of computations which declare
+/// such columns are rejected with [VerifierError::InvalidPreprocessedTraceCommitment].
+pub fn verify_bytes<AIR, HashFn, RandCoin>(
+    proof_bytes: &[u8],
+    pub_input_bytes: &[u8],
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    AIR::PublicInputs: Deserializable,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let proof = LazyProof::read_from_slice(proof_bytes)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    validate_proof_parameters::<HashFn, RandCoin>(&proof.context, acceptable_options)?;
+    let proof = proof
+        .read_sections()
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+
+    let pub_inputs = read_exact::<AIR::PublicInputs>(pub_input_bytes)
+        .map_err(|err| VerifierError::PublicInputsDeserializationError(err.to_string()))?;
+    let (air, public_coin_seed) =
+        build_air_and_coin_seed::<AIR, HashFn>(&proof.context, pub_inputs, &None)?;
+    verify_air::<AIR, HashFn, RandCoin>(
+        &air,
+        proof,
+        public_coin_seed,
+        None,
+        VerificationMode::Full,
+        &mut ErrorContextSlot::default(),
+    )
+    .map(|_| ())
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and returns the public outputs of the computation.
+///
+/// Works just like [verify()], but is intended for computations which declare public outputs
+/// (see [AirWithOutputs]). On success, the values of the trace cells declared as public outputs
+/// are read from the assertions of the `AIR`, and are returned in the form built by
+/// [AirWithOutputs::build_public_outputs()].
+///
+/// # Errors
+/// In addition to the errors returned by [verify()], returns an error if any of the cells
+/// declared as public outputs is not bound by an assertion. Computations with preprocessed trace
+/// columns are not supported; for them, [VerifierError::InvalidPreprocessedTraceCommitment] is
+/// returned.
+pub fn verify_with_outputs<AIR, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<AIR::PublicOutputs, VerifierError>
+where
+    AIR: AirWithOutputs,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    validate_proof_parameters::<HashFn, RandCoin>(&proof.context, acceptable_options)?;
+    let (air, public_coin_seed) =
+        build_air_and_coin_seed::<AIR, HashFn>(&proof.context, pub_inputs, &None)?;
+
+    let public_outputs = get_public_outputs(&air)?;
+    verify_air::<AIR, HashFn, RandCoin>(
+        &air,
+        proof.as_borrowed(),
+        public_coin_seed,
+        None,
+        VerificationMode::Full,
+        &mut ErrorContextSlot::default(),
+    )?;
+
+    Ok(public_outputs)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and returns the security levels provided by the proof.
+///
+/// Works just like [verify()], but on success, returns the conjectured and proven security levels
+/// computed from the parameters of the proof, the base field of the computation, and the collision
+/// resistance of `HashFn`. If the `AIR` excludes any positions from querying (see
+/// [Air::num_excluded_query_positions()]), both levels are reduced by one bit, which is the
+/// largest loss of security such exclusions can cause. Since `acceptable_options` only determine
+/// whether a proof is accepted, the returned levels can be used to monitor how close accepted
+/// proofs are to the minimum security level required by the verifier.
+///
+/// # Errors
+/// Returns the same errors as [verify()].
+pub fn verify_with_security_levels<AIR, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<SecurityLevels, VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    validate_proof_parameters::<HashFn, RandCoin>(&proof.context, acceptable_options)?;
+    let (air, public_coin_seed) =
+        build_air_and_coin_seed::<AIR, HashFn>(&proof.context, pub_inputs, &None)?;
+
+    verify_air::<AIR, HashFn, RandCoin>(
+        &air,
+        proof.as_borrowed(),
+        public_coin_seed,
+        None,
+        VerificationMode::Full,
+        &mut ErrorContextSlot::default(),
+    )?;
+
+    Ok(SecurityLevels::new::<HashFn>(&proof)
+        .with_excluded_query_positions(air.num_excluded_query_positions()))
+}
+
+/// Partially verifies that the specified computation was executed correctly against the specified
+/// inputs.
+///
+/// **This verification is unsound and must never be used to accept proofs from untrusted
+/// parties.** It is intended only for speeding up tests (e.g., fuzzing or CI of computations
+/// defined via [Air]) in which full verification time dominates test runtime.
+///
+/// Compared to [verify()], this function:
+/// - Does not check the proof parameters against a set of acceptable options.
+/// - Does not check the proof-of-work.
+/// - Computes evaluations of the DEEP composition polynomial and checks FRI layer folding only
+///   for `num_sampled_queries` queries evenly spread across the queried positions. Merkle
+///   authentication paths are still checked for all queries.
+///
+/// All other checks, including the consistency of the constraints evaluated at the out-of-domain
+/// point, are performed in the same way as by [verify()].
+///
+/// # Errors
+/// Returns an error if any of the performed checks fails. Computations with preprocessed trace
+/// columns are not supported and always fail with
+/// [VerifierError::InvalidPreprocessedTraceCommitment].
+#[cfg(feature = "unsound-testing")]
+pub fn verify_unsound_fast<AIR, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    num_sampled_queries: usize,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    validate_proof_parameters::<HashFn, RandCoin>(
+        &proof.context,
+        &AcceptableOptions::MinConjecturedSecurity(0),
+    )?;
+    let (air, public_coin_seed) =
+        build_air_and_coin_seed::<AIR, HashFn>(&proof.context, pub_inputs, &None)?;
+    verify_air::<AIR, HashFn, RandCoin>(
+        &air,
+        proof.as_borrowed(),
+        public_coin_seed,
+        None,
+        VerificationMode::UnsoundFast(num_sampled_queries),
+        &mut ErrorContextSlot::default(),
+    )
+    .map(|_| ())
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// without revealing which of the checks failed via the time it takes to verify the proof.
+///
+/// Works just like [verify()], but instead of returning an error on the first failed check, the
+/// verifier performs all of the checks of the query phase and combines their results; this
+/// includes checking Merkle authentication paths against trace and constraint commitments, the
+/// consistency of the constraints evaluated at the out-of-domain point, the proof-of-work, and
+/// the FRI layer foldings. Commitments and field elements are compared in constant time.
+///
+/// This is a best-effort measure: the time it takes to verify a proof may still depend on the
+/// values in the proof via the underlying field and hash function implementations.
+///
+/// # Errors
+/// Returns [VerifierError::ProofVerificationFailed] if any of the combined checks fails. Errors
+/// caused by unacceptable proof parameters or by a malformed proof (e.g., a proof which cannot be
+/// parsed, or in which the number of queried values does not match the number of positions
+/// queried by the verifier) are still returned as soon as they are encountered; these errors
+/// depend only on the structure of the proof. Among them is
+/// [VerifierError::InvalidPreprocessedTraceCommitment], which is returned for every computation
+/// with preprocessed trace columns, since these are not supported by this function.
+pub fn verify_constant_time<AIR, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    validate_proof_parameters::<HashFn, RandCoin>(&proof.context, acceptable_options)?;
+    let (air, public_coin_seed) =
+        build_air_and_coin_seed::<AIR, HashFn>(&proof.context, pub_inputs, &None)?;
+    verify_air::<AIR, HashFn, RandCoin>(
+        &air,
+        proof.as_borrowed(),
+        public_coin_seed,
+        None,
+        VerificationMode::ConstantTime,
+        &mut ErrorContextSlot::default(),
+    )
+    .map(|_| ())
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// using the hash functions recorded in the proof.
+///
+/// Works just like [verify()], but instead of being specified via type parameters, the hash
+/// function used for commitments and the hash function used by the public coin are determined
+/// from the proof context at runtime (see [ProofOptions::with_hash_functions()]). If both hash
+/// functions are the same, the public coin is assumed to be a [DefaultRandomCoin]; otherwise, it
+/// is assumed to be a [DualHashRandomCoin].
+///
+/// Only hash functions which are defined over any base field are supported; these are
+/// [Blake3_256], [Blake3_192], and [Sha3_256]. Proofs generated using arithmetization-friendly
+/// hash functions, which are defined over specific fields, must be verified via [verify()].
+///
+/// # Errors
+/// In addition to the errors returned by [verify()], returns an error if the proof does not
+/// record the hash functions used to generate it, or if any of these hash functions is not
+/// supported.
+pub fn verify_auto<AIR: Air>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError> {
+    let (commitment_hash_fn, random_coin_hash_fn) = proof
+        .options()
+        .hash_functions()
+        .ok_or(VerifierError::UnspecifiedHashFunctions)?;
+
+    match commitment_hash_fn {
+        HashFunctionId::Blake3_256 => {
+            verify_with_random_coin_hash::<AIR, Blake3_256<AIR::BaseField>>(
+                random_coin_hash_fn,
+                proof,
+                pub_inputs,
+                acceptable_options,
+            )
+        },
+        HashFunctionId::Blake3_192 => {
+            verify_with_random_coin_hash::<AIR, Blake3_192<AIR::BaseField>>(
+                random_coin_hash_fn,
+                proof,
+                pub_inputs,
+                acceptable_options,
+            )
+        },
+        HashFunctionId::Sha3_256 => verify_with_random_coin_hash::<AIR, Sha3_256<AIR::BaseField>>(
+            random_coin_hash_fn,
+            proof,
+            pub_inputs,
+            acceptable_options,
+        ),
+        hash_fn => Err(VerifierError::UnsupportedHashFunction(hash_fn)),
+    }
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and returns the intermediate values computed by the verifier.
+///
+/// Works just like [verify()], but on success, returns a [VerifierTrace] containing all random
+/// challenges drawn from the public coin, the out-of-domain evaluations, the evaluations of the
+/// DEEP composition polynomial at the query positions, and the values computed by folding each
+/// FRI layer. This makes it possible to cross-check each step of the verification, e.g., against
+/// an implementation of the verifier used for recursive proof verification.
+///
+/// Since the type of the intermediate values depends on the field extension used to generate the
+/// proof, the field `E` must be specified explicitly; it must be the base field of the `AIR` if
+/// the proof was generated without a field extension, or the extension of the required degree
+/// otherwise.
+///
+/// # Errors
+/// In addition to the errors returned by [verify()], returns an error if the degree of `E` over
+/// the base field does not match the field extension specified by the proof options.
+pub fn verify_with_trace<AIR, E, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<VerifierTrace<E>, VerifierError>
+where
+    AIR: Air,
+    E: FieldElement<BaseField = AIR::BaseField>,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    trace_verification::<AIR, E, HashFn, RandCoin>(&proof, pub_inputs, acceptable_options)
+        .map(|(_, trace)| trace)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and returns a witness for verifying the proof inside a constraint system defined over BN254.
+///
+/// Works just like [verify_with_trace()], but on success, returns a [SnarkWitness] which
+/// re-expresses the transcript of the verification and all commitment openings checked by the
+/// verifier as a sequence of BN254 scalar field elements. This is meant to be consumed by
+/// SNARK wrappers (e.g., Groth16 or Plonk circuits which verify STARK proofs), so that these
+/// wrappers do not need to depend on the internal layout of the proof.
+///
+/// # Errors
+/// Returns the same errors as [verify_with_trace()].
+pub fn verify_with_snark_witness<AIR, E, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<SnarkWitness, VerifierError>
+where
+    AIR: Air,
+    E: FieldElement<BaseField = AIR::BaseField>,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let public_inputs = pub_inputs.to_elements();
+    let (air, trace) =
+        trace_verification::<AIR, E, HashFn, RandCoin>(&proof, pub_inputs, acceptable_options)?;
+    build_snark_witness::<AIR, E, HashFn>(&air, proof.as_borrowed(), &public_inputs, trace)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and returns the result of the verification together with the transcript of the verification.
+///
+/// Works just like [verify()], but also records every commitment read from the proof and every
+/// challenge drawn by the verifier in a [Transcript]. The labels and the order of the entries are
+/// the same as in the transcript recorded by the prover; thus, the first point at which the
+/// verifier disagrees with the prover can be found by comparing the two transcripts via
+/// [Transcript::first_divergence()]. If the verification fails, the transcript contains all
+/// messages processed up to the point of failure; it is empty if the verification failed before
+/// any messages were read from the proof (e.g., if the proof was generated with unacceptable
+/// options).
+///
+/// Computations with preprocessed trace columns are not supported: for such computations, the
+/// verification fails with [VerifierError::InvalidPreprocessedTraceCommitment].
+pub fn verify_with_transcript<AIR, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> (Result<(), VerifierError>, Transcript)
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    if let Err(err) =
+        validate_proof_parameters::<HashFn, RandCoin>(&proof.context, acceptable_options)
+    {
+        return (Err(err), Transcript::new());
+    }
+    let (air, public_coin_seed) =
+        match build_air_and_coin_seed::<AIR, HashFn>(&proof.context, pub_inputs, &None) {
+            Ok(result) => result,
+            Err(err) => return (Err(err), Transcript::new()),
+        };
+
+    match air.options().field_extension() {
+        FieldExtension::None => transcribe_verification::<AIR, AIR::BaseField, HashFn, RandCoin>(
+            &air,
+            &proof,
+            public_coin_seed,
+        ),
+        FieldExtension::Quadratic => {
+            if !<QuadExtension<AIR::BaseField>>::is_supported() {
+                return (Err(VerifierError::UnsupportedFieldExtension(2)), Transcript::new());
+            }
+            transcribe_verification::<AIR, QuadExtension<AIR::BaseField>, HashFn, RandCoin>(
+                &air,
+                &proof,
+                public_coin_seed,
+            )
+        },
+        FieldExtension::Cubic => {
+            if !<CubeExtension<AIR::BaseField>>::is_supported() {
+                return (Err(VerifierError::UnsupportedFieldExtension(3)), Transcript::new());
+            }
+            transcribe_verification::<AIR, CubeExtension<AIR::BaseField>, HashFn, RandCoin>(
+                &air,
+                &proof,
+                public_coin_seed,
+            )
+        },
+    }
+}
+
+/// Runs the verification procedure in the field `E` with the transcript of the verification
+/// being recorded, and returns the result together with the transcript.
+fn transcribe_verification<AIR, E, HashFn, RandCoin>(
+    air: &AIR,
+    proof: &Proof,
+    public_coin_seed: Vec<AIR::BaseField>,
+) -> (Result<(), VerifierError>, Transcript)
+where
+    AIR: Air,
+    E: FieldElement<BaseField = AIR::BaseField>,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let mut channel = match VerifierChannel::new(air, proof.as_borrowed(), None) {
+        Ok(channel) => channel,
+        Err(err) => return (Err(err), Transcript::new()),
+    };
+    channel.record_transcript();
+
+    let public_coin = RandCoin::new(&public_coin_seed);
+    let result = perform_verification::<AIR, E, HashFn, RandCoin>(
+        air,
+        &mut channel,
+        public_coin,
+        VerificationMode::Full,
+    );
+    (result.map(|_| ()), channel.take_transcript().unwrap_or_default())
+}
+
+/// Verifies the specified proof, and returns the AIR instantiated for the proof together with
+/// the intermediate values computed by the verifier.
+fn trace_verification<AIR, E, HashFn, RandCoin>(
+    proof: &Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(AIR, VerifierTrace<E>), VerifierError>
+where
+    AIR: Air,
+    E: FieldElement<BaseField = AIR::BaseField>,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    validate_proof_parameters::<HashFn, RandCoin>(&proof.context, acceptable_options)?;
+    let (air, public_coin_seed) =
+        build_air_and_coin_seed::<AIR, HashFn>(&proof.context, pub_inputs, &None)?;
+
+    let extension_degree = air.options().field_extension().degree();
+    if E::EXTENSION_DEGREE != extension_degree as usize {
+        return Err(VerifierError::MismatchedFieldExtension(
+            E::EXTENSION_DEGREE as u32,
+            extension_degree,
+        ));
+    }
+
+    let public_coin = RandCoin::new(&public_coin_seed);
+    let mut channel = VerifierChannel::new(&air, proof.as_borrowed(), None)?;
+    let trace = perform_verification::<AIR, E, HashFn, RandCoin>(
+        &air,
+        &mut channel,
+        public_coin,
+        VerificationMode::Full,
+    )?;
+    Ok((air, trace))
+}
+
+/// Verifies a batch of proofs for the same computation, possibly against different public inputs.
+///
+/// Each entry in `proofs` consists of a proof and the public inputs against which it should be
+/// verified. The result of verifying each proof is returned in the same order as the proofs; the
+/// result for a given proof is the same as the one which would be returned by calling [verify()]
+/// for this proof individually.
+///
+/// Checks which depend only on the proof parameters (i.e., checking the parameters against
+/// `acceptable_options` and checking the hash functions recorded in the proof) are performed once
+/// for every distinct set of parameters in the batch. When the crate is compiled with `concurrent`
+/// feature enabled, the proofs are then verified in parallel.
+pub fn verify_batch<AIR, HashFn, RandCoin>(
+    proofs: Vec<(Proof, AIR::PublicInputs)>,
+    acceptable_options: &AcceptableOptions,
+) -> Vec<Result<(), VerifierError>>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    // validate the parameters of every distinct proof context only once
+    let mut validated_contexts: Vec<(&Context, Result<(), VerifierError>)> = Vec::new();
+    let mut validations = Vec::with_capacity(proofs.len());
+    for (proof, _) in proofs.iter() {
+        let validation = match validated_contexts.iter().find(|(ctx, _)| *ctx == &proof.context) {
+            Some((_, validation)) => validation.clone(),
+            None => {
+                let validation = validate_proof_parameters::<HashFn, RandCoin>(
+                    &proof.context,
+                    acceptable_options,
+                );
+                validated_contexts.push((&proof.context, validation.clone()));
+                validation
+            },
+        };
+        validations.push(validation);
+    }
+    drop(validated_contexts);
+
+    #[cfg(not(feature = "concurrent"))]
+    let proofs = proofs.into_iter();
+    #[cfg(feature = "concurrent")]
+    let proofs = proofs.into_par_iter();
+
+    proofs
+        .zip(validations)
+        .map(|((proof, pub_inputs), validation)| {
+            validation?;
+            run_verification::<AIR, HashFn, RandCoin>(
+                proof,
+                pub_inputs,
+                None,
+                &mut ErrorContextSlot::default(),
+            )
+            .map(|_| ())
+        })
+        .collect()
+}
+
+// VERIFICATION PROCEDURE
+// ================================================================================================
+/// Validates the proof parameters, instantiates the AIR, and runs the verification procedure in
+/// the extension field specified by the proof options.
+///
+/// If `preprocessed_commitment` is provided, it is used as the commitment to the preprocessed
+/// columns of the main trace segment. On success, the query positions are returned.
+fn dispatch_verification<AIR, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    preprocessed_commitment: Option<HashFn::Digest>,
+) -> Result<Vec<usize>, VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    validate_proof_parameters::<HashFn, RandCoin>(&proof.context, acceptable_options)?;
+    run_verification::<AIR, HashFn, RandCoin>(
+        proof,
+        pub_inputs,
+        preprocessed_commitment,
+        &mut ErrorContextSlot::default(),
+    )
+}
+
+/// Checks that a proof with the specified `context` was generated with an acceptable set of
+/// parameters from the point of view of the verifier, and with the hash functions used by the
+/// verifier (if the proof records them).
+fn validate_proof_parameters<HashFn, RandCoin>(
+    context: &Context,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    HashFn: ElementHasher,
+    RandCoin: RandomCoin<Hasher = HashFn>,
 {
     // check that `proof` was generated with an acceptable set of parameters from the point of view
     // of the verifier
-    acceptable_options.validate::<HashFn>(&proof)?;
+    acceptable_options.validate_context::<HashFn>(context)?;
 
-    // build a seed for the public coin; the initial seed is a hash of the proof context and the
-    // public inputs, but as the protocol progresses, the coin will be reseeded with the info
-    // received from the prover
-    let mut public_coin_seed = proof.context.to_elements();
-    public_coin_seed.append(&mut pub_inputs.to_elements());
+    // if the proof records the hash functions used to generate it, make sure they are the ones
+    // used by the verifier
+    if let Some(hash_functions) = context.options().hash_functions() {
+        if hash_functions != (HashFn::ID, RandCoin::HASH_FUNCTION_ID) {
+            return Err(VerifierError::MismatchedHashFunctions);
+        }
+    }
 
-    // create AIR instance for the computation specified in the proof
-    let air = AIR::new(proof.trace_info().clone(), pub_inputs, proof.options().clone());
+    // if the proof records the truncation width of commitment digests, make sure the digests of
+    // the hash function used by the verifier have the same size
+    if let Some(num_digest_bytes) = context.options().digest_truncation() {
+        let digest_size = HashFn::Digest::default().serialized_size();
+        if num_digest_bytes != digest_size {
+            return Err(VerifierError::MismatchedDigestSize(num_digest_bytes, digest_size));
+        }
+    }
+
+    // if the proof records the digest of the protocol configuration used to generate it, make
+    // sure it is the digest of the configuration of the verifier
+    if let Some(parameters_digest) = context.options().recorded_parameters_digest() {
+        let expected =
+            context.options().parameters_digest::<RandCoin::BaseField, HashFn, RandCoin>();
+        if parameters_digest != expected {
+            return Err(VerifierError::MismatchedParametersDigest);
+        }
+    }
+
+    Ok(())
+}
+
+/// Instantiates the AIR and runs the verification procedure for a proof whose parameters have
+/// already been validated (see [validate_proof_parameters()]).
+///
+/// If the verification fails, the context of the failure is stored in `error_context`.
+fn run_verification<AIR, HashFn, RandCoin>(
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    preprocessed_commitment: Option<HashFn::Digest>,
+    error_context: &mut ErrorContextSlot,
+) -> Result<Vec<usize>, VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let (air, public_coin_seed) = build_air_and_coin_seed::<AIR, HashFn>(
+        &proof.context,
+        pub_inputs,
+        &preprocessed_commitment,
+    )?;
+    verify_air::<AIR, HashFn, RandCoin>(
+        &air,
+        proof.as_borrowed(),
+        public_coin_seed,
+        preprocessed_commitment,
+        VerificationMode::Full,
+        error_context,
+    )
+}
 
+/// Runs the verification procedure for the specified instance of the AIR, selecting the field in
+/// which the procedure is executed based on the field extension specified by the proof options.
+///
+/// If the verification fails, the context of the failure is stored in `error_context`.
+fn verify_air<AIR, HashFn, RandCoin>(
+    air: &AIR,
+    proof: ProofRef<'_>,
+    public_coin_seed: Vec<AIR::BaseField>,
+    preprocessed_commitment: Option<HashFn::Digest>,
+    mode: VerificationMode,
+    error_context: &mut ErrorContextSlot,
+) -> Result<Vec<usize>, VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
     // figure out which version of the generic proof verification procedure to run. this is a sort
     // of static dispatch for selecting two generic parameter: extension field and hash function.
     match air.options().field_extension() {
         FieldExtension::None => {
             let public_coin = RandCoin::new(&public_coin_seed);
-            let channel = VerifierChannel::new(&air, proof)?;
-            perform_verification::<AIR, AIR::BaseField, HashFn, RandCoin>(air, channel, public_coin)
+            let mut channel = VerifierChannel::new(air, proof, preprocessed_commitment)?;
+            let result = perform_verification::<AIR, AIR::BaseField, HashFn, RandCoin>(
+                air,
+                &mut channel,
+                public_coin,
+                mode,
+            );
+            *error_context = channel.take_error_context();
+            result.map(|trace| trace.query_positions)
         },
         FieldExtension::Quadratic => {
             if !<QuadExtension<AIR::BaseField>>::is_supported() {
                 return Err(VerifierError::UnsupportedFieldExtension(2));
             }
             let public_coin = RandCoin::new(&public_coin_seed);
-            let channel = VerifierChannel::new(&air, proof)?;
-            perform_verification::<AIR, QuadExtension<AIR::BaseField>, HashFn, RandCoin>(
+            let mut channel = VerifierChannel::new(air, proof, preprocessed_commitment)?;
+            let result = perform_verification::<AIR, QuadExtension<AIR::BaseField>, HashFn, RandCoin>(
                 air,
-                channel,
+                &mut channel,
                 public_coin,
-            )
+                mode,
+            );
+            *error_context = channel.take_error_context();
+            result.map(|trace| trace.query_positions)
         },
         FieldExtension::Cubic => {
             if !<CubeExtension<AIR::BaseField>>::is_supported() {
                 return Err(VerifierError::UnsupportedFieldExtension(3));
             }
             let public_coin = RandCoin::new(&public_coin_seed);
-            let channel = VerifierChannel::new(&air, proof)?;
-            perform_verification::<AIR, CubeExtension<AIR::BaseField>, HashFn, RandCoin>(
+            let mut channel = VerifierChannel::new(air, proof, preprocessed_commitment)?;
+            let result = perform_verification::<AIR, CubeExtension<AIR::BaseField>, HashFn, RandCoin>(
                 air,
-                channel,
+                &mut channel,
                 public_coin,
-            )
+                mode,
+            );
+            *error_context = channel.take_error_context();
+            result.map(|trace| trace.query_positions)
         },
     }
 }
 
-// VERIFICATION PROCEDURE
-// ================================================================================================
-/// Performs the actual verification by reading the data from the `channel` and making sure it
-/// attests to a correct execution of the computation specified by the provided `air`.
-fn perform_verification<A, E, H, R>(
-    air: A,
-    mut channel: VerifierChannel<E, H>,
-    mut public_coin: R,
+/// Verifies the proof using `HashFn` for commitments and a public coin which draws elements using
+/// the hash function identified by `random_coin_hash_fn`.
+///
+/// This is the second step of the runtime hash function dispatch performed by [verify_auto()].
+fn verify_with_random_coin_hash<AIR, HashFn>(
+    random_coin_hash_fn: HashFunctionId,
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
 ) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+{
+    if random_coin_hash_fn == HashFn::ID {
+        return verify::<AIR, HashFn, DefaultRandomCoin<HashFn>>(
+            proof,
+            pub_inputs,
+            acceptable_options,
+        );
+    }
+
+    match random_coin_hash_fn {
+        HashFunctionId::Blake3_256 => verify::<
+            AIR,
+            HashFn,
+            DualHashRandomCoin<HashFn, Blake3_256<AIR::BaseField>>,
+        >(proof, pub_inputs, acceptable_options),
+        HashFunctionId::Blake3_192 => verify::<
+            AIR,
+            HashFn,
+            DualHashRandomCoin<HashFn, Blake3_192<AIR::BaseField>>,
+        >(proof, pub_inputs, acceptable_options),
+        HashFunctionId::Sha3_256 => verify::<
+            AIR,
+            HashFn,
+            DualHashRandomCoin<HashFn, Sha3_256<AIR::BaseField>>,
+        >(proof, pub_inputs, acceptable_options),
+        hash_fn => Err(VerifierError::UnsupportedHashFunction(hash_fn)),
+    }
+}
+
+/// Reads the values of the cells declared as public outputs by the specified `air` from its
+/// assertions, and builds the public outputs from them.
+fn get_public_outputs<AIR: AirWithOutputs>(air: &AIR) -> Result<AIR::PublicOutputs, VerifierError> {
+    let assertions = air.get_assertions();
+    let trace_length = air.trace_length();
+
+    let values = air
+        .public_output_cells()
+        .into_iter()
+        .map(|(column, step)| {
+            assertions
+                .iter()
+                .filter(|assertion| assertion.column() == column)
+                .find_map(|assertion| assertion.get_value_at(step, trace_length))
+                .ok_or(VerifierError::PublicOutputNotAsserted(column, step))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(air.build_public_outputs(values))
+}
+
+/// Instantiates the AIR for the computation specified by the proof `context`, and builds the
+/// initial seed for the public coin.
+fn build_air_and_coin_seed<AIR, HashFn>(
+    context: &Context,
+    pub_inputs: AIR::PublicInputs,
+    preprocessed_commitment: &Option<HashFn::Digest>,
+) -> Result<(AIR, Vec<AIR::BaseField>), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+{
+    // build a seed for the public coin; the initial seed is a hash of the proof context and the
+    // public inputs, but as the protocol progresses, the coin will be reseeded with the info
+    // received from the prover
+    let mut public_coin_seed = context.to_elements();
+    public_coin_seed.append(&mut pub_inputs.to_elements());
+
+    // make sure the LDE domain fits into the two-adic subgroup of the base field; otherwise, the
+    // AIR cannot be instantiated
+    let log_blowup_factor = context.options().blowup_factor().ilog2();
+    let max_trace_length = 1_usize << (AIR::BaseField::TWO_ADICITY - log_blowup_factor);
+    let trace_length = context.trace_info().length();
+    if trace_length > max_trace_length {
+        return Err(VerifierError::TraceLengthTooLarge(max_trace_length, trace_length));
+    }
+
+    // create AIR instance for the computation specified in the proof
+    let air = AIR::new(context.trace_info().clone(), pub_inputs, context.options().clone());
+
+    // make sure the commitment to the preprocessed columns is provided if and only if the AIR
+    // declares such columns
+    let has_preprocessed_columns = air.context().num_preprocessed_columns() > 0;
+    if has_preprocessed_columns != preprocessed_commitment.is_some() {
+        return Err(VerifierError::InvalidPreprocessedTraceCommitment);
+    }
+
+    Ok((air, public_coin_seed))
+}
+
+/// Reads the data from the `channel` up to the out-of-domain consistency check, and computes the
+/// values needed to perform this check; the `public_coin` is updated with all commitments and
+/// out-of-domain evaluations read from the channel.
+fn read_ood_evaluations<A, E, H, R>(
+    air: &A,
+    channel: &mut VerifierChannel<E, H>,
+    public_coin: &mut R,
+) -> Result<OodEvaluations<E>, VerifierError>
 where
     E: FieldElement<BaseField = A::BaseField>,
     A: Air,
@@ -160,12 +1155,25 @@ where
     // used to draw random elements needed to construct the next trace segment. The last trace
     // commitment is used to draw a set of random coefficients which the prover uses to compute
     // constraint composition polynomial.
-    const MAIN_TRACE_IDX: usize = 0;
-    const AUX_TRACE_IDX: usize = 1;
-    let trace_commitments = channel.read_trace_commitments();
+    // If the main trace segment is partitioned into tables, there is a commitment for each table,
+    // and the commitment to the auxiliary trace segment follows the commitments to all tables.
+    let num_main_trace_tables = air.context().num_main_trace_tables();
+    let aux_trace_idx = num_main_trace_tables;
+    let trace_commitments = channel.read_trace_commitments().to_vec();
+
+    // if the main trace segment contains preprocessed columns, reseed the coin with the commitment
+    // to these columns first; this commitment is known to the verifier in advance
+    if let Some(preprocessed_commitment) = channel.read_preprocessed_trace_commitment() {
+        channel.record_commitment("preprocessed_trace_root", &[preprocessed_commitment]);
+        public_coin.reseed(preprocessed_commitment);
+    }
 
-    // reseed the coin with the commitment to the main trace segment
-    public_coin.reseed(trace_commitments[MAIN_TRACE_IDX]);
+    // reseed the coin with the commitments to the tables of the main trace segment merged into
+    // a single digest
+    channel.record_commitment("trace_root", &trace_commitments[..num_main_trace_tables]);
+    public_coin.reseed(Commitments::merge_main_trace_table_roots::<H>(
+        &trace_commitments[..num_main_trace_tables],
+    ));
 
     // process auxiliary trace segments (if any), to build a set of random elements for each segment
     let aux_trace_rand_elements = if air.trace_info().is_multi_segment() {
@@ -180,22 +1188,27 @@ where
             };
             let lagrange_rand_elements = air
                 .get_auxiliary_proof_verifier::<E>()
-                .verify::<E, _>(gkr_proof, &mut public_coin)
+                .verify::<E, _>(gkr_proof, public_coin)
                 .map_err(|err| VerifierError::GkrProofVerificationFailed(err.to_string()))?;
 
-            let rand_elements = air.get_aux_rand_elements(&mut public_coin).expect(
+            let rand_elements = air.get_aux_rand_elements(public_coin).expect(
                 "failed to generate the random elements needed to build the auxiliary trace",
             );
+            channel.record_challenge("lagrange_kernel_rand_elements", &lagrange_rand_elements);
+            channel.record_challenge("aux_rand_elements", &rand_elements);
 
-            public_coin.reseed(trace_commitments[AUX_TRACE_IDX]);
+            channel.record_commitment("trace_root", &[trace_commitments[aux_trace_idx]]);
+            public_coin.reseed(trace_commitments[aux_trace_idx]);
 
             Some(AuxRandElements::new_with_lagrange(rand_elements, Some(lagrange_rand_elements)))
         } else {
-            let rand_elements = air.get_aux_rand_elements(&mut public_coin).expect(
+            let rand_elements = air.get_aux_rand_elements(public_coin).expect(
                 "failed to generate the random elements needed to build the auxiliary trace",
             );
+            channel.record_challenge("aux_rand_elements", &rand_elements);
 
-            public_coin.reseed(trace_commitments[AUX_TRACE_IDX]);
+            channel.record_commitment("trace_root", &[trace_commitments[aux_trace_idx]]);
+            public_coin.reseed(trace_commitments[aux_trace_idx]);
 
             Some(AuxRandElements::new(rand_elements))
         }
@@ -205,8 +1218,14 @@ where
 
     // build random coefficients for the composition polynomial
     let constraint_coeffs = air
-        .get_constraint_composition_coefficients(&mut public_coin)
+        .get_constraint_composition_coefficients(public_coin)
         .map_err(|_| VerifierError::RandomCoinError)?;
+    channel.record_challenge("transition_composition_coeffs", &constraint_coeffs.transition);
+    channel.record_challenge("boundary_composition_coeffs", &constraint_coeffs.boundary);
+    if let Some(lagrange) = &constraint_coeffs.lagrange {
+        channel.record_challenge("lagrange_transition_composition_coeffs", &lagrange.transition);
+        channel.record_challenge("lagrange_boundary_composition_coeff", &[lagrange.boundary]);
+    }
 
     // 2 ----- constraint commitment --------------------------------------------------------------
     // read the commitment to evaluations of the constraint composition polynomial over the LDE
@@ -215,12 +1234,15 @@ where
     // to the prover, and the prover evaluates trace and constraint composition polynomials at z,
     // and sends the results back to the verifier.
     let constraint_commitment = channel.read_constraint_commitment();
+    channel.record_commitment("constraint_root", &[constraint_commitment]);
     public_coin.reseed(constraint_commitment);
     let z = public_coin.draw::<E>().map_err(|_| VerifierError::RandomCoinError)?;
+    channel.record_challenge("ood_point", &[z]);
 
-    // 3 ----- OOD consistency check --------------------------------------------------------------
-    // make sure that evaluations obtained by evaluating constraints over the out-of-domain frame
-    // are consistent with the evaluations of composition polynomial columns sent by the prover
+    // 3 ----- OOD evaluations --------------------------------------------------------------------
+    // compute the values needed to make sure that evaluations obtained by evaluating constraints
+    // over the out-of-domain frame are consistent with the evaluations of composition polynomial
+    // columns sent by the prover
 
     // read the out-of-domain trace frames (the main trace frame and auxiliary trace frame, if
     // provided) sent by the prover and evaluate constraints over them; also, reseed the public
@@ -229,16 +1251,18 @@ where
     let ood_main_trace_frame = ood_trace_frame.main_frame();
     let ood_aux_trace_frame = ood_trace_frame.aux_frame();
     let ood_lagrange_kernel_frame = ood_trace_frame.lagrange_kernel_frame();
-    let ood_constraint_evaluation_1 = evaluate_constraints(
-        &air,
-        constraint_coeffs,
+    let constraint_evaluations = evaluate_constraints(
+        air,
+        constraint_coeffs.clone(),
         &ood_main_trace_frame,
         &ood_aux_trace_frame,
         ood_lagrange_kernel_frame,
         aux_trace_rand_elements.as_ref(),
         z,
     );
-    public_coin.reseed(ood_trace_frame.hash::<H>());
+    let ood_trace_states_hash = ood_trace_frame.hash::<H>();
+    channel.record_commitment("ood_trace_states_hash", &[ood_trace_states_hash]);
+    public_coin.reseed(ood_trace_states_hash);
 
     // read evaluations of composition polynomial columns sent by the prover, and reduce them into
     // a single value by computing \sum_{i=0}^{m-1}(z^(i * l) * value_i), where value_i is the
@@ -248,19 +1272,92 @@ where
     // H(X) = \sum_{i=0}^{m-1} X^{i * l} H_i(X).
     // Also, reseed the public coin with the OOD constraint evaluations received from the prover.
     let ood_constraint_evaluations = channel.read_ood_constraint_evaluations();
-    let ood_constraint_evaluation_2 =
+    let composition_evaluation =
         ood_constraint_evaluations
             .iter()
             .enumerate()
             .fold(E::ZERO, |result, (i, &value)| {
                 result + z.exp_vartime(((i * (air.trace_length())) as u32).into()) * value
             });
+    channel.record_commitment("ood_constraint_evaluations", &ood_constraint_evaluations);
     public_coin.reseed(H::hash_elements(&ood_constraint_evaluations));
 
-    // finally, make sure the values are the same
-    if ood_constraint_evaluation_1 != ood_constraint_evaluation_2 {
-        return Err(VerifierError::InconsistentOodConstraintEvaluations);
+    Ok(OodEvaluations {
+        aux_rand_elements: aux_trace_rand_elements,
+        constraint_coeffs,
+        z,
+        main_trace_frame: ood_main_trace_frame,
+        aux_trace_frame: ood_aux_trace_frame,
+        lagrange_kernel_frame: ood_lagrange_kernel_frame.cloned(),
+        constraint_evaluations,
+        composition_column_evaluations: ood_constraint_evaluations,
+        composition_evaluation,
+    })
+}
+
+/// Performs the actual verification by reading the data from the `channel` and making sure it
+/// attests to a correct execution of the computation specified by the provided `air`. On
+/// success, the intermediate values computed during verification are returned.
+fn perform_verification<A, E, H, R>(
+    air: &A,
+    channel: &mut VerifierChannel<E, H>,
+    mut public_coin: R,
+    mode: VerificationMode,
+) -> Result<VerifierTrace<E>, VerifierError>
+where
+    E: FieldElement<BaseField = A::BaseField>,
+    A: Air,
+    H: ElementHasher<BaseField = A::BaseField>,
+    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+{
+    // make sure the AIR does not exclude too many positions from querying; otherwise, the
+    // security level estimates would not hold
+    let num_excluded_positions = air.num_excluded_query_positions();
+    let max_excluded_positions = air.context().max_excluded_query_positions();
+    if num_excluded_positions > max_excluded_positions {
+        return Err(VerifierError::TooManyExcludedQueryPositions {
+            num_excluded: num_excluded_positions,
+            max_excluded: max_excluded_positions,
+        });
+    }
+
+    // 1, 2 ----- trace and constraint commitments ----------------------------------------------
+    // read the trace and constraint commitments and out-of-domain evaluations sent by the prover,
+    // and evaluate constraints over the out-of-domain trace frames (see read_ood_evaluations())
+    let OodEvaluations {
+        aux_rand_elements: aux_trace_rand_elements,
+        constraint_coeffs,
+        z,
+        main_trace_frame: ood_main_trace_frame,
+        aux_trace_frame: ood_aux_trace_frame,
+        lagrange_kernel_frame: ood_lagrange_kernel_frame,
+        constraint_evaluations,
+        composition_column_evaluations: ood_constraint_evaluations,
+        composition_evaluation: ood_constraint_evaluation_2,
+    } = read_ood_evaluations(air, channel, &mut public_coin)?;
+    let ood_lagrange_kernel_frame = ood_lagrange_kernel_frame.as_ref();
+    let ood_constraint_evaluation_1 = constraint_evaluations.combined();
+
+    // 3 ----- OOD consistency check --------------------------------------------------------------
+    // make sure that evaluations obtained by evaluating constraints over the out-of-domain frame
+    // are consistent with the evaluations of composition polynomial columns sent by the prover
+    let mut checks = Checks::new(mode);
+    let is_ood_consistent = if checks.is_constant_time() {
+        constant_time_eq(
+            E::elements_as_bytes(&[ood_constraint_evaluation_1]),
+            E::elements_as_bytes(&[ood_constraint_evaluation_2]),
+        )
+    } else {
+        ood_constraint_evaluation_1 == ood_constraint_evaluation_2
+    };
+    #[cfg(feature = "diagnostics")]
+    if !is_ood_consistent {
+        channel.record_error_context(ErrorContext::OodConstraintEvaluations {
+            from_trace: ood_constraint_evaluation_1.to_string(),
+            from_composition: ood_constraint_evaluation_2.to_string(),
+        });
     }
+    checks.check(is_ood_consistent, VerifierError::InconsistentOodConstraintEvaluations)?;
 
     // 4 ----- FRI commitments --------------------------------------------------------------------
     // draw coefficients for computing DEEP composition polynomial from the public coin; in the
@@ -270,6 +1367,11 @@ where
     let deep_coefficients = air
         .get_deep_composition_coefficients::<E, R>(&mut public_coin)
         .map_err(|_| VerifierError::RandomCoinError)?;
+    channel.record_challenge("deep_trace_coeffs", &deep_coefficients.trace);
+    channel.record_challenge("deep_constraint_coeffs", &deep_coefficients.constraints);
+    if let Some(lagrange) = deep_coefficients.lagrange {
+        channel.record_challenge("deep_lagrange_coeff", &[lagrange]);
+    }
 
     // instantiates a FRI verifier with the FRI layer commitments read from the channel. From the
     // verifier's perspective, this is equivalent to executing the commit phase of the FRI protocol.
@@ -277,68 +1379,301 @@ where
     // from them; in the interactive version of the protocol, the verifier sends these alphas to
     // the prover, and the prover uses them to compute and commit to the subsequent FRI layers.
     let fri_verifier = FriVerifier::new(
-        &mut channel,
+        channel,
         &mut public_coin,
         air.options().to_fri_options(),
         air.trace_poly_degree(),
     )
-    .map_err(VerifierError::FriVerificationFailed)?;
+    .map_err(|err| {
+        #[cfg(feature = "diagnostics")]
+        channel.record_fri_error_context(&err);
+        VerifierError::FriVerificationFailed(err)
+    })?;
     // TODO: make sure air.lde_domain_size() == fri_verifier.domain_size()
 
+    // the prover does not draw a folding challenge for the remainder, since the remainder is sent
+    // in the clear; thus, the last alpha is not recorded in the transcript
+    let num_fri_commitments = fri_verifier.layer_commitments().len();
+    for (depth, commitment) in fri_verifier.layer_commitments().iter().enumerate() {
+        channel.record_commitment("fri_layer_root", &[*commitment]);
+        if depth != num_fri_commitments - 1 {
+            channel.record_challenge("fri_alpha", &[fri_verifier.layer_alphas()[depth]]);
+        }
+    }
+
     // 5 ----- trace and constraint queries -------------------------------------------------------
     // read proof-of-work nonce sent by the prover
     let pow_nonce = channel.read_pow_nonce();
+    channel.record_commitment("pow_nonce", &[pow_nonce]);
 
     // make sure the proof-of-work specified by the grinding factor is satisfied
-    if public_coin.check_leading_zeros(pow_nonce) < air.options().grinding_factor() {
-        return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed);
+    if mode.checks_proof_of_work() {
+        checks.check(
+            public_coin.check_leading_zeros(pow_nonce) >= air.options().grinding_factor(),
+            VerifierError::QuerySeedProofOfWorkVerificationFailed,
+        )?;
     }
 
     // draw pseudo-random query positions for the LDE domain from the public coin; in the
     // interactive version of the protocol, the verifier sends these query positions to the prover,
     // and the prover responds with decommitments against these positions for trace and constraint
-    // composition polynomial evaluations.
-    let mut query_positions = public_coin
-        .draw_integers(air.options().num_queries(), air.lde_domain_size(), pow_nonce)
-        .map_err(|_| VerifierError::RandomCoinError)?;
-
-    // remove any potential duplicates from the positions as the prover will send openings only
-    // for unique queries
-    query_positions.sort_unstable();
-    query_positions.dedup();
+    // composition polynomial evaluations. duplicates are removed from the positions as the
+    // prover sends openings only for unique queries; positions excluded by the AIR are never
+    // drawn
+    let query_positions = draw_query_positions_with_exclusions(
+        &mut public_coin,
+        air.options().num_queries(),
+        air.lde_domain_size(),
+        pow_nonce,
+        |position| air.is_query_position_excluded(position),
+    )
+    .map_err(|_| VerifierError::RandomCoinError)?;
+    let positions = query_positions.iter().map(|&p| p as u64).collect::<Vec<_>>();
+    channel.record_challenge("query_positions", &positions);
 
     // read evaluations of trace and constraint composition polynomials at the queried positions;
     // this also checks that the read values are valid against trace and constraint commitments
     let (queried_main_trace_states, queried_aux_trace_states) =
-        channel.read_queried_trace_states(&query_positions)?;
-    let queried_constraint_evaluations = channel.read_constraint_evaluations(&query_positions)?;
+        channel.read_queried_trace_states(&query_positions, &mut checks)?;
+    let queried_constraint_evaluations =
+        channel.read_constraint_evaluations(&query_positions, &mut checks)?;
+
+    // in the unsound fast mode, keep only the sampled queries for the remaining checks
+    let (
+        sampled_positions,
+        queried_main_trace_states,
+        queried_aux_trace_states,
+        queried_constraint_evaluations,
+    ) = match mode {
+        VerificationMode::Full | VerificationMode::ConstantTime => (
+            query_positions.clone(),
+            queried_main_trace_states,
+            queried_aux_trace_states,
+            queried_constraint_evaluations,
+        ),
+        #[cfg(feature = "unsound-testing")]
+        VerificationMode::UnsoundFast(num_sampled_queries) => {
+            let indexes = sample_query_indexes(query_positions.len(), num_sampled_queries);
+            (
+                indexes.iter().map(|&idx| query_positions[idx]).collect(),
+                queried_main_trace_states.select_rows(&indexes),
+                queried_aux_trace_states.map(|states| states.select_rows(&indexes)),
+                queried_constraint_evaluations.select_rows(&indexes),
+            )
+        },
+    };
 
     // 6 ----- DEEP composition -------------------------------------------------------------------
     // compute evaluations of the DEEP composition polynomial at the queried positions
-    let composer = DeepComposer::new(&air, &query_positions, z, deep_coefficients);
+    let composer = DeepComposer::new(air, &sampled_positions, z, deep_coefficients.clone());
     let t_composition = composer.compose_trace_columns(
         queried_main_trace_states,
         queried_aux_trace_states,
-        ood_main_trace_frame,
-        ood_aux_trace_frame,
+        ood_main_trace_frame.clone(),
+        ood_aux_trace_frame.clone(),
         ood_lagrange_kernel_frame,
     );
-    let c_composition = composer
-        .compose_constraint_evaluations(queried_constraint_evaluations, ood_constraint_evaluations);
+    let c_composition = composer.compose_constraint_evaluations(
+        queried_constraint_evaluations,
+        ood_constraint_evaluations.clone(),
+    );
     let deep_evaluations = composer.combine_compositions(t_composition, c_composition);
 
     // 7 ----- Verify low-degree proof -------------------------------------------------------------
     // make sure that evaluations of the DEEP composition polynomial we computed in the previous
     // step are in fact evaluations of a polynomial of degree equal to trace polynomial degree
-    fri_verifier
-        .verify(&mut channel, &deep_evaluations, &query_positions)
-        .map_err(VerifierError::FriVerificationFailed)
+    let fri_result = match mode {
+        VerificationMode::Full => fri_verifier
+            .verify_with_folded_evaluations(channel, &deep_evaluations, &query_positions)
+            .map(|folded_evaluations| (folded_evaluations, true)),
+        #[cfg(feature = "unsound-testing")]
+        VerificationMode::UnsoundFast(_) => fri_verifier
+            .verify_sampled_queries(
+                channel,
+                &deep_evaluations,
+                &query_positions,
+                &sampled_positions,
+            )
+            .map(|_| (Vec::new(), true)),
+        VerificationMode::ConstantTime => fri_verifier
+            .verify_constant_time(channel, &deep_evaluations, &query_positions)
+            .map(|is_valid| (Vec::new(), is_valid)),
+    };
+    let (fri_folded_evaluations, is_fri_valid) = fri_result.map_err(|err| {
+        #[cfg(feature = "diagnostics")]
+        channel.record_fri_error_context(&err);
+        VerifierError::FriVerificationFailed(err)
+    })?;
+    checks.check(is_fri_valid, VerifierError::ProofVerificationFailed)?;
+    checks.finish()?;
+
+    Ok(VerifierTrace {
+        aux_rand_elements: aux_trace_rand_elements,
+        constraint_coefficients: constraint_coeffs,
+        ood_point: z,
+        ood_main_trace_frame,
+        ood_aux_trace_frame,
+        ood_lagrange_kernel_frame: ood_lagrange_kernel_frame.cloned(),
+        ood_constraint_evaluation: ood_constraint_evaluation_1,
+        ood_constraint_evaluations,
+        deep_coefficients,
+        fri_layer_alphas: fri_verifier.layer_alphas().to_vec(),
+        pow_nonce,
+        query_positions,
+        deep_evaluations,
+        fri_folded_evaluations,
+    })
+}
+
+/// Returns indexes of `num_sampled_queries` queries spread evenly across `num_queries` queries.
+#[cfg(feature = "unsound-testing")]
+fn sample_query_indexes(num_queries: usize, num_sampled_queries: usize) -> Vec<usize> {
+    let num_sampled_queries = num_sampled_queries.clamp(1, num_queries);
+    (0..num_sampled_queries)
+        .map(|i| i * num_queries / num_sampled_queries)
+        .collect()
+}
+
+/// Deserializes a value from the provided bytes and makes sure that all bytes were consumed.
+fn read_exact<T: Deserializable>(bytes: &[u8]) -> Result<T, DeserializationError> {
+    let mut reader = SliceReader::new(bytes);
+    let value = T::read_from(&mut reader)?;
+    if reader.has_more_bytes() {
+        return Err(DeserializationError::UnconsumedBytes);
+    }
+    Ok(value)
+}
+
+// VERIFICATION MODE
+// ================================================================================================
+
+/// Specifies which checks are performed by the verification procedure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VerificationMode {
+    /// All checks are performed.
+    Full,
+    /// The proof-of-work is not checked, and DEEP composition and FRI layer folding are checked
+    /// only for the specified number of queries (see [verify_unsound_fast()]).
+    #[cfg(feature = "unsound-testing")]
+    UnsoundFast(usize),
+    /// All checks are performed, but failed checks do not cause an early return (see
+    /// [verify_constant_time()]).
+    ConstantTime,
+}
+
+impl VerificationMode {
+    /// Returns true if the proof-of-work should be checked in this mode.
+    fn checks_proof_of_work(&self) -> bool {
+        match self {
+            #[cfg(feature = "unsound-testing")]
+            Self::UnsoundFast(_) => false,
+            _ => true,
+        }
+    }
+}
+
+// VERIFICATION CHECKS
+// ================================================================================================
+
+/// Accumulates the results of the checks performed by the verification procedure.
+///
+/// In constant-time mode, failed checks are recorded, and the verification fails only after all
+/// of the checks have been performed; otherwise, the verification fails on the first failed check.
+pub(crate) struct Checks {
+    constant_time: bool,
+    is_valid: bool,
+}
+
+impl Checks {
+    /// Returns a new accumulator of check results for the specified verification mode.
+    fn new(mode: VerificationMode) -> Self {
+        Self {
+            constant_time: mode == VerificationMode::ConstantTime,
+            is_valid: true,
+        }
+    }
+
+    /// Returns true if failed checks should not cause an early return.
+    pub fn is_constant_time(&self) -> bool {
+        self.constant_time
+    }
+
+    /// Records the result of a check.
+    ///
+    /// # Errors
+    /// Returns `error` if the check failed and the accumulator is not in constant-time mode.
+    pub fn check(&mut self, is_valid: bool, error: VerifierError) -> Result<(), VerifierError> {
+        if self.constant_time {
+            self.is_valid &= is_valid;
+            Ok(())
+        } else if is_valid {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Returns an error if any of the recorded checks failed.
+    fn finish(self) -> Result<(), VerifierError> {
+        if self.is_valid {
+            Ok(())
+        } else {
+            Err(VerifierError::ProofVerificationFailed)
+        }
+    }
+}
+
+// SECURITY LEVELS
+// ================================================================================================
+
+/// Security levels (in bits) provided by a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecurityLevels {
+    /// Conjectured security level of the proof.
+    pub conjectured: u32,
+    /// Proven security level of the proof.
+    pub proven: u32,
+}
+
+impl SecurityLevels {
+    /// Returns security levels of the specified proof assuming that the proof was generated
+    /// using hash function `H` for commitments.
+    ///
+    /// The returned levels do not take positions excluded from querying into account (see
+    /// [SecurityLevels::with_excluded_query_positions()]).
+    pub fn new<H: Hasher>(proof: &Proof) -> Self {
+        Self {
+            conjectured: proof.security_level::<H>(true),
+            proven: proof.security_level::<H>(false),
+        }
+    }
+
+    /// Returns these security levels adjusted for the specified number of positions excluded
+    /// from querying by the AIR.
+    ///
+    /// As long as the number of excluded positions does not exceed
+    /// [AirContext::max_excluded_query_positions()](air::AirContext::max_excluded_query_positions),
+    /// exclusions reduce security by at most one bit; thus, both levels are reduced by one bit
+    /// when any positions are excluded.
+    pub fn with_excluded_query_positions(self, num_excluded_positions: usize) -> Self {
+        if num_excluded_positions == 0 {
+            return self;
+        }
+        Self {
+            conjectured: self.conjectured.saturating_sub(1),
+            proven: self.proven.saturating_sub(1),
+        }
+    }
 }
 
 // ACCEPTABLE OPTIONS
 // ================================================================================================
-// Specifies either the minimal, conjectured or proven, security level or a set of
-// `ProofOptions` that are acceptable by the verification procedure.
+// Specifies either the minimal, conjectured or proven, security level, a set of `ProofOptions`,
+// or a policy describing parameters that are acceptable by the verification procedure.
+//
+// New ways of specifying acceptable parameters may be added in the future; thus, matches on this
+// enum outside of this crate must include a wildcard arm.
+#[non_exhaustive]
 pub enum AcceptableOptions {
     /// Minimal acceptable conjectured security level
     MinConjecturedSecurity(u32),
@@ -346,14 +1681,25 @@ pub enum AcceptableOptions {
     MinProvenSecurity(u32),
     /// Set of acceptable proof parameters
     OptionSet(Vec<ProofOptions>),
+    /// Policy which acceptable proof parameters must satisfy
+    Policy(VerificationPolicy),
 }
 
 impl AcceptableOptions {
     /// Checks that a proof was generated using an acceptable set of parameters.
     pub fn validate<H: Hasher>(&self, proof: &Proof) -> Result<(), VerifierError> {
+        self.validate_context::<H>(&proof.context)
+    }
+
+    /// Checks that proofs generated in the specified context use an acceptable set of
+    /// parameters.
+    pub(crate) fn validate_context<H: Hasher>(
+        &self,
+        context: &Context,
+    ) -> Result<(), VerifierError> {
         match self {
             AcceptableOptions::MinConjecturedSecurity(minimal_security) => {
-                let proof_security = proof.security_level::<H>(true);
+                let proof_security = context.security_level::<H>(true);
                 if proof_security < *minimal_security {
                     return Err(VerifierError::InsufficientConjecturedSecurity(
                         *minimal_security,
@@ -362,7 +1708,7 @@ impl AcceptableOptions {
                 }
             },
             AcceptableOptions::MinProvenSecurity(minimal_security) => {
-                let proof_security = proof.security_level::<H>(false);
+                let proof_security = context.security_level::<H>(false);
                 if proof_security < *minimal_security {
                     return Err(VerifierError::InsufficientProvenSecurity(
                         *minimal_security,
@@ -371,10 +1717,11 @@ impl AcceptableOptions {
                 }
             },
             AcceptableOptions::OptionSet(options) => {
-                if !options.iter().any(|opt| opt == proof.options()) {
+                if !options.iter().any(|opt| opt == context.options()) {
                     return Err(VerifierError::UnacceptableProofOptions);
                 }
             },
+            AcceptableOptions::Policy(policy) => policy.validate_context::<H>(context)?,
         }
         Ok(())
     }
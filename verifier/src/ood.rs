@@ -0,0 +1,153 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use air::{
+    proof::Proof, Air, AuxRandElements, ConstraintCompositionCoefficients, EvaluationFrame,
+    LagrangeKernelEvaluationFrame,
+};
+use crypto::{ElementHasher, RandomCoin};
+use math::FieldElement;
+
+use super::{build_air_and_coin_seed, read_ood_evaluations, VerifierChannel, VerifierError};
+
+// CONSTRAINT EVALUATIONS
+// ================================================================================================
+
+/// Evaluations of the constraints of a computation at an out-of-domain point, split by the kind
+/// of constraint.
+///
+/// The evaluations are computed by the verifier from the out-of-domain trace frames sent by the
+/// prover. The evaluations of individual transition constraints are computed before the
+/// constraints are combined and divided by their divisors; the remaining values are the
+/// contributions of each kind of constraints to the constraint composition polynomial at the
+/// out-of-domain point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintEvaluations<E: FieldElement> {
+    /// Evaluations of the main transition constraints (with selectors applied).
+    pub main_transition: Vec<E>,
+    /// Evaluations of the auxiliary transition constraints (with selectors applied); this is
+    /// empty if the trace consists of the main segment only.
+    pub aux_transition: Vec<E>,
+    /// Random linear combination of the transition constraints divided by their divisors.
+    pub transition: E,
+    /// Random linear combination of the boundary constraints divided by their divisors.
+    pub boundary: E,
+    /// Random linear combination of the Lagrange kernel constraints divided by their divisors;
+    /// this is ZERO if the trace does not contain a Lagrange kernel column.
+    pub lagrange_kernel: E,
+}
+
+impl<E: FieldElement> ConstraintEvaluations<E> {
+    /// Returns the evaluation of the constraint composition polynomial implied by these
+    /// constraint evaluations.
+    pub fn combined(&self) -> E {
+        self.transition + self.boundary + self.lagrange_kernel
+    }
+}
+
+// OOD CONSISTENCY REPORT
+// ================================================================================================
+
+/// Result of recomputing the out-of-domain constraint evaluation of a proof.
+///
+/// A report is returned by [check_ood_consistency()]. The proof passes the out-of-domain
+/// consistency check of the verifier if and only if [is_consistent()](Self::is_consistent)
+/// returns true.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OodConsistencyReport<E: FieldElement> {
+    /// The out-of-domain point z drawn from the public coin.
+    pub ood_point: E,
+    /// Evaluations of the constraints at z computed from the out-of-domain trace frames sent by
+    /// the prover.
+    pub constraint_evaluations: ConstraintEvaluations<E>,
+    /// Evaluation of the constraint composition polynomial at z computed from the evaluations of
+    /// the constraint composition polynomial columns sent by the prover.
+    pub composition_evaluation: E,
+}
+
+impl<E: FieldElement> OodConsistencyReport<E> {
+    /// Returns the evaluation of the constraint composition polynomial at z implied by the
+    /// out-of-domain trace frames.
+    pub fn trace_evaluation(&self) -> E {
+        self.constraint_evaluations.combined()
+    }
+
+    /// Returns true if the evaluation of the constraint composition polynomial implied by the
+    /// out-of-domain trace frames is equal to the one claimed by the prover.
+    pub fn is_consistent(&self) -> bool {
+        self.trace_evaluation() == self.composition_evaluation
+    }
+}
+
+/// Recomputes the out-of-domain constraint evaluation of the specified proof, and compares it to
+/// the evaluation of the constraint composition polynomial claimed by the prover.
+///
+/// This replays the verifier transcript up to the out-of-domain consistency check, evaluates the
+/// constraints of `AIR` over the out-of-domain trace frames contained in the proof, and returns
+/// the results broken down by the kind of constraint without performing any of the remaining
+/// checks. It is meant to help AIR developers localize the cause of a
+/// [VerifierError::InconsistentOodConstraintEvaluations] error: the evaluations of individual
+/// constraints at z can be compared against values computed independently (e.g., by evaluating
+/// the constraints over the trace polynomials of the execution trace at the same point), which
+/// pinpoints constraints evaluated differently by the prover and the verifier.
+///
+/// The field `E` must be specified explicitly; it must be the base field of the `AIR` if the
+/// proof was generated without a field extension, or the extension of the required degree
+/// otherwise.
+///
+/// # Errors
+/// Returns an error if the AIR could not be instantiated from the proof, if the degree of `E`
+/// does not match the field extension specified by the proof options, or if the proof could not
+/// be parsed up to the out-of-domain evaluations.
+pub fn check_ood_consistency<AIR, E, HashFn, RandCoin>(
+    proof: &Proof,
+    pub_inputs: AIR::PublicInputs,
+) -> Result<OodConsistencyReport<E>, VerifierError>
+where
+    AIR: Air,
+    E: FieldElement<BaseField = AIR::BaseField>,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let (air, public_coin_seed) =
+        build_air_and_coin_seed::<AIR, HashFn>(&proof.context, pub_inputs, &None)?;
+
+    let extension_degree = air.options().field_extension().degree();
+    if E::EXTENSION_DEGREE != extension_degree as usize {
+        return Err(VerifierError::MismatchedFieldExtension(
+            E::EXTENSION_DEGREE as u32,
+            extension_degree,
+        ));
+    }
+
+    let mut public_coin = RandCoin::new(&public_coin_seed);
+    let mut channel = VerifierChannel::new(&air, proof.as_borrowed(), None)?;
+    let ood =
+        read_ood_evaluations::<AIR, E, HashFn, RandCoin>(&air, &mut channel, &mut public_coin)?;
+
+    Ok(OodConsistencyReport {
+        ood_point: ood.z,
+        constraint_evaluations: ood.constraint_evaluations,
+        composition_evaluation: ood.composition_evaluation,
+    })
+}
+
+// OOD EVALUATIONS
+// ================================================================================================
+
+/// Values read and computed by the verifier up to the out-of-domain consistency check.
+pub(crate) struct OodEvaluations<E: FieldElement> {
+    pub aux_rand_elements: Option<AuxRandElements<E>>,
+    pub constraint_coeffs: ConstraintCompositionCoefficients<E>,
+    pub z: E,
+    pub main_trace_frame: EvaluationFrame<E>,
+    pub aux_trace_frame: Option<EvaluationFrame<E>>,
+    pub lagrange_kernel_frame: Option<LagrangeKernelEvaluationFrame<E>>,
+    pub constraint_evaluations: ConstraintEvaluations<E>,
+    pub composition_column_evaluations: Vec<E>,
+    pub composition_evaluation: E,
+}
@@ -0,0 +1,155 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use air::{
+    proof::{Context, Proof},
+    FieldExtension,
+};
+use crypto::{HashFunctionId, Hasher};
+
+use crate::VerifierError;
+
+// VERIFICATION POLICY
+// ================================================================================================
+
+/// A set of requirements which the parameters of a proof must satisfy for the proof to be
+/// accepted by the verifier.
+///
+/// A policy always specifies the minimum conjectured security level of acceptable proofs, and can
+/// additionally restrict:
+/// * The minimum proven security level.
+/// * The hash functions which may be used to generate proofs. This applies to the hash function
+///   used by the verifier for commitments, as well as to the hash functions recorded in the proof
+///   (see `ProofOptions::with_hash_functions()`).
+/// * The field extensions which may be used to generate proofs.
+/// * The maximum length of the execution trace, which bounds the amount of work the verifier can
+///   be asked to perform.
+///
+/// Policies are applied by passing them to the verifier via
+/// [AcceptableOptions::Policy](crate::AcceptableOptions::Policy).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationPolicy {
+    min_conjectured_security: u32,
+    min_proven_security: Option<u32>,
+    hash_functions: Option<Vec<HashFunctionId>>,
+    field_extensions: Option<Vec<FieldExtension>>,
+    max_trace_length: Option<usize>,
+}
+
+impl VerificationPolicy {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new policy which accepts proofs providing at least `min_conjectured_security`
+    /// bits of conjectured security.
+    pub fn new(min_conjectured_security: u32) -> Self {
+        Self {
+            min_conjectured_security,
+            min_proven_security: None,
+            hash_functions: None,
+            field_extensions: None,
+            max_trace_length: None,
+        }
+    }
+
+    /// Updates this policy to accept only proofs providing at least `min_proven_security` bits of
+    /// proven security.
+    pub fn with_min_proven_security(mut self, min_proven_security: u32) -> Self {
+        self.min_proven_security = Some(min_proven_security);
+        self
+    }
+
+    /// Updates this policy to accept only proofs generated using the specified hash functions.
+    ///
+    /// Hash functions are identified by their [HashFunctionId]. All hash functions defined
+    /// outside of the crypto crate share [HashFunctionId::Custom] identifier; thus, accepting this
+    /// identifier accepts proofs generated using any such hash function, and a policy cannot
+    /// distinguish between two custom hash functions.
+    pub fn with_hash_functions(mut self, hash_functions: &[HashFunctionId]) -> Self {
+        self.hash_functions = Some(hash_functions.to_vec());
+        self
+    }
+
+    /// Updates this policy to accept only proofs generated using the specified field extensions.
+    pub fn with_field_extensions(mut self, field_extensions: &[FieldExtension]) -> Self {
+        self.field_extensions = Some(field_extensions.to_vec());
+        self
+    }
+
+    /// Updates this policy to accept only proofs for execution traces with at most
+    /// `max_trace_length` steps.
+    pub fn with_max_trace_length(mut self, max_trace_length: usize) -> Self {
+        self.max_trace_length = Some(max_trace_length);
+        self
+    }
+
+    // VALIDATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that the parameters of the specified proof satisfy this policy, assuming that the
+    /// verifier uses hash function `H` for commitments.
+    ///
+    /// # Errors
+    /// Returns an error if any of the requirements of this policy are not satisfied.
+    pub fn validate<H: Hasher>(&self, proof: &Proof) -> Result<(), VerifierError> {
+        self.validate_context::<H>(&proof.context)
+    }
+
+    /// Checks that the parameters of proofs generated in the specified context satisfy this
+    /// policy, assuming that the verifier uses hash function `H` for commitments.
+    pub(crate) fn validate_context<H: Hasher>(
+        &self,
+        context: &Context,
+    ) -> Result<(), VerifierError> {
+        let options = context.options();
+
+        if let Some(max_trace_length) = self.max_trace_length {
+            let trace_length = context.trace_info().length();
+            if trace_length > max_trace_length {
+                return Err(VerifierError::TraceLengthTooLarge(max_trace_length, trace_length));
+            }
+        }
+
+        if let Some(ref hash_functions) = self.hash_functions {
+            let mut used_hash_functions = vec![H::ID];
+            if let Some((commitment_hash_fn, random_coin_hash_fn)) = options.hash_functions() {
+                used_hash_functions.extend_from_slice(&[commitment_hash_fn, random_coin_hash_fn]);
+            }
+            if used_hash_functions.iter().any(|hash_fn| !hash_functions.contains(hash_fn)) {
+                return Err(VerifierError::UnacceptableHashFunction);
+            }
+        }
+
+        if let Some(ref field_extensions) = self.field_extensions {
+            if !field_extensions.contains(&options.field_extension()) {
+                return Err(VerifierError::UnacceptableFieldExtension(
+                    options.field_extension().degree(),
+                ));
+            }
+        }
+
+        let conjectured_security = context.security_level::<H>(true);
+        if conjectured_security < self.min_conjectured_security {
+            return Err(VerifierError::InsufficientConjecturedSecurity(
+                self.min_conjectured_security,
+                conjectured_security,
+            ));
+        }
+
+        if let Some(min_proven_security) = self.min_proven_security {
+            let proven_security = context.security_level::<H>(false);
+            if proven_security < min_proven_security {
+                return Err(VerifierError::InsufficientProvenSecurity(
+                    min_proven_security,
+                    proven_security,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::vec::Vec;
+
+use air::{
+    AuxRandElements, ConstraintCompositionCoefficients, DeepCompositionCoefficients,
+    EvaluationFrame, LagrangeKernelEvaluationFrame,
+};
+use math::FieldElement;
+
+// VERIFIER TRACE
+// ================================================================================================
+
+/// Intermediate values computed by the verifier while verifying a proof.
+///
+/// The values are listed in the order in which they are computed by the verifier. Together, they
+/// make it possible to recompute and cross-check each step of the verification procedure
+/// independently of this crate; e.g., against an implementation of the verifier in a circuit
+/// used for recursive proof verification.
+///
+/// A verifier trace is returned by [verify_with_trace()](crate::verify_with_trace).
+#[derive(Debug, Clone)]
+pub struct VerifierTrace<E: FieldElement> {
+    /// Random elements drawn from the public coin for building the auxiliary trace segment, or
+    /// None if the trace consists of the main segment only.
+    pub aux_rand_elements: Option<AuxRandElements<E>>,
+    /// Random coefficients drawn from the public coin for combining constraints into the
+    /// constraint composition polynomial.
+    pub constraint_coefficients: ConstraintCompositionCoefficients<E>,
+    /// The out-of-domain point z drawn from the public coin.
+    pub ood_point: E,
+    /// Evaluations of the main trace polynomials at z and z * g sent by the prover.
+    pub ood_main_trace_frame: EvaluationFrame<E>,
+    /// Evaluations of the auxiliary trace polynomials at z and z * g sent by the prover, if any.
+    pub ood_aux_trace_frame: Option<EvaluationFrame<E>>,
+    /// Evaluations of the Lagrange kernel column polynomial sent by the prover, if any.
+    pub ood_lagrange_kernel_frame: Option<LagrangeKernelEvaluationFrame<E>>,
+    /// Evaluation of the constraint composition polynomial at z computed by the verifier by
+    /// evaluating constraints over the out-of-domain trace frames.
+    pub ood_constraint_evaluation: E,
+    /// Evaluations of the constraint composition polynomial columns at z sent by the prover.
+    pub ood_constraint_evaluations: Vec<E>,
+    /// Random coefficients drawn from the public coin for computing the DEEP composition
+    /// polynomial.
+    pub deep_coefficients: DeepCompositionCoefficients<E>,
+    /// Random values drawn from the public coin for folding each FRI layer; the last value
+    /// corresponds to the commitment to the FRI remainder polynomial.
+    pub fri_layer_alphas: Vec<E>,
+    /// Proof-of-work nonce sent by the prover.
+    pub pow_nonce: u64,
+    /// Sorted and deduplicated query positions drawn from the public coin.
+    pub query_positions: Vec<usize>,
+    /// Evaluations of the DEEP composition polynomial at the query positions computed by the
+    /// verifier from the trace and constraint queries.
+    pub deep_evaluations: Vec<E>,
+    /// Evaluations of the folded polynomial at the folded query positions for each FRI layer.
+    pub fri_folded_evaluations: Vec<Vec<E>>,
+}
@@ -0,0 +1,385 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use alloc::{string::ToString, vec::Vec};
+
+use air::{proof::ProofRef, Air};
+use crypto::{BatchMerkleProof, Digest, ElementHasher};
+use fri::{positions::fold_positions_for_layers, utils::map_positions_to_indexes};
+use math::FieldElement;
+use utils::Serializable;
+
+use crate::{VerifierError, VerifierTrace};
+
+// WITNESS ELEMENT
+// ================================================================================================
+
+/// An element of the scalar field of the BN254 curve.
+///
+/// The element is stored as a canonical 32-byte little-endian encoding of an integer smaller than
+/// the BN254 scalar field modulus. All values of a proof are mapped into such integers without
+/// any modular reduction:
+/// * An element of the base field of the proof (all base fields supported by this crate are at
+///   most 128 bits wide) is mapped into its canonical integer representation.
+/// * An element of an extension field is mapped into a sequence of its base field components.
+/// * A digest is split into two 128-bit limbs, the low limb first.
+/// * An integer (e.g., a query position or the proof-of-work nonce) is mapped into itself.
+///
+/// Thus, the encoding can be consumed directly by any constraint system defined over BN254, and
+/// each element can be range-checked to recover the original value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WitnessElement([u8; 32]);
+
+impl WitnessElement {
+    /// The element with value zero.
+    pub const ZERO: Self = Self([0; 32]);
+
+    /// Returns the little-endian encoding of this element.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Builds an element from at most 16 little-endian bytes.
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        debug_assert!(bytes.len() <= 16, "value is too wide for a witness element");
+        let mut result = [0; 32];
+        result[..bytes.len()].copy_from_slice(bytes);
+        Self(result)
+    }
+
+    /// Maps the specified digest into two witness elements, the low limb first.
+    fn from_digest<D: Digest>(digest: &D) -> [Self; 2] {
+        let bytes = digest.as_bytes();
+        [Self::from_le_bytes(&bytes[..16]), Self::from_le_bytes(&bytes[16..])]
+    }
+}
+
+impl From<u64> for WitnessElement {
+    fn from(value: u64) -> Self {
+        Self::from_le_bytes(&value.to_le_bytes())
+    }
+}
+
+impl From<usize> for WitnessElement {
+    fn from(value: usize) -> Self {
+        Self::from(value as u64)
+    }
+}
+
+// MERKLE OPENINGS
+// ================================================================================================
+
+/// Openings of a vector commitment at a set of leaf indexes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MerkleOpenings {
+    /// Indexes of the opened leaves in the Merkle tree.
+    pub indexes: Vec<WitnessElement>,
+    /// Values hashed into each opened leaf, in the order in which they are passed to
+    /// [ElementHasher::hash_elements()] by the verifier.
+    pub leaves: Vec<Vec<WitnessElement>>,
+    /// Sibling nodes along the authentication path from each opened leaf to the root of the
+    /// tree, starting with the sibling of the leaf; every node takes two elements.
+    pub paths: Vec<Vec<WitnessElement>>,
+}
+
+impl MerkleOpenings {
+    /// Builds openings at the specified `indexes` from the values at these indexes and the batch
+    /// Merkle proof against them.
+    fn new<'a, E, H>(
+        indexes: &[usize],
+        leaves: impl Iterator<Item = &'a [E]>,
+        proof: BatchMerkleProof<H>,
+    ) -> Result<Self, VerifierError>
+    where
+        E: FieldElement + 'a,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        let paths = proof
+            .into_paths(indexes)
+            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+
+        Ok(Self {
+            indexes: indexes.iter().map(|&index| WitnessElement::from(index)).collect(),
+            leaves: leaves.map(map_elements).collect(),
+            paths: paths
+                .iter()
+                .map(|path| path[1..].iter().flat_map(WitnessElement::from_digest).collect())
+                .collect(),
+        })
+    }
+
+    /// Appends the indexes, followed by each opened leaf and its authentication path, to the
+    /// specified vector.
+    fn write_into(&self, target: &mut Vec<WitnessElement>) {
+        target.extend_from_slice(&self.indexes);
+        for (leaf, path) in self.leaves.iter().zip(self.paths.iter()) {
+            target.extend_from_slice(leaf);
+            target.extend_from_slice(path);
+        }
+    }
+}
+
+// SNARK WITNESS
+// ================================================================================================
+
+/// A witness for verifying a STARK proof inside a constraint system defined over BN254.
+///
+/// The witness contains the transcript of verifying a proof (the values sent by the prover and
+/// the challenges drawn from the public coin), and all openings of the commitments checked by
+/// the verifier. Each section is encoded as a sequence of [WitnessElement]s, as described for
+/// every field below. The sections are listed in the order in which the corresponding values
+/// are consumed by the verifier; [SnarkWitness::to_elements()] concatenates them in this order.
+///
+/// Values which the verifier computes rather than reads from the proof (i.e., the evaluations
+/// of the DEEP composition polynomial and of the folded FRI layers) are included as hints; a
+/// wrapping circuit must constrain them to be computed correctly.
+///
+/// The GKR proof (if the computation uses a Lagrange kernel column) is not re-expressed in the
+/// witness; only the random elements derived from it are.
+///
+/// A witness is returned by [verify_with_snark_witness()](crate::verify_with_snark_witness).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnarkWitness {
+    /// Public inputs of the computation, as returned by
+    /// [ToElements::to_elements()](math::ToElements::to_elements).
+    pub public_inputs: Vec<WitnessElement>,
+    /// Commitments to the tables of the main trace segment, followed by the commitment to the
+    /// auxiliary trace segment (if any).
+    pub trace_commitments: Vec<WitnessElement>,
+    /// Random elements for building the auxiliary trace segment, followed by the random elements
+    /// for the Lagrange kernel (if any).
+    pub aux_rand_elements: Vec<WitnessElement>,
+    /// Constraint composition coefficients: transition coefficients, boundary coefficients, and
+    /// then coefficients of the Lagrange kernel constraints (if any).
+    pub constraint_coefficients: Vec<WitnessElement>,
+    /// Commitment to the evaluations of the constraint composition polynomial.
+    pub constraint_commitment: Vec<WitnessElement>,
+    /// The out-of-domain point z.
+    pub ood_point: Vec<WitnessElement>,
+    /// Out-of-domain evaluations of the main trace at z and z * g, followed by those of the
+    /// auxiliary trace (if any), and then by the Lagrange kernel frame (if any).
+    pub ood_trace_frame: Vec<WitnessElement>,
+    /// Out-of-domain evaluations of the constraint composition polynomial columns.
+    pub ood_constraint_evaluations: Vec<WitnessElement>,
+    /// DEEP composition coefficients: trace coefficients, constraint coefficients, and then the
+    /// Lagrange kernel coefficient (if any).
+    pub deep_coefficients: Vec<WitnessElement>,
+    /// Commitments to the FRI layers; the last one is the commitment to the FRI remainder.
+    pub fri_commitments: Vec<WitnessElement>,
+    /// Random values for folding each FRI layer.
+    pub fri_layer_alphas: Vec<WitnessElement>,
+    /// Proof-of-work nonce.
+    pub pow_nonce: WitnessElement,
+    /// Sorted and deduplicated query positions in the LDE domain.
+    pub query_positions: Vec<WitnessElement>,
+    /// Openings of each trace commitment at the query positions; the leaves are the queried rows.
+    pub trace_openings: Vec<MerkleOpenings>,
+    /// Openings of the constraint commitment at the query positions; the leaves are the queried
+    /// evaluations of the constraint composition polynomial columns.
+    pub constraint_openings: MerkleOpenings,
+    /// Evaluations of the DEEP composition polynomial at the query positions.
+    pub deep_evaluations: Vec<WitnessElement>,
+    /// Openings of each FRI layer commitment; the leaves are the groups of evaluations folded
+    /// into a single evaluation of the next layer.
+    pub fri_layer_openings: Vec<MerkleOpenings>,
+    /// Evaluations of the folded polynomial at the folded query positions for each FRI layer.
+    pub fri_folded_evaluations: Vec<WitnessElement>,
+    /// Coefficients of the FRI remainder polynomial.
+    pub fri_remainder: Vec<WitnessElement>,
+}
+
+impl SnarkWitness {
+    /// Returns all elements of this witness as a single vector.
+    ///
+    /// The sections are concatenated in the order in which they are listed in [SnarkWitness];
+    /// the openings of each commitment are written as the leaf indexes followed by every opened
+    /// leaf and its authentication path.
+    pub fn to_elements(&self) -> Vec<WitnessElement> {
+        let mut result = Vec::new();
+        result.extend_from_slice(&self.public_inputs);
+        result.extend_from_slice(&self.trace_commitments);
+        result.extend_from_slice(&self.aux_rand_elements);
+        result.extend_from_slice(&self.constraint_coefficients);
+        result.extend_from_slice(&self.constraint_commitment);
+        result.extend_from_slice(&self.ood_point);
+        result.extend_from_slice(&self.ood_trace_frame);
+        result.extend_from_slice(&self.ood_constraint_evaluations);
+        result.extend_from_slice(&self.deep_coefficients);
+        result.extend_from_slice(&self.fri_commitments);
+        result.extend_from_slice(&self.fri_layer_alphas);
+        result.push(self.pow_nonce);
+        result.extend_from_slice(&self.query_positions);
+        for openings in self.trace_openings.iter() {
+            openings.write_into(&mut result);
+        }
+        self.constraint_openings.write_into(&mut result);
+        result.extend_from_slice(&self.deep_evaluations);
+        for openings in self.fri_layer_openings.iter() {
+            openings.write_into(&mut result);
+        }
+        result.extend_from_slice(&self.fri_folded_evaluations);
+        result.extend_from_slice(&self.fri_remainder);
+        result
+    }
+}
+
+// WITNESS BUILDER
+// ================================================================================================
+
+/// Builds a witness for the specified proof from the intermediate values computed by the
+/// verifier while verifying the proof.
+///
+/// The proof must have been verified successfully against the specified `air`; the values which
+/// the verifier does not retain (commitments and openings) are parsed from the proof again.
+pub(crate) fn build_snark_witness<A, E, H>(
+    air: &A,
+    proof: ProofRef<'_>,
+    public_inputs: &[A::BaseField],
+    trace: VerifierTrace<E>,
+) -> Result<SnarkWitness, VerifierError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+{
+    let lde_domain_size = air.lde_domain_size();
+    let fri_options = air.options().to_fri_options();
+    let positions = &trace.query_positions;
+    let num_queries = positions.len();
+
+    // --- commitments ----------------------------------------------------------------------------
+    let main_trace_table_widths = air.context().main_trace_table_widths();
+    let num_trace_commitments = air.trace_info().num_segments() + main_trace_table_widths.len() - 1;
+    let (trace_roots, constraint_root, fri_roots) = proof
+        .commitments
+        .parse::<H>(num_trace_commitments, fri_options.num_fri_layers(lde_domain_size))
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+
+    // --- trace and constraint openings ----------------------------------------------------------
+    let mut trace_openings = Vec::with_capacity(num_trace_commitments);
+    for (queries, &table_width) in proof.trace_queries.iter().zip(main_trace_table_widths) {
+        let (merkle_proof, states) = queries
+            .parse::<H, A::BaseField>(lde_domain_size, num_queries, table_width)
+            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+        trace_openings.push(MerkleOpenings::new(positions, states.rows(), merkle_proof)?);
+    }
+    if air.trace_info().is_multi_segment() {
+        let (merkle_proof, states) = proof.trace_queries[main_trace_table_widths.len()]
+            .parse::<H, E>(lde_domain_size, num_queries, air.trace_info().get_aux_segment_width())
+            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+        trace_openings.push(MerkleOpenings::new(positions, states.rows(), merkle_proof)?);
+    }
+
+    let (merkle_proof, evaluations) = proof
+        .constraint_queries
+        .parse::<H, E>(
+            lde_domain_size,
+            num_queries,
+            air.context().num_constraint_composition_columns(),
+        )
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    let constraint_openings = MerkleOpenings::new(positions, evaluations.rows(), merkle_proof)?;
+
+    // --- FRI layer openings ---------------------------------------------------------------------
+    let folding_factors = fri_options.folding_factors(lde_domain_size);
+    let (layer_queries, layer_proofs) = proof
+        .fri_proof
+        .parse_layers_with_folding_factors::<H, E>(lde_domain_size, &folding_factors)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    let layer_positions = fold_positions_for_layers(positions, lde_domain_size, &folding_factors);
+    let num_partitions = proof.fri_proof.num_partitions();
+
+    let mut fri_layer_openings = Vec::with_capacity(layer_queries.len());
+    let mut domain_size = lde_domain_size;
+    for (((values, merkle_proof), positions), &folding_factor) in layer_queries
+        .iter()
+        .zip(layer_proofs)
+        .zip(layer_positions.iter())
+        .zip(folding_factors.iter())
+    {
+        let indexes =
+            map_positions_to_indexes(positions, domain_size, folding_factor, num_partitions);
+        fri_layer_openings.push(MerkleOpenings::new(
+            &indexes,
+            values.chunks(folding_factor),
+            merkle_proof,
+        )?);
+        domain_size /= folding_factor;
+    }
+
+    let fri_remainder = proof
+        .fri_proof
+        .parse_remainder::<E>()
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+
+    // --- challenges and out-of-domain evaluations -----------------------------------------------
+    let mut aux_rand_elements = Vec::new();
+    if let Some(elements) = trace.aux_rand_elements.as_ref() {
+        aux_rand_elements.extend_from_slice(elements.rand_elements());
+        if let Some(lagrange) = elements.lagrange() {
+            aux_rand_elements.extend_from_slice(lagrange);
+        }
+    }
+
+    let coefficients = &trace.constraint_coefficients;
+    let mut constraint_coefficients = coefficients.transition.clone();
+    constraint_coefficients.extend_from_slice(&coefficients.boundary);
+    if let Some(lagrange) = coefficients.lagrange.as_ref() {
+        constraint_coefficients.extend_from_slice(&lagrange.transition);
+        constraint_coefficients.push(lagrange.boundary);
+    }
+
+    let mut ood_trace_frame = trace.ood_main_trace_frame.current().to_vec();
+    ood_trace_frame.extend_from_slice(trace.ood_main_trace_frame.next());
+    if let Some(frame) = trace.ood_aux_trace_frame.as_ref() {
+        ood_trace_frame.extend_from_slice(frame.current());
+        ood_trace_frame.extend_from_slice(frame.next());
+    }
+    if let Some(frame) = trace.ood_lagrange_kernel_frame.as_ref() {
+        ood_trace_frame.extend_from_slice(frame.inner());
+    }
+
+    let mut deep_coefficients = trace.deep_coefficients.trace.clone();
+    deep_coefficients.extend_from_slice(&trace.deep_coefficients.constraints);
+    deep_coefficients.extend(trace.deep_coefficients.lagrange);
+
+    Ok(SnarkWitness {
+        public_inputs: map_elements(public_inputs),
+        trace_commitments: map_digests(&trace_roots),
+        aux_rand_elements: map_elements(&aux_rand_elements),
+        constraint_coefficients: map_elements(&constraint_coefficients),
+        constraint_commitment: map_digests(&[constraint_root]),
+        ood_point: map_elements(&[trace.ood_point]),
+        ood_trace_frame: map_elements(&ood_trace_frame),
+        ood_constraint_evaluations: map_elements(&trace.ood_constraint_evaluations),
+        deep_coefficients: map_elements(&deep_coefficients),
+        fri_commitments: map_digests(&fri_roots),
+        fri_layer_alphas: map_elements(&trace.fri_layer_alphas),
+        pow_nonce: WitnessElement::from(trace.pow_nonce),
+        query_positions: positions.iter().map(|&position| WitnessElement::from(position)).collect(),
+        trace_openings,
+        constraint_openings,
+        deep_evaluations: map_elements(&trace.deep_evaluations),
+        fri_layer_openings,
+        fri_folded_evaluations: map_elements(&trace.fri_folded_evaluations.concat()),
+        fri_remainder: map_elements(&fri_remainder),
+    })
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Maps the specified field elements into one witness element per base field component.
+fn map_elements<E: FieldElement>(values: &[E]) -> Vec<WitnessElement> {
+    E::slice_as_base_elements(values)
+        .iter()
+        .map(|element| WitnessElement::from_le_bytes(&element.to_bytes()))
+        .collect()
+}
+
+/// Maps the specified digests into two witness elements each.
+fn map_digests<D: Digest>(digests: &[D]) -> Vec<WitnessElement> {
+    digests.iter().flat_map(WitnessElement::from_digest).collect()
+}
@@ -0,0 +1,124 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use maybe_async::maybe_async;
+use prover::{
+    crypto::{
+        hashers::{Blake3_192, Blake3_256, Sha3_256},
+        DefaultRandomCoin, ElementHasher, HashFunctionId,
+    },
+    math::{ExtensibleField, StarkField},
+    Air, Proof, Prover, ProverError, Trace,
+};
+use verifier::{verify, AcceptableOptions, VerifierError};
+
+// PROVER BUILDER
+// ================================================================================================
+
+/// Builds provers for a computation which can be instantiated with any hash function.
+///
+/// This trait is used by [prove_with_hasher()] to instantiate a prover with a hash function
+/// selected at runtime. The random coin of the built provers is always a [DefaultRandomCoin]
+/// instantiated with the selected hash function, and thus, the generated proofs can be verified
+/// via [verify_with_hasher()].
+pub trait ProverBuilder {
+    /// Base field for the computation described by the built provers.
+    type BaseField: StarkField + ExtensibleField<2> + ExtensibleField<3>;
+
+    /// Execution trace of the computation described by the built provers.
+    type Trace: Trace<BaseField = Self::BaseField> + Send + Sync;
+
+    /// Prover instantiated with hash function `H`.
+    type Prover<H>: Prover<
+        BaseField = Self::BaseField,
+        Trace = Self::Trace,
+        HashFn = H,
+        RandomCoin = DefaultRandomCoin<H>,
+    >
+    where
+        H: ElementHasher<BaseField = Self::BaseField>;
+
+    /// Returns a prover instantiated with hash function `H`.
+    fn build_prover<H>(&self) -> Self::Prover<H>
+    where
+        H: ElementHasher<BaseField = Self::BaseField>;
+}
+
+// RUNTIME HASH FUNCTION DISPATCH
+// ================================================================================================
+
+/// Returns a STARK proof attesting to a correct execution of a computation, generated by a prover
+/// instantiated with the hash function identified by `hash_fn`.
+///
+/// This saves applications which select a hash function at runtime from having to match over all
+/// supported hash functions themselves. The prover is built via the provided `builder`, and the
+/// proof is generated as described in [Prover::prove()].
+///
+/// Only hash functions which are defined over any base field are supported; these are
+/// [Blake3_256], [Blake3_192], and [Sha3_256]. Arithmetization-friendly hash functions are defined
+/// over specific fields, and thus, provers which use them must be instantiated directly.
+///
+/// # Errors
+/// In addition to the errors returned by [Prover::prove()], returns an error if the specified
+/// hash function is not supported.
+#[maybe_async]
+pub fn prove_with_hasher<B: ProverBuilder>(
+    hash_fn: HashFunctionId,
+    builder: &B,
+    trace: B::Trace,
+) -> Result<Proof, ProverError> {
+    match hash_fn {
+        HashFunctionId::Blake3_256 => {
+            builder.build_prover::<Blake3_256<B::BaseField>>().prove(trace).await
+        },
+        HashFunctionId::Blake3_192 => {
+            builder.build_prover::<Blake3_192<B::BaseField>>().prove(trace).await
+        },
+        HashFunctionId::Sha3_256 => {
+            builder.build_prover::<Sha3_256<B::BaseField>>().prove(trace).await
+        },
+        hash_fn => Err(ProverError::UnsupportedHashFunction(hash_fn)),
+    }
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// using the hash function identified by `hash_fn`.
+///
+/// Works just like [verify()] instantiated with the specified hash function and a
+/// [DefaultRandomCoin]; the same hash functions as for [prove_with_hasher()] are supported. To
+/// determine the hash functions from the proof instead, use [verify_auto()](crate::verify_auto).
+///
+/// # Errors
+/// In addition to the errors returned by [verify()], returns an error if the specified hash
+/// function is not supported.
+pub fn verify_with_hasher<AIR: Air>(
+    hash_fn: HashFunctionId,
+    proof: Proof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError> {
+    match hash_fn {
+        HashFunctionId::Blake3_256 => {
+            verify::<AIR, Blake3_256<AIR::BaseField>, DefaultRandomCoin<_>>(
+                proof,
+                pub_inputs,
+                acceptable_options,
+            )
+        },
+        HashFunctionId::Blake3_192 => {
+            verify::<AIR, Blake3_192<AIR::BaseField>, DefaultRandomCoin<_>>(
+                proof,
+                pub_inputs,
+                acceptable_options,
+            )
+        },
+        HashFunctionId::Sha3_256 => verify::<AIR, Sha3_256<AIR::BaseField>, DefaultRandomCoin<_>>(
+            proof,
+            pub_inputs,
+            acceptable_options,
+        ),
+        hash_fn => Err(VerifierError::UnsupportedHashFunction(hash_fn)),
+    }
+}
@@ -39,6 +39,24 @@
 //! machine). The number of threads can be configured via `RAYON_NUM_THREADS` environment
 //! variable.
 //!
+//! When the crate is compiled with the `serde` feature enabled, [Proof], [ProofOptions],
+//! [Context](proof::Context), batch Merkle proofs, and hash digests implement `Serialize`
+//! and `Deserialize` traits from [serde](https://serde.rs). These types are encoded using their
+//! canonical binary representation (as a hex string in human-readable formats).
+//!
+//! When the crate is compiled with the `encoding` feature enabled, [Proof] and hash digests can
+//! be encoded as (and decoded from) hex and Base64 strings via `to_hex()`, `from_hex()`,
+//! `to_base64()`, and `from_base64()` methods. Decoding accepts only the canonical encoding of a
+//! value.
+//!
+//! When the crate is compiled with the `compression` feature enabled, a [Proof] can be serialized
+//! into (and read from) a DEFLATE-compressed form via `to_bytes_compressed()` and
+//! `from_bytes_compressed()` methods.
+//!
+//! When the hash function is selected at runtime, the prover can be instantiated via
+//! [prove_with_hasher()] (see [ProverBuilder]), and the resulting proof can be verified via
+//! [verify_with_hasher()].
+//!
 //! ## Prof verification
 //! To verify a [Proof] generated as described in the previous sections, you'll need to
 //! do the following:
@@ -591,17 +609,37 @@
 #[cfg(test)]
 extern crate std;
 
-pub use air::{AuxRandElements, GkrVerifier};
+pub use air::{AirWithOutputs, AuxRandElements, GkrVerifier};
+
+mod hash_fn;
+pub use hash_fn::{prove_with_hasher, verify_with_hasher, ProverBuilder};
+#[cfg(feature = "self-verify")]
+pub use prover::SelfVerificationFailure;
 pub use prover::{
-    crypto, iterators, math, matrix, Air, AirContext, Assertion, AuxTraceWithMetadata,
-    BoundaryConstraint, BoundaryConstraintGroup, ByteReader, ByteWriter, CompositionPolyTrace,
-    ConstraintCompositionCoefficients, ConstraintDivisor, ConstraintEvaluator,
-    DeepCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde, Deserializable,
-    DeserializationError, EvaluationFrame, FieldExtension, Proof, ProofOptions, Prover,
-    ProverError, ProverGkrProof, Serializable, SliceReader, StarkDomain, Trace, TraceInfo,
-    TraceLde, TracePolyTable, TraceTable, TraceTableFragment, TransitionConstraintDegree,
+    crypto, iterators, math, matrix, proof, Air, AirContext, Assertion, AuxTraceBuilder,
+    AuxTraceWithMetadata, BoundaryConstraint, BoundaryConstraintGroup, BuiltTrace, ByteReader,
+    ByteWriter, CompositionPolyTrace, ConstraintCommitment, ConstraintCompositionCoefficients,
+    ConstraintDivisor, ConstraintEvaluator, ConstraintViolation, DeepCompositionCoefficients,
+    DefaultConstraintEvaluator, DefaultTraceLde, Deserializable, DeserializationError,
+    EvaluationFrame, EvaluationFrameBatch, FieldExtension, MainTraceCommitment, MemoryEstimate,
+    PreprocessedTrace, Proof, ProofExtension, ProofOptions, Prover, ProverError, ProverGkrProof,
+    SelectorGroup, Serializable, SliceReader, StarkDomain, Trace, TraceBuilder, TraceInfo,
+    TraceLde, TracePadding, TracePolyTable, TraceTable, TraceTableFragment, Transcript,
+    TranscriptEntry, TranscriptEntryKind, TransitionConstraintDegree,
+};
+#[cfg(feature = "unsound-testing")]
+pub use verifier::verify_unsound_fast;
+pub use verifier::{
+    check_ood_consistency, evaluate_periodic_columns_at, verify, verify_auto, verify_batch,
+    verify_bytes, verify_compressed, verify_constant_time, verify_detached,
+    verify_main_trace_table_opening, verify_with_outputs, verify_with_preprocessed_trace,
+    verify_with_query_positions, verify_with_security_levels, verify_with_snark_witness,
+    verify_with_trace, verify_with_transcript, AcceptableOptions, ConstraintEvaluations,
+    MerkleOpenings, OodConsistencyReport, SecurityLevels, SnarkWitness, VerificationPolicy,
+    VerifierError, VerifierTrace, WitnessElement,
 };
-pub use verifier::{verify, AcceptableOptions, VerifierError};
+#[cfg(feature = "diagnostics")]
+pub use verifier::{verify_with_diagnostics, DiagnosedError, ErrorContext, MerklePathMismatch};
 
 #[cfg(test)]
 mod tests;
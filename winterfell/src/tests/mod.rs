@@ -3,24 +3,32 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use std::{vec, vec::Vec};
+use std::{marker::PhantomData, vec, vec::Vec};
 
-use air::LagrangeKernelRandElements;
+use air::{
+    proof::{DetachedSection, ProofSection},
+    LagrangeKernelRandElements,
+};
 use prover::{
-    crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin},
+    crypto::{hashers::Blake3_256, DefaultRandomCoin, ElementHasher, HashFunctionId, RandomCoin},
     math::{fields::f64::BaseElement, ExtensionOf, FieldElement},
     matrix::ColMatrix,
 };
 
 use super::*;
 
+mod fib;
+mod round_trip;
+mod selectors;
+mod verification;
+
 const AUX_TRACE_WIDTH: usize = 2;
 
 #[test]
 fn test_complex_lagrange_kernel_air() {
     let trace = LagrangeComplexTrace::new(2_usize.pow(10), AUX_TRACE_WIDTH);
 
-    let prover = LagrangeComplexProver::new(AUX_TRACE_WIDTH);
+    let prover = LagrangeComplexProver::<Blake3_256<BaseElement>>::new(AUX_TRACE_WIDTH);
 
     let proof = prover.prove(trace).unwrap();
 
@@ -32,6 +40,140 @@ fn test_complex_lagrange_kernel_air() {
     .unwrap()
 }
 
+#[test]
+fn test_lagrange_kernel_air_transcripts() {
+    let trace = LagrangeComplexTrace::new(2_usize.pow(10), AUX_TRACE_WIDTH);
+
+    let prover = LagrangeComplexProver::<Blake3_256<BaseElement>>::new(AUX_TRACE_WIDTH);
+
+    let (proof, prover_transcript) = prover.prove_with_transcript(trace).unwrap();
+
+    // the verifier observes the same messages as the prover, including the random elements for
+    // the auxiliary trace segment and the Lagrange kernel
+    let (result, verifier_transcript) =
+        verify_with_transcript::<
+            LagrangeKernelComplexAir,
+            Blake3_256<BaseElement>,
+            DefaultRandomCoin<Blake3_256<BaseElement>>,
+        >(proof, (), &AcceptableOptions::MinConjecturedSecurity(0));
+    assert!(result.is_ok());
+    assert_eq!(prover_transcript.first_divergence(&verifier_transcript), None);
+    assert!(verifier_transcript
+        .entries()
+        .iter()
+        .any(|entry| entry.label() == "lagrange_kernel_rand_elements"));
+}
+
+#[test]
+fn test_prove_and_verify_with_hasher() {
+    let builder = LagrangeComplexProverBuilder { aux_trace_width: AUX_TRACE_WIDTH };
+    let acceptable_options = AcceptableOptions::MinConjecturedSecurity(0);
+
+    for hash_fn in
+        [HashFunctionId::Blake3_256, HashFunctionId::Blake3_192, HashFunctionId::Sha3_256]
+    {
+        let trace = LagrangeComplexTrace::new(2_usize.pow(10), AUX_TRACE_WIDTH);
+        let proof = prove_with_hasher(hash_fn, &builder, trace).unwrap();
+        verify_with_hasher::<LagrangeKernelComplexAir>(
+            hash_fn,
+            proof.clone(),
+            (),
+            &acceptable_options,
+        )
+        .unwrap();
+
+        // a proof cannot be verified using a different hash function
+        let other_hash_fn = if hash_fn == HashFunctionId::Sha3_256 {
+            HashFunctionId::Blake3_256
+        } else {
+            HashFunctionId::Sha3_256
+        };
+        assert!(verify_with_hasher::<LagrangeKernelComplexAir>(
+            other_hash_fn,
+            proof,
+            (),
+            &acceptable_options
+        )
+        .is_err());
+    }
+
+    // hash functions defined over specific fields are not supported
+    let trace = LagrangeComplexTrace::new(2_usize.pow(10), AUX_TRACE_WIDTH);
+    assert_eq!(
+        Err(ProverError::UnsupportedHashFunction(HashFunctionId::Rp64_256)),
+        prove_with_hasher(HashFunctionId::Rp64_256, &builder, trace)
+    );
+}
+
+#[test]
+fn test_verify_detached() {
+    let trace = LagrangeComplexTrace::new(2_usize.pow(10), AUX_TRACE_WIDTH);
+    let prover = LagrangeComplexProver::<Blake3_256<BaseElement>>::new(AUX_TRACE_WIDTH);
+    let proof = prover.prove(trace).unwrap();
+
+    let sections = [ProofSection::TraceQueries, ProofSection::FriProof];
+    let (detached, parts) = proof.detach::<Blake3_256<BaseElement>>(&sections);
+    let fetch = |section: &DetachedSection| {
+        parts.iter().find(|(s, _)| s == section).map(|(_, bytes)| bytes.clone())
+    };
+
+    verify_detached::<
+        LagrangeKernelComplexAir,
+        Blake3_256<BaseElement>,
+        DefaultRandomCoin<Blake3_256<BaseElement>>,
+        _,
+    >(detached.clone(), (), &AcceptableOptions::MinConjecturedSecurity(0), fetch)
+    .unwrap();
+
+    // the proof cannot be verified without the detached sections
+    let result = verify_detached::<
+        LagrangeKernelComplexAir,
+        Blake3_256<BaseElement>,
+        DefaultRandomCoin<Blake3_256<BaseElement>>,
+        _,
+    >(detached, (), &AcceptableOptions::MinConjecturedSecurity(0), |_| None);
+    assert!(matches!(result, Err(VerifierError::ProofDeserializationError(_))));
+}
+
+#[test]
+fn test_verify_with_snark_witness() {
+    let trace = LagrangeComplexTrace::new(2_usize.pow(10), AUX_TRACE_WIDTH);
+    let prover = LagrangeComplexProver::<Blake3_256<BaseElement>>::new(AUX_TRACE_WIDTH);
+    let proof = prover.prove(trace).unwrap();
+    let lde_domain_size = proof.lde_domain_size();
+
+    let witness = verify_with_snark_witness::<
+        LagrangeKernelComplexAir,
+        BaseElement,
+        Blake3_256<BaseElement>,
+        DefaultRandomCoin<Blake3_256<BaseElement>>,
+    >(proof, (), &AcceptableOptions::MinConjecturedSecurity(0))
+    .unwrap();
+
+    // one commitment for the main trace segment and one for the auxiliary segment; each digest
+    // takes two witness elements
+    assert_eq!(4, witness.trace_commitments.len());
+    assert_eq!(2, witness.constraint_commitment.len());
+    assert_eq!(1, witness.ood_point.len());
+
+    // every trace opening contains a row of the corresponding segment and a full path
+    let num_queries = witness.query_positions.len();
+    let path_len = 2 * lde_domain_size.ilog2() as usize;
+    assert_eq!(2, witness.trace_openings.len());
+    for (openings, width) in witness.trace_openings.iter().zip([1, AUX_TRACE_WIDTH]) {
+        assert_eq!(witness.query_positions, openings.indexes);
+        assert!(openings.leaves.iter().all(|leaf| leaf.len() == width));
+        assert!(openings.paths.iter().all(|path| path.len() == path_len));
+    }
+    assert_eq!(num_queries, witness.constraint_openings.leaves.len());
+    assert_eq!(witness.fri_layer_alphas.len(), witness.fri_commitments.len() / 2);
+
+    // elements of the 64-bit base field are encoded in the low 8 bytes of witness elements
+    let elements = witness.to_elements();
+    assert!(elements.len() > witness.public_inputs.len() + path_len * num_queries);
+    assert!(witness.ood_trace_frame.iter().all(|e| e.as_bytes()[8..] == [0; 24]));
+}
+
 // LagrangeComplexTrace
 // =================================================================================================
 
@@ -194,25 +336,27 @@ impl Air for LagrangeKernelComplexAir {
 // LagrangeComplexProver
 // ================================================================================================
 
-struct LagrangeComplexProver {
+struct LagrangeComplexProver<H> {
     aux_trace_width: usize,
     options: ProofOptions,
+    _hasher: PhantomData<H>,
 }
 
-impl LagrangeComplexProver {
+impl<H> LagrangeComplexProver<H> {
     fn new(aux_trace_width: usize) -> Self {
         Self {
             aux_trace_width,
             options: ProofOptions::new(1, 2, 0, FieldExtension::None, 2, 1),
+            _hasher: PhantomData,
         }
     }
 }
 
-impl Prover for LagrangeComplexProver {
+impl<H: ElementHasher<BaseField = BaseElement>> Prover for LagrangeComplexProver<H> {
     type BaseField = BaseElement;
     type Air = LagrangeKernelComplexAir;
     type Trace = LagrangeComplexTrace;
-    type HashFn = Blake3_256<BaseElement>;
+    type HashFn = H;
     type RandomCoin = DefaultRandomCoin<Self::HashFn>;
     type TraceLde<E: FieldElement<BaseField = BaseElement>> = DefaultTraceLde<E, Self::HashFn>;
     type ConstraintEvaluator<'a, E: FieldElement<BaseField = BaseElement>> =
@@ -323,3 +467,23 @@ impl Prover for LagrangeComplexProver {
         ColMatrix::new(columns)
     }
 }
+
+struct LagrangeComplexProverBuilder {
+    aux_trace_width: usize,
+}
+
+impl ProverBuilder for LagrangeComplexProverBuilder {
+    type BaseField = BaseElement;
+    type Trace = LagrangeComplexTrace;
+    type Prover<H>
+        = LagrangeComplexProver<H>
+    where
+        H: ElementHasher<BaseField = BaseElement>;
+
+    fn build_prover<H>(&self) -> Self::Prover<H>
+    where
+        H: ElementHasher<BaseField = BaseElement>,
+    {
+        LagrangeComplexProver::new(self.aux_trace_width)
+    }
+}
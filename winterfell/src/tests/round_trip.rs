@@ -0,0 +1,435 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Randomized round-trip tests: random computations are proven and verified, and proofs of
+//! mutated traces, mutated proofs, and proofs verified against wrong public inputs must be
+//! rejected.
+
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    vec::Vec,
+};
+
+use proptest::{collection::vec, prelude::*, sample::Index};
+use prover::{
+    crypto::{hashers::Blake3_256, DefaultRandomCoin},
+    math::{fields::f64::BaseElement, FieldElement, ToElements},
+    matrix::ColMatrix,
+};
+
+use crate::{
+    verify, AcceptableOptions, Air, AirContext, Assertion, AuxRandElements,
+    ConstraintCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde,
+    EvaluationFrame, FieldExtension, Proof, ProofOptions, Prover, ProverError, StarkDomain, Trace,
+    TraceInfo, TracePolyTable, TraceTable, TransitionConstraintDegree, VerifierError,
+};
+
+type Hasher = Blake3_256<BaseElement>;
+
+/// Maximum degree of a term in the transition function of a random computation.
+const MAX_EXPONENT: u32 = 3;
+
+// TESTS
+// ================================================================================================
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn valid_proofs_are_accepted(
+        computation in computation_strategy(),
+        options in options_strategy(),
+    ) {
+        let trace = computation.build_trace();
+        let pub_inputs = computation.get_pub_inputs(&trace);
+        let proof = prove(trace, pub_inputs.clone(), options).unwrap();
+        prop_assert!(verify_proof(proof.clone(), pub_inputs.clone()).is_ok());
+
+        // a deserialized proof must be accepted as well
+        let proof = Proof::from_bytes(&proof.to_bytes()).unwrap();
+        prop_assert!(verify_proof(proof, pub_inputs).is_ok());
+    }
+
+    #[test]
+    fn proofs_against_wrong_inputs_are_rejected(
+        computation in computation_strategy(),
+        options in options_strategy(),
+        assertion_idx in any::<Index>(),
+        value_idx in any::<Index>(),
+    ) {
+        let trace = computation.build_trace();
+        let pub_inputs = computation.get_pub_inputs(&trace);
+        let proof = prove(trace, pub_inputs.clone(), options).unwrap();
+
+        let mut wrong_inputs = pub_inputs;
+        let assertion = assertion_idx.get_mut(&mut wrong_inputs.assertions);
+        let mut values = assertion.values().to_vec();
+        *value_idx.get_mut(&mut values) += BaseElement::ONE;
+        *assertion = if assertion.is_single() {
+            Assertion::single(assertion.column(), assertion.first_step(), values[0])
+        } else {
+            Assertion::sequence(assertion.column(), assertion.first_step(), assertion.stride(), values)
+        };
+        prop_assert!(verify_proof(proof, wrong_inputs).is_err());
+    }
+
+    #[test]
+    fn mutated_proofs_are_rejected(
+        computation in computation_strategy(),
+        options in options_strategy(),
+        byte_idx in any::<Index>(),
+        bit in 0..8u8,
+    ) {
+        let trace = computation.build_trace();
+        let pub_inputs = computation.get_pub_inputs(&trace);
+        let proof = prove(trace, pub_inputs.clone(), options).unwrap();
+
+        let mut proof_bytes = proof.to_bytes();
+        *byte_idx.get_mut(&mut proof_bytes) ^= 1 << bit;
+        if let Ok(proof) = Proof::from_bytes(&proof_bytes) {
+            prop_assert!(verify_proof(proof, pub_inputs).is_err());
+        }
+    }
+
+    #[test]
+    fn invalid_traces_are_rejected(
+        computation in computation_strategy(),
+        options in options_strategy(),
+        column_idx in any::<Index>(),
+        step_idx in any::<Index>(),
+        delta in 1..u64::MAX,
+    ) {
+        let mut trace = computation.build_trace();
+        let pub_inputs = computation.get_pub_inputs(&trace);
+
+        // modifying a cell in any row but the first one breaks the transition into that row
+        let column = column_idx.index(trace.width());
+        let step = 1 + step_idx.index(trace.length() - 1);
+        trace.set(column, step, trace.get(column, step) + BaseElement::new(delta));
+
+        // in debug builds the prover checks the trace and panics; otherwise, the generated proof
+        // must be rejected
+        let result = catch_unwind(AssertUnwindSafe(|| prove(trace, pub_inputs.clone(), options)));
+        if let Ok(Ok(proof)) = result {
+            prop_assert!(verify_proof(proof, pub_inputs).is_err());
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn prove(
+    trace: TraceTable<BaseElement>,
+    pub_inputs: RandomPublicInputs,
+    options: ProofOptions,
+) -> Result<Proof, ProverError> {
+    RandomProver { options, pub_inputs }.prove(trace)
+}
+
+fn verify_proof(proof: Proof, pub_inputs: RandomPublicInputs) -> Result<(), VerifierError> {
+    let acceptable_options = AcceptableOptions::MinConjecturedSecurity(0);
+    verify::<RandomAir, Hasher, DefaultRandomCoin<Hasher>>(proof, pub_inputs, &acceptable_options)
+}
+
+// STRATEGIES
+// ================================================================================================
+
+fn computation_strategy() -> impl Strategy<Value = RandomComputation> {
+    (1..=3usize, 5..=7u32).prop_flat_map(|(width, log_trace_length)| {
+        let trace_length = 1 << log_trace_length;
+        (
+            vec(any::<u64>(), width),
+            vec(vec(1..u64::MAX, width), width),
+            vec(vec(0..=MAX_EXPONENT, width), width),
+            vec(any::<u64>(), width),
+            vec(assertion_strategy(log_trace_length), width),
+        )
+            .prop_map(
+                move |(offsets, coefficients, exponents, initial_state, assertions)| {
+                    RandomComputation::new(
+                        offsets,
+                        coefficients,
+                        exponents,
+                        initial_state,
+                        assertions,
+                        trace_length,
+                    )
+                },
+            )
+    })
+}
+
+fn assertion_strategy(log_trace_length: u32) -> impl Strategy<Value = AssertionShape> {
+    let trace_length = 1usize << log_trace_length;
+    prop_oneof![
+        Just(AssertionShape::None),
+        (0..trace_length).prop_map(AssertionShape::Single),
+        (1..=log_trace_length).prop_flat_map(|log_stride| {
+            let stride = 1usize << log_stride;
+            (0..stride).prop_map(move |first_step| AssertionShape::Sequence { first_step, stride })
+        }),
+    ]
+}
+
+fn options_strategy() -> impl Strategy<Value = ProofOptions> {
+    (
+        4..=16usize,
+        prop_oneof![Just(8usize), Just(16)],
+        prop_oneof![
+            Just(FieldExtension::None),
+            Just(FieldExtension::Quadratic),
+            Just(FieldExtension::Cubic)
+        ],
+        prop_oneof![Just(2usize), Just(4), Just(8)],
+        prop_oneof![Just(7usize), Just(15)],
+    )
+        .prop_map(
+            |(num_queries, blowup_factor, field_extension, folding_factor, remainder)| {
+                ProofOptions::new(
+                    num_queries,
+                    blowup_factor,
+                    0,
+                    field_extension,
+                    folding_factor,
+                    remainder,
+                )
+            },
+        )
+}
+
+// RANDOM COMPUTATION
+// ================================================================================================
+
+/// Shape of an assertion placed against a single column of a random computation.
+#[derive(Debug, Clone, Copy)]
+enum AssertionShape {
+    None,
+    Single(usize),
+    Sequence { first_step: usize, stride: usize },
+}
+
+/// A computation in which every step updates the state as
+/// `next[i] = offsets[i] + sum_j coefficients[i][j] * current[j]^exponents[i][j]`.
+#[derive(Debug, Clone)]
+struct RandomComputation {
+    transition: Transition,
+    initial_state: Vec<BaseElement>,
+    assertions: Vec<AssertionShape>,
+    trace_length: usize,
+}
+
+impl RandomComputation {
+    fn new(
+        offsets: Vec<u64>,
+        coefficients: Vec<Vec<u64>>,
+        mut exponents: Vec<Vec<u32>>,
+        initial_state: Vec<u64>,
+        mut assertions: Vec<AssertionShape>,
+        trace_length: usize,
+    ) -> Self {
+        // every column depends on its own value in the current step, and thus, is not constant
+        for (i, exponents) in exponents.iter_mut().enumerate() {
+            exponents[i] = exponents[i].max(1);
+        }
+
+        // the AIR must not be empty
+        if assertions.iter().all(|shape| matches!(shape, AssertionShape::None)) {
+            assertions[0] = AssertionShape::Single(0);
+        }
+
+        let transition = Transition {
+            offsets: offsets.into_iter().map(BaseElement::new).collect(),
+            coefficients: coefficients
+                .into_iter()
+                .map(|row| row.into_iter().map(BaseElement::new).collect())
+                .collect(),
+            exponents,
+        };
+
+        Self {
+            transition,
+            initial_state: initial_state.into_iter().map(BaseElement::new).collect(),
+            assertions,
+            trace_length,
+        }
+    }
+
+    fn build_trace(&self) -> TraceTable<BaseElement> {
+        let width = self.initial_state.len();
+        let mut trace = TraceTable::new(width, self.trace_length);
+        trace.fill(
+            |state| state.copy_from_slice(&self.initial_state),
+            |_, state| {
+                let current = state.to_vec();
+                self.transition.apply(&current, state);
+            },
+        );
+        trace
+    }
+
+    fn get_pub_inputs(&self, trace: &TraceTable<BaseElement>) -> RandomPublicInputs {
+        let mut assertions = Vec::new();
+        for (column, shape) in self.assertions.iter().enumerate() {
+            match *shape {
+                AssertionShape::None => (),
+                AssertionShape::Single(step) => {
+                    assertions.push(Assertion::single(column, step, trace.get(column, step)));
+                },
+                AssertionShape::Sequence { first_step, stride } => {
+                    let values = (first_step..self.trace_length)
+                        .step_by(stride)
+                        .map(|step| trace.get(column, step))
+                        .collect();
+                    assertions.push(Assertion::sequence(column, first_step, stride, values));
+                },
+            }
+        }
+
+        RandomPublicInputs {
+            transition: self.transition.clone(),
+            assertions,
+        }
+    }
+}
+
+/// Transition function of a random computation.
+#[derive(Debug, Clone)]
+struct Transition {
+    offsets: Vec<BaseElement>,
+    coefficients: Vec<Vec<BaseElement>>,
+    exponents: Vec<Vec<u32>>,
+}
+
+impl Transition {
+    fn apply<E: FieldElement + From<BaseElement>>(&self, current: &[E], result: &mut [E]) {
+        for (i, result) in result.iter_mut().enumerate() {
+            *result = E::from(self.offsets[i]);
+            for (j, &value) in current.iter().enumerate() {
+                *result +=
+                    E::from(self.coefficients[i][j]) * value.exp(self.exponents[i][j].into());
+            }
+        }
+    }
+
+    fn degrees(&self) -> Vec<TransitionConstraintDegree> {
+        self.exponents
+            .iter()
+            .map(|exponents| {
+                let degree = exponents.iter().copied().max().unwrap_or(1);
+                TransitionConstraintDegree::new(degree as usize)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RandomPublicInputs {
+    transition: Transition,
+    assertions: Vec<Assertion<BaseElement>>,
+}
+
+impl ToElements<BaseElement> for RandomPublicInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        let transition = &self.transition;
+        let mut result = transition.offsets.clone();
+        result.extend(transition.coefficients.iter().flatten());
+        result.extend(transition.exponents.iter().flatten().map(|&e| BaseElement::from(e)));
+        for assertion in self.assertions.iter() {
+            result.extend_from_slice(assertion.values());
+        }
+        result
+    }
+}
+
+// AIR
+// ================================================================================================
+
+struct RandomAir {
+    context: AirContext<BaseElement>,
+    transition: Transition,
+    assertions: Vec<Assertion<BaseElement>>,
+}
+
+impl Air for RandomAir {
+    type BaseField = BaseElement;
+    type PublicInputs = RandomPublicInputs;
+    type GkrProof = ();
+    type GkrVerifier = ();
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::PublicInputs, options: ProofOptions) -> Self {
+        let degrees = pub_inputs.transition.degrees();
+        Self {
+            context: AirContext::new(trace_info, degrees, pub_inputs.assertions.len(), options),
+            transition: pub_inputs.transition,
+            assertions: pub_inputs.assertions,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.transition.apply(frame.current(), result);
+        for (result, &next) in result.iter_mut().zip(frame.next()) {
+            *result = next - *result;
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.assertions.clone()
+    }
+}
+
+// PROVER
+// ================================================================================================
+
+struct RandomProver {
+    options: ProofOptions,
+    pub_inputs: RandomPublicInputs,
+}
+
+impl Prover for RandomProver {
+    type BaseField = BaseElement;
+    type Air = RandomAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Hasher;
+    type RandomCoin = DefaultRandomCoin<Hasher>;
+    type TraceLde<E: FieldElement<BaseField = BaseElement>> = DefaultTraceLde<E, Hasher>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = BaseElement>> =
+        DefaultConstraintEvaluator<'a, RandomAir, E>;
+
+    fn get_pub_inputs(&self, _trace: &Self::Trace) -> RandomPublicInputs {
+        self.pub_inputs.clone()
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
@@ -0,0 +1,344 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Tests of transition constraints gated by selector columns.
+
+use std::{vec, vec::Vec};
+
+use prover::{
+    crypto::{hashers::Blake3_256, DefaultRandomCoin},
+    math::{fields::f64::BaseElement, ExtensionOf, FieldElement},
+    matrix::ColMatrix,
+};
+
+use crate::{
+    verify, AcceptableOptions, Air, AirContext, Assertion, AuxRandElements, AuxTraceWithMetadata,
+    ConstraintCompositionCoefficients, ConstraintViolation, DefaultConstraintEvaluator,
+    DefaultTraceLde, EvaluationFrame, FieldExtension, ProofOptions, Prover, SelectorGroup,
+    StarkDomain, Trace, TraceInfo, TracePolyTable, TraceTable, TransitionConstraintDegree,
+};
+
+type Hasher = Blake3_256<BaseElement>;
+
+const TRACE_LENGTH: usize = 64;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn selector_groups_adjust_constraint_degrees() {
+    let trace = build_trace(TRACE_LENGTH);
+    let air = SelectorAir::new(trace.info().clone(), BaseElement::ONE, build_options());
+    let context = air.context();
+
+    assert_eq!(2, context.selector_groups().len());
+    assert_eq!(Some(1), context.selector_group_index("bus"));
+    assert_eq!(None, context.selector_group_index("memory"));
+
+    // the degrees of the gated constraints are increased by one
+    let trace_poly_degree = TRACE_LENGTH - 1;
+    let coefficients = vec![BaseElement::ONE; context.num_transition_constraints()];
+    let constraints = air.get_transition_constraints(&coefficients);
+    let main_degrees = constraints
+        .main_constraint_degrees()
+        .iter()
+        .map(|degree| degree.get_evaluation_degree(TRACE_LENGTH) / trace_poly_degree)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![2, 1, 2], main_degrees);
+    let aux_degrees = constraints
+        .aux_constraint_degrees()
+        .iter()
+        .map(|degree| degree.get_evaluation_degree(TRACE_LENGTH) / trace_poly_degree)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![3, 2], aux_degrees);
+}
+
+#[test]
+fn selector_gated_constraints_are_proven_and_verified() {
+    let trace = build_trace(TRACE_LENGTH);
+    let result = trace.main_segment().get(2, TRACE_LENGTH - 1);
+    let prover = SelectorProver { options: build_options() };
+    let proof = prover.prove(trace).unwrap();
+
+    let acceptable_options = AcceptableOptions::MinConjecturedSecurity(0);
+    assert!(verify::<SelectorAir, Hasher, DefaultRandomCoin<Hasher>>(
+        proof.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+    assert!(verify::<SelectorAir, Hasher, DefaultRandomCoin<Hasher>>(
+        proof,
+        result + BaseElement::ONE,
+        &acceptable_options
+    )
+    .is_err());
+}
+
+#[test]
+fn selector_gated_constraints_are_enforced_in_selected_rows() {
+    let mut trace = build_trace(TRACE_LENGTH);
+    let result = trace.main_segment().get(2, TRACE_LENGTH - 1);
+    let air = SelectorAir::new(trace.info().clone(), result, build_options());
+    let prover = SelectorProver { options: build_options() };
+    let aux_rand_elements = AuxRandElements::new(vec![BaseElement::new(123456789)]);
+    let build_aux_trace = |trace: &SelectorTrace| AuxTraceWithMetadata {
+        aux_trace: prover.build_aux_trace(trace, &aux_rand_elements),
+        aux_rand_elements: aux_rand_elements.clone(),
+        gkr_proof: None,
+    };
+
+    // gated constraints are not enforced in rows in which the selector is ZERO
+    assert!(trace.check_constraints(&air, Some(&build_aux_trace(&trace))).is_ok());
+
+    // but they are enforced in rows in which the selector is ONE
+    let step = (0..TRACE_LENGTH - 1)
+        .find(|&i| trace.main_segment().get(0, i) == BaseElement::ONE)
+        .unwrap();
+    let value = trace.main_segment().get(1, step + 1);
+    trace.main_trace.set(1, step + 1, value + BaseElement::ONE);
+    let violation = trace.check_constraints(&air, Some(&build_aux_trace(&trace)));
+    assert!(matches!(
+        violation,
+        Err(ConstraintViolation::MainTransition { constraint: 0, step: s, .. }) if s == step
+    ));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_options() -> ProofOptions {
+    ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 7)
+}
+
+/// Builds a trace with columns `[s, a, b]`, where `s` is a pseudo-random binary selector, `b`
+/// is updated as `b' = a + b` in every row, and `a` is updated as `a' = a + b` in rows in
+/// which `s = 1`, and as `a' = 3 * a` otherwise.
+fn build_trace(length: usize) -> SelectorTrace {
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let selectors = (0..length)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            if seed & 1 == 1 {
+                BaseElement::ONE
+            } else {
+                BaseElement::ZERO
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut trace = TraceTable::new(3, length);
+    trace.fill(
+        |state| {
+            state[0] = selectors[0];
+            state[1] = BaseElement::ONE;
+            state[2] = BaseElement::ONE;
+        },
+        |step, state| {
+            let (a, b) = (state[1], state[2]);
+            state[1] = if state[0] == BaseElement::ONE {
+                a + b
+            } else {
+                a * BaseElement::from(3u32)
+            };
+            state[2] = a + b;
+            state[0] = selectors[step + 1];
+        },
+    );
+
+    let columns = (0..trace.width()).map(|i| trace.get_column(i).to_vec()).collect();
+    SelectorTrace {
+        info: TraceInfo::new_multi_segment(3, 1, 1, length, Vec::new()),
+        main_trace: ColMatrix::new(columns),
+    }
+}
+
+/// Execution trace of [SelectorAir].
+struct SelectorTrace {
+    info: TraceInfo,
+    main_trace: ColMatrix<BaseElement>,
+}
+
+impl Trace for SelectorTrace {
+    type BaseField = BaseElement;
+
+    fn info(&self) -> &TraceInfo {
+        &self.info
+    }
+
+    fn main_segment(&self) -> &ColMatrix<BaseElement> {
+        &self.main_trace
+    }
+
+    fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<BaseElement>) {
+        let next_row_idx = (row_idx + 1) % self.length();
+        self.main_trace.read_row_into(row_idx, frame.current_mut());
+        self.main_trace.read_row_into(next_row_idx, frame.next_mut());
+    }
+}
+
+// SELECTOR AIR
+// ================================================================================================
+
+/// An AIR in which some transition constraints are gated by the selector column `s`.
+///
+/// Main constraints:
+/// 0. `a' = a + b`, gated by `s` (group "fib").
+/// 1. `b' = a + b`.
+/// 2. `s` is binary.
+///
+/// Auxiliary constraints over a running product column `z`:
+/// 0. `z' = z * (alpha + a)`, gated by `s` (group "bus").
+/// 1. `(1 - s) * (z' - z) = 0`.
+struct SelectorAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for SelectorAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+    type GkrProof = ();
+    type GkrVerifier = ();
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let main_degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(2),
+        ];
+        let aux_degrees =
+            vec![TransitionConstraintDegree::new(2), TransitionConstraintDegree::new(2)];
+        let context = AirContext::new_multi_segment(
+            trace_info,
+            main_degrees,
+            aux_degrees,
+            3,
+            1,
+            None,
+            options,
+        )
+        .add_selector_group(SelectorGroup::new("fib", 0).with_main_constraints(0..1))
+        .add_selector_group(SelectorGroup::new("bus", 0).with_aux_constraints(0..1));
+        Self { context, result: pub_inputs }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[1] - (current[1] + current[2]);
+        result[1] = next[2] - (current[1] + current[2]);
+        result[2] = current[0] * current[0] - current[0];
+    }
+
+    fn evaluate_aux_transition<F, E>(
+        &self,
+        main_frame: &EvaluationFrame<F>,
+        aux_frame: &EvaluationFrame<E>,
+        _periodic_values: &[F],
+        aux_rand_elements: &[E],
+        result: &mut [E],
+    ) where
+        F: FieldElement<BaseField = Self::BaseField>,
+        E: FieldElement<BaseField = Self::BaseField> + ExtensionOf<F>,
+    {
+        let alpha = aux_rand_elements[0];
+        let s = main_frame.current()[0];
+        let a = main_frame.current()[1];
+        let z = aux_frame.current()[0];
+        let z_next = aux_frame.next()[0];
+        result[0] = z_next - z * (alpha + a.into());
+        result[1] = (z_next - z).mul_base(F::ONE - s);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(2, 0, BaseElement::ONE),
+            Assertion::single(2, last_step, self.result),
+        ]
+    }
+
+    fn get_aux_assertions<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        _aux_rand_elements: &[E],
+    ) -> Vec<Assertion<E>> {
+        vec![Assertion::single(0, 0, E::ONE)]
+    }
+}
+
+// SELECTOR PROVER
+// ================================================================================================
+
+struct SelectorProver {
+    options: ProofOptions,
+}
+
+impl Prover for SelectorProver {
+    type BaseField = BaseElement;
+    type Air = SelectorAir;
+    type Trace = SelectorTrace;
+    type HashFn = Hasher;
+    type RandomCoin = DefaultRandomCoin<Hasher>;
+    type TraceLde<E: FieldElement<BaseField = BaseElement>> = DefaultTraceLde<E, Hasher>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = BaseElement>> =
+        DefaultConstraintEvaluator<'a, SelectorAir, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        trace.main_segment().get(2, trace.length() - 1)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: Option<AuxRandElements<E>>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+
+    fn build_aux_trace<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace: &Self::Trace,
+        aux_rand_elements: &AuxRandElements<E>,
+    ) -> ColMatrix<E> {
+        let main_trace = trace.main_segment();
+        let alpha = aux_rand_elements.rand_elements()[0];
+        let mut z = vec![E::ONE; trace.length()];
+        for i in 0..trace.length() - 1 {
+            z[i + 1] = if main_trace.get(0, i) == BaseElement::ONE {
+                z[i] * (alpha + E::from(main_trace.get(1, i)))
+            } else {
+                z[i]
+            };
+        }
+        ColMatrix::new(vec![z])
+    }
+}
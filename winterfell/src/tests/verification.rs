@@ -0,0 +1,475 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use std::{vec, vec::Vec};
+
+use super::fib::{build_proof_options, compute_fib_term, Blake3, FibAir, FibProver};
+use crate::{
+    crypto::{hashers::Sha3_256, DefaultRandomCoin, DualHashRandomCoin, HashFunctionId},
+    math::{fields::f128::BaseElement, FieldElement},
+    proof::{Proof, Queries},
+    AcceptableOptions, Air, FieldExtension, Prover, Serializable, VerificationPolicy,
+    VerifierError,
+};
+
+type Coin = DefaultRandomCoin<Blake3>;
+
+// VERIFICATION TESTS
+// ================================================================================================
+
+#[test]
+fn compressed_proof_verification() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    let result = compute_fib_term::<BaseElement>(16);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    let compressed_proof = proof.compress::<Blake3>().unwrap();
+    assert!(compressed_proof.len() < proof.to_bytes().len());
+
+    let result = crate::verify_compressed::<FibAir, Blake3, Coin>(
+        &compressed_proof,
+        result,
+        &acceptable_options,
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn verification_policy() {
+    let verify = |proof: &Proof, policy: &VerificationPolicy| {
+        crate::verify::<FibAir, Blake3, Coin>(
+            proof.clone(),
+            compute_fib_term(16),
+            &AcceptableOptions::Policy(policy.clone()),
+        )
+    };
+
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    let security = proof.security_level::<Blake3>(true);
+    let trace_length = proof.trace_info().length();
+
+    let policy = VerificationPolicy::new(security)
+        .with_hash_functions(&[HashFunctionId::Blake3_256])
+        .with_field_extensions(&[FieldExtension::None])
+        .with_max_trace_length(trace_length);
+    assert!(verify(&proof, &policy).is_ok());
+
+    let policy = VerificationPolicy::new(security + 1);
+    assert_eq!(
+        verify(&proof, &policy),
+        Err(VerifierError::InsufficientConjecturedSecurity(security + 1, security))
+    );
+
+    let policy = VerificationPolicy::new(0).with_min_proven_security(security + 1);
+    assert!(matches!(
+        verify(&proof, &policy),
+        Err(VerifierError::InsufficientProvenSecurity(..))
+    ));
+
+    let policy = VerificationPolicy::new(0).with_hash_functions(&[HashFunctionId::Sha3_256]);
+    assert_eq!(verify(&proof, &policy), Err(VerifierError::UnacceptableHashFunction));
+
+    let policy = VerificationPolicy::new(0).with_field_extensions(&[FieldExtension::Quadratic]);
+    assert_eq!(verify(&proof, &policy), Err(VerifierError::UnacceptableFieldExtension(1)));
+
+    let policy = VerificationPolicy::new(0).with_max_trace_length(trace_length / 2);
+    assert_eq!(
+        verify(&proof, &policy),
+        Err(VerifierError::TraceLengthTooLarge(trace_length / 2, trace_length))
+    );
+}
+
+#[test]
+fn bytes_verification() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    let fib_result = compute_fib_term::<BaseElement>(16);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let proof_bytes = proof.to_bytes();
+    let pub_input_bytes = fib_result.to_bytes();
+
+    let result = crate::verify_bytes::<FibAir, Blake3, Coin>(
+        &proof_bytes,
+        &pub_input_bytes,
+        &acceptable_options,
+    );
+    assert!(result.is_ok());
+
+    // trailing bytes are rejected
+    let mut extended_proof_bytes = proof_bytes.clone();
+    extended_proof_bytes.push(0);
+    let result = crate::verify_bytes::<FibAir, Blake3, Coin>(
+        &extended_proof_bytes,
+        &pub_input_bytes,
+        &acceptable_options,
+    );
+    assert!(matches!(result, Err(VerifierError::ProofDeserializationError(_))));
+
+    // proof options are validated before the query sections are parsed
+    let result = crate::verify_bytes::<FibAir, Blake3, Coin>(
+        &extended_proof_bytes,
+        &pub_input_bytes,
+        &AcceptableOptions::MinConjecturedSecurity(128),
+    );
+    let security_level = proof.security_level::<Blake3>(true);
+    assert_eq!(Err(VerifierError::InsufficientConjecturedSecurity(128, security_level)), result);
+
+    let result = crate::verify_bytes::<FibAir, Blake3, Coin>(
+        &proof_bytes,
+        &pub_input_bytes[1..],
+        &acceptable_options,
+    );
+    assert!(matches!(result, Err(VerifierError::PublicInputsDeserializationError(_))));
+
+    // a wrong result is rejected
+    let wrong_result = fib_result + BaseElement::ONE;
+    let result = crate::verify_bytes::<FibAir, Blake3, Coin>(
+        &proof_bytes,
+        &wrong_result.to_bytes(),
+        &acceptable_options,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn batch_verification() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let sequence_lengths = [16, 32, 16, 64];
+    let traces = sequence_lengths.iter().map(|&n| prover.build_trace(n)).collect();
+    let proofs = prover.prove_batch(traces).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proofs[0].options().clone()]);
+
+    // the third proof is verified against a wrong result
+    let batch = proofs
+        .into_iter()
+        .zip(sequence_lengths)
+        .enumerate()
+        .map(|(i, (proof, n))| {
+            let result = compute_fib_term(n);
+            (proof, if i == 2 { result + BaseElement::ONE } else { result })
+        })
+        .collect();
+
+    let results = crate::verify_batch::<FibAir, Blake3, Coin>(batch, &acceptable_options);
+    assert_eq!(results.len(), sequence_lengths.len());
+    assert!(results[0].is_ok());
+    assert!(results[1].is_ok());
+    assert!(results[2].is_err());
+    assert!(results[3].is_ok());
+
+    // proofs generated with unacceptable options are rejected without affecting other proofs
+    let other_prover = FibProver::<Blake3>::new(build_proof_options(true));
+    let batch = vec![
+        (prover.prove(prover.build_trace(16)).unwrap(), compute_fib_term(16)),
+        (other_prover.prove(other_prover.build_trace(16)).unwrap(), compute_fib_term(16)),
+    ];
+    let results = crate::verify_batch::<FibAir, Blake3, Coin>(batch, &acceptable_options);
+    assert!(results[0].is_ok());
+    assert_eq!(results[1], Err(VerifierError::UnacceptableProofOptions));
+}
+
+#[test]
+fn verification_with_outputs() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    let result = crate::verify_with_outputs::<FibAir, Blake3, Coin>(
+        proof.clone(),
+        compute_fib_term(16),
+        &acceptable_options,
+    );
+    assert_eq!(Ok(compute_fib_term::<BaseElement>(16)), result);
+
+    // outputs are not returned for a proof which does not verify
+    let result = crate::verify_with_outputs::<FibAir, Blake3, Coin>(
+        proof,
+        compute_fib_term::<BaseElement>(16) + BaseElement::ONE,
+        &acceptable_options,
+    );
+    assert_eq!(Err(VerifierError::InconsistentOodConstraintEvaluations), result);
+}
+
+#[test]
+fn verification_with_security_levels() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    let conjectured = proof.security_level::<Blake3>(true);
+    let proven = proof.security_level::<Blake3>(false);
+
+    let result = crate::verify_with_security_levels::<FibAir, Blake3, Coin>(
+        proof.clone(),
+        compute_fib_term(16),
+        &AcceptableOptions::MinConjecturedSecurity(conjectured),
+    );
+    assert_eq!(Ok(crate::SecurityLevels { conjectured, proven }), result);
+
+    // minimum security levels are still enforced
+    let result = crate::verify_with_security_levels::<FibAir, Blake3, Coin>(
+        proof,
+        compute_fib_term(16),
+        &AcceptableOptions::MinProvenSecurity(proven + 1),
+    );
+    assert_eq!(Err(VerifierError::InsufficientProvenSecurity(proven + 1, proven)), result);
+}
+
+#[cfg(feature = "unsound-testing")]
+#[test]
+fn unsound_fast_verification() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let proof = prover.prove(prover.build_trace(1024)).unwrap();
+
+    for num_sampled_queries in [1, 4, 1000] {
+        let result = crate::verify_unsound_fast::<FibAir, Blake3, Coin>(
+            proof.clone(),
+            compute_fib_term(1024),
+            num_sampled_queries,
+        );
+        assert!(result.is_ok());
+    }
+
+    // the out-of-domain consistency check is still performed
+    let result = crate::verify_unsound_fast::<FibAir, Blake3, Coin>(
+        proof,
+        compute_fib_term::<BaseElement>(1024) + BaseElement::ONE,
+        4,
+    );
+    assert_eq!(Err(VerifierError::InconsistentOodConstraintEvaluations), result);
+}
+
+#[test]
+fn constant_time_verification() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let proof = prover.prove(prover.build_trace(1024)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    let result = crate::verify_constant_time::<FibAir, Blake3, Coin>(
+        proof.clone(),
+        compute_fib_term(1024),
+        &acceptable_options,
+    );
+    assert!(result.is_ok());
+
+    // a failed check is reported without revealing which of the checks failed
+    let mut tampered_proof = proof;
+    let num_queries = tampered_proof.num_unique_queries as usize;
+    let (merkle_proof, states) = tampered_proof.trace_queries[0]
+        .clone()
+        .parse::<Blake3, BaseElement>(tampered_proof.lde_domain_size(), num_queries, 2)
+        .unwrap();
+    let mut rows = states.rows().map(|row| row.to_vec()).collect::<Vec<_>>();
+    rows[1][0] += BaseElement::ONE;
+    tampered_proof.trace_queries[0] = Queries::new(merkle_proof, rows);
+
+    let result = crate::verify_constant_time::<FibAir, Blake3, Coin>(
+        tampered_proof,
+        compute_fib_term(1024),
+        &acceptable_options,
+    );
+    assert_eq!(Err(VerifierError::ProofVerificationFailed), result);
+}
+
+#[test]
+fn verification_with_runtime_hash_dispatch() {
+    let verify = |proof: Proof| {
+        let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        crate::verify_auto::<FibAir>(proof, compute_fib_term(16), &acceptable_options)
+    };
+
+    // a proof which uses the same hash function for commitments and for the public coin
+    let options = build_proof_options(false)
+        .with_hash_functions(HashFunctionId::Blake3_256, HashFunctionId::Blake3_256);
+    let prover = FibProver::<Blake3>::new(options);
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    assert!(verify(proof.clone()).is_ok());
+
+    // a proof which uses different hash functions for commitments and for the public coin
+    type Coin = DualHashRandomCoin<Blake3, Sha3_256<BaseElement>>;
+    let options = build_proof_options(false)
+        .with_hash_functions(HashFunctionId::Blake3_256, HashFunctionId::Sha3_256);
+    let prover = FibProver::<Blake3, Coin>::new(options);
+    assert!(verify(prover.prove(prover.build_trace(16)).unwrap()).is_ok());
+
+    // a proof which does not record hash functions cannot be dispatched
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let unspecified_proof = prover.prove(prover.build_trace(16)).unwrap();
+    assert_eq!(verify(unspecified_proof), Err(VerifierError::UnspecifiedHashFunctions));
+
+    // hash functions defined over specific fields are not supported
+    let mut unsupported_proof = proof;
+    let options = build_proof_options(false)
+        .with_hash_functions(HashFunctionId::Rp64_256, HashFunctionId::Rp64_256);
+    unsupported_proof.context =
+        air::proof::Context::new::<BaseElement>(unsupported_proof.trace_info().clone(), options);
+    assert_eq!(
+        verify(unsupported_proof),
+        Err(VerifierError::UnsupportedHashFunction(HashFunctionId::Rp64_256))
+    );
+}
+
+#[cfg(feature = "diagnostics")]
+#[test]
+fn verification_diagnostics() {
+    use crate::ErrorContext;
+
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    // a valid proof passes verification
+    let result = crate::verify_with_diagnostics::<FibAir, Blake3, Coin>(
+        proof.clone(),
+        compute_fib_term(16),
+        &acceptable_options,
+    );
+    assert!(result.is_ok());
+
+    // a wrong result is detected by the out-of-domain consistency check
+    let err = crate::verify_with_diagnostics::<FibAir, Blake3, Coin>(
+        proof.clone(),
+        compute_fib_term::<BaseElement>(16) + BaseElement::ONE,
+        &acceptable_options,
+    )
+    .unwrap_err();
+    assert_eq!(err.error(), &VerifierError::InconsistentOodConstraintEvaluations);
+    assert!(matches!(err.context(), Some(ErrorContext::OodConstraintEvaluations { .. })));
+
+    // a tampered trace query is attributed to the main trace commitment and to the query
+    let mut tampered_proof = proof;
+    let num_queries = tampered_proof.num_unique_queries as usize;
+    let (merkle_proof, states) = tampered_proof.trace_queries[0]
+        .clone()
+        .parse::<Blake3, BaseElement>(tampered_proof.lde_domain_size(), num_queries, 2)
+        .unwrap();
+    let mut rows = states.rows().map(|row| row.to_vec()).collect::<Vec<_>>();
+    rows[1][0] += BaseElement::ONE;
+    tampered_proof.trace_queries[0] = Queries::new(merkle_proof, rows);
+
+    let err = crate::verify_with_diagnostics::<FibAir, Blake3, Coin>(
+        tampered_proof,
+        compute_fib_term(16),
+        &acceptable_options,
+    )
+    .unwrap_err();
+    assert_eq!(err.error(), &VerifierError::TraceQueryDoesNotMatchCommitment);
+    match err.context() {
+        Some(ErrorContext::TraceQuery { commitment, mismatch }) => {
+            assert_eq!(*commitment, 0);
+            assert_ne!(mismatch.actual_root, Some(mismatch.expected_root));
+            assert!(mismatch.failed_queries.iter().any(|&(query_idx, _)| query_idx == 1));
+        },
+        context => panic!("unexpected error context: {context:?}"),
+    }
+}
+
+#[test]
+fn verification_with_trace() {
+    use crate::math::fields::QuadExtension;
+
+    type QuadElement = QuadExtension<BaseElement>;
+
+    let prover = FibProver::<Blake3>::new(build_proof_options(true));
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    let trace = crate::verify_with_trace::<FibAir, QuadElement, Blake3, Coin>(
+        proof.clone(),
+        compute_fib_term(16),
+        &acceptable_options,
+    )
+    .unwrap();
+
+    // query positions match the ones returned by the verifier
+    let query_positions = crate::verify_with_query_positions::<FibAir, Blake3, Coin>(
+        proof.clone(),
+        compute_fib_term(16),
+        &acceptable_options,
+    )
+    .unwrap();
+    assert_eq!(trace.query_positions, query_positions);
+    assert_eq!(trace.deep_evaluations.len(), query_positions.len());
+
+    // there is a folding challenge for every FRI layer and for the remainder
+    let num_fri_layers = proof.options().to_fri_options().num_fri_layers(proof.lde_domain_size());
+    assert_eq!(trace.fri_layer_alphas.len(), num_fri_layers + 1);
+    assert_eq!(trace.fri_folded_evaluations.len(), num_fri_layers);
+
+    // the evaluation of the constraint composition polynomial at z can be recomputed from the
+    // evaluations of its columns
+    let z_n = trace.ood_point.exp((proof.trace_info().length() as u64).into());
+    let from_columns = trace
+        .ood_constraint_evaluations
+        .iter()
+        .rev()
+        .fold(QuadElement::ZERO, |acc, &value| acc * z_n + value);
+    assert_eq!(trace.ood_constraint_evaluation, from_columns);
+
+    // the field of the verifier trace must match the field extension used by the proof
+    let result = crate::verify_with_trace::<FibAir, BaseElement, Blake3, Coin>(
+        proof,
+        compute_fib_term(16),
+        &acceptable_options,
+    );
+    assert_eq!(result.unwrap_err(), VerifierError::MismatchedFieldExtension(1, 2));
+}
+
+#[test]
+fn ood_consistency_check() {
+    let prover = FibProver::<Blake3>::new(build_proof_options(false));
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    // for a valid proof, the evaluations match the ones computed by the verifier
+    let report = crate::check_ood_consistency::<FibAir, BaseElement, Blake3, Coin>(
+        &proof,
+        compute_fib_term(16),
+    )
+    .unwrap();
+    assert!(report.is_consistent());
+    assert_eq!(2, report.constraint_evaluations.main_transition.len());
+    assert!(report.constraint_evaluations.aux_transition.is_empty());
+    assert_eq!(BaseElement::ZERO, report.constraint_evaluations.lagrange_kernel);
+
+    let trace = crate::verify_with_trace::<FibAir, BaseElement, Blake3, Coin>(
+        proof.clone(),
+        compute_fib_term(16),
+        &acceptable_options,
+    )
+    .unwrap();
+    assert_eq!(trace.ood_point, report.ood_point);
+    assert_eq!(trace.ood_constraint_evaluation, report.trace_evaluation());
+
+    // tamper with the constraint composition column evaluations sent by the prover; this does not
+    // affect the out-of-domain point
+    let air =
+        FibAir::new(proof.trace_info().clone(), compute_fib_term(16), build_proof_options(false));
+    let num_columns = air.context().num_constraint_composition_columns();
+    let (trace_frame, mut evaluations) =
+        proof.ood_frame.clone().parse::<BaseElement>(2, 0, num_columns).unwrap();
+    evaluations[0] += BaseElement::ONE;
+    let mut tampered_proof = proof.clone();
+    tampered_proof.ood_frame = Default::default();
+    tampered_proof.ood_frame.set_trace_states::<BaseElement, Blake3>(&trace_frame);
+    tampered_proof.ood_frame.set_constraint_evaluations(&evaluations);
+
+    let tampered_report = crate::check_ood_consistency::<FibAir, BaseElement, Blake3, Coin>(
+        &tampered_proof,
+        compute_fib_term(16),
+    )
+    .unwrap();
+    assert!(!tampered_report.is_consistent());
+    assert_eq!(report.ood_point, tampered_report.ood_point);
+    assert_eq!(report.constraint_evaluations, tampered_report.constraint_evaluations);
+    assert_ne!(report.composition_evaluation, tampered_report.composition_evaluation);
+
+    let result = crate::verify::<FibAir, Blake3, Coin>(
+        tampered_proof,
+        compute_fib_term(16),
+        &acceptable_options,
+    );
+    assert_eq!(Err(VerifierError::InconsistentOodConstraintEvaluations), result);
+}